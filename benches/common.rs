@@ -43,6 +43,24 @@ gen_random_ints!(gen_10, 10);
 gen_random_ints!(gen_100, 100);
 gen_random_ints!(gen_1000, 1000);
 
+macro_rules! gen_random_strs {
+  ($fname:ident, $limit:expr) => {
+    pub fn $fname(total: usize) -> (usize, Vec<ByteArray>) {
+      let mut values = Vec::with_capacity(total);
+      let mut rng = thread_rng();
+      for _ in 0..total {
+        let value = format!("parquet-value-{}", rng.gen_range::<usize>(0, $limit));
+        values.push(ByteArray::from(value.as_str()));
+      }
+      let bytes = values.iter().fold(0, |acc, w| acc + w.len());
+      (bytes, values)
+    }
+  }
+}
+
+gen_random_strs!(gen_strs_1000, 1000);
+gen_random_strs!(gen_strs_100k, 100_000);
+
 pub fn gen_test_strs(total: usize) -> (usize, Vec<ByteArray>) {
   let mut words = Vec::new();
   words.push("aaaaaaaaaa");
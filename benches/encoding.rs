@@ -97,7 +97,9 @@ dict!(dict_i32_1k_1000, 1024, Int32Type, Type::INT32, gen_1000);
 dict!(dict_i32_1m_10, 1024 * 1024, Int32Type, Type::INT32, gen_10);
 dict!(dict_i32_1m_100, 1024 * 1024, Int32Type, Type::INT32, gen_100);
 dict!(dict_i32_1m_1000, 1024 * 1024, Int32Type, Type::INT32, gen_1000);
-plain!(dict_str_1m, 1024 * 1024, ByteArrayType, Type::BYTE_ARRAY, gen_test_strs);
+dict!(dict_str_1m_10, 1024 * 1024, ByteArrayType, Type::BYTE_ARRAY, gen_test_strs);
+dict!(dict_str_1m_1000, 1024 * 1024, ByteArrayType, Type::BYTE_ARRAY, gen_strs_1000);
+dict!(dict_str_1m_100k, 1024 * 1024, ByteArrayType, Type::BYTE_ARRAY, gen_strs_100k);
 
 delta_bit_pack!(delta_bit_pack_i32_1k_10, 1024, Int32Type, gen_10);
 delta_bit_pack!(delta_bit_pack_i32_1k_100, 1024, Int32Type, gen_100);
@@ -0,0 +1,170 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Criterion benchmarks of decoding throughput per encoding and type, and of
+//! end-to-end scans of a file. Run with `cargo bench --features bench`.
+
+#[macro_use]
+extern crate criterion;
+extern crate parquet;
+extern crate rand;
+
+#[allow(dead_code)]
+#[path = "common.rs"]
+mod common;
+use common::*;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::rc::Rc;
+
+use criterion::{Benchmark, Criterion, Throughput};
+
+use parquet::basic::*;
+use parquet::bench;
+use parquet::data_type::*;
+use parquet::decoding::*;
+use parquet::encoding::*;
+use parquet::file::builder::InMemoryFileBuilder;
+use parquet::file::reader::SerializedFileReader;
+use parquet::memory::{ByteBufferPtr, MemTracker};
+use parquet::record::api::Row;
+use parquet::schema::parser::parse_message_type;
+use parquet::schema::types::ColumnDescPtr;
+
+const NUM_VALUES: usize = 1024 * 1024;
+const BATCH_SIZE: usize = 1024;
+const NUM_ROWS: usize = 100_000;
+
+/// Encodes values with the encoding and benchmarks decoding them.
+fn bench_decoding<T: DataType + 'static>(
+  c: &mut Criterion,
+  name: &str,
+  descr: ColumnDescPtr,
+  encoding: Encoding,
+  values: Vec<T::T>
+) {
+  let mem_tracker = Rc::new(MemTracker::new());
+  let mut encoder = get_encoder::<T>(descr.clone(), encoding, mem_tracker)
+    .expect("get_encoder() should be OK");
+  encoder.put(&values[..]).expect("put() should be OK");
+  let data = encoder.flush_buffer().expect("flush_buffer() should be OK");
+  let decoder = get_decoder::<T>(descr, encoding).expect("get_decoder() should be OK");
+  bench_decoder(c, name, values.len(), data, decoder);
+}
+
+/// Dictionary encodes values and benchmarks decoding them.
+fn bench_dict_decoding<T: DataType + 'static>(
+  c: &mut Criterion,
+  name: &str,
+  descr: ColumnDescPtr,
+  values: Vec<T::T>
+) {
+  let mem_tracker = Rc::new(MemTracker::new());
+  let mut encoder = DictEncoder::<T>::new(descr, mem_tracker);
+  encoder.put(&values[..]).expect("put() should be OK");
+  let mut dict_decoder = PlainDecoder::<T>::new(0);
+  dict_decoder.set_data(
+    encoder.write_dict().expect("write_dict() should be OK"),
+    encoder.num_entries()).expect("set_data() should be OK");
+  let data = encoder.flush_buffer().expect("flush_buffer() should be OK");
+  let mut decoder = DictDecoder::<T>::new();
+  decoder.set_dict(Box::new(dict_decoder)).expect("set_dict() should be OK");
+  bench_decoder(c, name, values.len(), data, Box::new(decoder));
+}
+
+fn bench_decoder<T: DataType + 'static>(
+  c: &mut Criterion,
+  name: &str,
+  num_values: usize,
+  data: ByteBufferPtr,
+  mut decoder: Box<Decoder<T>>
+) {
+  let num_bytes = data.len() as u32;
+  let benchmark = Benchmark::new(name, move |b| {
+    b.iter(|| {
+      bench::decode(&mut *decoder, data.clone(), num_values, BATCH_SIZE)
+        .expect("decode() should be OK")
+    })
+  });
+  c.bench("decoding", benchmark.throughput(Throughput::Bytes(num_bytes)));
+}
+
+fn decoding(c: &mut Criterion) {
+  let i32_descr = Rc::new(col_desc(0, Type::INT32));
+  let str_descr = Rc::new(col_desc(0, Type::BYTE_ARRAY));
+  let ints = gen_1000(NUM_VALUES).1;
+  let strs = gen_test_strs(NUM_VALUES).1;
+
+  bench_decoding::<Int32Type>(
+    c, "plain_i32", i32_descr.clone(), Encoding::PLAIN, ints.clone());
+  bench_dict_decoding::<Int32Type>(c, "dict_i32", i32_descr.clone(), ints.clone());
+  bench_decoding::<Int32Type>(
+    c, "delta_bit_pack_i32", i32_descr, Encoding::DELTA_BINARY_PACKED, ints);
+
+  bench_decoding::<ByteArrayType>(
+    c, "plain_str", str_descr.clone(), Encoding::PLAIN, strs.clone());
+  bench_dict_decoding::<ByteArrayType>(c, "dict_str", str_descr.clone(), strs.clone());
+  bench_decoding::<ByteArrayType>(
+    c, "delta_length_str", str_descr.clone(), Encoding::DELTA_LENGTH_BYTE_ARRAY,
+    strs.clone());
+  bench_decoding::<ByteArrayType>(
+    c, "delta_str", str_descr, Encoding::DELTA_BYTE_ARRAY, strs);
+}
+
+fn scan(c: &mut Criterion) {
+  let schema = "
+    message schema {
+      REQUIRED INT32 id;
+      OPTIONAL INT64 value;
+      OPTIONAL BYTE_ARRAY name (UTF8);
+    }
+  ";
+  let names = gen_test_strs(NUM_ROWS).1;
+  let rows: Vec<Row> = names.into_iter().enumerate().map(|(i, name)| {
+    let value = if i % 3 == 0 { Row::Null } else { Row::Long(i as i64 * 7) };
+    Row::Group(vec![
+      ("id".to_string(), Row::Int(i as i32)),
+      ("value".to_string(), value),
+      ("name".to_string(), Row::Bytes(name))
+    ])
+  }).collect();
+  let bytes = InMemoryFileBuilder::new(Rc::new(parse_message_type(schema).unwrap()))
+    .with_rows(rows)
+    .build()
+    .expect("build() should be OK");
+  let path = env::temp_dir().join("parquet_bench_scan.parquet");
+  fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+
+  let file = fs::File::open(&path).unwrap();
+  let reader = SerializedFileReader::new(file).expect("new() should be OK");
+  let benchmark = Benchmark::new("columns", move |b| {
+    b.iter(|| bench::scan_columns(&reader, BATCH_SIZE).expect("scan should be OK"))
+  });
+  c.bench("scan", benchmark.throughput(Throughput::Elements(NUM_ROWS as u32)));
+
+  let file = fs::File::open(&path).unwrap();
+  let reader = SerializedFileReader::new(file).expect("new() should be OK");
+  let benchmark = Benchmark::new("rows", move |b| {
+    b.iter(|| bench::scan_rows(&reader).expect("scan should be OK"))
+  });
+  c.bench("scan", benchmark.throughput(Throughput::Elements(NUM_ROWS as u32)));
+}
+
+criterion_group!(benches, decoding, scan);
+criterion_main!(benches);
@@ -0,0 +1,340 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Derive macros for writing Rust structs as Parquet records.
+//!
+//! `#[derive(ParquetRecordWriter)]` implements
+//! `parquet::record::writer::RecordWriter` for a struct with named fields, which
+//! provides message type of the struct and writes slices of records into row groups:
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate parquet_derive;
+//! extern crate parquet;
+//!
+//! use parquet::record::writer::RecordWriter;
+//!
+//! #[derive(ParquetRecordWriter)]
+//! struct Record<'a> {
+//!   id: i64,
+//!   name: Option<String>,
+//!   tag: &'a str
+//! }
+//!
+//! let schema = Record::schema().unwrap();
+//! // ... create file writer with `schema` and the next row group writer
+//! Record::write_to_row_group(&records, &mut *row_group_writer).unwrap();
+//! ```
+//!
+//! Supported field types are `bool`, `i8`, `i16`, `i32`, `i64`, `f32`, `f64`, `String`,
+//! `&str`, `Vec<u8>` and `Option` of any of them, which is written as an optional field.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{
+  Data, DataStruct, DeriveInput, Fields, GenericArgument, Ident, PathArguments,
+  PathSegment, Type
+};
+
+/// Derives `parquet::record::writer::RecordWriter` for a struct with named fields.
+#[proc_macro_derive(ParquetRecordWriter)]
+pub fn parquet_record_writer(input: TokenStream) -> TokenStream {
+  let input: DeriveInput = syn::parse(input).expect("Derive input should be valid");
+  let fields: Vec<Field> = match input.data {
+    Data::Struct(DataStruct { fields: Fields::Named(ref fields), .. }) => {
+      fields.named.iter().map(|field| {
+        let ident = field.ident.clone().expect("Named field should have identifier");
+        Field::new(ident, &field.ty)
+      }).collect()
+    },
+    _ => panic!("ParquetRecordWriter can only be derived for structs with named fields")
+  };
+
+  let name = &input.ident;
+  let schema = message_type(&name.to_string(), &fields);
+  let field_names: Vec<String> = fields.iter().map(|field| field.name()).collect();
+  let field_values: Vec<TokenStream2> =
+    fields.iter().map(|field| field.to_row()).collect();
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  let expanded = quote! {
+    impl #impl_generics ::parquet::record::writer::RecordWriter
+        for #name #ty_generics #where_clause {
+      fn schema() -> ::parquet::errors::Result<::parquet::schema::types::TypePtr> {
+        let schema = ::parquet::schema::parser::parse_message_type(#schema)?;
+        Ok(::std::rc::Rc::new(schema))
+      }
+
+      fn to_row(&self) -> ::parquet::record::api::Row {
+        ::parquet::record::api::Row::Group(vec![
+          #((String::from(#field_names), #field_values)),*
+        ])
+      }
+    }
+  };
+  expanded.into()
+}
+
+/// Returns message type of the struct in the format of the schema parser.
+fn message_type(name: &str, fields: &[Field]) -> String {
+  let mut schema = format!("message {} {{\n", name);
+  for field in fields {
+    schema.push_str(&format!("  {}\n", field.schema()));
+  }
+  schema.push_str("}\n");
+  schema
+}
+
+/// Supported Rust types of fields.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldType {
+  Bool,
+  I8,
+  I16,
+  I32,
+  I64,
+  F32,
+  F64,
+  String,
+  Str,
+  Bytes
+}
+
+impl FieldType {
+  /// Returns field type for the Rust type, or `None` if type is not supported.
+  fn from_type(ty: &Type) -> Option<FieldType> {
+    if let Type::Reference(ref reference) = *ty {
+      return match last_segment(&reference.elem) {
+        Some(segment) if segment.ident.to_string() == "str" => Some(FieldType::Str),
+        _ => None
+      };
+    }
+
+    let segment = last_segment(ty)?;
+    let field_type = match segment.ident.to_string().as_str() {
+      "bool" => FieldType::Bool,
+      "i8" => FieldType::I8,
+      "i16" => FieldType::I16,
+      "i32" => FieldType::I32,
+      "i64" => FieldType::I64,
+      "f32" => FieldType::F32,
+      "f64" => FieldType::F64,
+      "String" => FieldType::String,
+      "Vec" => match generic_argument(segment).and_then(last_segment) {
+        Some(element) if element.ident.to_string() == "u8" => FieldType::Bytes,
+        _ => return None
+      },
+      _ => return None
+    };
+    Some(field_type)
+  }
+
+  /// Returns physical type and logical type annotation of the column.
+  fn parquet_type(&self) -> (&'static str, Option<&'static str>) {
+    match *self {
+      FieldType::Bool => ("BOOLEAN", None),
+      FieldType::I8 => ("INT32", Some("INT_8")),
+      FieldType::I16 => ("INT32", Some("INT_16")),
+      FieldType::I32 => ("INT32", None),
+      FieldType::I64 => ("INT64", None),
+      FieldType::F32 => ("FLOAT", None),
+      FieldType::F64 => ("DOUBLE", None),
+      FieldType::String | FieldType::Str => ("BYTE_ARRAY", Some("UTF8")),
+      FieldType::Bytes => ("BYTE_ARRAY", None)
+    }
+  }
+
+  /// Returns expression that converts `value`, a reference to the field value, into
+  /// `Row`.
+  fn to_row(&self) -> TokenStream2 {
+    match *self {
+      FieldType::Bool => quote! { ::parquet::record::api::Row::Bool(*value) },
+      FieldType::I8 => quote! { ::parquet::record::api::Row::Byte(*value) },
+      FieldType::I16 => quote! { ::parquet::record::api::Row::Short(*value) },
+      FieldType::I32 => quote! { ::parquet::record::api::Row::Int(*value) },
+      FieldType::I64 => quote! { ::parquet::record::api::Row::Long(*value) },
+      FieldType::F32 => quote! { ::parquet::record::api::Row::Float(*value) },
+      FieldType::F64 => quote! { ::parquet::record::api::Row::Double(*value) },
+      FieldType::String => quote! { ::parquet::record::api::Row::Str(value.clone()) },
+      FieldType::Str => quote! { ::parquet::record::api::Row::Str(value.to_string()) },
+      FieldType::Bytes => quote! {
+        ::parquet::record::api::Row::Bytes(
+          ::parquet::data_type::ByteArray::from(value.clone()))
+      }
+    }
+  }
+}
+
+/// Field of a struct with its Rust type.
+struct Field {
+  ident: Ident,
+  field_type: FieldType,
+  is_optional: bool
+}
+
+impl Field {
+  fn new(ident: Ident, ty: &Type) -> Self {
+    let (field_type, is_optional) = match option_argument(ty) {
+      Some(inner) => (FieldType::from_type(inner), true),
+      None => (FieldType::from_type(ty), false)
+    };
+    match field_type {
+      Some(field_type) => Field {
+        ident: ident,
+        field_type: field_type,
+        is_optional: is_optional
+      },
+      None => panic!("Unsupported type of field {}", ident)
+    }
+  }
+
+  fn name(&self) -> String {
+    self.ident.to_string()
+  }
+
+  /// Returns definition of the field in message type.
+  fn schema(&self) -> String {
+    let repetition = if self.is_optional { "OPTIONAL" } else { "REQUIRED" };
+    let (physical_type, logical_type) = self.field_type.parquet_type();
+    match logical_type {
+      Some(logical_type) => {
+        format!("{} {} {} ({});", repetition, physical_type, self.name(), logical_type)
+      },
+      None => format!("{} {} {};", repetition, physical_type, self.name())
+    }
+  }
+
+  /// Returns expression that converts the field of `self` into `Row`.
+  fn to_row(&self) -> TokenStream2 {
+    let ident = &self.ident;
+    let conversion = self.field_type.to_row();
+    if self.is_optional {
+      quote! {
+        match self.#ident {
+          Some(ref value) => #conversion,
+          None => ::parquet::record::api::Row::Null
+        }
+      }
+    } else {
+      quote! {
+        {
+          let value = &self.#ident;
+          #conversion
+        }
+      }
+    }
+  }
+}
+
+/// Returns the last path segment of a type, e.g. `Option<T>` of `std::option::Option<T>`.
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+  match *ty {
+    Type::Path(ref type_path) if type_path.qself.is_none() => {
+      type_path.path.segments.iter().last()
+    },
+    _ => None
+  }
+}
+
+/// Returns the only generic type argument of a path segment, e.g. `T` of `Vec<T>`.
+fn generic_argument(segment: &PathSegment) -> Option<&Type> {
+  match segment.arguments {
+    PathArguments::AngleBracketed(ref arguments) if arguments.args.len() == 1 => {
+      match arguments.args.iter().next() {
+        Some(&GenericArgument::Type(ref ty)) => Some(ty),
+        _ => None
+      }
+    },
+    _ => None
+  }
+}
+
+/// Returns `T` if the type is `Option<T>`.
+fn option_argument(ty: &Type) -> Option<&Type> {
+  match last_segment(ty) {
+    Some(segment) if segment.ident.to_string() == "Option" => generic_argument(segment),
+    _ => None
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn field(name: &str, ty: &str) -> Field {
+    let ident = Ident::new(name, proc_macro2::Span::call_site());
+    Field::new(ident, &syn::parse_str::<Type>(ty).unwrap())
+  }
+
+  #[test]
+  fn test_field_type() {
+    let types = vec![
+      ("bool", FieldType::Bool),
+      ("i8", FieldType::I8),
+      ("i16", FieldType::I16),
+      ("i32", FieldType::I32),
+      ("i64", FieldType::I64),
+      ("f32", FieldType::F32),
+      ("f64", FieldType::F64),
+      ("String", FieldType::String),
+      ("std::string::String", FieldType::String),
+      ("&'a str", FieldType::Str),
+      ("Vec<u8>", FieldType::Bytes)
+    ];
+    for (ty, field_type) in types {
+      let ty = syn::parse_str::<Type>(ty).unwrap();
+      assert_eq!(FieldType::from_type(&ty), Some(field_type));
+    }
+
+    for ty in vec!["u64", "Vec<i32>", "&[u8]", "(i32, i64)", "HashMap<String, i32>"] {
+      let ty = syn::parse_str::<Type>(ty).unwrap();
+      assert_eq!(FieldType::from_type(&ty), None);
+    }
+  }
+
+  #[test]
+  fn test_field_schema() {
+    assert_eq!(field("a", "i32").schema(), "REQUIRED INT32 a;");
+    assert_eq!(field("b", "Option<i8>").schema(), "OPTIONAL INT32 b (INT_8);");
+    assert_eq!(field("c", "String").schema(), "REQUIRED BYTE_ARRAY c (UTF8);");
+    assert_eq!(field("d", "Option<&'a str>").schema(), "OPTIONAL BYTE_ARRAY d (UTF8);");
+    assert_eq!(field("e", "Vec<u8>").schema(), "REQUIRED BYTE_ARRAY e;");
+    assert_eq!(field("f", "bool").schema(), "REQUIRED BOOLEAN f;");
+  }
+
+  #[test]
+  #[should_panic(expected = "Unsupported type of field a")]
+  fn test_field_unsupported_type() {
+    field("a", "Option<Vec<String>>");
+  }
+
+  #[test]
+  fn test_message_type() {
+    let fields = vec![field("id", "i64"), field("name", "Option<String>")];
+    assert_eq!(
+      message_type("Record", &fields),
+      "message Record {\n  REQUIRED INT64 id;\n  OPTIONAL BYTE_ARRAY name (UTF8);\n}\n"
+    );
+  }
+}
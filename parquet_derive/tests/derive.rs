@@ -0,0 +1,134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+extern crate parquet;
+#[macro_use]
+extern crate parquet_derive;
+
+use std::env;
+use std::fs;
+use std::rc::Rc;
+
+use parquet::data_type::ByteArray;
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::{FileWriter, SerializedFileWriter};
+use parquet::record::api::Row;
+use parquet::record::writer::RecordWriter;
+
+#[derive(ParquetRecordWriter)]
+struct Record<'a> {
+  a_bool: bool,
+  a_byte: i8,
+  a_short: Option<i16>,
+  an_int: i32,
+  a_long: Option<i64>,
+  a_float: f32,
+  a_double: f64,
+  a_string: String,
+  a_str: &'a str,
+  maybe_str: Option<&'a str>,
+  bytes: Vec<u8>
+}
+
+#[test]
+fn test_derive_schema() {
+  let schema = Record::schema().unwrap();
+  let mut buf = Vec::new();
+  parquet::schema::printer::print_schema(&mut buf, &schema);
+  let expected = "message Record {
+  REQUIRED BOOLEAN a_bool;
+  REQUIRED INT32 a_byte (INT_8);
+  OPTIONAL INT32 a_short (INT_16);
+  REQUIRED INT32 an_int;
+  OPTIONAL INT64 a_long;
+  REQUIRED FLOAT a_float;
+  REQUIRED DOUBLE a_double;
+  REQUIRED BYTE_ARRAY a_string (UTF8);
+  REQUIRED BYTE_ARRAY a_str (UTF8);
+  OPTIONAL BYTE_ARRAY maybe_str (UTF8);
+  REQUIRED BYTE_ARRAY bytes;
+}
+";
+  assert_eq!(String::from_utf8(buf).unwrap(), expected);
+}
+
+#[test]
+fn test_derive_write_to_row_group() {
+  let name = String::from("b");
+  let records = vec![
+    Record {
+      a_bool: true,
+      a_byte: 1,
+      a_short: Some(2),
+      an_int: 3,
+      a_long: None,
+      a_float: 4.5,
+      a_double: 5.5,
+      a_string: String::from("a"),
+      a_str: &name,
+      maybe_str: None,
+      bytes: vec![1, 2, 3]
+    },
+    Record {
+      a_bool: false,
+      a_byte: -1,
+      a_short: None,
+      an_int: -3,
+      a_long: Some(7),
+      a_float: -4.5,
+      a_double: -5.5,
+      a_string: String::new(),
+      a_str: "c",
+      maybe_str: Some(&name),
+      bytes: vec![]
+    }
+  ];
+
+  let mut path = env::temp_dir();
+  path.push("parquet_derive_test_write_to_row_group.parquet");
+  let file = fs::File::create(&path).unwrap();
+  let props = Rc::new(WriterProperties::builder().build());
+  let schema = Record::schema().unwrap();
+  let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+  let mut row_group_writer = writer.next_row_group().unwrap();
+  Record::write_to_row_group(&records, &mut *row_group_writer).unwrap();
+  writer.close_row_group(row_group_writer).unwrap();
+  writer.close().unwrap();
+
+  let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+  let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+  assert_eq!(rows.len(), 2);
+  assert_eq!(rows[0], records[0].to_row());
+  assert_eq!(rows[1], records[1].to_row());
+  assert_eq!(
+    records[1].to_row(),
+    Row::Group(vec![
+      ("a_bool".to_string(), Row::Bool(false)),
+      ("a_byte".to_string(), Row::Byte(-1)),
+      ("a_short".to_string(), Row::Null),
+      ("an_int".to_string(), Row::Int(-3)),
+      ("a_long".to_string(), Row::Long(7)),
+      ("a_float".to_string(), Row::Float(-4.5)),
+      ("a_double".to_string(), Row::Double(-5.5)),
+      ("a_string".to_string(), Row::Str(String::new())),
+      ("a_str".to_string(), Row::Str("c".to_string())),
+      ("maybe_str".to_string(), Row::Str("b".to_string())),
+      ("bytes".to_string(), Row::Bytes(ByteArray::from(vec![])))
+    ])
+  );
+}
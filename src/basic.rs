@@ -248,6 +248,70 @@ pub enum PageType {
   DATA_PAGE_V2
 }
 
+// ----------------------------------------------------------------------
+// Sort order of values, not part of the Thrift definition
+
+/// Sort order for values of a column, which defines how min/max statistics are computed
+/// and compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+  /// Signed (either value or legacy byte-wise) comparison.
+  SIGNED,
+  /// Unsigned (depending on physical type either value or byte-wise) comparison.
+  UNSIGNED,
+  /// Comparison is undefined, statistics should not be used.
+  UNDEFINED
+}
+
+impl SortOrder {
+  /// Returns sort order for a column with physical type `physical_type` and logical
+  /// type `logical_type`.
+  pub fn from_types(physical_type: Type, logical_type: LogicalType) -> Self {
+    match logical_type {
+      // Unsigned byte-wise comparison
+      LogicalType::UTF8 | LogicalType::JSON | LogicalType::BSON | LogicalType::ENUM =>
+        SortOrder::UNSIGNED,
+
+      LogicalType::INT_8 | LogicalType::INT_16 | LogicalType::INT_32 |
+        LogicalType::INT_64 => SortOrder::SIGNED,
+
+      LogicalType::UINT_8 | LogicalType::UINT_16 | LogicalType::UINT_32 |
+        LogicalType::UINT_64 => SortOrder::UNSIGNED,
+
+      // Signed comparison of the represented value
+      LogicalType::DECIMAL => SortOrder::SIGNED,
+
+      LogicalType::DATE => SortOrder::SIGNED,
+
+      LogicalType::TIME_MILLIS | LogicalType::TIME_MICROS |
+        LogicalType::TIMESTAMP_MILLIS | LogicalType::TIMESTAMP_MICROS =>
+        SortOrder::SIGNED,
+
+      LogicalType::INTERVAL => SortOrder::UNDEFINED,
+
+      LogicalType::LIST | LogicalType::MAP | LogicalType::MAP_KEY_VALUE =>
+        SortOrder::UNDEFINED,
+
+      // Fall back to physical type
+      LogicalType::NONE => match physical_type {
+        // Order: false, true
+        Type::BOOLEAN => SortOrder::UNSIGNED,
+        Type::INT32 | Type::INT64 => SortOrder::SIGNED,
+        Type::INT96 => SortOrder::UNDEFINED,
+        // Notes to remember when comparing float/double values:
+        // If the min is a NaN, it should be ignored.
+        // If the max is a NaN, it should be ignored.
+        // If the min is +0, the row group may contain -0 values as well.
+        // If the max is -0, the row group may contain +0 values as well.
+        // When looking for NaN values, min and max should be ignored.
+        Type::FLOAT | Type::DOUBLE => SortOrder::SIGNED,
+        // Unsigned byte-wise comparison
+        Type::BYTE_ARRAY | Type::FIXED_LEN_BYTE_ARRAY => SortOrder::UNSIGNED
+      }
+    }
+  }
+}
+
 impl fmt::Display for Type {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     write!(f, "{:?}", self)
@@ -284,6 +348,12 @@ impl fmt::Display for PageType {
   }
 }
 
+impl fmt::Display for SortOrder {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
 impl convert::From<parquet::Type> for Type {
   fn from(tp: parquet::Type) -> Self {
     match tp {
@@ -383,6 +453,101 @@ impl convert::From<parquet::PageType> for PageType {
   }
 }
 
+impl convert::From<Type> for parquet::Type {
+  fn from(tp: Type) -> Self {
+    match tp {
+      Type::BOOLEAN => parquet::Type::BOOLEAN,
+      Type::INT32 => parquet::Type::INT32,
+      Type::INT64 => parquet::Type::INT64,
+      Type::INT96 => parquet::Type::INT96,
+      Type::FLOAT => parquet::Type::FLOAT,
+      Type::DOUBLE => parquet::Type::DOUBLE,
+      Type::BYTE_ARRAY => parquet::Type::BYTE_ARRAY,
+      Type::FIXED_LEN_BYTE_ARRAY => parquet::Type::FIXED_LEN_BYTE_ARRAY
+    }
+  }
+}
+
+impl convert::From<LogicalType> for Option<parquet::ConvertedType> {
+  fn from(tp: LogicalType) -> Self {
+    match tp {
+      LogicalType::NONE => None,
+      LogicalType::UTF8 => Some(parquet::ConvertedType::UTF8),
+      LogicalType::MAP => Some(parquet::ConvertedType::MAP),
+      LogicalType::MAP_KEY_VALUE => Some(parquet::ConvertedType::MAP_KEY_VALUE),
+      LogicalType::LIST => Some(parquet::ConvertedType::LIST),
+      LogicalType::ENUM => Some(parquet::ConvertedType::ENUM),
+      LogicalType::DECIMAL => Some(parquet::ConvertedType::DECIMAL),
+      LogicalType::DATE => Some(parquet::ConvertedType::DATE),
+      LogicalType::TIME_MILLIS => Some(parquet::ConvertedType::TIME_MILLIS),
+      LogicalType::TIME_MICROS => Some(parquet::ConvertedType::TIME_MICROS),
+      LogicalType::TIMESTAMP_MILLIS => Some(parquet::ConvertedType::TIMESTAMP_MILLIS),
+      LogicalType::TIMESTAMP_MICROS => Some(parquet::ConvertedType::TIMESTAMP_MICROS),
+      LogicalType::UINT_8 => Some(parquet::ConvertedType::UINT_8),
+      LogicalType::UINT_16 => Some(parquet::ConvertedType::UINT_16),
+      LogicalType::UINT_32 => Some(parquet::ConvertedType::UINT_32),
+      LogicalType::UINT_64 => Some(parquet::ConvertedType::UINT_64),
+      LogicalType::INT_8 => Some(parquet::ConvertedType::INT_8),
+      LogicalType::INT_16 => Some(parquet::ConvertedType::INT_16),
+      LogicalType::INT_32 => Some(parquet::ConvertedType::INT_32),
+      LogicalType::INT_64 => Some(parquet::ConvertedType::INT_64),
+      LogicalType::JSON => Some(parquet::ConvertedType::JSON),
+      LogicalType::BSON => Some(parquet::ConvertedType::BSON),
+      LogicalType::INTERVAL => Some(parquet::ConvertedType::INTERVAL)
+    }
+  }
+}
+
+impl convert::From<Repetition> for parquet::FieldRepetitionType {
+  fn from(tp: Repetition) -> Self {
+    match tp {
+      Repetition::REQUIRED => parquet::FieldRepetitionType::REQUIRED,
+      Repetition::OPTIONAL => parquet::FieldRepetitionType::OPTIONAL,
+      Repetition::REPEATED => parquet::FieldRepetitionType::REPEATED
+    }
+  }
+}
+
+impl convert::From<Encoding> for parquet::Encoding {
+  fn from(tp: Encoding) -> Self {
+    match tp {
+      Encoding::PLAIN => parquet::Encoding::PLAIN,
+      Encoding::PLAIN_DICTIONARY => parquet::Encoding::PLAIN_DICTIONARY,
+      Encoding::RLE => parquet::Encoding::RLE,
+      Encoding::BIT_PACKED => parquet::Encoding::BIT_PACKED,
+      Encoding::DELTA_BINARY_PACKED => parquet::Encoding::DELTA_BINARY_PACKED,
+      Encoding::DELTA_LENGTH_BYTE_ARRAY => parquet::Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Encoding::DELTA_BYTE_ARRAY => parquet::Encoding::DELTA_BYTE_ARRAY,
+      Encoding::RLE_DICTIONARY => parquet::Encoding::RLE_DICTIONARY
+    }
+  }
+}
+
+impl convert::From<Compression> for parquet::CompressionCodec {
+  fn from(tp: Compression) -> Self {
+    match tp {
+      Compression::UNCOMPRESSED => parquet::CompressionCodec::UNCOMPRESSED,
+      Compression::SNAPPY => parquet::CompressionCodec::SNAPPY,
+      Compression::GZIP => parquet::CompressionCodec::GZIP,
+      Compression::LZO => parquet::CompressionCodec::LZO,
+      Compression::BROTLI => parquet::CompressionCodec::BROTLI,
+      Compression::LZ4 => parquet::CompressionCodec::LZ4,
+      Compression::ZSTD => parquet::CompressionCodec::ZSTD
+    }
+  }
+}
+
+impl convert::From<PageType> for parquet::PageType {
+  fn from(tp: PageType) -> Self {
+    match tp {
+      PageType::DATA_PAGE => parquet::PageType::DATA_PAGE,
+      PageType::INDEX_PAGE => parquet::PageType::INDEX_PAGE,
+      PageType::DICTIONARY_PAGE => parquet::PageType::DICTIONARY_PAGE,
+      PageType::DATA_PAGE_V2 => parquet::PageType::DATA_PAGE_V2
+    }
+  }
+}
+
 impl str::FromStr for Repetition {
   type Err = ParquetError;
   fn from_str(s: &str) -> result::Result<Self, Self::Err> {
@@ -850,4 +1015,138 @@ mod tests {
     );
     assert_eq!(PageType::from(parquet::PageType::DATA_PAGE_V2), PageType::DATA_PAGE_V2);
   }
+
+  #[test]
+  fn test_into_thrift() {
+    let types = vec![
+      Type::BOOLEAN, Type::INT32, Type::INT64, Type::INT96, Type::FLOAT, Type::DOUBLE,
+      Type::BYTE_ARRAY, Type::FIXED_LEN_BYTE_ARRAY
+    ];
+    for tp in types {
+      assert_eq!(Type::from(parquet::Type::from(tp)), tp);
+    }
+
+    let logical_types = vec![
+      LogicalType::NONE, LogicalType::UTF8, LogicalType::MAP,
+      LogicalType::MAP_KEY_VALUE, LogicalType::LIST, LogicalType::ENUM,
+      LogicalType::DECIMAL, LogicalType::DATE, LogicalType::TIME_MILLIS,
+      LogicalType::TIME_MICROS, LogicalType::TIMESTAMP_MILLIS,
+      LogicalType::TIMESTAMP_MICROS, LogicalType::UINT_8, LogicalType::UINT_16,
+      LogicalType::UINT_32, LogicalType::UINT_64, LogicalType::INT_8,
+      LogicalType::INT_16, LogicalType::INT_32, LogicalType::INT_64, LogicalType::JSON,
+      LogicalType::BSON, LogicalType::INTERVAL
+    ];
+    for tp in logical_types {
+      let converted: Option<parquet::ConvertedType> = tp.into();
+      assert_eq!(LogicalType::from(converted), tp);
+    }
+    assert_eq!(Option::<parquet::ConvertedType>::from(LogicalType::NONE), None);
+
+    for tp in vec![Repetition::REQUIRED, Repetition::OPTIONAL, Repetition::REPEATED] {
+      assert_eq!(Repetition::from(parquet::FieldRepetitionType::from(tp)), tp);
+    }
+
+    let encodings = vec![
+      Encoding::PLAIN, Encoding::PLAIN_DICTIONARY, Encoding::RLE, Encoding::BIT_PACKED,
+      Encoding::DELTA_BINARY_PACKED, Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Encoding::DELTA_BYTE_ARRAY, Encoding::RLE_DICTIONARY
+    ];
+    for tp in encodings {
+      assert_eq!(Encoding::from(parquet::Encoding::from(tp)), tp);
+    }
+
+    let codecs = vec![
+      Compression::UNCOMPRESSED, Compression::SNAPPY, Compression::GZIP,
+      Compression::LZO, Compression::BROTLI, Compression::LZ4, Compression::ZSTD
+    ];
+    for tp in codecs {
+      assert_eq!(Compression::from(parquet::CompressionCodec::from(tp)), tp);
+    }
+
+    let page_types = vec![
+      PageType::DATA_PAGE, PageType::INDEX_PAGE, PageType::DICTIONARY_PAGE,
+      PageType::DATA_PAGE_V2
+    ];
+    for tp in page_types {
+      assert_eq!(PageType::from(parquet::PageType::from(tp)), tp);
+    }
+  }
+
+  #[test]
+  fn test_display_sort_order() {
+    assert_eq!(SortOrder::SIGNED.to_string(), "SIGNED");
+    assert_eq!(SortOrder::UNSIGNED.to_string(), "UNSIGNED");
+    assert_eq!(SortOrder::UNDEFINED.to_string(), "UNDEFINED");
+  }
+
+  #[test]
+  fn test_sort_order_from_types() {
+    fn check_sort_order(types: Vec<LogicalType>, expected_order: SortOrder) {
+      for tpe in types {
+        assert_eq!(SortOrder::from_types(Type::BYTE_ARRAY, tpe), expected_order);
+      }
+    }
+
+    // Unsigned comparison (physical type does not matter)
+    let unsigned = vec![
+      LogicalType::UTF8,
+      LogicalType::JSON,
+      LogicalType::BSON,
+      LogicalType::ENUM,
+      LogicalType::UINT_8,
+      LogicalType::UINT_16,
+      LogicalType::UINT_32,
+      LogicalType::UINT_64
+    ];
+    check_sort_order(unsigned, SortOrder::UNSIGNED);
+
+    // Signed comparison (physical type does not matter)
+    let signed = vec![
+      LogicalType::INT_8,
+      LogicalType::INT_16,
+      LogicalType::INT_32,
+      LogicalType::INT_64,
+      LogicalType::DECIMAL,
+      LogicalType::DATE,
+      LogicalType::TIME_MILLIS,
+      LogicalType::TIME_MICROS,
+      LogicalType::TIMESTAMP_MILLIS,
+      LogicalType::TIMESTAMP_MICROS
+    ];
+    check_sort_order(signed, SortOrder::SIGNED);
+
+    // Undefined comparison
+    let undefined = vec![
+      LogicalType::LIST,
+      LogicalType::MAP,
+      LogicalType::MAP_KEY_VALUE,
+      LogicalType::INTERVAL
+    ];
+    check_sort_order(undefined, SortOrder::UNDEFINED);
+
+    // Check None logical type
+    assert_eq!(
+      SortOrder::from_types(Type::BOOLEAN, LogicalType::NONE),
+      SortOrder::UNSIGNED
+    );
+    assert_eq!(SortOrder::from_types(Type::INT32, LogicalType::NONE), SortOrder::SIGNED);
+    assert_eq!(SortOrder::from_types(Type::INT64, LogicalType::NONE), SortOrder::SIGNED);
+    assert_eq!(
+      SortOrder::from_types(Type::INT96, LogicalType::NONE),
+      SortOrder::UNDEFINED
+    );
+    assert_eq!(SortOrder::from_types(Type::FLOAT, LogicalType::NONE), SortOrder::SIGNED);
+    assert_eq!(
+      SortOrder::from_types(Type::DOUBLE, LogicalType::NONE),
+      SortOrder::SIGNED
+    );
+    assert_eq!(
+      SortOrder::from_types(Type::BYTE_ARRAY, LogicalType::NONE),
+      SortOrder::UNSIGNED
+    );
+    assert_eq!(
+      SortOrder::from_types(Type::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE),
+      SortOrder::UNSIGNED
+    );
+  }
 }
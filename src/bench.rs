@@ -0,0 +1,339 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains benchmark harness to measure throughput of decoders and readers.
+//!
+//! - [`decode`](`decode`) measures decoding of an encoded buffer.
+//! - [`scan_columns`](`scan_columns`) reads every column of a file with typed column
+//! readers.
+//! - [`scan_rows`](`scan_rows`) reads all records of a file.
+//!
+//! Each function returns [`Throughput`](`Throughput`) of a single run.
+//!
+//! These functions back the criterion benchmarks of the crate, which are enabled with
+//! the `bench` feature (`cargo bench --features bench`), and the `parquet-bench`
+//! binary, which benchmarks reading a user-supplied file.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//!
+//! use parquet::basic::Type;
+//! use parquet::bench::decode;
+//! use parquet::data_type::Int32Type;
+//! use parquet::decoding::PlainDecoder;
+//! use parquet::encoding::{Encoder, PlainEncoder};
+//! use parquet::memory::MemTracker;
+//! use parquet::schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
+//!
+//! let primitive = SchemaType::primitive_type_builder("col", Type::INT32)
+//!   .build()
+//!   .unwrap();
+//! let descr = Rc::new(
+//!   ColumnDescriptor::new(Rc::new(primitive), None, 0, 0, ColumnPath::from("col")));
+//!
+//! let values: Vec<i32> = (0..1000).collect();
+//! let mem_tracker = Rc::new(MemTracker::new());
+//! let mut encoder = PlainEncoder::<Int32Type>::new(descr, mem_tracker, vec![]);
+//! encoder.put(&values).unwrap();
+//! let data = encoder.flush_buffer().unwrap();
+//!
+//! let mut decoder = PlainDecoder::<Int32Type>::new(0);
+//! let throughput = decode(&mut decoder, data, values.len(), 128).unwrap();
+//! assert_eq!(throughput.num_values(), 1000);
+//! assert_eq!(throughput.num_bytes(), 4000);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use column::reader::{ColumnReader, ColumnReaderImpl};
+use data_type::DataType;
+use encodings::decoding::Decoder;
+use errors::Result;
+use file::reader::FileReader;
+use util::memory::ByteBufferPtr;
+
+/// Default number of values read at once by column scans.
+pub const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Number of rows, values and bytes processed by a benchmark run, and its duration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Throughput {
+  num_rows: u64,
+  num_values: u64,
+  num_bytes: u64,
+  elapsed: Duration
+}
+
+impl Throughput {
+  /// Creates throughput from counters and duration of a run.
+  pub fn new(num_rows: u64, num_values: u64, num_bytes: u64, elapsed: Duration) -> Self {
+    Self {
+      num_rows: num_rows,
+      num_values: num_values,
+      num_bytes: num_bytes,
+      elapsed: elapsed
+    }
+  }
+
+  /// Returns the number of rows processed, `0` for decoding runs.
+  pub fn num_rows(&self) -> u64 {
+    self.num_rows
+  }
+
+  /// Returns the number of values processed, including nulls.
+  pub fn num_values(&self) -> u64 {
+    self.num_values
+  }
+
+  /// Returns the number of encoded bytes processed. For file scans this is the
+  /// compressed size of column chunks.
+  pub fn num_bytes(&self) -> u64 {
+    self.num_bytes
+  }
+
+  /// Returns duration of the run.
+  pub fn elapsed(&self) -> Duration {
+    self.elapsed
+  }
+
+  /// Returns rows processed per second.
+  pub fn rows_per_sec(&self) -> f64 {
+    self.per_sec(self.num_rows)
+  }
+
+  /// Returns values processed per second.
+  pub fn values_per_sec(&self) -> f64 {
+    self.per_sec(self.num_values)
+  }
+
+  /// Returns bytes processed per second.
+  pub fn bytes_per_sec(&self) -> f64 {
+    self.per_sec(self.num_bytes)
+  }
+
+  fn per_sec(&self, count: u64) -> f64 {
+    let nanos = self.elapsed.subsec_nanos() as f64;
+    let secs = self.elapsed.as_secs() as f64 + nanos / 1_000_000_000.0;
+    if secs > 0.0 { count as f64 / secs } else { 0.0 }
+  }
+}
+
+/// Decodes `num_values` values of the encoded buffer `data` with `decoder` in batches of
+/// `batch_size` values.
+///
+/// Decoder must be ready to decode data, e.g. dictionary decoder must have its
+/// dictionary set. Returns an error if decoding fails.
+pub fn decode<T: DataType>(
+  decoder: &mut Decoder<T>,
+  data: ByteBufferPtr,
+  num_values: usize,
+  batch_size: usize
+) -> Result<Throughput> {
+  assert!(batch_size > 0, "Batch size must be positive");
+  let num_bytes = data.len() as u64;
+  let mut values = vec![T::T::default(); batch_size];
+  let start = Instant::now();
+  decoder.set_data(data, num_values)?;
+  let mut num_decoded = 0;
+  loop {
+    let num_read = decoder.get(&mut values[..])?;
+    num_decoded += num_read;
+    if num_read < batch_size {
+      break;
+    }
+  }
+  Ok(Throughput::new(0, num_decoded as u64, num_bytes, start.elapsed()))
+}
+
+/// Reads all values and levels of every column of every row group with typed column
+/// readers, in batches of `batch_size` values.
+pub fn scan_columns(reader: &FileReader, batch_size: usize) -> Result<Throughput> {
+  assert!(batch_size > 0, "Batch size must be positive");
+  let start = Instant::now();
+  let mut num_values = 0;
+  for i in 0..reader.num_row_groups() {
+    let row_group_reader = reader.get_row_group(i)?;
+    for j in 0..row_group_reader.num_columns() {
+      num_values += match row_group_reader.get_column_reader(j)? {
+        ColumnReader::BoolColumnReader(ref mut typed) => scan_column(typed, batch_size)?,
+        ColumnReader::Int32ColumnReader(ref mut typed) => scan_column(typed, batch_size)?,
+        ColumnReader::Int64ColumnReader(ref mut typed) => scan_column(typed, batch_size)?,
+        ColumnReader::Int96ColumnReader(ref mut typed) => scan_column(typed, batch_size)?,
+        ColumnReader::FloatColumnReader(ref mut typed) => scan_column(typed, batch_size)?,
+        ColumnReader::DoubleColumnReader(ref mut typed) => {
+          scan_column(typed, batch_size)?
+        },
+        ColumnReader::ByteArrayColumnReader(ref mut typed) => {
+          scan_column(typed, batch_size)?
+        },
+        ColumnReader::FixedLenByteArrayColumnReader(ref mut typed) => {
+          scan_column(typed, batch_size)?
+        }
+      };
+    }
+  }
+  let elapsed = start.elapsed();
+  let (num_rows, num_bytes) = file_size(reader);
+  Ok(Throughput::new(num_rows, num_values, num_bytes, elapsed))
+}
+
+/// Reads all records of the file with the record reader.
+pub fn scan_rows(reader: &FileReader) -> Result<Throughput> {
+  let start = Instant::now();
+  let num_read = reader.get_row_iter(None)?.count() as u64;
+  let elapsed = start.elapsed();
+  let (_, num_bytes) = file_size(reader);
+  let num_values = reader.metadata().row_groups().iter()
+    .flat_map(|row_group| row_group.columns().iter())
+    .map(|column| column.num_values() as u64)
+    .sum();
+  Ok(Throughput::new(num_read, num_values, num_bytes, elapsed))
+}
+
+/// Reads all values and levels of the column, returns the number of levels read, or
+/// the number of values for required columns.
+fn scan_column<T: DataType>(
+  reader: &mut ColumnReaderImpl<T>,
+  batch_size: usize
+) -> Result<u64> {
+  let mut values = vec![T::T::default(); batch_size];
+  let mut def_levels = vec![0; batch_size];
+  let mut rep_levels = vec![0; batch_size];
+  let mut num_read = 0;
+  loop {
+    let (values_read, levels_read) = reader.read_batch(
+      batch_size,
+      Some(&mut def_levels[..]),
+      Some(&mut rep_levels[..]),
+      &mut values[..]
+    )?;
+    let batch_read = values_read.max(levels_read);
+    if batch_read == 0 {
+      break;
+    }
+    num_read += batch_read as u64;
+  }
+  Ok(num_read)
+}
+
+/// Returns the number of rows and compressed size of all column chunks of the file.
+fn file_size(reader: &FileReader) -> (u64, u64) {
+  let metadata = reader.metadata();
+  let num_rows = metadata.row_groups().iter()
+    .map(|row_group| row_group.num_rows() as u64)
+    .sum();
+  let num_bytes = metadata.row_groups().iter()
+    .flat_map(|row_group| row_group.columns().iter())
+    .map(|column| column.compressed_size() as u64)
+    .sum();
+  (num_rows, num_bytes)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::io::Write;
+  use std::rc::Rc;
+
+  use basic::{Encoding, Type as PhysicalType};
+  use data_type::Int32Type;
+  use encodings::decoding::{get_decoder, DictDecoder, PlainDecoder};
+  use encodings::encoding::{DictEncoder, Encoder, PlainEncoder};
+  use file::builder::InMemoryFileBuilder;
+  use file::reader::SerializedFileReader;
+  use record::api::Row;
+  use schema::parser::parse_message_type;
+  use schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
+  use util::memory::MemTracker;
+  use util::test_common::get_temp_filename;
+
+  #[test]
+  fn test_throughput() {
+    let throughput = Throughput::new(10, 20, 40, Duration::from_millis(500));
+    assert_eq!(throughput.rows_per_sec(), 20.0);
+    assert_eq!(throughput.values_per_sec(), 40.0);
+    assert_eq!(throughput.bytes_per_sec(), 80.0);
+
+    let throughput = Throughput::new(10, 20, 40, Duration::from_secs(0));
+    assert_eq!(throughput.rows_per_sec(), 0.0);
+  }
+
+  #[test]
+  fn test_decode() {
+    let primitive = SchemaType::primitive_type_builder("col", PhysicalType::INT32)
+      .build()
+      .unwrap();
+    let descr = Rc::new(
+      ColumnDescriptor::new(Rc::new(primitive), None, 0, 0, ColumnPath::from("col")));
+    let mem_tracker = Rc::new(MemTracker::new());
+    let values: Vec<i32> = (0..1000).map(|i| i % 10).collect();
+
+    let mut encoder =
+      PlainEncoder::<Int32Type>::new(descr.clone(), mem_tracker.clone(), vec![]);
+    encoder.put(&values).unwrap();
+    let data = encoder.flush_buffer().unwrap();
+    let mut decoder = get_decoder::<Int32Type>(descr.clone(), Encoding::PLAIN).unwrap();
+    let throughput = decode(&mut *decoder, data, values.len(), 128).unwrap();
+    assert_eq!(throughput.num_values(), 1000);
+    assert_eq!(throughput.num_bytes(), 4000);
+
+    let mut encoder = DictEncoder::<Int32Type>::new(descr, mem_tracker);
+    encoder.put(&values).unwrap();
+    let mut dict_decoder = PlainDecoder::<Int32Type>::new(0);
+    dict_decoder.set_data(encoder.write_dict().unwrap(), encoder.num_entries()).unwrap();
+    let data = encoder.flush_buffer().unwrap();
+    let mut decoder = DictDecoder::<Int32Type>::new();
+    decoder.set_dict(Box::new(dict_decoder)).unwrap();
+    let throughput = decode(&mut decoder, data, values.len(), 100).unwrap();
+    assert_eq!(throughput.num_values(), 1000);
+  }
+
+  #[test]
+  fn test_scan() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BYTE_ARRAY b (UTF8);
+      }
+    ";
+    let rows: Vec<Row> = (0..100).map(|i| {
+      let b = if i % 2 == 0 { Row::Str(i.to_string()) } else { Row::Null };
+      Row::Group(vec![("a".to_string(), Row::Int(i)), ("b".to_string(), b)])
+    }).collect();
+    let bytes = InMemoryFileBuilder::new(Rc::new(parse_message_type(schema).unwrap()))
+      .with_rows(rows[..60].to_vec())
+      .with_rows(rows[60..].to_vec())
+      .build()
+      .unwrap();
+    let path = get_temp_filename("test_bench_scan.parquet");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+
+    let throughput = scan_columns(&reader, 16).unwrap();
+    assert_eq!(throughput.num_rows(), 100);
+    assert_eq!(throughput.num_values(), 200);
+    assert!(throughput.num_bytes() > 0);
+
+    let throughput = scan_rows(&reader).unwrap();
+    assert_eq!(throughput.num_rows(), 100);
+    assert_eq!(throughput.num_values(), 200);
+  }
+}
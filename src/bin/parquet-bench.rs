@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to benchmark reading a Parquet file.
+//!
+//! # Install
+//!
+//! `parquet-bench` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-bench` should be globally available:
+//! ```
+//! parquet-bench XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --release --bin parquet-bench XYZ.parquet
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-bench <file-path> [iterations]
+//! ```
+//! where `file-path` is the path to a Parquet file and `iterations` is the optional
+//! number of times the file is scanned (default is 5).
+//!
+//! The file is scanned with typed column readers and with the record reader, and the
+//! best and mean throughput of each scan is printed in rows, values and compressed bytes
+//! per second.
+//!
+//! For example,
+//! ```
+//! parquet-bench data/alltypes_plain.snappy.parquet 10
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use parquet::bench::{scan_columns, scan_rows, Throughput, DEFAULT_BATCH_SIZE};
+use parquet::errors::Result;
+use parquet::file::reader::SerializedFileReader;
+
+const DEFAULT_ITERATIONS: usize = 5;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 && args.len() != 3 {
+    println!("Usage: parquet-bench <file-path> [iterations]");
+    process::exit(1);
+  }
+  let path = Path::new(&args[1]);
+  let mut iterations = DEFAULT_ITERATIONS;
+  if args.len() == 3 {
+    match args[2].parse() {
+      Ok(n) if n > 0 => iterations = n,
+      Ok(_) => panic!("Error when reading value for [iterations], must be positive"),
+      Err(e) => panic!("Error when reading value for [iterations], {}", e)
+    }
+  }
+  let parquet_reader = match SerializedFileReader::try_from_path(&path) {
+    Err(e) => panic!("{}", e),
+    Ok(reader) => reader
+  };
+
+  println!("Benchmark for file: {} ({} iterations)", &args[1], iterations);
+  print_throughput(
+    "column scan",
+    run(iterations, || scan_columns(&parquet_reader, DEFAULT_BATCH_SIZE))
+  );
+  print_throughput("record scan", run(iterations, || scan_rows(&parquet_reader)));
+}
+
+/// Runs the scan `iterations` times and returns throughput of every run.
+fn run<F: Fn() -> Result<Throughput>>(iterations: usize, scan: F) -> Vec<Throughput> {
+  (0..iterations).map(|_| match scan() {
+    Err(e) => panic!("Error when scanning Parquet file: {}", e),
+    Ok(throughput) => throughput
+  }).collect()
+}
+
+fn print_throughput(name: &str, runs: Vec<Throughput>) {
+  let best = runs.iter().min_by_key(|run| run.elapsed()).unwrap();
+  let mean_rows_per_sec =
+    runs.iter().map(|run| run.rows_per_sec()).sum::<f64>() / runs.len() as f64;
+  println!("");
+  println!("{}:", name);
+  println!(
+    "  rows: {}, values: {}, compressed bytes: {}",
+    best.num_rows(),
+    best.num_values(),
+    best.num_bytes()
+  );
+  println!("  best time: {:?}", best.elapsed());
+  println!(
+    "  best: {:.0} rows/s, {:.0} values/s, {:.2} MB/s",
+    best.rows_per_sec(),
+    best.values_per_sec(),
+    best.bytes_per_sec() / (1024.0 * 1024.0)
+  );
+  println!("  mean: {:.0} rows/s", mean_rows_per_sec);
+}
@@ -0,0 +1,70 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! Binary file to write golden fixture files for interoperability testing.
+//!
+//! # Install
+//!
+//! `parquet-fixtures` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-fixtures` should be globally available:
+//! ```
+//! parquet-fixtures fixtures/
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-fixtures fixtures/
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-fixtures <dir-path>
+//! ```
+//! where `dir-path` is the path to a directory, which is created if it does not exist.
+//!
+//! One file is written for every combination of physical type, encoding, compression
+//! codec and nullability supported by this crate, see [`parquet::file::fixtures`] for
+//! the values of the files and the naming scheme, e.g.
+//! `int32_delta_binary_packed_snappy_optional.parquet`.
+
+extern crate parquet;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use parquet::file::fixtures::write_fixtures;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 {
+    println!("Usage: parquet-fixtures <dir-path>");
+    process::exit(1);
+  }
+
+  let paths = match write_fixtures(Path::new(&args[1])) {
+    Err(e) => panic!("{}", e),
+    Ok(paths) => paths
+  };
+  for path in &paths {
+    println!("{}", path.display());
+  }
+  println!("Written {} files", paths.len());
+}
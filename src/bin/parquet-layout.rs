@@ -0,0 +1,162 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Binary file to print the physical layout of a Parquet file.
+//!
+//! # Install
+//!
+//! `parquet-layout` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-layout` should be globally available:
+//! ```
+//! parquet-layout XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-layout XYZ.parquet
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-layout <file-path> [verbose]
+//! ```
+//! where `file-path` is the path to a Parquet file and `verbose` is the optional boolean
+//! flag that allows to print a summary per column chunk only, when set to `false`
+//! (default behaviour when not provided), or additionally list every page, when set to
+//! `true`.
+//!
+//! Summary includes number of pages, compression ratio, encodings of data pages,
+//! histograms of page sizes and values per page, which can be used to tune writer
+//! properties, and min/max values of column chunk statistics, formatted according to
+//! the logical type of the column. Layout is collected from page headers, page data
+//! is not decoded.
+//!
+//! If the file was written with column chunk checksums, checksum of every column chunk
+//! is verified against its bytes in the file, and the summary reports whether it
+//! matches.
+//!
+//! For example,
+//! ```
+//! parquet-layout data/alltypes_plain.snappy.parquet
+//!
+//! parquet-layout data/alltypes_plain.snappy.parquet true
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use parquet::file::layout::ColumnChunkLayout;
+use parquet::file::metadata::ColumnChunkMetaData;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 && args.len() != 3 {
+    println!("Usage: parquet-layout <file-path> [verbose]");
+    process::exit(1);
+  }
+  let path = Path::new(&args[1]);
+  let mut verbose = false;
+  if args.len() == 3 {
+    match args[2].parse() {
+      Ok(b) => verbose = b,
+      Err(e) => panic!(
+        "Error when reading value for [verbose] \
+         (expected either 'true' or 'false'): {}",
+        e
+      )
+    }
+  }
+  let parquet_reader = match SerializedFileReader::try_from_path(&path) {
+    Err(e) => panic!("{}", e),
+    Ok(reader) => reader
+  };
+  let layout = match parquet_reader.layout() {
+    Err(e) => panic!("Error when reading layout of Parquet file: {}", e),
+    Ok(layout) => layout
+  };
+
+  let metadata = parquet_reader.metadata();
+
+  println!("Layout for file: {}", &args[1]);
+  for (i, row_group) in layout.row_groups().iter().enumerate() {
+    println!("");
+    println!("row group {} (num rows: {})", i, row_group.num_rows());
+    for (j, column) in row_group.columns().iter().enumerate() {
+      let checksum = match parquet_reader.verify_column_checksum(i, j) {
+        Ok(true) => "verified".to_string(),
+        Ok(false) => "n/a".to_string(),
+        Err(e) => format!("{}", e)
+      };
+      print_column_layout(column, metadata.row_group(i).column(j), &checksum, verbose);
+    }
+  }
+}
+
+fn print_column_layout(
+  column: &ColumnChunkLayout,
+  metadata: &ColumnChunkMetaData,
+  checksum: &str,
+  verbose: bool
+) {
+  println!("  column: {}", column.column_path());
+  println!("    compression: {}", column.compression());
+  println!(
+    "    num pages: {} ({} data pages)",
+    column.pages().len(),
+    column.num_data_pages()
+  );
+  println!(
+    "    size: {} compressed, {} uncompressed, ratio: {}",
+    column.compressed_size(),
+    column.uncompressed_size(),
+    column.compression_ratio().map(|r| format!("{:.2}", r)).unwrap_or("n/a".to_string())
+  );
+  let encodings: Vec<String> = column.encoding_mix().iter()
+    .map(|&(encoding, count)| format!("{}: {}", encoding, count))
+    .collect();
+  println!("    data page encodings: {}", encodings.join(", "));
+  println!("    data page sizes: {}", column.page_size_histogram());
+  println!("    values per data page: {}", column.values_per_page_histogram());
+  let min_max = metadata.statistics()
+    .and_then(|stats| stats.logical_min_max(metadata.column_descr()))
+    .map(|(min, max)| format!("{} - {}", min, max));
+  println!("    min/max: {}", min_max.unwrap_or("n/a".to_string()));
+  println!("    checksum: {}", checksum);
+  if verbose {
+    for page in column.pages() {
+      println!(
+        "    page {} at {}: header {} bytes, data {} bytes ({} uncompressed), \
+         values: {}, encoding: {}",
+        page.page_type,
+        page.offset,
+        page.header_size,
+        page.compressed_size,
+        page.uncompressed_size,
+        page.num_values,
+        page.encoding.map(|e| e.to_string()).unwrap_or("n/a".to_string())
+      );
+    }
+  }
+}
@@ -36,62 +36,101 @@
 //! # Usage
 //!
 //! ```
-//! parquet-read <file-path> [num-records]
+//! parquet-read <file-path> [num-records] [filter]
 //! ```
 //! where `file-path` is the path to a Parquet file and `num-records` is the optional
 //! numeric option that allows to specify number of records to read from a file.
 //! When not provided, all records are read.
 //!
-//! Note that `parquet-read` reads full file schema, no projection or filtering is
-//! applied.
+//! `filter` is the optional filter expression, e.g. `"a > 5 AND b = 'x'"`, see
+//! [`parquet::file::filter`] for the supported syntax. Row groups that cannot contain
+//! matching records according to column statistics are skipped, and only matching
+//! records are printed. If the second argument is not a number, it is treated as a
+//! filter.
+//!
+//! Note that `parquet-read` reads full file schema, no projection is applied.
 //!
 //! For example,
 //! ```
 //! parquet-read data/alltypes_plain.snappy.parquet
 //!
 //! parquet-read data/alltypes_plain.snappy.parquet 4
+//!
+//! parquet-read data/alltypes_plain.snappy.parquet 4 "id > 2 AND bool_col = true"
 //! ```
 
 extern crate parquet;
 
 use std::env;
-use std::fs::File;
 use std::path::Path;
 use std::process;
 
+use parquet::file::compat::WriterCompat;
+use parquet::file::filter::{parse_filter, Expr, Pruner};
 use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::reader::RowIter;
+
+fn print_usage_and_exit() -> ! {
+  println!("Usage: parquet-read <file-path> [num-records] [filter]");
+  process::exit(1);
+}
 
 fn main() {
   let args: Vec<String> = env::args().collect();
-  if args.len() != 2 && args.len() != 3 {
-    println!("Usage: parquet-read <file-path> [num-records]");
-    process::exit(1);
+  if args.len() < 2 || args.len() > 4 {
+    print_usage_and_exit();
   }
 
   let mut num_records: Option<usize> = None;
-  if args.len() == 3 {
-    match args[2].parse() {
-      Ok(value) => num_records = Some(value),
-      Err(e) => panic!("Error when reading value for [num-records], {}", e)
+  let mut filter: Option<Expr> = None;
+  for (i, arg) in args.iter().enumerate().skip(2) {
+    match arg.parse() {
+      Ok(value) if i == 2 => num_records = Some(value),
+      Err(_) if filter.is_none() => match parse_filter(arg) {
+        Ok(expr) => filter = Some(expr),
+        Err(e) => panic!("Error when reading value for [filter], {}", e)
+      },
+      _ => print_usage_and_exit()
     }
   }
 
   let path = Path::new(&args[1]);
-  let file = File::open(&path).unwrap();
-  let parquet_reader = SerializedFileReader::new(file).unwrap();
+  let parquet_reader = match SerializedFileReader::try_from_path(&path) {
+    Err(e) => panic!("{}", e),
+    Ok(reader) => reader
+  };
 
-  // Use full schema as projected schema
-  let mut iter = parquet_reader.get_row_iter(None).unwrap();
-
-  let mut start = 0;
-  let end = num_records.unwrap_or(0);
-  let all_records = num_records.is_none();
+  let metadata = parquet_reader.metadata();
+  let file_metadata = metadata.file_metadata();
+  if let Some(ref expr) = filter {
+    if let Err(e) = expr.validate(file_metadata.schema_descr()) {
+      panic!("Error when reading value for [filter], {}", e);
+    }
+  }
+  let created_by = file_metadata.created_by().as_ref().map(|s| s.as_str());
+  let pruner =
+    filter.map(|expr| Pruner::new(expr).with_compat(WriterCompat::new(created_by)));
 
-  while all_records || start < end {
-    match iter.next() {
-      Some(row) => println!("{}", row),
-      None => break
+  let mut num_printed = 0;
+  for i in 0..parquet_reader.num_row_groups() {
+    if let Some(ref pruner) = pruner {
+      if !pruner.might_match(&metadata.row_group(i)) {
+        continue;
+      }
+    }
+    let row_group_reader = parquet_reader.get_row_group(i).unwrap();
+    // Use full schema as projected schema
+    let iter = RowIter::from_row_group(None, &*row_group_reader)
+      .unwrap()
+      .with_compat(WriterCompat::new(created_by));
+    for row in iter {
+      if num_records.map_or(false, |end| num_printed >= end) {
+        return;
+      }
+      if pruner.as_ref().map_or(true, |pruner| pruner.expr().matches(&row)) {
+        println!("{}", row);
+        num_printed += 1;
+      }
     }
-    start += 1;
   }
 }
@@ -0,0 +1,116 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! Binary file to count rows in a Parquet file.
+//!
+//! # Install
+//!
+//! `parquet-rowcount` can be installed using `cargo`:
+//! ```
+//! cargo install parquet
+//! ```
+//! After this `parquet-rowcount` should be globally available:
+//! ```
+//! parquet-rowcount XYZ.parquet
+//! ```
+//!
+//! The binary can also be built from the source code and run as follows:
+//! ```
+//! cargo run --bin parquet-rowcount XYZ.parquet
+//! ```
+//!
+//! # Usage
+//!
+//! ```
+//! parquet-rowcount <file-path> [filter]
+//! ```
+//! where `file-path` is the path to a Parquet file and `filter` is the optional filter
+//! expression, e.g. `"a > 5 AND b = 'x'"`, see [`parquet::file::filter`] for the
+//! supported syntax.
+//!
+//! When filter is not provided, number of rows is read from the file metadata.
+//! Otherwise row groups that cannot contain matching records according to column
+//! statistics are skipped, and records of the remaining row groups are read and
+//! counted.
+//!
+//! For example,
+//! ```
+//! parquet-rowcount data/alltypes_plain.snappy.parquet
+//!
+//! parquet-rowcount data/alltypes_plain.snappy.parquet "id > 2 AND bool_col = true"
+//! ```
+
+extern crate parquet;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use parquet::file::compat::WriterCompat;
+use parquet::file::filter::{parse_filter, Pruner};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::reader::RowIter;
+
+fn main() {
+  let args: Vec<String> = env::args().collect();
+  if args.len() != 2 && args.len() != 3 {
+    println!("Usage: parquet-rowcount <file-path> [filter]");
+    process::exit(1);
+  }
+
+  let path = Path::new(&args[1]);
+  let parquet_reader = match SerializedFileReader::try_from_path(&path) {
+    Err(e) => panic!("{}", e),
+    Ok(reader) => reader
+  };
+  let metadata = parquet_reader.metadata();
+  let file_metadata = metadata.file_metadata();
+
+  if args.len() == 2 {
+    println!("Total number of rows: {}", file_metadata.num_rows());
+    return;
+  }
+
+  let filter = match parse_filter(&args[2]) {
+    Ok(expr) => expr,
+    Err(e) => panic!("Error when reading value for [filter], {}", e)
+  };
+  if let Err(e) = filter.validate(file_metadata.schema_descr()) {
+    panic!("Error when reading value for [filter], {}", e);
+  }
+  let created_by = file_metadata.created_by().as_ref().map(|s| s.as_str());
+  let pruner = Pruner::new(filter).with_compat(WriterCompat::new(created_by));
+
+  let mut num_rows = 0;
+  let mut num_skipped = 0;
+  for i in 0..parquet_reader.num_row_groups() {
+    if !pruner.might_match(&metadata.row_group(i)) {
+      num_skipped += 1;
+      continue;
+    }
+    let row_group_reader = parquet_reader.get_row_group(i).unwrap();
+    let iter = RowIter::from_row_group(None, &*row_group_reader)
+      .unwrap()
+      .with_compat(WriterCompat::new(created_by));
+    num_rows += iter.filter(|row| pruner.expr().matches(row)).count();
+  }
+  println!(
+    "Skipped row groups: {} of {}",
+    num_skipped,
+    parquet_reader.num_row_groups()
+  );
+  println!("Number of matching rows: {}", num_rows);
+}
@@ -53,7 +53,6 @@
 extern crate parquet;
 
 use std::env;
-use std::fs::File;
 use std::path::Path;
 use std::process;
 
@@ -78,12 +77,8 @@ fn main() {
       )
     }
   }
-  let file = match File::open(&path) {
-    Err(e) => panic!("Error when opening file {}: {}", path.display(), e),
-    Ok(f) => f
-  };
-  match SerializedFileReader::new(file) {
-    Err(e) => panic!("Error when parsing Parquet file: {}", e),
+  match SerializedFileReader::try_from_path(&path) {
+    Err(e) => panic!("{}", e),
     Ok(parquet_reader) => {
       let metadata = parquet_reader.metadata();
       println!("Metadata for file: {}", &args[1]);
@@ -0,0 +1,257 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains split block Bloom filter (SBBF) implementation, as described in the
+//! Parquet specification.
+//!
+//! Filter consists of 256-bit blocks, each block is 8 words of 32 bits. Values are
+//! hashed with XXH64 (seed `0`) over their plain encoded bytes, the upper 32 bits of
+//! the hash select a block and the lower 32 bits set one bit in every word of the
+//! block.
+//!
+//! Bloom filters are built by the column writer when enabled in writer properties,
+//! see `with_bloom_filter_enabled`, and the bitset is written right after the pages of
+//! the column chunk. Thrift definitions of the supported Parquet format version do not
+//! have fields for Bloom filter location yet, so offset and length of the bitset are
+//! stored in key/value metadata of the column chunk under keys in the `parquet-rs.`
+//! namespace of this crate. Bloom filters are therefore only read back by this crate,
+//! with `RowGroupReader::get_column_bloom_filter`, while other implementations, which
+//! look for the location in `ColumnChunk` fields, do not find them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::bloom_filter::Sbbf;
+//!
+//! let mut filter = Sbbf::new_with_ndv_fpp(1000, 0.01).unwrap();
+//! filter.insert(&"parquet");
+//! assert!(filter.check(&"parquet"));
+//! ```
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use data_type::AsBytes;
+use errors::{ParquetError, Result};
+use util::hash_util::xxhash64;
+
+/// Number of bytes in a block of the filter.
+pub const BLOCK_SIZE: usize = 32;
+/// Minimum size of the filter in bytes.
+pub const MIN_NUM_BYTES: usize = BLOCK_SIZE;
+/// Maximum size of the filter in bytes.
+pub const MAX_NUM_BYTES: usize = 128 * 1024 * 1024;
+
+/// Key of the column chunk key/value metadata entry with offset of Bloom filter.
+pub(crate) const BLOOM_FILTER_OFFSET_KEY: &str = "parquet-rs.bloom_filter.offset";
+/// Key of the column chunk key/value metadata entry with length of Bloom filter.
+pub(crate) const BLOOM_FILTER_LENGTH_KEY: &str = "parquet-rs.bloom_filter.length";
+
+/// Salt values used to compute bit masks of a block, as defined in the specification.
+const SALT: [u32; 8] = [
+  0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d,
+  0x705495c7, 0x2df1424b, 0x9efc4947, 0x5c6bfb31
+];
+
+/// Block of the filter, 8 words of 32 bits.
+type Block = [u32; 8];
+
+/// Returns mask with one bit set in every word of a block for `key`.
+#[inline]
+fn block_mask(key: u32) -> Block {
+  let mut mask = [0; 8];
+  for i in 0..8 {
+    mask[i] = 1 << (key.wrapping_mul(SALT[i]) >> 27);
+  }
+  mask
+}
+
+/// Returns optimal number of bytes for a filter with `ndv` distinct values and false
+/// positive probability `fpp`, rounded up to a power of two and clamped to
+/// [`MIN_NUM_BYTES`] and [`MAX_NUM_BYTES`].
+pub fn optimal_num_of_bytes(ndv: u64, fpp: f64) -> usize {
+  let num_bits = -8.0 * ndv as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+  // Clamp before conversion, so large values do not overflow `usize`
+  let num_bytes = (num_bits / 8.0).min(MAX_NUM_BYTES as f64) as usize;
+  num_bytes.max(MIN_NUM_BYTES).next_power_of_two()
+}
+
+/// Split block Bloom filter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sbbf {
+  blocks: Vec<Block>
+}
+
+impl Sbbf {
+  /// Creates empty filter of `num_bytes` size, which is rounded up to a power of two
+  /// and clamped to [`MIN_NUM_BYTES`] and [`MAX_NUM_BYTES`].
+  pub fn new(num_bytes: usize) -> Self {
+    let num_bytes = num_bytes.max(MIN_NUM_BYTES).min(MAX_NUM_BYTES).next_power_of_two();
+    Self { blocks: vec![[0; 8]; num_bytes / BLOCK_SIZE] }
+  }
+
+  /// Creates empty filter sized for `ndv` distinct values with false positive
+  /// probability `fpp`. Returns error if `fpp` is not in range `(0, 1)`.
+  pub fn new_with_ndv_fpp(ndv: u64, fpp: f64) -> Result<Self> {
+    if !(fpp > 0.0 && fpp < 1.0) {
+      return Err(general_err!(
+        "False positive probability must be between 0 and 1 exclusive, got {}",
+        fpp
+      ));
+    }
+    Ok(Self::new(optimal_num_of_bytes(ndv, fpp)))
+  }
+
+  /// Creates filter from its bitset, as serialized by `to_bytes`.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+    let num_bytes = bytes.len();
+    if num_bytes < MIN_NUM_BYTES || num_bytes > MAX_NUM_BYTES ||
+        !num_bytes.is_power_of_two() {
+      return Err(general_err!(
+        "Invalid Bloom filter size {}, expected power of two between {} and {}",
+        num_bytes,
+        MIN_NUM_BYTES,
+        MAX_NUM_BYTES
+      ));
+    }
+    let blocks = bytes.chunks(BLOCK_SIZE).map(|chunk| {
+      let mut block = [0; 8];
+      LittleEndian::read_u32_into(chunk, &mut block);
+      block
+    }).collect();
+    Ok(Self { blocks: blocks })
+  }
+
+  /// Returns bitset of this filter, words are stored in little endian order.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut bytes = vec![0; self.num_bytes()];
+    for (block, chunk) in self.blocks.iter().zip(bytes.chunks_mut(BLOCK_SIZE)) {
+      LittleEndian::write_u32_into(block, chunk);
+    }
+    bytes
+  }
+
+  /// Returns size of this filter in bytes.
+  pub fn num_bytes(&self) -> usize {
+    self.blocks.len() * BLOCK_SIZE
+  }
+
+  /// Returns index of the block for `hash`.
+  #[inline]
+  fn block_index(&self, hash: u64) -> usize {
+    (((hash >> 32) * self.blocks.len() as u64) >> 32) as usize
+  }
+
+  /// Inserts value into this filter.
+  pub fn insert<T: AsBytes + ?Sized>(&mut self, value: &T) {
+    self.insert_hash(hash_bytes(value.as_bytes()));
+  }
+
+  /// Inserts hash of a value, as computed by [`hash_bytes`], into this filter.
+  pub fn insert_hash(&mut self, hash: u64) {
+    let index = self.block_index(hash);
+    let mask = block_mask(hash as u32);
+    let block = &mut self.blocks[index];
+    for i in 0..8 {
+      block[i] |= mask[i];
+    }
+  }
+
+  /// Returns `false` if value is definitely not in this filter, `true` if value
+  /// might be in this filter.
+  pub fn check<T: AsBytes + ?Sized>(&self, value: &T) -> bool {
+    self.check_hash(hash_bytes(value.as_bytes()))
+  }
+
+  /// Same as `check`, but for hash of a value, as computed by [`hash_bytes`].
+  pub fn check_hash(&self, hash: u64) -> bool {
+    let block = &self.blocks[self.block_index(hash)];
+    let mask = block_mask(hash as u32);
+    (0..8).all(|i| block[i] & mask[i] != 0)
+  }
+}
+
+/// Returns hash of plain encoded bytes of a value, used by Bloom filters.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+  xxhash64(bytes, 0)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use data_type::ByteArray;
+
+  #[test]
+  fn test_optimal_num_of_bytes() {
+    assert_eq!(optimal_num_of_bytes(10, 0.01), 32);
+    assert_eq!(optimal_num_of_bytes(1000, 0.01), 2048);
+    assert_eq!(optimal_num_of_bytes(1000000, 0.05), 1024 * 1024);
+    assert_eq!(optimal_num_of_bytes(::std::u64::MAX, 0.01), MAX_NUM_BYTES);
+  }
+
+  #[test]
+  fn test_sbbf_new() {
+    assert_eq!(Sbbf::new(0).num_bytes(), MIN_NUM_BYTES);
+    assert_eq!(Sbbf::new(100).num_bytes(), 128);
+    assert_eq!(Sbbf::new_with_ndv_fpp(1000, 0.01).unwrap().num_bytes(), 2048);
+
+    for fpp in vec![0.0, 1.0, -0.5, ::std::f64::NAN] {
+      assert!(Sbbf::new_with_ndv_fpp(1000, fpp).is_err());
+    }
+  }
+
+  #[test]
+  fn test_sbbf_insert_check() {
+    let mut filter = Sbbf::new_with_ndv_fpp(1000, 0.01).unwrap();
+    for i in 0..1000i32 {
+      filter.insert(&i);
+      assert!(filter.check(&i));
+    }
+    for i in 0..1000 {
+      assert!(filter.check(&i));
+    }
+
+    let false_positives = (1000..11000i32).filter(|i| filter.check(i)).count();
+    assert!(false_positives < 200, "too many false positives: {}", false_positives);
+  }
+
+  #[test]
+  fn test_sbbf_byte_array() {
+    let mut filter = Sbbf::new(1024);
+    filter.insert(&ByteArray::from("parquet"));
+    assert!(filter.check(&"parquet"));
+    assert!(filter.check(&ByteArray::from("parquet")));
+    assert_eq!(
+      filter.check(&"parquet"),
+      filter.check_hash(hash_bytes("parquet".as_bytes()))
+    );
+  }
+
+  #[test]
+  fn test_sbbf_bytes_roundtrip() {
+    let mut filter = Sbbf::new(64);
+    for i in 0..10i64 {
+      filter.insert(&i);
+    }
+    let bytes = filter.to_bytes();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(Sbbf::from_bytes(&bytes).unwrap(), filter);
+
+    assert!(Sbbf::from_bytes(&[0; 16]).is_err());
+    assert!(Sbbf::from_bytes(&[0; 48]).is_err());
+  }
+}
@@ -15,7 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Low level column reader API.
+//! Low level column reader and writer API.
 //!
 //! This API is designed for the direct mapping with subsequent manual handling of
 //! definition and repetition levels and spacing. This allows to create column vectors
@@ -95,4 +95,6 @@
 //! ```
 
 pub mod page;
+pub mod predicate;
 pub mod reader;
+pub mod writer;
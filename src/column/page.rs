@@ -15,10 +15,13 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Contains Parquet Page definitions and page reader interface.
+//! Contains Parquet Page definitions and page reader/writer interfaces.
 
 use basic::{PageType, Encoding};
+use bloom_filter::Sbbf;
 use errors::Result;
+use file::metadata::ColumnChunkMetaData;
+use file::statistics::Statistics;
 use util::memory::ByteBufferPtr;
 
 /// Parquet Page definition.
@@ -26,13 +29,15 @@ use util::memory::ByteBufferPtr;
 /// List of supported pages.
 /// These are 1-to-1 mapped from the equivalent Thrift definitions, except `buf` which
 /// used to store uncompressed bytes of the page.
+#[derive(Clone)]
 pub enum Page {
   DataPage {
     buf: ByteBufferPtr,
     num_values: u32,
     encoding: Encoding,
     def_level_encoding: Encoding,
-    rep_level_encoding: Encoding
+    rep_level_encoding: Encoding,
+    statistics: Option<Statistics>
   },
   DataPageV2 {
     buf: ByteBufferPtr,
@@ -42,7 +47,8 @@ pub enum Page {
     num_rows: u32,
     def_levels_byte_len: u32,
     rep_levels_byte_len: u32,
-    is_compressed: bool
+    is_compressed: bool,
+    statistics: Option<Statistics>
   },
   DictionaryPage {
     buf: ByteBufferPtr,
@@ -88,6 +94,16 @@ impl Page {
       &Page::DictionaryPage { encoding, .. } => encoding
     }
   }
+
+  /// Returns optional [`Statistics`](`::file::statistics::Statistics`) of this page.
+  /// Dictionary pages do not have statistics.
+  pub fn statistics(&self) -> Option<&Statistics> {
+    match self {
+      &Page::DataPage { ref statistics, .. } => statistics.as_ref(),
+      &Page::DataPageV2 { ref statistics, .. } => statistics.as_ref(),
+      &Page::DictionaryPage { .. } => None
+    }
+  }
 }
 
 /// API for reading pages from a column chunk.
@@ -96,6 +112,179 @@ pub trait PageReader {
   /// Gets the next page in the column chunk associated with this reader.
   /// Returns `None` if there are no pages left.
   fn get_next_page(&mut self) -> Result<Option<Page>>;
+
+  /// Returns the number of values (including nulls) in data pages of the column chunk
+  /// that have not been returned by `get_next_page` yet, or `-1` if it is unknown.
+  ///
+  /// Default implementation returns `-1`.
+  fn num_values_left(&self) -> i64 {
+    -1
+  }
+
+  /// Returns cumulative metrics of pages returned by `get_next_page` so far.
+  ///
+  /// Default implementation returns empty metrics, for page readers that do not
+  /// track them.
+  fn metrics(&self) -> PageReaderMetrics {
+    PageReaderMetrics::default()
+  }
+}
+
+/// Cumulative metrics of a page reader, e.g. for IO accounting and cost attribution.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PageReaderMetrics {
+  num_pages: u64,
+  compressed_bytes: u64,
+  decompressed_bytes: u64
+}
+
+impl PageReaderMetrics {
+  /// Creates page reader metrics.
+  pub fn new(num_pages: u64, compressed_bytes: u64, decompressed_bytes: u64) -> Self {
+    Self {
+      num_pages: num_pages,
+      compressed_bytes: compressed_bytes,
+      decompressed_bytes: decompressed_bytes
+    }
+  }
+
+  /// Returns the number of pages returned, including dictionary pages.
+  pub fn num_pages(&self) -> u64 {
+    self.num_pages
+  }
+
+  /// Returns the number of bytes read from the column chunk, including page headers
+  /// and pages that were skipped.
+  pub fn compressed_bytes(&self) -> u64 {
+    self.compressed_bytes
+  }
+
+  /// Returns the number of bytes of returned pages after decompression.
+  pub fn decompressed_bytes(&self) -> u64 {
+    self.decompressed_bytes
+  }
+}
+
+/// Helper struct to represent pages with potentially compressed buffer (data page v1) or
+/// compressed and concatenated buffer (def levels + rep levels + compressed values for
+/// data page v2).
+///
+/// The difference with `Page` is that `Page` buffer is always uncompressed.
+pub struct CompressedPage {
+  compressed_page: Page,
+  uncompressed_size: usize
+}
+
+impl CompressedPage {
+  /// Creates `CompressedPage` from a page with potentially compressed buffer and
+  /// uncompressed size.
+  pub fn new(compressed_page: Page, uncompressed_size: usize) -> Self {
+    Self {
+      compressed_page: compressed_page,
+      uncompressed_size: uncompressed_size
+    }
+  }
+
+  /// Returns page type.
+  pub fn page_type(&self) -> PageType {
+    self.compressed_page.page_type()
+  }
+
+  /// Returns underlying page with potentially compressed buffer.
+  pub fn compressed_page(&self) -> &Page {
+    &self.compressed_page
+  }
+
+  /// Returns uncompressed size in bytes.
+  pub fn uncompressed_size(&self) -> usize {
+    self.uncompressed_size
+  }
+
+  /// Returns compressed size in bytes.
+  ///
+  /// Note that it is assumed that buffer is compressed, but it may not be. In this
+  /// case compressed size will be equal to uncompressed size.
+  pub fn compressed_size(&self) -> usize {
+    self.compressed_page.buffer().len()
+  }
+
+  /// Number of values in page.
+  pub fn num_values(&self) -> u32 {
+    self.compressed_page.num_values()
+  }
+
+  /// Returns encoding for values in page.
+  pub fn encoding(&self) -> Encoding {
+    self.compressed_page.encoding()
+  }
+
+  /// Returns slice of compressed buffer in the page.
+  pub fn data(&self) -> &[u8] {
+    self.compressed_page.buffer().data()
+  }
+}
+
+/// Contains page write metrics.
+pub struct PageWriteSpec {
+  pub page_type: PageType,
+  pub uncompressed_size: usize,
+  pub compressed_size: usize,
+  pub num_values: u32,
+  pub offset: u64,
+  pub bytes_written: u64
+}
+
+impl PageWriteSpec {
+  /// Creates new spec with default page write metrics.
+  pub fn new() -> Self {
+    Self {
+      page_type: PageType::DATA_PAGE,
+      uncompressed_size: 0,
+      compressed_size: 0,
+      num_values: 0,
+      offset: 0,
+      bytes_written: 0
+    }
+  }
+}
+
+/// API for writing pages in a column chunk.
+///
+/// It is reasonable to assume that all pages will be written in the correct order, e.g.
+/// dictionary page followed by data pages, or a set of data pages, etc.
+pub trait PageWriter {
+  /// Writes a page into the output stream/sink.
+  /// Returns `PageWriteSpec` that contains information about written page metrics,
+  /// including number of bytes, size, number of values, offset, etc.
+  ///
+  /// This method is called for every compressed page we write into underlying buffer,
+  /// either data page or dictionary page.
+  fn write_page(&mut self, page: CompressedPage) -> Result<PageWriteSpec>;
+
+  /// Writes bitset of the Bloom filter of the column chunk into the output
+  /// stream/sink. Returns offset of the written bitset.
+  ///
+  /// This method is called at most once after all pages are written, and before
+  /// column chunk metadata is written.
+  fn write_bloom_filter(&mut self, filter: &Sbbf) -> Result<u64>;
+
+  /// Writes column chunk metadata into the output stream/sink.
+  ///
+  /// This method is called once before page writer is closed, normally when writes are
+  /// finalised in column writer.
+  fn write_metadata(&mut self, metadata: &ColumnChunkMetaData) -> Result<()>;
+
+  /// Returns CRC32 checksum of all pages written so far, including page headers, if
+  /// page writer computes it. Checksum is stored in column chunk metadata.
+  ///
+  /// Default implementation does not compute checksum and returns `None`.
+  fn chunk_checksum(&self) -> Option<u32> {
+    None
+  }
+
+  /// Closes resources and flushes underlying sink.
+  /// Page writer should not be used after this method is called.
+  fn close(&mut self) -> Result<()>;
 }
 
 
@@ -110,12 +299,17 @@ mod tests {
       num_values: 10,
       encoding: Encoding::PLAIN,
       def_level_encoding: Encoding::RLE,
-      rep_level_encoding: Encoding::RLE
+      rep_level_encoding: Encoding::RLE,
+      statistics: Some(Statistics::int32(Some(1), Some(2), None, 1, false))
     };
     assert_eq!(data_page.page_type(), PageType::DATA_PAGE);
     assert_eq!(data_page.buffer().data(), vec![0, 1, 2].as_slice());
     assert_eq!(data_page.num_values(), 10);
     assert_eq!(data_page.encoding(), Encoding::PLAIN);
+    assert_eq!(
+      data_page.statistics(),
+      Some(&Statistics::int32(Some(1), Some(2), None, 1, false))
+    );
 
     let data_page_v2 = Page::DataPageV2 {
       buf: ByteBufferPtr::new(vec![0, 1, 2]),
@@ -125,12 +319,17 @@ mod tests {
       num_rows: 20,
       def_levels_byte_len: 30,
       rep_levels_byte_len: 40,
-      is_compressed: false
+      is_compressed: false,
+      statistics: Some(Statistics::int32(Some(1), Some(2), None, 1, false))
     };
     assert_eq!(data_page_v2.page_type(), PageType::DATA_PAGE_V2);
     assert_eq!(data_page_v2.buffer().data(), vec![0, 1, 2].as_slice());
     assert_eq!(data_page_v2.num_values(), 10);
     assert_eq!(data_page_v2.encoding(), Encoding::PLAIN);
+    assert_eq!(
+      data_page_v2.statistics(),
+      Some(&Statistics::int32(Some(1), Some(2), None, 1, false))
+    );
 
     let dict_page = Page::DictionaryPage {
       buf: ByteBufferPtr::new(vec![0, 1, 2]),
@@ -142,5 +341,27 @@ mod tests {
     assert_eq!(dict_page.buffer().data(), vec![0, 1, 2].as_slice());
     assert_eq!(dict_page.num_values(), 10);
     assert_eq!(dict_page.encoding(), Encoding::PLAIN);
+    assert_eq!(dict_page.statistics(), None);
+  }
+
+  #[test]
+  fn test_compressed_page() {
+    let data_page = Page::DataPage {
+      buf: ByteBufferPtr::new(vec![0, 1, 2]),
+      num_values: 10,
+      encoding: Encoding::PLAIN,
+      def_level_encoding: Encoding::RLE,
+      rep_level_encoding: Encoding::RLE,
+      statistics: Some(Statistics::int32(Some(1), Some(2), None, 1, false))
+    };
+
+    let cpage = CompressedPage::new(data_page, 5);
+
+    assert_eq!(cpage.page_type(), PageType::DATA_PAGE);
+    assert_eq!(cpage.uncompressed_size(), 5);
+    assert_eq!(cpage.compressed_size(), 3);
+    assert_eq!(cpage.num_values(), 10);
+    assert_eq!(cpage.encoding(), Encoding::PLAIN);
+    assert_eq!(cpage.data(), &[0, 1, 2]);
   }
 }
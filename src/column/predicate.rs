@@ -0,0 +1,531 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains batched evaluation of string predicates over BYTE_ARRAY pages.
+//!
+//! [`BytePredicateReader`](`BytePredicateReader`) evaluates an equality or prefix
+//! [`BytePredicate`](`BytePredicate`) over the data pages of a column chunk and returns a
+//! [`Selection`](`Selection`) bitmap with one bit per value, including nulls, which
+//! never match. PLAIN values are compared in place in the page buffer, and
+//! dictionary-encoded values are resolved through the predicate result of every
+//! dictionary entry, so no `ByteArray` is constructed for either encoding.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::fs::File;
+//! use std::path::Path;
+//!
+//! use parquet::column::predicate::{BytePredicate, BytePredicateReader};
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! # let path = Path::new("data/alltypes_plain.parquet");
+//! let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+//! let row_group_reader = reader.get_row_group(0).unwrap();
+//! // Column `string_col` contains "0" and "1"
+//! let selection = BytePredicateReader::new(
+//!   row_group_reader.metadata().column(9).column_descr_ptr(),
+//!   row_group_reader.get_column_page_reader(9).unwrap(),
+//!   BytePredicate::Eq(b"1".to_vec())
+//! ).unwrap().evaluate().unwrap();
+//! assert_eq!(selection.len(), 8);
+//! assert_eq!(selection.count(), 4);
+//! ```
+
+use std::cmp;
+use std::mem;
+
+use basic::{Encoding, Type};
+use column::page::{Page, PageReader};
+use data_type::{ByteArray, ByteArrayType};
+use encodings::decoding::get_decoder;
+use encodings::levels::LevelDecoder;
+use encodings::rle::RleDecoder;
+use errors::{ParquetError, Result};
+use schema::types::ColumnDescPtr;
+use util::bit_util::set_array_bit;
+use util::memory::ByteBufferPtr;
+
+/// Number of dictionary indexes that are decoded at a time.
+const INDEX_BATCH_SIZE: usize = 1024;
+
+/// Selection bitmap, where bit `i` is set if value `i` matches a predicate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selection {
+  bits: Vec<u8>,
+  len: usize
+}
+
+impl Selection {
+  /// Creates selection of `len` values with no value selected.
+  pub fn new(len: usize) -> Self {
+    Self {
+      bits: vec![0; (len + 7) / 8],
+      len: len
+    }
+  }
+
+  /// Returns the number of values in this selection.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns `true` if this selection has no values.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns `true` if value `i` is selected.
+  pub fn get(&self, i: usize) -> bool {
+    assert!(i < self.len, "Index {} out of bounds for selection of {}", i, self.len);
+    self.bits[i / 8] & (1 << (i % 8)) != 0
+  }
+
+  /// Selects value `i`.
+  pub fn set(&mut self, i: usize) {
+    assert!(i < self.len, "Index {} out of bounds for selection of {}", i, self.len);
+    set_array_bit(&mut self.bits, i);
+  }
+
+  /// Returns the number of selected values.
+  pub fn count(&self) -> usize {
+    self.bits.iter().map(|byte| byte.count_ones() as usize).sum()
+  }
+
+  /// Returns bitmap of this selection, where value `i` is bit `i % 8` of byte `i / 8`.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.bits
+  }
+
+  /// Appends values of another selection to the end of this selection.
+  pub fn extend(&mut self, other: &Selection) {
+    if self.len % 8 == 0 {
+      self.bits.extend_from_slice(&other.bits);
+      self.len += other.len;
+      return;
+    }
+    let offset = self.len;
+    self.len += other.len;
+    self.bits.resize((self.len + 7) / 8, 0);
+    for i in 0..other.len {
+      if other.get(i) {
+        self.set(offset + i);
+      }
+    }
+  }
+}
+
+/// Predicate on BYTE_ARRAY values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BytePredicate {
+  /// Value is equal to the bytes.
+  Eq(Vec<u8>),
+  /// Value starts with the bytes.
+  Prefix(Vec<u8>)
+}
+
+impl BytePredicate {
+  /// Returns `true` if value matches this predicate.
+  #[inline]
+  pub fn matches(&self, value: &[u8]) -> bool {
+    match *self {
+      BytePredicate::Eq(ref bytes) => value == &bytes[..],
+      BytePredicate::Prefix(ref bytes) => value.starts_with(bytes)
+    }
+  }
+
+  /// Evaluates this predicate over `num_values` PLAIN-encoded values, which are read
+  /// directly from `data` using their length prefixes.
+  ///
+  /// Returns an error if `data` has fewer values.
+  pub fn evaluate_plain(&self, data: &[u8], num_values: usize) -> Result<Selection> {
+    let mut selection = Selection::new(num_values);
+    let mut start = 0;
+    for i in 0..num_values {
+      if data.len() < start + mem::size_of::<u32>() {
+        return Err(eof_err!("Not enough bytes to decode"));
+      }
+      let len = read_num_bytes!(u32, 4, &data[start..]) as usize;
+      start += mem::size_of::<u32>();
+      if data.len() < start + len {
+        return Err(eof_err!("Not enough bytes to decode"));
+      }
+      if self.matches(&data[start..start + len]) {
+        selection.set(i);
+      }
+      start += len;
+    }
+    Ok(selection)
+  }
+}
+
+/// Evaluates a [`BytePredicate`](`BytePredicate`) over data pages of a BYTE_ARRAY
+/// column chunk.
+pub struct BytePredicateReader {
+  descr: ColumnDescPtr,
+  page_reader: Box<PageReader>,
+  predicate: BytePredicate,
+  // Result of the predicate for every value of the dictionary page
+  dictionary: Option<Selection>
+}
+
+impl BytePredicateReader {
+  /// Creates new predicate reader for the column and its page reader.
+  /// Returns an error if the column is not of BYTE_ARRAY type.
+  pub fn new(
+    descr: ColumnDescPtr,
+    page_reader: Box<PageReader>,
+    predicate: BytePredicate
+  ) -> Result<Self> {
+    if descr.physical_type() != Type::BYTE_ARRAY {
+      return Err(general_err!(
+        "Cannot evaluate byte predicate on column {} of type {}",
+        descr.path().string(),
+        descr.physical_type()
+      ));
+    }
+    Ok(Self {
+      descr: descr,
+      page_reader: page_reader,
+      predicate: predicate,
+      dictionary: None
+    })
+  }
+
+  /// Returns selection of the values of the next data page, or `None` if there are no
+  /// data pages left. Dictionary pages are consumed internally.
+  pub fn next_page(&mut self) -> Result<Option<Selection>> {
+    while let Some(page) = self.page_reader.get_next_page()? {
+      match page {
+        Page::DictionaryPage { buf, num_values, .. } => {
+          let num_values = num_values as usize;
+          let selection = self.predicate.evaluate_plain(buf.as_ref(), num_values)?;
+          self.dictionary = Some(selection);
+        },
+        Page::DataPage {
+          buf, num_values, encoding, def_level_encoding, rep_level_encoding, ..
+        } => {
+          let num_values = num_values as usize;
+          let mut buf = buf;
+          if self.descr.max_rep_level() > 0 {
+            let mut decoder =
+              LevelDecoder::new(rep_level_encoding, self.descr.max_rep_level())?;
+            let num_bytes = decoder.set_data(num_values, buf.all())?;
+            buf = buf.start_from(num_bytes);
+          }
+          let mut def_levels = None;
+          if self.descr.max_def_level() > 0 {
+            let mut decoder =
+              LevelDecoder::new(def_level_encoding, self.descr.max_def_level())?;
+            let num_bytes = decoder.set_data(num_values, buf.all())?;
+            buf = buf.start_from(num_bytes);
+            def_levels = Some(read_levels(&mut decoder, num_values)?);
+          }
+          return self.evaluate_page(buf, encoding, num_values, def_levels).map(Some);
+        },
+        Page::DataPageV2 {
+          buf, num_values, encoding, def_levels_byte_len, rep_levels_byte_len, ..
+        } => {
+          let num_values = num_values as usize;
+          let offset = (rep_levels_byte_len + def_levels_byte_len) as usize;
+          if offset > buf.len() {
+            return Err(eof_err!("Not enough bytes to decode levels"));
+          }
+          let mut def_levels = None;
+          if self.descr.max_def_level() > 0 {
+            let mut decoder =
+              LevelDecoder::new(Encoding::RLE, self.descr.max_def_level())?;
+            let start = rep_levels_byte_len as usize;
+            let len = def_levels_byte_len as usize;
+            decoder.set_data_range(num_values, &buf, start, len)?;
+            def_levels = Some(read_levels(&mut decoder, num_values)?);
+          }
+          let buf = buf.start_from(offset);
+          return self.evaluate_page(buf, encoding, num_values, def_levels).map(Some);
+        }
+      }
+    }
+    Ok(None)
+  }
+
+  /// Evaluates predicate over all remaining data pages and returns selection of their
+  /// values.
+  pub fn evaluate(mut self) -> Result<Selection> {
+    let mut selection = Selection::new(0);
+    while let Some(page_selection) = self.next_page()? {
+      selection.extend(&page_selection);
+    }
+    Ok(selection)
+  }
+
+  /// Evaluates predicate over encoded values of a data page, and maps selection of
+  /// non-null values onto all values of the page using definition levels.
+  fn evaluate_page(
+    &self,
+    buf: ByteBufferPtr,
+    encoding: Encoding,
+    num_values: usize,
+    def_levels: Option<Vec<i16>>
+  ) -> Result<Selection> {
+    let max_def_level = self.descr.max_def_level();
+    let num_non_null_values = match def_levels {
+      Some(ref levels) => levels.iter().filter(|level| **level == max_def_level).count(),
+      None => num_values
+    };
+
+    let values = match encoding {
+      Encoding::PLAIN => {
+        self.predicate.evaluate_plain(buf.as_ref(), num_non_null_values)?
+      },
+      Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => {
+        match self.dictionary {
+          Some(ref dictionary) => {
+            select_dictionary_values(dictionary, buf, num_non_null_values)?
+          },
+          None => return Err(general_err!("Dictionary page is missing"))
+        }
+      },
+      _ => {
+        // Other encodings do not store values contiguously, decode them instead
+        let mut decoder = get_decoder::<ByteArrayType>(self.descr.clone(), encoding)?;
+        decoder.set_data(buf, num_non_null_values)?;
+        let mut buffer = vec![ByteArray::new(); num_non_null_values];
+        let num_decoded = decoder.get(&mut buffer)?;
+        if num_decoded < num_non_null_values {
+          return Err(eof_err!("Not enough values to decode"));
+        }
+        let mut selection = Selection::new(num_non_null_values);
+        for (i, value) in buffer.iter().enumerate() {
+          if self.predicate.matches(value.data()) {
+            selection.set(i);
+          }
+        }
+        selection
+      }
+    };
+
+    match def_levels {
+      Some(levels) => {
+        let mut selection = Selection::new(num_values);
+        let mut value_index = 0;
+        for (i, level) in levels.iter().enumerate() {
+          if *level == max_def_level {
+            if values.get(value_index) {
+              selection.set(i);
+            }
+            value_index += 1;
+          }
+        }
+        Ok(selection)
+      },
+      None => Ok(values)
+    }
+  }
+}
+
+/// Reads `num_values` levels from the level decoder.
+fn read_levels(decoder: &mut LevelDecoder, num_values: usize) -> Result<Vec<i16>> {
+  let mut levels = vec![0; num_values];
+  let num_levels = decoder.get(&mut levels)?;
+  if num_levels < num_values {
+    return Err(eof_err!("Not enough levels to decode"));
+  }
+  Ok(levels)
+}
+
+/// Maps RLE-encoded dictionary indexes in `data`, prefixed with their bit width, to the
+/// selection of dictionary values.
+fn select_dictionary_values(
+  dictionary: &Selection,
+  data: ByteBufferPtr,
+  num_values: usize
+) -> Result<Selection> {
+  let mut selection = Selection::new(num_values);
+  if num_values == 0 {
+    return Ok(selection);
+  }
+  if data.len() == 0 {
+    return Err(eof_err!("Not enough bytes to decode"));
+  }
+
+  let mut decoder = RleDecoder::new(data.as_ref()[0]);
+  decoder.set_data(data.start_from(1));
+  let mut indexes = vec![0i32; cmp::min(num_values, INDEX_BATCH_SIZE)];
+  let mut i = 0;
+  while i < num_values {
+    let batch_size = cmp::min(indexes.len(), num_values - i);
+    let num_decoded = decoder.get_batch(&mut indexes[..batch_size])?;
+    if num_decoded == 0 {
+      return Err(eof_err!("Not enough bytes to decode"));
+    }
+    for &index in &indexes[..num_decoded] {
+      let index = index as usize;
+      if index >= dictionary.len() {
+        return Err(general_err!(
+          "Dictionary index {} out of bounds, dictionary has {} values",
+          index,
+          dictionary.len()
+        ));
+      }
+      if dictionary.get(index) {
+        selection.set(i);
+      }
+      i += 1;
+    }
+  }
+  Ok(selection)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::io::Write;
+  use std::rc::Rc;
+
+  use file::builder::InMemoryFileBuilder;
+  use file::properties::WriterProperties;
+  use file::reader::{FileReader, SerializedFileReader};
+  use record::api::Row;
+  use schema::parser::parse_message_type;
+  use util::test_common::get_temp_filename;
+
+  #[test]
+  fn test_selection() {
+    let mut selection = Selection::new(3);
+    selection.set(0);
+    selection.set(2);
+    assert_eq!(selection.count(), 2);
+    assert_eq!(selection.as_bytes(), &[0b101]);
+
+    let mut other = Selection::new(9);
+    other.set(1);
+    other.set(8);
+    selection.extend(&other);
+    assert_eq!(selection.len(), 12);
+    assert_eq!(
+      (0..12).filter(|i| selection.get(*i)).collect::<Vec<usize>>(),
+      vec![0, 2, 4, 11]
+    );
+  }
+
+  #[test]
+  fn test_evaluate_plain() {
+    let mut data = Vec::new();
+    for value in &["abc", "ab", "", "abd"] {
+      data.extend_from_slice(&[value.len() as u8, 0, 0, 0]);
+      data.extend_from_slice(value.as_bytes());
+    }
+
+    let selection = BytePredicate::Eq(b"ab".to_vec()).evaluate_plain(&data, 4).unwrap();
+    assert_eq!(selection.as_bytes(), &[0b0010]);
+    let selection =
+      BytePredicate::Prefix(b"ab".to_vec()).evaluate_plain(&data, 4).unwrap();
+    assert_eq!(selection.as_bytes(), &[0b1011]);
+    let selection = BytePredicate::Prefix(vec![]).evaluate_plain(&data, 4).unwrap();
+    assert_eq!(selection.count(), 4);
+
+    assert!(BytePredicate::Eq(vec![]).evaluate_plain(&data, 5).is_err());
+    let truncated = &data[..data.len() - 1];
+    assert!(BytePredicate::Eq(vec![]).evaluate_plain(truncated, 4).is_err());
+  }
+
+  #[test]
+  fn test_byte_predicate_reader() {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        OPTIONAL BYTE_ARRAY a (UTF8);
+        REQUIRED INT32 b;
+      }
+    ").unwrap());
+    let values = vec![Some("apple"), None, Some("apricot"), Some("banana"), None];
+    let rows: Vec<Row> = (0..1000).map(|i| {
+      let a = match values[i % values.len()] {
+        Some(value) => Row::Str(value.to_string()),
+        None => Row::Null
+      };
+      Row::Group(vec![("a".to_string(), a), ("b".to_string(), Row::Int(i as i32))])
+    }).collect();
+
+    for &dictionary_enabled in &[true, false] {
+      for &page_size in &[64, 1024 * 1024] {
+        let props = WriterProperties::builder()
+          .with_dictionary_enabled(dictionary_enabled)
+          .with_data_page_size(page_size)
+          .with_write_batch_size(10)
+          .build();
+        let bytes = InMemoryFileBuilder::new(schema.clone())
+          .with_properties(Rc::new(props))
+          .with_rows(rows.clone())
+          .build()
+          .unwrap();
+        let path = get_temp_filename("test_byte_predicate_reader.parquet");
+        fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+        let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+        let row_group_reader = reader.get_row_group(0).unwrap();
+
+        let evaluate = |predicate: BytePredicate| {
+          BytePredicateReader::new(
+            row_group_reader.metadata().column(0).column_descr_ptr(),
+            row_group_reader.get_column_page_reader(0).unwrap(),
+            predicate
+          ).unwrap().evaluate().unwrap()
+        };
+
+        let selection = evaluate(BytePredicate::Eq(b"apple".to_vec()));
+        assert_eq!(selection.len(), 1000);
+        assert_eq!(selection.count(), 200);
+        assert!((0..1000).all(|i| selection.get(i) == (i % 5 == 0)));
+
+        let selection = evaluate(BytePredicate::Prefix(b"ap".to_vec()));
+        assert_eq!(selection.count(), 400);
+        assert!((0..1000).all(|i| selection.get(i) == (i % 5 == 0 || i % 5 == 2)));
+
+        let selection = evaluate(BytePredicate::Eq(b"cherry".to_vec()));
+        assert_eq!(selection.count(), 0);
+      }
+    }
+  }
+
+  #[test]
+  fn test_byte_predicate_reader_invalid_column() {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED INT32 b;
+      }
+    ").unwrap());
+    let bytes = InMemoryFileBuilder::new(schema)
+      .with_rows(vec![Row::Group(vec![("b".to_string(), Row::Int(1))])])
+      .build()
+      .unwrap();
+    let path = get_temp_filename("test_byte_predicate_reader_invalid_column.parquet");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+
+    let result = BytePredicateReader::new(
+      row_group_reader.metadata().column(0).column_descr_ptr(),
+      row_group_reader.get_column_page_reader(0).unwrap(),
+      BytePredicate::Eq(vec![])
+    );
+    assert_eq!(
+      result.err().unwrap(),
+      general_err!("Cannot evaluate byte predicate on column b of type INT32")
+    );
+  }
+}
@@ -18,16 +18,21 @@
 //! Contains column reader API.
 
 use std::cmp::{max, min};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::mem;
 
-use super::page::{Page, PageReader};
+use super::page::{Page, PageReader, PageReaderMetrics};
 use basic::*;
 use data_type::*;
-use encodings::decoding::{get_decoder, Decoder, PlainDecoder, DictDecoder};
+use encodings::decoding::{get_decoder, get_dict_decoder, Decoder};
 use encodings::levels::LevelDecoder;
 use errors::{Result, ParquetError};
-use schema::types::ColumnDescPtr;
+use logical::decimal_bound;
+use record::api::Row;
+use record::coercion::Coercion;
+use schema::types::{ColumnDescPtr, ColumnDescriptor};
+use util::bit_util::set_array_bit;
 use util::memory::ByteBufferPtr;
 
 /// Column reader for a Parquet type.
@@ -42,6 +47,68 @@ pub enum ColumnReader {
   FixedLenByteArrayColumnReader(ColumnReaderImpl<FixedLenByteArrayType>)
 }
 
+impl ColumnReader {
+  /// Reads a batch of values of any physical type, converting each non-null value into
+  /// [`Row`](`::record::api::Row`) based on the physical and logical type of the column.
+  /// This is useful for tools that cannot be generic over the data type at compile time,
+  /// e.g. exporters or viewers; values are converted the same way as in the record API.
+  ///
+  /// Converted values are appended to `values`, definition and repetition levels are
+  /// handled the same way as in `ColumnReaderImpl::read_batch`.
+  ///
+  /// Returns a tuple where the first element is the actual number of values read,
+  /// and the second element is the actual number of levels read.
+  pub fn read_batch_dyn(
+    &mut self,
+    batch_size: usize,
+    def_levels: Option<&mut [i16]>,
+    rep_levels: Option<&mut [i16]>,
+    values: &mut Vec<Row>
+  ) -> Result<(usize, usize)> {
+    match *self {
+      ColumnReader::BoolColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::Int32ColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::Int64ColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::Int96ColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::FloatColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::DoubleColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::ByteArrayColumnReader(ref mut typed) => typed.read_batch_converted(
+        batch_size, def_levels, rep_levels, values, Row::from_physical),
+      ColumnReader::FixedLenByteArrayColumnReader(ref mut typed) => {
+        typed.read_batch_converted(
+          batch_size, def_levels, rep_levels, values, Row::from_physical)
+      }
+    }
+  }
+
+  /// Reads a batch of values the same way as `read_batch_dyn`, and applies `coercion`
+  /// to each value, e.g. to read INT32 values as `Row::Long` into a fixed schema.
+  /// See [`Coercion`](`::record::coercion::Coercion`) for details.
+  pub fn read_batch_coerced(
+    &mut self,
+    batch_size: usize,
+    def_levels: Option<&mut [i16]>,
+    rep_levels: Option<&mut [i16]>,
+    values: &mut Vec<Row>,
+    coercion: Coercion
+  ) -> Result<(usize, usize)> {
+    let start = values.len();
+    let result = self.read_batch_dyn(batch_size, def_levels, rep_levels, values)?;
+    if coercion != Coercion::Identity {
+      for value in &mut values[start..] {
+        *value = coercion.apply(mem::replace(value, Row::Null));
+      }
+    }
+    Ok(result)
+  }
+}
+
 /// Gets a specific column reader corresponding to column descriptor `col_descr`. The
 /// column reader will read from pages in `col_page_reader`.
 pub fn get_column_reader(
@@ -103,6 +170,10 @@ pub struct ColumnReaderImpl<T: DataType> {
   // so far.
   num_decoded_values: u32,
 
+  // The number of values for levels returned by `read_levels` that have not been read
+  // yet with `read_pending_values`.
+  num_pending_values: usize,
+
   // Cache of decoders for existing encodings
   decoders: HashMap<Encoding, Box<Decoder<T>>>
 }
@@ -118,6 +189,7 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
       current_encoding: None,
       num_buffered_values: 0,
       num_decoded_values: 0,
+      num_pending_values: 0,
       decoders: HashMap::new()
     }
   }
@@ -149,6 +221,8 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
     mut rep_levels: Option<&mut [i16]>,
     values: &mut [T::T]
   ) -> Result<(usize, usize)> {
+    self.check_no_pending_values()?;
+
     let mut values_read = 0;
     let mut levels_read = 0;
 
@@ -216,11 +290,12 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
             &mut levels[levels_read..levels_read + iter_batch_size])?;
 
           // If definition levels are defined, check that rep levels == def levels
-          if def_levels.is_some() {
-            assert_eq!(
-              num_def_levels, num_rep_levels,
-              "Number of decoded rep / def levels did not match"
-            );
+          if def_levels.is_some() && num_def_levels != num_rep_levels {
+            return Err(general_err!(
+              "Number of decoded rep / def levels did not match ({} vs {})",
+              num_rep_levels,
+              num_def_levels
+            ));
           }
         }
       }
@@ -249,6 +324,232 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
     Ok((values_read, levels_read))
   }
 
+  /// Reads a batch of values with `read_batch` and appends them to `values`, converted
+  /// with `convert` function.
+  fn read_batch_converted<F>(
+    &mut self,
+    batch_size: usize,
+    def_levels: Option<&mut [i16]>,
+    rep_levels: Option<&mut [i16]>,
+    values: &mut Vec<Row>,
+    convert: F
+  ) -> Result<(usize, usize)> where F: Fn(&ColumnDescriptor, &T::T) -> Row {
+    let mut buffer = vec![T::T::default(); batch_size];
+    let (values_read, levels_read) =
+      self.read_batch(batch_size, def_levels, rep_levels, &mut buffer)?;
+
+    values.reserve(values_read);
+    for value in buffer.iter().take(values_read) {
+      values.push(convert(&*self.descr, value));
+    }
+    Ok((values_read, levels_read))
+  }
+
+  /// Reads a batch of at most `batch_size` records of a non-repeated column, where null
+  /// values are returned as `None`. Definition levels are handled internally, so this is
+  /// a convenience alternative to `read_batch` for flat schemas.
+  ///
+  /// Returns an empty vector once the column chunk is exhausted, and an error if the
+  /// column is repeated, use [`RecordReader`](`RecordReader`) for repeated columns.
+  pub fn read_records(&mut self, batch_size: usize) -> Result<Vec<Option<T::T>>> {
+    if self.descr.max_rep_level() > 0 {
+      return Err(general_err!(
+        "Cannot read records of repeated column {}",
+        self.descr.path().string()
+      ));
+    }
+
+    let max_def_level = self.descr.max_def_level();
+    let mut values = vec![T::T::default(); batch_size];
+
+    if max_def_level == 0 {
+      let (values_read, _) = self.read_batch(batch_size, None, None, &mut values)?;
+      values.truncate(values_read);
+      return Ok(values.into_iter().map(Some).collect());
+    }
+
+    let mut def_levels = vec![0; batch_size];
+    let (_, levels_read) =
+      self.read_batch_spaced(batch_size, &mut def_levels[..], None, &mut values)?;
+
+    let records = values.into_iter()
+      .zip(def_levels.into_iter())
+      .take(levels_read)
+      .map(|(value, level)| if level == max_def_level { Some(value) } else { None })
+      .collect();
+    Ok(records)
+  }
+
+  /// Reads a batch of at most `batch_size` levels and values like `read_batch`, but
+  /// values are written at positions of their levels, i.e. `values[i]` is the value of
+  /// `def_levels[i]` if it equals the max definition level. Slots of nulls contain
+  /// unspecified values, so callers do not need to expand densely read values.
+  ///
+  /// Definition levels are not written for a required column, all slots contain values
+  /// in that case.
+  ///
+  /// Returns a tuple where the first element is the number of values read, and the
+  /// second element is the number of levels read, i.e. the number of slots filled.
+  pub fn read_batch_spaced(
+    &mut self,
+    batch_size: usize,
+    def_levels: &mut [i16],
+    mut rep_levels: Option<&mut [i16]>,
+    values: &mut [T::T]
+  ) -> Result<(usize, usize)> {
+    let mut batch_size = min(min(batch_size, def_levels.len()), values.len());
+    if let Some(ref levels) = rep_levels {
+      batch_size = min(batch_size, levels.len());
+    }
+
+    let max_def_level = self.descr.max_def_level();
+    let mut values_read = 0;
+    let mut levels_read = 0;
+    while levels_read < batch_size {
+      let (num_levels, num_values) = self.read_levels(
+        batch_size - levels_read,
+        Some(&mut def_levels[levels_read..]),
+        rep_levels.as_mut().map(|levels| &mut levels[levels_read..])
+      )?;
+      if num_levels == 0 {
+        break;
+      }
+
+      let slots = &mut values[levels_read..levels_read + num_levels];
+      let mut valid_bits = vec![0; (num_levels + 7) / 8];
+      for i in 0..num_levels {
+        if max_def_level == 0 || def_levels[levels_read + i] == max_def_level {
+          set_array_bit(&mut valid_bits, i);
+        }
+      }
+      let null_count = num_levels - num_values;
+      self.current_decoder()?.get_spaced(slots, null_count, &valid_bits)?;
+      self.num_pending_values = 0;
+      values_read += num_values;
+      levels_read += num_levels;
+    }
+    Ok((values_read, levels_read))
+  }
+
+  /// Reads definition and repetition levels of at most `batch_size` values, without
+  /// reading the values themselves. This is the first phase of a two-phase read, values
+  /// are read afterwards with `read_pending_values`, e.g. once the caller has decided
+  /// that they are needed based on levels.
+  ///
+  /// Levels are read from the current page only, so fewer than `batch_size` levels might
+  /// be returned even if there are more pages. Level slices are handled the same way as
+  /// in `read_batch`; for a required and non-repeated field no levels are written.
+  ///
+  /// Returns a tuple where the first element is the number of levels read (or values
+  /// skipped over for a required and non-repeated field), and the second element is the
+  /// number of values that should be read with `read_pending_values`.
+  ///
+  /// Returns an error if values of the previous call have not been read yet.
+  pub fn read_levels(
+    &mut self,
+    batch_size: usize,
+    mut def_levels: Option<&mut [i16]>,
+    mut rep_levels: Option<&mut [i16]>
+  ) -> Result<(usize, usize)> {
+    self.check_no_pending_values()?;
+
+    if !self.has_next()? {
+      return Ok((0, 0));
+    }
+
+    let mut batch_size = min(batch_size, self.values_left_in_page());
+    if let Some(ref levels) = def_levels {
+      batch_size = min(batch_size, levels.len());
+    }
+    if let Some(ref levels) = rep_levels {
+      batch_size = min(batch_size, levels.len());
+    }
+
+    let mut values_to_read = 0;
+    let mut num_def_levels = 0;
+    let mut num_rep_levels = 0;
+
+    if self.descr.max_def_level() > 0 && def_levels.is_some() {
+      if let Some(ref mut levels) = def_levels {
+        num_def_levels = self.read_def_levels(&mut levels[..batch_size])?;
+        for i in 0..num_def_levels {
+          if levels[i] == self.descr.max_def_level() {
+            values_to_read += 1;
+          }
+        }
+      }
+    } else {
+      values_to_read = batch_size;
+    }
+
+    if self.descr.max_rep_level() > 0 && rep_levels.is_some() {
+      if let Some(ref mut levels) = rep_levels {
+        num_rep_levels = self.read_rep_levels(&mut levels[..batch_size])?;
+        if def_levels.is_some() && num_def_levels != num_rep_levels {
+          return Err(general_err!(
+            "Number of decoded rep / def levels did not match: {} != {}",
+            num_rep_levels,
+            num_def_levels
+          ));
+        }
+      }
+    }
+
+    let levels_read = max(max(num_def_levels, num_rep_levels), values_to_read);
+    self.num_decoded_values += levels_read as u32;
+    self.num_pending_values = values_to_read;
+
+    Ok((levels_read, values_to_read))
+  }
+
+  /// Reads values for levels returned by the last call to `read_levels`, at most
+  /// `values.len()` values are read. This is the second phase of a two-phase read.
+  ///
+  /// Returns the actual number of values read.
+  pub fn read_pending_values(&mut self, values: &mut [T::T]) -> Result<usize> {
+    let values_to_read = min(self.num_pending_values, values.len());
+    if values_to_read == 0 {
+      return Ok(0);
+    }
+    let values_read = self.read_values(&mut values[..values_to_read])?;
+    self.num_pending_values -= values_read;
+    Ok(values_read)
+  }
+
+  /// Skips values for levels returned by the last call to `read_levels` without
+  /// decoding them, at most `num_values` values are skipped. This is an alternative to
+  /// `read_pending_values` when values of the batch are not needed.
+  ///
+  /// Returns the actual number of values skipped.
+  pub fn skip_pending_values(&mut self, num_values: usize) -> Result<usize> {
+    let values_to_skip = min(self.num_pending_values, num_values);
+    if values_to_skip == 0 {
+      return Ok(0);
+    }
+    let values_skipped = self.current_decoder()?.skip(values_to_skip)?;
+    self.num_pending_values -= values_skipped;
+    Ok(values_skipped)
+  }
+
+  /// Returns the number of values for levels returned by `read_levels` that have not
+  /// been read yet.
+  pub fn num_pending_values(&self) -> usize {
+    self.num_pending_values
+  }
+
+  /// Returns an error if values of a two-phase read are still pending, since reading
+  /// levels or values in any other way would desynchronize them.
+  #[inline]
+  fn check_no_pending_values(&self) -> Result<()> {
+    if self.num_pending_values > 0 {
+      return Err(general_err!(
+        "Cannot read levels, {} values of the previous batch have not been read",
+        self.num_pending_values
+      ));
+    }
+    Ok(())
+  }
+
   /// Reads a new page and set up the decoders for levels, values or dictionary.
   /// Returns false if there's no page left.
   fn read_new_page(&mut self) -> Result<bool> {
@@ -268,7 +569,7 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
             },
             // 2. Data page v1
             Page::DataPage {
-              buf, num_values, encoding, def_level_encoding, rep_level_encoding
+              buf, num_values, encoding, def_level_encoding, rep_level_encoding, ..
             } => {
               self.num_buffered_values = num_values;
               self.num_decoded_values = 0;
@@ -277,18 +578,18 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
 
               if self.descr.max_rep_level() > 0 {
                 let mut rep_decoder = LevelDecoder::new(
-                  rep_level_encoding, self.descr.max_rep_level());
+                  rep_level_encoding, self.descr.max_rep_level())?;
                 let total_bytes = rep_decoder.set_data(
-                  self.num_buffered_values as usize, buffer_ptr.all());
+                  self.num_buffered_values as usize, buffer_ptr.all())?;
                 buffer_ptr = buffer_ptr.start_from(total_bytes);
                 self.rep_level_decoder = Some(rep_decoder);
               }
 
               if self.descr.max_def_level() > 0 {
                 let mut def_decoder = LevelDecoder::new(
-                  def_level_encoding, self.descr.max_def_level());
+                  def_level_encoding, self.descr.max_def_level())?;
                 let total_bytes = def_decoder.set_data(
-                  self.num_buffered_values as usize, buffer_ptr.all());
+                  self.num_buffered_values as usize, buffer_ptr.all())?;
                 buffer_ptr = buffer_ptr.start_from(total_bytes);
                 self.def_level_decoder = Some(def_decoder);
               }
@@ -300,7 +601,7 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
             },
             // 3. Data page v2
             Page::DataPageV2 { buf, num_values, encoding, num_nulls: _, num_rows: _,
-                def_levels_byte_len, rep_levels_byte_len, is_compressed: _ } => {
+                def_levels_byte_len, rep_levels_byte_len, .. } => {
               self.num_buffered_values = num_values;
               self.num_decoded_values = 0;
 
@@ -309,10 +610,10 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
               // DataPage v2 only supports RLE encoding for repetition levels
               if self.descr.max_rep_level() > 0 {
                 let mut rep_decoder = LevelDecoder::new(
-                  Encoding::RLE, self.descr.max_rep_level());
+                  Encoding::RLE, self.descr.max_rep_level())?;
                 let bytes_read = rep_decoder.set_data_range(
                   self.num_buffered_values as usize, &buf, offset,
-                  rep_levels_byte_len as usize);
+                  rep_levels_byte_len as usize)?;
                 offset += bytes_read;
                 self.rep_level_decoder = Some(rep_decoder);
               }
@@ -320,10 +621,10 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
               // DataPage v2 only supports RLE encoding for definition levels
               if self.descr.max_def_level() > 0 {
                 let mut def_decoder = LevelDecoder::new(
-                  Encoding::RLE, self.descr.max_def_level());
+                  Encoding::RLE, self.descr.max_def_level())?;
                 let bytes_read = def_decoder.set_data_range(
                   self.num_buffered_values as usize, &buf, offset,
-                  def_levels_byte_len as usize);
+                  def_levels_byte_len as usize)?;
                 offset += bytes_read;
                 self.def_level_decoder = Some(def_decoder);
               }
@@ -352,17 +653,28 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
       encoding = Encoding::RLE_DICTIONARY;
     }
 
+    if offset > buffer_ptr.len() {
+      return Err(eof_err!(
+        "Data page has {} bytes, but values start at offset {}",
+        buffer_ptr.len(),
+        offset
+      ));
+    }
+
     let decoder =
       if encoding == Encoding::RLE_DICTIONARY {
-        self.decoders.get_mut(&encoding).expect("Decoder for dict should have been set")
+        match self.decoders.get_mut(&encoding) {
+          Some(decoder) => decoder,
+          None => return Err(general_err!("Dictionary page is missing"))
+        }
       } else {
-        // Search cache for data page decoder
-        if !self.decoders.contains_key(&encoding) {
-          // Initialize decoder for this page
-          let data_decoder = get_decoder::<T>(self.descr.clone(), encoding)?;
-          self.decoders.insert(encoding, data_decoder);
+        // Search cache for data page decoder, or initialize decoder for this page
+        match self.decoders.entry(encoding) {
+          Entry::Occupied(entry) => entry.into_mut(),
+          Entry::Vacant(entry) => {
+            entry.insert(get_decoder::<T>(self.descr.clone(), encoding)?)
+          }
         }
-        self.decoders.get_mut(&encoding).unwrap()
       };
 
     decoder.set_data(buffer_ptr.start_from(offset), len as usize)?;
@@ -370,8 +682,12 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
     Ok(())
   }
 
+  /// Returns `true` if there are more values (or levels) to read in the column chunk.
+  ///
+  /// This might load the next page, after which `values_left_in_page` reflects the
+  /// number of values in that page.
   #[inline]
-  fn has_next(&mut self) -> Result<bool> {
+  pub fn has_next(&mut self) -> Result<bool> {
     if self.num_buffered_values == 0 ||
         self.num_buffered_values == self.num_decoded_values {
         // TODO: should we return false if read_new_page() = true and
@@ -384,27 +700,76 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
     } else { Ok(true) }
   }
 
+  /// Returns the number of values (including nulls) left to read in the current page.
+  /// Returns `0` if no page is loaded, see `has_next`.
+  #[inline]
+  pub fn values_left_in_page(&self) -> usize {
+    (self.num_buffered_values - self.num_decoded_values) as usize
+  }
+
+  /// Returns the number of values (including nulls) left to read in the column chunk,
+  /// which can be used to size buffers for `read_batch`, or `None` if page reader does
+  /// not know the number of values left, see `PageReader::num_values_left`.
+  #[inline]
+  pub fn values_left_in_column_chunk(&self) -> Option<usize> {
+    let num_values_left = self.page_reader.num_values_left();
+    if num_values_left < 0 {
+      None
+    } else {
+      Some(self.values_left_in_page() + num_values_left as usize)
+    }
+  }
+
+  /// Returns cumulative metrics of pages read by this column reader so far.
+  #[inline]
+  pub fn page_reader_metrics(&self) -> PageReaderMetrics {
+    self.page_reader.metrics()
+  }
+
+  /// Returns the number of rows left to read in the row group, or `None` if the column
+  /// is repeated, because the number of rows cannot be computed without reading
+  /// repetition levels, or if the number of values left is unknown.
+  #[inline]
+  pub fn rows_left_in_row_group(&self) -> Option<usize> {
+    if self.descr.max_rep_level() > 0 {
+      None
+    } else {
+      self.values_left_in_column_chunk()
+    }
+  }
+
   #[inline]
   fn read_rep_levels(&mut self, buffer: &mut [i16]) -> Result<usize> {
-    let level_decoder = self.rep_level_decoder.as_mut()
-      .expect("rep_level_decoder be set");
-    level_decoder.get(buffer)
+    match self.rep_level_decoder {
+      Some(ref mut level_decoder) => level_decoder.get(buffer),
+      None => Err(general_err!("Repetition level decoder is not set"))
+    }
   }
 
   #[inline]
   fn read_def_levels(&mut self, buffer: &mut [i16]) -> Result<usize> {
-    let level_decoder = self.def_level_decoder.as_mut()
-      .expect("def_level_decoder be set");
-    level_decoder.get(buffer)
+    match self.def_level_decoder {
+      Some(ref mut level_decoder) => level_decoder.get(buffer),
+      None => Err(general_err!("Definition level decoder is not set"))
+    }
   }
 
   #[inline]
   fn read_values(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    let encoding = self.current_encoding.expect("current_encoding should be set");
-    let current_decoder = self.decoders
-      .get_mut(&encoding)
-      .expect(format!("decoder for encoding {} should be set", encoding).as_str());
-    current_decoder.get(buffer)
+    self.current_decoder()?.get(buffer)
+  }
+
+  /// Returns decoder of values of the current page, or an error if no page is loaded.
+  #[inline]
+  fn current_decoder(&mut self) -> Result<&mut Box<Decoder<T>>> {
+    let encoding = match self.current_encoding {
+      Some(encoding) => encoding,
+      None => return Err(general_err!("No page is loaded to decode values"))
+    };
+    match self.decoders.get_mut(&encoding) {
+      Some(decoder) => Ok(decoder),
+      None => Err(general_err!("Decoder for encoding {} is not set", encoding))
+    }
   }
 
   #[inline]
@@ -419,12 +784,7 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
     }
 
     if encoding == Encoding::RLE_DICTIONARY {
-      let mut dictionary = PlainDecoder::<T>::new(self.descr.type_length());
-      let num_values = page.num_values();
-      dictionary.set_data(page.buffer().clone(), num_values as usize)?;
-
-      let mut decoder = DictDecoder::new();
-      decoder.set_dict(Box::new(dictionary))?;
+      let decoder = get_dict_decoder::<T>(self.descr.clone(), &page)?;
       self.decoders.insert(encoding, Box::new(decoder));
       Ok(true)
     } else {
@@ -433,6 +793,312 @@ impl<T: DataType> ColumnReaderImpl<T> where T: 'static {
   }
 }
 
+impl<T: DataType<T = ByteArray>> ColumnReaderImpl<T> where T: 'static {
+  /// Reads a batch of DECIMAL values of a BYTE_ARRAY or FIXED_LEN_BYTE_ARRAY column as
+  /// unscaled `i128` values, see [`decode_decimals`](`decode_decimals`). Levels are
+  /// handled the same way as in `read_batch`.
+  ///
+  /// Returns a tuple where the first element is the actual number of values read,
+  /// and the second element is the actual number of levels read.
+  pub fn read_decimal_batch(
+    &mut self,
+    batch_size: usize,
+    def_levels: Option<&mut [i16]>,
+    rep_levels: Option<&mut [i16]>,
+    values: &mut [i128]
+  ) -> Result<(usize, usize)> {
+    let mut buffer = vec![ByteArray::new(); min(batch_size, values.len())];
+    let (values_read, levels_read) =
+      self.read_batch(batch_size, def_levels, rep_levels, &mut buffer)?;
+    let decimals = decode_decimals(&self.descr, &buffer[..values_read])?;
+    values[..values_read].copy_from_slice(&decimals);
+    Ok((values_read, levels_read))
+  }
+}
+
+impl ColumnReaderImpl<BoolType> {
+  /// Reads a batch of at most `batch_size` levels and values of a BOOLEAN column like
+  /// `read_batch`, but values are decoded directly into a packed bitmap, one bit per
+  /// value with the least-significant bit first, as used by Arrow boolean arrays.
+  ///
+  /// Values are written densely starting at bit `offset` of `values`, which is resized
+  /// to fit all bits read; bits before `offset` are not changed. Levels are handled the
+  /// same way as in `read_batch`.
+  ///
+  /// Returns a tuple where the first element is the actual number of values read,
+  /// and the second element is the actual number of levels read.
+  pub fn read_bitmap_batch(
+    &mut self,
+    batch_size: usize,
+    mut def_levels: Option<&mut [i16]>,
+    mut rep_levels: Option<&mut [i16]>,
+    values: &mut Vec<u8>,
+    offset: usize
+  ) -> Result<(usize, usize)> {
+    let mut batch_size = batch_size;
+    if let Some(ref levels) = def_levels {
+      batch_size = min(batch_size, levels.len());
+    }
+    if let Some(ref levels) = rep_levels {
+      batch_size = min(batch_size, levels.len());
+    }
+
+    let mut values_read = 0;
+    let mut levels_read = 0;
+    while levels_read < batch_size {
+      let (num_levels, num_values) = self.read_levels(
+        batch_size - levels_read,
+        def_levels.as_mut().map(|levels| &mut levels[levels_read..]),
+        rep_levels.as_mut().map(|levels| &mut levels[levels_read..])
+      )?;
+      if num_levels == 0 {
+        break;
+      }
+
+      let bit_offset = offset + values_read;
+      let num_bytes = (bit_offset + num_values + 7) / 8;
+      if values.len() < num_bytes {
+        values.resize(num_bytes, 0);
+      }
+      let bits_read = self.current_decoder()?.get_bitmap(values, bit_offset, num_values)?;
+      self.num_pending_values = 0;
+      if bits_read < num_values {
+        return Err(eof_err!(
+          "Expected to decode {} values, found {}",
+          num_values,
+          bits_read
+        ));
+      }
+      values_read += num_values;
+      levels_read += num_levels;
+    }
+    Ok((values_read, levels_read))
+  }
+}
+
+/// Converts DECIMAL values of BYTE_ARRAY or FIXED_LEN_BYTE_ARRAY column `descr`, stored
+/// as big-endian two's complement, into unscaled `i128` values. The value is
+/// `unscaled * 10^(-scale)` with scale of the column, see
+/// [`Decimal`](`::data_type::Decimal`).
+///
+/// Returns error if column is not annotated as DECIMAL, or if any value is empty, longer
+/// than 16 bytes or does not fit into the precision of the column.
+pub fn decode_decimals(
+  descr: &ColumnDescriptor,
+  values: &[ByteArray]
+) -> Result<Vec<i128>> {
+  if descr.logical_type() != LogicalType::DECIMAL {
+    return Err(general_err!("Column {} is not annotated as DECIMAL", descr.path()));
+  }
+  match descr.physical_type() {
+    Type::BYTE_ARRAY | Type::FIXED_LEN_BYTE_ARRAY => {},
+    other => return Err(general_err!(
+      "Cannot decode DECIMAL values of column {} of type {}",
+      descr.path(),
+      other
+    ))
+  }
+  let precision = descr.type_precision();
+  let bound = decimal_bound(precision);
+
+  let mut decimals = Vec::with_capacity(values.len());
+  for value in values {
+    let decimal = match value.to_decimal() {
+      Some(decimal) => decimal,
+      None => return Err(general_err!(
+        "Invalid DECIMAL value of {} bytes in column {}",
+        value.len(),
+        descr.path()
+      ))
+    };
+    if !bound.map(|b| decimal > -b && decimal < b).unwrap_or(true) {
+      return Err(general_err!(
+        "Value {} does not fit into column {} of type DECIMAL({}, {})",
+        decimal,
+        descr.path(),
+        precision,
+        descr.type_scale()
+      ));
+    }
+    decimals.push(decimal);
+  }
+  Ok(decimals)
+}
+
+/// Returns the number of records that start in the provided repetition levels, i.e.
+/// the number of levels equal to `0`.
+#[inline]
+pub fn num_records(rep_levels: &[i16]) -> usize {
+  rep_levels.iter().filter(|&&level| level == 0).count()
+}
+
+/// Default number of levels buffered by a record reader at a time.
+const DEFAULT_RECORD_BATCH_SIZE: usize = 1024;
+
+/// Reader of whole records (rows) of a column, as opposed to leaf values returned by
+/// `read_batch`. A record of a repeated column consists of all levels from repetition
+/// level `0` up to the next one and can span several pages; for a non-repeated column
+/// every level is a record.
+///
+/// Levels and values are buffered internally, so records are never split between calls,
+/// which makes it possible to limit or skip rows of repeated columns.
+pub struct RecordReader<T: DataType> {
+  reader: ColumnReaderImpl<T>,
+  batch_size: usize,
+  def_levels: Vec<i16>,
+  rep_levels: Vec<i16>,
+  values: Vec<T::T>,
+  // Number of buffered levels, or values if the column is required and non-repeated
+  num_levels: usize,
+  exhausted: bool
+}
+
+impl<T: DataType> RecordReader<T> where T: 'static {
+  /// Creates record reader for the column reader.
+  pub fn new(reader: ColumnReaderImpl<T>) -> Self {
+    Self {
+      reader: reader,
+      batch_size: DEFAULT_RECORD_BATCH_SIZE,
+      def_levels: Vec::new(),
+      rep_levels: Vec::new(),
+      values: Vec::new(),
+      num_levels: 0,
+      exhausted: false
+    }
+  }
+
+  /// Sets the number of levels read from the column reader at a time.
+  pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+    self.batch_size = max(batch_size, 1);
+    self
+  }
+
+  /// Reads at most `num_records` whole records, appending their definition levels,
+  /// repetition levels and non-null values to the provided vectors. Levels are only
+  /// appended if the column has them, i.e. its max definition or repetition level is
+  /// greater than `0`.
+  ///
+  /// Returns the number of records read, `0` once the column chunk is exhausted.
+  pub fn read_records(
+    &mut self,
+    num_records: usize,
+    def_levels: &mut Vec<i16>,
+    rep_levels: &mut Vec<i16>,
+    values: &mut Vec<T::T>
+  ) -> Result<usize> {
+    let (records, num_levels) = self.buffer_records(num_records)?;
+    let num_values = self.num_values(num_levels);
+    if self.reader.descr.max_def_level() > 0 {
+      def_levels.extend(self.def_levels.drain(..num_levels));
+    }
+    if self.reader.descr.max_rep_level() > 0 {
+      rep_levels.extend(self.rep_levels.drain(..num_levels));
+    }
+    values.extend(self.values.drain(..num_values));
+    self.num_levels -= num_levels;
+    Ok(records)
+  }
+
+  /// Skips at most `num_records` whole records.
+  ///
+  /// Returns the number of records skipped, `0` once the column chunk is exhausted.
+  pub fn skip_records(&mut self, num_records: usize) -> Result<usize> {
+    let (records, num_levels) = self.buffer_records(num_records)?;
+    let num_values = self.num_values(num_levels);
+    if self.reader.descr.max_def_level() > 0 {
+      self.def_levels.drain(..num_levels);
+    }
+    if self.reader.descr.max_rep_level() > 0 {
+      self.rep_levels.drain(..num_levels);
+    }
+    self.values.drain(..num_values);
+    self.num_levels -= num_levels;
+    Ok(records)
+  }
+
+  /// Buffers levels until at most `num_records` complete records are available.
+  /// Returns a tuple of the number of records and the number of levels they span.
+  fn buffer_records(&mut self, num_records: usize) -> Result<(usize, usize)> {
+    let mut records = 0;
+    let mut scanned = 0;
+    loop {
+      while scanned < self.num_levels {
+        // Buffered levels always start with a record, the start of the record after
+        // `num_records` records marks the end of the last one
+        if scanned == 0 || self.is_record_start(scanned) {
+          if records == num_records {
+            return Ok((records, scanned));
+          }
+          records += 1;
+        }
+        scanned += 1;
+      }
+      // The last buffered record is only complete if there are no levels left
+      if self.exhausted || !self.fill_buffer()? {
+        return Ok((records, scanned));
+      }
+    }
+  }
+
+  /// Reads the next batch of levels and values into buffers.
+  /// Returns `false` if there is nothing left to read.
+  fn fill_buffer(&mut self) -> Result<bool> {
+    let max_def_level = self.reader.descr.max_def_level();
+    let max_rep_level = self.reader.descr.max_rep_level();
+    let batch_size = self.batch_size;
+
+    let mut def_levels = vec![0; if max_def_level > 0 { batch_size } else { 0 }];
+    let mut rep_levels = vec![0; if max_rep_level > 0 { batch_size } else { 0 }];
+    let mut values = vec![T::T::default(); batch_size];
+    let (values_read, levels_read) = self.reader.read_batch(
+      batch_size,
+      if max_def_level > 0 { Some(&mut def_levels[..]) } else { None },
+      if max_rep_level > 0 { Some(&mut rep_levels[..]) } else { None },
+      &mut values
+    )?;
+
+    let num_levels = if max_def_level > 0 || max_rep_level > 0 {
+      levels_read
+    } else {
+      values_read
+    };
+    if num_levels == 0 {
+      self.exhausted = true;
+      return Ok(false);
+    }
+
+    if max_def_level > 0 {
+      self.def_levels.extend_from_slice(&def_levels[..num_levels]);
+    }
+    if max_rep_level > 0 {
+      self.rep_levels.extend_from_slice(&rep_levels[..num_levels]);
+    }
+    values.truncate(values_read);
+    self.values.extend(values);
+    self.num_levels += num_levels;
+    Ok(true)
+  }
+
+  #[inline]
+  fn is_record_start(&self, index: usize) -> bool {
+    self.reader.descr.max_rep_level() == 0 || self.rep_levels[index] == 0
+  }
+
+  /// Returns the number of non-null values in the first `num_levels` buffered levels.
+  #[inline]
+  fn num_values(&self, num_levels: usize) -> usize {
+    let max_def_level = self.reader.descr.max_def_level();
+    if max_def_level > 0 {
+      self.def_levels[..num_levels]
+        .iter()
+        .filter(|&&level| level == max_def_level)
+        .count()
+    } else {
+      num_levels
+    }
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -448,7 +1114,8 @@ mod tests {
   use encodings::levels::LevelEncoder;
   use schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
   use util::memory::{ByteBufferPtr, MemTracker, MemTrackerPtr};
-  use util::test_common::random_numbers_range;
+  use util::bit_util::get_array_bit;
+  use util::test_common::{random_bools, random_numbers_range};
 
   const NUM_LEVELS: usize = 128;
   const NUM_PAGES: usize = 2;
@@ -624,6 +1291,683 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_read_batch_values_left() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 0, 0, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 2, 10, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages.clone()));
+    let column_reader = get_column_reader(desc.clone(), Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    assert_eq!(typed_column_reader.values_left_in_page(), 0);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), Some(20));
+    assert_eq!(typed_column_reader.rows_left_in_row_group(), Some(20));
+
+    assert!(typed_column_reader.has_next().unwrap());
+    assert_eq!(typed_column_reader.values_left_in_page(), 10);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), Some(20));
+
+    let mut buf = vec![0; 15];
+    let (values_read, _) = typed_column_reader.read_batch(15, None, None, &mut buf)
+      .unwrap();
+    assert_eq!(values_read, 15);
+    assert_eq!(&buf[..], &values[..15]);
+    assert_eq!(typed_column_reader.values_left_in_page(), 5);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), Some(5));
+    assert_eq!(typed_column_reader.rows_left_in_row_group(), Some(5));
+
+    let mut buf = vec![0; typed_column_reader.values_left_in_column_chunk().unwrap()];
+    let (values_read, _) = typed_column_reader.read_batch(5, None, None, &mut buf)
+      .unwrap();
+    assert_eq!(values_read, 5);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), Some(0));
+    assert!(!typed_column_reader.has_next().unwrap());
+
+    // Page reader does not know the number of values left
+    let page_reader = UnknownSizePageReader(TestPageReader::new(Vec::from(pages)));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), None);
+    assert_eq!(typed_column_reader.rows_left_in_row_group(), None);
+    assert!(typed_column_reader.has_next().unwrap());
+    assert_eq!(typed_column_reader.values_left_in_page(), 10);
+    assert_eq!(typed_column_reader.values_left_in_column_chunk(), None);
+    assert_eq!(typed_column_reader.page_reader_metrics(), PageReaderMetrics::default());
+  }
+
+  #[test]
+  fn test_read_records() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 0, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 2, 20, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let mut values_iter = values.into_iter();
+    let expected: Vec<Option<i32>> = def_levels.iter().map(|&level| {
+      if level == 1 { values_iter.next() } else { None }
+    }).collect();
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    let mut records = Vec::new();
+    loop {
+      let batch = typed_column_reader.read_records(7).unwrap();
+      if batch.is_empty() {
+        break;
+      }
+      assert!(batch.len() <= 7);
+      records.extend(batch);
+    }
+    assert_eq!(records, expected);
+  }
+
+  #[test]
+  fn test_read_batch_spaced() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 1, ColumnPath::new(Vec::new())));
+
+    let mut expected_def_levels = Vec::new();
+    let mut expected_rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::RLE_DICTIONARY, 3, 20, 0, 100,
+      &mut expected_def_levels, &mut expected_rep_levels, &mut values, &mut pages,
+      false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    // Batches span pages
+    let mut def_levels = vec![0; 60];
+    let mut rep_levels = vec![0; 60];
+    let mut slots = vec![0; 60];
+    let mut values_read = 0;
+    let mut levels_read = 0;
+    while levels_read < 60 {
+      let (num_values, num_levels) = typed_column_reader.read_batch_spaced(
+        25,
+        &mut def_levels[levels_read..],
+        Some(&mut rep_levels[levels_read..]),
+        &mut slots[levels_read..]
+      ).unwrap();
+      assert!(num_levels > 0);
+      values_read += num_values;
+      levels_read += num_levels;
+    }
+    assert_eq!(values_read, values.len());
+    assert_eq!(def_levels, expected_def_levels);
+    assert_eq!(rep_levels, expected_rep_levels);
+
+    let mut values_iter = values.iter();
+    for (slot, &level) in slots.iter().zip(def_levels.iter()) {
+      if level == 1 {
+        assert_eq!(Some(slot), values_iter.next());
+      }
+    }
+    assert_eq!(
+      typed_column_reader.read_batch_spaced(5, &mut [0; 5], None, &mut [0; 5]).unwrap(),
+      (0, 0)
+    );
+  }
+
+  #[test]
+  fn test_read_bitmap_batch() {
+    let primitive_type = SchemaType::primitive_type_builder("a", PhysicalType::BOOLEAN)
+      .with_repetition(Repetition::OPTIONAL)
+      .build()
+      .unwrap();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 0, ColumnPath::new(Vec::new())));
+
+    for encoding in &[Encoding::PLAIN, Encoding::RLE] {
+      let mut def_levels = Vec::new();
+      let mut values = Vec::new();
+      let mut pages = Vec::new();
+      for _ in 0..3 {
+        let page_def_levels: Vec<i16> =
+          random_bools(50).into_iter().map(|valid| valid as i16).collect();
+        let num_values = page_def_levels.iter().filter(|&&level| level == 1).count();
+        let page_values = random_bools(num_values);
+        let mut pb = DataPageBuilderImpl::new(desc.clone(), 50, true);
+        pb.add_def_levels(1, &page_def_levels);
+        pb.add_values::<BoolType>(*encoding, &page_values);
+        pages.push(pb.consume());
+        def_levels.extend(page_def_levels);
+        values.extend(page_values);
+      }
+
+      let page_reader = TestPageReader::new(pages);
+      let column_reader = get_column_reader(desc.clone(), Box::new(page_reader));
+      let mut typed_column_reader = get_typed_column_reader::<BoolType>(column_reader);
+
+      // Batches span pages, existing bits are kept
+      let mut bitmap = vec![0b1];
+      let mut actual_def_levels = vec![0; def_levels.len()];
+      let mut values_read = 0;
+      let mut levels_read = 0;
+      loop {
+        let (num_values, num_levels) = typed_column_reader.read_bitmap_batch(
+          40,
+          Some(&mut actual_def_levels[levels_read..]),
+          None,
+          &mut bitmap,
+          1 + values_read
+        ).unwrap();
+        if num_levels == 0 {
+          break;
+        }
+        values_read += num_values;
+        levels_read += num_levels;
+      }
+      assert_eq!(levels_read, def_levels.len());
+      assert_eq!(values_read, values.len());
+      assert_eq!(actual_def_levels, def_levels);
+      assert_eq!(bitmap.len(), (values.len() + 1 + 7) / 8);
+      assert!(get_array_bit(&bitmap, 0));
+      for (i, value) in values.iter().enumerate() {
+        assert_eq!(get_array_bit(&bitmap, 1 + i), *value, "{:?} value {}", encoding, i);
+      }
+    }
+  }
+
+  #[test]
+  fn test_read_records_required() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 0, 0, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 1, 10, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    let records = typed_column_reader.read_records(16).unwrap();
+    let expected: Vec<Option<i32>> = values.into_iter().map(Some).collect();
+    assert_eq!(records, expected);
+  }
+
+  #[test]
+  fn test_read_batch_dyn() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 0, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 2, 20, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let mut column_reader = get_column_reader(desc, Box::new(page_reader));
+
+    let mut actual_values = Vec::new();
+    let mut actual_def_levels = vec![0; def_levels.len()];
+    let mut levels_read = 0;
+    loop {
+      let (_, num_levels) = column_reader.read_batch_dyn(
+        7,
+        Some(&mut actual_def_levels[levels_read..]),
+        None,
+        &mut actual_values
+      ).unwrap();
+      if num_levels == 0 {
+        break;
+      }
+      levels_read += num_levels;
+    }
+
+    let expected_values: Vec<Row> = values.into_iter().map(Row::Int).collect();
+    assert_eq!(actual_values, expected_values);
+    assert_eq!(actual_def_levels, def_levels);
+  }
+
+  #[test]
+  fn test_read_batch_coerced() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 0, ColumnPath::new(Vec::new())));
+
+    // Dictionary encoded values are read as plain values
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN_DICTIONARY, 1, 20, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let mut column_reader = get_column_reader(desc, Box::new(page_reader));
+
+    let mut actual_values = vec![Row::Str("existing".to_string())];
+    let mut actual_def_levels = vec![0; def_levels.len()];
+    let (_, num_levels) = column_reader.read_batch_coerced(
+      def_levels.len(),
+      Some(&mut actual_def_levels[..]),
+      None,
+      &mut actual_values,
+      Coercion::IntToLong
+    ).unwrap();
+    assert_eq!(num_levels, def_levels.len());
+
+    let mut expected_values = vec![Row::Str("existing".to_string())];
+    expected_values.extend(values.into_iter().map(|value| Row::Long(value as i64)));
+    assert_eq!(actual_values, expected_values);
+    assert_eq!(actual_def_levels, def_levels);
+  }
+
+  #[test]
+  fn test_read_decimal_batch() {
+    let primitive_type = SchemaType::decimal_type_builder("dec", 20, 2)
+      .with_repetition(Repetition::OPTIONAL)
+      .build()
+      .unwrap();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 0, ColumnPath::new(Vec::new())));
+    let length = desc.type_length() as usize;
+
+    let decimals = vec![-1, 10i128.pow(19), -(10i128.pow(19)) + 1, 0];
+    let values: Vec<ByteArray> = decimals
+      .iter()
+      .map(|&value| ByteArray::from_decimal(value, length).unwrap())
+      .collect();
+    let def_levels = vec![1, 0, 1, 1, 1];
+    let mut pb = DataPageBuilderImpl::new(desc.clone(), def_levels.len() as u32, true);
+    pb.add_def_levels(1, &def_levels);
+    pb.add_values::<FixedLenByteArrayType>(Encoding::PLAIN, &values);
+    let page_reader = TestPageReader::new(vec![pb.consume()]);
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<FixedLenByteArrayType>(
+      column_reader);
+
+    let mut actual_values = vec![0; 10];
+    let mut actual_def_levels = vec![0; 10];
+    let (values_read, levels_read) = typed_column_reader.read_decimal_batch(
+      10, Some(&mut actual_def_levels), None, &mut actual_values).unwrap();
+    assert_eq!(values_read, 4);
+    assert_eq!(levels_read, 5);
+    assert_eq!(&actual_values[..values_read], &decimals[..]);
+    assert_eq!(&actual_def_levels[..levels_read], &def_levels[..]);
+  }
+
+  #[test]
+  fn test_decode_decimals() {
+    let tpe = SchemaType::primitive_type_builder("dec", PhysicalType::BYTE_ARRAY)
+      .with_logical_type(LogicalType::DECIMAL)
+      .with_precision(3)
+      .with_scale(1)
+      .build()
+      .unwrap();
+    let desc = ColumnDescriptor::new(
+      Rc::new(tpe), None, 0, 0, ColumnPath::new(vec!["dec".to_string()]));
+
+    let values = vec![
+      ByteArray::from(vec![0x03, 0xE7]),
+      ByteArray::from(vec![0xFC, 0x19]),
+      ByteArray::from(vec![0; 16])
+    ];
+    assert_eq!(decode_decimals(&desc, &values).unwrap(), vec![999, -999, 0]);
+    assert_eq!(
+      decode_decimals(&desc, &[ByteArray::from(vec![0x03, 0xE8])]).unwrap_err(),
+      general_err!("Value 1000 does not fit into column \"dec\" of type DECIMAL(3, 1)")
+    );
+    assert_eq!(
+      decode_decimals(&desc, &[ByteArray::from(vec![])]).unwrap_err(),
+      general_err!("Invalid DECIMAL value of 0 bytes in column \"dec\"")
+    );
+    assert_eq!(
+      decode_decimals(&desc, &[ByteArray::from(vec![0; 17])]).unwrap_err(),
+      general_err!("Invalid DECIMAL value of 17 bytes in column \"dec\"")
+    );
+
+    let desc = ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 0, 0, ColumnPath::new(Vec::new()));
+    assert!(decode_decimals(&desc, &values).is_err());
+  }
+
+  #[test]
+  fn test_read_records_repeated() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 1, ColumnPath::from("a")));
+    let page_reader = TestPageReader::new(Vec::new());
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+    assert_eq!(
+      typed_column_reader.read_records(16).unwrap_err(),
+      general_err!("Cannot read records of repeated column a")
+    );
+  }
+
+  #[test]
+  fn test_num_records() {
+    assert_eq!(num_records(&[]), 0);
+    assert_eq!(num_records(&[0, 1, 1, 0, 2, 0]), 3);
+    assert_eq!(num_records(&[1, 1]), 0);
+  }
+
+  #[test]
+  fn test_record_reader_repeated() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 2, 2, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 3, 20, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_typed_column_reader::<Int32Type>(
+      get_column_reader(desc, Box::new(page_reader)));
+    let mut record_reader = RecordReader::new(column_reader).with_batch_size(7);
+
+    let mut actual_def_levels = Vec::new();
+    let mut actual_rep_levels = Vec::new();
+    let mut actual_values = Vec::new();
+    let mut total_records = 0;
+    loop {
+      let num_levels = actual_rep_levels.len();
+      let records = record_reader.read_records(
+        2, &mut actual_def_levels, &mut actual_rep_levels, &mut actual_values).unwrap();
+      if records == 0 {
+        break;
+      }
+      // Every batch consists of whole records
+      assert!(records <= 2);
+      let starts = record_starts(&rep_levels);
+      let end = actual_rep_levels.len();
+      assert_eq!(starts.iter().filter(|&&i| i >= num_levels && i < end).count(), records);
+      if actual_rep_levels.len() < rep_levels.len() {
+        assert!(starts.contains(&actual_rep_levels.len()));
+      }
+      total_records += records;
+    }
+
+    assert_eq!(total_records, record_starts(&rep_levels).len());
+    assert_eq!(actual_def_levels, def_levels);
+    assert_eq!(actual_rep_levels, rep_levels);
+    assert_eq!(actual_values, values);
+  }
+
+  #[test]
+  fn test_record_reader_skip_records() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 1, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 2, 20, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let starts = record_starts(&rep_levels);
+    let total_records = starts.len();
+    let to_skip = total_records / 2;
+    // Index of the first level of the first record after skipped records
+    let start = starts[to_skip];
+    let skipped_values = def_levels[..start].iter().filter(|&&level| level == 1).count();
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_typed_column_reader::<Int32Type>(
+      get_column_reader(desc, Box::new(page_reader)));
+    let mut record_reader = RecordReader::new(column_reader).with_batch_size(5);
+
+    assert_eq!(record_reader.skip_records(to_skip).unwrap(), to_skip);
+
+    let mut actual_def_levels = Vec::new();
+    let mut actual_rep_levels = Vec::new();
+    let mut actual_values = Vec::new();
+    let records = record_reader.read_records(
+      total_records, &mut actual_def_levels, &mut actual_rep_levels, &mut actual_values
+    ).unwrap();
+    assert_eq!(records, total_records - to_skip);
+    assert_eq!(&actual_def_levels[..], &def_levels[start..]);
+    assert_eq!(&actual_rep_levels[..], &rep_levels[start..]);
+    assert_eq!(&actual_values[..], &values[skipped_values..]);
+    assert_eq!(record_reader.skip_records(1).unwrap(), 0);
+  }
+
+  #[test]
+  fn test_record_reader_required() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 0, 0, ColumnPath::new(Vec::new())));
+
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 2, 10, 0, 100,
+      &mut def_levels, &mut rep_levels, &mut values, &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_typed_column_reader::<Int32Type>(
+      get_column_reader(desc, Box::new(page_reader)));
+    let mut record_reader = RecordReader::new(column_reader).with_batch_size(3);
+
+    assert_eq!(record_reader.skip_records(4).unwrap(), 4);
+    let mut actual_values = Vec::new();
+    let records = record_reader.read_records(
+      10, &mut Vec::new(), &mut Vec::new(), &mut actual_values).unwrap();
+    assert_eq!(records, 10);
+    assert_eq!(&actual_values[..], &values[4..14]);
+    assert_eq!(record_reader.skip_records(10).unwrap(), 6);
+  }
+
+  #[test]
+  fn test_read_levels_and_values() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 1, ColumnPath::new(Vec::new())));
+
+    let mut expected_def_levels = Vec::new();
+    let mut expected_rep_levels = Vec::new();
+    let mut expected_values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::PLAIN, 3, 20, 0, 100,
+      &mut expected_def_levels, &mut expected_rep_levels, &mut expected_values,
+      &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    let mut def_levels = vec![0; 60];
+    let mut rep_levels = vec![0; 60];
+    let mut values = vec![0; 60];
+    let mut levels_read = 0;
+    let mut values_read = 0;
+    loop {
+      let (num_levels, num_values) = typed_column_reader.read_levels(
+        7,
+        Some(&mut def_levels[levels_read..]),
+        Some(&mut rep_levels[levels_read..])
+      ).unwrap();
+      if num_levels == 0 {
+        break;
+      }
+      // Values must be read before reading the next batch of levels
+      assert_eq!(typed_column_reader.num_pending_values(), num_values);
+      if num_values > 0 {
+        assert!(typed_column_reader.read_levels(7, None, None).is_err());
+        assert!(typed_column_reader.read_batch(7, None, None, &mut [0; 7]).is_err());
+      }
+      let num_values_read =
+        typed_column_reader.read_pending_values(&mut values[values_read..]).unwrap();
+      assert_eq!(num_values_read, num_values);
+      assert_eq!(typed_column_reader.num_pending_values(), 0);
+      levels_read += num_levels;
+      values_read += num_values_read;
+    }
+
+    assert_eq!(levels_read, 60);
+    assert_eq!(def_levels, expected_def_levels);
+    assert_eq!(rep_levels, expected_rep_levels);
+    assert_eq!(&values[..values_read], &expected_values[..]);
+  }
+
+  #[test]
+  fn test_read_levels_and_skip_values() {
+    let primitive_type = get_test_int32_type();
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(primitive_type), None, 1, 1, ColumnPath::new(Vec::new())));
+
+    let mut expected_def_levels = Vec::new();
+    let mut expected_rep_levels = Vec::new();
+    let mut expected_values = Vec::new();
+    let mut pages = VecDeque::new();
+    make_pages::<Int32Type>(desc.clone(), Encoding::RLE_DICTIONARY, 3, 20, 0, 100,
+      &mut expected_def_levels, &mut expected_rep_levels, &mut expected_values,
+      &mut pages, false);
+
+    let page_reader = TestPageReader::new(Vec::from(pages));
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+
+    // Skip values of every other batch and read values of the rest
+    let mut values = Vec::new();
+    let mut values_seen = 0;
+    let mut batch = 0;
+    loop {
+      let (num_levels, num_values) =
+        typed_column_reader.read_levels(7, None, None).unwrap();
+      if num_levels == 0 {
+        break;
+      }
+      if batch % 2 == 0 {
+        let num_skipped = typed_column_reader.skip_pending_values(num_values).unwrap();
+        assert_eq!(num_skipped, num_values);
+      } else {
+        let mut buffer = vec![0; num_values];
+        let num_read = typed_column_reader.read_pending_values(&mut buffer).unwrap();
+        assert_eq!(num_read, num_values);
+        assert_eq!(&buffer[..], &expected_values[values_seen..values_seen + num_values]);
+        values.extend(buffer);
+      }
+      assert_eq!(typed_column_reader.num_pending_values(), 0);
+      values_seen += num_values;
+      batch += 1;
+    }
+
+    assert_eq!(values_seen, expected_values.len());
+    assert!(!values.is_empty());
+  }
+
+  #[test]
+  fn test_read_malformed_pages() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 1, 0, ColumnPath::new(Vec::new())));
+    let data_page = |buf: Vec<u8>, encoding, def_level_encoding| Page::DataPage {
+      buf: ByteBufferPtr::new(buf),
+      num_values: 4,
+      encoding: encoding,
+      def_level_encoding: def_level_encoding,
+      rep_level_encoding: Encoding::RLE,
+      statistics: None
+    };
+    // Definition levels of 4 non-null values, bit width 1
+    let levels = vec![2, 0, 0, 0, 8, 1];
+    let read_page = |page: Page| {
+      let page_reader = TestPageReader::new(vec![page]);
+      let column_reader = get_column_reader(desc.clone(), Box::new(page_reader));
+      let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+      let mut def_levels = vec![0; 4];
+      let mut values = vec![0; 4];
+      typed_column_reader.read_batch(4, Some(&mut def_levels), None, &mut values)
+    };
+
+    let mut buf = levels.clone();
+    buf.extend_from_slice(&[1, 2, 0]);
+    assert_eq!(
+      read_page(data_page(buf, Encoding::RLE_DICTIONARY, Encoding::RLE)).unwrap_err(),
+      general_err!("Dictionary page is missing")
+    );
+    assert_eq!(
+      read_page(data_page(vec![10, 0, 0, 0, 8], Encoding::PLAIN, Encoding::RLE))
+        .unwrap_err(),
+      eof_err!("Expected 10 bytes of levels, found 1")
+    );
+    assert_eq!(
+      read_page(data_page(levels.clone(), Encoding::PLAIN, Encoding::PLAIN)).unwrap_err(),
+      general_err!("Unsupported encoding PLAIN of levels")
+    );
+    let page = Page::DataPageV2 {
+      buf: ByteBufferPtr::new(vec![8, 1]),
+      num_values: 4,
+      encoding: Encoding::PLAIN,
+      num_nulls: 0,
+      num_rows: 4,
+      def_levels_byte_len: 3,
+      rep_levels_byte_len: 0,
+      is_compressed: false,
+      statistics: None
+    };
+    assert_eq!(
+      read_page(page).unwrap_err(),
+      eof_err!("Expected 3 bytes of levels at offset 0, found 2")
+    );
+  }
+
+  #[test]
+  fn test_read_batch_mismatched_levels() {
+    let desc = Rc::new(ColumnDescriptor::new(
+      Rc::new(get_test_int32_type()), None, 1, 1, ColumnPath::new(Vec::new())));
+    // Repetition levels are truncated to 2 of 4 values, definition levels are complete
+    let mut buf = vec![2, 0, 0, 0, 4, 0, 2, 0, 0, 0, 8, 1];
+    buf.extend_from_slice(&[0; 16]);
+    let page = Page::DataPage {
+      buf: ByteBufferPtr::new(buf),
+      num_values: 4,
+      encoding: Encoding::PLAIN,
+      def_level_encoding: Encoding::RLE,
+      rep_level_encoding: Encoding::RLE,
+      statistics: None
+    };
+    let page_reader = TestPageReader::new(vec![page]);
+    let column_reader = get_column_reader(desc, Box::new(page_reader));
+    let mut typed_column_reader = get_typed_column_reader::<Int32Type>(column_reader);
+    let mut def_levels = vec![0; 4];
+    let mut rep_levels = vec![0; 4];
+    let mut values = vec![0; 4];
+    let res = typed_column_reader.read_batch(
+      4, Some(&mut def_levels), Some(&mut rep_levels), &mut values);
+    assert_eq!(
+      res.unwrap_err(),
+      general_err!("Number of decoded rep / def levels did not match (2 vs 4)")
+    );
+  }
+
   // ----------------------------------------------------------------------
   // Helper methods to make pages and test
   //
@@ -667,6 +2011,12 @@ mod tests {
   // reader -> typed column reader, buffer values in `read_batch` method and compare
   // output with generated data.
 
+  // Returns indices of levels that start a record. Test pages have random repetition
+  // levels, so the first level is a record start regardless of its value.
+  fn record_starts(rep_levels: &[i16]) -> Vec<usize> {
+    (0..rep_levels.len()).filter(|&i| i == 0 || rep_levels[i] == 0).collect()
+  }
+
   // Returns dummy Parquet `Type` for primitive field, because most of our tests use
   // INT32 physical type.
   fn get_test_int32_type() -> SchemaType {
@@ -961,6 +2311,22 @@ mod tests {
     fn get_next_page(&mut self) -> Result<Option<Page>> {
       Ok(self.pages.next())
     }
+
+    fn num_values_left(&self) -> i64 {
+      self.pages.as_slice().iter().map(|page| match page {
+        &Page::DictionaryPage { .. } => 0,
+        page => page.num_values() as i64
+      }).sum()
+    }
+  }
+
+  /// Page reader that relies on default implementations of `PageReader`.
+  struct UnknownSizePageReader(TestPageReader);
+
+  impl PageReader for UnknownSizePageReader {
+    fn get_next_page(&mut self) -> Result<Option<Page>> {
+      self.0.get_next_page()
+    }
   }
 
   // ----------------------------------------------------------------------
@@ -1012,8 +2378,10 @@ mod tests {
 
     // Adds levels to the buffer and return number of encoded bytes
     fn add_levels(&mut self, max_level: i16, levels: &[i16]) -> u32 {
-      let size = LevelEncoder::max_buffer_size(Encoding::RLE, max_level, levels.len());
-      let mut level_encoder = LevelEncoder::new(Encoding::RLE, max_level, vec![0; size]);
+      let size =
+        LevelEncoder::max_buffer_size(Encoding::RLE, max_level, levels.len()).unwrap();
+      let mut level_encoder =
+        LevelEncoder::new(Encoding::RLE, max_level, vec![0; size]).unwrap();
       level_encoder.put(levels).expect("put() should be OK");
       let encoded_levels = level_encoder.consume().expect("consume() should be OK");
       // Actual encoded bytes (without length offset)
@@ -1077,7 +2445,8 @@ mod tests {
           num_rows: self.num_values, // also don't need this when reading data page
           def_levels_byte_len: self.def_levels_byte_len,
           rep_levels_byte_len: self.rep_levels_byte_len,
-          is_compressed: false
+          is_compressed: false,
+          statistics: None // set to None, we do not need statistics for tests
         }
       } else {
         Page::DataPage {
@@ -1085,7 +2454,8 @@ mod tests {
           num_values: self.num_values,
           encoding: self.encoding.unwrap(),
           def_level_encoding: Encoding::RLE,
-          rep_level_encoding: Encoding::RLE
+          rep_level_encoding: Encoding::RLE,
+          statistics: None // set to None, we do not need statistics for tests
         }
       }
     }
@@ -0,0 +1,3001 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains column writer API.
+
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::mem;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use basic::{Compression, Encoding, LogicalType, PageType, SortOrder, Type};
+use bloom_filter::Sbbf;
+use column::page::{CompressedPage, Page, PageWriteSpec, PageWriter};
+use compression::{
+  create_codec_with_dictionary, Codec, CompressionPool, PendingCompression
+};
+use data_type::*;
+use encodings::encoding::{get_encoder, DictEncoder, Encoder};
+use encodings::levels::LevelEncoder;
+use errors::{ParquetError, Result};
+use file::metadata::ColumnChunkMetaData;
+use file::page_index::{ColumnIndexBuilder, OffsetIndexBuilder};
+use file::properties::{WriterPropertiesPtr, WriterVersion};
+use file::statistics::{Statistics, StatisticsAccumulator};
+use logical::decimal_bound;
+use schema::types::{ColumnDescPtr, ColumnDescriptor};
+use util::bit_util::num_required_bits;
+use util::memory::{ByteBufferPtr, MemTracker, MemTrackerPtr, TrackedMemory};
+
+/// Column writer for a Parquet type.
+pub enum ColumnWriter {
+  BoolColumnWriter(ColumnWriterImpl<BoolType>),
+  Int32ColumnWriter(ColumnWriterImpl<Int32Type>),
+  Int64ColumnWriter(ColumnWriterImpl<Int64Type>),
+  Int96ColumnWriter(ColumnWriterImpl<Int96Type>),
+  FloatColumnWriter(ColumnWriterImpl<FloatType>),
+  DoubleColumnWriter(ColumnWriterImpl<DoubleType>),
+  ByteArrayColumnWriter(ColumnWriterImpl<ByteArrayType>),
+  FixedLenByteArrayColumnWriter(ColumnWriterImpl<FixedLenByteArrayType>)
+}
+
+/// Gets a specific column writer corresponding to column descriptor `descr`. The
+/// column writer will write pages into `page_writer`, using settings for the column
+/// from writer properties `props`.
+///
+/// Returns error if compression codec or encoding set for the column is not supported.
+pub fn get_column_writer(
+  descr: ColumnDescPtr,
+  props: WriterPropertiesPtr,
+  page_writer: Box<PageWriter>
+) -> Result<ColumnWriter> {
+  let mem_tracker = Rc::new(MemTracker::new());
+  get_column_writer_with_mem_tracker(descr, props, page_writer, mem_tracker)
+}
+
+/// Gets a specific column writer like `get_column_writer`, memory of encoders and
+/// buffered pages of the column writer is tracked by `mem_tracker`, which can be shared
+/// with other writers.
+pub fn get_column_writer_with_mem_tracker(
+  descr: ColumnDescPtr,
+  props: WriterPropertiesPtr,
+  page_writer: Box<PageWriter>,
+  mem_tracker: MemTrackerPtr
+) -> Result<ColumnWriter> {
+  let writer = match descr.physical_type() {
+    Type::BOOLEAN => ColumnWriter::BoolColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::INT32 => ColumnWriter::Int32ColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::INT64 => ColumnWriter::Int64ColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::INT96 => ColumnWriter::Int96ColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::FLOAT => ColumnWriter::FloatColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::DOUBLE => ColumnWriter::DoubleColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::BYTE_ARRAY => ColumnWriter::ByteArrayColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?),
+    Type::FIXED_LEN_BYTE_ARRAY => ColumnWriter::FixedLenByteArrayColumnWriter(
+      ColumnWriterImpl::new_with_mem_tracker(descr, props, page_writer, mem_tracker)?)
+  };
+  Ok(writer)
+}
+
+/// Gets a typed column writer for the specific type `T`, by "up-casting" `col_writer` of
+/// non-generic type to a generic column writer type `ColumnWriterImpl`.
+///
+/// NOTE: the caller MUST guarantee that the actual enum value for `col_writer` matches
+/// the type `T`. Otherwise, disastrous consequence could happen.
+pub fn get_typed_column_writer<T: DataType>(
+  col_writer: ColumnWriter
+) -> ColumnWriterImpl<T> {
+  match col_writer {
+    ColumnWriter::BoolColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::Int32ColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::Int64ColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::Int96ColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::FloatColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::DoubleColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::ByteArrayColumnWriter(r) => unsafe { mem::transmute(r) },
+    ColumnWriter::FixedLenByteArrayColumnWriter(r) => unsafe { mem::transmute(r) }
+  }
+}
+
+/// Same as `get_typed_column_writer`, but returns mutable reference to a typed column
+/// writer, e.g. for column writers of `BufferedRowGroupWriter`.
+///
+/// NOTE: the caller MUST guarantee that the actual enum value for `col_writer` matches
+/// the type `T`. Otherwise, disastrous consequence could happen.
+pub fn get_typed_column_writer_mut<T: DataType>(
+  col_writer: &mut ColumnWriter
+) -> &mut ColumnWriterImpl<T> {
+  match col_writer {
+    &mut ColumnWriter::BoolColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::Int32ColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::Int64ColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::Int96ColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::FloatColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::DoubleColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::ByteArrayColumnWriter(ref mut r) => unsafe { mem::transmute(r) },
+    &mut ColumnWriter::FixedLenByteArrayColumnWriter(ref mut r) => unsafe {
+      mem::transmute(r)
+    }
+  }
+}
+
+/// Finalises writes and closes column writer of any type, see
+/// [`ColumnWriterImpl::close`](`ColumnWriterImpl::close`).
+///
+/// Returns total bytes written, total rows written and column chunk metadata with
+/// offsets of pages in the file, sizes, encodings and statistics.
+pub fn close_column_writer(
+  col_writer: ColumnWriter
+) -> Result<(u64, u64, ColumnChunkMetaData)> {
+  match col_writer {
+    ColumnWriter::BoolColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int32ColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int64ColumnWriter(typed) => typed.close(),
+    ColumnWriter::Int96ColumnWriter(typed) => typed.close(),
+    ColumnWriter::FloatColumnWriter(typed) => typed.close(),
+    ColumnWriter::DoubleColumnWriter(typed) => typed.close(),
+    ColumnWriter::ByteArrayColumnWriter(typed) => typed.close(),
+    ColumnWriter::FixedLenByteArrayColumnWriter(typed) => typed.close()
+  }
+}
+
+/// Writes batch of DECIMAL values, given as unscaled `i128` values, and definition and
+/// repetition levels into a column writer of an INT32, INT64 or FIXED_LEN_BYTE_ARRAY
+/// column annotated as DECIMAL. FIXED_LEN_BYTE_ARRAY values are encoded as big-endian
+/// two's complement. Returns number of values written, see
+/// [`ColumnWriterImpl::write_batch`](`ColumnWriterImpl::write_batch`).
+///
+/// Returns error if column is not annotated as DECIMAL, or if any value does not fit
+/// into the precision or the physical type of the column.
+pub fn write_decimal_batch(
+  col_writer: &mut ColumnWriter,
+  values: &[i128],
+  def_levels: Option<&[i16]>,
+  rep_levels: Option<&[i16]>
+) -> Result<usize> {
+  match *col_writer {
+    ColumnWriter::Int32ColumnWriter(ref mut typed) => {
+      let values = decimal_values(typed.descr(), values, |value| {
+        if value >= i32::min_value() as i128 && value <= i32::max_value() as i128 {
+          Some(value as i32)
+        } else {
+          None
+        }
+      })?;
+      typed.write_batch(&values, def_levels, rep_levels)
+    },
+    ColumnWriter::Int64ColumnWriter(ref mut typed) => {
+      let values = decimal_values(typed.descr(), values, |value| {
+        if value >= i64::min_value() as i128 && value <= i64::max_value() as i128 {
+          Some(value as i64)
+        } else {
+          None
+        }
+      })?;
+      typed.write_batch(&values, def_levels, rep_levels)
+    },
+    ColumnWriter::FixedLenByteArrayColumnWriter(ref mut typed) => {
+      typed.write_decimal_batch(values, def_levels, rep_levels)
+    },
+    ColumnWriter::BoolColumnWriter(ref typed) => decimal_type_err(typed.descr()),
+    ColumnWriter::Int96ColumnWriter(ref typed) => decimal_type_err(typed.descr()),
+    ColumnWriter::FloatColumnWriter(ref typed) => decimal_type_err(typed.descr()),
+    ColumnWriter::DoubleColumnWriter(ref typed) => decimal_type_err(typed.descr()),
+    ColumnWriter::ByteArrayColumnWriter(ref typed) => decimal_type_err(typed.descr())
+  }
+}
+
+/// Converts unscaled DECIMAL values into values of the physical type of the column
+/// with `convert`, which returns `None` if value does not fit into the physical type.
+/// Returns error if column is not annotated as DECIMAL or any value does not fit into
+/// the column.
+fn decimal_values<T, F: Fn(i128) -> Option<T>>(
+  descr: &ColumnDescriptor,
+  values: &[i128],
+  convert: F
+) -> Result<Vec<T>> {
+  if descr.logical_type() != LogicalType::DECIMAL {
+    return Err(general_err!("Column {} is not annotated as DECIMAL", descr.path()));
+  }
+  let precision = descr.type_precision();
+  let bound = decimal_bound(precision);
+
+  let mut converted = Vec::with_capacity(values.len());
+  for &value in values {
+    let is_in_bound = bound.map(|b| value > -b && value < b).unwrap_or(true);
+    match convert(value) {
+      Some(v) if is_in_bound => converted.push(v),
+      _ => return Err(general_err!(
+        "Value {} does not fit into column {} of type DECIMAL({}, {})",
+        value,
+        descr.path(),
+        precision,
+        descr.type_scale()
+      ))
+    }
+  }
+  Ok(converted)
+}
+
+/// Returns error for a column that cannot store DECIMAL values.
+fn decimal_type_err(descr: &ColumnDescriptor) -> Result<usize> {
+  Err(general_err!(
+    "Cannot write DECIMAL values into column {} of type {}",
+    descr.path(),
+    descr.physical_type()
+  ))
+}
+
+/// Returns encoding for values of a column with `physical_type`, used when encoding is
+/// not set in writer properties or dictionary encoding falls back.
+///
+/// Parquet 1.0 only uses PLAIN encoding, while for Parquet 2.0 more compact encodings
+/// are selected, if supported for the type.
+fn fallback_encoding(physical_type: Type, version: WriterVersion) -> Encoding {
+  match (version, physical_type) {
+    (WriterVersion::PARQUET_2_0, Type::BOOLEAN) => Encoding::RLE,
+    (WriterVersion::PARQUET_2_0, Type::INT32) => Encoding::DELTA_BINARY_PACKED,
+    (WriterVersion::PARQUET_2_0, Type::INT64) => Encoding::DELTA_BINARY_PACKED,
+    (WriterVersion::PARQUET_2_0, Type::BYTE_ARRAY) => Encoding::DELTA_BYTE_ARRAY,
+    _ => Encoding::PLAIN
+  }
+}
+
+/// Selects encoding from `values` of the first data page of a column chunk with
+/// `physical_type`, see `WriterPropertiesBuilder::with_adaptive_encoding_enabled`.
+/// Returns `true` if dictionary encoding should be used, and encoding of values, which
+/// is the fallback encoding in case of dictionary encoding.
+fn adaptive_encoding<T: DataType>(
+  values: &[T::T],
+  physical_type: Type,
+  version: WriterVersion
+) -> (bool, Encoding) {
+  let fallback = fallback_encoding(physical_type, version);
+  // Dictionary encoding is not beneficial for booleans
+  if values.is_empty() || physical_type == Type::BOOLEAN {
+    return (false, fallback);
+  }
+
+  // Compare sizes of plain encoded values with sizes of plain encoded dictionary and
+  // bit-packed indices, where byte arrays are prefixed with their length
+  let length_size = if physical_type == Type::BYTE_ARRAY { 4 } else { 0 };
+  let mut distinct = HashSet::new();
+  let mut plain_size = 0;
+  let mut dict_size = 0;
+  for value in values {
+    let bytes = value.as_bytes();
+    plain_size += length_size + bytes.len();
+    if distinct.insert(bytes) {
+      dict_size += length_size + bytes.len();
+    }
+  }
+  let indices_size = values.len() * num_required_bits(distinct.len() as u64) / 8;
+  if dict_size + indices_size < plain_size {
+    return (true, fallback);
+  }
+
+  // Delta encodings are only used by Parquet 2.0 writers, see `fallback_encoding`
+  let is_sorted = values.windows(2).all(|w| w[0] <= w[1]) ||
+    values.windows(2).all(|w| w[0] >= w[1]);
+  let encoding = match (version, physical_type) {
+    (WriterVersion::PARQUET_2_0, Type::INT32) |
+    (WriterVersion::PARQUET_2_0, Type::INT64) if is_sorted => {
+      Encoding::DELTA_BINARY_PACKED
+    },
+    (WriterVersion::PARQUET_2_0, Type::BYTE_ARRAY) if is_sorted => {
+      Encoding::DELTA_BYTE_ARRAY
+    },
+    _ => Encoding::PLAIN
+  };
+  (false, encoding)
+}
+
+/// Data page buffered by the column writer until it can be written. Buffer of the page
+/// holds uncompressed levels of data page v2, if any, followed by compressed data.
+///
+/// Memory of the page, including data queued for compression, is tracked until the
+/// page is converted into a compressed page.
+struct BufferedPage {
+  page: Page,
+  data: PageData,
+  uncompressed_size: usize,
+  memory: TrackedMemory
+}
+
+/// Compressed data of a buffered page, which might still be compressed by the
+/// compression pool.
+enum PageData {
+  Compressed(Vec<u8>),
+  Compressing(PendingCompression)
+}
+
+impl BufferedPage {
+  fn new(
+    page: Page,
+    data: PageData,
+    uncompressed_size: usize,
+    mem_tracker: MemTrackerPtr
+  ) -> Self {
+    // Data queued for compression is uncompressed, including levels of data page v2
+    let size = match data {
+      PageData::Compressed(ref data) => page.buffer().len() + data.capacity(),
+      PageData::Compressing(_) => uncompressed_size
+    };
+    let mut memory = TrackedMemory::new().with_mem_tracker(mem_tracker);
+    memory.set_size(size);
+    Self {
+      page: page,
+      data: data,
+      uncompressed_size: uncompressed_size,
+      memory: memory
+    }
+  }
+
+  /// Returns `true` if data is compressed and page can be written without blocking.
+  fn is_compressed(&mut self) -> bool {
+    match self.data {
+      PageData::Compressed(_) => true,
+      PageData::Compressing(ref mut pending) => pending.is_ready()
+    }
+  }
+
+  /// Returns page with compressed data, blocks until data is compressed.
+  fn into_compressed(self) -> Result<CompressedPage> {
+    let data = match self.data {
+      PageData::Compressed(data) => data,
+      PageData::Compressing(pending) => pending.wait()?
+    };
+    let mut page = self.page;
+    match page {
+      Page::DataPage { ref mut buf, .. } |
+        Page::DataPageV2 { ref mut buf, .. } |
+        Page::DictionaryPage { ref mut buf, .. } => {
+        let buffer = if buf.len() == 0 {
+          data
+        } else {
+          let mut buffer = Vec::with_capacity(buf.len() + data.len());
+          buffer.extend_from_slice(buf.data());
+          buffer.extend_from_slice(&data);
+          buffer
+        };
+        *buf = ByteBufferPtr::new(buffer);
+      }
+    }
+    Ok(CompressedPage::new(page, self.uncompressed_size))
+  }
+}
+
+/// Typed column writer for a primitive column.
+///
+/// Values are dictionary encoded if dictionary is enabled for the column (except for
+/// BOOLEAN columns), writer falls back to the encoding set in writer properties, or
+/// the default encoding of the writer version, once the dictionary grows over the
+/// limit. Data pages are buffered in memory while dictionary is in use, because
+/// dictionary page must be written first.
+///
+/// If statistics are enabled for the column, min/max values and number of nulls are
+/// accumulated for each data page and for the whole column chunk.
+///
+/// If page index is enabled, locations of data pages are recorded in the offset index,
+/// and page statistics in the column index. Both are returned with column chunk
+/// metadata and written by the file writer.
+///
+/// If compression threads are set in writer properties, data pages are compressed by
+/// the shared compression pool while the writer encodes next pages, and are written
+/// in order once compressed.
+///
+/// If adaptive encoding is enabled in writer properties, values of the first data page
+/// are buffered until the encoding is selected from them, see `select_encoding`.
+///
+/// If audit is enabled in writer properties, every data page and the column chunk
+/// metadata are cross-checked before they are written, see `audit_data_page`,
+/// `audit_page_spec` and `audit_column_metadata`.
+///
+/// Memory of encoders, including the dictionary, and of buffered data pages, including
+/// pages queued for compression, is tracked by the memory tracker of the column writer,
+/// see `new_with_mem_tracker`.
+pub struct ColumnWriterImpl<T: DataType> {
+  // Column writer properties
+  descr: ColumnDescPtr,
+  props: WriterPropertiesPtr,
+  page_writer: Box<PageWriter>,
+  mem_tracker: MemTrackerPtr,
+  codec: Compression,
+  compressor: Option<Box<Codec>>,
+  compression_dictionary: Option<Arc<Vec<u8>>>,
+  compression_pool: Option<Arc<CompressionPool>>,
+  data_page_size: usize,
+  data_page_row_count_limit: usize,
+  dictionary_page_size_limit: usize,
+  write_batch_size: usize,
+  statistics_enabled: bool,
+  dictionary_page_compression_enabled: bool,
+  audit_enabled: bool,
+
+  // Bloom filter of the column chunk, if enabled
+  bloom_filter: Option<Sbbf>,
+
+  // Page index of the column chunk, if enabled; column index is discarded if min/max
+  // values are not available for a page with values
+  column_index_builder: Option<ColumnIndexBuilder>,
+  offset_index_builder: Option<OffsetIndexBuilder>,
+  last_page_min_max: Option<(T::T, T::T)>,
+
+  // Encoders; dictionary encoder is set as long as dictionary encoding is used
+  dict_encoder: Option<DictEncoder<T>>,
+  dict_page_encoding: Encoding,
+  dict_indices_encoding: Encoding,
+  encoder: Box<Encoder<T>>,
+  // Values of the first data page, buffered as long as adaptive encoding selects
+  // encoding of the column chunk
+  sample: Option<Vec<T::T>>,
+
+  // Metrics per page
+  num_buffered_values: u32,
+  num_buffered_rows: u32,
+  // Raw size of values buffered for adaptive encoding
+  buffered_values_size: usize,
+  page_statistics: StatisticsAccumulator<T>,
+
+  // Metrics per column writer
+  total_bytes_written: u64,
+  total_rows_written: u64,
+  total_uncompressed_size: u64,
+  total_compressed_size: u64,
+  total_num_values: u64,
+  total_num_levels: u64,
+  dictionary_page_offset: Option<u64>,
+  data_page_offset: Option<u64>,
+  next_page_offset: Option<u64>,
+  encodings: Vec<Encoding>,
+  chunk_statistics: StatisticsAccumulator<T>,
+
+  // Reused buffers
+  def_levels_sink: Vec<i16>,
+  rep_levels_sink: Vec<i16>,
+  data_pages: VecDeque<BufferedPage>
+}
+
+impl<T: DataType> ColumnWriterImpl<T> where T: 'static {
+  /// Creates new column writer based on column descriptor, writer properties and page
+  /// writer.
+  ///
+  /// Returns error if compression codec for the column is not supported, if dictionary
+  /// encoding is set as the column encoding in writer properties, or if the encoding
+  /// is not supported for the physical type of the column.
+  pub fn new(
+    descr: ColumnDescPtr,
+    props: WriterPropertiesPtr,
+    page_writer: Box<PageWriter>
+  ) -> Result<Self> {
+    Self::new_with_mem_tracker(descr, props, page_writer, Rc::new(MemTracker::new()))
+  }
+
+  /// Creates new column writer like `new`, memory of encoders and buffered pages is
+  /// tracked by `mem_tracker`, e.g. a memory tracker shared by all column writers of a
+  /// file writer. Memory is released once pages are written and the column writer is
+  /// closed.
+  pub fn new_with_mem_tracker(
+    descr: ColumnDescPtr,
+    props: WriterPropertiesPtr,
+    page_writer: Box<PageWriter>,
+    mem_tracker: MemTrackerPtr
+  ) -> Result<Self> {
+    let codec = props.compression(descr.path());
+    let compression_dictionary = props.compression_dictionary(descr.path());
+    let compressor = create_codec_with_dictionary(codec, compression_dictionary.clone())?;
+    // Worker threads are only used if pages are compressed
+    let compression_pool = if compressor.is_some() {
+      props.compression_pool()?
+    } else {
+      None
+    };
+    let sample = if props.adaptive_encoding_enabled(descr.path()) {
+      Some(Vec::new())
+    } else {
+      None
+    };
+
+    // Dictionary encoding is not beneficial for booleans, with adaptive encoding
+    // dictionary encoder is set once encoding is selected
+    let dict_encoder = if props.dictionary_enabled(descr.path()) &&
+        descr.physical_type() != Type::BOOLEAN && sample.is_none() {
+      Some(DictEncoder::new(descr.clone(), mem_tracker.clone()))
+    } else {
+      None
+    };
+
+    let encoding = props.encoding(descr.path()).unwrap_or_else(|| {
+      fallback_encoding(descr.physical_type(), props.writer_version())
+    });
+    if encoding == Encoding::PLAIN_DICTIONARY || encoding == Encoding::RLE_DICTIONARY {
+      return Err(general_err!(
+        "Encoding {} cannot be set for column {}, dictionary is enabled separately",
+        encoding,
+        descr.path()
+      ));
+    }
+    let encoder = get_encoder(descr.clone(), encoding, mem_tracker.clone())?;
+
+    // Parquet 2.0 deprecates PLAIN_DICTIONARY in favour of PLAIN encoded dictionary
+    // page and RLE_DICTIONARY encoded data pages
+    let (dict_page_encoding, dict_indices_encoding) = match props.writer_version() {
+      WriterVersion::PARQUET_1_0 => {
+        (Encoding::PLAIN_DICTIONARY, Encoding::PLAIN_DICTIONARY)
+      },
+      WriterVersion::PARQUET_2_0 => (Encoding::PLAIN, Encoding::RLE_DICTIONARY)
+    };
+    let sort_order = T::get_sort_order(descr.logical_type());
+
+    // Bloom filters are not defined for booleans
+    let bloom_filter = if props.bloom_filter_enabled(descr.path()) &&
+        descr.physical_type() != Type::BOOLEAN {
+      Some(Sbbf::new_with_ndv_fpp(
+        props.bloom_filter_ndv(descr.path()),
+        props.bloom_filter_fpp(descr.path())
+      )?)
+    } else {
+      None
+    };
+
+    // Column index requires page statistics with defined sort order
+    let offset_index_builder = if props.page_index_enabled() {
+      Some(OffsetIndexBuilder::new())
+    } else {
+      None
+    };
+    let column_index_builder = if props.page_index_enabled() &&
+        props.statistics_enabled(descr.path()) && sort_order != SortOrder::UNDEFINED {
+      Some(ColumnIndexBuilder::new())
+    } else {
+      None
+    };
+    let max_statistics_size = props.max_statistics_size(descr.path());
+    let logical_type = descr.logical_type();
+
+    Ok(Self {
+      descr: descr,
+      codec: codec,
+      compressor: compressor,
+      compression_dictionary: compression_dictionary,
+      compression_pool: compression_pool,
+      data_page_size: props.data_page_size(),
+      data_page_row_count_limit: props.data_page_row_count_limit(),
+      dictionary_page_size_limit: props.dictionary_page_size_limit(),
+      write_batch_size: props.write_batch_size(),
+      statistics_enabled: props.statistics_enabled(descr.path()),
+      dictionary_page_compression_enabled: props
+        .dictionary_page_compression_enabled(descr.path()),
+      audit_enabled: props.audit_enabled(),
+      bloom_filter: bloom_filter,
+      column_index_builder: column_index_builder,
+      offset_index_builder: offset_index_builder,
+      last_page_min_max: None,
+      props: props,
+      page_writer: page_writer,
+      mem_tracker: mem_tracker,
+      dict_encoder: dict_encoder,
+      dict_page_encoding: dict_page_encoding,
+      dict_indices_encoding: dict_indices_encoding,
+      encoder: encoder,
+      sample: sample,
+      num_buffered_values: 0,
+      num_buffered_rows: 0,
+      buffered_values_size: 0,
+      page_statistics: StatisticsAccumulator::new(sort_order)
+        .with_max_size(max_statistics_size)
+        .with_logical_type(logical_type),
+      total_bytes_written: 0,
+      total_rows_written: 0,
+      total_uncompressed_size: 0,
+      total_compressed_size: 0,
+      total_num_values: 0,
+      total_num_levels: 0,
+      dictionary_page_offset: None,
+      data_page_offset: None,
+      next_page_offset: None,
+      encodings: Vec::new(),
+      chunk_statistics: StatisticsAccumulator::new(sort_order)
+        .with_max_size(max_statistics_size)
+        .with_logical_type(logical_type),
+      def_levels_sink: Vec::new(),
+      rep_levels_sink: Vec::new(),
+      data_pages: VecDeque::new()
+    })
+  }
+
+  /// Writes batch of values, definition levels and repetition levels.
+  /// Returns number of values processed (written).
+  ///
+  /// If definition and repetition levels are provided, we write fully those levels and
+  /// select how many values to write (this number will be returned), since number of
+  /// actual written values may be smaller than provided values.
+  ///
+  /// If only values are provided, then all values are written and the length
+  /// of the values buffer is returned.
+  ///
+  /// Definition and/or repetition levels can be omitted, if values are
+  /// non-nullable and/or non-repeated.
+  ///
+  /// Provided levels are validated before anything is written: levels must not exceed
+  /// max levels of the column, column chunk must start with a new record, and each
+  /// repetition level must refer to a repeated field defined by the previous value.
+  pub fn write_batch(
+    &mut self,
+    values: &[T::T],
+    def_levels: Option<&[i16]>,
+    rep_levels: Option<&[i16]>
+  ) -> Result<usize> {
+    // We check for DataPage limits only after we have inserted the values. If a user
+    // writes a large number of values, the DataPage size can be well above the limit.
+    //
+    // The purpose of this chunking is to bound this. Even if a user writes large number
+    // of values, the chunking will ensure that we add data page at a reasonable pagesize
+    // limit.
+
+    // Number of levels, i.e. values including nulls, to process.
+    let num_levels = match def_levels {
+      Some(levels) => levels.len(),
+      None => values.len()
+    };
+    if let Some(levels) = rep_levels {
+      if levels.len() != num_levels {
+        return Err(general_err!(
+          "Inconsistent length of definition and repetition levels: {} != {}",
+          num_levels,
+          levels.len()
+        ));
+      }
+    }
+
+    self.validate_levels(def_levels, rep_levels)?;
+
+    let mut values_offset = 0;
+    let mut levels_offset = 0;
+
+    while levels_offset < num_levels {
+      // Mini batch never contains more levels than rows left for the current page, so
+      // each level adds at most one row and the page row count limit is never exceeded
+      let rows_left = self.data_page_row_count_limit
+        .saturating_sub(self.num_buffered_rows as usize);
+      let batch_size = ::std::cmp::min(self.write_batch_size, num_levels - levels_offset);
+      let batch_size = ::std::cmp::min(batch_size, ::std::cmp::max(rows_left, 1));
+      let levels_range = levels_offset..levels_offset + batch_size;
+      values_offset += self.write_mini_batch(
+        &values[values_offset..],
+        batch_size,
+        def_levels.map(|levels| &levels[levels_range.clone()]),
+        rep_levels.map(|levels| &levels[levels_range.clone()])
+      )?;
+      levels_offset += batch_size;
+    }
+
+    Ok(values_offset)
+  }
+
+  /// Writes values of a non-repeated column produced by an iterator, so that values do
+  /// not need to be collected first. Values are buffered and written in batches of
+  /// `write_batch_size` values with `write_batch`, all values are non-null.
+  /// Returns number of values written.
+  ///
+  /// Returns error if the column is repeated, in which case nothing is written.
+  pub fn write_iter<I>(&mut self, values: I) -> Result<usize>
+      where I: IntoIterator<Item = T::T> {
+    self.check_iter_column(false)?;
+    let max_def_level = self.descr.max_def_level();
+    let batch_size = ::std::cmp::max(self.write_batch_size, 1);
+    let def_levels = vec![max_def_level; if max_def_level > 0 { batch_size } else { 0 }];
+
+    let mut values = values.into_iter();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut values_written = 0;
+    loop {
+      batch.clear();
+      batch.extend(values.by_ref().take(batch_size));
+      if batch.is_empty() {
+        break;
+      }
+      let levels =
+        if max_def_level > 0 { Some(&def_levels[..batch.len()]) } else { None };
+      values_written += self.write_batch(&batch, levels, None)?;
+    }
+    Ok(values_written)
+  }
+
+  /// Writes optional values of a non-repeated column produced by an iterator, where
+  /// `None` is written as null, i.e. with definition level one less than the maximum
+  /// definition level of the column. Values are buffered and written in batches of
+  /// `write_batch_size` values with `write_batch`.
+  /// Returns number of non-null values written.
+  ///
+  /// Returns error if the column is repeated or required, in which case nothing is
+  /// written.
+  pub fn write_optional_iter<I>(&mut self, values: I) -> Result<usize>
+      where I: IntoIterator<Item = Option<T::T>> {
+    self.check_iter_column(true)?;
+    let max_def_level = self.descr.max_def_level();
+    let batch_size = ::std::cmp::max(self.write_batch_size, 1);
+
+    let mut values = values.into_iter();
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut def_levels = Vec::with_capacity(batch_size);
+    let mut values_written = 0;
+    loop {
+      batch.clear();
+      def_levels.clear();
+      for value in values.by_ref().take(batch_size) {
+        match value {
+          Some(value) => {
+            batch.push(value);
+            def_levels.push(max_def_level);
+          },
+          None => def_levels.push(max_def_level - 1)
+        }
+      }
+      if def_levels.is_empty() {
+        break;
+      }
+      values_written += self.write_batch(&batch, Some(&def_levels), None)?;
+    }
+    Ok(values_written)
+  }
+
+  /// Checks that values of the column can be written from an iterator, i.e. the column
+  /// is not repeated, and is not required if values are `optional`.
+  fn check_iter_column(&self, optional: bool) -> Result<()> {
+    if self.descr.max_rep_level() > 0 {
+      return Err(general_err!(
+        "Cannot write values of repeated column {} from an iterator",
+        self.descr.path()
+      ));
+    }
+    if optional && self.descr.max_def_level() == 0 {
+      return Err(general_err!(
+        "Cannot write optional values into required column {}",
+        self.descr.path()
+      ));
+    }
+    Ok(())
+  }
+
+  /// Returns descriptor of the column written by this column writer.
+  pub fn descr(&self) -> &ColumnDescriptor {
+    &self.descr
+  }
+
+  /// Returns total number of bytes written by this column writer so far.
+  /// This value is also returned when column writer is closed.
+  pub fn get_total_bytes_written(&self) -> u64 {
+    self.total_bytes_written
+  }
+
+  /// Returns total number of rows written by this column writer so far.
+  /// This value is also returned when column writer is closed.
+  pub fn get_total_rows_written(&self) -> u64 {
+    self.total_rows_written
+  }
+
+  /// Finalises writes and closes the column writer.
+  /// Returns total bytes written, total rows written and column chunk metadata.
+  pub fn close(mut self) -> Result<(u64, u64, ColumnChunkMetaData)> {
+    self.select_encoding()?;
+    // Empty dictionary is not written, since no values of all-null or empty column
+    // chunks are dictionary encoded
+    let has_dict_entries = self.dict_encoder.as_ref().map(|e| e.num_entries() > 0);
+    match has_dict_entries {
+      Some(true) => self.write_dictionary_page()?,
+      Some(false) => self.dict_encoder = None,
+      None => {}
+    }
+    // Empty column chunk consists of a single data page without values, so that
+    // offsets of the column chunk point to its location in the file
+    if self.data_page_offset.is_none() && self.data_pages.is_empty() {
+      self.add_data_page()?;
+    }
+    self.flush_data_pages()?;
+    let bloom_filter_offset = match self.bloom_filter {
+      Some(ref filter) => {
+        let offset = self.page_writer.write_bloom_filter(filter)?;
+        self.total_bytes_written += filter.num_bytes() as u64;
+        Some(offset as i64)
+      },
+      None => None
+    };
+    let metadata = self.make_column_metadata(bloom_filter_offset)?;
+    self.audit_column_metadata(&metadata)?;
+    self.page_writer.write_metadata(&metadata)?;
+    self.page_writer.close()?;
+
+    Ok((self.total_bytes_written, self.total_rows_written, metadata))
+  }
+
+  /// Checks that definition and repetition levels are valid for this column.
+  ///
+  /// Returns error if:
+  /// - any level is negative or greater than the max level of the column,
+  /// - the first value of the column chunk does not start a new record, i.e. its
+  /// repetition level is not 0,
+  /// - a value continues a repeated field (repetition level `r > 0`), but the previous
+  /// value has definition level less than `r`, so the repeated field at that level
+  /// could not have been defined.
+  fn validate_levels(
+    &self,
+    def_levels: Option<&[i16]>,
+    rep_levels: Option<&[i16]>
+  ) -> Result<()> {
+    let max_def_level = self.descr.max_def_level();
+    let max_rep_level = self.descr.max_rep_level();
+
+    if let Some(levels) = def_levels {
+      if let Some(pos) = levels.iter().position(|&l| l < 0 || l > max_def_level) {
+        return Err(general_err!(
+          "Invalid definition level {} at position {} for column {}, \
+           expected value between 0 and {}",
+          levels[pos],
+          pos,
+          self.descr.path(),
+          max_def_level
+        ));
+      }
+    }
+
+    if let Some(levels) = rep_levels {
+      if let Some(pos) = levels.iter().position(|&l| l < 0 || l > max_rep_level) {
+        return Err(general_err!(
+          "Invalid repetition level {} at position {} for column {}, \
+           expected value between 0 and {}",
+          levels[pos],
+          pos,
+          self.descr.path(),
+          max_rep_level
+        ));
+      }
+
+      if max_rep_level > 0 {
+        let is_chunk_start = self.total_rows_written == 0 && self.num_buffered_rows == 0;
+        if is_chunk_start && levels.len() > 0 && levels[0] != 0 {
+          return Err(general_err!(
+            "Invalid repetition level {} at position 0 for column {}, \
+             column chunk must start with a new record (repetition level 0)",
+            levels[0],
+            self.descr.path()
+          ));
+        }
+
+        if let Some(def_levels) = def_levels {
+          for i in 1..levels.len() {
+            if levels[i] > def_levels[i - 1] {
+              return Err(general_err!(
+                "Invalid repetition level {} at position {} for column {}, \
+                 previous definition level {} does not define a repeated field at \
+                 this level",
+                levels[i],
+                i,
+                self.descr.path(),
+                def_levels[i - 1]
+              ));
+            }
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes mini batch of `num_levels` values (including nulls), where `values` might
+  /// contain more values than needed.
+  /// Returns number of non-null values written.
+  fn write_mini_batch(
+    &mut self,
+    values: &[T::T],
+    num_levels: usize,
+    def_levels: Option<&[i16]>,
+    rep_levels: Option<&[i16]>
+  ) -> Result<usize> {
+    let mut values_to_write = 0;
+
+    // Process definition levels and determine how many values to write.
+    if self.descr.max_def_level() > 0 {
+      let levels = match def_levels {
+        Some(levels) => levels,
+        None => return Err(general_err!(
+          "Definition levels are required, because max definition level = {}",
+          self.descr.max_def_level()
+        ))
+      };
+      for &level in levels {
+        values_to_write += (level == self.descr.max_def_level()) as usize;
+      }
+      if self.statistics_enabled {
+        self.page_statistics.update_null_count((levels.len() - values_to_write) as u64);
+      }
+      self.def_levels_sink.extend_from_slice(levels);
+    } else {
+      values_to_write = num_levels;
+    }
+
+    // Process repetition levels and determine how many rows we are about to process.
+    if self.descr.max_rep_level() > 0 {
+      let levels = match rep_levels {
+        Some(levels) => levels,
+        None => return Err(general_err!(
+          "Repetition levels are required, because max repetition level = {}",
+          self.descr.max_rep_level()
+        ))
+      };
+      // Count the occasions where we start a new row
+      for &level in levels {
+        self.num_buffered_rows += (level == 0) as u32;
+      }
+      self.rep_levels_sink.extend_from_slice(levels);
+    } else {
+      // Each value is exactly one row, including nulls
+      self.num_buffered_rows += num_levels as u32;
+    }
+
+    // Check that we have enough values to write.
+    if values.len() < values_to_write {
+      return Err(general_err!(
+        "Expected to write {} values, but have only {}",
+        values_to_write,
+        values.len()
+      ));
+    }
+
+    self.write_values(&values[0..values_to_write])?;
+
+    self.num_buffered_values += num_levels as u32;
+    self.total_num_levels += num_levels as u64;
+
+    if self.should_add_data_page() {
+      self.add_data_page()?;
+    }
+
+    if self.should_dict_fallback() {
+      self.dict_fallback()?;
+    }
+
+    Ok(values_to_write)
+  }
+
+  /// Encodes values with the current encoder.
+  #[inline]
+  fn write_values(&mut self, values: &[T::T]) -> Result<()> {
+    if self.statistics_enabled {
+      self.page_statistics.update(values);
+    }
+    if let Some(ref mut filter) = self.bloom_filter {
+      for value in values {
+        filter.insert(value);
+      }
+    }
+    if let Some(ref mut sample) = self.sample {
+      for value in values {
+        self.buffered_values_size += value.as_bytes().len();
+      }
+      sample.extend_from_slice(values);
+      return Ok(());
+    }
+    match self.dict_encoder {
+      Some(ref mut encoder) => encoder.put(values),
+      None => self.encoder.put(values)
+    }
+  }
+
+  /// Selects encoding from values of the first data page, if adaptive encoding is
+  /// enabled and encoding has not been selected yet, and encodes these values.
+  fn select_encoding(&mut self) -> Result<()> {
+    let sample = match self.sample.take() {
+      Some(sample) => sample,
+      None => return Ok(())
+    };
+    let (use_dictionary, encoding) = adaptive_encoding::<T>(
+      &sample,
+      self.descr.physical_type(),
+      self.props.writer_version()
+    );
+    let mem_tracker = self.mem_tracker.clone();
+    if use_dictionary {
+      self.dict_encoder = Some(DictEncoder::new(self.descr.clone(), mem_tracker.clone()));
+    }
+    self.encoder = get_encoder(self.descr.clone(), encoding, mem_tracker)?;
+    match self.dict_encoder {
+      Some(ref mut encoder) => encoder.put(&sample),
+      None => self.encoder.put(&sample)
+    }
+  }
+
+  /// Returns true if there is enough data for a data page, false otherwise.
+  #[inline]
+  fn should_add_data_page(&self) -> bool {
+    self.estimated_values_size() >= self.data_page_size ||
+      self.num_buffered_rows as usize >= self.data_page_row_count_limit
+  }
+
+  /// Returns estimated size of the encoded values of the current data page. Raw size of
+  /// values is used while values are buffered for adaptive encoding.
+  #[inline]
+  fn estimated_values_size(&self) -> usize {
+    if self.sample.is_some() {
+      return self.buffered_values_size;
+    }
+    match self.dict_encoder {
+      Some(ref encoder) => encoder.estimated_data_encoded_size(),
+      None => self.encoder.estimated_data_encoded_size()
+    }
+  }
+
+  /// Returns true if we need to fall back to non-dictionary encoding.
+  #[inline]
+  fn should_dict_fallback(&self) -> bool {
+    match self.dict_encoder {
+      Some(ref encoder) => {
+        encoder.dict_encoded_size() as usize >= self.dictionary_page_size_limit
+      },
+      None => false
+    }
+  }
+
+  /// Performs dictionary fallback.
+  /// Prepares and writes dictionary and all data pages into page writer.
+  fn dict_fallback(&mut self) -> Result<()> {
+    // At this point we know that we need to fall back.
+    self.write_dictionary_page()?;
+    self.flush_data_pages()?;
+    self.dict_encoder = None;
+    Ok(())
+  }
+
+  /// Adds data page.
+  /// Data page is either buffered in case of dictionary encoding or written directly.
+  ///
+  /// Pages without non-null values are encoded with the fallback encoding while the
+  /// dictionary is empty, so that all-null column chunks do not need a dictionary page.
+  fn add_data_page(&mut self) -> Result<()> {
+    self.select_encoding()?;
+    let (value_bytes, encoding) = match self.dict_encoder {
+      Some(ref mut encoder) if encoder.num_entries() > 0 => {
+        (encoder.write_indices()?, self.dict_indices_encoding)
+      },
+      _ => (self.encoder.flush_buffer()?, self.encoder.encoding())
+    };
+
+    self.audit_data_page()?;
+    self.update_page_index();
+
+    let buffered_page = match self.props.writer_version() {
+      WriterVersion::PARQUET_1_0 => {
+        let mut buffer = vec![];
+
+        if self.descr.max_rep_level() > 0 {
+          buffer.extend_from_slice(&self.encode_levels(
+            self.descr.max_rep_level(), &self.rep_levels_sink[..])?);
+        }
+
+        if self.descr.max_def_level() > 0 {
+          buffer.extend_from_slice(&self.encode_levels(
+            self.descr.max_def_level(), &self.def_levels_sink[..])?);
+        }
+
+        buffer.extend_from_slice(value_bytes.data());
+
+        let uncompressed_size = buffer.len();
+        let data = self.compress_page_data(buffer)?;
+
+        let data_page = Page::DataPage {
+          buf: ByteBufferPtr::new(vec![]),
+          num_values: self.num_buffered_values,
+          encoding: encoding,
+          def_level_encoding: Encoding::RLE,
+          rep_level_encoding: Encoding::RLE,
+          statistics: self.flush_page_statistics()
+        };
+        BufferedPage::new(data_page, data, uncompressed_size, self.mem_tracker.clone())
+      },
+      WriterVersion::PARQUET_2_0 => {
+        // Levels are stored uncompressed in front of values, only values are
+        // compressed
+        let mut buffer = vec![];
+
+        let rep_levels = if self.descr.max_rep_level() > 0 {
+          self.encode_levels_v2(self.descr.max_rep_level(), &self.rep_levels_sink[..])?
+        } else {
+          vec![]
+        };
+        buffer.extend_from_slice(&rep_levels);
+
+        let def_levels = if self.descr.max_def_level() > 0 {
+          self.encode_levels_v2(self.descr.max_def_level(), &self.def_levels_sink[..])?
+        } else {
+          vec![]
+        };
+        buffer.extend_from_slice(&def_levels);
+
+        let uncompressed_size = buffer.len() + value_bytes.len();
+        let is_compressed = self.compressor.is_some();
+        let data = self.compress_page_data(value_bytes.data().to_vec())?;
+
+        let max_def_level = self.descr.max_def_level();
+        let num_nulls = if max_def_level > 0 {
+          self.def_levels_sink.iter().filter(|&&level| level < max_def_level).count()
+        } else {
+          0
+        };
+
+        let data_page = Page::DataPageV2 {
+          buf: ByteBufferPtr::new(buffer),
+          num_values: self.num_buffered_values,
+          encoding: encoding,
+          num_nulls: num_nulls as u32,
+          num_rows: self.num_buffered_rows,
+          def_levels_byte_len: def_levels.len() as u32,
+          rep_levels_byte_len: rep_levels.len() as u32,
+          is_compressed: is_compressed,
+          statistics: self.flush_page_statistics()
+        };
+        BufferedPage::new(data_page, data, uncompressed_size, self.mem_tracker.clone())
+      }
+    };
+
+    // Data pages are buffered until dictionary page is written in case of dictionary
+    // encoding, otherwise they are written once compressed.
+    self.data_pages.push_back(buffered_page);
+    if self.dict_encoder.is_none() {
+      self.write_compressed_data_pages()?;
+    }
+
+    // Update total number of rows.
+    self.total_rows_written += self.num_buffered_rows as u64;
+
+    // Reset state.
+    self.rep_levels_sink.clear();
+    self.def_levels_sink.clear();
+    self.num_buffered_values = 0;
+    self.num_buffered_rows = 0;
+    self.buffered_values_size = 0;
+
+    Ok(())
+  }
+
+  /// Finalises any outstanding data pages and flushes buffered data pages from
+  /// dictionary encoding into underlying sink.
+  #[inline]
+  fn flush_data_pages(&mut self) -> Result<()> {
+    // Write all outstanding data to a new page.
+    if self.num_buffered_values > 0 {
+      self.add_data_page()?;
+    }
+
+    while let Some(page) = self.data_pages.pop_front() {
+      self.write_data_page(page.into_compressed()?)?;
+    }
+
+    Ok(())
+  }
+
+  /// Writes buffered data pages in order, as long as their compression has completed.
+  /// Waits for the oldest page while more pages are in flight than the compression
+  /// pool queues, so that memory used by buffered pages stays bounded.
+  fn write_compressed_data_pages(&mut self) -> Result<()> {
+    let max_pending_pages = match self.compression_pool {
+      Some(ref pool) => pool.queue_size(),
+      None => 0
+    };
+    loop {
+      let num_pages = self.data_pages.len();
+      let should_write = match self.data_pages.front_mut() {
+        Some(page) => num_pages > max_pending_pages || page.is_compressed(),
+        None => false
+      };
+      if !should_write {
+        return Ok(());
+      }
+      if let Some(page) = self.data_pages.pop_front() {
+        self.write_data_page(page.into_compressed()?)?;
+      }
+    }
+  }
+
+  /// Compresses data of a data page with the column codec, either on this thread or
+  /// in the compression pool, if enabled. Data is kept as is if compression is
+  /// disabled.
+  fn compress_page_data(&mut self, data: Vec<u8>) -> Result<PageData> {
+    match (self.compressor.as_mut(), self.compression_pool.as_ref()) {
+      (Some(_), Some(pool)) => {
+        let dictionary = self.compression_dictionary.clone();
+        Ok(PageData::Compressing(pool.submit(self.codec, dictionary, data)?))
+      },
+      (Some(compressor), None) => Ok(PageData::Compressed(compressor.compress(&data)?)),
+      (None, _) => Ok(PageData::Compressed(data))
+    }
+  }
+
+  /// Records number of rows and statistics of the current data page in the page index,
+  /// if enabled. Must be called before page statistics are flushed.
+  fn update_page_index(&mut self) {
+    if let Some(ref mut builder) = self.offset_index_builder {
+      builder.append_row_count(self.num_buffered_rows as i64);
+    }
+
+    let mut is_valid = true;
+    if let Some(ref mut builder) = self.column_index_builder {
+      let stats = &self.page_statistics;
+      let null_count = stats.null_count();
+      match (stats.min(), stats.max(), stats.min_max_bytes()) {
+        (Some(min), Some(max), Some((min_bytes, max_bytes))) => {
+          if let Some((ref last_min, ref last_max)) = self.last_page_min_max {
+            builder.update_boundary_order(
+              stats.compare(min, last_min),
+              stats.compare(max, last_max)
+            );
+          }
+          builder.append(&min_bytes, &max_bytes, null_count as i64);
+          self.last_page_min_max = Some((min.clone(), max.clone()));
+        },
+        _ if null_count == self.num_buffered_values as u64 => {
+          builder.append_null_page(null_count as i64);
+        },
+        // Page has values, but no min/max, e.g. all values are NaN
+        _ => is_valid = false
+      }
+    }
+    if !is_valid {
+      self.column_index_builder = None;
+    }
+  }
+
+  /// Returns statistics of the current data page, if enabled, and merges them into
+  /// column chunk statistics.
+  #[inline]
+  fn flush_page_statistics(&mut self) -> Option<Statistics> {
+    if !self.statistics_enabled {
+      return None;
+    }
+    let statistics = self.page_statistics.statistics();
+    self.chunk_statistics.merge(&self.page_statistics);
+    self.page_statistics.reset();
+    Some(statistics)
+  }
+
+  /// Assembles and writes column chunk metadata, including page index, if enabled.
+  /// Bloom filter is written at `bloom_filter_offset`, if enabled.
+  fn make_column_metadata(
+    &mut self,
+    bloom_filter_offset: Option<i64>
+  ) -> Result<ColumnChunkMetaData> {
+    let total_compressed_size = self.total_compressed_size as i64;
+    let total_uncompressed_size = self.total_uncompressed_size as i64;
+    let num_values = self.total_num_values as i64;
+    let dict_page_offset = self.dictionary_page_offset.map(|v| v as i64);
+    // If data page offset is not set, then no pages have been written
+    let data_page_offset = self.data_page_offset.unwrap_or(0) as i64;
+    let statistics = if self.statistics_enabled {
+      Some(self.chunk_statistics.statistics())
+    } else {
+      None
+    };
+
+    let bloom_filter_length = self.bloom_filter.as_ref().map(|f| f.num_bytes() as i32);
+
+    let column_index = self.column_index_builder.take().and_then(|b| b.build());
+    let offset_index = match self.offset_index_builder.take() {
+      Some(builder) => Some(builder.build()?),
+      None => None
+    };
+
+    // Metadata is written right after the column chunk and its Bloom filter
+    let file_offset = match dict_page_offset {
+      Some(dict_offset) => dict_offset + total_compressed_size,
+      None => data_page_offset + total_compressed_size
+    } + bloom_filter_length.unwrap_or(0) as i64;
+
+    ColumnChunkMetaData::builder(self.descr.clone())
+      .with_compression(self.codec)
+      .with_encodings(self.encodings.clone())
+      .with_file_offset(file_offset)
+      .with_total_compressed_size(total_compressed_size)
+      .with_total_uncompressed_size(total_uncompressed_size)
+      .with_num_values(num_values)
+      .with_data_page_offset(data_page_offset)
+      .with_dictionary_page_offset(dict_page_offset)
+      .with_statistics(statistics)
+      .with_bloom_filter_offset(bloom_filter_offset)
+      .with_bloom_filter_length(bloom_filter_length)
+      .with_column_index(column_index)
+      .with_offset_index(offset_index)
+      .with_checksum(self.page_writer.chunk_checksum())
+      .build()
+  }
+
+  /// Encodes definition or repetition levels for data page v1.
+  #[inline]
+  fn encode_levels(&self, max_level: i16, levels: &[i16]) -> Result<Vec<u8>> {
+    let size = LevelEncoder::max_buffer_size(Encoding::RLE, max_level, levels.len())?;
+    let mut encoder = LevelEncoder::new(Encoding::RLE, max_level, vec![0; size])?;
+    encoder.put(levels)?;
+    encoder.consume()
+  }
+
+  /// Encodes definition or repetition levels for data page v2.
+  #[inline]
+  fn encode_levels_v2(&self, max_level: i16, levels: &[i16]) -> Result<Vec<u8>> {
+    let size = LevelEncoder::max_buffer_size(Encoding::RLE, max_level, levels.len())?;
+    let mut encoder = LevelEncoder::v2(max_level, vec![0; size]);
+    encoder.put(levels)?;
+    encoder.consume()
+  }
+
+  /// Writes compressed data page into underlying sink and updates global metrics.
+  #[inline]
+  fn write_data_page(&mut self, page: CompressedPage) -> Result<()> {
+    let encoding = page.encoding();
+    let page_spec = self.page_writer.write_page(page)?;
+    self.audit_page_spec(&page_spec)?;
+    if let Some(ref mut builder) = self.offset_index_builder {
+      builder.append_offset_and_size(
+        page_spec.offset as i64,
+        page_spec.bytes_written as i32
+      );
+    }
+    self.add_encoding(encoding);
+    if self.descr.max_def_level() > 0 || self.descr.max_rep_level() > 0 {
+      self.add_encoding(Encoding::RLE);
+    }
+    self.update_metrics_for_page(page_spec)
+  }
+
+  /// Writes dictionary page into underlying sink.
+  #[inline]
+  fn write_dictionary_page(&mut self) -> Result<()> {
+    let compressed_page = {
+      let encoder = match self.dict_encoder {
+        Some(ref encoder) => encoder,
+        None => return Err(general_err!("Dictionary encoder is not set"))
+      };
+
+      let mut values_buf = encoder.write_dict()?.data().to_vec();
+      let uncompressed_size = values_buf.len();
+
+      // Dictionary page is stored as is, if compression is disabled for it. Readers
+      // of this crate treat a dictionary page with equal sizes as uncompressed, so a
+      // page that compression does not shrink or grow is stored as is as well.
+      if self.dictionary_page_compression_enabled {
+        if let Some(ref mut compressor) = self.compressor {
+          let compressed_buf = compressor.compress(&values_buf[..])?;
+          if compressed_buf.len() != uncompressed_size {
+            values_buf = compressed_buf;
+          }
+        }
+      }
+
+      let dict_page = Page::DictionaryPage {
+        buf: ByteBufferPtr::new(values_buf),
+        num_values: encoder.num_entries() as u32,
+        encoding: self.dict_page_encoding,
+        is_sorted: false
+      };
+      CompressedPage::new(dict_page, uncompressed_size)
+    };
+
+    let page_spec = self.page_writer.write_page(compressed_page)?;
+    self.audit_page_spec(&page_spec)?;
+    let encoding = self.dict_page_encoding;
+    self.add_encoding(encoding);
+    self.update_metrics_for_page(page_spec)
+  }
+
+  /// Records encoding as used in this column chunk.
+  #[inline]
+  fn add_encoding(&mut self, encoding: Encoding) {
+    if !self.encodings.contains(&encoding) {
+      self.encodings.push(encoding);
+    }
+  }
+
+  /// Updates column writer metrics with each page metadata.
+  #[inline]
+  fn update_metrics_for_page(&mut self, page_spec: PageWriteSpec) -> Result<()> {
+    self.total_uncompressed_size += page_spec.uncompressed_size as u64;
+    self.total_compressed_size += page_spec.compressed_size as u64;
+    self.total_bytes_written += page_spec.bytes_written;
+    self.next_page_offset = Some(page_spec.offset + page_spec.bytes_written);
+
+    match page_spec.page_type {
+      PageType::DATA_PAGE | PageType::DATA_PAGE_V2 => {
+        self.total_num_values += page_spec.num_values as u64;
+        if self.data_page_offset.is_none() {
+          self.data_page_offset = Some(page_spec.offset);
+        }
+      },
+      PageType::DICTIONARY_PAGE => {
+        if self.dictionary_page_offset.is_some() {
+          return Err(general_err!("Dictionary offset is already set"));
+        }
+        self.dictionary_page_offset = Some(page_spec.offset);
+      },
+      _ => {}
+    }
+    Ok(())
+  }
+
+  // ----------------------------------------------------------------------
+  // Audit
+
+  /// Checks levels, number of rows and statistics of the buffered data page, if audit
+  /// is enabled. Must be called before page statistics are flushed.
+  fn audit_data_page(&self) -> Result<()> {
+    if !self.audit_enabled {
+      return Ok(());
+    }
+    let num_values = self.num_buffered_values as usize;
+    let max_def_level = self.descr.max_def_level();
+    let max_rep_level = self.descr.max_rep_level();
+
+    if max_def_level > 0 && self.def_levels_sink.len() != num_values {
+      return Err(self.audit_err(format!(
+        "data page has {} values, but {} definition levels",
+        num_values,
+        self.def_levels_sink.len()
+      )));
+    }
+    if max_rep_level > 0 && self.rep_levels_sink.len() != num_values {
+      return Err(self.audit_err(format!(
+        "data page has {} values, but {} repetition levels",
+        num_values,
+        self.rep_levels_sink.len()
+      )));
+    }
+
+    let num_rows = if max_rep_level > 0 {
+      self.rep_levels_sink.iter().filter(|&&level| level == 0).count()
+    } else {
+      num_values
+    };
+    if num_rows != self.num_buffered_rows as usize {
+      return Err(self.audit_err(format!(
+        "data page has {} rows, but {} rows are recorded",
+        num_rows,
+        self.num_buffered_rows
+      )));
+    }
+
+    if self.statistics_enabled {
+      let stats = &self.page_statistics;
+      let num_nulls = if max_def_level > 0 {
+        self.def_levels_sink.iter().filter(|&&level| level < max_def_level).count()
+      } else {
+        0
+      };
+      if stats.null_count() != num_nulls as u64 {
+        return Err(self.audit_err(format!(
+          "data page has {} nulls, but statistics report {} nulls",
+          num_nulls,
+          stats.null_count()
+        )));
+      }
+      if let (Some(min), Some(max)) = (stats.min(), stats.max()) {
+        if stats.compare(min, max) == Ordering::Greater {
+          return Err(self.audit_err(
+            "min value of data page statistics is greater than max value".to_string()
+          ));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Checks that the page has been written right after the previous page of the
+  /// column chunk, and that dictionary page precedes all data pages, if audit is
+  /// enabled. Must be called before metrics are updated for the page.
+  fn audit_page_spec(&self, spec: &PageWriteSpec) -> Result<()> {
+    if !self.audit_enabled {
+      return Ok(());
+    }
+    if let Some(offset) = self.next_page_offset {
+      if spec.offset != offset {
+        return Err(self.audit_err(format!(
+          "{} page is written at offset {}, but previous page ends at offset {}",
+          spec.page_type,
+          spec.offset,
+          offset
+        )));
+      }
+    }
+    if spec.page_type == PageType::DICTIONARY_PAGE &&
+        (self.dictionary_page_offset.is_some() || self.data_page_offset.is_some()) {
+      return Err(self.audit_err(format!(
+        "dictionary page at offset {} is written after other pages",
+        spec.offset
+      )));
+    }
+    if spec.bytes_written < spec.compressed_size as u64 {
+      return Err(self.audit_err(format!(
+        "{} page at offset {} has {} bytes of data, but {} bytes are written",
+        spec.page_type,
+        spec.offset,
+        spec.compressed_size,
+        spec.bytes_written
+      )));
+    }
+    Ok(())
+  }
+
+  /// Checks number of values, offsets, statistics and offset index of the column chunk
+  /// metadata against values and pages written, if audit is enabled.
+  fn audit_column_metadata(&self, metadata: &ColumnChunkMetaData) -> Result<()> {
+    if !self.audit_enabled {
+      return Ok(());
+    }
+    if metadata.num_values() as u64 != self.total_num_levels {
+      return Err(self.audit_err(format!(
+        "column chunk metadata has {} values, but {} values are written",
+        metadata.num_values(),
+        self.total_num_levels
+      )));
+    }
+    if let Some(dict_offset) = metadata.dictionary_page_offset() {
+      if metadata.num_values() > 0 && dict_offset >= metadata.data_page_offset() {
+        return Err(self.audit_err(format!(
+          "dictionary page offset {} is not before data page offset {}",
+          dict_offset,
+          metadata.data_page_offset()
+        )));
+      }
+    }
+
+    if self.statistics_enabled {
+      let stats = &self.chunk_statistics;
+      if stats.null_count() > self.total_num_levels {
+        return Err(self.audit_err(format!(
+          "column chunk has {} values, but statistics report {} nulls",
+          self.total_num_levels,
+          stats.null_count()
+        )));
+      }
+      if let (Some(min), Some(max)) = (stats.min(), stats.max()) {
+        if stats.compare(min, max) == Ordering::Greater {
+          return Err(self.audit_err(
+            "min value of column chunk statistics is greater than max value".to_string()
+          ));
+        }
+      }
+    }
+
+    if let Some(offset_index) = metadata.offset_index() {
+      let locations = offset_index.page_locations();
+      if let Some(first) = locations.first() {
+        if first.offset != metadata.data_page_offset() || first.first_row_index != 0 {
+          return Err(self.audit_err(format!(
+            "offset index starts at offset {} with row {}, but data pages start at \
+             offset {}",
+            first.offset,
+            first.first_row_index,
+            metadata.data_page_offset()
+          )));
+        }
+      }
+      for i in 1..locations.len() {
+        let (prev, location) = (&locations[i - 1], &locations[i]);
+        let prev_end = prev.offset + prev.compressed_page_size as i64;
+        let is_ordered = location.first_row_index >= prev.first_row_index;
+        if location.offset != prev_end || !is_ordered {
+          return Err(self.audit_err(format!(
+            "offset index page {} at offset {} with first row {} does not follow \
+             previous page, which ends at offset {} and starts with row {}",
+            i,
+            location.offset,
+            location.first_row_index,
+            prev_end,
+            prev.first_row_index
+          )));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns error for a failed audit check of this column.
+  fn audit_err(&self, message: String) -> ParquetError {
+    general_err!("Audit of column {} failed: {}", self.descr.path(), message)
+  }
+}
+
+impl ColumnWriterImpl<FixedLenByteArrayType> {
+  /// Writes batch of DECIMAL values, given as unscaled `i128` values, and definition
+  /// and repetition levels. Values are encoded as big-endian two's complement of the
+  /// length of the column. Returns number of values written, see `write_batch`.
+  ///
+  /// Returns error if column is not annotated as DECIMAL, or if any value does not fit
+  /// into the precision or the length of the column.
+  pub fn write_decimal_batch(
+    &mut self,
+    values: &[i128],
+    def_levels: Option<&[i16]>,
+    rep_levels: Option<&[i16]>
+  ) -> Result<usize> {
+    let length = self.descr.type_length() as usize;
+    let values = decimal_values(&self.descr, values, |value| {
+      ByteArray::from_decimal(value, length)
+    })?;
+    self.write_batch(&values, def_levels, rep_levels)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::cell::RefCell;
+
+  use basic::Repetition;
+  use column::page::PageReader;
+  use column::reader::{get_column_reader, get_typed_column_reader};
+  use file::page_index::{BoundaryOrder, PageLocation};
+  use file::properties::WriterProperties;
+  use schema::types::{ColumnDescriptor, ColumnPath, Type as SchemaType};
+  use util::test_common::{random_bools, random_numbers_range};
+
+  #[test]
+  fn test_column_writer_inconsistent_def_rep_length() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 1);
+    let res = writer.write_batch(&[1, 2, 3, 4], Some(&[1, 1, 1]), Some(&[0, 0]));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Inconsistent length of definition and repetition levels: 3 != 2"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_invalid_def_levels() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    let res = writer.write_batch(&[1, 2, 3, 4], None, None);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Definition levels are required, because max definition level = 1"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_invalid_rep_levels() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 1);
+    let res = writer.write_batch(&[1, 2, 3, 4], None, None);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Repetition levels are required, because max repetition level = 1"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_def_level_out_of_range() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    let res = writer.write_batch(&[1, 2], Some(&[1, 2, 1]), None);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Invalid definition level 2 at position 1 for column \"col\", \
+         expected value between 0 and 1"
+      );
+    }
+    // Nothing should be written
+    assert_eq!(writer.num_buffered_values, 0);
+
+    let res = writer.write_batch(&[1, 2], Some(&[1, -1, 1]), None);
+    assert!(res.is_err());
+  }
+
+  #[test]
+  fn test_column_writer_rep_level_out_of_range() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 1);
+    let res = writer.write_batch(&[1, 2, 3], Some(&[1, 1, 1]), Some(&[0, 1, 2]));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Invalid repetition level 2 at position 2 for column \"col\", \
+         expected value between 0 and 1"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_rep_level_must_start_record() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 1);
+    let res = writer.write_batch(&[1, 2], Some(&[1, 1]), Some(&[1, 0]));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Invalid repetition level 1 at position 0 for column \"col\", \
+         column chunk must start with a new record (repetition level 0)"
+      );
+    }
+
+    // Record can span several batches
+    writer.write_batch(&[1, 2], Some(&[1, 1]), Some(&[0, 1])).unwrap();
+    writer.write_batch(&[3], Some(&[1]), Some(&[1])).unwrap();
+    assert_eq!(writer.num_buffered_values, 3);
+  }
+
+  #[test]
+  fn test_column_writer_rep_level_undefined_list() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 1);
+    let res = writer.write_batch(&[1], Some(&[0, 1]), Some(&[0, 1]));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Invalid repetition level 1 at position 1 for column \"col\", \
+         previous definition level 0 does not define a repeated field at this level"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_not_enough_values_to_write() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    let res = writer.write_batch(&[1, 2], Some(&[1, 1, 1, 1]), None);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Expected to write 4 values, but have only 2"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_close_metadata() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&[1, 2, 3, 4], None, None).unwrap();
+    let (bytes_written, rows_written, metadata) = writer.close().unwrap();
+    assert_eq!(rows_written, 4);
+    assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN_DICTIONARY]);
+    assert_eq!(metadata.num_values(), 4);
+    assert_eq!(metadata.compressed_size(), bytes_written as i64);
+    assert_eq!(metadata.uncompressed_size(), bytes_written as i64);
+    // Dictionary page contains 4 PLAIN encoded INT32 values
+    assert_eq!(metadata.dictionary_page_offset(), Some(0));
+    assert_eq!(metadata.data_page_offset(), 16);
+    assert!(bytes_written > 16);
+  }
+
+  #[test]
+  fn test_column_writer_statistics() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_size(12)
+        .with_write_batch_size(4)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    writer.write_batch(
+      &[5, -2, 7, 0, 3],
+      Some(&[1, 0, 1, 1, 0, 0, 1, 1]),
+      None
+    ).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.statistics(),
+      Some(&Statistics::int32(Some(-2), Some(7), None, 3, false))
+    );
+
+    // Each page has its own statistics
+    let page_statistics: Vec<Option<Statistics>> =
+      pages.borrow().iter().map(|page| page.compressed_page().statistics().cloned())
+        .collect();
+    assert_eq!(
+      page_statistics,
+      vec![
+        Some(Statistics::int32(Some(-2), Some(7), None, 1, false)),
+        Some(Statistics::int32(Some(0), Some(3), None, 2, false))
+      ]
+    );
+  }
+
+  #[test]
+  fn test_column_writer_statistics_truncation() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_page_index_enabled(true)
+        .with_max_statistics_size(3)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, props, 0, 0);
+    writer.write_batch(
+      &[ByteArray::from("parquet"), ByteArray::from("ab"), ByteArray::from("rust")],
+      None,
+      None
+    ).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    let expected = Statistics::byte_array(
+      Some(ByteArray::from("ab")), Some(ByteArray::from("rut")), None, 0, false);
+    assert_eq!(metadata.statistics(), Some(&expected));
+    assert_eq!(
+      pages.borrow().back().unwrap().compressed_page().statistics(),
+      Some(&expected)
+    );
+
+    let column_index = metadata.column_index().unwrap();
+    assert_eq!(column_index.min_values(), &[b"ab".to_vec()][..]);
+    assert_eq!(column_index.max_values(), &[b"rut".to_vec()][..]);
+
+    // Strings are truncated at character boundaries
+    let props = Rc::new(WriterProperties::builder().with_max_statistics_size(4).build());
+    let tpe = SchemaType::primitive_type_builder("col", Type::BYTE_ARRAY)
+      .with_logical_type(LogicalType::UTF8)
+      .build()
+      .unwrap();
+    let descr = Rc::new(
+      ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col")));
+    let column_writer =
+      get_column_writer(descr, props.clone(), get_test_page_writer()).unwrap();
+    let mut writer = get_typed_column_writer::<ByteArrayType>(column_writer);
+    writer.write_batch(&[ByteArray::from("aé€"), ByteArray::from("€éa")], None, None)
+      .unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.statistics(),
+      Some(&Statistics::byte_array(
+        Some(ByteArray::from("aé")), Some(ByteArray::from("₭")), None, 0, false))
+    );
+
+    // Fixed length byte arrays are not truncated
+    let tpe = SchemaType::primitive_type_builder("col", Type::FIXED_LEN_BYTE_ARRAY)
+      .with_length(6)
+      .build()
+      .unwrap();
+    let descr = Rc::new(
+      ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col")));
+    let column_writer = get_column_writer(descr, props, get_test_page_writer()).unwrap();
+    let mut writer = get_typed_column_writer::<FixedLenByteArrayType>(column_writer);
+    let values = vec![ByteArray::from("parque"), ByteArray::from("rusted")];
+    writer.write_batch(&values, None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.statistics(),
+      Some(&Statistics::fixed_len_byte_array(
+        Some(ByteArray::from("parque")), Some(ByteArray::from("rusted")), None, 0, false))
+    );
+  }
+
+  #[test]
+  fn test_column_writer_statistics_disabled() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder().with_statistics_enabled(false).build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    writer.write_batch(&[1, 2], Some(&[1, 0, 1]), None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.statistics(), None);
+    assert!(
+      pages.borrow().iter().all(|page| page.compressed_page().statistics().is_none())
+    );
+  }
+
+  #[test]
+  fn test_column_writer_empty_column() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 0);
+    let (bytes_written, rows_written, metadata) = writer.close().unwrap();
+    // Only empty data page is written
+    assert_eq!(bytes_written, 0);
+    assert_eq!(rows_written, 0);
+    assert_eq!(metadata.num_values(), 0);
+    assert_eq!(metadata.dictionary_page_offset(), None);
+    assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN, Encoding::RLE]);
+    assert_eq!(
+      metadata.statistics(),
+      Some(&Statistics::int32(None, None, None, 0, false))
+    );
+  }
+
+  #[test]
+  fn test_column_writer_all_nulls() {
+    for &version in &[WriterVersion::PARQUET_1_0, WriterVersion::PARQUET_2_0] {
+      let pages = Rc::new(RefCell::new(VecDeque::new()));
+      let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_writer_version(version)
+          .with_data_page_row_count_limit(4)
+          .with_write_batch_size(4)
+          .with_page_index_enabled(true)
+          .build()
+      );
+      let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, props, 1, 0);
+      assert_eq!(writer.write_batch(&[], Some(&[0; 10]), None).unwrap(), 0);
+      let (_, rows_written, metadata) = writer.close().unwrap();
+      assert_eq!(rows_written, 10);
+      assert_eq!(metadata.num_values(), 10);
+      assert_eq!(metadata.dictionary_page_offset(), None);
+      assert_eq!(
+        metadata.statistics(),
+        Some(&Statistics::byte_array(None, None, None, 10, false))
+      );
+      let column_index = metadata.column_index().unwrap();
+      assert_eq!(column_index.null_pages(), &[true, true, true][..]);
+
+      // Only data pages without values are written
+      assert_eq!(pages.borrow().len(), 3);
+      assert!(pages.borrow().iter().all(|page| {
+        page.page_type() != PageType::DICTIONARY_PAGE &&
+          page.encoding() != Encoding::RLE_DICTIONARY
+      }));
+      let descr = get_test_column_descr::<ByteArrayType>(1, 0);
+      let (values, def_levels, _) = read_column::<ByteArrayType>(descr, pages, 10);
+      assert!(values.is_empty());
+      assert_eq!(def_levels, vec![0; 10]);
+    }
+  }
+
+  #[test]
+  fn test_column_writer_bool_plain() {
+    let values = random_bools(100);
+    column_roundtrip::<BoolType>("bool_plain", &values, None, None);
+  }
+
+  #[test]
+  fn test_column_writer_int32_dict() {
+    let mut values = vec![];
+    random_numbers_range(2000, -10, 10, &mut values);
+    column_roundtrip::<Int32Type>("int32_dict", &values, None, None);
+  }
+
+  #[test]
+  fn test_column_writer_int64_nullable() {
+    let mut def_levels = vec![];
+    random_numbers_range(3000, 0, 2, &mut def_levels);
+    let num_values = def_levels.iter().filter(|&&level| level == 1).count();
+    let mut values = vec![];
+    random_numbers_range(num_values, ::std::i64::MIN, ::std::i64::MAX, &mut values);
+    column_roundtrip::<Int64Type>("int64_nullable", &values, Some(&def_levels[..]), None);
+  }
+
+  #[test]
+  fn test_column_writer_double_repeated() {
+    let mut def_levels = vec![];
+    random_numbers_range(1500, 0, 3, &mut def_levels);
+    let mut rep_levels = vec![0];
+    random_numbers_range(1499, 0, 2, &mut rep_levels);
+    // Value can only continue a list, if previous value defined that list
+    for i in 1..rep_levels.len() {
+      rep_levels[i] = ::std::cmp::min(rep_levels[i], def_levels[i - 1]);
+    }
+    let num_values = def_levels.iter().filter(|&&level| level == 2).count();
+    let mut values = vec![];
+    random_numbers_range(num_values, -1000.0, 1000.0, &mut values);
+    column_roundtrip::<DoubleType>(
+      "double_repeated", &values, Some(&def_levels[..]), Some(&rep_levels[..]));
+  }
+
+  #[test]
+  fn test_column_writer_byte_array() {
+    let values: Vec<ByteArray> = (0..1500)
+      .map(|i| ByteArray::from(format!("value {}", i % 7).into_bytes()))
+      .collect();
+    column_roundtrip::<ByteArrayType>("byte_array", &values, None, None);
+  }
+
+  #[test]
+  fn test_column_writer_duplicate_dictionary_page() {
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&[1, 2, 3, 4], None, None).unwrap();
+    writer.write_dictionary_page().unwrap();
+    assert_eq!(
+      writer.write_dictionary_page(),
+      Err(general_err!("Dictionary offset is already set"))
+    );
+  }
+
+  #[test]
+  fn test_column_writer_dict_fallback() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_page_size_limit(32)
+        .with_data_page_size(16)
+        .with_write_batch_size(10)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+
+    let values: Vec<i32> = (0..100).collect();
+    writer.write_batch(&values[..], None, None).unwrap();
+    let (_, rows_written, metadata) = writer.close().unwrap();
+    assert_eq!(rows_written, 100);
+    assert_eq!(
+      metadata.encodings(),
+      &vec![Encoding::PLAIN_DICTIONARY, Encoding::PLAIN]
+    );
+
+    // Dictionary page is written first, followed by data pages
+    let page_types: Vec<PageType> =
+      pages.borrow().iter().map(|page| page.page_type()).collect();
+    assert_eq!(page_types[0], PageType::DICTIONARY_PAGE);
+    assert!(page_types[1..].iter().all(|&page_type| page_type == PageType::DATA_PAGE));
+
+    let result = read_column::<Int32Type>(get_test_column_descr::<Int32Type>(0, 0),
+      pages, 100);
+    assert_eq!(result.0, values);
+  }
+
+  #[test]
+  fn test_column_writer_data_page_row_count_limit() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(3)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&[1, 2], None, None).unwrap();
+    writer.write_batch(&[3, 4, 5, 6, 7, 8, 9, 10], None, None).unwrap();
+    writer.close().unwrap();
+
+    let num_values: Vec<u32> = pages.borrow().iter().map(|p| p.num_values()).collect();
+    assert_eq!(num_values, vec![3, 3, 3, 1]);
+
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let (values, _, _) = read_column::<Int32Type>(descr, pages, 10);
+    assert_eq!(values, (1..11).collect::<Vec<i32>>());
+  }
+
+  #[test]
+  fn test_column_writer_data_page_size_encoded() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_data_page_size(64)
+        .with_write_batch_size(10)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    let values: Vec<i32> = (0..1000).map(|i| i % 4).collect();
+    writer.write_batch(&values, None, None).unwrap();
+    writer.close().unwrap();
+
+    // Page size is compared with the size of dictionary indices, which take 2 bits
+    // per value, rather than with the size of values
+    let num_values: Vec<u32> = pages.borrow().iter()
+      .filter(|p| p.page_type() == PageType::DATA_PAGE)
+      .map(|p| p.num_values())
+      .collect();
+    assert_eq!(num_values, vec![250; 4]);
+
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let (actual_values, _, _) = read_column::<Int32Type>(descr, pages, 1000);
+    assert_eq!(actual_values, values);
+  }
+
+  #[test]
+  fn test_column_writer_dictionary_page_compression() {
+    for &enabled in &[true, false] {
+      let pages = Rc::new(RefCell::new(VecDeque::new()));
+      let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_compression(Compression::GZIP)
+          .with_dictionary_page_compression_enabled(enabled)
+          .build()
+      );
+      let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+      let values: Vec<i32> = (0..100).map(|i| i % 50).collect();
+      writer.write_batch(&values[..], None, None).unwrap();
+      writer.close().unwrap();
+
+      let pages = pages.borrow();
+      assert_eq!(pages[0].page_type(), PageType::DICTIONARY_PAGE);
+      assert_eq!(
+        pages[0].compressed_size() == pages[0].uncompressed_size(),
+        !enabled
+      );
+      // Data pages are always compressed
+      assert_eq!(pages[1].page_type(), PageType::DATA_PAGE);
+      assert!(pages[1].compressed_size() != pages[1].uncompressed_size());
+    }
+  }
+
+  #[test]
+  fn test_column_writer_compression_threads() {
+    // Returns written pages and total compressed size of the column chunk
+    let write_pages = |props: WriterProperties| {
+      let pages = Rc::new(RefCell::new(VecDeque::new()));
+      let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+      let mut writer =
+        get_test_column_writer::<Int32Type>(page_writer, Rc::new(props), 1, 0);
+      let values: Vec<i32> = (0..1000).map(|i| i % 300).collect();
+      let def_levels: Vec<i16> =
+        (0..1200).map(|i| if i % 6 == 0 { 0 } else { 1 }).collect();
+      writer.write_batch(&values, Some(&def_levels), None).unwrap();
+      let (_, _, metadata) = writer.close().unwrap();
+      let pages: Vec<(PageType, Vec<u8>, usize)> = pages.borrow().iter().map(|page| {
+        let buffer = page.compressed_page().buffer().data().to_vec();
+        (page.page_type(), buffer, page.uncompressed_size())
+      }).collect();
+      (pages, metadata.compressed_size())
+    };
+
+    for &version in &[WriterVersion::PARQUET_1_0, WriterVersion::PARQUET_2_0] {
+      for &dictionary_enabled in &[true, false] {
+        let builder = || {
+          WriterProperties::builder()
+            .with_writer_version(version)
+            .with_compression(Compression::ZSTD)
+            .with_dictionary_enabled(dictionary_enabled)
+            .with_dictionary_page_size_limit(600)
+            .with_data_page_row_count_limit(50)
+        };
+        let (expected, expected_size) = write_pages(builder().build());
+        let (pages, size) = write_pages(builder().with_compression_threads(2).build());
+        assert!(expected.len() > 10);
+        assert_eq!(pages, expected, "{:?}, dictionary {}", version, dictionary_enabled);
+        assert_eq!(size, expected_size);
+      }
+    }
+  }
+
+  #[test]
+  fn test_column_writer_mem_tracker() {
+    for &compression_threads in &[0, 2] {
+      let pages = Rc::new(RefCell::new(VecDeque::new()));
+      let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_compression(Compression::ZSTD)
+          .with_compression_threads(compression_threads)
+          .with_data_page_row_count_limit(50)
+          .build()
+      );
+      let mem_tracker = Rc::new(MemTracker::new());
+      let descr = get_test_column_descr::<Int32Type>(0, 0);
+      let mut writer = ColumnWriterImpl::<Int32Type>::new_with_mem_tracker(
+        descr,
+        props,
+        page_writer,
+        mem_tracker.clone()
+      ).unwrap();
+
+      // Dictionary holds all distinct values after the first batch, data pages are
+      // buffered until the dictionary page is written
+      let values: Vec<i32> = (0..1000).map(|i| i % 300).collect();
+      writer.write_batch(&values[..300], None, None).unwrap();
+      let dictionary_usage = mem_tracker.memory_usage();
+      assert!(dictionary_usage > 0);
+      writer.write_batch(&values[300..], None, None).unwrap();
+      let buffered_usage = mem_tracker.memory_usage();
+      assert!(buffered_usage > dictionary_usage);
+      assert!(pages.borrow().is_empty());
+
+      writer.close().unwrap();
+      assert_eq!(mem_tracker.memory_usage(), 0);
+      assert!(mem_tracker.max_memory_usage() >= buffered_usage);
+      assert_eq!(pages.borrow().len(), 21);
+    }
+  }
+
+  #[test]
+  fn test_column_writer_dict_fallback_byte_array() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_page_size_limit(1024)
+        .with_write_batch_size(10)
+        .with_encoding(Encoding::DELTA_BYTE_ARRAY)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<ByteArrayType>(page_writer, props, 0, 0);
+
+    // Each unique value takes 4 + 100 bytes in the dictionary
+    let values: Vec<ByteArray> = (0..100)
+      .map(|i| ByteArray::from(format!("{:0100}", i).into_bytes()))
+      .collect();
+    writer.write_batch(&values[..], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(
+      metadata.encodings(),
+      &vec![Encoding::PLAIN_DICTIONARY, Encoding::DELTA_BYTE_ARRAY]
+    );
+
+    // Writer falls back after the first batch that exceeds the limit
+    match pages.borrow()[0].compressed_page() {
+      &Page::DictionaryPage { num_values, .. } => assert_eq!(num_values, 10),
+      _ => panic!("Expected dictionary page")
+    }
+
+    let result = read_column::<ByteArrayType>(
+      get_test_column_descr::<ByteArrayType>(0, 0), pages, 100);
+    assert_eq!(result.0, values);
+  }
+
+  #[test]
+  fn test_column_writer_dictionary_disabled() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_column_dictionary_enabled(ColumnPath::from("col"), false)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+
+    let values: Vec<i32> = (0..100).map(|i| i % 3).collect();
+    writer.write_batch(&values[..], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN]);
+    assert_eq!(metadata.dictionary_page_offset(), None);
+    assert!(pages.borrow().iter().all(|page| page.page_type() == PageType::DATA_PAGE));
+
+    let result = read_column::<Int32Type>(get_test_column_descr::<Int32Type>(0, 0),
+      pages, 100);
+    assert_eq!(result.0, values);
+  }
+
+  #[test]
+  fn test_column_writer_fallback_encoding() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_encoding(Encoding::DELTA_BINARY_PACKED)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+
+    let values: Vec<i32> = (0..100).collect();
+    writer.write_batch(&values[..], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.encodings(), &vec![Encoding::DELTA_BINARY_PACKED]);
+
+    let result = read_column::<Int32Type>(get_test_column_descr::<Int32Type>(0, 0),
+      pages, 100);
+    assert_eq!(result.0, values);
+  }
+
+  #[test]
+  fn test_column_writer_invalid_encoding() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_encoding(Encoding::RLE_DICTIONARY)
+        .build()
+    );
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let res = get_column_writer(descr, props, get_test_page_writer());
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Encoding RLE_DICTIONARY cannot be set for column \"col\", \
+         dictionary is enabled separately"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_unsupported_column_encoding() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_column_encoding(ColumnPath::from("col"), Encoding::DELTA_BINARY_PACKED)
+        .build()
+    );
+    let descr = get_test_column_descr::<ByteArrayType>(0, 0);
+    let res = get_column_writer(descr, props, get_test_page_writer());
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        format!("{}", err),
+        "Parquet error: Encoding DELTA_BINARY_PACKED is not supported for column col of \
+         type BYTE_ARRAY"
+      );
+    }
+  }
+
+  #[test]
+  fn test_column_writer_audit() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_audit_enabled(true)
+        .with_page_index_enabled(true)
+        .with_data_page_row_count_limit(10)
+        .build()
+    );
+    let values: Vec<i32> = (0..50).map(|i| i % 7).collect();
+    let def_levels: Vec<i16> = (0..60).map(|i| if i % 6 == 0 { 0 } else { 1 }).collect();
+
+    let mut writer =
+      get_test_column_writer::<Int32Type>(get_test_page_writer(), props.clone(), 1, 0);
+    writer.write_batch(&values, Some(&def_levels), None).unwrap();
+    let (_, rows_written, metadata) = writer.close().unwrap();
+    assert_eq!(rows_written, 60);
+    assert_eq!(metadata.num_values(), 60);
+
+    // Data pages are not written right after the dictionary page
+    let page_writer = Box::new(GapPageWriter { offset: 0 });
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&values, None, None).unwrap();
+    let err = writer.close().err().unwrap();
+    assert!(
+      format!("{}", err).starts_with(
+        "Parquet error: Audit of column \"col\" failed: DATA_PAGE page is written at"
+      ),
+      "Unexpected error: {}",
+      err
+    );
+  }
+
+  #[test]
+  fn test_write_decimal_batch() {
+    let values = vec![0, 12345, -12345, 999_999_999];
+    assert_eq!(
+      test_decimal_roundtrip::<Int32Type>(9, &values),
+      vec![0, 12345, -12345, 999_999_999]
+    );
+    assert_eq!(
+      test_decimal_roundtrip::<Int64Type>(18, &values),
+      vec![0, 12345, -12345, 999_999_999]
+    );
+    let flba_values = test_decimal_roundtrip::<FixedLenByteArrayType>(20, &values);
+    assert_eq!(flba_values.len(), 4);
+    assert_eq!(flba_values[1].data(), &[0, 0, 0, 0, 0, 0, 0, 0x30, 0x39]);
+    assert_eq!(
+      flba_values[2].data(),
+      &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xCF, 0xC7]
+    );
+  }
+
+  #[test]
+  fn test_column_writer_write_decimal_batch() {
+    let tpe = SchemaType::decimal_type_builder("col", 30, 4).build().unwrap();
+    let descr =
+      Rc::new(ColumnDescriptor::new(Rc::new(tpe), None, 1, 0, ColumnPath::from("col")));
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = ColumnWriterImpl::<FixedLenByteArrayType>::new(
+      descr.clone(),
+      props,
+      page_writer
+    ).unwrap();
+
+    let values = [-1, 10i128.pow(29), -(10i128.pow(29))];
+    let def_levels = [1, 0, 1, 1];
+    let values_written =
+      writer.write_decimal_batch(&values, Some(&def_levels[..]), None).unwrap();
+    assert_eq!(values_written, 3);
+    assert_eq!(
+      writer.write_decimal_batch(&[10i128.pow(30)], Some(&[1][..]), None).unwrap_err(),
+      general_err!(
+        "Value 1000000000000000000000000000000 does not fit into column \"col\" of \
+         type DECIMAL(30, 4)"
+      )
+    );
+    writer.close().unwrap();
+
+    let (values_read, def_levels_read, _) =
+      read_column::<FixedLenByteArrayType>(descr, pages, def_levels.len());
+    let decimals: Vec<Option<i128>> =
+      values_read.iter().map(|value| value.to_decimal()).collect();
+    assert_eq!(decimals, vec![Some(-1), Some(10i128.pow(29)), Some(-(10i128.pow(29)))]);
+    assert_eq!(def_levels_read, def_levels);
+  }
+
+  #[test]
+  fn test_write_decimal_batch_errors() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let tpe = SchemaType::decimal_type_builder("col", 4, 2).build().unwrap();
+    let descr =
+      Rc::new(ColumnDescriptor::new(Rc::new(tpe), None, 1, 0, ColumnPath::from("col")));
+    let mut writer = get_column_writer(descr, props.clone(), get_test_page_writer())
+      .unwrap();
+    assert_eq!(
+      write_decimal_batch(&mut writer, &[9999, -10000], Some(&[1, 1][..]), None)
+        .unwrap_err(),
+      general_err!("Value -10000 does not fit into column \"col\" of type DECIMAL(4, 2)")
+    );
+
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let mut writer = get_column_writer(descr, props.clone(), get_test_page_writer())
+      .unwrap();
+    assert_eq!(
+      write_decimal_batch(&mut writer, &[1], None, None).unwrap_err(),
+      general_err!("Column \"col\" is not annotated as DECIMAL")
+    );
+
+    let descr = get_test_column_descr::<DoubleType>(0, 0);
+    let mut writer = get_column_writer(descr, props, get_test_page_writer()).unwrap();
+    assert_eq!(
+      write_decimal_batch(&mut writer, &[1], None, None).unwrap_err(),
+      general_err!("Cannot write DECIMAL values into column \"col\" of type DOUBLE")
+    );
+  }
+
+  #[test]
+  fn test_column_writer_write_iter() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(WriterProperties::builder().with_write_batch_size(3).build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    assert_eq!(writer.write_iter((0..10).map(|i| i * 2)).unwrap(), 10);
+    assert_eq!(writer.write_iter(vec![]).unwrap(), 0);
+    writer.close().unwrap();
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let (values, _, _) = read_column::<Int32Type>(descr, pages, 10);
+    assert_eq!(values, (0..10).map(|i| i * 2).collect::<Vec<i32>>());
+
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(WriterProperties::builder().with_write_batch_size(3).build());
+    let mut writer = get_test_column_writer::<Int64Type>(page_writer, props, 1, 0);
+    let values = (0..10).map(|i| if i % 3 == 0 { None } else { Some(i as i64) });
+    assert_eq!(writer.write_optional_iter(values).unwrap(), 6);
+    assert_eq!(writer.write_iter(vec![10, 11]).unwrap(), 2);
+    let (_, rows_written, _) = writer.close().unwrap();
+    assert_eq!(rows_written, 12);
+    let descr = get_test_column_descr::<Int64Type>(1, 0);
+    let (values, def_levels, _) = read_column::<Int64Type>(descr, pages, 12);
+    assert_eq!(values, vec![1, 2, 4, 5, 7, 8, 10, 11]);
+    assert_eq!(def_levels, vec![0, 1, 1, 0, 1, 1, 0, 1, 1, 0, 1, 1]);
+  }
+
+  #[test]
+  fn test_column_writer_write_iter_errors() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer =
+      get_test_column_writer::<Int32Type>(get_test_page_writer(), props.clone(), 1, 1);
+    assert_eq!(
+      writer.write_iter(vec![1]).unwrap_err(),
+      general_err!("Cannot write values of repeated column \"col\" from an iterator")
+    );
+
+    let mut writer =
+      get_test_column_writer::<Int32Type>(get_test_page_writer(), props, 0, 0);
+    assert_eq!(
+      writer.write_optional_iter(vec![Some(1)]).unwrap_err(),
+      general_err!("Cannot write optional values into required column \"col\"")
+    );
+    assert_eq!(writer.get_total_rows_written(), 0);
+  }
+
+  #[test]
+  fn test_column_writer_bloom_filter() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_bloom_filter_enabled(true)
+        .with_bloom_filter_ndv(1000)
+        .with_bloom_filter_fpp(0.01)
+        .build()
+    );
+    let page_writer = get_test_page_writer();
+    let mut writer =
+      get_test_column_writer::<Int32Type>(page_writer, props.clone(), 0, 0);
+    writer.write_batch(&[1, 2, 3, 4], None, None).unwrap();
+    let (bytes_written, _, metadata) = writer.close().unwrap();
+    // Bloom filter is written right after the pages
+    assert_eq!(metadata.bloom_filter_offset(), Some(metadata.compressed_size()));
+    assert_eq!(metadata.bloom_filter_length(), Some(2048));
+    assert_eq!(bytes_written, metadata.compressed_size() as u64 + 2048);
+
+    // Bloom filters are not built for booleans
+    let page_writer = get_test_page_writer();
+    let mut writer = get_test_column_writer::<BoolType>(page_writer, props, 0, 0);
+    writer.write_batch(&[true, false], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.bloom_filter_offset(), None);
+    assert_eq!(metadata.bloom_filter_length(), None);
+  }
+
+  #[test]
+  fn test_column_writer_page_index() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_page_index_enabled(true)
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(2)
+        .build()
+    );
+    let mut writer =
+      get_test_column_writer::<Int32Type>(page_writer, props.clone(), 1, 0);
+    writer.write_batch(&[1, 2, 3, 4], Some(&[1, 1, 0, 0, 1, 1]), None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+
+    let column_index = metadata.column_index().unwrap();
+    assert_eq!(column_index.null_pages(), &[false, true, false]);
+    let bytes = |value: i32| value.as_bytes().to_vec();
+    assert_eq!(column_index.min_values(), &[bytes(1), vec![], bytes(3)][..]);
+    assert_eq!(column_index.max_values(), &[bytes(2), vec![], bytes(4)][..]);
+    assert_eq!(column_index.null_counts(), Some(&[0, 2, 0][..]));
+    assert_eq!(column_index.boundary_order(), BoundaryOrder::ASCENDING);
+
+    let page_sizes: Vec<i32> =
+      pages.borrow().iter().map(|page| page.compressed_size() as i32).collect();
+    let offset_index = metadata.offset_index().unwrap();
+    assert_eq!(
+      offset_index.page_locations(),
+      &[
+        PageLocation {
+          offset: 0,
+          compressed_page_size: page_sizes[0],
+          first_row_index: 0
+        },
+        PageLocation {
+          offset: page_sizes[0] as i64,
+          compressed_page_size: page_sizes[1],
+          first_row_index: 2
+        },
+        PageLocation {
+          offset: (page_sizes[0] + page_sizes[1]) as i64,
+          compressed_page_size: page_sizes[2],
+          first_row_index: 4
+        }
+      ]
+    );
+
+    // Column index is not written, if a page with values has no min/max values
+    let page_writer = get_test_page_writer();
+    let mut writer = get_test_column_writer::<DoubleType>(page_writer, props, 0, 0);
+    let values = [1.0, 2.0, ::std::f64::NAN, ::std::f64::NAN];
+    writer.write_batch(&values, None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert!(metadata.column_index().is_none());
+    assert_eq!(metadata.offset_index().unwrap().page_locations().len(), 2);
+
+    // Page index is not written by default
+    let page_writer = get_test_page_writer();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&[1, 2, 3], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert!(metadata.column_index().is_none());
+    assert!(metadata.offset_index().is_none());
+  }
+
+  #[test]
+  fn test_column_writer_v2_data_pages() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_writer_version(WriterVersion::PARQUET_2_0)
+        .with_dictionary_enabled(false)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 1, 1);
+    let values = [1, 2, 3, 4, 5];
+    let def_levels = [1, 0, 1, 1, 0, 1, 1];
+    let rep_levels = [0, 1, 0, 1, 0, 0, 1];
+    writer.write_batch(&values, Some(&def_levels), Some(&rep_levels)).unwrap();
+    let (_, rows_written, metadata) = writer.close().unwrap();
+    assert_eq!(rows_written, 4);
+    assert_eq!(
+      metadata.encodings(),
+      &vec![Encoding::DELTA_BINARY_PACKED, Encoding::RLE]
+    );
+
+    match pages.borrow()[0].compressed_page() {
+      &Page::DataPageV2 { num_values, encoding, num_nulls, num_rows, .. } => {
+        assert_eq!(num_values, 7);
+        assert_eq!(encoding, Encoding::DELTA_BINARY_PACKED);
+        assert_eq!(num_nulls, 2);
+        assert_eq!(num_rows, 4);
+      },
+      _ => panic!("Expected data page v2")
+    }
+
+    let descr = get_test_column_descr::<Int32Type>(1, 1);
+    let (actual_values, actual_def_levels, actual_rep_levels) =
+      read_column::<Int32Type>(descr, pages, def_levels.len());
+    assert_eq!(&actual_values[..], &values[..]);
+    assert_eq!(&actual_def_levels[..], &def_levels[..]);
+    assert_eq!(&actual_rep_levels[..], &rep_levels[..]);
+  }
+
+  #[test]
+  fn test_column_writer_v2_dictionary_encodings() {
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_writer_version(WriterVersion::PARQUET_2_0)
+        .build()
+    );
+    let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+    writer.write_batch(&[1, 2, 1, 2], None, None).unwrap();
+    let (_, _, metadata) = writer.close().unwrap();
+    assert_eq!(metadata.encodings(), &vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY]);
+
+    let descr = get_test_column_descr::<Int32Type>(0, 0);
+    let (actual_values, _, _) = read_column::<Int32Type>(descr, pages, 4);
+    assert_eq!(&actual_values[..], &[1, 2, 1, 2]);
+  }
+
+  #[test]
+  fn test_fallback_encoding_by_writer_version() {
+    let v1 = WriterVersion::PARQUET_1_0;
+    let v2 = WriterVersion::PARQUET_2_0;
+    assert_eq!(fallback_encoding(Type::INT32, v1), Encoding::PLAIN);
+    assert_eq!(fallback_encoding(Type::BYTE_ARRAY, v1), Encoding::PLAIN);
+    assert_eq!(fallback_encoding(Type::BOOLEAN, v2), Encoding::RLE);
+    assert_eq!(fallback_encoding(Type::INT32, v2), Encoding::DELTA_BINARY_PACKED);
+    assert_eq!(fallback_encoding(Type::INT64, v2), Encoding::DELTA_BINARY_PACKED);
+    assert_eq!(fallback_encoding(Type::BYTE_ARRAY, v2), Encoding::DELTA_BYTE_ARRAY);
+    assert_eq!(fallback_encoding(Type::DOUBLE, v2), Encoding::PLAIN);
+  }
+
+  #[test]
+  fn test_column_writer_adaptive_encoding() {
+    let low_cardinality: Vec<i32> = (0..100).map(|i| i % 3).collect();
+    let sorted: Vec<i32> = (0..100).collect();
+    let unsorted: Vec<i32> = (0..100).map(|i| (i * 37) % 100).collect();
+    let cases = vec![
+      (low_cardinality, vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY]),
+      (sorted, vec![Encoding::DELTA_BINARY_PACKED]),
+      (unsorted, vec![Encoding::PLAIN])
+    ];
+    for (values, encodings) in cases {
+      let pages = Rc::new(RefCell::new(VecDeque::new()));
+      let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+      // Encoding and dictionary settings are ignored
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_writer_version(WriterVersion::PARQUET_2_0)
+          .with_data_page_size(64)
+          .with_dictionary_enabled(false)
+          .with_encoding(Encoding::PLAIN)
+          .with_adaptive_encoding_enabled(true)
+          .build()
+      );
+      let mut writer = get_test_column_writer::<Int32Type>(page_writer, props, 0, 0);
+      writer.write_batch(&values[..10], None, None).unwrap();
+      writer.write_batch(&values[10..], None, None).unwrap();
+      let (_, _, metadata) = writer.close().unwrap();
+      assert_eq!(metadata.encodings(), &encodings);
+
+      let descr = get_test_column_descr::<Int32Type>(0, 0);
+      let (actual_values, _, _) = read_column::<Int32Type>(descr, pages, 100);
+      assert_eq!(actual_values, values);
+    }
+  }
+
+  #[test]
+  fn test_adaptive_encoding() {
+    let v1 = WriterVersion::PARQUET_1_0;
+    let v2 = WriterVersion::PARQUET_2_0;
+    let sorted: Vec<ByteArray> =
+      vec!["apple", "banana", "cherry"].into_iter().map(ByteArray::from).collect();
+    let repeated: Vec<ByteArray> =
+      vec!["apple", "apple", "apple"].into_iter().map(ByteArray::from).collect();
+    assert_eq!(
+      adaptive_encoding::<ByteArrayType>(&sorted, Type::BYTE_ARRAY, v2),
+      (false, Encoding::DELTA_BYTE_ARRAY)
+    );
+    assert_eq!(
+      adaptive_encoding::<ByteArrayType>(&sorted, Type::BYTE_ARRAY, v1),
+      (false, Encoding::PLAIN)
+    );
+    assert_eq!(
+      adaptive_encoding::<ByteArrayType>(&repeated, Type::BYTE_ARRAY, v1),
+      (true, Encoding::PLAIN)
+    );
+    assert_eq!(
+      adaptive_encoding::<BoolType>(&[true, true, true], Type::BOOLEAN, v2),
+      (false, Encoding::RLE)
+    );
+    assert_eq!(
+      adaptive_encoding::<Int64Type>(&[], Type::INT64, v2),
+      (false, Encoding::DELTA_BINARY_PACKED)
+    );
+    assert_eq!(
+      adaptive_encoding::<DoubleType>(&[1.0, 2.0, 3.0], Type::DOUBLE, v2),
+      (false, Encoding::PLAIN)
+    );
+  }
+
+  /// Writes values with levels into a column, reads them back and compares.
+  fn column_roundtrip<T: DataType>(
+    name: &str,
+    values: &[T::T],
+    def_levels: Option<&[i16]>,
+    rep_levels: Option<&[i16]>
+  ) where T: 'static {
+    let max_def_level = def_levels.map(|l| *l.iter().max().unwrap()).unwrap_or(0);
+    let max_rep_level = rep_levels.map(|l| *l.iter().max().unwrap()).unwrap_or(0);
+    let num_levels = def_levels.map(|l| l.len()).unwrap_or(values.len());
+
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(WriterProperties::builder().with_write_batch_size(100).build());
+    let mut writer =
+      get_test_column_writer::<T>(page_writer, props, max_def_level, max_rep_level);
+    let values_written = writer.write_batch(values, def_levels, rep_levels).unwrap();
+    assert_eq!(values_written, values.len(), "{}", name);
+    let (_, rows_written, metadata) = writer.close().unwrap();
+
+    let expected_rows = match rep_levels {
+      Some(levels) => levels.iter().filter(|&&level| level == 0).count(),
+      None => num_levels
+    };
+    assert_eq!(rows_written as usize, expected_rows, "{}", name);
+    assert_eq!(metadata.num_values() as usize, num_levels, "{}", name);
+
+    let descr = get_test_column_descr::<T>(max_def_level, max_rep_level);
+    let (actual_values, actual_def_levels, actual_rep_levels) =
+      read_column::<T>(descr, pages, num_levels);
+    assert_eq!(&actual_values[..], values, "{}", name);
+    if let Some(levels) = def_levels {
+      assert_eq!(&actual_def_levels[..], levels, "{}", name);
+    }
+    if let Some(levels) = rep_levels {
+      assert_eq!(&actual_rep_levels[..], levels, "{}", name);
+    }
+  }
+
+  /// Reads back all pages written by `TestPageWriter` with a column reader.
+  /// Writes DECIMAL values into a required column with `precision`, which is stored
+  /// as physical type of `T`, and returns values read back.
+  fn test_decimal_roundtrip<T: DataType>(precision: i32, values: &[i128]) -> Vec<T::T>
+      where T: 'static {
+    let tpe = SchemaType::decimal_type_builder("col", precision, 2)
+      .with_repetition(Repetition::REQUIRED)
+      .build()
+      .unwrap();
+    let descr =
+      Rc::new(ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col")));
+    let pages = Rc::new(RefCell::new(VecDeque::new()));
+    let page_writer = Box::new(TestPageWriter::new(pages.clone()));
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = get_column_writer(descr.clone(), props, page_writer).unwrap();
+    let values_written = write_decimal_batch(&mut writer, values, None, None).unwrap();
+    assert_eq!(values_written, values.len());
+    get_typed_column_writer::<T>(writer).close().unwrap();
+    read_column::<T>(descr, pages, values.len()).0
+  }
+
+  fn read_column<T: DataType>(
+    descr: ColumnDescPtr,
+    pages: Rc<RefCell<VecDeque<CompressedPage>>>,
+    num_levels: usize
+  ) -> (Vec<T::T>, Vec<i16>, Vec<i16>) where T: 'static {
+    let pages: Vec<Page> = pages.borrow().iter().map(to_page).collect();
+    let page_reader = Box::new(TestPageReader { pages: pages.into_iter().collect() });
+    let column_reader = get_column_reader(descr, page_reader);
+    let mut typed_column_reader = get_typed_column_reader::<T>(column_reader);
+
+    let mut values = vec![T::T::default(); num_levels];
+    let mut def_levels = vec![0; num_levels];
+    let mut rep_levels = vec![0; num_levels];
+    let (values_read, levels_read) = typed_column_reader.read_batch(
+      num_levels,
+      Some(&mut def_levels[..]),
+      Some(&mut rep_levels[..]),
+      &mut values[..]
+    ).unwrap();
+    values.truncate(values_read);
+    def_levels.truncate(levels_read);
+    rep_levels.truncate(levels_read);
+    (values, def_levels, rep_levels)
+  }
+
+  fn to_page(page: &CompressedPage) -> Page {
+    match page.compressed_page() {
+      &Page::DataPage {
+        ref buf, num_values, encoding, def_level_encoding, rep_level_encoding,
+        ref statistics
+      } => Page::DataPage {
+        buf: buf.clone(),
+        num_values: num_values,
+        encoding: encoding,
+        def_level_encoding: def_level_encoding,
+        rep_level_encoding: rep_level_encoding,
+        statistics: statistics.clone()
+      },
+      &Page::DataPageV2 {
+        ref buf, num_values, encoding, num_nulls, num_rows, def_levels_byte_len,
+        rep_levels_byte_len, is_compressed, ref statistics
+      } => Page::DataPageV2 {
+        buf: buf.clone(),
+        num_values: num_values,
+        encoding: encoding,
+        num_nulls: num_nulls,
+        num_rows: num_rows,
+        def_levels_byte_len: def_levels_byte_len,
+        rep_levels_byte_len: rep_levels_byte_len,
+        is_compressed: is_compressed,
+        statistics: statistics.clone()
+      },
+      &Page::DictionaryPage { ref buf, num_values, encoding, is_sorted } => {
+        Page::DictionaryPage {
+          buf: buf.clone(),
+          num_values: num_values,
+          encoding: encoding,
+          is_sorted: is_sorted
+        }
+      },
+      _ => panic!("Unexpected page type")
+    }
+  }
+
+  fn get_test_column_descr<T: DataType>(
+    max_def_level: i16,
+    max_rep_level: i16
+  ) -> ColumnDescPtr {
+    let tpe = SchemaType::primitive_type_builder("col", T::get_physical_type())
+      .build()
+      .unwrap();
+    let path = ColumnPath::from("col");
+    Rc::new(ColumnDescriptor::new(
+      Rc::new(tpe), None, max_def_level, max_rep_level, path))
+  }
+
+  fn get_test_column_writer<T: DataType>(
+    page_writer: Box<PageWriter>,
+    props: WriterPropertiesPtr,
+    max_def_level: i16,
+    max_rep_level: i16
+  ) -> ColumnWriterImpl<T> where T: 'static {
+    let descr = get_test_column_descr::<T>(max_def_level, max_rep_level);
+    let column_writer = get_column_writer(descr, props, page_writer).unwrap();
+    get_typed_column_writer::<T>(column_writer)
+  }
+
+  fn get_test_page_writer() -> Box<PageWriter> {
+    Box::new(TestPageWriter::new(Rc::new(RefCell::new(VecDeque::new()))))
+  }
+
+  /// Page writer that keeps all written pages in memory.
+  struct TestPageWriter {
+    pages: Rc<RefCell<VecDeque<CompressedPage>>>,
+    offset: u64
+  }
+
+  impl TestPageWriter {
+    fn new(pages: Rc<RefCell<VecDeque<CompressedPage>>>) -> Self {
+      Self { pages: pages, offset: 0 }
+    }
+  }
+
+  impl PageWriter for TestPageWriter {
+    fn write_page(&mut self, page: CompressedPage) -> Result<PageWriteSpec> {
+      let mut res = PageWriteSpec::new();
+      res.page_type = page.page_type();
+      res.uncompressed_size = page.uncompressed_size();
+      res.compressed_size = page.compressed_size();
+      res.num_values = page.num_values();
+      res.offset = self.offset;
+      res.bytes_written = page.compressed_size() as u64;
+      self.offset += res.bytes_written;
+      self.pages.borrow_mut().push_back(page);
+      Ok(res)
+    }
+
+    fn write_bloom_filter(&mut self, filter: &Sbbf) -> Result<u64> {
+      let offset = self.offset;
+      self.offset += filter.num_bytes() as u64;
+      Ok(offset)
+    }
+
+    fn write_metadata(&mut self, _metadata: &ColumnChunkMetaData) -> Result<()> {
+      Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+      Ok(())
+    }
+  }
+
+  /// Page writer that leaves a gap of one byte after every page.
+  struct GapPageWriter {
+    offset: u64
+  }
+
+  impl PageWriter for GapPageWriter {
+    fn write_page(&mut self, page: CompressedPage) -> Result<PageWriteSpec> {
+      let mut res = PageWriteSpec::new();
+      res.page_type = page.page_type();
+      res.uncompressed_size = page.uncompressed_size();
+      res.compressed_size = page.compressed_size();
+      res.num_values = page.num_values();
+      res.offset = self.offset;
+      res.bytes_written = page.compressed_size() as u64;
+      self.offset += res.bytes_written + 1;
+      Ok(res)
+    }
+
+    fn write_bloom_filter(&mut self, filter: &Sbbf) -> Result<u64> {
+      let offset = self.offset;
+      self.offset += filter.num_bytes() as u64;
+      Ok(offset)
+    }
+
+    fn write_metadata(&mut self, _metadata: &ColumnChunkMetaData) -> Result<()> {
+      Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+      Ok(())
+    }
+  }
+
+  struct TestPageReader {
+    pages: VecDeque<Page>
+  }
+
+  impl PageReader for TestPageReader {
+    fn get_next_page(&mut self) -> Result<Option<Page>> {
+      Ok(self.pages.pop_front())
+    }
+
+    fn num_values_left(&self) -> i64 {
+      self.pages.iter().map(|page| match page {
+        &Page::DictionaryPage { .. } => 0,
+        page => page.num_values() as i64
+      }).sum()
+    }
+  }
+}
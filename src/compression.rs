@@ -40,7 +40,11 @@
 //! assert_eq!(output, data);
 //! ```
 
+use std::fmt;
 use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
 
 use basic::Compression as CodecType;
 use errors::{Result, ParquetError};
@@ -49,6 +53,7 @@ use flate2::Compression;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use snap::{decompress_len, Decoder, Encoder};
+use zstd;
 
 /// Parquet compression codec interface.
 pub trait Codec {
@@ -67,10 +72,23 @@ pub trait Codec {
 /// bytes for the compression type.
 /// This returns `None` if the codec type is `UNCOMPRESSED`.
 pub fn create_codec(codec: CodecType) -> Result<Option<Box<Codec>>> {
+  create_codec_with_dictionary(codec, None)
+}
+
+/// Same as `create_codec`, but uses compression `dictionary` if the codec supports
+/// it. Only ZSTD codec supports dictionaries, for other codecs dictionary is ignored.
+///
+/// Data compressed with a dictionary can only be decompressed with the same
+/// dictionary.
+pub fn create_codec_with_dictionary(
+  codec: CodecType,
+  dictionary: Option<Arc<Vec<u8>>>
+) -> Result<Option<Box<Codec>>> {
   match codec {
     CodecType::BROTLI => Ok(Some(Box::new(BrotliCodec::new()))),
     CodecType::GZIP => Ok(Some(Box::new(GZipCodec::new()))),
     CodecType::SNAPPY => Ok(Some(Box::new(SnappyCodec::new()))),
+    CodecType::ZSTD => Ok(Some(Box::new(ZSTDCodec::new(dictionary)))),
     CodecType::UNCOMPRESSED => Ok(None),
     _ => Err(nyi_err!("The codec type {} is not supported yet", codec))
   }
@@ -167,6 +185,225 @@ impl Codec for BrotliCodec {
   }
 }
 
+const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
+pub struct ZSTDCodec {
+  dictionary: Option<Arc<Vec<u8>>>
+}
+
+impl ZSTDCodec {
+  /// Creates new Zstandard compression codec with optional compression dictionary.
+  fn new(dictionary: Option<Arc<Vec<u8>>>) -> Self {
+    Self { dictionary: dictionary }
+  }
+}
+
+impl Codec for ZSTDCodec {
+  fn decompress(&mut self, input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
+    let mut decoder = match self.dictionary {
+      Some(ref dictionary) => zstd::Decoder::with_dictionary(input_buf, dictionary)?,
+      None => zstd::Decoder::new(input_buf)?
+    };
+    decoder
+      .read_to_end(output_buf)
+      .map_err(|e| general_err!("Error when decompressing using ZSTD: {}", e))
+  }
+
+  fn compress(&mut self, input_buf: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = match self.dictionary {
+      Some(ref dictionary) => {
+        zstd::Encoder::with_dictionary(Vec::new(), ZSTD_COMPRESSION_LEVEL, dictionary)?
+      },
+      None => zstd::Encoder::new(Vec::new(), ZSTD_COMPRESSION_LEVEL)?
+    };
+    encoder.write_all(input_buf)?;
+    encoder
+      .finish()
+      .map_err(|e| general_err!("Error when compressing using ZSTD: {}", e))
+  }
+}
+
+// ----------------------------------------------------------------------
+// Compression worker pool
+
+/// Pool of worker threads that compress page buffers, so that the writer thread keeps
+/// encoding pages while previous pages are compressed.
+///
+/// Buffers are submitted into a bounded queue of `queue_size` buffers, `submit` blocks
+/// when workers fall behind, which bounds memory used by buffers in flight. Workers
+/// stop when the pool is dropped.
+pub struct CompressionPool {
+  sender: Mutex<Option<SyncSender<CompressionJob>>>,
+  workers: Vec<JoinHandle<()>>,
+  queue_size: usize
+}
+
+/// Buffer to compress with codec `codec` and optional compression dictionary.
+struct CompressionJob {
+  codec: CodecType,
+  dictionary: Option<Arc<Vec<u8>>>,
+  data: Vec<u8>,
+  result: Sender<Result<Vec<u8>>>
+}
+
+impl CompressionPool {
+  /// Creates pool of `num_threads` worker threads with a queue of `queue_size`
+  /// buffers.
+  pub fn new(num_threads: usize, queue_size: usize) -> Result<Self> {
+    if num_threads == 0 {
+      return Err(general_err!("Compression pool requires at least one thread"));
+    }
+    let (sender, receiver) = mpsc::sync_channel::<CompressionJob>(queue_size);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let mut workers = Vec::with_capacity(num_threads);
+    for i in 0..num_threads {
+      let receiver = receiver.clone();
+      let worker = thread::Builder::new()
+        .name(format!("parquet-compression-{}", i))
+        .spawn(move || run_compression_worker(receiver))?;
+      workers.push(worker);
+    }
+    Ok(Self {
+      sender: Mutex::new(Some(sender)),
+      workers: workers,
+      queue_size: queue_size
+    })
+  }
+
+  /// Returns number of worker threads.
+  pub fn num_threads(&self) -> usize {
+    self.workers.len()
+  }
+
+  /// Returns number of buffers that can be queued before `submit` blocks.
+  pub fn queue_size(&self) -> usize {
+    self.queue_size
+  }
+
+  /// Submits buffer `data` to be compressed with codec `codec` and optional compression
+  /// `dictionary`, blocks if the queue is full. Compression errors, e.g. an
+  /// unsupported codec, are returned by [`PendingCompression::wait`].
+  pub fn submit(
+    &self,
+    codec: CodecType,
+    dictionary: Option<Arc<Vec<u8>>>,
+    data: Vec<u8>
+  ) -> Result<PendingCompression> {
+    let (result, receiver) = mpsc::channel();
+    let job = CompressionJob {
+      codec: codec,
+      dictionary: dictionary,
+      data: data,
+      result: result
+    };
+    // Sender is cloned, so that the lock is not held while the queue is full
+    let sender = match self.sender.lock() {
+      Ok(sender) => sender.clone(),
+      Err(_) => None
+    };
+    let sender = match sender {
+      Some(sender) => sender,
+      None => return Err(general_err!("Compression pool is stopped"))
+    };
+    sender.send(job).map_err(|_| general_err!("Compression workers have stopped"))?;
+    Ok(PendingCompression { receiver: receiver, result: None })
+  }
+}
+
+impl Drop for CompressionPool {
+  fn drop(&mut self) {
+    // Closing the queue stops workers once queued buffers are compressed
+    if let Ok(mut sender) = self.sender.lock() {
+      sender.take();
+    }
+    for worker in self.workers.drain(..) {
+      let _ = worker.join();
+    }
+  }
+}
+
+impl fmt::Debug for CompressionPool {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("CompressionPool")
+      .field("num_threads", &self.num_threads())
+      .field("queue_size", &self.queue_size)
+      .finish()
+  }
+}
+
+/// Compresses buffers received from the pool queue until the queue is closed. Codec
+/// is reused between buffers with the same codec type and dictionary.
+fn run_compression_worker(receiver: Arc<Mutex<Receiver<CompressionJob>>>) {
+  let mut cached: Option<(CodecType, Option<Arc<Vec<u8>>>, Box<Codec>)> = None;
+  loop {
+    let job = match receiver.lock() {
+      Ok(receiver) => match receiver.recv() {
+        Ok(job) => job,
+        Err(_) => return
+      },
+      Err(_) => return
+    };
+    let is_cached = match cached {
+      Some((ref codec, ref dictionary, _)) => {
+        *codec == job.codec && match (dictionary, &job.dictionary) {
+          (&Some(ref a), &Some(ref b)) => Arc::ptr_eq(a, b),
+          (&None, &None) => true,
+          _ => false
+        }
+      },
+      None => false
+    };
+    if !is_cached {
+      cached = match create_codec_with_dictionary(job.codec, job.dictionary.clone()) {
+        Ok(Some(codec)) => Some((job.codec, job.dictionary.clone(), codec)),
+        Ok(None) => None,
+        Err(e) => {
+          let _ = job.result.send(Err(e));
+          continue;
+        }
+      };
+    }
+    let result = match cached {
+      Some((_, _, ref mut codec)) => codec.compress(&job.data),
+      // Uncompressed buffers are returned as is
+      None => Ok(job.data)
+    };
+    // Receiver might have been dropped, e.g. if writer failed
+    let _ = job.result.send(result);
+  }
+}
+
+/// Buffer that is being compressed by [`CompressionPool`](`CompressionPool`).
+pub struct PendingCompression {
+  receiver: Receiver<Result<Vec<u8>>>,
+  result: Option<Result<Vec<u8>>>
+}
+
+impl PendingCompression {
+  /// Returns `true` if compressed buffer is available without blocking.
+  pub fn is_ready(&mut self) -> bool {
+    if self.result.is_none() {
+      match self.receiver.try_recv() {
+        Ok(result) => self.result = Some(result),
+        Err(TryRecvError::Empty) => return false,
+        Err(TryRecvError::Disconnected) => {
+          self.result = Some(Err(general_err!("Compression worker has stopped")))
+        }
+      }
+    }
+    true
+  }
+
+  /// Returns compressed buffer, blocks until it is available.
+  pub fn wait(self) -> Result<Vec<u8>> {
+    match self.result {
+      Some(result) => result,
+      None => self.receiver.recv().unwrap_or_else(|_| {
+        Err(general_err!("Compression worker has stopped"))
+      })
+    }
+  }
+}
 
 #[cfg(test)]
 mod tests {
@@ -223,4 +460,68 @@ mod tests {
   fn test_codec_brotli() {
     test_codec(CodecType::BROTLI);
   }
+
+  #[test]
+  fn test_codec_zstd() {
+    test_codec(CodecType::ZSTD);
+  }
+
+  #[test]
+  fn test_codec_zstd_dictionary() {
+    let mut dictionary = Vec::new();
+    for _ in 0..8 {
+      dictionary.extend_from_slice(b"timestamp=2018-01-01 level=INFO message=");
+    }
+    let dictionary = Arc::new(dictionary);
+    let data = b"timestamp=2018-01-01 level=INFO message=started".to_vec();
+
+    let mut with_dict =
+      create_codec_with_dictionary(CodecType::ZSTD, Some(dictionary.clone()))
+        .unwrap()
+        .unwrap();
+    let compressed = with_dict.compress(&data).unwrap();
+    let mut decompressed = Vec::new();
+    let size = with_dict.decompress(&compressed, &mut decompressed).unwrap();
+    assert_eq!(&decompressed[..size], &data[..]);
+
+    // Dictionary improves compression ratio of small similar inputs
+    let mut without_dict = create_codec(CodecType::ZSTD).unwrap().unwrap();
+    assert!(compressed.len() < without_dict.compress(&data).unwrap().len());
+
+    // Data cannot be decompressed without dictionary
+    let mut decompressed = Vec::new();
+    assert!(without_dict.decompress(&compressed, &mut decompressed).is_err());
+  }
+
+  #[test]
+  fn test_compression_pool() {
+    let pool = CompressionPool::new(2, 3).unwrap();
+    assert_eq!(pool.num_threads(), 2);
+    assert_eq!(pool.queue_size(), 3);
+
+    let inputs: Vec<Vec<u8>> = (0..10).map(|i| random_bytes(100 * (i + 1))).collect();
+    let pending: Vec<PendingCompression> = inputs.iter()
+      .map(|data| pool.submit(CodecType::GZIP, None, data.clone()).unwrap())
+      .collect();
+    let mut codec = create_codec(CodecType::GZIP).unwrap().unwrap();
+    for (data, pending) in inputs.iter().zip(pending.into_iter()) {
+      assert_eq!(pending.wait().unwrap(), codec.compress(data).unwrap());
+    }
+
+    // Uncompressed buffers are returned as is
+    let mut pending = pool.submit(CodecType::UNCOMPRESSED, None, vec![1, 2, 3]).unwrap();
+    while !pending.is_ready() {}
+    assert_eq!(pending.wait().unwrap(), vec![1, 2, 3]);
+
+    assert!(CompressionPool::new(0, 1).is_err());
+  }
+
+  #[test]
+  fn test_codec_dictionary_ignored() {
+    let dictionary = Some(Arc::new(vec![1, 2, 3]));
+    let codec = create_codec_with_dictionary(CodecType::SNAPPY, dictionary).unwrap();
+    assert!(codec.is_some());
+    let codec = create_codec_with_dictionary(CodecType::UNCOMPRESSED, None).unwrap();
+    assert!(codec.is_none());
+  }
 }
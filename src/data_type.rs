@@ -18,9 +18,11 @@
 //! Data types that connect Parquet physical types with their Rust-specific
 //! representations.
 
+use std::cmp::Ordering;
+use std::fmt;
 use std::mem;
 
-use basic::Type;
+use basic::{LogicalType, SortOrder, Type};
 use rand::{Rand, Rng};
 use util::memory::{ByteBuffer, ByteBufferPtr};
 
@@ -50,6 +52,49 @@ impl Int96 {
     assert_eq!(v.len(), 3);
     self.value = Some(v);
   }
+
+  /// Creates INT96 timestamp from milliseconds since Unix epoch.
+  pub fn from_millis(millis: i64) -> Self {
+    Self::from_nanos(millis * 1_000_000)
+  }
+
+  /// Creates INT96 timestamp from nanoseconds since Unix epoch.
+  ///
+  /// Value is stored as nanoseconds of the day in the first 8 bytes, followed by
+  /// Julian day in the last 4 bytes, as written by Hive, Impala and Spark.
+  pub fn from_nanos(nanos: i64) -> Self {
+    let julian_to_unix_epoch_days: i64 = 2_440_588;
+    let nano_seconds_in_a_day: i64 = 86_400_000_000_000;
+
+    let mut days = nanos / nano_seconds_in_a_day;
+    let mut nanoseconds = nanos % nano_seconds_in_a_day;
+    // Timestamps before epoch belong to the previous day
+    if nanoseconds < 0 {
+      days -= 1;
+      nanoseconds += nano_seconds_in_a_day;
+    }
+    let julian_day = (days + julian_to_unix_epoch_days) as u32;
+    let nanoseconds = nanoseconds as u64;
+    Int96::from(vec![nanoseconds as u32, (nanoseconds >> 32) as u32, julian_day])
+  }
+
+  /// Returns timestamp in nanoseconds since Unix epoch.
+  pub fn to_nanos(&self) -> i64 {
+    let julian_to_unix_epoch_days: i64 = 2_440_588;
+    let nano_seconds_in_a_day: i64 = 86_400_000_000_000;
+
+    let data = self.data();
+    let days = data[2] as i64 - julian_to_unix_epoch_days;
+    let nanoseconds = ((data[1] as i64) << 32) + data[0] as i64;
+    days * nano_seconds_in_a_day + nanoseconds
+  }
+
+  /// Returns timestamp in milliseconds since Unix epoch, rounded down.
+  pub fn to_millis(&self) -> i64 {
+    let nanos = self.to_nanos();
+    let millis = nanos / 1_000_000;
+    if nanos % 1_000_000 < 0 { millis - 1 } else { millis }
+  }
 }
 
 impl Default for Int96 {
@@ -64,6 +109,14 @@ impl PartialEq for Int96 {
   }
 }
 
+impl PartialOrd for Int96 {
+  /// Compares values as timestamps: by Julian day first, then by nanoseconds of the day.
+  fn partial_cmp(&self, other: &Int96) -> Option<Ordering> {
+    let (a, b) = (self.data(), other.data());
+    (a[2], a[1], a[0]).partial_cmp(&(b[2], b[1], b[0]))
+  }
+}
+
 impl From<Vec<u32>> for Int96 {
   fn from(buf: Vec<u32>) -> Int96 {
     assert_eq!(buf.len(), 3);
@@ -118,6 +171,100 @@ impl ByteArray {
     assert!(self.data.is_some());
     Self::from(self.data.as_ref().unwrap().range(start, len))
   }
+
+  /// Creates byte array of `length` bytes from unscaled DECIMAL value, encoded as
+  /// big-endian two's complement, as stored in FIXED_LEN_BYTE_ARRAY columns.
+  /// Returns `None` if value does not fit into `length` bytes.
+  pub fn from_decimal(value: i128, length: usize) -> Option<Self> {
+    let bytes = value.to_be_bytes();
+    let sign_byte = if value < 0 { 0xFF } else { 0 };
+    if length < bytes.len() {
+      // Truncated bytes must only extend the sign of the remaining bytes
+      let (prefix, suffix) = bytes.split_at(bytes.len() - length);
+      let is_sign_extended = prefix.iter().all(|&b| b == sign_byte) &&
+        (length > 0 && (suffix[0] as i8 >= 0) == (value >= 0));
+      if !is_sign_extended {
+        return None;
+      }
+      return Some(Self::from(suffix.to_vec()));
+    }
+    let mut buf = vec![sign_byte; length - bytes.len()];
+    buf.extend_from_slice(&bytes);
+    Some(Self::from(buf))
+  }
+
+  /// Returns unscaled DECIMAL value of bytes encoded as big-endian two's complement,
+  /// as stored in FIXED_LEN_BYTE_ARRAY and BYTE_ARRAY columns. Returns `None` if byte
+  /// array is empty or longer than 16 bytes.
+  pub fn to_decimal(&self) -> Option<i128> {
+    let data = self.data();
+    if data.is_empty() || data.len() > 16 {
+      return None;
+    }
+    let sign_byte = if (data[0] as i8) < 0 { 0xFF } else { 0 };
+    let mut bytes = [sign_byte; 16];
+    bytes[16 - data.len()..].copy_from_slice(data);
+    Some(i128::from_be_bytes(bytes))
+  }
+}
+
+/// DECIMAL value, represented by unscaled value with precision and scale of the
+/// column, i.e. the value is `unscaled * 10^(-scale)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decimal {
+  unscaled: i128,
+  precision: i32,
+  scale: i32
+}
+
+impl Decimal {
+  /// Creates DECIMAL value from unscaled value, precision and scale.
+  pub fn new(unscaled: i128, precision: i32, scale: i32) -> Self {
+    Self {
+      unscaled: unscaled,
+      precision: precision,
+      scale: scale
+    }
+  }
+
+  /// Returns unscaled value.
+  pub fn unscaled(&self) -> i128 {
+    self.unscaled
+  }
+
+  /// Returns precision, the maximum number of digits.
+  pub fn precision(&self) -> i32 {
+    self.precision
+  }
+
+  /// Returns scale, the number of digits after the decimal point.
+  pub fn scale(&self) -> i32 {
+    self.scale
+  }
+}
+
+impl fmt::Display for Decimal {
+  /// Formats value with `scale` digits after the decimal point, e.g. `-1.50`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let digits = self.unscaled.to_string();
+    let (sign, digits) = if self.unscaled < 0 {
+      ("-", &digits[1..])
+    } else {
+      ("", &digits[..])
+    };
+    if self.scale <= 0 {
+      let zeros = if self.unscaled == 0 { 0 } else { -self.scale as usize };
+      return write!(f, "{}{}{}", sign, digits, "0".repeat(zeros));
+    }
+    let scale = self.scale as usize;
+    let digits = if digits.len() <= scale {
+      format!("{}{}", "0".repeat(scale + 1 - digits.len()), digits)
+    } else {
+      digits.to_string()
+    };
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    write!(f, "{}{}.{}", sign, integer, fraction)
+  }
 }
 
 impl From<Vec<u8>> for ByteArray {
@@ -159,6 +306,13 @@ impl PartialEq for ByteArray {
   }
 }
 
+impl PartialOrd for ByteArray {
+  /// Compares values byte-wise as unsigned bytes.
+  fn partial_cmp(&self, other: &ByteArray) -> Option<Ordering> {
+    self.data().partial_cmp(other.data())
+  }
+}
+
 impl Rand for ByteArray {
   fn rand<R: Rng>(rng: &mut R) -> Self {
     let mut result = ByteArray::new();
@@ -236,14 +390,19 @@ impl AsBytes for str {
 /// Contains the Parquet physical type information as well as the Rust primitive type
 /// presentation.
 pub trait DataType {
-  type T: ::std::cmp::PartialEq + ::std::fmt::Debug + ::std::default::Default
-    + ::std::clone::Clone + Rand + AsBytes;
+  type T: ::std::cmp::PartialEq + ::std::cmp::PartialOrd + ::std::fmt::Debug
+    + ::std::default::Default + ::std::clone::Clone + Rand + AsBytes;
 
   /// Returns Parquet physical type.
   fn get_physical_type() -> Type;
 
   /// Returns size in bytes for Rust representation of the physical type.
   fn get_type_size() -> usize;
+
+  /// Returns sort order of values of this physical type annotated with `logical_type`.
+  fn get_sort_order(logical_type: LogicalType) -> SortOrder {
+    SortOrder::from_types(Self::get_physical_type(), logical_type)
+  }
 }
 
 macro_rules! make_type {
@@ -312,4 +471,104 @@ mod tests {
     let ba = ByteArray::from(vec![1, 2, 3]);
     assert_eq!(ba.as_bytes(), &[1, 2, 3]);
   }
+
+  #[test]
+  fn test_value_ordering() {
+    assert!(ByteArray::from("a") < ByteArray::from("b"));
+    assert!(ByteArray::from("a") < ByteArray::from("ab"));
+    assert!(ByteArray::from("z") < ByteArray::from(vec![0xff]));
+    assert!(Int96::from(vec![0, 0, 2]) > Int96::from(vec![5, 5, 1]));
+    assert!(Int96::from(vec![1, 3, 1]) > Int96::from(vec![2, 2, 1]));
+    assert!(Int96::from(vec![2, 2, 1]) > Int96::from(vec![1, 2, 1]));
+  }
+
+  #[test]
+  fn test_int96_from_timestamp() {
+    assert_eq!(Int96::from_millis(0), Int96::from(vec![0, 0, 2440588]));
+    assert_eq!(Int96::from_millis(1), Int96::from(vec![1_000_000, 0, 2440588]));
+    assert_eq!(
+      Int96::from_nanos(86_400_000_000_000 + 5),
+      Int96::from(vec![5, 0, 2440589])
+    );
+    // 1 ms before epoch is the last millisecond of the previous day
+    assert_eq!(
+      Int96::from_millis(-1),
+      Int96::from(vec![2436873664, 20116, 2440587])
+    );
+    assert_eq!(
+      Int96::from_millis(1_238_544_000_000),
+      Int96::from(vec![0, 0, 2454923])
+    );
+  }
+
+  #[test]
+  fn test_int96_to_timestamp() {
+    assert_eq!(Int96::from(vec![0, 0, 2440588]).to_millis(), 0);
+    assert_eq!(Int96::from(vec![5, 0, 2440589]).to_nanos(), 86_400_000_000_005);
+    assert_eq!(Int96::from_nanos(-1).to_nanos(), -1);
+    assert_eq!(Int96::from_nanos(-1).to_millis(), -1);
+    assert_eq!(Int96::from_millis(1_238_544_000_000).to_millis(), 1_238_544_000_000);
+  }
+
+  #[test]
+  fn test_byte_array_from_decimal() {
+    let from_decimal = |value: i128, length: usize| {
+      ByteArray::from_decimal(value, length).map(|bytes| bytes.data().to_vec())
+    };
+    assert_eq!(from_decimal(1, 1), Some(vec![1]));
+    assert_eq!(from_decimal(-1, 2), Some(vec![0xFF, 0xFF]));
+    assert_eq!(from_decimal(127, 1), Some(vec![0x7F]));
+    assert_eq!(from_decimal(128, 1), None);
+    assert_eq!(from_decimal(128, 2), Some(vec![0, 0x80]));
+    assert_eq!(from_decimal(-128, 1), Some(vec![0x80]));
+    assert_eq!(from_decimal(-129, 1), None);
+    assert_eq!(from_decimal(0, 0), None);
+
+    let mut expected = vec![0xFF; 20];
+    expected[19] = 0xFE;
+    assert_eq!(from_decimal(-2, 20), Some(expected));
+    let mut expected = vec![0xFF; 16];
+    expected[0] = 0x7F;
+    assert_eq!(from_decimal(i128::max_value(), 16), Some(expected));
+  }
+
+  #[test]
+  fn test_byte_array_to_decimal() {
+    assert_eq!(ByteArray::from(vec![1]).to_decimal(), Some(1));
+    assert_eq!(ByteArray::from(vec![0xFF, 0xFE]).to_decimal(), Some(-2));
+    assert_eq!(ByteArray::from(vec![0, 0x80]).to_decimal(), Some(128));
+    assert_eq!(ByteArray::from(vec![0x80]).to_decimal(), Some(-128));
+    assert_eq!(ByteArray::from(vec![]).to_decimal(), None);
+    assert_eq!(ByteArray::from(vec![0; 17]).to_decimal(), None);
+
+    for &value in &[0, 1, -1, 123_456_789, -987_654_321_012, i128::min_value()] {
+      let bytes = ByteArray::from_decimal(value, 16).unwrap();
+      assert_eq!(bytes.to_decimal(), Some(value));
+    }
+  }
+
+  #[test]
+  fn test_decimal_display() {
+    assert_eq!(Decimal::new(150, 5, 2).to_string(), "1.50");
+    assert_eq!(Decimal::new(-150, 5, 2).to_string(), "-1.50");
+    assert_eq!(Decimal::new(-5, 5, 3).to_string(), "-0.005");
+    assert_eq!(Decimal::new(0, 5, 2).to_string(), "0.00");
+    assert_eq!(Decimal::new(42, 5, 0).to_string(), "42");
+    assert_eq!(Decimal::new(42, 5, -2).to_string(), "4200");
+  }
+
+  #[test]
+  fn test_data_type_sort_order() {
+    assert_eq!(BoolType::get_sort_order(LogicalType::NONE), SortOrder::UNSIGNED);
+    assert_eq!(Int32Type::get_sort_order(LogicalType::NONE), SortOrder::SIGNED);
+    assert_eq!(Int32Type::get_sort_order(LogicalType::UINT_32), SortOrder::UNSIGNED);
+    assert_eq!(Int64Type::get_sort_order(LogicalType::UINT_64), SortOrder::UNSIGNED);
+    assert_eq!(Int96Type::get_sort_order(LogicalType::NONE), SortOrder::UNDEFINED);
+    assert_eq!(DoubleType::get_sort_order(LogicalType::NONE), SortOrder::SIGNED);
+    assert_eq!(ByteArrayType::get_sort_order(LogicalType::UTF8), SortOrder::UNSIGNED);
+    assert_eq!(
+      FixedLenByteArrayType::get_sort_order(LogicalType::DECIMAL),
+      SortOrder::SIGNED
+    );
+  }
 }
@@ -20,19 +20,27 @@
 use std::cmp;
 use std::marker::PhantomData;
 use std::mem;
-use std::slice::from_raw_parts_mut;
+
+use byteorder::{ByteOrder, LittleEndian};
 
 use super::rle::RleDecoder;
 use basic::*;
+use column::page::Page;
 use data_type::*;
 use errors::{ParquetError, Result};
 use schema::types::ColumnDescPtr;
-use util::bit_util::BitReader;
+use util::bit_util::{
+  get_array_bit, set_array_bit, unset_array_bit, BitReader, MsbBitReader
+};
 use util::memory::{ByteBuffer, ByteBufferPtr};
 
 // ----------------------------------------------------------------------
 // Decoders
 
+/// Maximum number of values decoded into a scratch buffer at once, e.g. by the default
+/// implementations of `Decoder::skip` and `Decoder::get_bitmap`.
+const SCRATCH_BATCH_SIZE: usize = 1024;
+
 /// A Parquet decoder for the data type `T`.
 pub trait Decoder<T: DataType> {
   /// Sets the data to decode to be `data`, which should contain `num_values` of values
@@ -46,6 +54,93 @@ pub trait Decoder<T: DataType> {
   /// unless the remaining number of values is less than `buffer.len()`.
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize>;
 
+  /// Consumes values from this decoder and writes them into `buffer` at positions whose
+  /// bits are set in `valid_bits`, so that values land at their positions in a batch
+  /// with `null_count` nulls. Slots of nulls contain unspecified values afterwards.
+  ///
+  /// `valid_bits` must have at least `buffer.len()` bits, of which `buffer.len() -
+  /// null_count` are set. Returns the number of slots filled, which is `buffer.len()`,
+  /// or an error if fewer values are left in this decoder.
+  ///
+  /// The default implementation decodes values densely into the front of `buffer` and
+  /// moves them to their positions, decoders override it when values can be written
+  /// to their positions directly.
+  fn get_spaced(
+    &mut self,
+    buffer: &mut [T::T],
+    null_count: usize,
+    valid_bits: &[u8]
+  ) -> Result<usize> {
+    check_spaced(buffer.len(), null_count, valid_bits)?;
+    let num_values = buffer.len();
+    let values_to_read = num_values - null_count;
+    let values_read = self.get(&mut buffer[..values_to_read])?;
+    if values_read != values_to_read {
+      return Err(eof_err!(
+        "Expected to decode {} values, but only {} values left",
+        values_to_read,
+        values_read
+      ));
+    }
+    if null_count == 0 {
+      return Ok(num_values);
+    }
+    // Values are moved starting from the last one, so that no value is overwritten
+    // before it is moved
+    let mut values_to_move = values_read;
+    for i in (0..num_values).rev() {
+      if values_to_move == 0 {
+        break;
+      }
+      if get_array_bit(valid_bits, i) {
+        values_to_move -= 1;
+        buffer.swap(i, values_to_move);
+      }
+    }
+    Ok(num_values)
+  }
+
+  /// Skips at most `num_values` values without returning them.
+  ///
+  /// Returns the actual number of values skipped, which should be equal to `num_values`
+  /// unless the remaining number of values is less than `num_values`.
+  ///
+  /// The default implementation decodes values into a scratch buffer, decoders override
+  /// it when values can be skipped without decoding them.
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let mut buffer = vec![T::T::default(); cmp::min(num_values, SCRATCH_BATCH_SIZE)];
+    let mut values_skipped = 0;
+    while values_skipped < num_values {
+      let batch_size = cmp::min(buffer.len(), num_values - values_skipped);
+      let values_read = self.get(&mut buffer[..batch_size])?;
+      if values_read == 0 {
+        break;
+      }
+      values_skipped += values_read;
+    }
+    Ok(values_skipped)
+  }
+
+  /// Consumes at most `num_values` boolean values from this decoder and writes them as
+  /// packed bits into `bitmap` starting at bit `offset`, least-significant bit first,
+  /// as in Arrow validity and boolean value buffers. Other bits of `bitmap` are not
+  /// changed.
+  ///
+  /// Returns the number of values decoded, which should be equal to `num_values` unless
+  /// the remaining number of values is less than `num_values`. Returns an error if
+  /// values are not boolean, or if `bitmap` has fewer than `offset + num_values` bits.
+  ///
+  /// The default implementation decodes values into a scratch buffer and packs them,
+  /// decoders override it when values can be unpacked directly into the bitmap.
+  fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    get_bitmap_from_values(self, bitmap, offset, num_values)
+  }
+
   /// Returns the number of values left in this decoder stream.
   fn values_left(&self) -> usize;
 
@@ -55,12 +150,20 @@ pub trait Decoder<T: DataType> {
 
 /// Gets a decoder for the column descriptor `descr` and encoding type `encoding`.
 ///
-/// NOTE: the primitive type in `descr` MUST match the data type `T`, otherwise
-/// disastrous consequence could occur.
+/// Returns an error if the physical type in `descr` does not match the data type `T`.
 pub fn get_decoder<T: DataType>(
   descr: ColumnDescPtr,
   encoding: Encoding
 ) -> Result<Box<Decoder<T>>> where T: 'static {
+  if descr.physical_type() != T::get_physical_type() {
+    return Err(general_err!(
+      "Cannot create decoder of type {} for column {} of type {}",
+      T::get_physical_type(),
+      descr.path().string(),
+      descr.physical_type()
+    ));
+  }
+
   let decoder: Box<Decoder<T>> = match encoding {
     Encoding::PLAIN => {
       Box::new(PlainDecoder::new(descr.type_length()))
@@ -71,6 +174,9 @@ pub fn get_decoder<T: DataType>(
     Encoding::RLE => {
       Box::new(RleValueDecoder::new())
     },
+    Encoding::BIT_PACKED if T::get_physical_type() == Type::BOOLEAN => {
+      Box::new(BitPackedValueDecoder::new())
+    },
     Encoding::DELTA_BINARY_PACKED => {
       Box::new(DeltaBitPackDecoder::new())
     },
@@ -85,6 +191,129 @@ pub fn get_decoder<T: DataType>(
   Ok(decoder)
 }
 
+/// Gets a dictionary decoder for the column descriptor `descr` with values of the
+/// dictionary page `dict_page`, which are decoded with PLAIN encoding. Data of each
+/// dictionary encoded data page is then set with `set_data`.
+///
+/// Returns an error if `dict_page` is not a dictionary page, encoding of the page is
+/// not supported, or the physical type in `descr` does not match the data type `T`.
+pub fn get_dict_decoder<T: DataType>(
+  descr: ColumnDescPtr,
+  dict_page: &Page
+) -> Result<DictDecoder<T>> where T: 'static {
+  let (buf, num_values, encoding) = match *dict_page {
+    Page::DictionaryPage { ref buf, num_values, encoding, .. } => {
+      (buf, num_values, encoding)
+    },
+    _ => {
+      return Err(general_err!(
+        "Expected dictionary page, found {}",
+        dict_page.page_type()
+      ))
+    }
+  };
+  match encoding {
+    Encoding::PLAIN | Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY => {},
+    e => return Err(nyi_err!("Invalid/Unsupported encoding type for dictionary: {}", e))
+  }
+
+  let mut dictionary = get_decoder::<T>(descr, Encoding::PLAIN)?;
+  dictionary.set_data(buf.clone(), num_values as usize)?;
+  let mut decoder = DictDecoder::new();
+  decoder.set_dict(dictionary)?;
+  Ok(decoder)
+}
+
+/// Returns error for a decoder that is used before its data is set.
+fn not_initialized_err(decoder: &str) -> ParquetError {
+  general_err!("{} is not initialized, set_data() must be called first", decoder)
+}
+
+/// Checks arguments of `Decoder::get_spaced` for a buffer of `num_values` slots.
+fn check_spaced(num_values: usize, null_count: usize, valid_bits: &[u8]) -> Result<()> {
+  if null_count > num_values {
+    return Err(general_err!(
+      "Null count {} exceeds the number of values {}",
+      null_count,
+      num_values
+    ));
+  }
+  if valid_bits.len() * 8 < num_values {
+    return Err(general_err!(
+      "Validity bitmap of {} bytes is too short for {} values",
+      valid_bits.len(),
+      num_values
+    ));
+  }
+  Ok(())
+}
+
+/// Checks arguments of `Decoder::get_bitmap` for values of type `T`.
+fn check_bitmap<T: DataType>(
+  bitmap: &[u8],
+  offset: usize,
+  num_values: usize
+) -> Result<()> {
+  if T::get_physical_type() != Type::BOOLEAN {
+    return Err(general_err!(
+      "Cannot decode values of type {} into a bitmap",
+      T::get_physical_type()
+    ));
+  }
+  if bitmap.len() * 8 < offset + num_values {
+    return Err(general_err!(
+      "Bitmap of {} bytes is too short for {} values at bit offset {}",
+      bitmap.len(),
+      num_values,
+      offset
+    ));
+  }
+  Ok(())
+}
+
+/// Decodes at most `num_values` values with `decoder` into a scratch buffer, and packs
+/// them into `bitmap` starting at bit `offset`. See `Decoder::get_bitmap`.
+fn get_bitmap_from_values<T: DataType, D: Decoder<T> + ?Sized>(
+  decoder: &mut D,
+  bitmap: &mut [u8],
+  offset: usize,
+  num_values: usize
+) -> Result<usize> {
+  check_bitmap::<T>(bitmap, offset, num_values)?;
+  let mut buffer = vec![T::T::default(); cmp::min(num_values, SCRATCH_BATCH_SIZE)];
+  let mut values_read = 0;
+  while values_read < num_values {
+    let batch_size = cmp::min(buffer.len(), num_values - values_read);
+    let batch_read = decoder.get(&mut buffer[..batch_size])?;
+    if batch_read == 0 {
+      break;
+    }
+    for (i, value) in buffer[..batch_read].iter().enumerate() {
+      // Boolean values are stored as a single byte
+      if value.as_bytes()[0] != 0 {
+        set_array_bit(bitmap, offset + values_read + i);
+      } else {
+        unset_array_bit(bitmap, offset + values_read + i);
+      }
+    }
+    values_read += batch_read;
+  }
+  Ok(values_read)
+}
+
+/// Checks that bit width of values read from a stream fits into values of `T`.
+fn check_bit_width<T>(bit_width: u8, name: &str) -> Result<()> {
+  if bit_width as usize > mem::size_of::<T>() * 8 {
+    return Err(general_err!(
+      "Invalid bit width {} of {}, maximum is {}",
+      bit_width,
+      name,
+      mem::size_of::<T>() * 8
+    ));
+  }
+  Ok(())
+}
+
 // ----------------------------------------------------------------------
 // PLAIN Decoding
 
@@ -124,6 +353,45 @@ impl<T: DataType> PlainDecoder<T> {
       _phantom: PhantomData
     }
   }
+
+  /// Skips at most `num_values` values of `value_size` bytes each by advancing the
+  /// start of the next value.
+  #[inline]
+  fn skip_fixed_size(&mut self, num_values: usize, value_size: usize) -> Result<usize> {
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let num_values = cmp::min(num_values, self.num_values);
+    let bytes_to_skip = value_size.saturating_mul(num_values);
+    if data.len() - self.start < bytes_to_skip {
+      return Err(eof_err!("Not enough bytes to skip"));
+    }
+    self.start += bytes_to_skip;
+    self.num_values -= num_values;
+    Ok(num_values)
+  }
+
+  /// Decodes at most `buffer.len()` fixed-size values, which are converted from
+  /// little-endian bytes with `read_into`. Byte order functions only copy bytes on
+  /// little-endian targets, and do not require data to be aligned.
+  #[inline]
+  fn get_fixed_size<V>(
+    &mut self,
+    buffer: &mut [V],
+    read_into: fn(&[u8], &mut [V])
+  ) -> Result<usize> {
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let num_values = cmp::min(buffer.len(), self.num_values);
+    let bytes_left = data.len() - self.start;
+    let bytes_to_decode = mem::size_of::<V>() * num_values;
+    if bytes_left < bytes_to_decode {
+      return Err(eof_err!("Not enough bytes to decode"));
+    }
+    let end = self.start + bytes_to_decode;
+    read_into(&data.as_ref()[self.start..end], &mut buffer[..num_values]);
+    self.start = end;
+    self.num_values -= num_values;
+
+    Ok(num_values)
+  }
 }
 
 impl<T: DataType> Decoder<T> for PlainDecoder<T> {
@@ -146,32 +414,48 @@ impl<T: DataType> Decoder<T> for PlainDecoder<T> {
   }
 
   #[inline]
-  default fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.data.is_some());
+  default fn get(&mut self, _: &mut [T::T]) -> Result<usize> {
+    Err(general_err!("PlainDecoder does not support type {}", T::get_physical_type()))
+  }
 
-    let data = self.data.as_mut().unwrap();
-    let num_values = cmp::min(buffer.len(), self.num_values);
-    let bytes_left = data.len() - self.start;
-    let bytes_to_decode = mem::size_of::<T::T>() * num_values;
-    if bytes_left < bytes_to_decode {
-      return Err(eof_err!("Not enough bytes to decode"));
-    }
-    let raw_buffer: &mut [u8] = unsafe {
-      from_raw_parts_mut(buffer.as_ptr() as *mut u8, bytes_to_decode)
-    };
-    raw_buffer.copy_from_slice(data.range(self.start, bytes_to_decode).as_ref());
-    self.start += bytes_to_decode;
-    self.num_values -= num_values;
+  #[inline]
+  default fn skip(&mut self, num_values: usize) -> Result<usize> {
+    self.skip_fixed_size(num_values, mem::size_of::<T::T>())
+  }
 
-    Ok(num_values)
+  #[inline]
+  default fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    get_bitmap_from_values(self, bitmap, offset, num_values)
   }
 }
 
+/// Implements PLAIN decoding of a numeric type with a byte order function that reads
+/// little-endian values.
+macro_rules! plain_decoder_impl {
+  ($ty:ty, $read_into:ident) => {
+    impl Decoder<$ty> for PlainDecoder<$ty> {
+      #[inline]
+      fn get(&mut self, buffer: &mut [<$ty as DataType>::T]) -> Result<usize> {
+        self.get_fixed_size(buffer, LittleEndian::$read_into)
+      }
+    }
+  }
+}
+
+plain_decoder_impl!(Int32Type, read_i32_into);
+plain_decoder_impl!(Int64Type, read_i64_into);
+plain_decoder_impl!(FloatType, read_f32_into);
+plain_decoder_impl!(DoubleType, read_f64_into);
+
 impl Decoder<Int96Type> for PlainDecoder<Int96Type> {
   fn get(&mut self, buffer: &mut [Int96]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_mut().unwrap();
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let data = data.as_ref();
     let num_values = cmp::min(buffer.len(), self.num_values);
     let bytes_left = data.len() - self.start;
     let bytes_to_decode = 12 * num_values;
@@ -179,20 +463,19 @@ impl Decoder<Int96Type> for PlainDecoder<Int96Type> {
       return Err(eof_err!("Not enough bytes to decode"));
     }
     for i in 0..num_values {
-      buffer[i].set_data(
-        unsafe {
-          // TODO: avoid this copying
-          let slice = ::std::slice::from_raw_parts(
-            data.range(self.start, 12).as_ref().as_ptr() as *mut u32, 3);
-          Vec::from(slice)
-        }
-      );
+      let mut value = vec![0; 3];
+      LittleEndian::read_u32_into(&data[self.start..self.start + 12], &mut value);
+      buffer[i].set_data(value);
       self.start += 12;
     }
     self.num_values -= num_values;
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    self.skip_fixed_size(num_values, 12)
+  }
 }
 
 impl Decoder<BoolType> for PlainDecoder<BoolType> {
@@ -203,10 +486,36 @@ impl Decoder<BoolType> for PlainDecoder<BoolType> {
   }
 
   fn get(&mut self, buffer: &mut [bool]) -> Result<usize> {
-    assert!(self.bit_reader.is_some());
+    let bit_reader =
+      self.bit_reader.as_mut().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let num_values = cmp::min(buffer.len(), self.num_values);
+    let values_read = bit_reader.get_batch::<bool>(&mut buffer[..num_values], 1)?;
+    self.num_values -= values_read;
+
+    Ok(values_read)
+  }
 
-    let bit_reader = self.bit_reader.as_mut().unwrap();
-    let values_read = bit_reader.get_batch::<bool>(buffer, 1);
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let bit_reader =
+      self.bit_reader.as_mut().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let num_values = cmp::min(num_values, self.num_values);
+    let values_skipped = bit_reader.skip(num_values, 1)?;
+    self.num_values -= values_skipped;
+
+    Ok(values_skipped)
+  }
+
+  fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    check_bitmap::<BoolType>(bitmap, offset, num_values)?;
+    let bit_reader =
+      self.bit_reader.as_mut().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let num_values = cmp::min(num_values, self.num_values);
+    let values_read = bit_reader.get_bitmap(bitmap, offset, num_values)?;
     self.num_values -= values_read;
 
     Ok(values_read)
@@ -215,15 +524,15 @@ impl Decoder<BoolType> for PlainDecoder<BoolType> {
 
 impl Decoder<ByteArrayType> for PlainDecoder<ByteArrayType> {
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_mut().unwrap();
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
-      let len: usize = read_num_bytes!(
-        u32, 4, data.start_from(self.start).as_ref()) as usize;
+      if data.len() < self.start + mem::size_of::<u32>() {
+        return Err(eof_err!("Not enough bytes to decode"));
+      }
+      let len = LittleEndian::read_u32(&data.as_ref()[self.start..]) as usize;
       self.start += mem::size_of::<u32>();
-      if data.len() < self.start + len {
+      if data.len() - self.start < len {
         return Err(eof_err!("Not enough bytes to decode"));
       }
       buffer[i].set_data(data.range(self.start, len));
@@ -233,18 +542,35 @@ impl Decoder<ByteArrayType> for PlainDecoder<ByteArrayType> {
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
+    let data = data.as_ref();
+    let num_values = cmp::min(num_values, self.num_values);
+    for _ in 0..num_values {
+      if data.len() < self.start + mem::size_of::<u32>() {
+        return Err(eof_err!("Not enough bytes to skip"));
+      }
+      let len = LittleEndian::read_u32(&data[self.start..]) as usize;
+      self.start += mem::size_of::<u32>();
+      if data.len() - self.start < len {
+        return Err(eof_err!("Not enough bytes to skip"));
+      }
+      self.start += len;
+    }
+    self.num_values -= num_values;
+
+    Ok(num_values)
+  }
 }
 
 impl Decoder<FixedLenByteArrayType> for PlainDecoder<FixedLenByteArrayType> {
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-    assert!(self.type_length > 0);
-
-    let data = self.data.as_mut().unwrap();
-    let type_length = self.type_length as usize;
+    let type_length = self.fixed_type_length()?;
+    let data = self.data.as_ref().ok_or_else(|| not_initialized_err("PlainDecoder"))?;
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
-      if data.len() < self.start + type_length {
+      if data.len() - self.start < type_length {
         return Err(eof_err!("Not enough bytes to decode"));
       }
       buffer[i].set_data(data.range(self.start, type_length));
@@ -254,6 +580,25 @@ impl Decoder<FixedLenByteArrayType> for PlainDecoder<FixedLenByteArrayType> {
 
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let type_length = self.fixed_type_length()?;
+    self.skip_fixed_size(num_values, type_length)
+  }
+}
+
+impl PlainDecoder<FixedLenByteArrayType> {
+  /// Returns type length of FIXED_LEN_BYTE_ARRAY values, which must be positive.
+  #[inline]
+  fn fixed_type_length(&self) -> Result<usize> {
+    if self.type_length <= 0 {
+      return Err(general_err!(
+        "Invalid type length {} of FIXED_LEN_BYTE_ARRAY values",
+        self.type_length
+      ));
+    }
+    Ok(self.type_length as usize)
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -289,33 +634,146 @@ impl<T: DataType> DictDecoder<T> {
   }
 
   /// Decodes and sets values for dictionary using `decoder` decoder.
+  ///
+  /// Returns an error if `decoder` has fewer values than it reports.
   pub fn set_dict(&mut self, mut decoder: Box<Decoder<T>>) -> Result<()> {
     let num_values = decoder.values_left();
-    self.dictionary.resize(num_values, T::T::default());
-    let _ = decoder.get(&mut self.dictionary)?;
+    self.dictionary.clear();
+    self.has_dictionary = false;
+    // Values are decoded in batches, so that a corrupt number of values does not
+    // allocate a dictionary larger than the page
+    let mut buffer = vec![T::T::default(); cmp::min(num_values, SCRATCH_BATCH_SIZE)];
+    while self.dictionary.len() < num_values {
+      let batch_size = cmp::min(buffer.len(), num_values - self.dictionary.len());
+      let values_read = decoder.get(&mut buffer[..batch_size])?;
+      if values_read == 0 {
+        return Err(eof_err!(
+          "Expected {} dictionary values, but only decoded {}",
+          num_values,
+          self.dictionary.len()
+        ));
+      }
+      self.dictionary.extend_from_slice(&buffer[..values_read]);
+    }
     self.has_dictionary = true;
     Ok(())
   }
+
+  /// Returns RLE decoder of indices, if both dictionary and data have been set.
+  #[inline]
+  fn rle_decoder(&mut self) -> Result<&mut RleDecoder> {
+    if !self.has_dictionary {
+      return Err(general_err!("DictDecoder has no dictionary, call set_dict() first"));
+    }
+    self.rle_decoder.as_mut().ok_or_else(|| not_initialized_err("DictDecoder"))
+  }
+
+  /// Returns values of the dictionary, which is empty until `set_dict` is called.
+  ///
+  /// Together with [`get_indices`](`DictDecoder::get_indices`) it allows to process
+  /// dictionary encoded data without materializing every value, e.g. to evaluate a
+  /// predicate once per dictionary value.
+  pub fn dictionary(&self) -> &[T::T] {
+    &self.dictionary
+  }
+
+  /// Decodes at most `indices.len()` dictionary indices into `indices` instead of
+  /// looking up their values, and returns the number of indices decoded.
+  ///
+  /// Returns an error if an index is out of bounds of the dictionary.
+  pub fn get_indices(&mut self, indices: &mut [i32]) -> Result<usize> {
+    let num_values = cmp::min(indices.len(), self.num_values);
+    let indices_read = self.rle_decoder()?.get_batch(&mut indices[..num_values])?;
+    self.num_values -= indices_read;
+    for &index in &indices[..indices_read] {
+      if index < 0 || index as usize >= self.dictionary.len() {
+        return Err(general_err!(
+          "Dictionary index {} out of bounds, dictionary has {} values",
+          index,
+          self.dictionary.len()
+        ));
+      }
+    }
+    Ok(indices_read)
+  }
 }
 
 impl<T: DataType> Decoder<T> for DictDecoder<T> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
-    // First byte in `data` is bit width
-    let bit_width = data.as_ref()[0];
+    // First byte in `data` is bit width, which may be missing if there are no values
+    let bit_width = match data.as_ref().first() {
+      Some(bit_width) => *bit_width,
+      None if num_values == 0 => 0,
+      None => return Err(eof_err!("Not enough bytes to decode bit width of indices"))
+    };
+    check_bit_width::<i32>(bit_width, "dictionary indices")?;
     let mut rle_decoder = RleDecoder::new(bit_width);
-    rle_decoder.set_data(data.start_from(1));
+    rle_decoder.set_data(data.start_from(cmp::min(1, data.len())));
     self.num_values = num_values;
     self.rle_decoder = Some(rle_decoder);
     Ok(())
   }
 
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.rle_decoder.is_some());
-    assert!(self.has_dictionary, "Must call set_dict() first!");
-
-    let rle = self.rle_decoder.as_mut().unwrap();
     let num_values = cmp::min(buffer.len(), self.num_values);
-    rle.get_batch_with_dict(&self.dictionary[..], buffer, num_values)
+    // Decoder is borrowed separately from the dictionary, once it is checked
+    self.rle_decoder()?;
+    let rle = self.rle_decoder.as_mut().unwrap();
+    let values_read = rle.get_batch_with_dict(&self.dictionary[..], buffer, num_values)?;
+    self.num_values -= values_read;
+    Ok(values_read)
+  }
+
+  fn get_spaced(
+    &mut self,
+    buffer: &mut [T::T],
+    null_count: usize,
+    valid_bits: &[u8]
+  ) -> Result<usize> {
+    check_spaced(buffer.len(), null_count, valid_bits)?;
+
+    // Only indices are decoded densely, values are looked up at their positions
+    let values_to_read = buffer.len() - null_count;
+    if values_to_read > self.num_values {
+      return Err(eof_err!(
+        "Expected to decode {} values, but only {} values left",
+        values_to_read,
+        self.num_values
+      ));
+    }
+    let mut indices = vec![0i32; values_to_read];
+    let indices_read = self.get_indices(&mut indices)?;
+    if indices_read != values_to_read {
+      return Err(eof_err!(
+        "Expected to decode {} dictionary indices, but only decoded {}",
+        values_to_read,
+        indices_read
+      ));
+    }
+
+    let mut indices = indices.into_iter();
+    for i in 0..buffer.len() {
+      if get_array_bit(valid_bits, i) {
+        // Indices are checked to be within bounds of the dictionary
+        match indices.next() {
+          Some(index) => buffer[i] = self.dictionary[index as usize].clone(),
+          None => {
+            return Err(general_err!(
+              "Validity bitmap has more than {} valid values",
+              values_to_read
+            ))
+          }
+        }
+      }
+    }
+    Ok(buffer.len())
+  }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let num_values = cmp::min(num_values, self.num_values);
+    let values_skipped = self.rle_decoder()?.skip(num_values)?;
+    self.num_values -= values_skipped;
+    Ok(values_skipped)
   }
 
   /// Number of values left in this decoder stream
@@ -356,8 +814,19 @@ impl<T: DataType> RleValueDecoder<T> {
   ) -> Result<()> {
     // We still need to remove prefix of i32 from the stream.
     let i32_size = mem::size_of::<i32>();
-    let data_size = read_num_bytes!(i32, i32_size, data.as_ref()) as usize;
-    let rle_decoder = self.decoder.as_mut().expect("RLE decoder is not initialized");
+    if data.len() < i32_size {
+      return Err(eof_err!("Not enough bytes to decode length of RLE data"));
+    }
+    let data_size = LittleEndian::read_u32(data.as_ref()) as usize;
+    if data.len() - i32_size < data_size {
+      return Err(eof_err!(
+        "Not enough bytes to decode RLE data of {} bytes, {} bytes left",
+        data_size,
+        data.len() - i32_size
+      ));
+    }
+    let rle_decoder =
+      self.decoder.as_mut().ok_or_else(|| not_initialized_err("RleValueDecoder"))?;
     rle_decoder.set_data(data.range(i32_size, data_size));
     self.values_left = num_values;
     Ok(())
@@ -371,7 +840,7 @@ impl<T: DataType> Decoder<T> for RleValueDecoder<T> {
     _data: ByteBufferPtr,
     _num_values: usize
   ) -> Result<()> {
-    panic!("RleValueDecoder only supports BoolType");
+    Err(general_err!("RleValueDecoder only supports BoolType"))
   }
 
   #[inline]
@@ -386,11 +855,32 @@ impl<T: DataType> Decoder<T> for RleValueDecoder<T> {
 
   #[inline]
   fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    let rle_decoder = self.decoder.as_mut().expect("RLE decoder is not initialized");
-    let values_read = rle_decoder.get_batch(buffer)?;
+    let rle_decoder =
+      self.decoder.as_mut().ok_or_else(|| not_initialized_err("RleValueDecoder"))?;
+    let num_values = cmp::min(buffer.len(), self.values_left);
+    let values_read = rle_decoder.get_batch(&mut buffer[..num_values])?;
     self.values_left -= values_read;
     Ok(values_read)
   }
+
+  #[inline]
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let rle_decoder =
+      self.decoder.as_mut().ok_or_else(|| not_initialized_err("RleValueDecoder"))?;
+    let values_skipped = rle_decoder.skip(cmp::min(num_values, self.values_left))?;
+    self.values_left -= values_skipped;
+    Ok(values_skipped)
+  }
+
+  #[inline]
+  default fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    get_bitmap_from_values(self, bitmap, offset, num_values)
+  }
 }
 
 impl Decoder<BoolType> for RleValueDecoder<BoolType> {
@@ -400,6 +890,91 @@ impl Decoder<BoolType> for RleValueDecoder<BoolType> {
     self.decoder = Some(RleDecoder::new(1));
     self.set_data_internal(data, num_values)
   }
+
+  #[inline]
+  fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    check_bitmap::<BoolType>(bitmap, offset, num_values)?;
+    let rle_decoder =
+      self.decoder.as_mut().ok_or_else(|| not_initialized_err("RleValueDecoder"))?;
+    let num_values = cmp::min(num_values, self.values_left);
+    let values_read = rle_decoder.get_bitmap(bitmap, offset, num_values)?;
+    self.values_left -= values_read;
+    Ok(values_read)
+  }
+}
+
+// ----------------------------------------------------------------------
+// BIT_PACKED Decoding
+
+/// Decoding for the deprecated BIT_PACKED encoding, found in files written by legacy
+/// parquet-mr versions. Values are packed from the most significant bit to the least
+/// significant bit of every byte, without any header.
+/// Only supports boolean types, which have bit width of 1.
+pub struct BitPackedValueDecoder<T: DataType> {
+  values_left: usize,
+  bit_reader: MsbBitReader,
+  _phantom: PhantomData<T>
+}
+
+impl<T: DataType> BitPackedValueDecoder<T> {
+  pub fn new() -> Self {
+    Self {
+      values_left: 0,
+      bit_reader: MsbBitReader::from(Vec::new()),
+      _phantom: PhantomData
+    }
+  }
+}
+
+impl<T: DataType> Decoder<T> for BitPackedValueDecoder<T> {
+  #[inline]
+  fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
+    self.bit_reader.reset(data);
+    self.values_left = num_values;
+    Ok(())
+  }
+
+  #[inline]
+  default fn get(&mut self, _buffer: &mut [T::T]) -> Result<usize> {
+    Err(general_err!("BitPackedValueDecoder only supports BoolType"))
+  }
+
+  #[inline]
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    let num_values = cmp::min(num_values, self.values_left);
+    if self.bit_reader.skip(num_values, 1) < num_values {
+      return Err(eof_err!("Not enough bytes to skip"));
+    }
+    self.values_left -= num_values;
+    Ok(num_values)
+  }
+
+  #[inline]
+  fn values_left(&self) -> usize {
+    self.values_left
+  }
+
+  #[inline]
+  fn encoding(&self) -> Encoding {
+    Encoding::BIT_PACKED
+  }
+}
+
+impl Decoder<BoolType> for BitPackedValueDecoder<BoolType> {
+  fn get(&mut self, buffer: &mut [bool]) -> Result<usize> {
+    let num_values = cmp::min(buffer.len(), self.values_left);
+    let values_read = self.bit_reader.get_batch(&mut buffer[..num_values], 1)?;
+    if values_read < num_values {
+      return Err(eof_err!("Not enough bytes to decode"));
+    }
+    self.values_left -= values_read;
+    Ok(values_read)
+  }
 }
 
 // ----------------------------------------------------------------------
@@ -450,16 +1025,18 @@ impl<T: DataType> DeltaBitPackDecoder<T> {
       delta_bit_width: 0,
       delta_bit_widths: ByteBuffer::new(),
       deltas_in_mini_block: vec![],
-      use_batch: mem::size_of::<T::T>() == 4,
+      use_batch: mem::size_of::<T::T>() == 4 || mem::size_of::<T::T>() == 8,
       current_value: 0,
       _phantom: PhantomData
     }
   }
 
   /// Returns underlying bit reader offset.
-  pub fn get_offset(&self) -> usize {
-    assert!(self.initialized, "Bit reader is not initialized");
-    self.bit_reader.get_byte_offset()
+  pub fn get_offset(&self) -> Result<usize> {
+    if !self.initialized {
+      return Err(not_initialized_err("DeltaBitPackDecoder"));
+    }
+    Ok(self.bit_reader.get_byte_offset())
   }
 
   /// Initializes new mini block.
@@ -474,29 +1051,54 @@ impl<T: DataType> DeltaBitPackDecoder<T> {
       let w = self.bit_reader
         .get_aligned::<u8>(1)
         .ok_or(eof_err!("Not enough data to decode 'width'"))?;
+      check_bit_width::<T::T>(w, "deltas")?;
       widths.push(w);
     }
 
     self.delta_bit_widths.set_data(widths);
     self.mini_block_idx = 0;
+    // Number of mini blocks is checked to be positive in `set_data`
     self.delta_bit_width = self.delta_bit_widths.data()[0];
     self.values_current_mini_block = self.values_per_mini_block;
     Ok(())
   }
 
+  /// Moves to the next mini block, which starts a new block after the last mini block,
+  /// and loads its deltas.
+  #[inline]
+  fn next_mini_block(&mut self) -> Result<()> {
+    self.mini_block_idx += 1;
+    if self.mini_block_idx < self.delta_bit_widths.size() {
+      self.delta_bit_width = self.delta_bit_widths.data()[self.mini_block_idx];
+      self.values_current_mini_block = self.values_per_mini_block;
+    } else {
+      self.init_block()?;
+    }
+    self.load_deltas_in_mini_block()
+  }
+
   /// Loads delta into mini block.
   #[inline]
   fn load_deltas_in_mini_block(&mut self) -> Result<()> {
     self.deltas_in_mini_block.clear();
     if self.use_batch {
+      // Mini block must fit into the remaining data, which also bounds the memory
+      // allocated for deltas of a corrupt header
+      let bits_left = self.bit_reader.bits_left() as u64;
+      let bits_to_load =
+        self.values_current_mini_block as u64 * self.delta_bit_width as u64;
+      if bits_to_load > bits_left {
+        return Err(eof_err!("Not enough data to decode mini block"));
+      }
       self.deltas_in_mini_block.resize(self.values_current_mini_block, T::T::default());
       let loaded = self.bit_reader.get_batch::<T::T>(
         &mut self.deltas_in_mini_block[..], self.delta_bit_width as usize
-      );
-      assert!(loaded == self.values_current_mini_block);
+      )?;
+      if loaded < self.values_current_mini_block {
+        return Err(eof_err!("Not enough data to decode mini block"));
+      }
     } else {
       for _ in 0..self.values_current_mini_block {
-        // TODO: load one batch at a time similar to int32
         let delta = self.bit_reader
           .get_value::<T::T>(self.delta_bit_width as usize)
           .ok_or(eof_err!("Not enough data to decode 'delta'"))?;
@@ -512,6 +1114,14 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
   // # of total values is derived from encoding
   #[inline]
   default fn set_data(&mut self, data: ByteBufferPtr, _: usize) -> Result<()> {
+    match T::get_physical_type() {
+      Type::INT32 | Type::INT64 => {},
+      _ => {
+        return Err(general_err!(
+          "DeltaBitPackDecoder only supports Int32Type and Int64Type"
+        ))
+      }
+    }
     self.bit_reader = BitReader::new(data);
     self.initialized = true;
 
@@ -521,27 +1131,48 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
     self.num_mini_blocks = self.bit_reader
       .get_vlq_int()
       .ok_or(eof_err!("Not enough data to decode 'num_mini_blocks'"))?;
-    self.num_values = self.bit_reader
+    let num_values = self.bit_reader
       .get_vlq_int()
-      .ok_or(eof_err!("Not enough data to decode 'num_values'"))? as usize;
+      .ok_or(eof_err!("Not enough data to decode 'num_values'"))?;
     self.first_value = self.bit_reader
       .get_zigzag_vlq_int()
       .ok_or(eof_err!("Not enough data to decode 'first_value'"))?;
 
     // Reset decoding state
+    self.num_values = 0;
     self.first_value_read = false;
     self.mini_block_idx = 0;
     self.delta_bit_widths.clear();
     self.values_current_mini_block = 0;
 
-    self.values_per_mini_block = (block_size / self.num_mini_blocks) as usize;
-    assert!(self.values_per_mini_block % 8 == 0);
+    if num_values < 0 {
+      return Err(general_err!("Invalid number of values {}", num_values));
+    }
+    if block_size <= 0 || self.num_mini_blocks <= 0 ||
+        block_size % self.num_mini_blocks != 0 {
+      return Err(general_err!(
+        "Invalid block size {} with {} mini blocks",
+        block_size,
+        self.num_mini_blocks
+      ));
+    }
+    let values_per_mini_block = block_size / self.num_mini_blocks;
+    if values_per_mini_block % 8 != 0 {
+      return Err(general_err!(
+        "Invalid number of values {} in a mini block, must be a multiple of 8",
+        values_per_mini_block
+      ));
+    }
+    self.values_per_mini_block = values_per_mini_block as usize;
+    self.num_values = num_values as usize;
 
     Ok(())
   }
 
   default fn get(&mut self, buffer: &mut [T::T]) -> Result<usize> {
-    assert!(self.initialized, "Bit reader is not initialized");
+    if !self.initialized {
+      return Err(not_initialized_err("DeltaBitPackDecoder"));
+    }
 
     let num_values = cmp::min(buffer.len(), self.num_values);
     for i in 0..num_values {
@@ -553,14 +1184,7 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
       }
 
       if self.values_current_mini_block == 0 {
-        self.mini_block_idx += 1;
-        if self.mini_block_idx < self.delta_bit_widths.size() {
-          self.delta_bit_width = self.delta_bit_widths.data()[self.mini_block_idx];
-          self.values_current_mini_block = self.values_per_mini_block;
-        } else {
-          self.init_block()?;
-        }
-        self.load_deltas_in_mini_block()?;
+        self.next_mini_block()?;
       }
 
       // we decrement values in current mini block, so we need to invert index for delta
@@ -580,6 +1204,41 @@ impl<T: DataType> Decoder<T> for DeltaBitPackDecoder<T> {
     Ok(num_values)
   }
 
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    if !self.initialized {
+      return Err(not_initialized_err("DeltaBitPackDecoder"));
+    }
+
+    let num_values = cmp::min(num_values, self.num_values);
+    let mut values_skipped = 0;
+    if num_values > 0 && !self.first_value_read {
+      self.current_value = self.first_value;
+      self.first_value_read = true;
+      values_skipped += 1;
+    }
+
+    // Values are not written anywhere, but deltas of skipped values still have to be
+    // accumulated, so that values after them are decoded correctly
+    while values_skipped < num_values {
+      if self.values_current_mini_block == 0 {
+        self.next_mini_block()?;
+      }
+      let num_deltas =
+        cmp::min(num_values - values_skipped, self.values_current_mini_block);
+      let start = self.deltas_in_mini_block.len() - self.values_current_mini_block;
+      for index in start..start + num_deltas {
+        let delta = self.get_delta(index);
+        self.current_value = self.current_value.wrapping_add(self.min_delta);
+        self.current_value = self.current_value.wrapping_add(delta as i64);
+      }
+      self.values_current_mini_block -= num_deltas;
+      values_skipped += num_deltas;
+    }
+
+    self.num_values -= num_values;
+    Ok(num_values)
+  }
+
   fn values_left(&self) -> usize {
     self.num_values
   }
@@ -599,6 +1258,7 @@ trait DeltaBitPackDecoderConversion<T: DataType> {
   fn set_decoded_value(&self, buffer: &mut [T::T], index: usize, value: i64);
 }
 
+// Other types are rejected in `set_data`, so default implementations are never called
 impl<T: DataType> DeltaBitPackDecoderConversion<T> for DeltaBitPackDecoder<T> {
   #[inline]
   default fn get_delta(&self, _: usize) -> i64 {
@@ -645,12 +1305,12 @@ impl DeltaBitPackDecoderConversion<Int64Type> for DeltaBitPackDecoder<Int64Type>
 /// See [`DeltaLengthByteArrayEncoder`](`::encoding::DeltaLengthByteArrayEncoder`)
 /// for more information.
 pub struct DeltaLengthByteArrayDecoder<T: DataType> {
-  // Lengths for each byte array in `data`
-  // TODO: add memory tracker to this
-  lengths: Vec<i32>,
+  // Decoder for lengths of byte arrays in `data`, lengths are decoded lazily for each
+  // batch of values requested in `get`
+  len_decoder: Option<DeltaBitPackDecoder<Int32Type>>,
 
-  // Current index into `lengths`
-  current_idx: usize,
+  // Lengths of the current batch of values, reused between calls to `get`
+  lengths: Vec<i32>,
 
   // Concatenated byte array data
   data: Option<ByteBufferPtr>,
@@ -669,14 +1329,37 @@ impl<T: DataType> DeltaLengthByteArrayDecoder<T> {
   /// Creates new delta length byte array decoder.
   pub fn new() -> Self {
     Self {
+      len_decoder: None,
       lengths: vec![],
-      current_idx: 0,
       data: None,
       offset: 0,
       num_values: 0,
       _phantom: PhantomData
     }
   }
+
+  /// Decodes lengths of the next `num_values` byte arrays into `lengths`.
+  ///
+  /// Returns an error if fewer lengths are decoded or a length is negative.
+  fn read_lengths(&mut self, num_values: usize) -> Result<()> {
+    let len_decoder = match self.len_decoder {
+      Some(ref mut len_decoder) => len_decoder,
+      None => return Err(not_initialized_err("DeltaLengthByteArrayDecoder"))
+    };
+    self.lengths.resize(num_values, 0);
+    let lengths_read = len_decoder.get(&mut self.lengths[..])?;
+    if lengths_read < num_values {
+      return Err(eof_err!(
+        "Expected {} byte array lengths, but only decoded {}",
+        num_values,
+        lengths_read
+      ));
+    }
+    if let Some(len) = self.lengths.iter().find(|len| **len < 0) {
+      return Err(general_err!("Invalid byte array length {}", len));
+    }
+    Ok(())
+  }
 }
 
 impl<T: DataType> Decoder<T> for DeltaLengthByteArrayDecoder<T> {
@@ -688,6 +1371,10 @@ impl<T: DataType> Decoder<T> for DeltaLengthByteArrayDecoder<T> {
     Err(general_err!("DeltaLengthByteArrayDecoder only support ByteArrayType"))
   }
 
+  default fn skip(&mut self, _: usize) -> Result<usize> {
+    Err(general_err!("DeltaLengthByteArrayDecoder only support ByteArrayType"))
+  }
+
   fn values_left(&self) -> usize {
     self.num_values
   }
@@ -699,34 +1386,94 @@ impl<T: DataType> Decoder<T> for DeltaLengthByteArrayDecoder<T> {
 
 impl Decoder<ByteArrayType> for DeltaLengthByteArrayDecoder<ByteArrayType> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
-    let mut len_decoder = DeltaBitPackDecoder::<Int32Type>::new();
-    len_decoder.set_data(data.all(), num_values)?;
-    let num_lengths = len_decoder.values_left();
-    self.lengths.resize(num_lengths, 0);
-    len_decoder.get(&mut self.lengths[..])?;
-
-    self.data = Some(data.start_from(len_decoder.get_offset()));
+    let (len_decoder, data_offset) = init_delta_bit_packed(&data, num_values)?;
+    self.num_values = len_decoder.values_left();
+    self.len_decoder = Some(len_decoder);
+    self.data = Some(data.start_from(data_offset));
     self.offset = 0;
-    self.current_idx = 0;
-    self.num_values = num_lengths;
     Ok(())
   }
 
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.data.is_some());
-
-    let data = self.data.as_ref().unwrap();
     let num_values = cmp::min(buffer.len(), self.num_values);
+    self.read_lengths(num_values)?;
+
+    let data = match self.data {
+      Some(ref data) => data,
+      None => return Err(not_initialized_err("DeltaLengthByteArrayDecoder"))
+    };
     for i in 0..num_values {
-      let len = self.lengths[self.current_idx] as usize;
+      let len = self.lengths[i] as usize;
+      if len > data.len() - self.offset {
+        return Err(eof_err!(
+          "Not enough data to decode byte array of length {}, {} bytes left",
+          len,
+          data.len() - self.offset
+        ));
+      }
       buffer[i].set_data(data.range(self.offset, len));
       self.offset += len;
-      self.current_idx += 1;
     }
 
     self.num_values -= num_values;
     Ok(num_values)
   }
+
+  fn skip(&mut self, num_values: usize) -> Result<usize> {
+    // Only lengths are decoded, byte arrays are skipped by advancing the offset
+    let num_values = cmp::min(num_values, self.num_values);
+    self.read_lengths(num_values)?;
+    let len = self.lengths
+      .iter()
+      .fold(0usize, |sum, len| sum.saturating_add(*len as usize));
+
+    let data = match self.data {
+      Some(ref data) => data,
+      None => return Err(not_initialized_err("DeltaLengthByteArrayDecoder"))
+    };
+    if len > data.len() - self.offset {
+      return Err(eof_err!(
+        "Not enough data to skip byte arrays of length {}, {} bytes left",
+        len,
+        data.len() - self.offset
+      ));
+    }
+    self.offset += len;
+
+    self.num_values -= num_values;
+    Ok(num_values)
+  }
+}
+
+/// Maximum number of values decoded at once, when `DELTA_BINARY_PACKED` data is
+/// scanned to find where it ends.
+const DELTA_BIT_PACKED_SCAN_BATCH_SIZE: usize = 1024;
+
+/// Returns `DELTA_BINARY_PACKED` decoder of lengths stored at the beginning of `data`,
+/// and byte offset of the end of encoded lengths.
+///
+/// Encoded lengths are followed by other data, and there is no way to find where they
+/// end other than decoding them. Lengths are scanned in small batches and then decoded
+/// again by returned decoder, so decoders of byte arrays do not need to keep all
+/// lengths of a page in memory.
+fn init_delta_bit_packed(
+  data: &ByteBufferPtr,
+  num_values: usize
+) -> Result<(DeltaBitPackDecoder<Int32Type>, usize)> {
+  let mut scan_decoder = DeltaBitPackDecoder::<Int32Type>::new();
+  scan_decoder.set_data(data.all(), num_values)?;
+  let batch_size = cmp::min(scan_decoder.values_left(), DELTA_BIT_PACKED_SCAN_BATCH_SIZE);
+  let mut batch = vec![0; batch_size];
+  while scan_decoder.values_left() > 0 {
+    if scan_decoder.get(&mut batch[..])? == 0 {
+      return Err(eof_err!("Not enough data to decode lengths"));
+    }
+  }
+  let offset = scan_decoder.get_offset()?;
+
+  let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
+  decoder.set_data(data.all(), num_values)?;
+  Ok((decoder, offset))
 }
 
 // ----------------------------------------------------------------------
@@ -737,19 +1484,25 @@ impl Decoder<ByteArrayType> for DeltaLengthByteArrayDecoder<ByteArrayType> {
 /// using `DELTA_LENGTH_BYTE_ARRAY` encoding.
 /// See [`DeltaByteArrayEncoder`](`::encoding::DeltaByteArrayEncoder`) for more
 /// information.
+///
+/// Values are decoded incrementally, memory used by the decoder is proportional to the
+/// size of the output buffer passed into `get` rather than to the size of the page.
 pub struct DeltaByteArrayDecoder<T: DataType> {
-  // Prefix lengths for each byte array
-  // TODO: add memory tracker to this
-  prefix_lengths: Vec<i32>,
+  // Decoder for prefix lengths, prefix lengths are decoded lazily for each batch of
+  // values requested in `get`
+  prefix_len_decoder: Option<DeltaBitPackDecoder<Int32Type>>,
 
-  // The current index into `prefix_lengths`,
-  current_idx: usize,
+  // Prefix lengths of the current batch of values, reused between calls to `get`
+  prefix_lengths: Vec<i32>,
 
-  // Decoder for all suffixes, the # of which should be the same as `prefix_lengths.len()`
+  // Decoder for all suffixes, the # of which should be the same as # of prefix lengths
   suffix_decoder: Option<DeltaLengthByteArrayDecoder<ByteArrayType>>,
 
+  // Suffixes of the current batch of values, reused between calls to `get`
+  suffixes: Vec<ByteArray>,
+
   // The last byte array, used to derive the current prefix
-  previous_value: Vec<u8>,
+  previous_value: ByteArray,
 
   // Number of values left
   num_values: usize,
@@ -762,10 +1515,11 @@ impl<T: DataType> DeltaByteArrayDecoder<T> {
   /// Creates new delta byte array decoder.
   pub fn new() -> Self {
     Self {
+      prefix_len_decoder: None,
       prefix_lengths: vec![],
-      current_idx: 0,
       suffix_decoder: None,
-      previous_value: vec![],
+      suffixes: vec![],
+      previous_value: ByteArray::from(vec![]),
       num_values: 0,
       _phantom: PhantomData
     }
@@ -792,46 +1546,70 @@ impl<'m, T: DataType> Decoder<T> for DeltaByteArrayDecoder<T> {
 
 impl<> Decoder<ByteArrayType> for DeltaByteArrayDecoder<ByteArrayType> {
   fn set_data(&mut self, data: ByteBufferPtr, num_values: usize) -> Result<()> {
-    let mut prefix_len_decoder = DeltaBitPackDecoder::<Int32Type>::new();
-    prefix_len_decoder.set_data(data.all(), num_values)?;
-    let num_prefixes = prefix_len_decoder.values_left();
-    self.prefix_lengths.resize(num_prefixes, 0);
-    prefix_len_decoder.get(&mut self.prefix_lengths[..])?;
+    let (prefix_len_decoder, suffix_offset) = init_delta_bit_packed(&data, num_values)?;
+    self.num_values = prefix_len_decoder.values_left();
+    self.prefix_len_decoder = Some(prefix_len_decoder);
 
     let mut suffix_decoder = DeltaLengthByteArrayDecoder::new();
-    suffix_decoder.set_data(
-      data.start_from(prefix_len_decoder.get_offset()), num_values)?;
+    suffix_decoder.set_data(data.start_from(suffix_offset), num_values)?;
     self.suffix_decoder = Some(suffix_decoder);
-    self.num_values = num_prefixes;
-    self.current_idx = 0;
-    self.previous_value.clear();
+    self.previous_value = ByteArray::from(vec![]);
     Ok(())
   }
 
   fn get(&mut self, buffer: &mut [ByteArray]) -> Result<usize> {
-    assert!(self.suffix_decoder.is_some());
+    let (prefix_len_decoder, suffix_decoder) =
+      match (self.prefix_len_decoder.as_mut(), self.suffix_decoder.as_mut()) {
+        (Some(prefix_len_decoder), Some(suffix_decoder)) => {
+          (prefix_len_decoder, suffix_decoder)
+        },
+        _ => return Err(not_initialized_err("DeltaByteArrayDecoder"))
+      };
 
     let num_values = cmp::min(buffer.len(), self.num_values);
-    for i in 0..num_values {
-      // Process suffix
-      // TODO: this is awkward - maybe we should add a non-vectorized API?
-      let mut suffix = vec![ByteArray::new(); 1];
-      let suffix_decoder = self.suffix_decoder.as_mut().unwrap();
-      suffix_decoder.get(&mut suffix[..])?;
-      let suffix = suffix[0].data();
+    self.prefix_lengths.resize(num_values, 0);
+    let num_prefix_lengths = prefix_len_decoder.get(&mut self.prefix_lengths[..])?;
+    if num_prefix_lengths < num_values {
+      return Err(eof_err!(
+        "Not enough prefix lengths to decode, expected {}, found {}",
+        num_values,
+        num_prefix_lengths
+      ));
+    }
+    self.suffixes.resize(num_values, ByteArray::new());
+    let num_suffixes = suffix_decoder.get(&mut self.suffixes[..])?;
+    if num_suffixes < num_values {
+      return Err(eof_err!(
+        "Not enough suffixes to decode, expected {}, found {}",
+        num_values,
+        num_suffixes
+      ));
+    }
 
+    for i in 0..num_values {
       // Extract current prefix length, can be 0
-      let prefix_len = self.prefix_lengths[self.current_idx] as usize;
-
-      // Concatenate prefix with suffix
-      let mut result = Vec::new();
-      result.extend_from_slice(&self.previous_value[0..prefix_len]);
-      result.extend_from_slice(suffix);
+      let prefix_len = self.prefix_lengths[i];
+      if prefix_len < 0 || prefix_len as usize > self.previous_value.len() {
+        return Err(general_err!(
+          "Prefix length {} exceeds length of previous value {}",
+          prefix_len,
+          self.previous_value.len()
+        ));
+      }
 
-      let data = ByteBufferPtr::new(result.clone());
-      buffer[i].set_data(data);
-      self.previous_value = result;
-      self.current_idx += 1;
+      let prefix_len = prefix_len as usize;
+      if prefix_len == 0 {
+        // Suffix is the whole value and references page data, no need to copy
+        buffer[i] = self.suffixes[i].clone();
+      } else {
+        // Concatenate prefix with suffix
+        let suffix = self.suffixes[i].data();
+        let mut result = Vec::with_capacity(prefix_len + suffix.len());
+        result.extend_from_slice(&self.previous_value.data()[0..prefix_len]);
+        result.extend_from_slice(suffix);
+        buffer[i].set_data(ByteBufferPtr::new(result));
+      }
+      self.previous_value = buffer[i].clone();
     }
 
     self.num_values -= num_values;
@@ -845,6 +1623,7 @@ mod tests {
   use super::super::encoding::*;
   use super::*;
   use schema::types::{ColumnDescriptor, ColumnPath, Type as Tpe};
+  use std::cmp;
   use std::mem;
   use std::rc::Rc;
   use util::bit_util::set_array_bit;
@@ -859,6 +1638,7 @@ mod tests {
     test_get_decoder::<Int32Type>(Encoding::DELTA_LENGTH_BYTE_ARRAY, None);
     test_get_decoder::<Int32Type>(Encoding::DELTA_BYTE_ARRAY, None);
     test_get_decoder::<BoolType>(Encoding::RLE, None);
+    test_get_decoder::<BoolType>(Encoding::BIT_PACKED, None);
 
     // error when initializing
     test_get_decoder::<Int32Type>(
@@ -875,6 +1655,64 @@ mod tests {
       Encoding::BIT_PACKED,
       Some(nyi_err!("Encoding BIT_PACKED is not supported"))
     );
+
+    // physical type mismatch
+    let decoder = get_decoder::<Int64Type>(
+      get_test_column_desc_ptr::<Int32Type>(), Encoding::PLAIN);
+    assert_eq!(
+      decoder.err().unwrap(),
+      general_err!("Cannot create decoder of type INT64 for column col of type INT32")
+    );
+  }
+
+  #[test]
+  fn test_get_dict_decoder() {
+    let dict_page = Page::DictionaryPage {
+      buf: ByteBufferPtr::new(vec![1, 0, 0, 0, 2, 0, 0, 0]),
+      num_values: 2,
+      encoding: Encoding::PLAIN_DICTIONARY,
+      is_sorted: false
+    };
+    let descr = get_test_column_desc_ptr::<Int32Type>();
+    let mut decoder = get_dict_decoder::<Int32Type>(descr.clone(), &dict_page).unwrap();
+    assert_eq!(decoder.dictionary(), &[1, 2]);
+    // Bit width 1, bit packed run of 8 values with indices 1, 0 and 1
+    decoder.set_data(ByteBufferPtr::new(vec![1, 3, 0b101]), 3).unwrap();
+    let mut buffer = vec![0; 3];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 3);
+    assert_eq!(buffer, vec![2, 1, 2]);
+
+    let decoder = get_dict_decoder::<Int64Type>(descr.clone(), &dict_page);
+    assert_eq!(
+      decoder.err().unwrap(),
+      general_err!("Cannot create decoder of type INT64 for column col of type INT32")
+    );
+
+    let data_page = Page::DataPage {
+      buf: ByteBufferPtr::new(vec![]),
+      num_values: 0,
+      encoding: Encoding::PLAIN,
+      def_level_encoding: Encoding::RLE,
+      rep_level_encoding: Encoding::RLE,
+      statistics: None
+    };
+    let decoder = get_dict_decoder::<Int32Type>(descr.clone(), &data_page);
+    assert_eq!(
+      decoder.err().unwrap(),
+      general_err!("Expected dictionary page, found DATA_PAGE")
+    );
+
+    let dict_page = Page::DictionaryPage {
+      buf: ByteBufferPtr::new(vec![]),
+      num_values: 0,
+      encoding: Encoding::DELTA_BINARY_PACKED,
+      is_sorted: false
+    };
+    let decoder = get_dict_decoder::<Int32Type>(descr, &dict_page);
+    assert_eq!(
+      decoder.err().unwrap(),
+      nyi_err!("Invalid/Unsupported encoding type for dictionary: DELTA_BINARY_PACKED")
+    );
   }
 
   #[test]
@@ -917,6 +1755,45 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_plain_decode_little_endian_unaligned() {
+    // Values start at an odd offset of the buffer
+    let data = ByteBufferPtr::new(vec![
+      0xFF, 0x01, 0x02, 0x03, 0x04, 0xFE, 0xFF, 0xFF, 0xFF
+    ]).start_from(1);
+    let mut buffer = vec![0; 2];
+    test_plain_decode::<Int32Type>(
+      data.all(), 2, -1, &mut buffer[..], &[0x04030201, -2]
+    );
+
+    let mut buffer = vec![0; 1];
+    test_plain_decode::<Int64Type>(
+      data.range(0, 8), 1, -1, &mut buffer[..], &[-8_522_628_607]
+    );
+
+    let mut bytes = vec![0xFF];
+    bytes.extend_from_slice(&[0x00, 0x00, 0xC0, 0x3F, 0x00, 0x00, 0x00, 0x00]);
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0xC0]);
+    let data = ByteBufferPtr::new(bytes).start_from(1);
+    let mut buffer = vec![0.0; 2];
+    test_plain_decode::<FloatType>(
+      data.range(0, 8), 2, -1, &mut buffer[..], &[1.5, 0.0]
+    );
+    let mut buffer = vec![0.0; 1];
+    test_plain_decode::<DoubleType>(
+      data.range(8, 8), 1, -1, &mut buffer[..], &[-2.5]
+    );
+
+    let mut bytes = vec![0xFF];
+    bytes.extend_from_slice(&[0x01, 0, 0, 0, 0x02, 0, 0, 0, 0x8C, 0x3D, 0x25, 0]);
+    let mut expected = Int96::new();
+    expected.set_data(vec![1, 2, 2_440_588]);
+    let mut buffer = vec![Int96::new(); 1];
+    test_plain_decode::<Int96Type>(
+      ByteBufferPtr::new(bytes).start_from(1), 1, -1, &mut buffer[..], &[expected]
+    );
+  }
+
   #[test]
   fn test_plain_decode_int96() {
     let v0 = vec![11, 22, 33];
@@ -978,10 +1855,12 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(expected = "RleValueDecoder only supports BoolType")]
   fn test_rle_value_decode_int32_not_supported() {
     let mut decoder = RleValueDecoder::<Int32Type>::new();
-    decoder.set_data(ByteBufferPtr::new(vec![5, 0, 0, 0]), 1).unwrap();
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![5, 0, 0, 0]), 1).unwrap_err(),
+      general_err!("RleValueDecoder only supports BoolType")
+    );
   }
 
   #[test]
@@ -996,20 +1875,105 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(expected = "Bit reader is not initialized")]
+  fn test_bit_packed_value_decode_bool() {
+    let mut decoder = BitPackedValueDecoder::<BoolType>::new();
+    decoder.set_data(ByteBufferPtr::new(vec![0b10110000, 0b01000000]), 10).unwrap();
+    let mut buffer = vec![false; 6];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 6);
+    assert_eq!(buffer, vec![true, false, true, true, false, false]);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 4);
+    assert_eq!(&buffer[..4], &[false, false, false, true]);
+    assert_eq!(decoder.values_left(), 0);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 0);
+
+    // Fewer values in data than expected
+    decoder.set_data(ByteBufferPtr::new(vec![0xFF]), 10).unwrap();
+    assert!(decoder.get(&mut vec![false; 10]).is_err());
+  }
+
+  #[test]
+  fn test_decode_bool_bitmap() {
+    let data = BoolType::gen_vec(-1, 1000);
+    for encoding in &[Encoding::PLAIN, Encoding::RLE] {
+      let mut encoder = get_encoder::<BoolType>(
+        get_test_column_desc_ptr::<BoolType>(),
+        *encoding,
+        Rc::new(MemTracker::new())
+      ).unwrap();
+      encoder.put(&data[..]).unwrap();
+      let bytes = encoder.flush_buffer().unwrap();
+
+      let mut decoder =
+        get_decoder::<BoolType>(get_test_column_desc_ptr::<BoolType>(), *encoding)
+          .unwrap();
+      decoder.set_data(bytes, data.len()).unwrap();
+      // Bits before the offset must not be changed
+      let mut bitmap = vec![0b101; (data.len() + 3 + 7) / 8];
+      let mut values_read = 0;
+      while decoder.values_left() > 0 {
+        values_read += decoder.get_bitmap(&mut bitmap, 3 + values_read, 99).unwrap();
+      }
+      assert_eq!(values_read, data.len());
+      assert_eq!(decoder.get_bitmap(&mut bitmap, 0, 1).unwrap(), 0);
+      assert_eq!(bitmap[0] & 0b111, 0b101);
+      for (i, value) in data.iter().enumerate() {
+        assert_eq!(get_array_bit(&bitmap, 3 + i), *value, "{:?} value {}", encoding, i);
+      }
+    }
+
+    // Default implementation decodes values into a scratch buffer
+    let mut decoder = BitPackedValueDecoder::<BoolType>::new();
+    decoder.set_data(ByteBufferPtr::new(vec![0b10110000, 0b01000000]), 10).unwrap();
+    let mut bitmap = vec![0xFF; 2];
+    assert_eq!(decoder.get_bitmap(&mut bitmap, 1, 16).unwrap(), 10);
+    assert_eq!(bitmap, vec![0b00011011, 0b11111100]);
+  }
+
+  #[test]
+  fn test_decode_bitmap_errors() {
+    let mut decoder = PlainDecoder::<BoolType>::new(0);
+    decoder.set_data(ByteBufferPtr::new(vec![0xFF]), 8).unwrap();
+    assert_eq!(
+      decoder.get_bitmap(&mut [0], 1, 8).unwrap_err(),
+      general_err!("Bitmap of 1 bytes is too short for 8 values at bit offset 1")
+    );
+
+    let mut decoder = PlainDecoder::<Int32Type>::new(0);
+    decoder.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0]), 1).unwrap();
+    assert_eq!(
+      decoder.get_bitmap(&mut [0], 0, 1).unwrap_err(),
+      general_err!("Cannot decode values of type INT32 into a bitmap")
+    );
+  }
+
+  #[test]
+  fn test_bit_packed_value_decode_int32_not_supported() {
+    let mut decoder = BitPackedValueDecoder::<Int32Type>::new();
+    decoder.set_data(ByteBufferPtr::new(vec![5, 0, 0, 0]), 1).unwrap();
+    assert_eq!(
+      decoder.get(&mut [0]).unwrap_err(),
+      general_err!("BitPackedValueDecoder only supports BoolType")
+    );
+  }
+
+  #[test]
   fn test_delta_bit_packed_not_initialized_offset() {
     // Fail if set_data() is not called before get_offset()
     let decoder = DeltaBitPackDecoder::<Int32Type>::new();
-    decoder.get_offset();
+    assert_eq!(
+      decoder.get_offset().unwrap_err(),
+      general_err!(
+        "DeltaBitPackDecoder is not initialized, set_data() must be called first"
+      )
+    );
   }
 
   #[test]
-  #[should_panic(expected = "Bit reader is not initialized")]
   fn test_delta_bit_packed_not_initialized_get() {
     // Fail if set_data() is not called before get()
     let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
     let mut buffer = vec![];
-    decoder.get(&mut buffer).unwrap();
+    assert!(decoder.get(&mut buffer).is_err());
   }
 
   #[test]
@@ -1109,6 +2073,15 @@ mod tests {
     test_delta_bit_packed_decode::<Int64Type>(vec![block_data]);
   }
 
+  #[test]
+  fn test_delta_bit_packed_int64_wide_deltas() {
+    // Deltas need more than 32 bits, mini blocks are unpacked 8 values at a time
+    let block_data = (0..100)
+      .map(|i: i64| (i % 7) * (1 << 40) - (i % 3) * (1 << 35) + i)
+      .collect();
+    test_delta_bit_packed_decode::<Int64Type>(vec![block_data]);
+  }
+
   #[test]
   fn test_delta_bit_packed_int64_multiple_blocks() {
     // Test multiple 'put' calls on the same encoder
@@ -1134,10 +2107,10 @@ mod tests {
     decoder.set_data(buffer, 3).unwrap();
     // check exact offsets, because when reading partial values we end up with
     // some data not being read from bit reader
-    assert_eq!(decoder.get_offset(), 5);
+    assert_eq!(decoder.get_offset().unwrap(), 5);
     let mut result = vec![0, 0, 0];
     decoder.get(&mut result).unwrap();
-    assert_eq!(decoder.get_offset(), 34);
+    assert_eq!(decoder.get_offset().unwrap(), 34);
     assert_eq!(result, vec![29, 43, 89]);
   }
 
@@ -1175,18 +2148,342 @@ mod tests {
     test_delta_byte_array_decode(data);
   }
 
+  #[test]
+  fn test_delta_byte_array_incremental_get() {
+    let values: Vec<ByteArray> = (0..3000)
+      .map(|i| ByteArray::from(format!("prefix_{}", i).into_bytes()))
+      .collect();
+    let encodings = vec![Encoding::DELTA_LENGTH_BYTE_ARRAY, Encoding::DELTA_BYTE_ARRAY];
+    for encoding in encodings {
+      let mut encoder = get_encoder::<ByteArrayType>(
+        get_test_column_desc_ptr::<ByteArrayType>(),
+        encoding,
+        Rc::new(MemTracker::new())
+      ).unwrap();
+      encoder.put(&values[..]).unwrap();
+      let bytes = encoder.flush_buffer().unwrap();
+
+      let descr = get_test_column_desc_ptr::<ByteArrayType>();
+      let mut decoder = get_decoder::<ByteArrayType>(descr, encoding).unwrap();
+      decoder.set_data(bytes, values.len()).unwrap();
+
+      // Decode values using small buffer
+      let mut result = Vec::new();
+      let mut buffer = vec![ByteArray::new(); 7];
+      while decoder.values_left() > 0 {
+        let num_values = decoder.get(&mut buffer[..]).unwrap();
+        result.extend_from_slice(&buffer[..num_values]);
+      }
+      assert_eq!(result, values);
+    }
+
+    // Decoder buffers only as many prefix lengths as requested
+    let mut encoder = DeltaByteArrayEncoder::<ByteArrayType>::new();
+    encoder.put(&values[..]).unwrap();
+    let mut decoder = DeltaByteArrayDecoder::<ByteArrayType>::new();
+    decoder.set_data(encoder.flush_buffer().unwrap(), values.len()).unwrap();
+    let mut buffer = vec![ByteArray::new(); 7];
+    assert_eq!(decoder.get(&mut buffer[..]).unwrap(), 7);
+    assert_eq!(decoder.prefix_lengths.len(), 7);
+    assert_eq!(decoder.suffixes.len(), 7);
+    assert_eq!(&buffer[..], &values[..7]);
+  }
+
+  #[test]
+  fn test_skip() {
+    test_skip_decode::<BoolType>(BoolType::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<BoolType>(BoolType::gen_vec(-1, 300), Encoding::RLE);
+    test_skip_decode::<Int32Type>(Int32Type::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<Int64Type>(Int64Type::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<Int96Type>(Int96Type::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<DoubleType>(DoubleType::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<ByteArrayType>(ByteArrayType::gen_vec(-1, 300), Encoding::PLAIN);
+    test_skip_decode::<Int32Type>(
+      Int32Type::gen_vec(-1, 300), Encoding::DELTA_BINARY_PACKED);
+    test_skip_decode::<Int64Type>(
+      Int64Type::gen_vec(-1, 300), Encoding::DELTA_BINARY_PACKED);
+    test_skip_decode::<ByteArrayType>(
+      ByteArrayType::gen_vec(-1, 300), Encoding::DELTA_LENGTH_BYTE_ARRAY);
+    test_skip_decode::<ByteArrayType>(
+      ByteArrayType::gen_vec(-1, 300), Encoding::DELTA_BYTE_ARRAY);
+
+    let values: Vec<i32> = (0..300).map(|i| i % 7).collect();
+    test_skip_decode::<Int32Type>(values, Encoding::PLAIN_DICTIONARY);
+    let values: Vec<ByteArray> =
+      (0..300).map(|i| ByteArray::from(vec![(i % 3) as u8])).collect();
+    test_skip_decode::<ByteArrayType>(values, Encoding::PLAIN_DICTIONARY);
+  }
+
+  #[test]
+  fn test_get_spaced() {
+    test_get_spaced_decode::<BoolType>(BoolType::gen_vec(-1, 300), Encoding::PLAIN);
+    test_get_spaced_decode::<Int32Type>(Int32Type::gen_vec(-1, 300), Encoding::PLAIN);
+    test_get_spaced_decode::<ByteArrayType>(
+      ByteArrayType::gen_vec(-1, 300), Encoding::PLAIN);
+    test_get_spaced_decode::<Int64Type>(
+      Int64Type::gen_vec(-1, 300), Encoding::DELTA_BINARY_PACKED);
+
+    let values: Vec<i32> = (0..300).map(|i| i % 7).collect();
+    test_get_spaced_decode::<Int32Type>(values, Encoding::PLAIN_DICTIONARY);
+    let values: Vec<ByteArray> =
+      (0..300).map(|i| ByteArray::from(vec![(i % 3) as u8])).collect();
+    test_get_spaced_decode::<ByteArrayType>(values, Encoding::PLAIN_DICTIONARY);
+  }
+
+  #[test]
+  fn test_dict_decoder_indices() {
+    let data: Vec<ByteArray> = (0..100)
+      .map(|i| ByteArray::from(vec![b'a' + (i * 7 % 5) as u8]))
+      .collect();
+    let descr = get_test_column_desc_ptr::<ByteArrayType>();
+    let mem_tracker = Rc::new(MemTracker::new());
+    let mut encoder = DictEncoder::<ByteArrayType>::new(descr, mem_tracker);
+    encoder.put(&data).unwrap();
+    let mut dictionary = PlainDecoder::<ByteArrayType>::new(-1);
+    dictionary.set_data(encoder.write_dict().unwrap(), encoder.num_entries()).unwrap();
+    let mut decoder = DictDecoder::<ByteArrayType>::new();
+    assert!(decoder.dictionary().is_empty());
+    decoder.set_dict(Box::new(dictionary)).unwrap();
+    decoder.set_data(encoder.write_indices().unwrap(), data.len()).unwrap();
+    assert_eq!(decoder.dictionary().len(), 5);
+
+    let mut indices = vec![0; 64];
+    assert_eq!(decoder.get_indices(&mut indices).unwrap(), 64);
+    assert_eq!(decoder.values_left(), 36);
+    let mut buffer = vec![ByteArray::new(); 64];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 36);
+    assert_eq!(&buffer[..36], &data[64..]);
+    for (i, &index) in indices.iter().enumerate() {
+      assert_eq!(decoder.dictionary()[index as usize], data[i]);
+    }
+    assert_eq!(decoder.get_indices(&mut indices).unwrap(), 0);
+  }
+
+  #[test]
+  fn test_dict_decoder_indices_out_of_bounds() {
+    let mut dictionary = PlainDecoder::<Int32Type>::new(-1);
+    dictionary.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0]), 1).unwrap();
+    let mut decoder = DictDecoder::<Int32Type>::new();
+    decoder.set_dict(Box::new(dictionary)).unwrap();
+    // Bit width 1, bit packed run of 8 values with indices 0 and 1
+    decoder.set_data(ByteBufferPtr::new(vec![1, 3, 0b10]), 2).unwrap();
+    let mut indices = vec![0; 2];
+    assert_eq!(
+      decoder.get_indices(&mut indices).unwrap_err(),
+      general_err!("Dictionary index 1 out of bounds, dictionary has 1 values")
+    );
+
+    decoder.set_data(ByteBufferPtr::new(vec![1, 3, 0b10]), 2).unwrap();
+    let mut buffer = vec![0; 2];
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      general_err!("Dictionary index 1 out of bounds, dictionary has 1 values")
+    );
+    // RLE run of 2 values with index 1
+    decoder.set_data(ByteBufferPtr::new(vec![1, 4, 1]), 2).unwrap();
+    assert!(decoder.get(&mut buffer).is_err());
+  }
+
+  #[test]
+  fn test_decoders_corrupt_data() {
+    // Delta bit packed headers with invalid blocks
+    let mut decoder = DeltaBitPackDecoder::<Int32Type>::new();
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![0x80, 1, 0, 1, 0]), 1).unwrap_err(),
+      general_err!("Invalid block size 128 with 0 mini blocks")
+    );
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![12, 1, 1, 0]), 1).unwrap_err(),
+      general_err!("Invalid number of values 12 in a mini block, must be a multiple of 8")
+    );
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![0x80, 1, 4, 0xFF]), 1).is_err());
+
+    // Bit width of deltas exceeds size of values, and truncated mini block
+    let mut buffer = vec![0; 2];
+    decoder.set_data(ByteBufferPtr::new(vec![8, 1, 2, 0, 0, 33]), 2).unwrap();
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      general_err!("Invalid bit width 33 of deltas, maximum is 32")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![8, 1, 2, 0, 0, 8]), 2).unwrap();
+    assert!(decoder.get(&mut buffer).is_err());
+    decoder.set_data(ByteBufferPtr::new(vec![8, 1, 2, 0, 0, 8]), 2).unwrap();
+    assert!(decoder.skip(2).is_err());
+
+    let mut decoder = DeltaBitPackDecoder::<FloatType>::new();
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![8, 1, 1, 0]), 1).unwrap_err(),
+      general_err!("DeltaBitPackDecoder only supports Int32Type and Int64Type")
+    );
+
+    // Negative length of a byte array
+    let mut decoder = DeltaLengthByteArrayDecoder::<ByteArrayType>::new();
+    decoder.set_data(ByteBufferPtr::new(vec![0x80, 1, 4, 1, 1]), 1).unwrap();
+    assert_eq!(
+      decoder.get(&mut vec![ByteArray::new(); 1]).unwrap_err(),
+      general_err!("Invalid byte array length -1")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![0x80, 1, 4, 1, 1]), 1).unwrap();
+    assert!(decoder.skip(1).is_err());
+
+    let mut decoder = DeltaByteArrayDecoder::<ByteArrayType>::new();
+    assert!(decoder.get(&mut vec![ByteArray::new(); 1]).is_err());
+
+    // Dictionary indices without bit width, or with bit width over 32 bits
+    let mut decoder = DictDecoder::<Int32Type>::new();
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![]), 0).is_ok());
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![]), 1).unwrap_err(),
+      eof_err!("Not enough bytes to decode bit width of indices")
+    );
+    assert_eq!(
+      decoder.set_data(ByteBufferPtr::new(vec![40]), 1).unwrap_err(),
+      general_err!("Invalid bit width 40 of dictionary indices, maximum is 32")
+    );
+    decoder.set_data(ByteBufferPtr::new(vec![1, 2, 0]), 1).unwrap();
+    assert_eq!(
+      decoder.get(&mut vec![0; 1]).unwrap_err(),
+      general_err!("DictDecoder has no dictionary, call set_dict() first")
+    );
+    let mut dictionary = PlainDecoder::<Int32Type>::new(-1);
+    dictionary.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0]), 2).unwrap();
+    assert!(decoder.set_dict(Box::new(dictionary)).is_err());
+
+    // Invalid arguments of spaced decoding
+    let mut decoder = PlainDecoder::<Int32Type>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0]), 1).unwrap();
+    assert!(decoder.get_spaced(&mut vec![0; 2], 3, &[0b1]).is_err());
+    assert!(decoder.get_spaced(&mut vec![0; 9], 8, &[0b1]).is_err());
+
+    // Truncated RLE data and invalid type length
+    let mut decoder = RleValueDecoder::<BoolType>::new();
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![10, 0, 0, 0, 1]), 1).is_err());
+    assert!(decoder.set_data(ByteBufferPtr::new(vec![10, 0]), 1).is_err());
+    let mut decoder = PlainDecoder::<FixedLenByteArrayType>::new(0);
+    decoder.set_data(ByteBufferPtr::new(vec![1, 2]), 1).unwrap();
+    assert_eq!(
+      decoder.get(&mut vec![ByteArray::new(); 1]).unwrap_err(),
+      general_err!("Invalid type length 0 of FIXED_LEN_BYTE_ARRAY values")
+    );
+  }
+
+  #[test]
+  fn test_skip_plain_not_enough_bytes() {
+    let mut decoder = PlainDecoder::<Int32Type>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(vec![0; 10]), 3).unwrap();
+    assert_eq!(decoder.skip(2).unwrap(), 2);
+    assert!(decoder.skip(1).is_err());
+
+    let mut decoder = PlainDecoder::<ByteArrayType>::new(-1);
+    decoder.set_data(ByteBufferPtr::new(vec![1, 0, 0, 0, 42, 5, 0, 0, 0]), 2).unwrap();
+    assert_eq!(decoder.skip(1).unwrap(), 1);
+    assert!(decoder.skip(1).is_err());
+  }
+
   // Test column descriptor for the column.
   // Used for testing of Int32Type decoders and as a placeholder for delta encodings.
-  fn get_test_column_desc_ptr() -> ColumnDescPtr {
-    let type_ptr =
-      Rc::new(Tpe::primitive_type_builder("col", Type::INT32).build().unwrap());
+  fn get_test_column_desc_ptr<T: DataType>() -> ColumnDescPtr {
+    let type_ptr = Rc::new(
+      Tpe::primitive_type_builder("col", T::get_physical_type()).build().unwrap());
     Rc::new(ColumnDescriptor::new(type_ptr, None, 0, 0, ColumnPath::from("col")))
   }
 
+  // Encodes `data` with `encoding` and returns decoder set to decode it, dictionary
+  // encoding is requested with `PLAIN_DICTIONARY`.
+  fn make_test_decoder<T: 'static + DataType>(
+    data: &[T::T],
+    encoding: Encoding
+  ) -> Box<Decoder<T>> {
+    let descr = get_test_column_desc_ptr::<T>();
+    let mem_tracker = Rc::new(MemTracker::new());
+    if encoding == Encoding::PLAIN_DICTIONARY {
+      let mut encoder = DictEncoder::<T>::new(descr.clone(), mem_tracker);
+      encoder.put(data).expect("ok to encode");
+      let mut dictionary = PlainDecoder::<T>::new(descr.type_length());
+      dictionary.set_data(encoder.write_dict().expect("ok to write dictionary"),
+        encoder.num_entries()).expect("ok to set data");
+      let mut decoder = DictDecoder::<T>::new();
+      decoder.set_dict(Box::new(dictionary)).expect("ok to set dictionary");
+      decoder.set_data(encoder.write_indices().expect("ok to write indices"),
+        data.len()).expect("ok to set data");
+      Box::new(decoder)
+    } else {
+      let mut encoder = get_encoder::<T>(descr.clone(), encoding, mem_tracker)
+        .expect("get encoder");
+      encoder.put(data).expect("ok to encode");
+      let mut decoder = get_decoder::<T>(descr, encoding).expect("get decoder");
+      decoder.set_data(encoder.flush_buffer().expect("ok to flush buffer"), data.len())
+        .expect("ok to set data");
+      decoder
+    }
+  }
+
+  // Encodes values, and then alternates between skipping and reading values of
+  // different batch sizes.
+  fn test_skip_decode<T: 'static + DataType>(data: Vec<T::T>, encoding: Encoding) {
+    let mut decoder = make_test_decoder::<T>(&data[..], encoding);
+    let batch_sizes = [1, 7, 64, 3, 130];
+    let mut offset = 0;
+    let mut i = 0;
+    while offset < data.len() {
+      let batch_size = batch_sizes[i % batch_sizes.len()];
+      let expected = cmp::min(batch_size, data.len() - offset);
+      if i % 2 == 0 {
+        assert_eq!(decoder.skip(batch_size).expect("ok to skip"), expected);
+      } else {
+        let mut buffer = vec![T::T::default(); batch_size];
+        assert_eq!(decoder.get(&mut buffer[..]).expect("ok to decode"), expected);
+        assert_eq!(&buffer[..expected], &data[offset..offset + expected]);
+      }
+      offset += expected;
+      i += 1;
+      assert_eq!(decoder.values_left(), data.len() - offset);
+    }
+    assert_eq!(decoder.skip(1).expect("ok to skip"), 0);
+  }
+
+  // Decodes `data` with `get_spaced` in batches, where every third slot is null.
+  fn test_get_spaced_decode<T: 'static + DataType>(data: Vec<T::T>, encoding: Encoding) {
+    let mut decoder = make_test_decoder::<T>(&data[..], encoding);
+    let mut values = data.iter();
+    let mut num_values_left = data.len();
+    let mut slot = 0;
+    while num_values_left > 0 {
+      let mut valid_bits = vec![0; 8];
+      let mut num_slots = 0;
+      let mut null_count = 0;
+      while num_slots < 50 && num_values_left > 0 {
+        if (slot + num_slots) % 3 == 1 {
+          null_count += 1;
+        } else {
+          set_array_bit(&mut valid_bits, num_slots);
+          num_values_left -= 1;
+        }
+        num_slots += 1;
+      }
+
+      let mut buffer = vec![T::T::default(); num_slots];
+      let slots_read = decoder.get_spaced(&mut buffer[..], null_count, &valid_bits)
+        .expect("ok to decode");
+      assert_eq!(slots_read, num_slots);
+      for i in 0..num_slots {
+        if (slot + i) % 3 != 1 {
+          assert_eq!(Some(&buffer[i]), values.next());
+        }
+      }
+      slot += num_slots;
+    }
+    assert_eq!(decoder.values_left(), 0);
+
+    // Not enough values left for a batch
+    let mut buffer = vec![T::T::default(); 2];
+    assert!(decoder.get_spaced(&mut buffer[..], 1, &[1]).is_err());
+  }
+
   fn test_get_decoder<T: 'static + DataType>(
     encoding: Encoding, err: Option<ParquetError>
   ) {
-    let descr = get_test_column_desc_ptr();
+    let descr = get_test_column_desc_ptr::<T>();
     let decoder = get_decoder::<T>(descr, encoding);
     match err {
       Some(parquet_error) => {
@@ -1237,7 +2534,7 @@ mod tests {
     encoding: Encoding
   ) {
     // Encode data
-    let mut encoder = get_encoder::<T>(get_test_column_desc_ptr(), encoding,
+    let mut encoder = get_encoder::<T>(get_test_column_desc_ptr::<T>(), encoding,
       Rc::new(MemTracker::new())).expect("get encoder");
 
     for v in &data[..] {
@@ -1249,7 +2546,7 @@ mod tests {
     let expected: Vec<T::T> = data.iter().flat_map(|s| s.clone()).collect();
 
     // Decode data and compare with original
-    let mut decoder = get_decoder::<T>(get_test_column_desc_ptr(), encoding)
+    let mut decoder = get_decoder::<T>(get_test_column_desc_ptr::<T>(), encoding)
       .expect("get decoder");
 
     let mut result = vec![T::T::default(); expected.len()];
@@ -28,8 +28,8 @@ use data_type::*;
 use encodings::rle::RleEncoder;
 use errors::{ParquetError, Result};
 use schema::types::ColumnDescPtr;
-use util::bit_util::{log2, num_required_bits, BitWriter};
-use util::memory::{Buffer, ByteBuffer, ByteBufferPtr, MemTrackerPtr};
+use util::bit_util::{ceil, log2, num_required_bits, BitWriter};
+use util::memory::{Buffer, ByteBuffer, ByteBufferPtr, MemTrackerPtr, TrackedMemory};
 use util::hash_util;
 
 // ----------------------------------------------------------------------
@@ -46,6 +46,13 @@ pub trait Encoder<T: DataType> {
   /// Returns the encoding type of this encoder.
   fn encoding(&self) -> Encoding;
 
+  /// Returns an estimate of the encoded size in bytes of the values put since the last
+  /// flush, without flushing the buffered values.
+  ///
+  /// Column writers use the estimate to decide when a data page has reached its size
+  /// limit. Values buffered internally, which are not encoded yet, may not be included.
+  fn estimated_data_encoded_size(&self) -> usize;
+
   /// Flushes the underlying byte buffer that's being processed by this encoder, and
   /// return the immutable copy of it. This will also reset the internal state.
   fn flush_buffer(&mut self) -> Result<ByteBufferPtr>;
@@ -53,11 +60,22 @@ pub trait Encoder<T: DataType> {
 
 /// Gets a encoder for the particular data type `T` and encoding `encoding`. Memory usage
 /// for the encoder instance is tracked by `mem_tracker`.
+///
+/// Returns error if encoding is not supported for the physical type of the column.
 pub fn get_encoder<T: DataType>(
   desc: ColumnDescPtr,
   encoding: Encoding,
   mem_tracker: MemTrackerPtr
 ) -> Result<Box<Encoder<T>>> where T: 'static {
+  if !is_encoding_supported(encoding, desc.physical_type()) {
+    return Err(general_err!(
+      "Encoding {} is not supported for column {} of type {}",
+      encoding,
+      desc.path().string(),
+      desc.physical_type()
+    ));
+  }
+
   let encoder: Box<Encoder<T>> = match encoding {
     Encoding::PLAIN => {
       Box::new(PlainEncoder::new(desc, mem_tracker, vec![]))
@@ -66,22 +84,40 @@ pub fn get_encoder<T: DataType>(
       Box::new(DictEncoder::new(desc, mem_tracker))
     },
     Encoding::RLE => {
-      Box::new(RleValueEncoder::new())
+      Box::new(RleValueEncoder::new().with_mem_tracker(mem_tracker))
     },
     Encoding::DELTA_BINARY_PACKED => {
-      Box::new(DeltaBitPackEncoder::new())
+      Box::new(DeltaBitPackEncoder::new().with_mem_tracker(mem_tracker))
     },
     Encoding::DELTA_LENGTH_BYTE_ARRAY => {
-      Box::new(DeltaLengthByteArrayEncoder::new())
+      Box::new(DeltaLengthByteArrayEncoder::new().with_mem_tracker(mem_tracker))
     },
     Encoding::DELTA_BYTE_ARRAY => {
-      Box::new(DeltaByteArrayEncoder::new())
+      Box::new(DeltaByteArrayEncoder::new().with_mem_tracker(mem_tracker))
     },
     e => return Err(nyi_err!("Encoding {} is not supported.", e))
   };
   Ok(encoder)
 }
 
+/// Returns `false` if values of `physical_type` cannot be encoded with `encoding`.
+///
+/// `PLAIN` and dictionary encodings support all physical types, `RLE` supports only
+/// BOOLEAN, `DELTA_BINARY_PACKED` supports INT32 and INT64, and byte array delta
+/// encodings support only BYTE_ARRAY.
+pub fn is_encoding_supported(encoding: Encoding, physical_type: Type) -> bool {
+  match encoding {
+    Encoding::RLE => physical_type == Type::BOOLEAN,
+    Encoding::DELTA_BINARY_PACKED => {
+      physical_type == Type::INT32 || physical_type == Type::INT64
+    },
+    Encoding::DELTA_LENGTH_BYTE_ARRAY | Encoding::DELTA_BYTE_ARRAY => {
+      physical_type == Type::BYTE_ARRAY
+    },
+    _ => true
+  }
+}
+
 // ----------------------------------------------------------------------
 // Plain encoding
 
@@ -133,6 +169,11 @@ impl<T: DataType> Encoder<T> for PlainEncoder<T> {
     Encoding::PLAIN
   }
 
+  #[inline]
+  fn estimated_data_encoded_size(&self) -> usize {
+    self.buffer.size() + self.bit_writer.bytes_written()
+  }
+
   #[inline]
   default fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     self.buffer.write(self.bit_writer.flush_buffer())?;
@@ -190,6 +231,16 @@ const INITIAL_HASH_TABLE_SIZE: usize = 1024;
 const MAX_HASH_LOAD: f32 = 0.7;
 const HASH_SLOT_EMPTY: i32 = -1;
 
+/// Slot of the dictionary hash table: index of the dictionary entry stored in the slot,
+/// or `HASH_SLOT_EMPTY`, and hash of the entry value.
+#[derive(Clone, Copy)]
+struct HashSlot {
+  index: i32,
+  hash: u32
+}
+
+const EMPTY_HASH_SLOT: HashSlot = HashSlot { index: HASH_SLOT_EMPTY, hash: 0 };
+
 /// Dictionary encoder.
 /// The dictionary encoding builds a dictionary of values encountered in a given column.
 /// The dictionary page is written first, before the data pages of the column chunk.
@@ -200,6 +251,13 @@ const HASH_SLOT_EMPTY: i32 = -1;
 /// Data page format: the bit width used to encode the entry ids stored as 1 byte
 /// (max bit width = 32), followed by the values encoded using RLE/Bit packed described
 /// above (with the given bit width).
+///
+/// Unique values are kept in a contiguous arena of value bytes. Values are looked up
+/// in an open-addressing hash table with linear probing, which stores the hash of the
+/// value next to its index, so that value bytes are only compared when hashes match
+/// and the table is grown without rehashing. Values are compared by their bytes, e.g.
+/// `0.0` and `-0.0` are different dictionary entries, and all NaN values with the same
+/// bit pattern are a single entry.
 pub struct DictEncoder<T: DataType> {
   // Descriptor for the column to be encoded.
   desc: ColumnDescPtr,
@@ -211,54 +269,88 @@ pub struct DictEncoder<T: DataType> {
   // `j % hash_table_size`, but uses far fewer CPU cycles.
   mod_bitmask: u32,
 
-  // Stores indices which map (many-to-one) to the values in the `uniques` array.
-  // Here we are using fix-sized array with linear probing.
-  // A slot with `HASH_SLOT_EMPTY` indicates the slot is not currently occupied.
-  hash_slots: Buffer<i32>,
+  // Slots of the hash table, which map (many-to-one) to the dictionary entries.
+  // A slot with `HASH_SLOT_EMPTY` index is not currently occupied.
+  hash_slots: Buffer<HashSlot>,
 
   // Indices that have not yet be written out by `write_indices()`.
   buffered_indices: Buffer<i32>,
 
-  // The unique observed values.
-  uniques: Buffer<T::T>,
+  // Bytes of the unique observed values, stored back to back.
+  dict_values: ByteBuffer,
+
+  // Offsets of the unique values in `dict_values`, followed by the end offset of the
+  // last value.
+  dict_offsets: Buffer<usize>,
 
   // The number of bytes needed to encode this dictionary
   dict_encoded_size: u64,
 
   // Tracking memory usage for the various data structures in this struct.
-  mem_tracker: MemTrackerPtr
+  mem_tracker: MemTrackerPtr,
+
+  _phantom: PhantomData<T>
 }
 
 impl<T: DataType> DictEncoder<T> {
   /// Creates new dictionary encoder.
   pub fn new(desc: ColumnDescPtr, mem_tracker: MemTrackerPtr) -> Self {
     let mut slots = Buffer::new().with_mem_tracker(mem_tracker.clone());
-    slots.resize(INITIAL_HASH_TABLE_SIZE, -1);
+    slots.resize(INITIAL_HASH_TABLE_SIZE, EMPTY_HASH_SLOT);
+    let mut offsets = Buffer::new().with_mem_tracker(mem_tracker.clone());
+    offsets.push(0);
     Self {
       desc: desc,
       hash_table_size: INITIAL_HASH_TABLE_SIZE,
       mod_bitmask: (INITIAL_HASH_TABLE_SIZE - 1) as u32,
       hash_slots: slots,
       buffered_indices: Buffer::new().with_mem_tracker(mem_tracker.clone()),
-      uniques: Buffer::new().with_mem_tracker(mem_tracker.clone()),
+      dict_values: Buffer::new().with_mem_tracker(mem_tracker.clone()),
+      dict_offsets: offsets,
       dict_encoded_size: 0,
-      mem_tracker: mem_tracker
+      mem_tracker: mem_tracker,
+      _phantom: PhantomData
     }
   }
 
   /// Returns number of unique entries in the dictionary.
   pub fn num_entries(&self) -> usize {
-    self.uniques.size()
+    self.dict_offsets.size() - 1
+  }
+
+  /// Returns size in bytes of the dictionary once it is PLAIN encoded, i.e. the size of
+  /// the dictionary page before compression.
+  ///
+  /// Column writers compare this value with the dictionary page size limit to decide
+  /// when to fall back to a non-dictionary encoding.
+  pub fn dict_encoded_size(&self) -> u64 {
+    self.dict_encoded_size
   }
 
   /// Writes out the dictionary values with PLAIN encoding in a byte buffer, and return
   /// the result.
   #[inline]
   pub fn write_dict(&self) -> Result<ByteBufferPtr> {
-    let mut plain_encoder = PlainEncoder::<T>::new(
-      self.desc.clone(), self.mem_tracker.clone(), vec![]);
-    plain_encoder.put(self.uniques.data())?;
-    plain_encoder.flush_buffer()
+    let mut result = Vec::with_capacity(self.dict_encoded_size as usize);
+    match self.desc.physical_type() {
+      Type::BOOLEAN => {
+        let mut bit_writer = BitWriter::new(self.num_entries() / 8 + 1);
+        for value in self.dict_values.data() {
+          bit_writer.put_value(*value as u64, 1);
+        }
+        result.extend_from_slice(bit_writer.flush_buffer());
+      },
+      Type::BYTE_ARRAY => {
+        for i in 0..self.num_entries() {
+          let value = self.dict_value(i);
+          result.extend_from_slice((value.len() as u32).to_le().as_bytes());
+          result.extend_from_slice(value);
+        }
+      },
+      _ => result.extend_from_slice(self.dict_values.data())
+    }
+    self.mem_tracker.alloc(result.capacity() as i64);
+    Ok(ByteBufferPtr::new(result).with_mem_tracker(self.mem_tracker.clone()))
   }
 
   /// Writes out the dictionary values with RLE encoding in a byte buffer, and return the
@@ -271,7 +363,6 @@ impl<T: DataType> DictEncoder<T> {
       RleEncoder::max_buffer_size(bit_width, self.buffered_indices.size());
     let mut buffer: Vec<u8> = vec![0; buffer_len as usize];
     buffer[0] = bit_width as u8;
-    self.mem_tracker.alloc(buffer.capacity() as i64);
 
     // Write bit width in the first byte
     buffer.write((self.bit_width() as u8).as_bytes())?;
@@ -282,45 +373,71 @@ impl<T: DataType> DictEncoder<T> {
       }
     }
     self.buffered_indices.clear();
-    Ok(ByteBufferPtr::new(encoder.consume()?))
+    let result = encoder.consume()?;
+    self.mem_tracker.alloc(result.capacity() as i64);
+    Ok(ByteBufferPtr::new(result).with_mem_tracker(self.mem_tracker.clone()))
   }
 
   #[inline]
   fn put_one(&mut self, value: &T::T) -> Result<()> {
-    let mut j = (hash_util::hash(value, 0) & self.mod_bitmask) as usize;
-    let mut index = self.hash_slots[j];
+    let bytes = value.as_bytes();
+    let hash = hash_util::hash(value, 0);
+    let mut j = (hash & self.mod_bitmask) as usize;
+    let mut slot = self.hash_slots[j];
 
-    while index != HASH_SLOT_EMPTY && self.uniques[index as usize] != *value {
+    while slot.index != HASH_SLOT_EMPTY &&
+        (slot.hash != hash || self.dict_value(slot.index as usize) != bytes) {
       j += 1;
       if j == self.hash_table_size {
         j = 0;
       }
-      index = self.hash_slots[j];
+      slot = self.hash_slots[j];
     }
 
-    if index == HASH_SLOT_EMPTY {
-      index = self.uniques.size() as i32;
-      self.hash_slots[j] = index;
-      self.add_dict_key(value.clone());
+    if slot.index == HASH_SLOT_EMPTY {
+      slot = HashSlot { index: self.num_entries() as i32, hash: hash };
+      self.hash_slots[j] = slot;
+      self.add_dict_key(value)?;
 
-      if self.uniques.size() > (self.hash_table_size as f32 * MAX_HASH_LOAD) as usize {
+      if self.num_entries() > (self.hash_table_size as f32 * MAX_HASH_LOAD) as usize {
         self.double_table_size();
       }
     }
 
-    self.buffered_indices.push(index);
+    self.buffered_indices.push(slot.index);
     Ok(())
   }
 
   #[inline]
-  fn add_dict_key(&mut self, value: T::T) {
-    self.uniques.push(value);
-    self.dict_encoded_size += mem::size_of::<T::T>() as u64;
+  fn add_dict_key(&mut self, value: &T::T) -> Result<()> {
+    self.dict_encoded_size += self.plain_encoded_size(value) as u64;
+    self.dict_values.write_all(value.as_bytes())?;
+    let end = self.dict_values.size();
+    self.dict_offsets.push(end);
+    Ok(())
+  }
+
+  /// Returns bytes of the `i`th dictionary entry.
+  #[inline]
+  fn dict_value(&self, i: usize) -> &[u8] {
+    let offsets = self.dict_offsets.data();
+    &self.dict_values.data()[offsets[i]..offsets[i + 1]]
+  }
+
+  /// Returns number of bytes the value takes in the PLAIN encoded dictionary.
+  /// Byte arrays are prefixed with their length, so the actual length of the value is
+  /// used instead of the size of its in-memory representation.
+  #[inline]
+  fn plain_encoded_size(&self, value: &T::T) -> usize {
+    match self.desc.physical_type() {
+      Type::BYTE_ARRAY => mem::size_of::<u32>() + value.as_bytes().len(),
+      _ => value.as_bytes().len()
+    }
   }
 
   #[inline]
   fn bit_width(&self) -> u8 {
-    let num_entries = self.uniques.size();
+    let num_entries = self.num_entries();
     if num_entries == 0 { 0 }
     else if num_entries == 1 { 1 }
     else { log2(num_entries as u64) as u8 }
@@ -329,29 +446,26 @@ impl<T: DataType> DictEncoder<T> {
   #[inline]
   fn double_table_size(&mut self) {
     let new_size = self.hash_table_size * 2;
+    let new_bitmask = (new_size - 1) as u32;
     let mut new_hash_slots = Buffer::new().with_mem_tracker(self.mem_tracker.clone());
-    new_hash_slots.resize(new_size, HASH_SLOT_EMPTY);
-    for i in 0..self.hash_table_size {
-      let index = self.hash_slots[i];
-      if index == HASH_SLOT_EMPTY {
+    new_hash_slots.resize(new_size, EMPTY_HASH_SLOT);
+    // Entries are unique, so the first empty slot is taken without comparing values
+    for slot in self.hash_slots.data() {
+      if slot.index == HASH_SLOT_EMPTY {
         continue;
       }
-      let value = &self.uniques[index as usize];
-      let mut j = (hash_util::hash(value, 0) & ((new_size - 1) as u32)) as usize;
-      let mut slot = new_hash_slots[j];
-      while slot != HASH_SLOT_EMPTY && self.uniques[slot as usize] != *value {
+      let mut j = (slot.hash & new_bitmask) as usize;
+      while new_hash_slots[j].index != HASH_SLOT_EMPTY {
         j += 1;
         if j == new_size {
           j = 0;
         }
-        slot = new_hash_slots[j];
       }
-
-      new_hash_slots[j] = index;
+      new_hash_slots[j] = *slot;
     }
 
     self.hash_table_size = new_size;
-    self.mod_bitmask = (new_size - 1) as u32;
+    self.mod_bitmask = new_bitmask;
     mem::replace(&mut self.hash_slots, new_hash_slots);
   }
 }
@@ -370,6 +484,14 @@ impl<T: DataType> Encoder<T> for DictEncoder<T> {
     Encoding::PLAIN_DICTIONARY
   }
 
+  /// Returns size of the bit-packed indices, prefixed with the bit width, ignoring RLE
+  /// runs.
+  #[inline]
+  fn estimated_data_encoded_size(&self) -> usize {
+    let num_bits = self.buffered_indices.size() * self.bit_width() as usize;
+    1 + ceil(num_bits as i64, 8) as usize
+  }
+
   #[inline]
   fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     self.write_indices()
@@ -387,6 +509,8 @@ pub struct RleValueEncoder<T: DataType> {
   // Buffer with raw values that we collect,
   // when flushing buffer they are encoded using RLE encoder
   encoder: Option<RleEncoder>,
+  // Memory of the RLE encoder buffer
+  memory: TrackedMemory,
   _phantom: PhantomData<T>
 }
 
@@ -395,9 +519,17 @@ impl<T: DataType> RleValueEncoder<T> {
   pub fn new() -> Self {
     Self {
       encoder: None,
+      memory: TrackedMemory::new(),
       _phantom: PhantomData
     }
   }
+
+  /// Adds [`MemTracker`](`::util::memory::MemTracker`) to track memory of the internal
+  /// buffers and of the flushed buffers of this encoder.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.memory = self.memory.with_mem_tracker(mem_tracker);
+    self
+  }
 }
 
 impl<T: DataType> Encoder<T> for RleValueEncoder<T> {
@@ -410,6 +542,15 @@ impl<T: DataType> Encoder<T> for RleValueEncoder<T> {
     Encoding::RLE
   }
 
+  #[inline]
+  fn estimated_data_encoded_size(&self) -> usize {
+    match self.encoder {
+      // Encoded data is prefixed with its length
+      Some(ref encoder) => mem::size_of::<i32>() + encoder.len(),
+      None => 0
+    }
+  }
+
   #[inline]
   default fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     panic!("RleValueEncoder only supports BoolType");
@@ -421,6 +562,7 @@ impl Encoder<BoolType> for RleValueEncoder<BoolType> {
   default fn put(&mut self, values: &[bool]) -> Result<()> {
     if self.encoder.is_none() {
       self.encoder = Some(RleEncoder::new(1, DEFAULT_RLE_BUFFER_LEN));
+      self.memory.set_size(DEFAULT_RLE_BUFFER_LEN);
     }
     let rle_encoder = self.encoder.as_mut().unwrap();
     for value in values {
@@ -451,7 +593,7 @@ impl Encoder<BoolType> for RleValueEncoder<BoolType> {
     // Reset rle encoder for the next batch
     rle_encoder.clear();
 
-    Ok(ByteBufferPtr::new(encoded_data))
+    Ok(self.memory.to_buffer_ptr(encoded_data))
   }
 }
 
@@ -497,6 +639,7 @@ pub struct DeltaBitPackEncoder<T: DataType> {
   num_mini_blocks: usize,
   values_in_block: usize,
   deltas: Vec<i64>,
+  memory: TrackedMemory,
   _phantom: PhantomData<T>
 }
 
@@ -509,6 +652,12 @@ impl<T: DataType> DeltaBitPackEncoder<T> {
     assert!(mini_block_size % 8 == 0);
     Self::assert_supported_type();
 
+    let mut memory = TrackedMemory::new();
+    memory.set_size(
+      MAX_PAGE_HEADER_WRITER_SIZE + MAX_BIT_WRITER_SIZE +
+        block_size * mem::size_of::<i64>()
+    );
+
     DeltaBitPackEncoder {
       page_header_writer: BitWriter::new(MAX_PAGE_HEADER_WRITER_SIZE),
       bit_writer: BitWriter::new(MAX_BIT_WRITER_SIZE),
@@ -520,10 +669,18 @@ impl<T: DataType> DeltaBitPackEncoder<T> {
       num_mini_blocks: num_mini_blocks,
       values_in_block: 0, // will be at most block_size
       deltas: vec![0; block_size],
+      memory: memory,
       _phantom: PhantomData
     }
   }
 
+  /// Adds [`MemTracker`](`::util::memory::MemTracker`) to track memory of the internal
+  /// buffers and of the flushed buffers of this encoder.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.memory = self.memory.with_mem_tracker(mem_tracker);
+    self
+  }
+
   /// Writes page header for blocks, this method is invoked when we are done encoding
   /// values. It is also okay to encode when no values have been provided
   fn write_page_header(&mut self) {
@@ -641,16 +798,21 @@ impl<T: DataType> Encoder<T> for DeltaBitPackEncoder<T> {
     Encoding::DELTA_BINARY_PACKED
   }
 
+  /// Returns size of the encoded blocks, values of the current block are not included
+  /// until the block is full.
+  fn estimated_data_encoded_size(&self) -> usize {
+    self.bit_writer.bytes_written()
+  }
+
   fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     // Write remaining values
     self.flush_block_values()?;
     // Write page header with total values
     self.write_page_header();
 
-    let mut buffer = ByteBuffer::new();
-    buffer.write(self.page_header_writer.flush_buffer())?;
-    buffer.write(self.bit_writer.flush_buffer())?;
-    buffer.flush()?;
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(self.page_header_writer.flush_buffer());
+    buffer.extend_from_slice(self.bit_writer.flush_buffer());
 
     // Reset state
     self.page_header_writer.clear();
@@ -660,7 +822,7 @@ impl<T: DataType> Encoder<T> for DeltaBitPackEncoder<T> {
     self.current_value = 0;
     self.values_in_block = 0;
 
-    Ok(buffer.consume())
+    Ok(self.memory.to_buffer_ptr(buffer))
   }
 }
 
@@ -755,6 +917,8 @@ pub struct DeltaLengthByteArrayEncoder<T: DataType> {
   len_encoder: DeltaBitPackEncoder<Int32Type>,
   // byte array data
   data: Vec<ByteArray>,
+  // memory of the buffered byte array data
+  memory: TrackedMemory,
   _phantom: PhantomData<T>
 }
 
@@ -764,9 +928,18 @@ impl<T: DataType> DeltaLengthByteArrayEncoder<T> {
     Self {
       len_encoder: DeltaBitPackEncoder::new(),
       data: vec![],
+      memory: TrackedMemory::new(),
       _phantom: PhantomData
     }
   }
+
+  /// Adds [`MemTracker`](`::util::memory::MemTracker`) to track memory of the internal
+  /// buffers and of the flushed buffers of this encoder.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.len_encoder = self.len_encoder.with_mem_tracker(mem_tracker.clone());
+    self.memory = self.memory.with_mem_tracker(mem_tracker);
+    self
+  }
 }
 
 impl<T: DataType> Encoder<T> for DeltaLengthByteArrayEncoder<T> {
@@ -778,6 +951,11 @@ impl<T: DataType> Encoder<T> for DeltaLengthByteArrayEncoder<T> {
     Encoding::DELTA_LENGTH_BYTE_ARRAY
   }
 
+  fn estimated_data_encoded_size(&self) -> usize {
+    // Memory size is the number of bytes of the buffered byte arrays
+    self.len_encoder.estimated_data_encoded_size() + self.memory.size()
+  }
+
   default fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     panic!("DeltaLengthByteArrayEncoder only supports ByteArrayType");
   }
@@ -788,9 +966,12 @@ impl Encoder<ByteArrayType> for DeltaLengthByteArrayEncoder<ByteArrayType> {
     let lengths: Vec<i32> =
       values.iter().map(|byte_array| byte_array.len() as i32).collect();
     self.len_encoder.put(&lengths)?;
+    let mut num_bytes = self.memory.size();
     for byte_array in values {
       self.data.push(byte_array.clone());
+      num_bytes += byte_array.len();
     }
+    self.memory.set_size(num_bytes);
     Ok(())
   }
 
@@ -802,7 +983,8 @@ impl Encoder<ByteArrayType> for DeltaLengthByteArrayEncoder<ByteArrayType> {
       total_bytes.extend_from_slice(byte_array.data());
     });
     self.data.clear();
-    Ok(ByteBufferPtr::new(total_bytes))
+    self.memory.set_size(0);
+    Ok(self.memory.to_buffer_ptr(total_bytes))
   }
 }
 
@@ -815,6 +997,8 @@ pub struct DeltaByteArrayEncoder<T: DataType> {
   prefix_len_encoder: DeltaBitPackEncoder<Int32Type>,
   suffix_writer: DeltaLengthByteArrayEncoder<T>,
   previous: Vec<u8>,
+  // memory of the previous value
+  memory: TrackedMemory,
   _phantom: PhantomData<T>
 }
 
@@ -825,9 +1009,20 @@ impl<T: DataType> DeltaByteArrayEncoder<T> {
       prefix_len_encoder: DeltaBitPackEncoder::<Int32Type>::new(),
       suffix_writer: DeltaLengthByteArrayEncoder::<T>::new(),
       previous: vec![],
+      memory: TrackedMemory::new(),
       _phantom: PhantomData
     }
   }
+
+  /// Adds [`MemTracker`](`::util::memory::MemTracker`) to track memory of the internal
+  /// buffers and of the flushed buffers of this encoder.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.prefix_len_encoder =
+      self.prefix_len_encoder.with_mem_tracker(mem_tracker.clone());
+    self.suffix_writer = self.suffix_writer.with_mem_tracker(mem_tracker.clone());
+    self.memory = self.memory.with_mem_tracker(mem_tracker);
+    self
+  }
 }
 
 impl<T: DataType> Encoder<T> for DeltaByteArrayEncoder<T> {
@@ -839,6 +1034,11 @@ impl<T: DataType> Encoder<T> for DeltaByteArrayEncoder<T> {
     Encoding::DELTA_BYTE_ARRAY
   }
 
+  fn estimated_data_encoded_size(&self) -> usize {
+    self.prefix_len_encoder.estimated_data_encoded_size() +
+      self.suffix_writer.estimated_data_encoded_size()
+  }
+
   default fn flush_buffer(&mut self) -> Result<ByteBufferPtr> {
     panic!("DeltaByteArrayEncoder only supports ByteArrayType");
   }
@@ -865,6 +1065,7 @@ impl Encoder<ByteArrayType> for DeltaByteArrayEncoder<ByteArrayType> {
     }
     self.prefix_len_encoder.put(&prefix_lengths)?;
     self.suffix_writer.put(&suffixes)?;
+    self.memory.set_size(self.previous.capacity());
     Ok(())
   }
 
@@ -879,7 +1080,7 @@ impl Encoder<ByteArrayType> for DeltaByteArrayEncoder<ByteArrayType> {
     let suffixes = self.suffix_writer.flush_buffer()?;
     total_bytes.extend_from_slice(suffixes.data());
 
-    Ok(ByteBufferPtr::new(total_bytes))
+    Ok(self.memory.to_buffer_ptr(total_bytes))
   }
 }
 
@@ -948,6 +1149,195 @@ mod tests {
     FixedLenByteArrayType::test(Encoding::PLAIN_DICTIONARY, TEST_SET_SIZE, 100);
   }
 
+  #[test]
+  fn test_dict_encoded_size() {
+    fn test_dict_size<T: DataType>(type_len: i32, values: &[T::T]) {
+      let mut encoder = create_test_dict_encoder::<T>(type_len);
+      assert_eq!(encoder.dict_encoded_size(), 0);
+      encoder.put(values).unwrap();
+      let dict = encoder.write_dict().unwrap();
+      assert_eq!(encoder.dict_encoded_size(), dict.len() as u64);
+    }
+
+    test_dict_size::<Int32Type>(-1, &[1, 2, 1, 3, 2]);
+    test_dict_size::<DoubleType>(-1, &[1.0, 2.0, 1.0]);
+    test_dict_size::<Int96Type>(-1, &vec![Int96::from(vec![1, 2, 3]); 4]);
+    test_dict_size::<ByteArrayType>(-1, &[
+      ByteArray::from("a"),
+      ByteArray::from("parquet"),
+      ByteArray::from("a"),
+      ByteArray::from(vec![0; 1024])
+    ]);
+    test_dict_size::<FixedLenByteArrayType>(3, &[
+      ByteArray::from("abc"),
+      ByteArray::from("def"),
+      ByteArray::from("abc")
+    ]);
+  }
+
+  #[test]
+  fn test_estimated_data_encoded_size() {
+    let mut encoder = create_test_encoder::<Int32Type>(-1, Encoding::PLAIN);
+    assert_eq!(encoder.estimated_data_encoded_size(), 0);
+    encoder.put(&(0..100).collect::<Vec<i32>>()).unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 400);
+    assert_eq!(encoder.flush_buffer().unwrap().len(), 400);
+    assert_eq!(encoder.estimated_data_encoded_size(), 0);
+
+    let mut encoder = create_test_encoder::<BoolType>(-1, Encoding::PLAIN);
+    encoder.put(&[true; 10]).unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 2);
+
+    let mut encoder = create_test_encoder::<ByteArrayType>(-1, Encoding::PLAIN);
+    encoder.put(&[ByteArray::from("a"), ByteArray::from("bc")]).unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 11);
+
+    // Indices of 4 dictionary entries take 2 bits each
+    let mut encoder = create_test_dict_encoder::<Int32Type>(-1);
+    encoder.put(&(0..100).map(|i| i % 4).collect::<Vec<i32>>()).unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 1 + 25);
+    encoder.flush_buffer().unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 1);
+
+    // Buffered values of encoders, that encode values in blocks, are not included
+    let mut encoder = create_test_encoder::<Int32Type>(-1, Encoding::DELTA_BINARY_PACKED);
+    encoder.put(&[1, 2, 3]).unwrap();
+    assert_eq!(encoder.estimated_data_encoded_size(), 0);
+    encoder.put(&(0..300).collect::<Vec<i32>>()).unwrap();
+    let estimate = encoder.estimated_data_encoded_size();
+    assert!(estimate > 0 && estimate <= encoder.flush_buffer().unwrap().len());
+
+    let mut encoder =
+      create_test_encoder::<ByteArrayType>(-1, Encoding::DELTA_LENGTH_BYTE_ARRAY);
+    encoder.put(&[ByteArray::from("parquet"), ByteArray::from("rs")]).unwrap();
+    let estimate = encoder.estimated_data_encoded_size();
+    assert!(estimate >= 9 && estimate <= encoder.flush_buffer().unwrap().len());
+  }
+
+  #[test]
+  fn test_dict_encoder_entries() {
+    let mut encoder = create_test_dict_encoder::<Int32Type>(-1);
+    let values: Vec<i32> = (0..5000).chain(0..5000).collect();
+    encoder.put(&values).unwrap();
+    assert_eq!(encoder.num_entries(), 5000);
+    assert_eq!(encoder.dict_encoded_size(), 5000 * 4);
+
+    // Floating point values are compared by their bytes
+    let mut encoder = create_test_dict_encoder::<DoubleType>(-1);
+    encoder.put(&[0.0, -0.0, ::std::f64::NAN, 0.0, ::std::f64::NAN]).unwrap();
+    assert_eq!(encoder.num_entries(), 3);
+  }
+
+  #[test]
+  fn test_dict_encoder_float_values() {
+    fn bytes_of<V: AsBytes>(values: &[V]) -> Vec<Vec<u8>> {
+      values.iter().map(|v| v.as_bytes().to_vec()).collect()
+    }
+
+    fn test_float_dict<T: DataType>(values: &[T::T], entries: &[T::T]) {
+      let mut encoder = create_test_dict_encoder::<T>(-1);
+      encoder.put(values).unwrap();
+      assert_eq!(encoder.num_entries(), entries.len());
+      let dict = encoder.write_dict().unwrap();
+
+      let mut dict_decoder = PlainDecoder::<T>::new(-1);
+      dict_decoder.set_data(dict.clone(), entries.len()).unwrap();
+      let mut result = vec![T::T::default(); entries.len()];
+      assert_eq!(dict_decoder.get(&mut result).unwrap(), entries.len());
+      assert_eq!(bytes_of(&result), bytes_of(entries));
+
+      // Values are decoded with the exact bits they were written with
+      let mut dict_decoder = PlainDecoder::<T>::new(-1);
+      dict_decoder.set_data(dict, entries.len()).unwrap();
+      let mut decoder = create_test_dict_decoder::<T>();
+      decoder.set_dict(Box::new(dict_decoder)).unwrap();
+      decoder.set_data(encoder.flush_buffer().unwrap(), values.len()).unwrap();
+      let mut result = vec![T::T::default(); values.len()];
+      assert_eq!(decoder.get(&mut result).unwrap(), values.len());
+      assert_eq!(bytes_of(&result), bytes_of(values));
+    }
+
+    // Zeros of different signs are separate entries, NaN values are deduplicated only
+    // if they have the same bit pattern
+    let nan = ::std::f32::NAN;
+    let other_nan = f32::from_bits(nan.to_bits() | 1);
+    test_float_dict::<FloatType>(
+      &[0.0, -0.0, nan, 0.0, nan, other_nan, -0.0, other_nan],
+      &[0.0, -0.0, nan, other_nan]
+    );
+
+    let nan = ::std::f64::NAN;
+    let other_nan = f64::from_bits(nan.to_bits() | 1);
+    test_float_dict::<DoubleType>(
+      &[-0.0, nan, other_nan, 0.0, -0.0, nan, 1.5, 0.0],
+      &[-0.0, nan, other_nan, 0.0, 1.5]
+    );
+  }
+
+  #[test]
+  fn test_get_encoder_unsupported_type() {
+    let ty = SchemaType::primitive_type_builder("col", Type::INT32).build().unwrap();
+    let desc = ColumnDescriptor::new(Rc::new(ty), None, 0, 0, ColumnPath::from("col"));
+    let res = get_encoder::<Int32Type>(
+      Rc::new(desc),
+      Encoding::DELTA_BYTE_ARRAY,
+      Rc::new(MemTracker::new())
+    );
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Encoding DELTA_BYTE_ARRAY is not supported for column col of type INT32"
+      )
+    );
+
+    assert!(is_encoding_supported(Encoding::RLE, Type::BOOLEAN));
+    assert!(!is_encoding_supported(Encoding::RLE, Type::INT32));
+    assert!(is_encoding_supported(Encoding::DELTA_BINARY_PACKED, Type::INT64));
+    assert!(!is_encoding_supported(Encoding::DELTA_BINARY_PACKED, Type::DOUBLE));
+    assert!(!is_encoding_supported(Encoding::DELTA_BYTE_ARRAY, Type::INT96));
+    assert!(is_encoding_supported(Encoding::PLAIN_DICTIONARY, Type::INT96));
+  }
+
+  #[test]
+  fn test_get_encoder_mem_tracker() {
+    let encodings = vec![
+      Encoding::DELTA_BINARY_PACKED,
+      Encoding::PLAIN,
+      Encoding::PLAIN_DICTIONARY
+    ];
+    for encoding in encodings {
+      let mem_tracker = Rc::new(MemTracker::new());
+      {
+        let desc = Rc::new(create_test_col_desc(-1, Type::INT32));
+        let mut encoder =
+          get_encoder::<Int32Type>(desc, encoding, mem_tracker.clone()).unwrap();
+        encoder.put(&[1, 2, 3, 4]).unwrap();
+        assert!(mem_tracker.memory_usage() > 0, "Encoding {} is not tracked", encoding);
+
+        let usage = mem_tracker.memory_usage();
+        let buffer = encoder.flush_buffer().unwrap();
+        assert!(buffer.is_mem_tracked());
+        assert!(mem_tracker.memory_usage() >= usage);
+      }
+      assert_eq!(mem_tracker.memory_usage(), 0, "Encoding {} leaks memory", encoding);
+    }
+  }
+
+  #[test]
+  fn test_byte_array_encoder_mem_tracker() {
+    let mem_tracker = Rc::new(MemTracker::new());
+    {
+      let desc = Rc::new(create_test_col_desc(-1, Type::BYTE_ARRAY));
+      let mut encoder = get_encoder::<ByteArrayType>(
+        desc, Encoding::DELTA_BYTE_ARRAY, mem_tracker.clone()).unwrap();
+      let values = vec![ByteArray::from("parquet"), ByteArray::from("parquet-rs")];
+      encoder.put(&values).unwrap();
+      assert!(mem_tracker.memory_usage() > 0);
+      let _ = encoder.flush_buffer().unwrap();
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
   trait EncodingTester<T: DataType> {
     fn test(enc: Encoding, total: usize, type_length: i32) {
       let result = match enc {
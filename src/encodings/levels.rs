@@ -22,21 +22,23 @@ use super::rle::{RleDecoder, RleEncoder};
 use basic::Encoding;
 use data_type::AsBytes;
 use errors::{ParquetError, Result};
-use util::bit_util::{ceil, log2, BitReader, BitWriter};
+use util::bit_util::{ceil, log2, MsbBitReader, MsbBitWriter};
 use util::memory::ByteBufferPtr;
 
 enum InternalEncoder {
   RLE(RleEncoder),
-  BIT_PACKED(BitWriter)
+  RLE_V2(RleEncoder),
+  BIT_PACKED(MsbBitWriter)
 }
 
 enum InternalDecoder {
   RLE(RleDecoder),
-  BIT_PACKED(BitReader)
+  BIT_PACKED(MsbBitReader)
 }
 
 /// A encoder for definition/repetition levels.
-/// Currently only supports RLE and BIT_PACKED (dev/null) encoding.
+/// Currently only supports RLE and BIT_PACKED (dev/null) encoding, where BIT_PACKED
+/// levels are packed from the most significant bit, as in legacy parquet-mr files.
 pub struct LevelEncoder {
   bit_width: u8,
   encoder: InternalEncoder
@@ -47,30 +49,39 @@ impl LevelEncoder {
   /// For bit packed encoding it is assumed that buffer is already allocated with
   /// 'LevelEncoder::max_buffer_size' method.
   ///
-  /// Panics, if encoding is not supported
-  pub fn new(encoding: Encoding, max_level: i16, byte_buffer: Vec<u8>) -> Self {
+  /// Returns an error if encoding is not supported.
+  pub fn new(encoding: Encoding, max_level: i16, byte_buffer: Vec<u8>) -> Result<Self> {
     let bit_width = log2(max_level as u64 + 1) as u8;
     match encoding {
       Encoding::RLE => {
-        LevelEncoder {
+        Ok(LevelEncoder {
           bit_width: bit_width,
           encoder: InternalEncoder::RLE(
             RleEncoder::new_from_buf(bit_width, byte_buffer, mem::size_of::<i32>()))
-        }
+        })
       },
       Encoding::BIT_PACKED => {
         // Here we set full byte buffer without adjusting for num_buffered_values,
         // because byte buffer will already be allocated with size from
         // `max_buffer_size()` method.
-        LevelEncoder {
+        Ok(LevelEncoder {
           bit_width: bit_width,
-          encoder: InternalEncoder::BIT_PACKED(BitWriter::new_from_buf(byte_buffer, 0))
-        }
+          encoder: InternalEncoder::BIT_PACKED(MsbBitWriter::new_from_buf(byte_buffer))
+        })
       },
-      _ => panic!("Unsupported encoding type {}", encoding)
+      _ => Err(unsupported_encoding_err(encoding))
     }
   }
 
+  /// Creates new RLE level encoder for data page v2 based on max level and underlying
+  /// byte buffer. Unlike data page v1, encoded levels are not prefixed with their
+  /// length, which is stored in the data page v2 header instead.
+  pub fn v2(max_level: i16, byte_buffer: Vec<u8>) -> Self {
+    let bit_width = log2(max_level as u64 + 1) as u8;
+    let rle_encoder = RleEncoder::new_from_buf(bit_width, byte_buffer, 0);
+    LevelEncoder { bit_width: bit_width, encoder: InternalEncoder::RLE_V2(rle_encoder) }
+  }
+
   /// Put/encode levels vector into this level encoder.
   /// Returns number of encoded values that are less than or equal to length of the input
   /// buffer.
@@ -81,7 +92,8 @@ impl LevelEncoder {
   pub fn put(&mut self, buffer: &[i16]) -> Result<usize> {
     let mut num_encoded = 0;
     match self.encoder {
-      InternalEncoder::RLE(ref mut rle_encoder) => {
+      InternalEncoder::RLE(ref mut rle_encoder) |
+      InternalEncoder::RLE_V2(ref mut rle_encoder) => {
         for value in buffer {
           if !rle_encoder.put(*value as u64)? {
             return Err(general_err!("RLE buffer is full"));
@@ -97,7 +109,6 @@ impl LevelEncoder {
           }
           num_encoded += 1;
         }
-        bit_packed_encoder.flush();
       },
     }
     Ok(num_encoded)
@@ -106,22 +117,24 @@ impl LevelEncoder {
   /// Computes max buffer size for level encoder/decoder based on encoding, max
   /// repetition/definition level and number of total buffered values (includes null
   /// values).
+  ///
+  /// Returns an error if encoding is not supported.
   #[inline]
   pub fn max_buffer_size(
     encoding: Encoding,
     max_level: i16,
     num_buffered_values: usize
-  ) -> usize {
+  ) -> Result<usize> {
     let bit_width = log2(max_level as u64 + 1) as u8;
     match encoding {
       Encoding::RLE => {
-        RleEncoder::max_buffer_size(bit_width, num_buffered_values) +
-          RleEncoder::min_buffer_size(bit_width)
+        Ok(RleEncoder::max_buffer_size(bit_width, num_buffered_values) +
+          RleEncoder::min_buffer_size(bit_width))
       },
       Encoding::BIT_PACKED => {
-        ceil((num_buffered_values * bit_width as usize) as i64, 8) as usize
+        Ok(ceil((num_buffered_values * bit_width as usize) as i64, 8) as usize)
       },
-      _ => panic!("Unsupported encoding type {}", encoding)
+      _ => Err(unsupported_encoding_err(encoding))
     }
   }
 
@@ -139,6 +152,7 @@ impl LevelEncoder {
         encoded_data[0..len_bytes.len()].copy_from_slice(len_bytes);
         Ok(encoded_data)
       },
+      InternalEncoder::RLE_V2(rle_encoder) => rle_encoder.consume(),
       InternalEncoder::BIT_PACKED(bit_packed_encoder) => {
         Ok(bit_packed_encoder.consume())
       },
@@ -147,9 +161,11 @@ impl LevelEncoder {
 }
 
 /// A decoder for definition/repetition levels.
-/// Currently only supports RLE and BIT_PACKED (dev/null) encoding.
+/// Currently only supports RLE and BIT_PACKED (dev/null) encoding, where BIT_PACKED
+/// levels are packed from the most significant bit, as in legacy parquet-mr files.
 pub struct LevelDecoder {
   bit_width: u8,
+  max_level: i16,
   num_values: Option<usize>,
   decoder: InternalDecoder
 }
@@ -159,15 +175,23 @@ impl LevelDecoder {
   /// This method only initializes level decoder, `set_data()` method must be called
   /// before reading any value.
   ///
-  /// Panics if encoding is not supported
-  pub fn new(encoding: Encoding, max_level: i16) -> Self {
+  /// Returns an error if encoding is not supported, e.g. when it is read from a
+  /// malformed page header.
+  pub fn new(encoding: Encoding, max_level: i16) -> Result<Self> {
     let bit_width = log2(max_level as u64 + 1) as u8;
     let decoder = match encoding {
       Encoding::RLE => InternalDecoder::RLE(RleDecoder::new(bit_width)),
-      Encoding::BIT_PACKED => InternalDecoder::BIT_PACKED(BitReader::from(Vec::new())),
-      _ => panic!("Unsupported encoding type {}", encoding),
+      Encoding::BIT_PACKED => {
+        InternalDecoder::BIT_PACKED(MsbBitReader::from(Vec::new()))
+      },
+      _ => return Err(unsupported_encoding_err(encoding))
     };
-    LevelDecoder { bit_width: bit_width, num_values: None, decoder: decoder }
+    Ok(LevelDecoder {
+      bit_width: bit_width,
+      max_level: max_level,
+      num_values: None,
+      decoder: decoder
+    })
   }
 
   /// Sets data for this level decoder, and returns total number of bytes set.
@@ -177,30 +201,48 @@ impl LevelDecoder {
   ///
   /// Both RLE and BIT_PACKED level decoders set `num_buffered_values` as total number of
   /// values that they can return and track num values.
+  ///
+  /// Returns an error if `data` is shorter than the length of RLE encoded levels.
   #[inline]
-  pub fn set_data(&mut self, num_buffered_values: usize, data: ByteBufferPtr) -> usize {
+  pub fn set_data(
+    &mut self,
+    num_buffered_values: usize,
+    data: ByteBufferPtr
+  ) -> Result<usize> {
     self.num_values = Some(num_buffered_values);
     match self.decoder {
       InternalDecoder::RLE(ref mut rle_decoder) => {
         let i32_size = mem::size_of::<i32>();
-        let data_size = read_num_bytes!(i32, i32_size, data.as_ref()) as usize;
+        if data.len() < i32_size {
+          return Err(eof_err!("Not enough bytes to decode length of levels"));
+        }
+        let data_size = read_num_bytes!(i32, i32_size, data.as_ref());
+        if data_size < 0 || data_size as usize > data.len() - i32_size {
+          return Err(eof_err!(
+            "Expected {} bytes of levels, found {}",
+            data_size,
+            data.len() - i32_size
+          ));
+        }
+        let data_size = data_size as usize;
         rle_decoder.set_data(data.range(i32_size, data_size));
-        i32_size + data_size
+        Ok(i32_size + data_size)
       },
       InternalDecoder::BIT_PACKED(ref mut bit_packed_decoder) => {
         // Set appropriate number of bytes: if max size is larger than buffer - set full
         // buffer
         let num_bytes = ceil((num_buffered_values * self.bit_width as usize) as i64, 8);
         let data_size = cmp::min(num_bytes as usize, data.len());
-        bit_packed_decoder.reset(data.range(data.start(), data_size));
-        data_size
+        bit_packed_decoder.reset(data.range(0, data_size));
+        Ok(data_size)
       },
     }
   }
 
   /// Sets byte array explicitly when start position `start` and length `len` are known in
   /// advance. Only supported by RLE level decoder.
-  /// Returns number of total bytes set for this decoder (len)
+  /// Returns number of total bytes set for this decoder (len), or an error if the range
+  /// is out of bounds of `data` or the decoder is not an RLE level decoder.
   #[inline]
   pub fn set_data_range(
     &mut self,
@@ -208,46 +250,77 @@ impl LevelDecoder {
     data: &ByteBufferPtr,
     start: usize,
     len: usize
-  ) -> usize {
+  ) -> Result<usize> {
     match self.decoder {
       InternalDecoder::RLE(ref mut rle_decoder) => {
+        if start + len > data.len() {
+          return Err(eof_err!(
+            "Expected {} bytes of levels at offset {}, found {}",
+            len,
+            start,
+            data.len()
+          ));
+        }
         rle_decoder.set_data(data.range(start, len));
         self.num_values = Some(num_buffered_values);
-        len
+        Ok(len)
       },
-      _ => panic!("set_data_range() method is only supported by RLE encoding type")
+      _ => Err(general_err!("set_data_range() is only supported by RLE level decoder"))
     }
   }
 
   /// Decodes values and puts them into `buffer`.
   /// Returns number of values that were successfully decoded (less than or equal to
-  /// buffer length).
+  /// buffer length), or an error if no data is set.
+  ///
+  /// With `strict-validation` feature enabled, also returns an error if any of decoded
+  /// levels is larger than max level.
   #[inline]
   pub fn get(&mut self, buffer: &mut [i16]) -> Result<usize> {
-    assert!(self.num_values.is_some(), "No data set for decoding");
+    let num_values = match self.num_values {
+      Some(num_values) => num_values,
+      None => return Err(general_err!("No data set for decoding"))
+    };
+    // Max length we can read
+    let len = cmp::min(num_values, buffer.len());
     let values_read = match self.decoder {
       InternalDecoder::RLE(ref mut rle_decoder) => {
-        // Max length we can read
-        let len = cmp::min(self.num_values.unwrap(), buffer.len());
-        rle_decoder.get_batch::<i16>(&mut buffer[0..len])?
+        rle_decoder.get_i16_batch(&mut buffer[0..len])?
       },
       InternalDecoder::BIT_PACKED(ref mut bit_packed_decoder) => {
         // When extracting values from bit reader, it might return more values than left
         // because of padding to a full byte, we use num_values to track precise number
         // of values.
-        let len = cmp::min(self.num_values.unwrap(), buffer.len());
-        let values_read = bit_packed_decoder.get_batch::<i16>(
-          &mut buffer[..len], self.bit_width as usize
-        );
-        values_read
+        bit_packed_decoder.get_batch::<i16>(&mut buffer[..len], self.bit_width as usize)?
       }
     };
+    if cfg!(feature = "strict-validation") {
+      check_levels(&buffer[..values_read], self.max_level)?;
+    }
     // Update current num_values
-    self.num_values = self.num_values.map(|len| len - values_read);
+    self.num_values = Some(num_values - values_read);
     Ok(values_read)
   }
 }
 
+/// Returns an error if any of `levels` is larger than `max_level`. Bit width of levels
+/// allows such values when max level is not a power of two minus one, so they can
+/// only come from corrupt data.
+#[inline]
+fn check_levels(levels: &[i16], max_level: i16) -> Result<()> {
+  match levels.iter().find(|&&level| level > max_level) {
+    Some(level) => {
+      Err(general_err!("Level {} is out of range for max level {}", level, max_level))
+    },
+    None => Ok(())
+  }
+}
+
+/// Returns error for encoding that is not supported for levels.
+fn unsupported_encoding_err(encoding: Encoding) -> ParquetError {
+  general_err!("Unsupported encoding {} of levels", encoding)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -255,13 +328,13 @@ mod tests {
   use util::test_common::random_numbers_range;
 
   fn test_internal_roundtrip(enc: Encoding, levels: &[i16], max_level: i16) {
-    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len());
-    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]);
+    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len()).unwrap();
+    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]).unwrap();
     encoder.put(&levels).expect("put() should be OK");
     let encoded_levels = encoder.consume().expect("consume() should be OK");
 
-    let mut decoder = LevelDecoder::new(enc, max_level);
-    decoder.set_data(levels.len(), ByteBufferPtr::new(encoded_levels));
+    let mut decoder = LevelDecoder::new(enc, max_level).unwrap();
+    decoder.set_data(levels.len(), ByteBufferPtr::new(encoded_levels)).unwrap();
     let mut buffer = vec![0; levels.len()];
     let num_decoded = decoder.get(&mut buffer).expect("get() should be OK");
     assert_eq!(num_decoded, levels.len());
@@ -270,13 +343,13 @@ mod tests {
 
   // Performs incremental read until all bytes are read
   fn test_internal_roundtrip_incremental(enc: Encoding, levels: &[i16], max_level: i16) {
-    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len());
-    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]);
+    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len()).unwrap();
+    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]).unwrap();
     encoder.put(&levels).expect("put() should be OK");
     let encoded_levels = encoder.consume().expect("consume() should be OK");
 
-    let mut decoder = LevelDecoder::new(enc, max_level);
-    decoder.set_data(levels.len(), ByteBufferPtr::new(encoded_levels));
+    let mut decoder = LevelDecoder::new(enc, max_level).unwrap();
+    decoder.set_data(levels.len(), ByteBufferPtr::new(encoded_levels)).unwrap();
 
     let mut buffer = vec![0; levels.len() * 2];
     let mut total_decoded = 0;
@@ -299,16 +372,16 @@ mod tests {
   // Tests encoding/decoding of values when output buffer is larger than number of
   // encoded values
   fn test_internal_roundtrip_underflow(enc: Encoding, levels: &[i16], max_level: i16) {
-    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len());
-    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]);
+    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len()).unwrap();
+    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]).unwrap();
     // Encode only one value
     let num_encoded = encoder.put(&levels[0..1]).expect("put() should be OK");
     let encoded_levels = encoder.consume().expect("consume() should be OK");
     assert_eq!(num_encoded, 1);
 
-    let mut decoder = LevelDecoder::new(enc, max_level);
+    let mut decoder = LevelDecoder::new(enc, max_level).unwrap();
     // Set one encoded value as `num_buffered_values`
-    decoder.set_data(1, ByteBufferPtr::new(encoded_levels));
+    decoder.set_data(1, ByteBufferPtr::new(encoded_levels)).unwrap();
     let mut buffer = vec![0; levels.len()];
     let num_decoded = decoder.get(&mut buffer).expect("get() should be OK");
     assert_eq!(num_decoded, num_encoded);
@@ -317,8 +390,8 @@ mod tests {
 
   // Tests when encoded values are larger than encoder's buffer
   fn test_internal_roundtrip_overflow(enc: Encoding, levels: &[i16], max_level: i16) {
-    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len());
-    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]);
+    let size = LevelEncoder::max_buffer_size(enc, max_level, levels.len()).unwrap();
+    let mut encoder = LevelEncoder::new(enc, max_level, vec![0; size]).unwrap();
     let mut found_err = false;
     // Insert a large number of values, so we run out of space
     for _ in 0..100 {
@@ -336,6 +409,44 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_roundtrip_v2() {
+    let levels = vec![0, 1, 2, 3, 3, 3, 3, 3, 3, 2, 1, 0];
+    let max_level = 3;
+    let size =
+      LevelEncoder::max_buffer_size(Encoding::RLE, max_level, levels.len()).unwrap();
+    let mut encoder = LevelEncoder::v2(max_level, vec![0; size]);
+    encoder.put(&levels).expect("put() should be OK");
+    let encoded_levels = ByteBufferPtr::new(encoder.consume().expect("consume() is OK"));
+
+    // Levels are not prefixed with length, and can be decoded using byte range
+    let mut decoder = LevelDecoder::new(Encoding::RLE, max_level).unwrap();
+    let len = encoded_levels.len();
+    assert_eq!(
+      decoder.set_data_range(levels.len(), &encoded_levels, 0, len).unwrap(),
+      len
+    );
+    let mut buffer = vec![0; levels.len()];
+    let num_decoded = decoder.get(&mut buffer).expect("get() should be OK");
+    assert_eq!(num_decoded, levels.len());
+    assert_eq!(buffer, levels);
+  }
+
+  #[test]
+  fn test_bit_packed_legacy_levels() {
+    // Levels are packed from the most significant bit, and may follow other data
+    let data = ByteBufferPtr::new(vec![0xFF, 0b00000101, 0b00111001, 0b01110111]);
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, 7).unwrap();
+    assert_eq!(decoder.set_data(8, data.start_from(1)).unwrap(), 3);
+    let mut buffer = vec![0; 10];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 8);
+    assert_eq!(buffer, vec![0, 1, 2, 3, 4, 5, 6, 7, 0, 0]);
+
+    let mut encoder = LevelEncoder::new(Encoding::BIT_PACKED, 7, vec![0; 3]).unwrap();
+    assert_eq!(encoder.put(&[0, 1, 2, 3, 4, 5, 6, 7]).unwrap(), 8);
+    assert_eq!(encoder.consume().unwrap(), vec![0b00000101, 0b00111001, 0b01110111]);
+  }
+
   #[test]
   fn test_roundtrip_one() {
     let levels = vec![0, 1, 1, 1, 1, 0, 0, 0, 0, 1];
@@ -400,16 +511,16 @@ mod tests {
     let buffer = ByteBufferPtr::new(vec![5, 198, 2, 5, 42, 168, 10, 0, 2, 3, 36, 73]);
 
     let max_rep_level = 1;
-    let mut decoder = LevelDecoder::new(Encoding::RLE, max_rep_level);
-    assert_eq!(decoder.set_data_range(10, &buffer, 0, 3), 3);
+    let mut decoder = LevelDecoder::new(Encoding::RLE, max_rep_level).unwrap();
+    assert_eq!(decoder.set_data_range(10, &buffer, 0, 3).unwrap(), 3);
     let mut result = vec![0; 10];
     let num_decoded = decoder.get(&mut result).expect("get() should be OK");
     assert_eq!(num_decoded, 10);
     assert_eq!(result, vec![0, 1, 1, 0, 0, 0, 1, 1, 0, 1]);
 
     let max_def_level = 2;
-    let mut decoder = LevelDecoder::new(Encoding::RLE, max_def_level);
-    assert_eq!(decoder.set_data_range(10, &buffer, 3, 5), 5);
+    let mut decoder = LevelDecoder::new(Encoding::RLE, max_def_level).unwrap();
+    assert_eq!(decoder.set_data_range(10, &buffer, 3, 5).unwrap(), 5);
     let mut result = vec![0; 10];
     let num_decoded = decoder.get(&mut result).expect("get() should be OK");
     assert_eq!(num_decoded, 10);
@@ -417,15 +528,15 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(
-    expected = "set_data_range() method is only supported by RLE encoding type"
-  )]
   fn test_bit_packed_decoder_set_data_range() {
     // Buffer containing both repetition and definition levels
     let buffer = ByteBufferPtr::new(vec![1, 2, 3, 4, 5]);
     let max_level = 1;
-    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_level);
-    decoder.set_data_range(10, &buffer, 0, 3);
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_level).unwrap();
+    assert_eq!(
+      decoder.set_data_range(10, &buffer, 0, 3).unwrap_err(),
+      general_err!("set_data_range() is only supported by RLE level decoder")
+    );
   }
 
   #[test]
@@ -433,30 +544,118 @@ mod tests {
     // Test the maximum size that is assigned based on number of values and buffer length
     let buffer = ByteBufferPtr::new(vec![1, 2, 3, 4, 5]);
     let max_level = 1;
-    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_level);
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_level).unwrap();
     // This should reset to entire buffer
-    assert_eq!(decoder.set_data(1024, buffer.all()), buffer.len());
+    assert_eq!(decoder.set_data(1024, buffer.all()).unwrap(), buffer.len());
     // This should set smallest num bytes
-    assert_eq!(decoder.set_data(3, buffer.all()), 1);
+    assert_eq!(decoder.set_data(3, buffer.all()).unwrap(), 1);
+  }
+
+  #[test]
+  fn test_rle_decoder_set_data_malformed() {
+    let mut decoder = LevelDecoder::new(Encoding::RLE, 1).unwrap();
+    assert_eq!(
+      decoder.set_data(10, ByteBufferPtr::new(vec![1, 0])).unwrap_err(),
+      eof_err!("Not enough bytes to decode length of levels")
+    );
+    assert_eq!(
+      decoder.set_data(10, ByteBufferPtr::new(vec![8, 0, 0, 0, 5, 198])).unwrap_err(),
+      eof_err!("Expected 8 bytes of levels, found 2")
+    );
+    assert_eq!(
+      decoder.set_data(10, ByteBufferPtr::new(vec![255, 255, 255, 255])).unwrap_err(),
+      eof_err!("Expected -1 bytes of levels, found 0")
+    );
+    let buffer = ByteBufferPtr::new(vec![5, 198, 2]);
+    assert_eq!(
+      decoder.set_data_range(10, &buffer, 1, 3).unwrap_err(),
+      eof_err!("Expected 3 bytes of levels at offset 1, found 3")
+    );
+    assert_eq!(
+      LevelDecoder::new(Encoding::PLAIN, 1).err().unwrap(),
+      general_err!("Unsupported encoding PLAIN of levels")
+    );
+    assert_eq!(
+      LevelEncoder::new(Encoding::PLAIN, 1, vec![]).err().unwrap(),
+      general_err!("Unsupported encoding PLAIN of levels")
+    );
+    assert_eq!(
+      LevelEncoder::max_buffer_size(Encoding::DELTA_BINARY_PACKED, 1, 10).unwrap_err(),
+      general_err!("Unsupported encoding DELTA_BINARY_PACKED of levels")
+    );
   }
 
   #[test]
-  #[should_panic(expected = "No data set for decoding")]
   fn test_rle_level_decoder_get_no_set_data() {
-    // `get()` normally panics because bit_reader is not set for RLE decoding
-    // we have explicit check now in set_data
     let max_rep_level = 2;
-    let mut decoder = LevelDecoder::new(Encoding::RLE, max_rep_level);
+    let mut decoder = LevelDecoder::new(Encoding::RLE, max_rep_level).unwrap();
     let mut buffer = vec![0; 16];
-    decoder.get(&mut buffer).unwrap();
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      general_err!("No data set for decoding")
+    );
   }
 
   #[test]
-  #[should_panic(expected = "No data set for decoding")]
   fn test_bit_packed_level_decoder_get_no_set_data() {
     let max_rep_level = 2;
-    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_rep_level);
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, max_rep_level).unwrap();
     let mut buffer = vec![0; 16];
-    decoder.get(&mut buffer).unwrap();
+    assert_eq!(
+      decoder.get(&mut buffer).unwrap_err(),
+      general_err!("No data set for decoding")
+    );
+  }
+
+  #[test]
+  fn test_level_decoder_out_of_range_levels() {
+    // Levels of 2 bits with max level 2: bit-packed run of 0, 1, 2, 3, 0, 1, 2, 3
+    let data = vec![3, 0, 0, 0, 0x03, 0b11100100, 0b11100100];
+    let mut decoder = LevelDecoder::new(Encoding::RLE, 2).unwrap();
+    decoder.set_data(8, ByteBufferPtr::new(data)).unwrap();
+    let mut buffer = vec![0; 8];
+    if cfg!(feature = "strict-validation") {
+      assert_eq!(
+        decoder.get(&mut buffer).unwrap_err(),
+        general_err!("Level 3 is out of range for max level 2")
+      );
+    } else {
+      assert_eq!(decoder.get(&mut buffer).unwrap(), 8);
+      assert_eq!(buffer, vec![0, 1, 2, 3, 0, 1, 2, 3]);
+    }
+
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, 2).unwrap();
+    decoder.set_data(4, ByteBufferPtr::new(vec![0b00011011])).unwrap();
+    let mut buffer = vec![0; 4];
+    if cfg!(feature = "strict-validation") {
+      assert!(decoder.get(&mut buffer).is_err());
+    } else {
+      assert_eq!(decoder.get(&mut buffer).unwrap(), 4);
+    }
+  }
+
+  #[test]
+  fn test_rle_level_decoder_truncated_data() {
+    // Levels of 2 bits: complete bit-packed run of 8 values, followed by RLE run of 4
+    // values without the value of the run
+    let mut decoder = LevelDecoder::new(Encoding::RLE, 3).unwrap();
+    let data = vec![4, 0, 0, 0, 0x03, 0b11100100, 0b11100100, 0x08];
+    assert_eq!(decoder.set_data(12, ByteBufferPtr::new(data)).unwrap(), 8);
+    let mut buffer = vec![0; 12];
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 8);
+    assert_eq!(&buffer[..8], &[0, 1, 2, 3, 0, 1, 2, 3]);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 0);
+
+    // Bit-packed run of 16 values, which is truncated after the first 8 values
+    let data = vec![3, 0, 0, 0, 0x05, 0b11100100, 0b11100100];
+    assert_eq!(decoder.set_data(16, ByteBufferPtr::new(data)).unwrap(), 7);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 8);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 0);
+
+    // Bit-packed levels are truncated
+    let mut decoder = LevelDecoder::new(Encoding::BIT_PACKED, 3).unwrap();
+    assert_eq!(decoder.set_data(8, ByteBufferPtr::new(vec![0b00011011])).unwrap(), 1);
+    assert_eq!(decoder.get(&mut buffer).unwrap(), 4);
+    assert_eq!(&buffer[..4], &[0, 1, 2, 3]);
   }
 }
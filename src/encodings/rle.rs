@@ -351,7 +351,7 @@ impl RleDecoder {
 
   #[inline]
   pub fn get<T: Default>(&mut self) -> Result<Option<T>> {
-    assert!(size_of::<T>() <= 8);
+    check_value_size::<T>()?;
 
     while self.rle_left <= 0 && self.bit_packed_left <= 0 {
       if !self.reload() {
@@ -361,16 +361,14 @@ impl RleDecoder {
 
     let value =
       if self.rle_left > 0 {
-        let rle_value = unsafe {
-          transmute_copy::<u64, T>(
-            self.current_value.as_mut().expect("current_value should be Some"))
-        };
+        let current_value = self.current_value()?;
+        let rle_value = unsafe { transmute_copy::<u64, T>(&current_value) };
         self.rle_left -= 1;
         rle_value
       } else { // self.bit_packed_left > 0
-        let bit_reader = self.bit_reader.as_mut().expect("bit_reader should be Some");
-        let bit_packed_value = bit_reader
-          .get_value(self.bit_width as usize)
+        let bit_width = self.bit_width as usize;
+        let bit_packed_value = self.bit_reader()?
+          .get_value(bit_width)
           .ok_or(eof_err!("Not enough data for 'bit_packed_value'"))?;
         self.bit_packed_left -= 1;
         bit_packed_value
@@ -381,33 +379,33 @@ impl RleDecoder {
 
   #[inline]
   pub fn get_batch<T: Default>(&mut self, buffer: &mut [T]) -> Result<usize> {
-    assert!(self.bit_reader.is_some());
-    assert!(size_of::<T>() <= 8);
+    self.bit_reader()?;
+    check_value_size::<T>()?;
 
     let mut values_read = 0;
     while values_read < buffer.len() {
       if self.rle_left > 0 {
-        assert!(self.current_value.is_some());
+        let current_value = self.current_value()?;
         let num_values = cmp::min(buffer.len() - values_read, self.rle_left as usize);
         for i in 0..num_values {
-          let repeated_value = unsafe {
-            transmute_copy::<u64, T>(self.current_value.as_mut().unwrap())
-          };
+          let repeated_value = unsafe { transmute_copy::<u64, T>(&current_value) };
           buffer[values_read + i] = repeated_value;
         }
         self.rle_left -= num_values as u32;
         values_read += num_values;
       } else if self.bit_packed_left > 0 {
-        assert!(self.bit_reader.is_some());
-        let mut num_values = cmp::min(
+        let num_values = cmp::min(
           buffer.len() - values_read, self.bit_packed_left as usize
         );
-        if let Some(ref mut bit_reader) = self.bit_reader {
-          num_values = bit_reader.get_batch::<T>(
-            &mut buffer[values_read..values_read + num_values], self.bit_width as usize
-          );
-          self.bit_packed_left -= num_values as u32;
-          values_read += num_values;
+        let bit_width = self.bit_width as usize;
+        let num_values = self.bit_reader()?.get_batch::<T>(
+          &mut buffer[values_read..values_read + num_values], bit_width
+        )?;
+        self.bit_packed_left -= num_values as u32;
+        values_read += num_values;
+        if num_values == 0 {
+          // Not enough data for the rest of the bit-packed run
+          break;
         }
       } else {
         if !self.reload() {
@@ -426,40 +424,52 @@ impl RleDecoder {
     buffer: &mut [T],
     max_values: usize
   ) -> Result<usize> where T: Default + Clone {
-    assert!(buffer.len() >= max_values);
+    if buffer.len() < max_values {
+      return Err(general_err!(
+        "Buffer of {} values is too small for {} values",
+        buffer.len(),
+        max_values
+      ));
+    }
 
     let mut values_read = 0;
     while values_read < max_values {
       if self.rle_left > 0 {
-        assert!(self.current_value.is_some());
         let num_values = cmp::min(max_values - values_read, self.rle_left as usize);
-        let dict_idx = self.current_value.unwrap() as usize;
+        let dict_idx = self.current_value()? as usize;
+        if dict_idx >= dict.len() {
+          return Err(dict_index_err(dict_idx, dict.len()));
+        }
         for i in 0..num_values {
           buffer[values_read + i] = dict[dict_idx].clone();
         }
         self.rle_left -= num_values as u32;
         values_read += num_values;
       } else if self.bit_packed_left > 0 {
-        assert!(self.bit_reader.is_some());
         let mut num_values = cmp::min(
           max_values - values_read, self.bit_packed_left as usize
         );
-        if let Some(ref mut bit_reader) = self.bit_reader {
-          let mut index_buf = self.index_buf.unwrap();
-          num_values = cmp::min(num_values, index_buf.len());
-          loop {
+        match (self.bit_reader.as_mut(), self.index_buf.as_mut()) {
+          (Some(bit_reader), Some(index_buf)) => {
+            num_values = cmp::min(num_values, index_buf.len());
             num_values = bit_reader.get_batch::<i32>(
               &mut index_buf[..num_values], self.bit_width as usize
-            );
+            )?;
             for i in 0..num_values {
-              buffer[values_read + i] = dict[index_buf[i] as usize].clone();
-            }
-            self.bit_packed_left -= num_values as u32;
-            values_read += num_values;
-            if num_values < index_buf.len() {
-              break;
+              let dict_idx = index_buf[i] as u32 as usize;
+              if dict_idx >= dict.len() {
+                return Err(dict_index_err(dict_idx, dict.len()));
+              }
+              buffer[values_read + i] = dict[dict_idx].clone();
             }
-          }
+          },
+          _ => return Err(no_data_err())
+        }
+        self.bit_packed_left -= num_values as u32;
+        values_read += num_values;
+        if num_values == 0 {
+          // Not enough data for the rest of the bit-packed run
+          break;
         }
       } else {
         if !self.reload() {
@@ -471,18 +481,167 @@ impl RleDecoder {
     Ok(values_read)
   }
 
+  /// Decodes at most `buffer.len()` values into `buffer`, specialized for definition and
+  /// repetition levels: runs are filled at once and bit-packed values of small bit
+  /// widths are unpacked a word at a time.
+  ///
+  /// Returns the number of values decoded.
+  #[inline]
+  pub fn get_i16_batch(&mut self, buffer: &mut [i16]) -> Result<usize> {
+    self.bit_reader()?;
+    if self.bit_width > 16 {
+      return Err(general_err!("Bit width {} is out of range for levels", self.bit_width));
+    }
+
+    let mut values_read = 0;
+    while values_read < buffer.len() {
+      if self.rle_left > 0 {
+        let num_values = cmp::min(buffer.len() - values_read, self.rle_left as usize);
+        let value = self.current_value()? as i16;
+        for v in &mut buffer[values_read..values_read + num_values] {
+          *v = value;
+        }
+        self.rle_left -= num_values as u32;
+        values_read += num_values;
+      } else if self.bit_packed_left > 0 {
+        let num_values = cmp::min(
+          buffer.len() - values_read, self.bit_packed_left as usize
+        );
+        let bit_width = self.bit_width as usize;
+        let num_read = self.bit_reader()?.get_i16_batch(
+          &mut buffer[values_read..values_read + num_values], bit_width
+        )?;
+        self.bit_packed_left -= num_read as u32;
+        values_read += num_read;
+        if num_read < num_values {
+          break;
+        }
+      } else {
+        if !self.reload() {
+          break;
+        }
+      }
+    }
+
+    Ok(values_read)
+  }
+
+  /// Decodes at most `num_values` values of bit width 1 into `bitmap` starting at bit
+  /// `offset`, used to decode boolean values: runs are filled at once and bit-packed
+  /// values are copied a word at a time.
+  ///
+  /// Returns the number of values decoded.
+  #[inline]
+  pub fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    self.bit_reader()?;
+    if self.bit_width != 1 {
+      return Err(general_err!("Bit width {} is out of range for bitmap", self.bit_width));
+    }
+    if offset + num_values > bitmap.len() * 8 {
+      return Err(general_err!(
+        "Bitmap of {} bytes is too small for {} values at offset {}",
+        bitmap.len(),
+        num_values,
+        offset
+      ));
+    }
+
+    let mut values_read = 0;
+    while values_read < num_values {
+      if self.rle_left > 0 {
+        let num_run_values = cmp::min(num_values - values_read, self.rle_left as usize);
+        let value = self.current_value()? != 0;
+        bit_util::fill_array_bits(bitmap, offset + values_read, num_run_values, value);
+        self.rle_left -= num_run_values as u32;
+        values_read += num_run_values;
+      } else if self.bit_packed_left > 0 {
+        let num_packed_values = cmp::min(
+          num_values - values_read, self.bit_packed_left as usize
+        );
+        let num_read = self.bit_reader()?
+          .get_bitmap(bitmap, offset + values_read, num_packed_values)?;
+        self.bit_packed_left -= num_read as u32;
+        values_read += num_read;
+        if num_read < num_packed_values {
+          break;
+        }
+      } else {
+        if !self.reload() {
+          break;
+        }
+      }
+    }
+
+    Ok(values_read)
+  }
+
+  /// Skips at most `num_values` values without decoding them, runs are advanced and
+  /// bit-packed values are skipped in the underlying bit reader.
+  ///
+  /// Returns the number of values skipped.
+  #[inline]
+  pub fn skip(&mut self, num_values: usize) -> Result<usize> {
+    self.bit_reader()?;
+
+    let mut values_skipped = 0;
+    while values_skipped < num_values {
+      if self.rle_left > 0 {
+        let num_values = cmp::min(num_values - values_skipped, self.rle_left as usize);
+        self.rle_left -= num_values as u32;
+        values_skipped += num_values;
+      } else if self.bit_packed_left > 0 {
+        let num_values = cmp::min(
+          num_values - values_skipped, self.bit_packed_left as usize
+        );
+        let bit_width = self.bit_width as usize;
+        let num_skipped = self.bit_reader()?.skip(num_values, bit_width)?;
+        if num_skipped < num_values {
+          return Err(eof_err!("Not enough data for 'bit_packed_value'"));
+        }
+        self.bit_packed_left -= num_values as u32;
+        values_skipped += num_values;
+      } else {
+        if !self.reload() {
+          break;
+        }
+      }
+    }
+
+    Ok(values_skipped)
+  }
+
+  /// Returns bit reader, or an error if no data is set.
+  #[inline]
+  fn bit_reader(&mut self) -> Result<&mut BitReader> {
+    self.bit_reader.as_mut().ok_or_else(no_data_err)
+  }
+
+  /// Returns value of the current RLE run.
+  #[inline]
+  fn current_value(&self) -> Result<u64> {
+    self.current_value.ok_or_else(|| general_err!("No value of RLE run to decode"))
+  }
+
   #[inline]
   fn reload(&mut self) -> bool {
-    assert!(self.bit_reader.is_some());
     if let Some(ref mut bit_reader) = self.bit_reader {
       if let Some(indicator_value) = bit_reader.get_vlq_int() {
         if indicator_value & 1 == 1 {
-          self.bit_packed_left = ((indicator_value >> 1) * 8) as u32;
+          self.bit_packed_left = (indicator_value >> 1).wrapping_mul(8) as u32;
         } else {
           self.rle_left = (indicator_value >> 1) as u32;
           let value_width = bit_util::ceil(self.bit_width as i64, 8);
           self.current_value = bit_reader.get_aligned::<u64>(value_width as usize);
-          assert!(self.current_value.is_some());
+          if self.current_value.is_none() {
+            // Value of the run is truncated
+            self.rle_left = 0;
+            return false;
+          }
         }
         return true;
       } else {
@@ -493,12 +652,36 @@ impl RleDecoder {
   }
 }
 
+/// Returns error for decoding without data set.
+fn no_data_err() -> ParquetError {
+  general_err!("No data set for decoding")
+}
+
+/// Returns an error if values of type `T` do not fit into 64 bits of RLE values.
+#[inline]
+fn check_value_size<T>() -> Result<()> {
+  if size_of::<T>() > 8 {
+    return Err(general_err!("Values of {} bytes cannot be RLE decoded", size_of::<T>()));
+  }
+  Ok(())
+}
+
+/// Returns error for dictionary index that is out of bounds of the dictionary.
+fn dict_index_err(index: usize, dict_len: usize) -> ParquetError {
+  general_err!(
+    "Dictionary index {} out of bounds, dictionary has {} values",
+    index,
+    dict_len
+  )
+}
+
 
 #[cfg(test)]
 mod tests {
   use super::*;
   use rand::{self, thread_rng, Rng, SeedableRng};
   use util::memory::ByteBufferPtr;
+  use util::test_common::random_bools;
 
   const MAX_WIDTH: usize = 32;
 
@@ -516,6 +699,30 @@ mod tests {
     assert_eq!(buffer, expected);
   }
 
+  #[test]
+  fn test_rle_skip() {
+    // RLE run of 48 values of 3, followed by bit-packed 0-7, with bit width 3
+    let mut encoder = RleEncoder::new(3, 64);
+    for _ in 0..48 {
+      encoder.put(3).unwrap();
+    }
+    for i in 0..8 {
+      encoder.put(i).unwrap();
+    }
+    let buffer = ByteBufferPtr::new(encoder.consume().unwrap());
+
+    let mut decoder = RleDecoder::new(3);
+    decoder.set_data(buffer);
+    assert_eq!(decoder.skip(46).unwrap(), 46);
+    let mut values = vec![0; 4];
+    assert_eq!(decoder.get_batch::<i32>(&mut values).unwrap(), 4);
+    assert_eq!(values, vec![3, 3, 0, 1]);
+    assert_eq!(decoder.skip(3).unwrap(), 3);
+    assert_eq!(decoder.get::<i32>().unwrap(), Some(5));
+    assert_eq!(decoder.skip(10).unwrap(), 2);
+    assert_eq!(decoder.get::<i32>().unwrap(), None);
+  }
+
   #[test]
   fn test_rle_consume_flush_buffer() {
     let data = vec![1, 1, 1, 2, 2, 3, 3, 3];
@@ -646,6 +853,98 @@ mod tests {
     assert_eq!(&values_read[..], values);
   }
 
+  #[test]
+  fn test_rle_decode_bitmap() {
+    // Runs of repeated values mixed with bit-packed values
+    let mut values = vec![true; 100];
+    values.extend(random_bools(77));
+    values.extend(vec![false; 50]);
+    values.extend(random_bools(30));
+    let mut encoder = RleEncoder::new(1, 1024);
+    for &value in &values {
+      assert!(encoder.put(value as u64).unwrap());
+    }
+    let buffer = ByteBufferPtr::new(encoder.consume().unwrap());
+
+    let offset = 5;
+    let mut decoder = RleDecoder::new(1);
+    decoder.set_data(buffer);
+    let mut bitmap = vec![0xff; (offset + values.len() + 7) / 8];
+    let mut values_read = 0;
+    while values_read < values.len() {
+      let num_values =
+        decoder.get_bitmap(&mut bitmap, offset + values_read, 37).unwrap();
+      assert!(num_values > 0);
+      values_read += num_values;
+    }
+    assert_eq!(values_read, values.len());
+    assert_eq!(bitmap[0] & 0x1f, 0x1f);
+    for (i, &value) in values.iter().enumerate() {
+      assert_eq!(bit_util::get_array_bit(&bitmap, offset + i), value);
+    }
+  }
+
+  #[test]
+  fn test_rle_decode_corrupt_data() {
+    let mut decoder = RleDecoder::new(3);
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+      decoder.get_batch::<i32>(&mut buffer).unwrap_err(),
+      general_err!("No data set for decoding")
+    );
+    assert_eq!(decoder.skip(1).unwrap_err(), general_err!("No data set for decoding"));
+
+    // Bit-packed run of 8 values without data
+    decoder.set_data(ByteBufferPtr::new(vec![0x03]));
+    assert_eq!(
+      decoder.get::<i32>().unwrap_err(),
+      eof_err!("Not enough data for 'bit_packed_value'")
+    );
+
+    // Bit-packed run of 8 values, which is truncated after 2 values
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0x88]));
+    assert_eq!(
+      decoder.skip(8).unwrap_err(),
+      eof_err!("Not enough data for 'bit_packed_value'")
+    );
+
+    // Dictionary indexes do not fit into the buffer
+    decoder.set_data(ByteBufferPtr::new(vec![0x06, 0x00]));
+    assert_eq!(
+      decoder.get_batch_with_dict(&[1, 2, 3], &mut buffer[..2], 3).unwrap_err(),
+      general_err!("Buffer of 2 values is too small for 3 values")
+    );
+
+    // Bit width is too large for values
+    let mut decoder = RleDecoder::new(17);
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0xFF, 0xFF, 0xFF]));
+    let mut levels = vec![0; 8];
+    assert_eq!(
+      decoder.get_i16_batch(&mut levels).unwrap_err(),
+      general_err!("Bit width 17 is out of range for levels")
+    );
+    let mut decoder = RleDecoder::new(65);
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0xFF, 0xFF, 0xFF]));
+    assert_eq!(
+      decoder.get_batch::<u64>(&mut [0; 8]).unwrap_err(),
+      general_err!("Bit width 65 is out of range for values of 64 bits")
+    );
+
+    // Bitmap requires values of 1 bit and enough space for values
+    let mut bitmap = vec![0; 1];
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0xFF]));
+    assert_eq!(
+      decoder.get_bitmap(&mut bitmap, 0, 8).unwrap_err(),
+      general_err!("Bit width 65 is out of range for bitmap")
+    );
+    let mut decoder = RleDecoder::new(1);
+    decoder.set_data(ByteBufferPtr::new(vec![0x03, 0xFF]));
+    assert_eq!(
+      decoder.get_bitmap(&mut bitmap, 4, 8).unwrap_err(),
+      general_err!("Bitmap of 1 bytes is too small for 8 values at offset 4")
+    );
+  }
+
   #[test]
   fn test_rle_specific_sequences() {
     let mut expected_buffer = Vec::new();
@@ -763,6 +1062,17 @@ mod tests {
     let mut values_read: Vec<i32> = vec![0; values.len()];
     decoder.get_batch(&mut values_read[..]).expect("get_batch() should be OK");
     assert_eq!(&values_read[..], values);
+
+    // Verify batch read of levels
+    if bit_width <= 16 {
+      let mut decoder = RleDecoder::new(bit_width);
+      decoder.set_data(buffer.all());
+      let mut levels: Vec<i16> = vec![0; values.len()];
+      let num_read = decoder.get_i16_batch(&mut levels[..]).expect("should be OK");
+      assert_eq!(num_read, values.len());
+      let expected: Vec<i16> = values.iter().map(|v| *v as i16).collect();
+      assert_eq!(levels, expected);
+    }
   }
 
   #[test]
@@ -0,0 +1,379 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains a helper to write a set of files and commit them together, e.g. to replace
+//! partitions of a dataset.
+//!
+//! [`AtomicFileSet`](`AtomicFileSet`) creates every file under a temporary name next
+//! to its final path. Temporary names start with `.`, so files are not discovered by
+//! [`Dataset`](`::file::dataset::Dataset`) until they are committed. Commit links
+//! existing files to backup paths, renames all temporary files over their final paths
+//! and moves files scheduled for deletion away; if any step fails, previous files are
+//! restored.
+//!
+//! Every file is replaced with a single rename over the existing file, so readers never
+//! observe a partially written or missing file. Renames of different files are not
+//! atomic as a group, a reader that lists files while commit is in progress can see a
+//! mix of old and new files.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use std::rc::Rc;
+//!
+//! use parquet::file::atomic::AtomicFileSet;
+//! use parquet::file::properties::WriterProperties;
+//! use parquet::file::writer::{FileWriter, SerializedFileWriter};
+//! use parquet::schema::parser::parse_message_type;
+//!
+//! let schema = "message schema { REQUIRED INT32 a; }";
+//! let schema = Rc::new(parse_message_type(schema).unwrap());
+//! let props = Rc::new(WriterProperties::builder().build());
+//!
+//! let mut files = AtomicFileSet::new();
+//! for name in &["data/table/p=1/part-0.parquet", "data/table/p=2/part-0.parquet"] {
+//!   let file = files.create(Path::new(name)).unwrap();
+//!   let mut writer =
+//!     SerializedFileWriter::new(file, schema.clone(), props.clone()).unwrap();
+//!   // ... write row groups
+//!   writer.close().unwrap();
+//! }
+//! files.delete(Path::new("data/table/p=1/part-1.parquet")).unwrap();
+//! files.commit().unwrap();
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use errors::{ParquetError, Result};
+
+/// Number of file sets created by this process, makes temporary names unique.
+static NUM_FILE_SETS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set of files that are written under temporary names and committed together.
+///
+/// Files that are not committed, because `commit` failed or was not called, are
+/// removed when the set is dropped.
+pub struct AtomicFileSet {
+  id: String,
+  files: Vec<PendingFile>,
+  deletes: Vec<PathBuf>,
+  is_committed: bool
+}
+
+/// File written under a temporary path.
+struct PendingFile {
+  path: PathBuf,
+  temp_path: PathBuf
+}
+
+/// Change made by commit, which is reverted on failure.
+enum Change {
+  // Existing file at path (first) was linked or moved to backup path (second)
+  Backup(PathBuf, PathBuf),
+  // New file was moved into path
+  Create(PathBuf)
+}
+
+impl AtomicFileSet {
+  /// Creates new empty file set.
+  pub fn new() -> Self {
+    let nanos = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.subsec_nanos())
+      .unwrap_or(0);
+    let id = format!(
+      "{}-{}-{}",
+      process::id(),
+      nanos,
+      NUM_FILE_SETS.fetch_add(1, Ordering::SeqCst)
+    );
+    Self { id: id, files: Vec::new(), deletes: Vec::new(), is_committed: false }
+  }
+
+  /// Creates file that is committed into `path`, and returns handle of the file
+  /// created under a temporary path in the same directory. Parent directories are
+  /// created if they do not exist.
+  ///
+  /// Returns an error if the path is already part of this set.
+  pub fn create(&mut self, path: &Path) -> Result<fs::File> {
+    self.check_new_path(path)?;
+    let temp_path = self.sibling_path(path, "tmp")?;
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    let file = fs::File::create(&temp_path)?;
+    self.files.push(PendingFile { path: path.to_path_buf(), temp_path: temp_path });
+    Ok(file)
+  }
+
+  /// Schedules file at `path` to be deleted on commit, e.g. a file of a replaced
+  /// partition. Missing files are ignored.
+  ///
+  /// Returns an error if the path is already part of this set.
+  pub fn delete(&mut self, path: &Path) -> Result<()> {
+    self.check_new_path(path)?;
+    self.deletes.push(path.to_path_buf());
+    Ok(())
+  }
+
+  /// Returns final paths of created files in the order of creation.
+  pub fn paths(&self) -> Vec<&Path> {
+    self.files.iter().map(|file| file.path.as_path()).collect()
+  }
+
+  /// Returns temporary paths of created files in the order of creation.
+  pub fn temp_paths(&self) -> Vec<&Path> {
+    self.files.iter().map(|file| file.temp_path.as_path()).collect()
+  }
+
+  /// Commits all files of the set: temporary files are synced to disk and renamed into
+  /// their final paths, replacing existing files, and files scheduled for deletion
+  /// are removed.
+  ///
+  /// All files must be closed before commit. If commit fails, files that existed
+  /// before are restored on a best-effort basis, temporary files are removed and the
+  /// error is returned.
+  pub fn commit(mut self) -> Result<()> {
+    let mut changes = Vec::new();
+    match self.apply(&mut changes) {
+      Ok(()) => {
+        self.is_committed = true;
+        // Backups are not needed anymore, failure to remove them does not affect
+        // committed files
+        for change in changes {
+          if let Change::Backup(_, backup_path) = change {
+            let _ = fs::remove_file(backup_path);
+          }
+        }
+        Ok(())
+      },
+      Err(error) => {
+        for change in changes.into_iter().rev() {
+          let _ = match change {
+            Change::Backup(path, backup_path) => fs::rename(backup_path, path),
+            Change::Create(path) => fs::remove_file(path)
+          };
+        }
+        Err(error)
+      }
+    }
+  }
+
+  /// Discards all files of the set and removes temporary files.
+  pub fn abort(mut self) -> Result<()> {
+    self.remove_temp_files()
+  }
+
+  /// Backs up existing files and renames temporary files into final paths, recording
+  /// every change in `changes`.
+  fn apply(&self, changes: &mut Vec<Change>) -> Result<()> {
+    for file in &self.files {
+      fs::File::open(&file.temp_path)?.sync_all()?;
+      // Existing file stays in place until it is replaced by the rename
+      self.backup(&file.path, false, changes)?;
+      fs::rename(&file.temp_path, &file.path)?;
+      changes.push(Change::Create(file.path.clone()));
+    }
+    for path in &self.deletes {
+      self.backup(path, true, changes)?;
+    }
+    Ok(())
+  }
+
+  /// Backs up existing file at `path`. The file is moved into the backup path if
+  /// `remove` is set, otherwise it is hard linked, or copied if the file system does
+  /// not support hard links, so that the file remains at `path`.
+  fn backup(&self, path: &Path, remove: bool, changes: &mut Vec<Change>) -> Result<()> {
+    if path.exists() {
+      let backup_path = self.sibling_path(path, "bak")?;
+      if remove {
+        fs::rename(path, &backup_path)?;
+      } else {
+        fs::hard_link(path, &backup_path)
+          .or_else(|_| fs::copy(path, &backup_path).map(|_| ()))?;
+      }
+      changes.push(Change::Backup(path.to_path_buf(), backup_path));
+    }
+    Ok(())
+  }
+
+  /// Removes temporary files that have not been committed.
+  fn remove_temp_files(&mut self) -> Result<()> {
+    for file in self.files.drain(..) {
+      if file.temp_path.exists() {
+        fs::remove_file(&file.temp_path)?;
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns error if the path has already been added to this set.
+  fn check_new_path(&self, path: &Path) -> Result<()> {
+    let is_added = self.files.iter().any(|file| file.path == path) ||
+      self.deletes.iter().any(|delete| delete == path);
+    if is_added {
+      return Err(general_err!("File {} is already part of the set", path.display()));
+    }
+    Ok(())
+  }
+
+  /// Returns hidden path in the same directory as `path`, with id of this set and
+  /// `suffix`.
+  fn sibling_path(&self, path: &Path, suffix: &str) -> Result<PathBuf> {
+    match path.file_name().and_then(|name| name.to_str()) {
+      Some(name) => Ok(path.with_file_name(format!(".{}.{}.{}", name, self.id, suffix))),
+      None => Err(general_err!("Invalid file path {}", path.display()))
+    }
+  }
+}
+
+impl Drop for AtomicFileSet {
+  fn drop(&mut self) {
+    if !self.is_committed {
+      let _ = self.remove_temp_files();
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Read, Write};
+
+  use util::test_common::get_temp_filename;
+
+  fn write_file(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::File::create(path).unwrap().write_all(content.as_bytes()).unwrap();
+  }
+
+  fn read_file(path: &Path) -> String {
+    let mut content = String::new();
+    fs::File::open(path).unwrap().read_to_string(&mut content).unwrap();
+    content
+  }
+
+  fn list_dir(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir).unwrap()
+      .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+      .collect();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn test_atomic_file_set_commit() {
+    let dir = get_temp_filename("test_atomic_file_set_commit");
+    let _ = fs::remove_dir_all(&dir);
+    write_file(&dir.join("a.parquet"), "old a");
+    write_file(&dir.join("b.parquet"), "old b");
+
+    let mut files = AtomicFileSet::new();
+    files.create(&dir.join("a.parquet")).unwrap().write_all(b"new a").unwrap();
+    files.create(&dir.join("c/c.parquet")).unwrap().write_all(b"new c").unwrap();
+    files.delete(&dir.join("b.parquet")).unwrap();
+    files.delete(&dir.join("missing.parquet")).unwrap();
+    assert_eq!(
+      files.create(&dir.join("a.parquet")).unwrap_err(),
+      general_err!("File {} is already part of the set", dir.join("a.parquet").display())
+    );
+    assert_eq!(files.paths(), vec![dir.join("a.parquet"), dir.join("c/c.parquet")]);
+
+    // Files are not visible before commit
+    assert_eq!(read_file(&dir.join("a.parquet")), "old a");
+    assert!(!dir.join("c/c.parquet").exists());
+
+    files.commit().unwrap();
+    assert_eq!(read_file(&dir.join("a.parquet")), "new a");
+    assert_eq!(read_file(&dir.join("c/c.parquet")), "new c");
+    assert_eq!(list_dir(&dir), vec!["a.parquet", "c"]);
+    assert_eq!(list_dir(&dir.join("c")), vec!["c.parquet"]);
+  }
+
+  #[test]
+  fn test_atomic_file_set_rollback() {
+    let dir = get_temp_filename("test_atomic_file_set_rollback");
+    let _ = fs::remove_dir_all(&dir);
+    write_file(&dir.join("a.parquet"), "old a");
+    write_file(&dir.join("b.parquet"), "old b");
+
+    let mut files = AtomicFileSet::new();
+    files.create(&dir.join("a.parquet")).unwrap().write_all(b"new a").unwrap();
+    files.create(&dir.join("b.parquet")).unwrap().write_all(b"new b").unwrap();
+    files.create(&dir.join("c.parquet")).unwrap().write_all(b"new c").unwrap();
+    // Commit fails after the first two files have been renamed
+    fs::remove_file(files.temp_paths()[2]).unwrap();
+
+    assert!(files.commit().is_err());
+    assert_eq!(list_dir(&dir), vec!["a.parquet", "b.parquet"]);
+    assert_eq!(read_file(&dir.join("a.parquet")), "old a");
+    assert_eq!(read_file(&dir.join("b.parquet")), "old b");
+  }
+
+  #[test]
+  fn test_atomic_file_set_backup() {
+    let dir = get_temp_filename("test_atomic_file_set_backup");
+    let _ = fs::remove_dir_all(&dir);
+    write_file(&dir.join("a.parquet"), "old a");
+    write_file(&dir.join("b.parquet"), "old b");
+
+    let files = AtomicFileSet::new();
+    let mut changes = Vec::new();
+    // Files to be replaced remain in place, deleted files are moved away
+    files.backup(&dir.join("a.parquet"), false, &mut changes).unwrap();
+    files.backup(&dir.join("b.parquet"), true, &mut changes).unwrap();
+    files.backup(&dir.join("c.parquet"), false, &mut changes).unwrap();
+    assert_eq!(read_file(&dir.join("a.parquet")), "old a");
+    assert!(!dir.join("b.parquet").exists());
+
+    let backups: Vec<(PathBuf, String)> = changes.into_iter().map(|change| match change {
+      Change::Backup(path, backup_path) => (path, read_file(&backup_path)),
+      Change::Create(path) => panic!("Unexpected new file {}", path.display())
+    }).collect();
+    assert_eq!(backups, vec![
+      (dir.join("a.parquet"), "old a".to_string()),
+      (dir.join("b.parquet"), "old b".to_string())
+    ]);
+  }
+
+  #[test]
+  fn test_atomic_file_set_abort() {
+    let dir = get_temp_filename("test_atomic_file_set_abort");
+    let _ = fs::remove_dir_all(&dir);
+    write_file(&dir.join("a.parquet"), "old a");
+
+    let mut files = AtomicFileSet::new();
+    files.create(&dir.join("a.parquet")).unwrap().write_all(b"new a").unwrap();
+    files.create(&dir.join("b.parquet")).unwrap().write_all(b"new b").unwrap();
+    files.abort().unwrap();
+    assert_eq!(list_dir(&dir), vec!["a.parquet"]);
+    assert_eq!(read_file(&dir.join("a.parquet")), "old a");
+
+    // Temporary files are removed when the set is dropped
+    {
+      let mut files = AtomicFileSet::new();
+      files.create(&dir.join("b.parquet")).unwrap();
+    }
+    assert_eq!(list_dir(&dir), vec!["a.parquet"]);
+  }
+}
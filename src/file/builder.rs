@@ -0,0 +1,298 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains a builder of complete Parquet files in memory, mainly to construct fixture
+//! files in unit tests without touching disk.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//!
+//! use parquet::data_type::Int32Type;
+//! use parquet::file::builder::{ColumnData, InMemoryFileBuilder};
+//! use parquet::record::api::Row;
+//! use parquet::schema::parser::parse_message_type;
+//!
+//! let schema = "
+//!   message schema {
+//!     REQUIRED INT32 a;
+//!     OPTIONAL INT32 b;
+//!   }
+//! ";
+//! let schema = Rc::new(parse_message_type(schema).unwrap());
+//! let bytes = InMemoryFileBuilder::new(schema)
+//!   // First row group from values of each column
+//!   .with_columns(vec![
+//!     ColumnData::new::<Int32Type>(vec![1, 2]),
+//!     ColumnData::new::<Int32Type>(vec![3]).with_def_levels(vec![1, 0])
+//!   ])
+//!   // Second row group from rows
+//!   .with_rows(vec![
+//!     Row::Group(vec![("a".to_string(), Row::Int(4)), ("b".to_string(), Row::Null)])
+//!   ])
+//!   .build()
+//!   .unwrap();
+//! assert_eq!(&bytes[0..4], b"PAR1");
+//! ```
+
+use std::rc::Rc;
+
+use basic::Type;
+use column::writer::{get_typed_column_writer_mut, ColumnWriter};
+use data_type::DataType;
+use errors::{ParquetError, Result};
+use file::properties::{WriterProperties, WriterPropertiesPtr};
+use file::writer::{FileWriter, RowGroupWriter, SerializedFileWriter};
+use record::api::Row;
+use record::writer::RowWriter;
+use schema::types::{SchemaDescriptor, TypePtr};
+
+/// Values and levels of a column in a row group, typed by the physical type of the
+/// column.
+pub struct ColumnData {
+  physical_type: Type,
+  def_levels: Option<Vec<i16>>,
+  rep_levels: Option<Vec<i16>>,
+  write_values: Box<WriteValues>
+}
+
+/// Function that writes values with optional definition and repetition levels into a
+/// column writer.
+type WriteValues = Fn(&mut ColumnWriter, Option<&[i16]>, Option<&[i16]>) -> Result<()>;
+
+impl ColumnData {
+  /// Creates column data from values of type `T`, which must match physical type of
+  /// the column.
+  pub fn new<T: 'static + DataType>(values: Vec<T::T>) -> Self {
+    Self {
+      physical_type: T::get_physical_type(),
+      def_levels: None,
+      rep_levels: None,
+      write_values: Box::new(move |
+        column_writer: &mut ColumnWriter,
+        def_levels: Option<&[i16]>,
+        rep_levels: Option<&[i16]>
+      | {
+        let typed = get_typed_column_writer_mut::<T>(column_writer);
+        typed.write_batch(&values, def_levels, rep_levels)?;
+        Ok(())
+      })
+    }
+  }
+
+  /// Sets definition levels, required for optional and repeated columns, and returns
+  /// itself.
+  pub fn with_def_levels(mut self, def_levels: Vec<i16>) -> Self {
+    self.def_levels = Some(def_levels);
+    self
+  }
+
+  /// Sets repetition levels, required for repeated columns, and returns itself.
+  pub fn with_rep_levels(mut self, rep_levels: Vec<i16>) -> Self {
+    self.rep_levels = Some(rep_levels);
+    self
+  }
+
+  /// Writes values and levels into the column writer.
+  fn write(&self, column_writer: &mut ColumnWriter) -> Result<()> {
+    let def_levels = self.def_levels.as_ref().map(|levels| &levels[..]);
+    let rep_levels = self.rep_levels.as_ref().map(|levels| &levels[..]);
+    (self.write_values)(column_writer, def_levels, rep_levels)
+  }
+}
+
+/// Content of a row group.
+enum RowGroupData {
+  Columns(Vec<ColumnData>),
+  Rows(Vec<Row>)
+}
+
+/// Builder of a complete Parquet file in memory.
+///
+/// Each call of `with_columns` or `with_rows` adds a row group, and `build` writes all
+/// row groups with [`SerializedFileWriter`](`::file::writer::SerializedFileWriter`)
+/// and returns bytes of the file.
+pub struct InMemoryFileBuilder {
+  schema: TypePtr,
+  props: WriterPropertiesPtr,
+  row_groups: Vec<RowGroupData>
+}
+
+impl InMemoryFileBuilder {
+  /// Creates new builder for the message type with default writer properties.
+  pub fn new(schema: TypePtr) -> Self {
+    Self {
+      schema: schema,
+      props: Rc::new(WriterProperties::builder().build()),
+      row_groups: Vec::new()
+    }
+  }
+
+  /// Sets writer properties and returns itself.
+  pub fn with_properties(mut self, props: WriterPropertiesPtr) -> Self {
+    self.props = props;
+    self
+  }
+
+  /// Adds a row group with data of every leaf column in the order of the schema, and
+  /// returns itself.
+  pub fn with_columns(mut self, columns: Vec<ColumnData>) -> Self {
+    self.row_groups.push(RowGroupData::Columns(columns));
+    self
+  }
+
+  /// Adds a row group with rows, which are shredded with
+  /// [`RowWriter`](`::record::writer::RowWriter`), and returns itself.
+  pub fn with_rows(mut self, rows: Vec<Row>) -> Self {
+    self.row_groups.push(RowGroupData::Rows(rows));
+    self
+  }
+
+  /// Writes all row groups and returns bytes of the file.
+  ///
+  /// Returns an error if data does not match the schema.
+  pub fn build(self) -> Result<Vec<u8>> {
+    let descr = Rc::new(SchemaDescriptor::new(self.schema.clone()));
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), self.schema, self.props.clone())?;
+    for row_group in self.row_groups {
+      let mut row_group_writer = file_writer.next_row_group()?;
+      match row_group {
+        RowGroupData::Columns(columns) => {
+          if columns.len() != descr.num_columns() {
+            return Err(general_err!(
+              "Expected {} columns, found {}",
+              descr.num_columns(),
+              columns.len()
+            ));
+          }
+          for (i, column) in columns.iter().enumerate() {
+            let column_descr = descr.column(i);
+            if column.physical_type != column_descr.physical_type() {
+              return Err(general_err!(
+                "Cannot write {} values into column {} of type {}",
+                column.physical_type,
+                column_descr.path().string(),
+                column_descr.physical_type()
+              ));
+            }
+            write_column(&mut *row_group_writer, column)?;
+          }
+        },
+        RowGroupData::Rows(rows) => {
+          let mut row_writer =
+            RowWriter::new(descr.clone()).with_properties(self.props.clone());
+          for row in &rows {
+            row_writer.write(row)?;
+          }
+          row_writer.write_columns(&mut *row_group_writer)?;
+        }
+      }
+      file_writer.close_row_group(row_group_writer)?;
+    }
+    file_writer.close()?;
+    file_writer.into_inner()
+  }
+}
+
+/// Writes column data into the next column of the row group writer.
+fn write_column(
+  row_group_writer: &mut RowGroupWriter,
+  column: &ColumnData
+) -> Result<()> {
+  let mut column_writer = match row_group_writer.next_column()? {
+    Some(column_writer) => column_writer,
+    None => return Err(general_err!("Row group has fewer columns than schema"))
+  };
+  column.write(&mut column_writer)?;
+  row_group_writer.close_column(column_writer)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::io::Write;
+
+  use data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+  use file::reader::{FileReader, SerializedFileReader};
+  use schema::parser::parse_message_type;
+  use util::test_common::get_temp_filename;
+
+  fn test_schema() -> TypePtr {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BYTE_ARRAY b (UTF8);
+      }
+    ";
+    Rc::new(parse_message_type(schema).unwrap())
+  }
+
+  #[test]
+  fn test_in_memory_file_builder() {
+    let bytes = InMemoryFileBuilder::new(test_schema())
+      .with_columns(vec![
+        ColumnData::new::<Int32Type>(vec![1, 2]),
+        ColumnData::new::<ByteArrayType>(vec![ByteArray::from("x")])
+          .with_def_levels(vec![0, 1])
+      ])
+      .with_rows(vec![
+        Row::Group(vec![
+          ("a".to_string(), Row::Int(3)), ("b".to_string(), Row::Str("y".to_string()))])
+      ])
+      .build()
+      .unwrap();
+
+    let path = get_temp_filename("test_in_memory_file_builder.parquet");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 2);
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(
+      rows,
+      vec![
+        Row::Group(vec![("a".to_string(), Row::Int(1)), ("b".to_string(), Row::Null)]),
+        Row::Group(vec![
+          ("a".to_string(), Row::Int(2)), ("b".to_string(), Row::Str("x".to_string()))]),
+        Row::Group(vec![
+          ("a".to_string(), Row::Int(3)), ("b".to_string(), Row::Str("y".to_string()))])
+      ]
+    );
+  }
+
+  #[test]
+  fn test_in_memory_file_builder_invalid_columns() {
+    let result = InMemoryFileBuilder::new(test_schema())
+      .with_columns(vec![ColumnData::new::<Int32Type>(vec![1])])
+      .build();
+    assert_eq!(result.unwrap_err(), general_err!("Expected 2 columns, found 1"));
+
+    let result = InMemoryFileBuilder::new(test_schema())
+      .with_columns(vec![
+        ColumnData::new::<Int64Type>(vec![1]),
+        ColumnData::new::<ByteArrayType>(vec![]).with_def_levels(vec![0])
+      ])
+      .build();
+    assert_eq!(
+      result.unwrap_err(),
+      general_err!("Cannot write INT64 values into column a of type INT32")
+    );
+  }
+}
@@ -0,0 +1,357 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains compatibility workarounds for files produced by specific writers.
+//!
+//! The application that wrote a file is recorded in the `created_by` field of the file
+//! metadata, e.g. `parquet-mr version 1.8.0 (build 0fda28af84b9)`. It is parsed into
+//! [`ApplicationVersion`], which is then used by [`WriterCompat`] to decide which
+//! workarounds should be applied when reading the file.
+//!
+//! Currently supported workarounds:
+//! - Impala writes strings as `BYTE_ARRAY` without `UTF8` annotation, such values are
+//! read as strings instead of bytes.
+//! - parquet-mr before 1.8.0 computed min/max statistics of binary columns using signed
+//! byte comparison (PARQUET-251), such statistics are ignored when pruning row groups
+//! with [`Pruner::with_compat`](`::file::filter::Pruner::with_compat`).
+//!
+//! Workarounds are applied automatically by [`RowIter`](`::record::reader::RowIter`) for
+//! files, and can be disabled with [`WriterCompat::disabled`].
+//!
+//! Legacy 2-level list layouts, written by older versions of parquet-mr, parquet-avro
+//! and parquet-thrift, are recognized with [`is_list_element`] for all files, as
+//! required by backward-compatibility rules of the format, since they are valid lists
+//! regardless of the writer, which is not always recorded.
+
+use basic::{LogicalType, Type};
+use schema::types::Type as SchemaType;
+
+/// Application and version that wrote a Parquet file, parsed from `created_by`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApplicationVersion {
+  application: String,
+  major: u32,
+  minor: u32,
+  patch: u32,
+  build: Option<String>
+}
+
+impl ApplicationVersion {
+  /// Parses `created_by` string of the format
+  /// `<application> version <version> (build <build hash>)`, where version and build
+  /// are optional.
+  ///
+  /// Non-numeric suffixes of version components are ignored, missing components are
+  /// set to `0`. Returns `None` if application name is empty.
+  pub fn parse(created_by: &str) -> Option<Self> {
+    let (application, rest) = match created_by.find(" version ") {
+      Some(pos) => (&created_by[..pos], &created_by[pos + 9..]),
+      None => (created_by, "")
+    };
+    let application = application.trim();
+    if application.is_empty() {
+      return None;
+    }
+
+    let (version, build) = match rest.find("(build ") {
+      Some(pos) => {
+        let build = rest[pos + 7..].trim().trim_right_matches(')').trim();
+        (&rest[..pos], if build.is_empty() { None } else { Some(build.to_string()) })
+      },
+      None => (rest, None)
+    };
+
+    let mut numbers = [0u32; 3];
+    for (i, part) in version.trim().split('.').take(3).enumerate() {
+      let digits: String = part.chars().take_while(|c| c.is_digit(10)).collect();
+      numbers[i] = digits.parse().unwrap_or(0);
+    }
+
+    Some(Self {
+      application: application.to_string(),
+      major: numbers[0],
+      minor: numbers[1],
+      patch: numbers[2],
+      build: build
+    })
+  }
+
+  /// Returns name of the application, e.g. `parquet-mr`.
+  pub fn application(&self) -> &str {
+    &self.application
+  }
+
+  /// Returns major version.
+  pub fn major(&self) -> u32 {
+    self.major
+  }
+
+  /// Returns minor version.
+  pub fn minor(&self) -> u32 {
+    self.minor
+  }
+
+  /// Returns patch version.
+  pub fn patch(&self) -> u32 {
+    self.patch
+  }
+
+  /// Returns build hash, if available.
+  pub fn build(&self) -> Option<&str> {
+    self.build.as_ref().map(|b| b.as_str())
+  }
+
+  /// Returns `true` if this version is strictly older than `major.minor.patch`.
+  pub fn is_before(&self, major: u32, minor: u32, patch: u32) -> bool {
+    (self.major, self.minor, self.patch) < (major, minor, patch)
+  }
+}
+
+/// Set of workarounds for known issues of the writer of a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WriterCompat {
+  binary_as_string: bool,
+  ignore_binary_statistics: bool
+}
+
+impl WriterCompat {
+  /// Creates workarounds for a file based on its `created_by` field.
+  pub fn new(created_by: Option<&str>) -> Self {
+    let mut compat = Self::disabled();
+    if let Some(version) = created_by.and_then(ApplicationVersion::parse) {
+      match version.application() {
+        "impala" => {
+          compat.binary_as_string = true;
+        },
+        "parquet-mr" => {
+          compat.ignore_binary_statistics = version.is_before(1, 8, 0);
+        },
+        _ => {}
+      }
+    }
+    compat
+  }
+
+  /// Creates compatibility settings with all workarounds disabled.
+  pub fn disabled() -> Self {
+    Self {
+      binary_as_string: false,
+      ignore_binary_statistics: false
+    }
+  }
+
+  /// Returns `true` if `BYTE_ARRAY` values without logical type should be read as
+  /// UTF8 strings.
+  pub fn binary_as_string(&self) -> bool {
+    self.binary_as_string
+  }
+
+  /// Returns `true` if min/max statistics of `BYTE_ARRAY` and `FIXED_LEN_BYTE_ARRAY`
+  /// columns are unreliable and should be ignored.
+  pub fn ignore_binary_statistics(&self) -> bool {
+    self.ignore_binary_statistics
+  }
+
+  /// Returns logical type that should be used to convert values of a column with
+  /// `physical_type` and declared `logical_type`.
+  pub fn logical_type(
+    &self,
+    physical_type: Type,
+    logical_type: LogicalType
+  ) -> LogicalType {
+    if self.binary_as_string && physical_type == Type::BYTE_ARRAY &&
+        logical_type == LogicalType::NONE {
+      LogicalType::UTF8
+    } else {
+      logical_type
+    }
+  }
+}
+
+/// Returns `true` if `repeated_type`, the repeated field of list `list_type`, is the
+/// element type of a legacy 2-level list, `false` if it is the repeated group of a
+/// standard 3-level list, which contains the element type as its only field.
+///
+/// Rules are the same as in Spark Parquet reader and are based on the reference:
+/// https://github.com/apache/parquet-format/blob/master/LogicalTypes.md
+///   #backward-compatibility-rules
+pub fn is_list_element(list_type: &SchemaType, repeated_type: &SchemaType) -> bool {
+  // For legacy 2-level list types with primitive element type, e.g.:
+  //
+  //    // ARRAY<INT> (nullable list, non-null elements)
+  //    optional group my_list (LIST) {
+  //      repeated int32 element;
+  //    }
+  //
+  repeated_type.is_primitive() ||
+  // For legacy 2-level list types whose element type is a group type with 2 or more
+  // fields, e.g.:
+  //
+  //    // ARRAY<STRUCT<str: STRING, num: INT>> (nullable list, non-null elements)
+  //    optional group my_list (LIST) {
+  //      repeated group element {
+  //        required binary str (UTF8);
+  //        required int32 num;
+  //      };
+  //    }
+  //
+  repeated_type.is_group() && repeated_type.get_fields().len() > 1 ||
+  // For legacy 2-level list types generated by parquet-avro (Parquet version < 1.6.0),
+  // e.g.:
+  //
+  //    // ARRAY<STRUCT<str: STRING>> (nullable list, non-null elements)
+  //    optional group my_list (LIST) {
+  //      repeated group array {
+  //        required binary str (UTF8);
+  //      };
+  //    }
+  //
+  repeated_type.name() == "array" ||
+  // For Parquet data generated by parquet-thrift, where repeated group is named after
+  // the list, e.g.:
+  //
+  //    // ARRAY<STRUCT<str: STRING>> (nullable list, non-null elements)
+  //    optional group my_list (LIST) {
+  //      repeated group my_list_tuple {
+  //        required binary str (UTF8);
+  //      };
+  //    }
+  //
+  repeated_type.name() == format!("{}_tuple", list_type.name())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use schema::parser::parse_message_type;
+
+  #[test]
+  fn test_application_version_parse() {
+    let version = ApplicationVersion::parse(
+      "parquet-mr version 1.8.1 (build 4aba4dae7bb0d4edbcf7923ae1339f28fd3f7fcf)"
+    ).unwrap();
+    assert_eq!(version.application(), "parquet-mr");
+    assert_eq!((version.major(), version.minor(), version.patch()), (1, 8, 1));
+    assert_eq!(version.build(), Some("4aba4dae7bb0d4edbcf7923ae1339f28fd3f7fcf"));
+
+    let version = ApplicationVersion::parse(
+      "impala version 1.3.0-INTERNAL (build 8a48ddb1eff84592b3fc06bc6f51ec120e1fffc9)"
+    ).unwrap();
+    assert_eq!(version.application(), "impala");
+    assert_eq!((version.major(), version.minor(), version.patch()), (1, 3, 0));
+
+    let version = ApplicationVersion::parse("parquet-cpp version 1.3").unwrap();
+    assert_eq!(version.application(), "parquet-cpp");
+    assert_eq!((version.major(), version.minor(), version.patch()), (1, 3, 0));
+    assert_eq!(version.build(), None);
+
+    let version = ApplicationVersion::parse("some-writer").unwrap();
+    assert_eq!(version.application(), "some-writer");
+    assert_eq!((version.major(), version.minor(), version.patch()), (0, 0, 0));
+
+    assert_eq!(ApplicationVersion::parse(""), None);
+    assert_eq!(ApplicationVersion::parse(" version 1.0.0"), None);
+  }
+
+  #[test]
+  fn test_application_version_is_before() {
+    let version = ApplicationVersion::parse("parquet-mr version 1.7.2").unwrap();
+    assert!(version.is_before(1, 8, 0));
+    assert!(version.is_before(1, 7, 3));
+    assert!(!version.is_before(1, 7, 2));
+    assert!(!version.is_before(1, 6, 9));
+  }
+
+  #[test]
+  fn test_writer_compat() {
+    let compat = WriterCompat::new(Some("impala version 2.8.0-cdh5.11.0"));
+    assert!(compat.binary_as_string());
+    assert!(!compat.ignore_binary_statistics());
+    assert_eq!(
+      compat.logical_type(Type::BYTE_ARRAY, LogicalType::NONE),
+      LogicalType::UTF8
+    );
+    assert_eq!(
+      compat.logical_type(Type::BYTE_ARRAY, LogicalType::BSON),
+      LogicalType::BSON
+    );
+    assert_eq!(
+      compat.logical_type(Type::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE),
+      LogicalType::NONE
+    );
+
+    let compat = WriterCompat::new(Some("parquet-mr version 1.6.0"));
+    assert!(!compat.binary_as_string());
+    assert!(compat.ignore_binary_statistics());
+
+    let compat = WriterCompat::new(Some("parquet-mr version 1.8.0"));
+    assert_eq!(compat, WriterCompat::disabled());
+
+    assert_eq!(WriterCompat::new(None), WriterCompat::disabled());
+  }
+
+  #[test]
+  fn test_is_list_element() {
+    let schema = parse_message_type("
+      message schema {
+        OPTIONAL GROUP primitive (LIST) {
+          REPEATED INT32 element;
+        }
+        OPTIONAL GROUP multiple_fields (LIST) {
+          REPEATED GROUP element {
+            REQUIRED BYTE_ARRAY str (UTF8);
+            REQUIRED INT32 num;
+          }
+        }
+        OPTIONAL GROUP avro (LIST) {
+          REPEATED GROUP array {
+            REQUIRED BYTE_ARRAY str (UTF8);
+          }
+        }
+        OPTIONAL GROUP thrift (LIST) {
+          REPEATED GROUP thrift_tuple {
+            REQUIRED BYTE_ARRAY str (UTF8);
+          }
+        }
+        OPTIONAL GROUP standard (LIST) {
+          REPEATED GROUP list {
+            OPTIONAL BYTE_ARRAY element (UTF8);
+          }
+        }
+        OPTIONAL GROUP other_tuple (LIST) {
+          REPEATED GROUP thrift_tuple {
+            REQUIRED BYTE_ARRAY element (UTF8);
+          }
+        }
+      }
+    ").unwrap();
+    let expected = vec![
+      ("primitive", true),
+      ("multiple_fields", true),
+      ("avro", true),
+      ("thrift", true),
+      ("standard", false),
+      ("other_tuple", false)
+    ];
+    for (list_type, (name, is_element)) in schema.get_fields().iter().zip(expected) {
+      assert_eq!(list_type.name(), name);
+      let repeated_type = &list_type.get_fields()[0];
+      assert_eq!(is_list_element(list_type, repeated_type), is_element, "{}", name);
+    }
+  }
+}
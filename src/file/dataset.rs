@@ -0,0 +1,426 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains dataset reader, which discovers Parquet files in a directory tree.
+//!
+//! Discovery follows the rules used by Spark:
+//! - directories are listed recursively;
+//! - files and directories starting with `.` are hidden and skipped;
+//! - files and directories starting with `_` are skipped, e.g. `_SUCCESS`, `_metadata`
+//! or `_temporary`, unless the name contains `=` (partition directory such as
+//! `_col=1`);
+//! - files that are still being copied (`._COPYING_` suffix) are skipped;
+//! - only files with `parquet` extension are included.
+//!
+//! All rules can be changed with [`DatasetOptions`].
+//!
+//! Projections can be expressed by field ids with [`Dataset::get_projected_reader`],
+//! which resolves them against the schema of each file, so scans are not affected by
+//! renamed columns.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use parquet::file::dataset::{Dataset, DatasetOptions};
+//! use parquet::file::reader::FileReader;
+//!
+//! let dataset = Dataset::new(Path::new("data/table"), DatasetOptions::new()).unwrap();
+//! for i in 0..dataset.num_files() {
+//!   let reader = dataset.get_reader(i).unwrap();
+//!   println!("{}: {} rows", dataset.files()[i].display(),
+//!     reader.metadata().file_metadata().num_rows());
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use errors::{ParquetError, Result};
+use file::reader::{FileReader, SerializedFileReader};
+use schema::types::Type;
+
+/// Default extension of Parquet files included in a dataset.
+pub const DEFAULT_EXTENSION: &str = "parquet";
+
+/// Options for discovery of files in a dataset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatasetOptions {
+  extension: Option<String>,
+  recursive: bool,
+  include_hidden: bool,
+  include_underscore: bool
+}
+
+impl DatasetOptions {
+  /// Creates options with default discovery rules.
+  pub fn new() -> Self {
+    Self {
+      extension: Some(DEFAULT_EXTENSION.to_string()),
+      recursive: true,
+      include_hidden: false,
+      include_underscore: false
+    }
+  }
+
+  /// Sets extension of included files, without leading `.`; `None` includes files
+  /// with any extension.
+  pub fn with_extension(mut self, value: Option<&str>) -> Self {
+    self.extension = value.map(|v| v.to_string());
+    self
+  }
+
+  /// Sets flag to list subdirectories recursively.
+  pub fn with_recursive(mut self, value: bool) -> Self {
+    self.recursive = value;
+    self
+  }
+
+  /// Sets flag to include files and directories starting with `.`.
+  pub fn with_include_hidden(mut self, value: bool) -> Self {
+    self.include_hidden = value;
+    self
+  }
+
+  /// Sets flag to include files and directories starting with `_`, e.g. `_metadata`.
+  pub fn with_include_underscore(mut self, value: bool) -> Self {
+    self.include_underscore = value;
+    self
+  }
+
+  /// Returns extension of included files, if set.
+  pub fn extension(&self) -> Option<&str> {
+    self.extension.as_ref().map(|v| v.as_str())
+  }
+
+  /// Returns `true` if subdirectories are listed recursively.
+  pub fn recursive(&self) -> bool {
+    self.recursive
+  }
+
+  /// Returns `true` if files and directories starting with `.` are included.
+  pub fn include_hidden(&self) -> bool {
+    self.include_hidden
+  }
+
+  /// Returns `true` if files and directories starting with `_` are included.
+  pub fn include_underscore(&self) -> bool {
+    self.include_underscore
+  }
+
+  /// Returns `true` if file or directory with `name` should be skipped.
+  fn is_excluded(&self, name: &str) -> bool {
+    (!self.include_hidden && name.starts_with('.')) ||
+      (!self.include_underscore && name.starts_with('_') && !name.contains('=')) ||
+      name.ends_with("._COPYING_")
+  }
+
+  /// Returns `true` if file at `path` has the expected extension.
+  fn has_extension(&self, path: &Path) -> bool {
+    match self.extension {
+      Some(ref expected) => {
+        path.extension().and_then(|ext| ext.to_str()) == Some(expected.as_str())
+      },
+      None => true
+    }
+  }
+}
+
+impl Default for DatasetOptions {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Dataset of Parquet files discovered in a directory tree.
+pub struct Dataset {
+  root: PathBuf,
+  files: Vec<PathBuf>
+}
+
+impl Dataset {
+  /// Discovers files of a dataset at `root` using `options`.
+  ///
+  /// If `root` is a file, dataset consists of this file only. Files are sorted by
+  /// path, so the order is deterministic.
+  pub fn new(root: &Path, options: DatasetOptions) -> Result<Self> {
+    let metadata = fs::metadata(root).map_err(|e| {
+      general_err!("Could not list dataset \"{}\": {}", root.display(), e)
+    })?;
+    let mut files = Vec::new();
+    if metadata.is_dir() {
+      list_files(root, &options, &mut files)?;
+      files.sort();
+    } else {
+      files.push(root.to_path_buf());
+    }
+    Ok(Self { root: root.to_path_buf(), files: files })
+  }
+
+  /// Returns root path of this dataset.
+  pub fn root(&self) -> &Path {
+    &self.root
+  }
+
+  /// Returns paths of all files in this dataset.
+  pub fn files(&self) -> &[PathBuf] {
+    &self.files
+  }
+
+  /// Returns number of files in this dataset.
+  pub fn num_files(&self) -> usize {
+    self.files.len()
+  }
+
+  /// Opens file reader for the `i`th file of this dataset.
+  /// Returns error if `i` is not less than the number of files.
+  pub fn get_reader(&self, i: usize) -> Result<SerializedFileReader> {
+    match self.files.get(i) {
+      Some(path) => SerializedFileReader::try_from_path(path),
+      None => Err(general_err!(
+        "File index {} out of bounds, dataset has {} files",
+        i,
+        self.files.len()
+      ))
+    }
+  }
+
+  /// Opens file reader for the `i`th file of this dataset and returns it with the
+  /// projection of the file schema to fields with `field_ids`, which can be passed to
+  /// [`get_row_iter`](`::file::reader::FileReader::get_row_iter`).
+  ///
+  /// See [`Type::project_field_ids`](`::schema::types::Type::project_field_ids`) for
+  /// how ids are resolved. Returns error if the file has none of the fields.
+  pub fn get_projected_reader(
+    &self,
+    i: usize,
+    field_ids: &[i32]
+  ) -> Result<(SerializedFileReader, Type)> {
+    let reader = self.get_reader(i)?;
+    let projection = reader
+      .metadata()
+      .file_metadata()
+      .schema()
+      .project_field_ids(field_ids)?;
+    Ok((reader, projection))
+  }
+}
+
+/// Appends files of directory `dir` that match `options` to `files`.
+fn list_files(
+  dir: &Path,
+  options: &DatasetOptions,
+  files: &mut Vec<PathBuf>
+) -> Result<()> {
+  let entries = fs::read_dir(dir).map_err(|e| {
+    general_err!("Could not list directory \"{}\": {}", dir.display(), e)
+  })?;
+  for entry in entries {
+    let entry = entry?;
+    let path = entry.path();
+    let excluded = match entry.file_name().to_str() {
+      Some(name) => options.is_excluded(name),
+      None => false
+    };
+    if excluded {
+      continue;
+    }
+    if entry.file_type()?.is_dir() {
+      if options.recursive {
+        list_files(&path, options, files)?;
+      }
+    } else if options.has_extension(&path) {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Write;
+  use std::rc::Rc;
+
+  use file::builder::InMemoryFileBuilder;
+  use record::api::Row;
+  use schema::parser::parse_message_type;
+  use util::test_common::get_temp_filename;
+
+  /// Creates directory `name` in temp directory with empty files at relative `paths`.
+  fn create_test_dataset(name: &str, paths: &[&str]) -> PathBuf {
+    let root = get_temp_filename(name);
+    let _ = fs::remove_dir_all(&root);
+    for path in paths {
+      let path = root.join(path);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::File::create(&path).unwrap();
+    }
+    root
+  }
+
+  fn relative_files(dataset: &Dataset) -> Vec<String> {
+    dataset.files().iter()
+      .map(|path| {
+        let relative = path.strip_prefix(dataset.root()).unwrap();
+        relative.to_str().unwrap().replace('\\', "/")
+      })
+      .collect()
+  }
+
+  const TEST_PATHS: &[&str] = &[
+    "part-0.parquet",
+    "_SUCCESS",
+    "_metadata",
+    ".part-0.parquet.crc",
+    ".hidden/part-1.parquet",
+    "_temporary/part-2.parquet",
+    "year=2018/part-3.parquet",
+    "year=2018/month=1/part-4.parquet",
+    "_col=1/part-5.parquet",
+    "part-6.parquet._COPYING_",
+    "part-7.csv"
+  ];
+
+  #[test]
+  fn test_dataset_default_options() {
+    let root = create_test_dataset("dataset_default_options", TEST_PATHS);
+    let dataset = Dataset::new(&root, DatasetOptions::new()).unwrap();
+    assert_eq!(
+      relative_files(&dataset),
+      vec![
+        "_col=1/part-5.parquet",
+        "part-0.parquet",
+        "year=2018/month=1/part-4.parquet",
+        "year=2018/part-3.parquet"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_dataset_custom_options() {
+    let root = create_test_dataset("dataset_custom_options", TEST_PATHS);
+
+    let options = DatasetOptions::new().with_recursive(false);
+    let dataset = Dataset::new(&root, options).unwrap();
+    assert_eq!(relative_files(&dataset), vec!["part-0.parquet"]);
+
+    let options = DatasetOptions::new()
+      .with_include_hidden(true)
+      .with_include_underscore(true);
+    let dataset = Dataset::new(&root, options).unwrap();
+    assert_eq!(
+      relative_files(&dataset),
+      vec![
+        ".hidden/part-1.parquet",
+        "_col=1/part-5.parquet",
+        "_temporary/part-2.parquet",
+        "part-0.parquet",
+        "year=2018/month=1/part-4.parquet",
+        "year=2018/part-3.parquet"
+      ]
+    );
+
+    let options = DatasetOptions::new().with_extension(None).with_recursive(false);
+    let dataset = Dataset::new(&root, options).unwrap();
+    assert_eq!(relative_files(&dataset), vec!["part-0.parquet", "part-7.csv"]);
+  }
+
+  #[test]
+  fn test_dataset_single_file() {
+    let root = create_test_dataset("dataset_single_file", &["part-0.parquet"]);
+    let path = root.join("part-0.parquet");
+    let dataset = Dataset::new(&path, DatasetOptions::new()).unwrap();
+    assert_eq!(dataset.files(), &[path]);
+  }
+
+  #[test]
+  fn test_dataset_missing_root() {
+    let root = get_temp_filename("dataset_missing_root");
+    let _ = fs::remove_dir_all(&root);
+    let res = Dataset::new(&root, DatasetOptions::new());
+    assert!(res.is_err());
+    if let Err(ParquetError::General(message)) = res {
+      assert!(message.starts_with(
+        &format!("Could not list dataset \"{}\": ", root.display())));
+    }
+  }
+
+  #[test]
+  fn test_dataset_get_reader() {
+    let root = create_test_dataset("dataset_get_reader", &[]);
+    fs::create_dir_all(&root).unwrap();
+    fs::copy(
+      "data/alltypes_plain.parquet",
+      root.join("alltypes_plain.parquet")
+    ).unwrap();
+    let dataset = Dataset::new(&root, DatasetOptions::new()).unwrap();
+    assert_eq!(dataset.num_files(), 1);
+    assert!(dataset.get_reader(0).is_ok());
+    assert_eq!(
+      dataset.get_reader(1).err().unwrap(),
+      general_err!("File index 1 out of bounds, dataset has 1 files")
+    );
+  }
+
+  #[test]
+  fn test_dataset_get_projected_reader() {
+    let root = create_test_dataset("dataset_get_projected_reader", &[]);
+    fs::create_dir_all(&root).unwrap();
+    // Column `b` is renamed to `c` in the second file
+    for (i, name) in ["b", "c"].iter().enumerate() {
+      let schema = parse_message_type(&format!("
+        message schema {{
+          REQUIRED INT32 a = 1;
+          REQUIRED INT64 {} = 2;
+        }}
+      ", name)).unwrap();
+      let row = Row::Group(vec![
+        ("a".to_string(), Row::Int(i as i32)),
+        (name.to_string(), Row::Long(10 + i as i64))
+      ]);
+      let bytes = InMemoryFileBuilder::new(Rc::new(schema))
+        .with_rows(vec![row])
+        .build()
+        .unwrap();
+      let path = root.join(format!("part-{}.parquet", i));
+      fs::File::create(path).unwrap().write_all(&bytes).unwrap();
+    }
+
+    let dataset = Dataset::new(&root, DatasetOptions::new()).unwrap();
+    let mut values = Vec::new();
+    for i in 0..dataset.num_files() {
+      let (reader, projection) = dataset.get_projected_reader(i, &[2]).unwrap();
+      for row in reader.get_row_iter(Some(projection)).unwrap() {
+        match row {
+          Row::Group(fields) => {
+            assert_eq!(fields.len(), 1);
+            values.push(fields[0].1.clone());
+          },
+          _ => panic!("Expected group, found {:?}", row)
+        }
+      }
+    }
+    assert_eq!(values, vec![Row::Long(10), Row::Long(11)]);
+
+    assert_eq!(
+      dataset.get_projected_reader(0, &[3]).err().unwrap(),
+      general_err!("Schema does not contain field ids [3]")
+    );
+  }
+}
@@ -0,0 +1,1227 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains filter expressions, which are used to skip row groups based on column
+//! statistics and to filter records.
+//!
+//! Filter is parsed from a string with [`parse_filter`], for example
+//! `a > 5 AND b = 'x'`. Supported syntax:
+//! - comparisons `=`, `==`, `!=`, `<>`, `<`, `<=`, `>`, `>=` of a column with a
+//! literal, either side can be the column;
+//! - `IS NULL` and `IS NOT NULL` checks of a column;
+//! - `AND`, `OR`, `NOT` and parentheses, keywords are case insensitive;
+//! - column names are dot-separated paths of leaf columns, e.g. `b.c`, names with
+//! special characters are quoted with double quotes, e.g. `"my col"`;
+//! - literals are integers, floating point numbers, `TRUE`/`FALSE` and strings in
+//! single quotes, where quote is escaped as `''`.
+//!
+//! Comparisons with null values evaluate to `false`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::file::filter::parse_filter;
+//! use parquet::record::api::Row;
+//!
+//! let filter = parse_filter("a > 5 AND b = 'x'").unwrap();
+//! let row = Row::Group(vec![
+//!   ("a".to_string(), Row::Int(6)),
+//!   ("b".to_string(), Row::Str("x".to_string()))
+//! ]);
+//! assert!(filter.matches(&row));
+//! ```
+//!
+//! Row groups can also be pruned with external indexes, e.g. an external min/max or
+//! inverted index service, which implement [`ExternalIndex`] and are registered in
+//! [`Pruner`] alongside built-in statistics. External indexes can also rule out
+//! individual data pages, see [`Pruner::matching_row_ranges`].
+
+use std::cmp::{self, Ordering};
+use std::fmt;
+use std::ops::Range;
+
+use basic::{SortOrder, Type};
+use data_type::Decimal;
+use errors::{ParquetError, Result};
+use file::compat::WriterCompat;
+use file::layout::PageRowRange;
+use file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use logical::LogicalValue;
+use record::api::Row;
+use schema::types::SchemaDescriptor;
+
+/// Parses filter string into an expression. Returns general error if filter is
+/// invalid.
+pub fn parse_filter(filter: &str) -> Result<Expr> {
+  let tokens = tokenize(filter)
+    .map_err(|e| general_err!("Invalid filter '{}': {}", filter, e))?;
+  let mut parser = Parser { tokens: tokens, index: 0 };
+  parser.parse().map_err(|e| general_err!("Invalid filter '{}': {}", filter, e))
+}
+
+/// Literal value of a filter expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Literal {
+  Bool(bool),
+  Int(i64),
+  Double(f64),
+  Str(String)
+}
+
+impl Literal {
+  /// Compares this literal with `other`. Integers and floating point numbers are
+  /// compared as numbers, returns `None` if values are not comparable.
+  fn compare(&self, other: &Literal) -> Option<Ordering> {
+    match (self, other) {
+      (&Literal::Bool(a), &Literal::Bool(b)) => Some(a.cmp(&b)),
+      (&Literal::Int(a), &Literal::Int(b)) => Some(a.cmp(&b)),
+      (&Literal::Int(a), &Literal::Double(b)) => (a as f64).partial_cmp(&b),
+      (&Literal::Double(a), &Literal::Int(b)) => a.partial_cmp(&(b as f64)),
+      (&Literal::Double(a), &Literal::Double(b)) => a.partial_cmp(&b),
+      (&Literal::Str(ref a), &Literal::Str(ref b)) => Some(a.cmp(b)),
+      _ => None
+    }
+  }
+}
+
+impl fmt::Display for Literal {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Literal::Bool(value) => write!(f, "{}", if value { "TRUE" } else { "FALSE" }),
+      Literal::Int(value) => write!(f, "{}", value),
+      Literal::Double(value) => write!(f, "{:?}", value),
+      Literal::Str(ref value) => write!(f, "'{}'", value.replace('\'', "''"))
+    }
+  }
+}
+
+/// Comparison operator of a filter expression.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+  Eq,
+  NotEq,
+  Lt,
+  LtEq,
+  Gt,
+  GtEq
+}
+
+impl CompareOp {
+  /// Returns `true` if ordering of a value relative to a literal satisfies this
+  /// operator.
+  fn matches(&self, ordering: Ordering) -> bool {
+    match *self {
+      CompareOp::Eq => ordering == Ordering::Equal,
+      CompareOp::NotEq => ordering != Ordering::Equal,
+      CompareOp::Lt => ordering == Ordering::Less,
+      CompareOp::LtEq => ordering != Ordering::Greater,
+      CompareOp::Gt => ordering == Ordering::Greater,
+      CompareOp::GtEq => ordering != Ordering::Less
+    }
+  }
+
+  /// Returns operator with swapped operands, e.g. `5 < a` is `a > 5`.
+  fn swap(&self) -> Self {
+    match *self {
+      CompareOp::Lt => CompareOp::Gt,
+      CompareOp::LtEq => CompareOp::GtEq,
+      CompareOp::Gt => CompareOp::Lt,
+      CompareOp::GtEq => CompareOp::LtEq,
+      other => other
+    }
+  }
+}
+
+impl fmt::Display for CompareOp {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let op = match *self {
+      CompareOp::Eq => "=",
+      CompareOp::NotEq => "!=",
+      CompareOp::Lt => "<",
+      CompareOp::LtEq => "<=",
+      CompareOp::Gt => ">",
+      CompareOp::GtEq => ">="
+    };
+    write!(f, "{}", op)
+  }
+}
+
+/// Filter expression, columns are referenced by dot-separated paths.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+  Compare(String, CompareOp, Literal),
+  IsNull(String),
+  IsNotNull(String),
+  Not(Box<Expr>),
+  And(Box<Expr>, Box<Expr>),
+  Or(Box<Expr>, Box<Expr>)
+}
+
+impl Expr {
+  /// Returns paths of all columns referenced by this expression.
+  pub fn columns(&self) -> Vec<&str> {
+    let mut columns = Vec::new();
+    self.collect_columns(&mut columns);
+    columns
+  }
+
+  fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+    match *self {
+      Expr::Compare(ref column, _, _) | Expr::IsNull(ref column) |
+        Expr::IsNotNull(ref column) => {
+        if !columns.contains(&column.as_str()) {
+          columns.push(column);
+        }
+      },
+      Expr::Not(ref expr) => expr.collect_columns(columns),
+      Expr::And(ref left, ref right) | Expr::Or(ref left, ref right) => {
+        left.collect_columns(columns);
+        right.collect_columns(columns);
+      }
+    }
+  }
+
+  /// Returns error if this expression references columns that are not leaf columns
+  /// of `schema`.
+  pub fn validate(&self, schema: &SchemaDescriptor) -> Result<()> {
+    for column in self.columns() {
+      let found = (0..schema.num_columns())
+        .any(|i| schema.column(i).path().string() == column);
+      if !found {
+        return Err(general_err!("Column '{}' not found in schema", column));
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns `false` if no row of the row group can match this expression based on
+  /// statistics of its column chunks, `true` otherwise.
+  ///
+  /// Statistics are used only if they were computed with the same order, as used for
+  /// comparisons with literals: signed order for integers, unsigned byte-wise order
+  /// for strings.
+  pub fn might_match(&self, row_group: &RowGroupMetaData) -> bool {
+    self.might_match_with_indexes(row_group, &WriterCompat::disabled(), &[])
+  }
+
+  /// Same as `might_match`, but comparisons are also checked against external
+  /// `indexes`, row group is skipped if either statistics or any of the indexes
+  /// rule out a comparison. Statistics that are unreliable according to `compat` are
+  /// not used.
+  fn might_match_with_indexes(
+    &self,
+    row_group: &RowGroupMetaData,
+    compat: &WriterCompat,
+    indexes: &[Box<ExternalIndex>]
+  ) -> bool {
+    match *self {
+      Expr::Compare(ref column, op, ref literal) => {
+        let chunk = match find_column_chunk(row_group, column) {
+          Some(chunk) => chunk,
+          None => return true
+        };
+        let stats_might_match = match column_range(chunk, compat) {
+          Some((min, max)) => range_might_match(&min, &max, op, literal),
+          None => true
+        };
+        stats_might_match &&
+          indexes.iter().all(|index| index.might_match(chunk, op, literal))
+      },
+      // Null counts are optional, and cannot be used to skip row groups
+      Expr::IsNull(_) | Expr::IsNotNull(_) | Expr::Not(_) => true,
+      Expr::And(ref left, ref right) => {
+        left.might_match_with_indexes(row_group, compat, indexes) &&
+          right.might_match_with_indexes(row_group, compat, indexes)
+      },
+      Expr::Or(ref left, ref right) => {
+        left.might_match_with_indexes(row_group, compat, indexes) ||
+          right.might_match_with_indexes(row_group, compat, indexes)
+      }
+    }
+  }
+
+  /// Returns ranges of rows of the row group that might match this expression
+  /// according to page level information of external `indexes`, see
+  /// `Pruner::matching_row_ranges`.
+  fn matching_row_ranges<F>(
+    &self,
+    row_group: &RowGroupMetaData,
+    indexes: &[Box<ExternalIndex>],
+    page_row_ranges: &mut F
+  ) -> Result<Vec<Range<i64>>>
+    where F: FnMut(usize) -> Result<Vec<PageRowRange>> {
+    let all_rows = vec![0..row_group.num_rows()];
+    match *self {
+      Expr::Compare(ref column, op, ref literal) => {
+        let i = match row_group.columns().iter()
+          .position(|chunk| chunk.column_path().string() == *column) {
+          Some(i) => i,
+          None => return Ok(all_rows)
+        };
+        let chunk = row_group.column(i);
+        let ranges = page_row_ranges(i)?.into_iter()
+          .filter(|page| {
+            indexes.iter().all(|index| index.page_might_match(chunk, page, op, literal))
+          })
+          .map(|page| page.row_range())
+          .collect();
+        Ok(union_row_ranges(ranges, Vec::new()))
+      },
+      // Pages are only pruned based on comparisons
+      Expr::IsNull(_) | Expr::IsNotNull(_) | Expr::Not(_) => Ok(all_rows),
+      Expr::And(ref left, ref right) => {
+        let left = left.matching_row_ranges(row_group, indexes, page_row_ranges)?;
+        let right = right.matching_row_ranges(row_group, indexes, page_row_ranges)?;
+        Ok(intersect_row_ranges(&left, &right))
+      },
+      Expr::Or(ref left, ref right) => {
+        let left = left.matching_row_ranges(row_group, indexes, page_row_ranges)?;
+        let right = right.matching_row_ranges(row_group, indexes, page_row_ranges)?;
+        Ok(union_row_ranges(left, right))
+      }
+    }
+  }
+
+  /// Returns `true` if record `row` matches this expression. Missing fields are
+  /// treated as nulls.
+  pub fn matches(&self, row: &Row) -> bool {
+    match *self {
+      Expr::Compare(ref column, op, ref literal) => {
+        match find_field(row, column).and_then(row_literal) {
+          Some(value) => match value.compare(literal) {
+            Some(ordering) => op.matches(ordering),
+            None => false
+          },
+          None => false
+        }
+      },
+      Expr::IsNull(ref column) => is_null(find_field(row, column)),
+      Expr::IsNotNull(ref column) => !is_null(find_field(row, column)),
+      Expr::Not(ref expr) => !expr.matches(row),
+      Expr::And(ref left, ref right) => left.matches(row) && right.matches(row),
+      Expr::Or(ref left, ref right) => left.matches(row) || right.matches(row)
+    }
+  }
+}
+
+/// External index of column chunks, e.g. an external min/max or inverted index
+/// service, that provides additional pruning information to built-in statistics.
+pub trait ExternalIndex {
+  /// Returns `false` if no value of column chunk `chunk` satisfies comparison `op` with
+  /// `literal`, `true` if some value might satisfy it or index has no information about
+  /// the column chunk.
+  ///
+  /// Column chunk is identified by its metadata, e.g. column path and offsets.
+  fn might_match(
+    &self,
+    chunk: &ColumnChunkMetaData,
+    op: CompareOp,
+    literal: &Literal
+  ) -> bool;
+
+  /// Returns `false` if no value of data page `page` of column chunk `chunk`
+  /// satisfies comparison `op` with `literal`, `true` if some value might satisfy it
+  /// or index has no information about the page.
+  ///
+  /// Page is identified by its offset in the file, `page.page.offset`, or by its row
+  /// range within the row group. Default implementation has no page information.
+  fn page_might_match(
+    &self,
+    _chunk: &ColumnChunkMetaData,
+    _page: &PageRowRange,
+    _op: CompareOp,
+    _literal: &Literal
+  ) -> bool {
+    true
+  }
+}
+
+/// Row group and page pruner, evaluates filter expression against statistics of column
+/// chunks and registered external indexes.
+pub struct Pruner {
+  expr: Expr,
+  compat: WriterCompat,
+  indexes: Vec<Box<ExternalIndex>>
+}
+
+impl Pruner {
+  /// Creates new pruner for filter expression `expr` without external indexes, which
+  /// uses all statistics.
+  pub fn new(expr: Expr) -> Self {
+    Self { expr: expr, compat: WriterCompat::disabled(), indexes: Vec::new() }
+  }
+
+  /// Sets workarounds for the writer of the file, e.g. `WriterCompat::new` with
+  /// `created_by` of the file to ignore statistics that are known to be incorrect, and
+  /// returns itself.
+  pub fn with_compat(mut self, compat: WriterCompat) -> Self {
+    self.compat = compat;
+    self
+  }
+
+  /// Registers external index, which is consulted alongside statistics, and returns
+  /// itself.
+  pub fn with_external_index(mut self, index: Box<ExternalIndex>) -> Self {
+    self.indexes.push(index);
+    self
+  }
+
+  /// Returns filter expression of this pruner.
+  pub fn expr(&self) -> &Expr {
+    &self.expr
+  }
+
+  /// Returns `false` if no row of the row group can match filter expression based on
+  /// statistics of its column chunks or any of the external indexes, `true`
+  /// otherwise.
+  pub fn might_match(&self, row_group: &RowGroupMetaData) -> bool {
+    self.expr.might_match_with_indexes(row_group, &self.compat, &self.indexes)
+  }
+
+  /// Returns sorted, non-overlapping ranges of rows of the row group that might match
+  /// filter expression, i.e. rows of data pages that are not ruled out by any of the
+  /// external indexes. Returns no ranges if `might_match` rules out the whole row
+  /// group.
+  ///
+  /// `page_row_ranges` returns data pages of the `i`th column chunk of the row group
+  /// with their row ranges, e.g.
+  /// [`SerializedRowGroupReader::get_column_page_row_ranges`](
+  /// `::file::reader::SerializedRowGroupReader::get_column_page_row_ranges`), and is
+  /// only called for columns that are compared with literals.
+  pub fn matching_row_ranges<F>(
+    &self,
+    row_group: &RowGroupMetaData,
+    mut page_row_ranges: F
+  ) -> Result<Vec<Range<i64>>>
+    where F: FnMut(usize) -> Result<Vec<PageRowRange>> {
+    if !self.might_match(row_group) {
+      return Ok(Vec::new());
+    }
+    self.expr.matching_row_ranges(row_group, &self.indexes, &mut page_row_ranges)
+  }
+}
+
+impl fmt::Display for Expr {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Expr::Compare(ref column, op, ref literal) => {
+        write!(f, "{} {} {}", column, op, literal)
+      },
+      Expr::IsNull(ref column) => write!(f, "{} IS NULL", column),
+      Expr::IsNotNull(ref column) => write!(f, "{} IS NOT NULL", column),
+      Expr::Not(ref expr) => write!(f, "NOT ({})", expr),
+      Expr::And(ref left, ref right) => write!(f, "({}) AND ({})", left, right),
+      Expr::Or(ref left, ref right) => write!(f, "({}) OR ({})", left, right)
+    }
+  }
+}
+
+/// Returns `true` if some value between `min` and `max` might satisfy comparison with
+/// `literal`.
+fn range_might_match(
+  min: &Literal,
+  max: &Literal,
+  op: CompareOp,
+  literal: &Literal
+) -> bool {
+  let (min_ordering, max_ordering) = match (min.compare(literal), max.compare(literal)) {
+    (Some(min_ordering), Some(max_ordering)) => (min_ordering, max_ordering),
+    _ => return true
+  };
+  match op {
+    CompareOp::Eq => {
+      min_ordering != Ordering::Greater && max_ordering != Ordering::Less
+    },
+    CompareOp::NotEq => {
+      min_ordering != Ordering::Equal || max_ordering != Ordering::Equal
+    },
+    CompareOp::Lt | CompareOp::LtEq => op.matches(min_ordering),
+    CompareOp::Gt | CompareOp::GtEq => op.matches(max_ordering)
+  }
+}
+
+/// Returns sorted, non-overlapping union of row ranges `left` and `right`, adjacent
+/// ranges are merged.
+fn union_row_ranges(left: Vec<Range<i64>>, right: Vec<Range<i64>>) -> Vec<Range<i64>> {
+  let mut ranges = left;
+  ranges.extend(right);
+  ranges.retain(|range| range.start < range.end);
+  ranges.sort_by_key(|range| range.start);
+  let mut result: Vec<Range<i64>> = Vec::with_capacity(ranges.len());
+  for range in ranges {
+    let merged = match result.last_mut() {
+      Some(last) if range.start <= last.end => {
+        last.end = cmp::max(last.end, range.end);
+        true
+      },
+      _ => false
+    };
+    if !merged {
+      result.push(range);
+    }
+  }
+  result
+}
+
+/// Returns intersection of sorted, non-overlapping row ranges `left` and `right`.
+fn intersect_row_ranges(left: &[Range<i64>], right: &[Range<i64>]) -> Vec<Range<i64>> {
+  let mut result = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < left.len() && j < right.len() {
+    let start = cmp::max(left[i].start, right[j].start);
+    let end = cmp::min(left[i].end, right[j].end);
+    if start < end {
+      result.push(start..end);
+    }
+    if left[i].end < right[j].end {
+      i += 1;
+    } else {
+      j += 1;
+    }
+  }
+  result
+}
+
+/// Returns column chunk of column `column` in `row_group`.
+fn find_column_chunk<'a>(
+  row_group: &'a RowGroupMetaData,
+  column: &str
+) -> Option<&'a ColumnChunkMetaData> {
+  row_group.columns().iter()
+    .find(|chunk| chunk.column_path().string() == column)
+    .map(|chunk| chunk.as_ref())
+}
+
+/// Returns min/max values of column chunk `chunk` as literals, if statistics are set,
+/// reliable according to `compat` and can be compared with literals.
+fn column_range(
+  chunk: &ColumnChunkMetaData,
+  compat: &WriterCompat
+) -> Option<(Literal, Literal)> {
+  let stats = chunk.statistics()?;
+  if !stats.has_min_max_set() {
+    return None;
+  }
+  let descr = chunk.column_descr();
+  let is_binary = match descr.physical_type() {
+    Type::BYTE_ARRAY | Type::FIXED_LEN_BYTE_ARRAY => true,
+    _ => false
+  };
+  if is_binary && compat.ignore_binary_statistics() {
+    return None;
+  }
+  let sort_order = SortOrder::from_types(descr.physical_type(), descr.logical_type());
+  if sort_order == SortOrder::UNDEFINED {
+    return None;
+  }
+  match stats.logical_min_max(descr)? {
+    // Order of arbitrary bytes does not match order of string literals
+    (LogicalValue::Bytes(_), _) | (_, LogicalValue::Bytes(_)) => None,
+    (min, max) => Some((logical_literal(&min)?, logical_literal(&max)?))
+  }
+}
+
+/// Converts logical value into literal. Timestamps are compared as integers, byte
+/// arrays as strings if they are valid UTF-8.
+fn logical_literal(value: &LogicalValue) -> Option<Literal> {
+  match *value {
+    LogicalValue::Bool(value) => Some(Literal::Bool(value)),
+    LogicalValue::Byte(value) => Some(Literal::Int(value as i64)),
+    LogicalValue::Short(value) => Some(Literal::Int(value as i64)),
+    LogicalValue::Int(value) => Some(Literal::Int(value as i64)),
+    LogicalValue::Long(value) => Some(Literal::Int(value)),
+    LogicalValue::Timestamp(value) => Some(Literal::Int(value)),
+    LogicalValue::Float(value) => Some(Literal::Double(value as f64)),
+    LogicalValue::Double(value) => Some(Literal::Double(value)),
+    LogicalValue::Str(ref value) => Some(Literal::Str(value.clone())),
+    LogicalValue::Bytes(ref value) => {
+      String::from_utf8(value.data().to_vec()).ok().map(Literal::Str)
+    },
+    LogicalValue::Decimal(ref value) => decimal_literal(value)
+  }
+}
+
+/// Returns DECIMAL value as a literal, an integer if scale is zero, otherwise a
+/// floating point number. Returns `None` if unscaled value does not fit into 53 bits
+/// of `f64` mantissa, since rounding could then change results of comparisons.
+fn decimal_literal(value: &Decimal) -> Option<Literal> {
+  let unscaled = value.unscaled();
+  if value.scale() == 0 &&
+      unscaled >= i64::min_value() as i128 && unscaled <= i64::max_value() as i128 {
+    return Some(Literal::Int(unscaled as i64));
+  }
+  let max_exact: i128 = 1 << 53;
+  if unscaled < -max_exact || unscaled > max_exact {
+    return None;
+  }
+  // Parsing decimal string rounds the same way as parsing of filter literals
+  value.to_string().parse().ok().map(Literal::Double)
+}
+
+/// Returns field of record `row` at dot-separated path `column`.
+fn find_field<'a>(row: &'a Row, column: &str) -> Option<&'a Row> {
+  let mut current = row;
+  for name in column.split('.') {
+    current = match *current {
+      Row::Group(ref fields) => {
+        fields.iter().find(|&&(ref key, _)| key == name).map(|&(_, ref value)| value)?
+      },
+      _ => return None
+    };
+  }
+  Some(current)
+}
+
+/// Returns `true` if field is missing or null.
+fn is_null(field: Option<&Row>) -> bool {
+  match field {
+    None | Some(&Row::Null) => true,
+    _ => false
+  }
+}
+
+/// Converts primitive field into literal, returns `None` for nulls and complex types.
+fn row_literal(row: &Row) -> Option<Literal> {
+  logical_literal(&row.to_logical()?)
+}
+
+// ----------------------------------------------------------------------
+// Filter parser
+
+/// Token of a filter string.
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Column(String),
+  Literal(Literal),
+  Op(CompareOp),
+  And,
+  Or,
+  Not,
+  Is,
+  Null,
+  LeftParen,
+  RightParen
+}
+
+impl fmt::Display for Token {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Token::Column(ref name) => write!(f, "{}", name),
+      Token::Literal(ref literal) => write!(f, "{}", literal),
+      Token::Op(op) => write!(f, "{}", op),
+      Token::And => write!(f, "AND"),
+      Token::Or => write!(f, "OR"),
+      Token::Not => write!(f, "NOT"),
+      Token::Is => write!(f, "IS"),
+      Token::Null => write!(f, "NULL"),
+      Token::LeftParen => write!(f, "("),
+      Token::RightParen => write!(f, ")")
+    }
+  }
+}
+
+/// Splits filter string into tokens.
+fn tokenize(filter: &str) -> Result<Vec<Token>> {
+  let chars: Vec<char> = filter.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    if c.is_whitespace() {
+      i += 1;
+      continue;
+    }
+    let (token, next) = match c {
+      '(' => (Token::LeftParen, i + 1),
+      ')' => (Token::RightParen, i + 1),
+      '=' if chars.get(i + 1) == Some(&'=') => (Token::Op(CompareOp::Eq), i + 2),
+      '=' => (Token::Op(CompareOp::Eq), i + 1),
+      '!' if chars.get(i + 1) == Some(&'=') => (Token::Op(CompareOp::NotEq), i + 2),
+      '<' if chars.get(i + 1) == Some(&'>') => (Token::Op(CompareOp::NotEq), i + 2),
+      '<' if chars.get(i + 1) == Some(&'=') => (Token::Op(CompareOp::LtEq), i + 2),
+      '<' => (Token::Op(CompareOp::Lt), i + 1),
+      '>' if chars.get(i + 1) == Some(&'=') => (Token::Op(CompareOp::GtEq), i + 2),
+      '>' => (Token::Op(CompareOp::Gt), i + 1),
+      '\'' | '"' => {
+        let (value, next) = read_quoted(&chars, i)?;
+        if c == '\'' {
+          (Token::Literal(Literal::Str(value)), next)
+        } else {
+          (Token::Column(value), next)
+        }
+      },
+      c if c.is_ascii_digit() || c == '-' || c == '.' => {
+        let end = scan(&chars, i + 1, |c| {
+          c.is_ascii_alphanumeric() || c == '.' || c == '+' || c == '-'
+        });
+        let value: String = chars[i..end].iter().collect();
+        (Token::Literal(parse_number(&value)?), end)
+      },
+      c if c.is_alphabetic() || c == '_' => {
+        let end = scan(&chars, i + 1, |c| c.is_alphanumeric() || c == '_' || c == '.');
+        let word: String = chars[i..end].iter().collect();
+        let token = match word.to_uppercase().as_str() {
+          "AND" => Token::And,
+          "OR" => Token::Or,
+          "NOT" => Token::Not,
+          "IS" => Token::Is,
+          "NULL" => Token::Null,
+          "TRUE" => Token::Literal(Literal::Bool(true)),
+          "FALSE" => Token::Literal(Literal::Bool(false)),
+          _ => Token::Column(word)
+        };
+        (token, end)
+      },
+      other => return Err(general_err!("unexpected character '{}'", other))
+    };
+    tokens.push(token);
+    i = next;
+  }
+  Ok(tokens)
+}
+
+/// Returns index of the first character starting at `start` that does not match
+/// `predicate`.
+fn scan<F: Fn(char) -> bool>(chars: &[char], start: usize, predicate: F) -> usize {
+  let mut end = start;
+  while end < chars.len() && predicate(chars[end]) {
+    end += 1;
+  }
+  end
+}
+
+/// Reads string enclosed in quotes starting at `start`, quote character is escaped
+/// by doubling it. Returns value and index after the closing quote.
+fn read_quoted(chars: &[char], start: usize) -> Result<(String, usize)> {
+  let quote = chars[start];
+  let mut value = String::new();
+  let mut i = start + 1;
+  while i < chars.len() {
+    if chars[i] == quote {
+      if chars.get(i + 1) == Some(&quote) {
+        value.push(quote);
+        i += 2;
+        continue;
+      }
+      return Ok((value, i + 1));
+    }
+    value.push(chars[i]);
+    i += 1;
+  }
+  Err(general_err!("unterminated string starting at position {}", start))
+}
+
+/// Parses numeric literal, integers that do not fit into `i64` are parsed as
+/// floating point numbers.
+fn parse_number(value: &str) -> Result<Literal> {
+  if let Ok(int) = value.parse::<i64>() {
+    return Ok(Literal::Int(int));
+  }
+  value.parse::<f64>()
+    .map(Literal::Double)
+    .map_err(|_| general_err!("invalid number '{}'", value))
+}
+
+/// Recursive descent parser of filter tokens, `AND` has higher precedence than `OR`.
+struct Parser {
+  tokens: Vec<Token>,
+  index: usize
+}
+
+impl Parser {
+  fn parse(&mut self) -> Result<Expr> {
+    let expr = self.parse_or()?;
+    match self.next() {
+      Some(token) => Err(general_err!("unexpected token '{}'", token)),
+      None => Ok(expr)
+    }
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.index).cloned();
+    if token.is_some() {
+      self.index += 1;
+    }
+    token
+  }
+
+  /// Consumes next token if it is equal to `expected`.
+  fn consume(&mut self, expected: &Token) -> bool {
+    if self.tokens.get(self.index) == Some(expected) {
+      self.index += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn parse_or(&mut self) -> Result<Expr> {
+    let mut expr = self.parse_and()?;
+    while self.consume(&Token::Or) {
+      expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+    }
+    Ok(expr)
+  }
+
+  fn parse_and(&mut self) -> Result<Expr> {
+    let mut expr = self.parse_not()?;
+    while self.consume(&Token::And) {
+      expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+    }
+    Ok(expr)
+  }
+
+  fn parse_not(&mut self) -> Result<Expr> {
+    if self.consume(&Token::Not) {
+      Ok(Expr::Not(Box::new(self.parse_not()?)))
+    } else {
+      self.parse_primary()
+    }
+  }
+
+  fn parse_primary(&mut self) -> Result<Expr> {
+    match self.next() {
+      Some(Token::LeftParen) => {
+        let expr = self.parse_or()?;
+        if !self.consume(&Token::RightParen) {
+          return Err(general_err!("expected ')'"));
+        }
+        Ok(expr)
+      },
+      Some(Token::Column(column)) => match self.next() {
+        Some(Token::Op(op)) => match self.next() {
+          Some(Token::Literal(literal)) => Ok(Expr::Compare(column, op, literal)),
+          _ => Err(general_err!("expected literal after '{} {}'", column, op))
+        },
+        Some(Token::Is) => {
+          let negated = self.consume(&Token::Not);
+          if !self.consume(&Token::Null) {
+            return Err(general_err!("expected NULL after IS"));
+          }
+          Ok(if negated { Expr::IsNotNull(column) } else { Expr::IsNull(column) })
+        },
+        _ => Err(general_err!("expected comparison or IS after '{}'", column))
+      },
+      Some(Token::Literal(literal)) => match (self.next(), self.next()) {
+        (Some(Token::Op(op)), Some(Token::Column(column))) => {
+          Ok(Expr::Compare(column, op.swap(), literal))
+        },
+        _ => Err(general_err!("expected comparison with column after '{}'", literal))
+      },
+      Some(token) => Err(general_err!("unexpected token '{}'", token)),
+      None => Err(general_err!("unexpected end of filter"))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use basic::{Encoding, PageType};
+  use data_type::ByteArray;
+  use file::layout::PageLayout;
+  use file::reader::{FileReader, SerializedFileReader};
+  use file::statistics::Statistics;
+  use schema::parser::parse_message_type;
+  use util::test_common::get_test_file;
+
+  fn column(name: &str) -> String {
+    name.to_string()
+  }
+
+  #[test]
+  fn test_parse_filter() {
+    assert_eq!(
+      parse_filter("a > 5 AND b = 'x'").unwrap(),
+      Expr::And(
+        Box::new(Expr::Compare(column("a"), CompareOp::Gt, Literal::Int(5))),
+        Box::new(Expr::Compare(column("b"), CompareOp::Eq, Literal::Str("x".to_string())))
+      )
+    );
+    assert_eq!(
+      parse_filter("a = 1 or b.c <= -2.5 and not d is null").unwrap(),
+      Expr::Or(
+        Box::new(Expr::Compare(column("a"), CompareOp::Eq, Literal::Int(1))),
+        Box::new(Expr::And(
+          Box::new(Expr::Compare(column("b.c"), CompareOp::LtEq, Literal::Double(-2.5))),
+          Box::new(Expr::Not(Box::new(Expr::IsNull(column("d")))))
+        ))
+      )
+    );
+    assert_eq!(
+      parse_filter("(a <> TRUE OR 10 < \"my col\") AND s == 'it''s'").unwrap(),
+      Expr::And(
+        Box::new(Expr::Or(
+          Box::new(Expr::Compare(column("a"), CompareOp::NotEq, Literal::Bool(true))),
+          Box::new(Expr::Compare(column("my col"), CompareOp::Gt, Literal::Int(10)))
+        )),
+        Box::new(Expr::Compare(column("s"), CompareOp::Eq, Literal::Str("it's".into())))
+      )
+    );
+    assert_eq!(
+      parse_filter("a IS NOT NULL").unwrap(),
+      Expr::IsNotNull(column("a"))
+    );
+  }
+
+  #[test]
+  fn test_parse_filter_invalid() {
+    let filters = vec![
+      "", "a", "a >", "a > b", "(a > 1", "a > 1)", "a > 1 AND", "a IS 1", "a > 'x",
+      "a > 1x", "a # 1", "1 > 2"
+    ];
+    for filter in filters {
+      match parse_filter(filter) {
+        Err(ParquetError::General(message)) => {
+          assert!(message.starts_with(&format!("Invalid filter '{}': ", filter)));
+        },
+        other => panic!("Expected error for filter '{}', got {:?}", filter, other)
+      }
+    }
+  }
+
+  #[test]
+  fn test_filter_display() {
+    let filter = parse_filter("NOT a >= 1.5 OR b = 'it''s' AND c IS NULL").unwrap();
+    assert_eq!(
+      filter.to_string(),
+      "(NOT (a >= 1.5)) OR ((b = 'it''s') AND (c IS NULL))"
+    );
+    assert_eq!(parse_filter(&filter.to_string()).unwrap(), filter);
+    assert_eq!(filter.columns(), vec!["a", "b", "c"]);
+  }
+
+  #[test]
+  fn test_filter_matches() {
+    let row = Row::Group(vec![
+      (column("a"), Row::Int(6)),
+      (column("b"), Row::Group(vec![(column("c"), Row::Str("x".to_string()))])),
+      (column("d"), Row::Null),
+      (column("e"), Row::Double(1.5))
+    ]);
+    let cases = vec![
+      ("a > 5", true),
+      ("a > 6", false),
+      ("a = 6.0", true),
+      ("e < 2", true),
+      ("b.c = 'x' AND a >= 6", true),
+      ("b.c != 'x' OR a < 6", false),
+      ("d IS NULL AND missing IS NULL", true),
+      ("d IS NOT NULL", false),
+      ("d = 1 OR d != 1", false),
+      ("NOT d = 1", true),
+      ("b = 'x'", false),
+      ("a = 'x'", false)
+    ];
+    for (filter, expected) in cases {
+      assert_eq!(parse_filter(filter).unwrap().matches(&row), expected, "{}", filter);
+    }
+  }
+
+  /// Returns row group metadata with statistics for columns `a` INT32, `b` UTF8, `c`
+  /// UINT_32 and `d` DOUBLE.
+  fn test_row_group() -> RowGroupMetaData {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BYTE_ARRAY b (UTF8);
+        REQUIRED INT32 c (UINT_32);
+        OPTIONAL DOUBLE d;
+      }
+    ").unwrap();
+    let schema_descr = Rc::new(SchemaDescriptor::new(Rc::new(schema)));
+    let stats = vec![
+      Statistics::int32(Some(1), Some(10), None, 0, false),
+      Statistics::byte_array(Some("b".into()), Some("d".into()), None, 0, false),
+      Statistics::int32(Some(1), Some(10), None, 0, false),
+      Statistics::double(None, None, None, 0, false)
+    ];
+    let columns = schema_descr.columns().iter().zip(stats.into_iter())
+      .map(|(descr, stats)| {
+        Rc::new(ColumnChunkMetaData::builder(descr.clone())
+          .with_statistics(Some(stats))
+          .build()
+          .unwrap())
+      })
+      .collect();
+    RowGroupMetaData::builder(schema_descr)
+      .with_column_metadata(columns)
+      .build()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_filter_might_match() {
+    let row_group = test_row_group();
+    let schema_descr = row_group.schema_descr_ptr();
+    let cases = vec![
+      ("a = 5", true),
+      ("a = 0 OR a = 11", false),
+      ("a < 1", false),
+      ("a <= 1", true),
+      ("a > 10", false),
+      ("a >= 10", true),
+      ("a != 5", true),
+      ("a > 2.5", true),
+      ("a > 10.5", false),
+      ("b = 'c'", true),
+      ("b < 'b'", false),
+      ("b > 'da'", false),
+      ("a > 5 AND b = 'a'", false),
+      ("a > 50 OR b = 'c'", true),
+      ("c > 50", true),
+      ("d > 1", true),
+      ("NOT a > 50", true),
+      ("b IS NULL", true),
+      ("b = 1", true),
+      ("missing = 1", true)
+    ];
+    for (filter, expected) in cases {
+      let filter = parse_filter(filter).unwrap();
+      assert_eq!(filter.might_match(&row_group), expected, "{}", filter);
+    }
+
+    assert!(parse_filter("b = 'x' AND a > 1").unwrap().validate(&schema_descr).is_ok());
+    match parse_filter("a > 1 OR x = 2").unwrap().validate(&schema_descr) {
+      Err(ParquetError::General(message)) => {
+        assert_eq!(message, "Column 'x' not found in schema")
+      },
+      other => panic!("Expected error, got {:?}", other)
+    }
+  }
+
+  #[test]
+  fn test_filter_might_match_decimal() {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a (DECIMAL(5, 2));
+        REQUIRED FIXED_LEN_BYTE_ARRAY(8) b (DECIMAL(18, 0));
+        REQUIRED FIXED_LEN_BYTE_ARRAY(16) c (DECIMAL(38, 2));
+      }
+    ").unwrap();
+    let schema_descr = Rc::new(SchemaDescriptor::new(Rc::new(schema)));
+    let stats = vec![
+      Statistics::int32(Some(-150), Some(2500), None, 0, false),
+      Statistics::fixed_len_byte_array(
+        ByteArray::from_decimal(-20, 8), ByteArray::from_decimal(7, 8), None, 0, false),
+      Statistics::fixed_len_byte_array(
+        ByteArray::from_decimal(0, 16),
+        ByteArray::from_decimal(1 << 60, 16),
+        None,
+        0,
+        false
+      )
+    ];
+    let columns = schema_descr.columns().iter().zip(stats.into_iter())
+      .map(|(descr, stats)| {
+        Rc::new(ColumnChunkMetaData::builder(descr.clone())
+          .with_statistics(Some(stats))
+          .build()
+          .unwrap())
+      })
+      .collect();
+    let row_group = RowGroupMetaData::builder(schema_descr)
+      .with_column_metadata(columns)
+      .build()
+      .unwrap();
+
+    let cases = vec![
+      ("a = 1.5", true),
+      ("a < -1.5", false),
+      ("a <= -1.5", true),
+      ("a > 25", false),
+      ("a >= 25.0", true),
+      ("b < -20", false),
+      ("b = -3", true),
+      ("b > 7.5", false),
+      // Unscaled values exceed precision of floating point numbers
+      ("c < 0", true)
+    ];
+    for (filter, expected) in cases {
+      let filter = parse_filter(filter).unwrap();
+      assert_eq!(filter.might_match(&row_group), expected, "{}", filter);
+    }
+  }
+
+  /// External index that knows exact set of values of column `a`.
+  struct TestIndex {
+    values: Vec<i64>
+  }
+
+  impl ExternalIndex for TestIndex {
+    fn might_match(
+      &self,
+      chunk: &ColumnChunkMetaData,
+      op: CompareOp,
+      literal: &Literal
+    ) -> bool {
+      if chunk.column_path().string() != "a" {
+        return true;
+      }
+      values_might_match(&self.values, op, literal)
+    }
+
+    fn page_might_match(
+      &self,
+      chunk: &ColumnChunkMetaData,
+      page: &PageRowRange,
+      op: CompareOp,
+      literal: &Literal
+    ) -> bool {
+      if chunk.column_path().string() != "a" {
+        return true;
+      }
+      // Page at offset `n * 100` contains values `n * 10..n * 10 + 10`
+      let first = page.page.offset as i64 / 100 * 10;
+      let values: Vec<i64> = (first..first + 10).collect();
+      values_might_match(&values, op, literal)
+    }
+  }
+
+  fn values_might_match(values: &[i64], op: CompareOp, literal: &Literal) -> bool {
+    values.iter().any(|value| {
+      match Literal::Int(*value).compare(literal) {
+        Some(ordering) => op.matches(ordering),
+        None => true
+      }
+    })
+  }
+
+  #[test]
+  fn test_filter_pruner_external_index() {
+    let row_group = test_row_group();
+    let pruner = |filter: &str| {
+      Pruner::new(parse_filter(filter).unwrap())
+        .with_external_index(Box::new(TestIndex { values: vec![1, 3, 10] }))
+    };
+
+    let cases = vec![
+      // Ruled out by external index only
+      ("a = 5", false),
+      ("a = 3", true),
+      ("a = 2 OR a > 10", false),
+      ("a = 5 OR b = 'c'", true),
+      // Ruled out by statistics only
+      ("a > 10", false),
+      ("b < 'b'", false),
+      // External index does not know column
+      ("d > 1", true)
+    ];
+    for (filter, expected) in cases {
+      let pruner = pruner(filter);
+      assert_eq!(pruner.might_match(&row_group), expected, "{}", pruner.expr());
+    }
+
+    // Without external indexes only statistics are used
+    assert!(Pruner::new(parse_filter("a = 5").unwrap()).might_match(&row_group));
+  }
+
+  #[test]
+  fn test_filter_pruner_matching_row_ranges() {
+    let row_group = RowGroupMetaData::builder(test_row_group().schema_descr_ptr())
+      .with_column_metadata(test_row_group().columns().to_vec())
+      .with_num_rows(30)
+      .build()
+      .unwrap();
+    // Every column chunk has pages at offsets 0, 100 and 200 of 10 rows each
+    let page_row_ranges = |_: usize| -> Result<Vec<PageRowRange>> {
+      let pages = (0..3).map(|n| PageRowRange {
+        page: PageLayout {
+          page_type: PageType::DATA_PAGE,
+          offset: n * 100,
+          header_size: 10,
+          compressed_size: 90,
+          uncompressed_size: 90,
+          num_values: 10,
+          encoding: Some(Encoding::PLAIN),
+          crc: None,
+          num_rows: None
+        },
+        first_row_index: n as i64 * 10,
+        num_rows: 10
+      });
+      Ok(pages.collect())
+    };
+    let pruner = |filter: &str| {
+      Pruner::new(parse_filter(filter).unwrap())
+        .with_external_index(Box::new(TestIndex { values: vec![1, 3, 10, 25] }))
+    };
+
+    let cases = vec![
+      ("a = 3", vec![0..10]),
+      ("a >= 10", vec![10..30]),
+      ("a = 3 OR a = 25", vec![0..10, 20..30]),
+      ("a >= 10 AND a < 20", vec![10..20]),
+      ("a < 5 AND a >= 10", vec![]),
+      ("a = 3 OR b = 'c'", vec![0..30]),
+      ("NOT (a = 3)", vec![0..30]),
+      // Ruled out for the whole row group
+      ("a = 5", vec![]),
+      ("d > 1", vec![0..30])
+    ];
+    for (filter, expected) in cases {
+      let pruner = pruner(filter);
+      assert_eq!(
+        pruner.matching_row_ranges(&row_group, &page_row_ranges).unwrap(),
+        expected,
+        "{}",
+        pruner.expr()
+      );
+    }
+
+    let pruner = pruner("a = 3");
+    assert_eq!(
+      pruner.matching_row_ranges(&row_group, |_| Err(general_err!("Page error"))),
+      Err(general_err!("Page error"))
+    );
+  }
+
+  #[test]
+  fn test_filter_pruner_writer_compat() {
+    let row_group = test_row_group();
+    let pruner = |filter: &str, created_by: &str| {
+      Pruner::new(parse_filter(filter).unwrap())
+        .with_compat(WriterCompat::new(Some(created_by)))
+    };
+
+    // Binary statistics of parquet-mr before 1.8.0 are ignored
+    assert!(pruner("b < 'b'", "parquet-mr version 1.7.0").might_match(&row_group));
+    assert!(!pruner("a > 10", "parquet-mr version 1.7.0").might_match(&row_group));
+    assert!(!pruner("b < 'b'", "parquet-mr version 1.8.0").might_match(&row_group));
+    assert!(!pruner("b < 'b'", "parquet-cpp version 1.3.0").might_match(&row_group));
+    assert!(!Pruner::new(parse_filter("b < 'b'").unwrap()).might_match(&row_group));
+  }
+
+  #[test]
+  fn test_filter_file_records() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let filter = parse_filter("id > 5 OR id = 0").unwrap();
+    filter.validate(reader.metadata().file_metadata().schema_descr()).unwrap();
+    let ids: Vec<String> = reader.get_row_iter(None).unwrap()
+      .filter(|row| filter.matches(row))
+      .map(|row| match row {
+        Row::Group(ref fields) => fields[0].1.to_string(),
+        _ => panic!("Expected group")
+      })
+      .collect();
+    assert_eq!(ids, vec!["6", "7", "0"]);
+  }
+}
@@ -0,0 +1,301 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains golden fixture files for interoperability testing.
+//!
+//! [`Fixture::all`](`Fixture::all`) returns a matrix of small files that covers every
+//! combination of physical type, encoding, compression codec and nullability that this
+//! crate can write. Each file has a single column `value` with the same deterministic
+//! values, so files can be cross-checked against other implementations, such as
+//! parquet-mr and parquet-cpp, or used to validate other readers.
+//!
+//! Files are written into a directory with [`write_fixtures`](`write_fixtures`), or
+//! with the `parquet-fixtures` binary.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use basic::{Compression, Encoding, LogicalType, Repetition, Type as PhysicalType};
+use data_type::ByteArray;
+use errors::Result;
+use file::builder::InMemoryFileBuilder;
+use file::properties::{WriterProperties, WriterPropertiesPtr};
+use record::api::Row;
+use schema::types::{Type, TypePtr};
+
+/// Number of rows in every fixture file.
+pub const FIXTURE_NUM_ROWS: usize = 12;
+
+/// Length of values of FIXED_LEN_BYTE_ARRAY fixtures.
+const FIXED_LEN_BYTE_ARRAY_LENGTH: i32 = 4;
+
+/// Physical types of fixtures.
+const PHYSICAL_TYPES: [PhysicalType; 8] = [
+  PhysicalType::BOOLEAN,
+  PhysicalType::INT32,
+  PhysicalType::INT64,
+  PhysicalType::INT96,
+  PhysicalType::FLOAT,
+  PhysicalType::DOUBLE,
+  PhysicalType::BYTE_ARRAY,
+  PhysicalType::FIXED_LEN_BYTE_ARRAY
+];
+
+/// Compression codecs supported by the writer.
+const COMPRESSIONS: [Compression; 5] = [
+  Compression::UNCOMPRESSED,
+  Compression::SNAPPY,
+  Compression::GZIP,
+  Compression::BROTLI,
+  Compression::ZSTD
+];
+
+/// Golden fixture file with a single column, described by its physical type, encoding
+/// of values, compression codec and repetition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fixture {
+  physical_type: PhysicalType,
+  encoding: Encoding,
+  compression: Compression,
+  repetition: Repetition
+}
+
+impl Fixture {
+  /// Creates fixture description.
+  ///
+  /// `PLAIN_DICTIONARY` encoding enables dictionary encoding of the column, any other
+  /// encoding is set as the encoding of the column with dictionary disabled.
+  pub fn new(
+    physical_type: PhysicalType,
+    encoding: Encoding,
+    compression: Compression,
+    repetition: Repetition
+  ) -> Self {
+    Self {
+      physical_type: physical_type,
+      encoding: encoding,
+      compression: compression,
+      repetition: repetition
+    }
+  }
+
+  /// Returns all supported combinations of physical type, encoding, compression and
+  /// repetition (`REQUIRED` and `OPTIONAL`).
+  pub fn all() -> Vec<Fixture> {
+    let mut fixtures = Vec::new();
+    for &physical_type in PHYSICAL_TYPES.iter() {
+      for encoding in supported_encodings(physical_type) {
+        for &compression in COMPRESSIONS.iter() {
+          for &repetition in &[Repetition::REQUIRED, Repetition::OPTIONAL] {
+            fixtures.push(Fixture::new(physical_type, encoding, compression, repetition));
+          }
+        }
+      }
+    }
+    fixtures
+  }
+
+  /// Returns physical type of the column.
+  pub fn physical_type(&self) -> PhysicalType {
+    self.physical_type
+  }
+
+  /// Returns encoding of values of the column.
+  pub fn encoding(&self) -> Encoding {
+    self.encoding
+  }
+
+  /// Returns compression codec of the column.
+  pub fn compression(&self) -> Compression {
+    self.compression
+  }
+
+  /// Returns repetition of the column.
+  pub fn repetition(&self) -> Repetition {
+    self.repetition
+  }
+
+  /// Returns file name of the fixture, e.g.
+  /// `int32_delta_binary_packed_snappy_optional.parquet`.
+  pub fn file_name(&self) -> String {
+    format!(
+      "{}_{}_{}_{}.parquet",
+      self.physical_type,
+      self.encoding,
+      self.compression,
+      self.repetition
+    ).to_lowercase()
+  }
+
+  /// Returns message type of the fixture.
+  pub fn schema(&self) -> TypePtr {
+    let mut builder = Type::primitive_type_builder("value", self.physical_type)
+      .with_repetition(self.repetition);
+    match self.physical_type {
+      PhysicalType::BYTE_ARRAY => {
+        builder = builder.with_logical_type(LogicalType::UTF8);
+      },
+      PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+        builder = builder.with_length(FIXED_LEN_BYTE_ARRAY_LENGTH);
+      },
+      _ => {}
+    }
+    let mut fields = vec![Rc::new(builder.build().unwrap())];
+    Rc::new(
+      Type::group_type_builder("fixture")
+        .with_fields(&mut fields)
+        .build()
+        .unwrap()
+    )
+  }
+
+  /// Returns writer properties of the fixture.
+  pub fn props(&self) -> WriterPropertiesPtr {
+    let builder = WriterProperties::builder()
+      .with_compression(self.compression)
+      .with_int96_timestamps_enabled(true);
+    let builder = if self.encoding == Encoding::PLAIN_DICTIONARY {
+      builder.with_dictionary_enabled(true).with_encoding(Encoding::PLAIN)
+    } else {
+      builder.with_dictionary_enabled(false).with_encoding(self.encoding)
+    };
+    Rc::new(builder.build())
+  }
+
+  /// Returns rows of the fixture, every fourth value starting from the second one is
+  /// null in optional columns.
+  pub fn rows(&self) -> Vec<Row> {
+    (0..FIXTURE_NUM_ROWS).map(|i| {
+      let value = if self.repetition == Repetition::OPTIONAL && i % 4 == 1 {
+        Row::Null
+      } else {
+        fixture_value(self.physical_type, i)
+      };
+      Row::Group(vec![("value".to_string(), value)])
+    }).collect()
+  }
+
+  /// Writes fixture into memory and returns bytes of the file.
+  pub fn build(&self) -> Result<Vec<u8>> {
+    InMemoryFileBuilder::new(self.schema())
+      .with_properties(self.props())
+      .with_rows(self.rows())
+      .build()
+  }
+}
+
+/// Writes all fixtures into the directory, which is created if it does not exist, and
+/// returns paths of the written files.
+pub fn write_fixtures(dir: &Path) -> Result<Vec<PathBuf>> {
+  fs::create_dir_all(dir)?;
+  let mut paths = Vec::new();
+  for fixture in Fixture::all() {
+    let path = dir.join(fixture.file_name());
+    fs::File::create(&path)?.write_all(&fixture.build()?)?;
+    paths.push(path);
+  }
+  Ok(paths)
+}
+
+/// Returns encodings that the column writer supports for the physical type, where
+/// `PLAIN_DICTIONARY` stands for dictionary encoding.
+fn supported_encodings(physical_type: PhysicalType) -> Vec<Encoding> {
+  match physical_type {
+    PhysicalType::BOOLEAN => vec![Encoding::PLAIN, Encoding::RLE],
+    PhysicalType::INT32 | PhysicalType::INT64 => vec![
+      Encoding::PLAIN,
+      Encoding::PLAIN_DICTIONARY,
+      Encoding::DELTA_BINARY_PACKED
+    ],
+    PhysicalType::BYTE_ARRAY => vec![
+      Encoding::PLAIN,
+      Encoding::PLAIN_DICTIONARY,
+      Encoding::DELTA_LENGTH_BYTE_ARRAY,
+      Encoding::DELTA_BYTE_ARRAY
+    ],
+    _ => vec![Encoding::PLAIN, Encoding::PLAIN_DICTIONARY]
+  }
+}
+
+/// Returns non-null value of the row `i` for the physical type. Values repeat, so
+/// dictionary encoding is effective.
+fn fixture_value(physical_type: PhysicalType, i: usize) -> Row {
+  match physical_type {
+    PhysicalType::BOOLEAN => Row::Bool(i % 3 == 0),
+    PhysicalType::INT32 => Row::Int((i % 5) as i32 - 2),
+    PhysicalType::INT64 => Row::Long(((i % 6) as i64 - 3) * 1_000_000_000_000),
+    PhysicalType::INT96 => Row::Timestamp(1_238_544_000_000 + (i % 4) as u64 * 3_600_000),
+    PhysicalType::FLOAT => Row::Float((i % 5) as f32 * 0.5 - 1.0),
+    PhysicalType::DOUBLE => Row::Double((i % 5) as f64 * -1.25),
+    PhysicalType::BYTE_ARRAY => Row::Str(format!("value_{}", i % 4)),
+    PhysicalType::FIXED_LEN_BYTE_ARRAY => {
+      Row::Bytes(ByteArray::from(vec![b'v', b'_', b'0' + (i % 4) as u8, 0]))
+    }
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use file::reader::{FileReader, SerializedFileReader};
+  use util::test_common::get_temp_filename;
+
+  #[test]
+  fn test_fixture_matrix() {
+    let fixtures = Fixture::all();
+    // (2 + 3 + 3 + 2 + 2 + 2 + 4 + 2) encodings * 5 codecs * 2 repetitions
+    assert_eq!(fixtures.len(), 200);
+
+    let fixture = Fixture::new(
+      PhysicalType::INT32,
+      Encoding::DELTA_BINARY_PACKED,
+      Compression::SNAPPY,
+      Repetition::OPTIONAL
+    );
+    assert!(fixtures.contains(&fixture));
+    assert_eq!(fixture.file_name(), "int32_delta_binary_packed_snappy_optional.parquet");
+  }
+
+  #[test]
+  fn test_write_fixtures() {
+    let dir = get_temp_filename("fixtures");
+    let paths = write_fixtures(&dir).unwrap();
+    assert_eq!(paths.len(), 200);
+
+    for (fixture, path) in Fixture::all().iter().zip(paths.iter()) {
+      let reader = SerializedFileReader::new(fs::File::open(path).unwrap()).unwrap();
+      let metadata = reader.metadata();
+      assert_eq!(metadata.file_metadata().num_rows(), FIXTURE_NUM_ROWS as i64);
+
+      let column = metadata.row_group(0).column(0);
+      assert_eq!(column.compression(), fixture.compression());
+      assert!(
+        column.encodings().contains(&fixture.encoding()),
+        "Encoding {} not found in {:?}",
+        fixture.encoding(),
+        column.encodings()
+      );
+
+      let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+      assert_eq!(rows, fixture.rows(), "Rows of {}", fixture.file_name());
+    }
+  }
+}
@@ -0,0 +1,572 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains API to analyse physical layout of a Parquet file.
+//!
+//! Layout is collected from page headers only, page data is neither decompressed nor
+//! decoded. This makes it cheap to inspect how a file was written (page sizes, number
+//! of values per page, compression ratios and encodings) in order to tune writer
+//! properties.
+//!
+//! See [`SerializedFileReader::layout`](`::file::reader::SerializedFileReader::layout`)
+//! for the entry point.
+//!
+//! Data pages can also be listed together with the range of row numbers they cover,
+//! see [`page_row_ranges`](`::file::layout::page_row_ranges`). This allows external
+//! systems to build row-addressable indexes over Parquet files.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use basic::{Compression, Encoding, PageType};
+use errors::{ParquetError, Result};
+use file::page_index::OffsetIndex;
+use schema::types::ColumnPath;
+
+// ----------------------------------------------------------------------
+// Histogram
+
+/// Histogram of non-negative values with power-of-two buckets.
+///
+/// Bucket `0` contains value `0`, bucket `i > 0` contains values in the range
+/// `[2^(i - 1), 2^i)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+  counts: Vec<usize>,
+  num_values: usize,
+  sum: u64,
+  min: u64,
+  max: u64
+}
+
+impl Histogram {
+  /// Creates new empty histogram.
+  pub fn new() -> Self {
+    Self {
+      counts: vec![0; 65],
+      num_values: 0,
+      sum: 0,
+      min: u64::max_value(),
+      max: 0
+    }
+  }
+
+  /// Adds value to this histogram.
+  pub fn add(&mut self, value: u64) {
+    let bucket = 64 - value.leading_zeros() as usize;
+    self.counts[bucket] += 1;
+    self.num_values += 1;
+    self.sum += value;
+    if value < self.min {
+      self.min = value;
+    }
+    if value > self.max {
+      self.max = value;
+    }
+  }
+
+  /// Merges values of another histogram into this one.
+  pub fn merge(&mut self, other: &Histogram) {
+    for i in 0..self.counts.len() {
+      self.counts[i] += other.counts[i];
+    }
+    self.num_values += other.num_values;
+    self.sum += other.sum;
+    if other.min < self.min {
+      self.min = other.min;
+    }
+    if other.max > self.max {
+      self.max = other.max;
+    }
+  }
+
+  /// Returns number of values added to this histogram.
+  pub fn num_values(&self) -> usize {
+    self.num_values
+  }
+
+  /// Returns sum of all values.
+  pub fn sum(&self) -> u64 {
+    self.sum
+  }
+
+  /// Returns minimum value, or `None` if histogram is empty.
+  pub fn min(&self) -> Option<u64> {
+    if self.num_values == 0 { None } else { Some(self.min) }
+  }
+
+  /// Returns maximum value, or `None` if histogram is empty.
+  pub fn max(&self) -> Option<u64> {
+    if self.num_values == 0 { None } else { Some(self.max) }
+  }
+
+  /// Returns mean value, or `None` if histogram is empty.
+  pub fn mean(&self) -> Option<f64> {
+    if self.num_values == 0 {
+      None
+    } else {
+      Some(self.sum as f64 / self.num_values as f64)
+    }
+  }
+
+  /// Returns non-empty buckets as tuples of `(lower bound, upper bound, count)`, where
+  /// lower bound is inclusive and upper bound is exclusive.
+  pub fn buckets(&self) -> Vec<(u64, u64, usize)> {
+    let mut result = Vec::new();
+    for (i, count) in self.counts.iter().enumerate() {
+      if *count == 0 {
+        continue;
+      }
+      let (lower, upper) = match i {
+        0 => (0, 1),
+        64 => (1 << 63, u64::max_value()),
+        _ => (1 << (i - 1), 1 << i)
+      };
+      result.push((lower, upper, *count));
+    }
+    result
+  }
+}
+
+impl fmt::Display for Histogram {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.num_values == 0 {
+      return write!(f, "<empty>");
+    }
+    write!(
+      f,
+      "min: {}, max: {}, mean: {:.1}",
+      self.min,
+      self.max,
+      self.mean().unwrap()
+    )?;
+    for (lower, upper, count) in self.buckets() {
+      write!(f, ", [{}, {}): {}", lower, upper, count)?;
+    }
+    Ok(())
+  }
+}
+
+// ----------------------------------------------------------------------
+// Layout of pages, column chunks and row groups
+
+/// Layout information of a single page, collected from the page header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageLayout {
+  /// Type of the page.
+  pub page_type: PageType,
+  /// Offset of the page header in the file.
+  pub offset: u64,
+  /// Size of the serialized page header in bytes.
+  pub header_size: usize,
+  /// Size of the page data as stored in the file, in bytes.
+  pub compressed_size: usize,
+  /// Size of the page data after decompression, in bytes.
+  pub uncompressed_size: usize,
+  /// Number of values (including nulls) in the page, `0` for index pages.
+  pub num_values: u32,
+  /// Encoding of values in the page, `None` for index pages.
+  pub encoding: Option<Encoding>,
+  /// CRC32 checksum of the page data, if written into the page header.
+  pub crc: Option<u32>,
+  /// Number of rows in the page, as recorded in v2 data page headers, `None` for
+  /// other pages.
+  pub num_rows: Option<u32>
+}
+
+impl PageLayout {
+  /// Returns `true` if page is a data page (either v1 or v2).
+  pub fn is_data_page(&self) -> bool {
+    self.page_type == PageType::DATA_PAGE || self.page_type == PageType::DATA_PAGE_V2
+  }
+}
+
+/// Data page together with the range of rows it covers within a row group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRowRange {
+  /// Layout of the data page.
+  pub page: PageLayout,
+  /// Index of the first row of the page within the row group.
+  pub first_row_index: i64,
+  /// Number of rows in the page.
+  pub num_rows: i64
+}
+
+impl PageRowRange {
+  /// Returns range of row indexes within the row group covered by the page.
+  pub fn row_range(&self) -> Range<i64> {
+    self.first_row_index..self.first_row_index + self.num_rows
+  }
+}
+
+/// Returns data pages of a column chunk together with the range of rows each page
+/// covers, where `pages` are all pages of the column chunk in file order and `num_rows`
+/// is number of rows in the row group.
+///
+/// Row ranges are taken from the offset index, if provided, and the offset index is
+/// checked against page offsets. Otherwise number of rows is taken from v2 data page
+/// headers, or from number of values for columns without repetition, i.e.
+/// `max_rep_level` is `0`. Returns error if row ranges cannot be determined or do not
+/// add up to `num_rows`.
+pub fn page_row_ranges(
+  pages: &[PageLayout],
+  offset_index: Option<&OffsetIndex>,
+  max_rep_level: i16,
+  num_rows: i64
+) -> Result<Vec<PageRowRange>> {
+  let data_pages: Vec<&PageLayout> = pages.iter().filter(|p| p.is_data_page()).collect();
+  let mut result = Vec::with_capacity(data_pages.len());
+
+  if let Some(offset_index) = offset_index {
+    let locations = offset_index.page_locations();
+    if locations.len() != data_pages.len() {
+      return Err(general_err!(
+        "Offset index has {} page locations, but column chunk has {} data pages",
+        locations.len(),
+        data_pages.len()
+      ));
+    }
+    for (i, (page, location)) in data_pages.iter().zip(locations).enumerate() {
+      if location.offset as u64 != page.offset {
+        return Err(general_err!(
+          "Offset index location {} does not match data page at offset {}",
+          location.offset,
+          page.offset
+        ));
+      }
+      let end = locations.get(i + 1).map(|l| l.first_row_index).unwrap_or(num_rows);
+      if end < location.first_row_index {
+        return Err(general_err!(
+          "Invalid first row index {} of data page at offset {}",
+          location.first_row_index,
+          page.offset
+        ));
+      }
+      result.push(PageRowRange {
+        page: (*page).clone(),
+        first_row_index: location.first_row_index,
+        num_rows: end - location.first_row_index
+      });
+    }
+    return Ok(result);
+  }
+
+  let mut first_row_index = 0;
+  for page in data_pages {
+    let page_num_rows = match page.num_rows {
+      Some(page_num_rows) => page_num_rows as i64,
+      None if max_rep_level == 0 => page.num_values as i64,
+      None => return Err(general_err!(
+        "Cannot determine number of rows of data page at offset {} in a repeated \
+         column without offset index",
+        page.offset
+      ))
+    };
+    result.push(PageRowRange {
+      page: page.clone(),
+      first_row_index: first_row_index,
+      num_rows: page_num_rows
+    });
+    first_row_index += page_num_rows;
+  }
+  if first_row_index != num_rows {
+    return Err(general_err!(
+      "Data pages contain {} rows, but row group has {} rows",
+      first_row_index,
+      num_rows
+    ));
+  }
+  Ok(result)
+}
+
+/// Layout information of a column chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnChunkLayout {
+  column_path: ColumnPath,
+  compression: Compression,
+  pages: Vec<PageLayout>
+}
+
+impl ColumnChunkLayout {
+  /// Creates new column chunk layout from a list of pages.
+  pub fn new(
+    column_path: ColumnPath,
+    compression: Compression,
+    pages: Vec<PageLayout>
+  ) -> Self {
+    Self { column_path, compression, pages }
+  }
+
+  /// Returns column path of this column chunk.
+  pub fn column_path(&self) -> &ColumnPath {
+    &self.column_path
+  }
+
+  /// Returns compression codec of this column chunk.
+  pub fn compression(&self) -> Compression {
+    self.compression
+  }
+
+  /// Returns all pages of this column chunk in file order.
+  pub fn pages(&self) -> &[PageLayout] {
+    &self.pages
+  }
+
+  /// Returns number of data pages in this column chunk.
+  pub fn num_data_pages(&self) -> usize {
+    self.pages.iter().filter(|p| p.is_data_page()).count()
+  }
+
+  /// Returns total size of pages (including page headers) as stored in the file.
+  pub fn compressed_size(&self) -> u64 {
+    self.pages.iter().map(|p| (p.header_size + p.compressed_size) as u64).sum()
+  }
+
+  /// Returns total size of pages (including page headers) after decompression.
+  pub fn uncompressed_size(&self) -> u64 {
+    self.pages.iter().map(|p| (p.header_size + p.uncompressed_size) as u64).sum()
+  }
+
+  /// Returns ratio of uncompressed size to compressed size, or `None` when column
+  /// chunk is empty.
+  pub fn compression_ratio(&self) -> Option<f64> {
+    let compressed = self.compressed_size();
+    if compressed == 0 {
+      None
+    } else {
+      Some(self.uncompressed_size() as f64 / compressed as f64)
+    }
+  }
+
+  /// Returns histogram of compressed sizes of data pages.
+  pub fn page_size_histogram(&self) -> Histogram {
+    let mut histogram = Histogram::new();
+    for page in self.pages.iter().filter(|p| p.is_data_page()) {
+      histogram.add(page.compressed_size as u64);
+    }
+    histogram
+  }
+
+  /// Returns histogram of number of values in data pages.
+  pub fn values_per_page_histogram(&self) -> Histogram {
+    let mut histogram = Histogram::new();
+    for page in self.pages.iter().filter(|p| p.is_data_page()) {
+      histogram.add(page.num_values as u64);
+    }
+    histogram
+  }
+
+  /// Returns number of data pages per value encoding, sorted by encoding name.
+  pub fn encoding_mix(&self) -> Vec<(Encoding, usize)> {
+    let mut counts: HashMap<Encoding, usize> = HashMap::new();
+    for page in self.pages.iter().filter(|p| p.is_data_page()) {
+      if let Some(encoding) = page.encoding {
+        *counts.entry(encoding).or_insert(0) += 1;
+      }
+    }
+    let mut result: Vec<(Encoding, usize)> = counts.into_iter().collect();
+    result.sort_by_key(|&(encoding, _)| encoding.to_string());
+    result
+  }
+}
+
+/// Layout information of a row group.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowGroupLayout {
+  num_rows: i64,
+  columns: Vec<ColumnChunkLayout>
+}
+
+impl RowGroupLayout {
+  /// Creates new row group layout.
+  pub fn new(num_rows: i64, columns: Vec<ColumnChunkLayout>) -> Self {
+    Self { num_rows, columns }
+  }
+
+  /// Returns number of rows in this row group.
+  pub fn num_rows(&self) -> i64 {
+    self.num_rows
+  }
+
+  /// Returns layout of all column chunks in this row group.
+  pub fn columns(&self) -> &[ColumnChunkLayout] {
+    &self.columns
+  }
+}
+
+/// Layout information of a Parquet file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileLayout {
+  row_groups: Vec<RowGroupLayout>
+}
+
+impl FileLayout {
+  /// Creates new file layout.
+  pub fn new(row_groups: Vec<RowGroupLayout>) -> Self {
+    Self { row_groups }
+  }
+
+  /// Returns layout of all row groups in this file.
+  pub fn row_groups(&self) -> &[RowGroupLayout] {
+    &self.row_groups
+  }
+
+  /// Returns histogram of compressed data page sizes for the `i`th column across all
+  /// row groups.
+  pub fn column_page_size_histogram(&self, i: usize) -> Histogram {
+    let mut histogram = Histogram::new();
+    for row_group in &self.row_groups {
+      histogram.merge(&row_group.columns[i].page_size_histogram());
+    }
+    histogram
+  }
+
+  /// Returns histogram of number of values per data page for the `i`th column across
+  /// all row groups.
+  pub fn column_values_per_page_histogram(&self, i: usize) -> Histogram {
+    let mut histogram = Histogram::new();
+    for row_group in &self.row_groups {
+      histogram.merge(&row_group.columns[i].values_per_page_histogram());
+    }
+    histogram
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use file::page_index::PageLocation;
+  use file::reader::SerializedFileReader;
+  use util::test_common::get_test_file;
+
+  #[test]
+  fn test_histogram() {
+    let mut histogram = Histogram::new();
+    assert_eq!(histogram.min(), None);
+    assert_eq!(histogram.mean(), None);
+    assert_eq!(histogram.to_string(), "<empty>");
+
+    for value in &[0, 1, 3, 4, 7, 100] {
+      histogram.add(*value);
+    }
+    assert_eq!(histogram.num_values(), 6);
+    assert_eq!(histogram.sum(), 115);
+    assert_eq!(histogram.min(), Some(0));
+    assert_eq!(histogram.max(), Some(100));
+    assert_eq!(
+      histogram.buckets(),
+      vec![(0, 1, 1), (1, 2, 1), (2, 4, 1), (4, 8, 2), (64, 128, 1)]
+    );
+
+    let mut other = Histogram::new();
+    other.add(5);
+    histogram.merge(&other);
+    assert_eq!(histogram.num_values(), 7);
+    assert_eq!(histogram.buckets()[3], (4, 8, 3));
+  }
+
+  #[test]
+  fn test_file_layout() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let layout = reader.layout().unwrap();
+    assert_eq!(layout.row_groups().len(), 1);
+
+    let row_group = &layout.row_groups()[0];
+    assert_eq!(row_group.num_rows(), 8);
+    assert_eq!(row_group.columns().len(), 11);
+
+    let column = &row_group.columns()[0];
+    assert_eq!(column.column_path().string(), "id");
+    assert_eq!(column.compression(), Compression::UNCOMPRESSED);
+    assert_eq!(column.pages().len(), 2);
+    assert_eq!(column.num_data_pages(), 1);
+
+    let dict_page = &column.pages()[0];
+    assert_eq!(dict_page.page_type, PageType::DICTIONARY_PAGE);
+    assert_eq!(dict_page.uncompressed_size, 32);
+    assert_eq!(dict_page.num_values, 8);
+    assert_eq!(dict_page.encoding, Some(Encoding::PLAIN_DICTIONARY));
+
+    let data_page = &column.pages()[1];
+    assert_eq!(data_page.page_type, PageType::DATA_PAGE);
+    assert_eq!(data_page.uncompressed_size, 11);
+    assert_eq!(data_page.num_values, 8);
+    assert_eq!(
+      data_page.offset,
+      dict_page.offset + (dict_page.header_size + dict_page.compressed_size) as u64
+    );
+
+    assert_eq!(column.encoding_mix(), vec![(Encoding::PLAIN_DICTIONARY, 1)]);
+    assert_eq!(column.compression_ratio(), Some(1.0));
+    assert_eq!(layout.column_values_per_page_histogram(0).buckets(), vec![(8, 16, 1)]);
+  }
+
+  #[test]
+  fn test_page_row_ranges_without_offset_index() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let layout = reader.layout().unwrap();
+    let pages = layout.row_groups()[0].columns()[0].pages();
+
+    let ranges = page_row_ranges(pages, None, 0, 8).unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].page, pages[1]);
+    assert_eq!(ranges[0].row_range(), 0..8);
+
+    assert!(page_row_ranges(pages, None, 0, 10).is_err());
+    assert!(page_row_ranges(pages, None, 1, 8).is_err());
+  }
+
+  #[test]
+  fn test_page_row_ranges_with_offset_index() {
+    let page = |offset, num_values| PageLayout {
+      page_type: PageType::DATA_PAGE,
+      offset: offset,
+      header_size: 10,
+      compressed_size: 90,
+      uncompressed_size: 90,
+      num_values: num_values,
+      encoding: Some(Encoding::PLAIN),
+      crc: None,
+      num_rows: None
+    };
+    let location = |offset, first_row_index| PageLocation {
+      offset: offset,
+      compressed_page_size: 100,
+      first_row_index: first_row_index
+    };
+    let pages = vec![page(4, 12), page(104, 7)];
+
+    let offset_index = OffsetIndex::new(vec![location(4, 0), location(104, 3)]);
+    let ranges = page_row_ranges(&pages, Some(&offset_index), 1, 5).unwrap();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[0].page, pages[0]);
+    assert_eq!(ranges[0].row_range(), 0..3);
+    assert_eq!(ranges[1].page, pages[1]);
+    assert_eq!(ranges[1].row_range(), 3..5);
+
+    let offset_index = OffsetIndex::new(vec![location(4, 0)]);
+    assert!(page_row_ranges(&pages, Some(&offset_index), 1, 5).is_err());
+    let offset_index = OffsetIndex::new(vec![location(4, 0), location(100, 3)]);
+    assert!(page_row_ranges(&pages, Some(&offset_index), 1, 5).is_err());
+    let offset_index = OffsetIndex::new(vec![location(4, 0), location(104, 6)]);
+    assert!(page_row_ranges(&pages, Some(&offset_index), 1, 5).is_err());
+  }
+}
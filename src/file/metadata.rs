@@ -22,21 +22,41 @@
 //! [`ParquetMetaData`] contains [`FileMetaData`] and zero or more [`RowGroupMetaData`]
 //! for each row group.
 //!
-//! [`FileMetaData`] includes file version, application specific metadata.
+//! [`FileMetaData`] includes file version, application specific metadata and
+//! [`KeyValue`] pairs attached by the writer.
 //!
 //! Each [`RowGroupMetaData`] contains information about row group and one or more
 //! [`ColumnChunkMetaData`] for each column chunk.
 //!
 //! [`ColumnChunkMetaData`] has information about column chunk (primitive leaf column),
-//! including encoding/compression, number of values, etc.
+//! including encoding/compression, number of values, [`PageEncodingStats`] and
+//! key/value pairs attached to the column chunk, etc.
 
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
-use basic::{Compression, Encoding, Type};
+use basic::{Compression, Encoding, PageType, Type};
+use bloom_filter::{BLOOM_FILTER_LENGTH_KEY, BLOOM_FILTER_OFFSET_KEY};
 use errors::{ParquetError, Result};
+use file::page_index::{
+  ColumnIndex, OffsetIndex, COLUMN_INDEX_LENGTH_KEY, COLUMN_INDEX_OFFSET_KEY,
+  OFFSET_INDEX_LENGTH_KEY, OFFSET_INDEX_OFFSET_KEY
+};
+use file::statistics::{self, Statistics};
 use schema::types::{ColumnDescriptor, ColumnDescPtr, ColumnPath};
 use schema::types::{SchemaDescriptor, SchemaDescPtr, Type as SchemaType, TypePtr};
 use parquet_format::{ColumnChunk, ColumnMetaData, RowGroup};
+use parquet_format::KeyValue as TKeyValue;
+use parquet_format::PageEncodingStats as TPageEncodingStats;
+
+/// Key of the column chunk key/value metadata entry with CRC32 checksum of all pages of
+/// the column chunk, including page headers. The checksum is not part of the Parquet
+/// format, hence the key is prefixed with the name of this crate rather than `parquet.`.
+pub(crate) const CHUNK_CHECKSUM_KEY: &str = "parquet-rs.column_chunk.crc32";
+
+/// Prefix of keys of file key/value metadata entries with writer information set by the
+/// application that wrote the file, see `WriterPropertiesBuilder::with_writer_info`.
+pub const WRITER_INFO_KEY_PREFIX: &str = "writer.info.";
 
 /// Reference counted pointer for [`ParquetMetaData`].
 pub type ParquetMetaDataPtr = Rc<ParquetMetaData>;
@@ -67,6 +87,12 @@ impl ParquetMetaData {
     self.row_groups.len()
   }
 
+  /// Returns `true` if this file has no rows, i.e. it has no row groups or all row
+  /// groups are empty.
+  pub fn is_empty(&self) -> bool {
+    self.row_groups.iter().all(|row_group| row_group.is_empty())
+  }
+
   /// Returns row group metadata for `i`th position.
   /// Position should be less than number of row groups `num_row_groups`.
   pub fn row_group(&self, i: usize) -> RowGroupMetaDataPtr {
@@ -77,6 +103,20 @@ impl ParquetMetaData {
   pub fn row_groups(&self) -> &[RowGroupMetaDataPtr] {
     &self.row_groups.as_slice()
   }
+
+  /// Returns distinct compression codecs of all column chunks in this file, in the
+  /// order they first appear. Column chunks can be compressed with different codecs.
+  pub fn compressions(&self) -> Vec<Compression> {
+    let mut compressions = Vec::new();
+    for row_group in &self.row_groups {
+      for compression in row_group.compressions() {
+        if !compressions.contains(&compression) {
+          compressions.push(compression);
+        }
+      }
+    }
+    compressions
+  }
 }
 
 /// Reference counted pointer for [`FileMetaData`].
@@ -87,6 +127,7 @@ pub struct FileMetaData {
   version: i32,
   num_rows: i64,
   created_by: Option<String>,
+  key_value_metadata: Option<Vec<KeyValue>>,
   schema: TypePtr,
   schema_descr: SchemaDescPtr
 }
@@ -97,6 +138,7 @@ impl FileMetaData {
     version: i32,
     num_rows: i64,
     created_by: Option<String>,
+    key_value_metadata: Option<Vec<KeyValue>>,
     schema: TypePtr,
     schema_descr: SchemaDescPtr
   ) -> Self {
@@ -104,6 +146,7 @@ impl FileMetaData {
       version,
       num_rows,
       created_by,
+      key_value_metadata,
       schema,
       schema_descr
     }
@@ -119,6 +162,11 @@ impl FileMetaData {
     self.num_rows
   }
 
+  /// Returns `true` if the file has no rows.
+  pub fn is_empty(&self) -> bool {
+    self.num_rows == 0
+  }
+
   /// String message for application that wrote this file.
   ///
   /// This should have the following format:
@@ -131,6 +179,29 @@ impl FileMetaData {
     &self.created_by
   }
 
+  /// Returns key/value metadata attached to this file by the writer, if any.
+  pub fn key_value_metadata(&self) -> &Option<Vec<KeyValue>> {
+    &self.key_value_metadata
+  }
+
+  /// Returns writer information set by the application that wrote this file, i.e.
+  /// key/value metadata entries with keys prefixed with `writer.info.`, keyed by the
+  /// rest of the key. Entries without value are skipped.
+  pub fn writer_info(&self) -> BTreeMap<String, String> {
+    let mut writer_info = BTreeMap::new();
+    if let Some(ref key_values) = self.key_value_metadata {
+      for kv in key_values {
+        if kv.key().starts_with(WRITER_INFO_KEY_PREFIX) {
+          if let Some(value) = kv.value() {
+            let key = &kv.key()[WRITER_INFO_KEY_PREFIX.len()..];
+            writer_info.insert(key.to_owned(), value.to_owned());
+          }
+        }
+      }
+    }
+    writer_info
+  }
+
   /// Returns Parquet ['Type`] that describes schema in this file.
   pub fn schema(&self) -> &SchemaType {
     self.schema.as_ref()
@@ -147,6 +218,88 @@ impl FileMetaData {
   }
 }
 
+/// Application specific key/value pair stored in the file metadata.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyValue {
+  key: String,
+  value: Option<String>
+}
+
+impl KeyValue {
+  /// Creates new key/value pair, value is optional.
+  pub fn new(key: String, value: Option<String>) -> Self {
+    KeyValue { key, value }
+  }
+
+  /// Returns key.
+  pub fn key(&self) -> &str {
+    &self.key
+  }
+
+  /// Returns value, if set.
+  pub fn value(&self) -> Option<&str> {
+    self.value.as_ref().map(|v| v.as_str())
+  }
+
+  /// Method to convert from Thrift.
+  pub fn from_thrift(kv: TKeyValue) -> Self {
+    KeyValue { key: kv.key, value: kv.value }
+  }
+
+  /// Method to convert to Thrift.
+  pub fn to_thrift(&self) -> TKeyValue {
+    TKeyValue { key: self.key.clone(), value: self.value.clone() }
+  }
+}
+
+/// Number of pages of a page type and encoding in a column chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageEncodingStats {
+  page_type: PageType,
+  encoding: Encoding,
+  count: i32
+}
+
+impl PageEncodingStats {
+  /// Creates new page encoding stats.
+  pub fn new(page_type: PageType, encoding: Encoding, count: i32) -> Self {
+    PageEncodingStats { page_type, encoding, count }
+  }
+
+  /// Returns page type.
+  pub fn page_type(&self) -> PageType {
+    self.page_type
+  }
+
+  /// Returns encoding of pages.
+  pub fn encoding(&self) -> Encoding {
+    self.encoding
+  }
+
+  /// Returns number of pages of this type and encoding.
+  pub fn count(&self) -> i32 {
+    self.count
+  }
+
+  /// Method to convert from Thrift.
+  pub fn from_thrift(stats: TPageEncodingStats) -> Self {
+    PageEncodingStats {
+      page_type: PageType::from(stats.page_type),
+      encoding: Encoding::from(stats.encoding),
+      count: stats.count
+    }
+  }
+
+  /// Method to convert to Thrift.
+  pub fn to_thrift(&self) -> TPageEncodingStats {
+    TPageEncodingStats {
+      page_type: self.page_type.into(),
+      encoding: self.encoding.into(),
+      count: self.count
+    }
+  }
+}
+
 /// Reference counted pointer for [`RowGroupMetaData`].
 pub type RowGroupMetaDataPtr = Rc<RowGroupMetaData>;
 
@@ -174,11 +327,28 @@ impl RowGroupMetaData {
     &self.columns
   }
 
+  /// Returns distinct compression codecs of column chunks in this row group, in the
+  /// order of columns.
+  pub fn compressions(&self) -> Vec<Compression> {
+    let mut compressions = Vec::new();
+    for column in &self.columns {
+      if !compressions.contains(&column.compression()) {
+        compressions.push(column.compression());
+      }
+    }
+    compressions
+  }
+
   /// Number of rows in this row group.
   pub fn num_rows(&self) -> i64 {
     self.num_rows
   }
 
+  /// Returns `true` if this row group has no rows.
+  pub fn is_empty(&self) -> bool {
+    self.num_rows == 0
+  }
+
   /// Total byte size of all uncompressed column data in this row group.
   pub fn total_byte_size(&self) -> i64 {
     self.total_byte_size
@@ -199,7 +369,13 @@ impl RowGroupMetaData {
     schema_descr: SchemaDescPtr,
     mut rg: RowGroup
   ) -> Result<RowGroupMetaData> {
-    assert_eq!(schema_descr.num_columns(), rg.columns.len());
+    if schema_descr.num_columns() != rg.columns.len() {
+      return Err(general_err!(
+        "Column length mismatch: {} != {}",
+        schema_descr.num_columns(),
+        rg.columns.len()
+      ));
+    }
     let total_byte_size = rg.total_byte_size;
     let num_rows = rg.num_rows;
     let mut columns = vec![];
@@ -214,6 +390,79 @@ impl RowGroupMetaData {
       schema_descr
     })
   }
+
+  /// Method to convert to Thrift.
+  pub fn to_thrift(&self) -> RowGroup {
+    RowGroup {
+      columns: self.columns.iter().map(|c| c.to_thrift()).collect(),
+      total_byte_size: self.total_byte_size,
+      num_rows: self.num_rows,
+      sorting_columns: None
+    }
+  }
+
+  /// Returns builder for row group metadata of the schema `schema_descr`.
+  pub fn builder(schema_descr: SchemaDescPtr) -> RowGroupMetaDataBuilder {
+    RowGroupMetaDataBuilder::new(schema_descr)
+  }
+}
+
+/// A builder for row group metadata, used when writing row groups.
+/// Number of rows and total byte size are set to `0` by default.
+pub struct RowGroupMetaDataBuilder {
+  columns: Vec<ColumnChunkMetaDataPtr>,
+  schema_descr: SchemaDescPtr,
+  num_rows: i64,
+  total_byte_size: i64
+}
+
+impl RowGroupMetaDataBuilder {
+  /// Creates new row group metadata builder for the schema `schema_descr`.
+  pub fn new(schema_descr: SchemaDescPtr) -> Self {
+    Self {
+      columns: Vec::with_capacity(schema_descr.num_columns()),
+      schema_descr: schema_descr,
+      num_rows: 0,
+      total_byte_size: 0
+    }
+  }
+
+  /// Sets column chunk metadata for all columns, in schema order.
+  pub fn with_column_metadata(mut self, columns: Vec<ColumnChunkMetaDataPtr>) -> Self {
+    self.columns = columns;
+    self
+  }
+
+  /// Sets number of rows in this row group.
+  pub fn with_num_rows(mut self, num_rows: i64) -> Self {
+    self.num_rows = num_rows;
+    self
+  }
+
+  /// Sets total size in bytes of all uncompressed column data in this row group.
+  pub fn with_total_byte_size(mut self, total_byte_size: i64) -> Self {
+    self.total_byte_size = total_byte_size;
+    self
+  }
+
+  /// Builds row group metadata.
+  /// Returns error if number of column chunks does not match number of columns in
+  /// the schema.
+  pub fn build(self) -> Result<RowGroupMetaData> {
+    if self.schema_descr.num_columns() != self.columns.len() {
+      return Err(general_err!(
+        "Column length mismatch: {} != {}",
+        self.schema_descr.num_columns(),
+        self.columns.len()
+      ));
+    }
+    Ok(RowGroupMetaData {
+      columns: self.columns,
+      num_rows: self.num_rows,
+      total_byte_size: self.total_byte_size,
+      schema_descr: self.schema_descr
+    })
+  }
 }
 
 /// Reference counted pointer for [`ColumnChunkMetaData`].
@@ -233,7 +482,19 @@ pub struct ColumnChunkMetaData {
   total_uncompressed_size: i64,
   data_page_offset: i64,
   index_page_offset: Option<i64>,
-  dictionary_page_offset: Option<i64>
+  dictionary_page_offset: Option<i64>,
+  statistics: Option<Statistics>,
+  bloom_filter_offset: Option<i64>,
+  bloom_filter_length: Option<i32>,
+  column_index_offset: Option<i64>,
+  column_index_length: Option<i32>,
+  offset_index_offset: Option<i64>,
+  offset_index_length: Option<i32>,
+  encoding_stats: Option<Vec<PageEncodingStats>>,
+  checksum: Option<u32>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  column_index: Option<ColumnIndex>,
+  offset_index: Option<OffsetIndex>
 }
 
 /// Represents common operations for a column chunk.
@@ -316,15 +577,83 @@ impl ColumnChunkMetaData {
     self.dictionary_page_offset.is_some()
   }
 
-  /// TODO: add statistics
-
   /// Returns the offset for the dictionary page, if any.
   pub fn dictionary_page_offset(&self) -> Option<i64> {
     self.dictionary_page_offset
   }
 
+  /// Returns statistics that are set for this column chunk,
+  /// or `None` if no statistics are available.
+  pub fn statistics(&self) -> Option<&Statistics> {
+    self.statistics.as_ref()
+  }
+
+  /// Returns the offset for the Bloom filter bitset, if any.
+  pub fn bloom_filter_offset(&self) -> Option<i64> {
+    self.bloom_filter_offset
+  }
+
+  /// Returns the length in bytes of the Bloom filter bitset, if any.
+  pub fn bloom_filter_length(&self) -> Option<i32> {
+    self.bloom_filter_length
+  }
+
+  /// Returns the offset for the column index, if any.
+  pub fn column_index_offset(&self) -> Option<i64> {
+    self.column_index_offset
+  }
+
+  /// Returns the length in bytes of the column index, if any.
+  pub fn column_index_length(&self) -> Option<i32> {
+    self.column_index_length
+  }
+
+  /// Returns the offset for the offset index, if any.
+  pub fn offset_index_offset(&self) -> Option<i64> {
+    self.offset_index_offset
+  }
+
+  /// Returns the length in bytes of the offset index, if any.
+  pub fn offset_index_length(&self) -> Option<i32> {
+    self.offset_index_length
+  }
+
+  /// Returns number of pages of each page type and encoding, if set by the writer.
+  pub fn encoding_stats(&self) -> Option<&Vec<PageEncodingStats>> {
+    self.encoding_stats.as_ref()
+  }
+
+  /// Returns CRC32 checksum of all pages of this column chunk as stored in the file,
+  /// including page headers, if set by the writer.
+  pub fn checksum(&self) -> Option<u32> {
+    self.checksum
+  }
+
+  /// Returns key/value pairs attached to this column chunk, if any.
+  ///
+  /// Does not include pairs that store locations of bloom filter and page indexes, and
+  /// checksum of the column chunk, which are returned by their own methods.
+  pub fn key_value_metadata(&self) -> Option<&Vec<KeyValue>> {
+    self.key_value_metadata.as_ref()
+  }
+
+  /// Returns column index built by the writer, which is not yet written into the file.
+  pub fn column_index(&self) -> Option<&ColumnIndex> {
+    self.column_index.as_ref()
+  }
+
+  /// Returns offset index built by the writer, which is not yet written into the file.
+  pub fn offset_index(&self) -> Option<&OffsetIndex> {
+    self.offset_index.as_ref()
+  }
+
+  /// Returns builder for column chunk metadata of the column `column_descr`.
+  pub fn builder(column_descr: ColumnDescPtr) -> ColumnChunkMetaDataBuilder {
+    ColumnChunkMetaDataBuilder::new(column_descr)
+  }
+
   /// Method to convert from Thrift.
-  fn from_thrift(column_descr: ColumnDescPtr, cc: ColumnChunk) -> Result<Self> {
+  pub fn from_thrift(column_descr: ColumnDescPtr, cc: ColumnChunk) -> Result<Self> {
     if cc.meta_data.is_none() {
       return Err(general_err!("Expected to have column metadata"));
     }
@@ -341,6 +670,46 @@ impl ColumnChunkMetaData {
     let data_page_offset = col_metadata.data_page_offset;
     let index_page_offset = col_metadata.index_page_offset;
     let dictionary_page_offset = col_metadata.dictionary_page_offset;
+    let statistics = statistics::from_thrift(column_type, col_metadata.statistics);
+    let mut bloom_filter_offset: Option<i64> = None;
+    let mut bloom_filter_length: Option<i32> = None;
+    let mut column_index_offset: Option<i64> = None;
+    let mut column_index_length: Option<i32> = None;
+    let mut offset_index_offset: Option<i64> = None;
+    let mut offset_index_length: Option<i32> = None;
+    let mut checksum: Option<u32> = None;
+    let mut key_values = Vec::new();
+    for kv in col_metadata.key_value_metadata.unwrap_or(vec![]) {
+      let value = kv.value.as_ref().map(|v| v.as_str()).unwrap_or("");
+      match kv.key.as_str() {
+        BLOOM_FILTER_OFFSET_KEY => {
+          bloom_filter_offset = Some(parse_location(value, "Bloom filter offset")?)
+        },
+        BLOOM_FILTER_LENGTH_KEY => {
+          bloom_filter_length = Some(parse_location(value, "Bloom filter length")?)
+        },
+        COLUMN_INDEX_OFFSET_KEY => {
+          column_index_offset = Some(parse_location(value, "column index offset")?)
+        },
+        COLUMN_INDEX_LENGTH_KEY => {
+          column_index_length = Some(parse_location(value, "column index length")?)
+        },
+        OFFSET_INDEX_OFFSET_KEY => {
+          offset_index_offset = Some(parse_location(value, "offset index offset")?)
+        },
+        OFFSET_INDEX_LENGTH_KEY => {
+          offset_index_length = Some(parse_location(value, "offset index length")?)
+        },
+        CHUNK_CHECKSUM_KEY => {
+          checksum = Some(parse_location(value, "column chunk checksum")?)
+        },
+        _ => key_values.push(KeyValue::from_thrift(kv.clone()))
+      }
+    }
+    let key_value_metadata = if key_values.is_empty() { None } else { Some(key_values) };
+    let encoding_stats = col_metadata.encoding_stats.map(|stats| {
+      stats.into_iter().map(PageEncodingStats::from_thrift).collect()
+    });
     let result = ColumnChunkMetaData {
       column_type,
       column_path,
@@ -354,8 +723,492 @@ impl ColumnChunkMetaData {
       total_uncompressed_size,
       data_page_offset,
       index_page_offset,
-      dictionary_page_offset
+      dictionary_page_offset,
+      statistics,
+      bloom_filter_offset,
+      bloom_filter_length,
+      column_index_offset,
+      column_index_length,
+      offset_index_offset,
+      offset_index_length,
+      encoding_stats,
+      checksum,
+      key_value_metadata,
+      column_index: None,
+      offset_index: None
     };
     Ok(result)
   }
+
+  /// Method to convert to Thrift.
+  pub fn to_thrift(&self) -> ColumnChunk {
+    // Locations of structures that are not part of the supported Thrift definitions
+    let locations = vec![
+      (BLOOM_FILTER_OFFSET_KEY, BLOOM_FILTER_LENGTH_KEY,
+        self.bloom_filter_offset, self.bloom_filter_length),
+      (COLUMN_INDEX_OFFSET_KEY, COLUMN_INDEX_LENGTH_KEY,
+        self.column_index_offset, self.column_index_length),
+      (OFFSET_INDEX_OFFSET_KEY, OFFSET_INDEX_LENGTH_KEY,
+        self.offset_index_offset, self.offset_index_length)
+    ];
+    let mut key_values = Vec::new();
+    for (offset_key, length_key, offset, length) in locations {
+      if let (Some(offset), Some(length)) = (offset, length) {
+        key_values.push(TKeyValue {
+          key: offset_key.to_owned(),
+          value: Some(offset.to_string())
+        });
+        key_values.push(TKeyValue {
+          key: length_key.to_owned(),
+          value: Some(length.to_string())
+        });
+      }
+    }
+    if let Some(checksum) = self.checksum {
+      key_values.push(TKeyValue {
+        key: CHUNK_CHECKSUM_KEY.to_owned(),
+        value: Some(checksum.to_string())
+      });
+    }
+    if let Some(ref custom) = self.key_value_metadata {
+      key_values.extend(custom.iter().map(|kv| kv.to_thrift()));
+    }
+    let key_value_metadata = if key_values.is_empty() { None } else { Some(key_values) };
+    let column_metadata = ColumnMetaData {
+      type_: self.column_type.into(),
+      encodings: self.encodings.iter().map(|&e| e.into()).collect(),
+      path_in_schema: Vec::from(self.column_path.parts()),
+      codec: self.compression.into(),
+      num_values: self.num_values,
+      total_uncompressed_size: self.total_uncompressed_size,
+      total_compressed_size: self.total_compressed_size,
+      key_value_metadata: key_value_metadata,
+      data_page_offset: self.data_page_offset,
+      index_page_offset: self.index_page_offset,
+      dictionary_page_offset: self.dictionary_page_offset,
+      statistics: statistics::to_thrift(self.statistics.as_ref()),
+      encoding_stats: self.encoding_stats.as_ref().map(|stats| {
+        stats.iter().map(|s| s.to_thrift()).collect()
+      })
+    };
+
+    ColumnChunk {
+      file_path: self.file_path.clone(),
+      file_offset: self.file_offset,
+      meta_data: Some(column_metadata)
+    }
+  }
+}
+
+/// A builder for column chunk metadata, used when writing column chunks.
+/// All attributes are optional, except column descriptor; compression is set to
+/// `UNCOMPRESSED`, sizes and offsets are set to `0` by default.
+pub struct ColumnChunkMetaDataBuilder {
+  column_descr: ColumnDescPtr,
+  encodings: Vec<Encoding>,
+  file_path: Option<String>,
+  file_offset: i64,
+  num_values: i64,
+  compression: Compression,
+  total_compressed_size: i64,
+  total_uncompressed_size: i64,
+  data_page_offset: i64,
+  index_page_offset: Option<i64>,
+  dictionary_page_offset: Option<i64>,
+  statistics: Option<Statistics>,
+  bloom_filter_offset: Option<i64>,
+  bloom_filter_length: Option<i32>,
+  column_index_offset: Option<i64>,
+  column_index_length: Option<i32>,
+  offset_index_offset: Option<i64>,
+  offset_index_length: Option<i32>,
+  encoding_stats: Option<Vec<PageEncodingStats>>,
+  checksum: Option<u32>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  column_index: Option<ColumnIndex>,
+  offset_index: Option<OffsetIndex>
+}
+
+impl ColumnChunkMetaDataBuilder {
+  /// Creates new column chunk metadata builder for the column `column_descr`.
+  pub fn new(column_descr: ColumnDescPtr) -> Self {
+    Self {
+      column_descr: column_descr,
+      encodings: Vec::new(),
+      file_path: None,
+      file_offset: 0,
+      num_values: 0,
+      compression: Compression::UNCOMPRESSED,
+      total_compressed_size: 0,
+      total_uncompressed_size: 0,
+      data_page_offset: 0,
+      index_page_offset: None,
+      dictionary_page_offset: None,
+      statistics: None,
+      bloom_filter_offset: None,
+      bloom_filter_length: None,
+      column_index_offset: None,
+      column_index_length: None,
+      offset_index_offset: None,
+      offset_index_length: None,
+      encoding_stats: None,
+      checksum: None,
+      key_value_metadata: None,
+      column_index: None,
+      offset_index: None
+    }
+  }
+
+  /// Creates new column chunk metadata builder with all attributes of `metadata`.
+  pub fn from_metadata(metadata: &ColumnChunkMetaData) -> Self {
+    Self {
+      column_descr: metadata.column_descr.clone(),
+      encodings: metadata.encodings.clone(),
+      file_path: metadata.file_path.clone(),
+      file_offset: metadata.file_offset,
+      num_values: metadata.num_values,
+      compression: metadata.compression,
+      total_compressed_size: metadata.total_compressed_size,
+      total_uncompressed_size: metadata.total_uncompressed_size,
+      data_page_offset: metadata.data_page_offset,
+      index_page_offset: metadata.index_page_offset,
+      dictionary_page_offset: metadata.dictionary_page_offset,
+      statistics: metadata.statistics.clone(),
+      bloom_filter_offset: metadata.bloom_filter_offset,
+      bloom_filter_length: metadata.bloom_filter_length,
+      column_index_offset: metadata.column_index_offset,
+      column_index_length: metadata.column_index_length,
+      offset_index_offset: metadata.offset_index_offset,
+      offset_index_length: metadata.offset_index_length,
+      encoding_stats: metadata.encoding_stats.clone(),
+      checksum: metadata.checksum,
+      key_value_metadata: metadata.key_value_metadata.clone(),
+      column_index: metadata.column_index.clone(),
+      offset_index: metadata.offset_index.clone()
+    }
+  }
+
+  /// Sets list of encodings used in this column chunk.
+  pub fn with_encodings(mut self, encodings: Vec<Encoding>) -> Self {
+    self.encodings = encodings;
+    self
+  }
+
+  /// Sets path of the file where the column chunk is stored.
+  pub fn with_file_path(mut self, file_path: String) -> Self {
+    self.file_path = Some(file_path);
+    self
+  }
+
+  /// Sets byte offset of the column chunk metadata in the file.
+  pub fn with_file_offset(mut self, file_offset: i64) -> Self {
+    self.file_offset = file_offset;
+    self
+  }
+
+  /// Sets total number of values (including nulls) in this column chunk.
+  pub fn with_num_values(mut self, num_values: i64) -> Self {
+    self.num_values = num_values;
+    self
+  }
+
+  /// Sets compression codec of this column chunk.
+  pub fn with_compression(mut self, compression: Compression) -> Self {
+    self.compression = compression;
+    self
+  }
+
+  /// Sets total compressed size of all pages, including page headers.
+  pub fn with_total_compressed_size(mut self, size: i64) -> Self {
+    self.total_compressed_size = size;
+    self
+  }
+
+  /// Sets total uncompressed size of all pages, including page headers.
+  pub fn with_total_uncompressed_size(mut self, size: i64) -> Self {
+    self.total_uncompressed_size = size;
+    self
+  }
+
+  /// Sets byte offset of the first data page.
+  pub fn with_data_page_offset(mut self, offset: i64) -> Self {
+    self.data_page_offset = offset;
+    self
+  }
+
+  /// Sets byte offset of the index page, if any.
+  pub fn with_index_page_offset(mut self, offset: Option<i64>) -> Self {
+    self.index_page_offset = offset;
+    self
+  }
+
+  /// Sets byte offset of the dictionary page, if any.
+  pub fn with_dictionary_page_offset(mut self, offset: Option<i64>) -> Self {
+    self.dictionary_page_offset = offset;
+    self
+  }
+
+  /// Sets statistics of the column chunk, if any.
+  pub fn with_statistics(mut self, statistics: Option<Statistics>) -> Self {
+    self.statistics = statistics;
+    self
+  }
+
+  /// Sets byte offset of the Bloom filter bitset, if any.
+  pub fn with_bloom_filter_offset(mut self, offset: Option<i64>) -> Self {
+    self.bloom_filter_offset = offset;
+    self
+  }
+
+  /// Sets length in bytes of the Bloom filter bitset, if any.
+  pub fn with_bloom_filter_length(mut self, length: Option<i32>) -> Self {
+    self.bloom_filter_length = length;
+    self
+  }
+
+  /// Sets byte offset of the column index, if any.
+  pub fn with_column_index_offset(mut self, offset: Option<i64>) -> Self {
+    self.column_index_offset = offset;
+    self
+  }
+
+  /// Sets length in bytes of the column index, if any.
+  pub fn with_column_index_length(mut self, length: Option<i32>) -> Self {
+    self.column_index_length = length;
+    self
+  }
+
+  /// Sets byte offset of the offset index, if any.
+  pub fn with_offset_index_offset(mut self, offset: Option<i64>) -> Self {
+    self.offset_index_offset = offset;
+    self
+  }
+
+  /// Sets length in bytes of the offset index, if any.
+  pub fn with_offset_index_length(mut self, length: Option<i32>) -> Self {
+    self.offset_index_length = length;
+    self
+  }
+
+  /// Sets column index to be written by the file writer, if any.
+  pub fn with_column_index(mut self, column_index: Option<ColumnIndex>) -> Self {
+    self.column_index = column_index;
+    self
+  }
+
+  /// Sets number of pages of each page type and encoding.
+  pub fn with_encoding_stats(mut self, stats: Option<Vec<PageEncodingStats>>) -> Self {
+    self.encoding_stats = stats;
+    self
+  }
+
+  /// Sets CRC32 checksum of all pages of this column chunk, if any.
+  pub fn with_checksum(mut self, checksum: Option<u32>) -> Self {
+    self.checksum = checksum;
+    self
+  }
+
+  /// Sets key/value pairs attached to this column chunk.
+  pub fn with_key_value_metadata(mut self, key_values: Option<Vec<KeyValue>>) -> Self {
+    self.key_value_metadata = key_values;
+    self
+  }
+
+  /// Sets offset index to be written by the file writer, if any.
+  pub fn with_offset_index(mut self, offset_index: Option<OffsetIndex>) -> Self {
+    self.offset_index = offset_index;
+    self
+  }
+
+  /// Builds column chunk metadata.
+  pub fn build(self) -> Result<ColumnChunkMetaData> {
+    Ok(ColumnChunkMetaData {
+      column_type: self.column_descr.physical_type(),
+      column_path: self.column_descr.path().clone(),
+      column_descr: self.column_descr,
+      encodings: self.encodings,
+      file_path: self.file_path,
+      file_offset: self.file_offset,
+      num_values: self.num_values,
+      compression: self.compression,
+      total_compressed_size: self.total_compressed_size,
+      total_uncompressed_size: self.total_uncompressed_size,
+      data_page_offset: self.data_page_offset,
+      index_page_offset: self.index_page_offset,
+      dictionary_page_offset: self.dictionary_page_offset,
+      statistics: self.statistics,
+      bloom_filter_offset: self.bloom_filter_offset,
+      bloom_filter_length: self.bloom_filter_length,
+      column_index_offset: self.column_index_offset,
+      column_index_length: self.column_index_length,
+      offset_index_offset: self.offset_index_offset,
+      offset_index_length: self.offset_index_length,
+      encoding_stats: self.encoding_stats,
+      checksum: self.checksum,
+      key_value_metadata: self.key_value_metadata,
+      column_index: self.column_index,
+      offset_index: self.offset_index
+    })
+  }
+}
+
+/// Parses offset or length of a structure, or checksum stored in column chunk key/value
+/// metadata.
+fn parse_location<T: ::std::str::FromStr>(value: &str, name: &str) -> Result<T> {
+  value.parse().map_err(|_| general_err!("Invalid {} '{}'", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::error::Error;
+
+  use schema::parser::parse_message_type;
+
+  fn get_test_schema_descr() -> SchemaDescPtr {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL group b {
+          REPEATED BYTE_ARRAY c (UTF8);
+        }
+      }
+    ").unwrap();
+    Rc::new(SchemaDescriptor::new(Rc::new(schema)))
+  }
+
+  #[test]
+  fn test_key_value_thrift_conversion() {
+    let kv = KeyValue::new("key".to_owned(), Some("value".to_owned()));
+    assert_eq!(kv.key(), "key");
+    assert_eq!(kv.value(), Some("value"));
+    assert_eq!(KeyValue::from_thrift(kv.to_thrift()), kv);
+
+    let kv = KeyValue::new("key".to_owned(), None);
+    assert_eq!(kv.value(), None);
+    assert_eq!(KeyValue::from_thrift(kv.to_thrift()), kv);
+  }
+
+  #[test]
+  fn test_row_group_metadata_thrift_conversion() {
+    let schema_descr = get_test_schema_descr();
+    let mut columns = vec![];
+    for column_descr in schema_descr.columns() {
+      let column = ColumnChunkMetaData::builder(column_descr.clone())
+        .with_encodings(vec![Encoding::PLAIN, Encoding::RLE])
+        .with_file_path("file_path".to_owned())
+        .with_file_offset(100)
+        .with_num_values(1000)
+        .with_compression(Compression::SNAPPY)
+        .with_total_compressed_size(2000)
+        .with_total_uncompressed_size(3000)
+        .with_data_page_offset(4000)
+        .with_dictionary_page_offset(Some(5000))
+        .with_statistics(Some(Statistics::from_bytes(
+          column_descr.physical_type(), None, None, None, 10, false)))
+        .with_bloom_filter_offset(Some(6000))
+        .with_bloom_filter_length(Some(1024))
+        .with_column_index_offset(Some(7000))
+        .with_column_index_length(Some(100))
+        .with_offset_index_offset(Some(7100))
+        .with_offset_index_length(Some(50))
+        .with_encoding_stats(Some(vec![
+          PageEncodingStats::new(PageType::DICTIONARY_PAGE, Encoding::PLAIN, 1),
+          PageEncodingStats::new(PageType::DATA_PAGE, Encoding::RLE_DICTIONARY, 3)
+        ]))
+        .with_checksum(Some(0xdeadbeef))
+        .with_key_value_metadata(Some(vec![
+          KeyValue::new("planner.ndv".to_owned(), Some("42".to_owned()))
+        ]))
+        .build()
+        .unwrap();
+      columns.push(Rc::new(column));
+    }
+    let row_group_meta = RowGroupMetaData::builder(schema_descr.clone())
+      .with_num_rows(1000)
+      .with_total_byte_size(2000)
+      .with_column_metadata(columns)
+      .build()
+      .unwrap();
+
+    let row_group_exp = row_group_meta.to_thrift();
+    let row_group_res = RowGroupMetaData::from_thrift(
+      schema_descr.clone(),
+      row_group_exp.clone()
+    ).unwrap().to_thrift();
+    assert_eq!(row_group_res, row_group_exp);
+
+    let column = row_group_meta.column(1);
+    assert_eq!(column.column_path().string(), "b.c");
+    assert_eq!(column.column_type(), Type::BYTE_ARRAY);
+    assert_eq!(column.compression(), Compression::SNAPPY);
+    assert_eq!(column.dictionary_page_offset(), Some(5000));
+    assert_eq!(column.index_page_offset(), None);
+    assert_eq!(column.bloom_filter_offset(), Some(6000));
+    assert_eq!(column.bloom_filter_length(), Some(1024));
+    assert_eq!(column.column_index_offset(), Some(7000));
+    assert_eq!(column.column_index_length(), Some(100));
+    assert_eq!(column.offset_index_offset(), Some(7100));
+    assert_eq!(column.offset_index_length(), Some(50));
+    assert_eq!(column.checksum(), Some(0xdeadbeef));
+    assert_eq!(column.compressed_size(), 2000);
+    assert_eq!(column.uncompressed_size(), 3000);
+    assert_eq!(
+      column.encoding_stats(),
+      Some(&vec![
+        PageEncodingStats::new(PageType::DICTIONARY_PAGE, Encoding::PLAIN, 1),
+        PageEncodingStats::new(PageType::DATA_PAGE, Encoding::RLE_DICTIONARY, 3)
+      ])
+    );
+    assert_eq!(
+      column.key_value_metadata(),
+      Some(&vec![KeyValue::new("planner.ndv".to_owned(), Some("42".to_owned()))])
+    );
+    // Locations and checksum are not returned as key/value pairs
+    let thrift = column.to_thrift();
+    let key_values = thrift.meta_data.as_ref().unwrap().key_value_metadata.clone();
+    let keys: Vec<String> = key_values.unwrap().into_iter().map(|kv| kv.key).collect();
+    assert_eq!(keys.len(), 8);
+    assert!(keys.contains(&"parquet-rs.column_index.offset".to_owned()));
+    assert!(keys.contains(&"parquet-rs.offset_index.length".to_owned()));
+    assert!(keys.contains(&"parquet-rs.column_chunk.crc32".to_owned()));
+    assert!(keys.contains(&"parquet-rs.bloom_filter.offset".to_owned()));
+    assert!(keys.contains(&"parquet-rs.bloom_filter.length".to_owned()));
+    let column =
+      ColumnChunkMetaData::from_thrift(column.column_descr_ptr(), thrift).unwrap();
+    assert_eq!(column.key_value_metadata().unwrap().len(), 1);
+    assert_eq!(column.encoding_stats().unwrap().len(), 2);
+    assert_eq!(
+      column.statistics(),
+      Some(&Statistics::byte_array(None, None, None, 10, false))
+    );
+  }
+
+  #[test]
+  fn test_row_group_metadata_builder_column_mismatch() {
+    let schema_descr = get_test_schema_descr();
+    let res = RowGroupMetaData::builder(schema_descr)
+      .with_column_metadata(vec![])
+      .build();
+    assert!(res.is_err());
+    if let Err(e) = res {
+      assert_eq!(e.description(), "Column length mismatch: 2 != 0");
+    }
+  }
+
+  #[test]
+  fn test_row_group_metadata_from_thrift_column_mismatch() {
+    let schema_descr = get_test_schema_descr();
+    let row_group = RowGroup {
+      columns: vec![],
+      total_byte_size: 2000,
+      num_rows: 1000,
+      sorting_columns: None
+    };
+    assert_eq!(
+      RowGroupMetaData::from_thrift(schema_descr, row_group).unwrap_err(),
+      general_err!("Column length mismatch: 2 != 0")
+    );
+  }
 }
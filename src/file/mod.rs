@@ -16,10 +16,11 @@
 // under the License.
 
 //! Main entrypoint for working with Parquet API.
-//! Provides access to file and row group readers, record API, etc.
+//! Provides access to file and row group readers and writers, record API, etc.
 //!
 //! See [`reader::SerializedFileReader`] for a starting reference and
-//! [`metadata::ParquetMetaData`] for file metadata.
+//! [`metadata::ParquetMetaData`] for file metadata. Files are written with
+//! [`writer::SerializedFileWriter`], configured with [`properties::WriterProperties`].
 //!
 //! # Example
 //!
@@ -35,5 +36,19 @@
 //! let row_group = reader.get_row_group(0);
 //! ```
 
+pub mod atomic;
+pub mod builder;
+pub mod compat;
+pub mod dataset;
+pub mod filter;
+pub mod fixtures;
+pub mod layout;
 pub mod metadata;
+pub mod page_cache;
+pub mod page_index;
+pub mod progress;
+pub mod properties;
 pub mod reader;
+pub mod rewrite;
+pub mod statistics;
+pub mod writer;
@@ -0,0 +1,319 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains LRU cache of decompressed pages that can be shared between file readers.
+//!
+//! A [`PageCache`](`PageCache`) is set on file readers with
+//! [`SerializedFileReader::with_page_cache`](
+//! `::file::reader::SerializedFileReader::with_page_cache`). Pages are keyed by path of
+//! the file and offset of the page header, so repeated reads of the same pages, e.g.
+//! point lookups against hot files, neither read nor decompress them again. Clones of
+//! the cache share the same entries.
+//!
+//! Files are assumed to be immutable while pages are cached, use
+//! [`PageCache::clear`](`PageCache::clear`) after a file is rewritten.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//!
+//! use parquet::file::page_cache::PageCache;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//!
+//! // Cache up to 64 MiB of decompressed pages
+//! let cache = PageCache::new(64 * 1024 * 1024);
+//! for _ in 0..2 {
+//!   let reader = SerializedFileReader::try_from_path(Path::new("data.parquet"))
+//!     .unwrap()
+//!     .with_page_cache(cache.clone())
+//!     .unwrap();
+//!   let _rows: Vec<_> = reader.get_row_iter(None).unwrap().collect();
+//! }
+//! // Second scan is served from the cache
+//! assert!(cache.hits() > 0);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use column::page::Page;
+
+/// Key of a cached page, i.e. path of the file and offset of the page header.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PageCacheKey {
+  path: Rc<PathBuf>,
+  offset: usize
+}
+
+impl PageCacheKey {
+  /// Creates key of the page whose header starts at `offset` in file at `path`.
+  pub fn new(path: Rc<PathBuf>, offset: usize) -> Self {
+    Self { path: path, offset: offset }
+  }
+
+  /// Returns path of the file.
+  pub fn path(&self) -> &PathBuf {
+    &self.path
+  }
+
+  /// Returns offset of the page header in the file.
+  pub fn offset(&self) -> usize {
+    self.offset
+  }
+}
+
+/// Decompressed page with the number of bytes it occupies in the file.
+#[derive(Clone)]
+pub struct CachedPage {
+  page: Page,
+  file_len: usize
+}
+
+impl CachedPage {
+  /// Creates cached page, `file_len` is the number of bytes of the page header and
+  /// compressed page data in the file.
+  pub fn new(page: Page, file_len: usize) -> Self {
+    Self { page: page, file_len: file_len }
+  }
+
+  /// Returns decompressed page.
+  pub fn page(&self) -> &Page {
+    &self.page
+  }
+
+  /// Returns the number of bytes of the page header and page data in the file.
+  pub fn file_len(&self) -> usize {
+    self.file_len
+  }
+
+  /// Returns the number of bytes this page occupies in the cache.
+  fn size(&self) -> usize {
+    self.page.buffer().len()
+  }
+}
+
+/// Cache entry with the tick of its last access.
+struct Entry {
+  page: CachedPage,
+  tick: u64
+}
+
+/// LRU state of the cache.
+struct LruCache {
+  capacity: usize,
+  size: usize,
+  tick: u64,
+  entries: HashMap<PageCacheKey, Entry>,
+  // Keys ordered by the tick of their last access, least recently used first
+  order: BTreeMap<u64, PageCacheKey>,
+  hits: u64,
+  misses: u64
+}
+
+impl LruCache {
+  fn next_tick(&mut self) -> u64 {
+    self.tick += 1;
+    self.tick
+  }
+
+  fn get(&mut self, key: &PageCacheKey) -> Option<CachedPage> {
+    let tick = self.next_tick();
+    match self.entries.get_mut(key) {
+      Some(entry) => {
+        self.order.remove(&entry.tick);
+        self.order.insert(tick, key.clone());
+        entry.tick = tick;
+        self.hits += 1;
+        Some(entry.page.clone())
+      },
+      None => {
+        self.misses += 1;
+        None
+      }
+    }
+  }
+
+  fn insert(&mut self, key: PageCacheKey, page: CachedPage) {
+    self.remove(&key);
+    let size = page.size();
+    if size > self.capacity {
+      return;
+    }
+    while self.size + size > self.capacity {
+      let lru_key = match self.order.values().next() {
+        Some(key) => key.clone(),
+        None => break
+      };
+      self.remove(&lru_key);
+    }
+    let tick = self.next_tick();
+    self.order.insert(tick, key.clone());
+    self.entries.insert(key, Entry { page: page, tick: tick });
+    self.size += size;
+  }
+
+  fn remove(&mut self, key: &PageCacheKey) {
+    if let Some(entry) = self.entries.remove(key) {
+      self.order.remove(&entry.tick);
+      self.size -= entry.page.size();
+    }
+  }
+}
+
+/// LRU cache of decompressed pages, bounded by the total size of page buffers in bytes.
+///
+/// Clones share the same entries, so one cache can be set on any number of file
+/// readers.
+#[derive(Clone)]
+pub struct PageCache {
+  inner: Rc<RefCell<LruCache>>
+}
+
+impl PageCache {
+  /// Creates empty cache that holds at most `capacity` bytes of decompressed pages.
+  /// Pages larger than the capacity are never cached.
+  pub fn new(capacity: usize) -> Self {
+    let cache = LruCache {
+      capacity: capacity,
+      size: 0,
+      tick: 0,
+      entries: HashMap::new(),
+      order: BTreeMap::new(),
+      hits: 0,
+      misses: 0
+    };
+    Self { inner: Rc::new(RefCell::new(cache)) }
+  }
+
+  /// Returns page for the key and marks it as the most recently used one, or `None`
+  /// if page is not cached.
+  pub fn get(&self, key: &PageCacheKey) -> Option<CachedPage> {
+    self.inner.borrow_mut().get(key)
+  }
+
+  /// Inserts page for the key, evicting least recently used pages if capacity is
+  /// exceeded.
+  pub fn insert(&self, key: PageCacheKey, page: CachedPage) {
+    self.inner.borrow_mut().insert(key, page)
+  }
+
+  /// Removes all pages from the cache.
+  pub fn clear(&self) {
+    let mut inner = self.inner.borrow_mut();
+    inner.entries.clear();
+    inner.order.clear();
+    inner.size = 0;
+  }
+
+  /// Returns maximum number of bytes of cached pages.
+  pub fn capacity(&self) -> usize {
+    self.inner.borrow().capacity
+  }
+
+  /// Returns number of bytes of cached pages.
+  pub fn size(&self) -> usize {
+    self.inner.borrow().size
+  }
+
+  /// Returns number of cached pages.
+  pub fn len(&self) -> usize {
+    self.inner.borrow().entries.len()
+  }
+
+  /// Returns `true` if no pages are cached.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns number of lookups that found a cached page.
+  pub fn hits(&self) -> u64 {
+    self.inner.borrow().hits
+  }
+
+  /// Returns number of lookups that did not find a cached page.
+  pub fn misses(&self) -> u64 {
+    self.inner.borrow().misses
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use basic::Encoding;
+  use util::memory::ByteBufferPtr;
+
+  fn page(len: usize) -> CachedPage {
+    let page = Page::DictionaryPage {
+      buf: ByteBufferPtr::new(vec![0; len]),
+      num_values: 1,
+      encoding: Encoding::PLAIN,
+      is_sorted: false
+    };
+    CachedPage::new(page, len + 10)
+  }
+
+  fn key(offset: usize) -> PageCacheKey {
+    PageCacheKey::new(Rc::new(PathBuf::from("file.parquet")), offset)
+  }
+
+  #[test]
+  fn test_page_cache_lru_eviction() {
+    let cache = PageCache::new(100);
+    cache.insert(key(0), page(40));
+    cache.insert(key(50), page(40));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.size(), 80);
+
+    // Access first page, so the second one is evicted
+    assert_eq!(cache.get(&key(0)).unwrap().file_len(), 50);
+    cache.insert(key(100), page(40));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.size(), 80);
+    assert!(cache.get(&key(50)).is_none());
+    assert!(cache.get(&key(0)).is_some());
+    assert!(cache.get(&key(100)).is_some());
+    assert_eq!(cache.hits(), 3);
+    assert_eq!(cache.misses(), 1);
+
+    // Same offset in another file is a different page
+    let other = PageCacheKey::new(Rc::new(PathBuf::from("other.parquet")), 0);
+    assert!(cache.get(&other).is_none());
+  }
+
+  #[test]
+  fn test_page_cache_replace_and_clear() {
+    let cache = PageCache::new(100);
+    cache.insert(key(0), page(40));
+    cache.insert(key(0), page(60));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.size(), 60);
+
+    // Pages larger than capacity are not cached
+    cache.insert(key(200), page(101));
+    assert_eq!(cache.len(), 1);
+
+    // Clones share entries
+    cache.clone().clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.size(), 0);
+  }
+}
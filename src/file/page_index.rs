@@ -0,0 +1,590 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains page index of a column chunk: [`ColumnIndex`] with min/max values and null
+//! counts of data pages, and [`OffsetIndex`] with locations of data pages.
+//!
+//! Page index is written by the file writer after all row groups, when enabled with
+//! `with_page_index_enabled` in writer properties, and allows readers to skip data
+//! pages without reading page headers.
+//!
+//! Structures are serialized with Thrift compact protocol, as defined in the Parquet
+//! format. Thrift definitions of the supported Parquet format version do not have
+//! these structures and `ColumnChunk` fields for their location yet, so offsets and
+//! lengths are stored in key/value metadata of the column chunk under keys in the
+//! `parquet-rs.` namespace of this crate. Page index is therefore private to this
+//! crate: it is read back with `RowGroupReader::get_column_index` and
+//! `RowGroupReader::get_offset_index`, while other implementations, which look for the
+//! location in `ColumnChunk` fields, do not find it and read files without it.
+
+use std::cmp::Ordering;
+
+use errors::{ParquetError, Result};
+use thrift::protocol::{
+  TCompactInputProtocol, TCompactOutputProtocol, TFieldIdentifier, TInputProtocol,
+  TListIdentifier, TOutputProtocol, TStructIdentifier, TType
+};
+
+/// Key of the column chunk key/value metadata entry with offset of column index.
+pub(crate) const COLUMN_INDEX_OFFSET_KEY: &str = "parquet-rs.column_index.offset";
+/// Key of the column chunk key/value metadata entry with length of column index.
+pub(crate) const COLUMN_INDEX_LENGTH_KEY: &str = "parquet-rs.column_index.length";
+/// Key of the column chunk key/value metadata entry with offset of offset index.
+pub(crate) const OFFSET_INDEX_OFFSET_KEY: &str = "parquet-rs.offset_index.offset";
+/// Key of the column chunk key/value metadata entry with length of offset index.
+pub(crate) const OFFSET_INDEX_LENGTH_KEY: &str = "parquet-rs.offset_index.length";
+
+/// Order of min/max values of data pages in a column index.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BoundaryOrder {
+  UNORDERED,
+  ASCENDING,
+  DESCENDING
+}
+
+impl BoundaryOrder {
+  fn from_i32(value: i32) -> Result<Self> {
+    match value {
+      0 => Ok(BoundaryOrder::UNORDERED),
+      1 => Ok(BoundaryOrder::ASCENDING),
+      2 => Ok(BoundaryOrder::DESCENDING),
+      _ => Err(general_err!("Invalid boundary order {}", value))
+    }
+  }
+
+  fn as_i32(&self) -> i32 {
+    match *self {
+      BoundaryOrder::UNORDERED => 0,
+      BoundaryOrder::ASCENDING => 1,
+      BoundaryOrder::DESCENDING => 2
+    }
+  }
+}
+
+/// Min/max values and null counts of data pages of a column chunk.
+///
+/// Min/max values are plain encoded, like values of statistics. Pages that contain
+/// only nulls have empty min/max values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnIndex {
+  null_pages: Vec<bool>,
+  min_values: Vec<Vec<u8>>,
+  max_values: Vec<Vec<u8>>,
+  boundary_order: BoundaryOrder,
+  null_counts: Option<Vec<i64>>
+}
+
+impl ColumnIndex {
+  /// Returns number of pages in this index.
+  pub fn num_pages(&self) -> usize {
+    self.null_pages.len()
+  }
+
+  /// Returns flags, whether each page contains only nulls.
+  pub fn null_pages(&self) -> &[bool] {
+    &self.null_pages
+  }
+
+  /// Returns plain encoded min values of pages.
+  pub fn min_values(&self) -> &[Vec<u8>] {
+    &self.min_values
+  }
+
+  /// Returns plain encoded max values of pages.
+  pub fn max_values(&self) -> &[Vec<u8>] {
+    &self.max_values
+  }
+
+  /// Returns order of min/max values of pages.
+  pub fn boundary_order(&self) -> BoundaryOrder {
+    self.boundary_order
+  }
+
+  /// Returns null counts of pages, if set.
+  pub fn null_counts(&self) -> Option<&[i64]> {
+    self.null_counts.as_ref().map(|counts| &counts[..])
+  }
+
+  /// Serializes this index with Thrift compact protocol.
+  pub fn to_bytes(&self) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      prot.write_struct_begin(&TStructIdentifier::new("ColumnIndex"))?;
+
+      prot.write_field_begin(&TFieldIdentifier::new("null_pages", TType::List, 1))?;
+      prot.write_list_begin(
+        &TListIdentifier::new(TType::Bool, self.null_pages.len() as i32))?;
+      for &value in &self.null_pages {
+        prot.write_bool(value)?;
+      }
+      prot.write_list_end()?;
+      prot.write_field_end()?;
+
+      write_binary_list(&mut prot, "min_values", 2, &self.min_values)?;
+      write_binary_list(&mut prot, "max_values", 3, &self.max_values)?;
+
+      prot.write_field_begin(&TFieldIdentifier::new("boundary_order", TType::I32, 4))?;
+      prot.write_i32(self.boundary_order.as_i32())?;
+      prot.write_field_end()?;
+
+      if let Some(ref counts) = self.null_counts {
+        prot.write_field_begin(&TFieldIdentifier::new("null_counts", TType::List, 5))?;
+        prot.write_list_begin(&TListIdentifier::new(TType::I64, counts.len() as i32))?;
+        for &value in counts {
+          prot.write_i64(value)?;
+        }
+        prot.write_list_end()?;
+        prot.write_field_end()?;
+      }
+
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      prot.flush()?;
+    }
+    Ok(buf)
+  }
+
+  /// Deserializes index from bytes, as written by `to_bytes`.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+    let mut prot = TCompactInputProtocol::new(bytes);
+    let mut null_pages = None;
+    let mut min_values = None;
+    let mut max_values = None;
+    let mut boundary_order = None;
+    let mut null_counts = None;
+
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::List) => {
+          let list = prot.read_list_begin()?;
+          let mut values = Vec::new();
+          for _ in 0..list.size {
+            values.push(prot.read_bool()?);
+          }
+          prot.read_list_end()?;
+          null_pages = Some(values);
+        },
+        (Some(2), TType::List) => min_values = Some(read_binary_list(&mut prot)?),
+        (Some(3), TType::List) => max_values = Some(read_binary_list(&mut prot)?),
+        (Some(4), TType::I32) => {
+          boundary_order = Some(BoundaryOrder::from_i32(prot.read_i32()?)?)
+        },
+        (Some(5), TType::List) => {
+          let list = prot.read_list_begin()?;
+          let mut values = Vec::new();
+          for _ in 0..list.size {
+            values.push(prot.read_i64()?);
+          }
+          prot.read_list_end()?;
+          null_counts = Some(values);
+        },
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+
+    let index = ColumnIndex {
+      null_pages: required_field(null_pages, "ColumnIndex.null_pages")?,
+      min_values: required_field(min_values, "ColumnIndex.min_values")?,
+      max_values: required_field(max_values, "ColumnIndex.max_values")?,
+      boundary_order: required_field(boundary_order, "ColumnIndex.boundary_order")?,
+      null_counts: null_counts
+    };
+    let num_pages = index.num_pages();
+    if index.min_values.len() != num_pages || index.max_values.len() != num_pages ||
+        index.null_counts().map_or(false, |counts| counts.len() != num_pages) {
+      return Err(general_err!("Inconsistent number of pages in column index"));
+    }
+    Ok(index)
+  }
+}
+
+/// Builder of [`ColumnIndex`], pages are appended in the order they are written.
+pub struct ColumnIndexBuilder {
+  null_pages: Vec<bool>,
+  min_values: Vec<Vec<u8>>,
+  max_values: Vec<Vec<u8>>,
+  null_counts: Vec<i64>,
+  is_ascending: bool,
+  is_descending: bool
+}
+
+impl ColumnIndexBuilder {
+  /// Creates new builder without pages.
+  pub fn new() -> Self {
+    Self {
+      null_pages: Vec::new(),
+      min_values: Vec::new(),
+      max_values: Vec::new(),
+      null_counts: Vec::new(),
+      is_ascending: true,
+      is_descending: true
+    }
+  }
+
+  /// Appends page with plain encoded `min` and `max` values and `null_count` nulls.
+  pub fn append(&mut self, min: &[u8], max: &[u8], null_count: i64) {
+    self.null_pages.push(false);
+    self.min_values.push(min.to_vec());
+    self.max_values.push(max.to_vec());
+    self.null_counts.push(null_count);
+  }
+
+  /// Appends page that contains only `null_count` nulls.
+  pub fn append_null_page(&mut self, null_count: i64) {
+    self.null_pages.push(true);
+    self.min_values.push(Vec::new());
+    self.max_values.push(Vec::new());
+    self.null_counts.push(null_count);
+  }
+
+  /// Updates boundary order with orderings of min and max values of the appended
+  /// page relative to the previous page with values.
+  pub fn update_boundary_order(
+    &mut self,
+    min_ordering: Ordering,
+    max_ordering: Ordering
+  ) {
+    if min_ordering == Ordering::Less || max_ordering == Ordering::Less {
+      self.is_ascending = false;
+    }
+    if min_ordering == Ordering::Greater || max_ordering == Ordering::Greater {
+      self.is_descending = false;
+    }
+  }
+
+  /// Returns column index, or `None` if no pages have been appended.
+  pub fn build(self) -> Option<ColumnIndex> {
+    if self.null_pages.is_empty() {
+      return None;
+    }
+    let boundary_order = if self.is_ascending {
+      BoundaryOrder::ASCENDING
+    } else if self.is_descending {
+      BoundaryOrder::DESCENDING
+    } else {
+      BoundaryOrder::UNORDERED
+    };
+    Some(ColumnIndex {
+      null_pages: self.null_pages,
+      min_values: self.min_values,
+      max_values: self.max_values,
+      boundary_order: boundary_order,
+      null_counts: Some(self.null_counts)
+    })
+  }
+}
+
+/// Location of a data page in a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageLocation {
+  /// Offset of the page header in the file.
+  pub offset: i64,
+  /// Size of the page including page header.
+  pub compressed_page_size: i32,
+  /// Index of the first row of the page within the row group.
+  pub first_row_index: i64
+}
+
+/// Locations of data pages of a column chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OffsetIndex {
+  page_locations: Vec<PageLocation>
+}
+
+impl OffsetIndex {
+  /// Creates offset index from page locations.
+  pub fn new(page_locations: Vec<PageLocation>) -> Self {
+    Self { page_locations: page_locations }
+  }
+
+  /// Returns locations of data pages, in the order they are written.
+  pub fn page_locations(&self) -> &[PageLocation] {
+    &self.page_locations
+  }
+
+  /// Serializes this index with Thrift compact protocol.
+  pub fn to_bytes(&self) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    {
+      let mut prot = TCompactOutputProtocol::new(&mut buf);
+      prot.write_struct_begin(&TStructIdentifier::new("OffsetIndex"))?;
+      prot.write_field_begin(&TFieldIdentifier::new("page_locations", TType::List, 1))?;
+      prot.write_list_begin(
+        &TListIdentifier::new(TType::Struct, self.page_locations.len() as i32))?;
+      for location in &self.page_locations {
+        prot.write_struct_begin(&TStructIdentifier::new("PageLocation"))?;
+        prot.write_field_begin(&TFieldIdentifier::new("offset", TType::I64, 1))?;
+        prot.write_i64(location.offset)?;
+        prot.write_field_end()?;
+        prot.write_field_begin(
+          &TFieldIdentifier::new("compressed_page_size", TType::I32, 2))?;
+        prot.write_i32(location.compressed_page_size)?;
+        prot.write_field_end()?;
+        prot.write_field_begin(&TFieldIdentifier::new("first_row_index", TType::I64, 3))?;
+        prot.write_i64(location.first_row_index)?;
+        prot.write_field_end()?;
+        prot.write_field_stop()?;
+        prot.write_struct_end()?;
+      }
+      prot.write_list_end()?;
+      prot.write_field_end()?;
+      prot.write_field_stop()?;
+      prot.write_struct_end()?;
+      prot.flush()?;
+    }
+    Ok(buf)
+  }
+
+  /// Deserializes index from bytes, as written by `to_bytes`.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+    let mut prot = TCompactInputProtocol::new(bytes);
+    let mut page_locations = None;
+
+    prot.read_struct_begin()?;
+    loop {
+      let field = prot.read_field_begin()?;
+      if field.field_type == TType::Stop {
+        break;
+      }
+      match (field.id, field.field_type) {
+        (Some(1), TType::List) => {
+          let list = prot.read_list_begin()?;
+          let mut locations = Vec::new();
+          for _ in 0..list.size {
+            locations.push(read_page_location(&mut prot)?);
+          }
+          prot.read_list_end()?;
+          page_locations = Some(locations);
+        },
+        (_, field_type) => prot.skip(field_type)?
+      }
+      prot.read_field_end()?;
+    }
+    prot.read_struct_end()?;
+
+    Ok(Self::new(required_field(page_locations, "OffsetIndex.page_locations")?))
+  }
+}
+
+/// Builder of [`OffsetIndex`]. Number of rows of each data page is appended when the
+/// page is created, and its location when the page is written, which might happen
+/// later, e.g. pages are buffered while dictionary encoding is used.
+pub struct OffsetIndexBuilder {
+  row_counts: Vec<i64>,
+  offsets: Vec<i64>,
+  sizes: Vec<i32>
+}
+
+impl OffsetIndexBuilder {
+  /// Creates new builder without pages.
+  pub fn new() -> Self {
+    Self { row_counts: Vec::new(), offsets: Vec::new(), sizes: Vec::new() }
+  }
+
+  /// Appends number of rows of the next data page.
+  pub fn append_row_count(&mut self, row_count: i64) {
+    self.row_counts.push(row_count);
+  }
+
+  /// Appends offset and size including page header of the next written data page.
+  pub fn append_offset_and_size(&mut self, offset: i64, size: i32) {
+    self.offsets.push(offset);
+    self.sizes.push(size);
+  }
+
+  /// Returns offset index. Returns error if number of row counts and page locations
+  /// do not match.
+  pub fn build(self) -> Result<OffsetIndex> {
+    if self.row_counts.len() != self.offsets.len() {
+      return Err(general_err!(
+        "Inconsistent offset index: {} row counts, {} page locations",
+        self.row_counts.len(),
+        self.offsets.len()
+      ));
+    }
+    let mut first_row_index = 0;
+    let mut page_locations = Vec::with_capacity(self.offsets.len());
+    for i in 0..self.offsets.len() {
+      page_locations.push(PageLocation {
+        offset: self.offsets[i],
+        compressed_page_size: self.sizes[i],
+        first_row_index: first_row_index
+      });
+      first_row_index += self.row_counts[i];
+    }
+    Ok(OffsetIndex::new(page_locations))
+  }
+}
+
+// ----------------------------------------------------------------------
+// Thrift helpers
+
+fn required_field<T>(value: Option<T>, name: &str) -> Result<T> {
+  value.ok_or_else(|| general_err!("Required field {} is not set", name))
+}
+
+fn write_binary_list(
+  prot: &mut TOutputProtocol,
+  name: &str,
+  id: i16,
+  values: &[Vec<u8>]
+) -> Result<()> {
+  prot.write_field_begin(&TFieldIdentifier::new(name, TType::List, id))?;
+  prot.write_list_begin(&TListIdentifier::new(TType::String, values.len() as i32))?;
+  for value in values {
+    prot.write_bytes(value)?;
+  }
+  prot.write_list_end()?;
+  prot.write_field_end()?;
+  Ok(())
+}
+
+fn read_binary_list(prot: &mut TInputProtocol) -> Result<Vec<Vec<u8>>> {
+  let list = prot.read_list_begin()?;
+  let mut values = Vec::new();
+  for _ in 0..list.size {
+    values.push(prot.read_bytes()?);
+  }
+  prot.read_list_end()?;
+  Ok(values)
+}
+
+fn read_page_location(prot: &mut TInputProtocol) -> Result<PageLocation> {
+  let mut offset = None;
+  let mut compressed_page_size = None;
+  let mut first_row_index = None;
+
+  prot.read_struct_begin()?;
+  loop {
+    let field = prot.read_field_begin()?;
+    if field.field_type == TType::Stop {
+      break;
+    }
+    match (field.id, field.field_type) {
+      (Some(1), TType::I64) => offset = Some(prot.read_i64()?),
+      (Some(2), TType::I32) => compressed_page_size = Some(prot.read_i32()?),
+      (Some(3), TType::I64) => first_row_index = Some(prot.read_i64()?),
+      (_, field_type) => prot.skip(field_type)?
+    }
+    prot.read_field_end()?;
+  }
+  prot.read_struct_end()?;
+
+  Ok(PageLocation {
+    offset: required_field(offset, "PageLocation.offset")?,
+    compressed_page_size: required_field(
+      compressed_page_size, "PageLocation.compressed_page_size")?,
+    first_row_index: required_field(first_row_index, "PageLocation.first_row_index")?
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_column_index_builder() {
+    let mut builder = ColumnIndexBuilder::new();
+    builder.append(&[1], &[3], 0);
+    builder.append_null_page(5);
+    builder.append(&[2], &[4], 1);
+    builder.update_boundary_order(Ordering::Greater, Ordering::Greater);
+    let index = builder.build().unwrap();
+    assert_eq!(index.num_pages(), 3);
+    assert_eq!(index.null_pages(), &[false, true, false]);
+    assert_eq!(index.min_values(), &[vec![1u8], vec![], vec![2]][..]);
+    assert_eq!(index.max_values(), &[vec![3u8], vec![], vec![4]][..]);
+    assert_eq!(index.null_counts(), Some(&[0, 5, 1][..]));
+    assert_eq!(index.boundary_order(), BoundaryOrder::ASCENDING);
+
+    let mut builder = ColumnIndexBuilder::new();
+    builder.append(&[2], &[4], 0);
+    builder.append(&[1], &[4], 0);
+    builder.update_boundary_order(Ordering::Less, Ordering::Equal);
+    assert_eq!(builder.build().unwrap().boundary_order(), BoundaryOrder::DESCENDING);
+
+    let mut builder = ColumnIndexBuilder::new();
+    builder.update_boundary_order(Ordering::Less, Ordering::Greater);
+    builder.append(&[1], &[4], 0);
+    assert_eq!(builder.build().unwrap().boundary_order(), BoundaryOrder::UNORDERED);
+
+    assert!(ColumnIndexBuilder::new().build().is_none());
+  }
+
+  #[test]
+  fn test_column_index_bytes_roundtrip() {
+    let mut builder = ColumnIndexBuilder::new();
+    builder.append(b"a", b"c", 0);
+    builder.append_null_page(10);
+    builder.append(b"b", b"zz", 3);
+    let index = builder.build().unwrap();
+    let bytes = index.to_bytes().unwrap();
+    assert_eq!(ColumnIndex::from_bytes(&bytes).unwrap(), index);
+
+    assert!(ColumnIndex::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+  }
+
+  #[test]
+  fn test_offset_index_builder() {
+    let mut builder = OffsetIndexBuilder::new();
+    builder.append_row_count(10);
+    builder.append_row_count(20);
+    builder.append_offset_and_size(4, 100);
+    builder.append_row_count(5);
+    builder.append_offset_and_size(104, 200);
+    assert!(builder.build().is_err());
+
+    let mut builder = OffsetIndexBuilder::new();
+    builder.append_row_count(10);
+    builder.append_row_count(20);
+    builder.append_offset_and_size(4, 100);
+    builder.append_offset_and_size(104, 200);
+    let index = builder.build().unwrap();
+    assert_eq!(
+      index.page_locations(),
+      &[
+        PageLocation { offset: 4, compressed_page_size: 100, first_row_index: 0 },
+        PageLocation { offset: 104, compressed_page_size: 200, first_row_index: 10 }
+      ]
+    );
+
+    let bytes = index.to_bytes().unwrap();
+    assert_eq!(OffsetIndex::from_bytes(&bytes).unwrap(), index);
+  }
+
+  #[test]
+  fn test_page_index_missing_required_field() {
+    // Empty struct, only field stop
+    let res = OffsetIndex::from_bytes(&[0]);
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        err.to_string(),
+        "Parquet error: Required field OffsetIndex.page_locations is not set"
+      );
+    }
+    assert!(ColumnIndex::from_bytes(&[0]).is_err());
+  }
+}
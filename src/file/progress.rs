@@ -0,0 +1,135 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains progress reporting for long scans and writes.
+//!
+//! A [`ProgressTracker`](`ProgressTracker`) is set on a file reader or a file writer and
+//! accumulates the number of rows processed, bytes read or written and row groups
+//! completed. Its callback is invoked with the accumulated [`Progress`](`Progress`) at
+//! page and row group boundaries, e.g. to update a progress bar.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Accumulated progress of a scan or a write.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Progress {
+  num_rows: u64,
+  num_bytes: u64,
+  num_row_groups: usize
+}
+
+impl Progress {
+  /// Creates progress with provided counters.
+  pub fn new(num_rows: u64, num_bytes: u64, num_row_groups: usize) -> Self {
+    Self {
+      num_rows: num_rows,
+      num_bytes: num_bytes,
+      num_row_groups: num_row_groups
+    }
+  }
+
+  /// Returns the number of rows of completed row groups.
+  pub fn num_rows(&self) -> u64 {
+    self.num_rows
+  }
+
+  /// Returns the number of bytes read or written.
+  pub fn num_bytes(&self) -> u64 {
+    self.num_bytes
+  }
+
+  /// Returns the number of completed row groups.
+  pub fn num_row_groups(&self) -> usize {
+    self.num_row_groups
+  }
+}
+
+/// Callback that is invoked with accumulated progress.
+pub type ProgressCallback = Rc<Fn(&Progress)>;
+
+/// Progress tracker shared between readers or writers of a file, clones update the same
+/// counters.
+#[derive(Clone)]
+pub struct ProgressTracker {
+  progress: Rc<Cell<Progress>>,
+  callback: ProgressCallback
+}
+
+impl ProgressTracker {
+  /// Creates new tracker that reports progress to `callback`.
+  pub fn new(callback: ProgressCallback) -> Self {
+    Self {
+      progress: Rc::new(Cell::new(Progress::default())),
+      callback: callback
+    }
+  }
+
+  /// Returns accumulated progress.
+  pub fn progress(&self) -> Progress {
+    self.progress.get()
+  }
+
+  /// Adds bytes read or written at a page boundary and invokes callback.
+  pub fn add_bytes(&self, num_bytes: u64) {
+    self.update(0, num_bytes, 0);
+  }
+
+  /// Records a completed row group with `num_rows` rows and `num_bytes` bytes that have
+  /// not been reported yet, and invokes callback.
+  pub fn complete_row_group(&self, num_rows: u64, num_bytes: u64) {
+    self.update(num_rows, num_bytes, 1);
+  }
+
+  fn update(&self, num_rows: u64, num_bytes: u64, num_row_groups: usize) {
+    let current = self.progress.get();
+    let progress = Progress::new(
+      current.num_rows + num_rows,
+      current.num_bytes + num_bytes,
+      current.num_row_groups + num_row_groups
+    );
+    self.progress.set(progress);
+    (self.callback)(&progress);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::RefCell;
+
+  #[test]
+  fn test_progress_tracker() {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let reports_clone = reports.clone();
+    let tracker = ProgressTracker::new(Rc::new(move |progress: &Progress| {
+      reports_clone.borrow_mut().push(*progress);
+    }));
+
+    tracker.add_bytes(10);
+    // Clones share counters
+    tracker.clone().complete_row_group(5, 2);
+    tracker.add_bytes(3);
+
+    assert_eq!(tracker.progress(), Progress::new(5, 15, 1));
+    assert_eq!(
+      *reports.borrow(),
+      vec![Progress::new(0, 10, 0), Progress::new(5, 12, 1), Progress::new(5, 15, 1)]
+    );
+  }
+}
@@ -0,0 +1,1269 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Writer properties.
+//!
+//! Properties are set using [`WriterPropertiesBuilder`] and are immutable once built.
+//! Settings that apply to column chunks (encoding, compression, dictionary, statistics)
+//! can be set globally for all columns and overwritten for a specific column,
+//! identified by [`ColumnPath`](`::schema::types::ColumnPath`).
+//!
+//! # Usage
+//!
+//! ```rust
+//! use parquet::basic::{Compression, Encoding};
+//! use parquet::file::properties::*;
+//! use parquet::schema::types::ColumnPath;
+//!
+//! // Create properties with default configuration.
+//! let props = WriterProperties::builder().build();
+//!
+//! // Use properties builder to set certain options and assemble the configuration.
+//! let props = WriterProperties::builder()
+//!   .with_writer_version(WriterVersion::PARQUET_1_0)
+//!   .with_encoding(Encoding::PLAIN)
+//!   .with_column_encoding(ColumnPath::from("col1"), Encoding::DELTA_BINARY_PACKED)
+//!   .with_compression(Compression::SNAPPY)
+//!   .build();
+//!
+//! assert_eq!(props.writer_version(), WriterVersion::PARQUET_1_0);
+//! assert_eq!(
+//!   props.encoding(&ColumnPath::from("col1")),
+//!   Some(Encoding::DELTA_BINARY_PACKED)
+//! );
+//! assert_eq!(props.encoding(&ColumnPath::from("col2")), Some(Encoding::PLAIN));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use basic::{Compression, Encoding};
+use compression::CompressionPool;
+use errors::{ParquetError, Result};
+use file::metadata::{KeyValue, WRITER_INFO_KEY_PREFIX};
+use schema::types::ColumnPath;
+
+/// Default size in bytes of encoded values after which a data page is added.
+pub const DEFAULT_DATA_PAGE_SIZE: usize = 1024 * 1024;
+/// Default size in bytes of a dictionary after which writer falls back to fallback
+/// encoding.
+pub const DEFAULT_DICTIONARY_PAGE_SIZE_LIMIT: usize = 1024 * 1024;
+/// Default maximum number of rows in a data page, not limited by default.
+pub const DEFAULT_DATA_PAGE_ROW_COUNT_LIMIT: usize = ::std::usize::MAX;
+/// Default number of levels that are processed at once in `write_batch`.
+pub const DEFAULT_WRITE_BATCH_SIZE: usize = 1024;
+/// Default writer version.
+pub const DEFAULT_WRITER_VERSION: WriterVersion = WriterVersion::PARQUET_1_0;
+/// Default compression codec for all columns.
+pub const DEFAULT_COMPRESSION: Compression = Compression::UNCOMPRESSED;
+/// Default flag to enable dictionary encoding for all columns.
+pub const DEFAULT_DICTIONARY_ENABLED: bool = true;
+/// Default flag to enable statistics for all columns.
+pub const DEFAULT_STATISTICS_ENABLED: bool = true;
+/// Default flag to compress dictionary pages with the column compression codec.
+pub const DEFAULT_DICTIONARY_PAGE_COMPRESSION_ENABLED: bool = true;
+/// Default flag to build Bloom filters for all columns.
+pub const DEFAULT_BLOOM_FILTER_ENABLED: bool = false;
+/// Default expected number of distinct values in a column chunk, used to size Bloom
+/// filters.
+pub const DEFAULT_BLOOM_FILTER_NDV: u64 = 1_000_000;
+/// Default false positive probability of Bloom filters.
+pub const DEFAULT_BLOOM_FILTER_FPP: f64 = 0.05;
+/// Default flag to share identical column chunks between row groups.
+pub const DEFAULT_CHUNK_DEDUP_ENABLED: bool = false;
+/// Default flag to select encoding of column chunks from the first page of values.
+pub const DEFAULT_ADAPTIVE_ENCODING_ENABLED: bool = false;
+/// Default flag to write CRC32 checksum of page data into page headers.
+pub const DEFAULT_PAGE_CHECKSUM_ENABLED: bool = false;
+/// Default flag to store CRC32 checksum of every column chunk in column chunk metadata.
+pub const DEFAULT_CHUNK_CHECKSUM_ENABLED: bool = false;
+/// Default flag to write column index and offset index of column chunks.
+pub const DEFAULT_PAGE_INDEX_ENABLED: bool = false;
+/// Default flag to write timestamps into INT96 columns.
+pub const DEFAULT_INT96_TIMESTAMPS_ENABLED: bool = false;
+/// Default flag to cross-check pages, levels, statistics and offsets on write.
+pub const DEFAULT_AUDIT_ENABLED: bool = false;
+/// Default flag to guarantee byte-identical output for identical input.
+pub const DEFAULT_DETERMINISTIC_OUTPUT_ENABLED: bool = false;
+/// Default number of compression worker threads, pages are compressed on the writer
+/// thread by default.
+pub const DEFAULT_COMPRESSION_THREADS: usize = 0;
+/// Number of pages queued for compression per compression worker thread.
+const COMPRESSION_QUEUE_SIZE_PER_THREAD: usize = 2;
+/// Default application name and version written into the `created_by` field.
+pub const DEFAULT_CREATED_BY: &str =
+  concat!("parquet-rs version ", env!("CARGO_PKG_VERSION"));
+
+/// Parquet writer version.
+///
+/// Basic constant, which is not part of the Thrift definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WriterVersion {
+  PARQUET_1_0,
+  PARQUET_2_0
+}
+
+impl WriterVersion {
+  /// Returns writer version as `i32`, as it is written into the file metadata.
+  pub fn as_num(&self) -> i32 {
+    match self {
+      &WriterVersion::PARQUET_1_0 => 1,
+      &WriterVersion::PARQUET_2_0 => 2
+    }
+  }
+}
+
+/// Reference counted writer properties.
+pub type WriterPropertiesPtr = Rc<WriterProperties>;
+
+/// Writer properties.
+///
+/// It is created as an immutable data structure, use [`WriterPropertiesBuilder`] to
+/// assemble the properties.
+#[derive(Debug, Clone)]
+pub struct WriterProperties {
+  data_page_size: usize,
+  data_page_row_count_limit: usize,
+  dictionary_page_size_limit: usize,
+  write_batch_size: usize,
+  writer_version: WriterVersion,
+  page_checksum_enabled: bool,
+  chunk_checksum_enabled: bool,
+  page_index_enabled: bool,
+  int96_timestamps_enabled: bool,
+  audit_enabled: bool,
+  deterministic_output_enabled: bool,
+  compression_threads: usize,
+  compression_pool: Arc<Mutex<Option<Arc<CompressionPool>>>>,
+  created_by: String,
+  created_by_suffix: Option<String>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  writer_info: BTreeMap<String, String>,
+  default_column_properties: ColumnProperties,
+  column_properties: HashMap<ColumnPath, ColumnProperties>
+}
+
+impl WriterProperties {
+  /// Returns builder for writer properties with default values.
+  pub fn builder() -> WriterPropertiesBuilder {
+    WriterPropertiesBuilder::new()
+  }
+
+  /// Returns data page size limit in bytes.
+  pub fn data_page_size(&self) -> usize {
+    self.data_page_size
+  }
+
+  /// Returns maximum number of rows in a data page.
+  pub fn data_page_row_count_limit(&self) -> usize {
+    self.data_page_row_count_limit
+  }
+
+  /// Returns dictionary page size limit in bytes.
+  pub fn dictionary_page_size_limit(&self) -> usize {
+    self.dictionary_page_size_limit
+  }
+
+  /// Returns number of levels that are processed at once when writing a batch.
+  pub fn write_batch_size(&self) -> usize {
+    self.write_batch_size
+  }
+
+  /// Returns writer version.
+  pub fn writer_version(&self) -> WriterVersion {
+    self.writer_version
+  }
+
+  /// Returns `true` if CRC32 checksum of page data is written into page headers.
+  pub fn page_checksum_enabled(&self) -> bool {
+    self.page_checksum_enabled
+  }
+
+  /// Returns `true` if CRC32 checksum of every column chunk is stored in column chunk
+  /// metadata.
+  pub fn chunk_checksum_enabled(&self) -> bool {
+    self.chunk_checksum_enabled
+  }
+
+  /// Returns `true` if column index and offset index are written for column chunks.
+  pub fn page_index_enabled(&self) -> bool {
+    self.page_index_enabled
+  }
+
+  /// Returns `true` if timestamps are written into INT96 columns.
+  pub fn int96_timestamps_enabled(&self) -> bool {
+    self.int96_timestamps_enabled
+  }
+
+  /// Returns `true` if writers audit everything they write.
+  pub fn audit_enabled(&self) -> bool {
+    self.audit_enabled
+  }
+
+  /// Returns `true` if writers guarantee byte-identical output for identical input.
+  pub fn deterministic_output_enabled(&self) -> bool {
+    self.deterministic_output_enabled
+  }
+
+  /// Returns number of compression worker threads, `0` if pages are compressed on the
+  /// writer thread.
+  pub fn compression_threads(&self) -> usize {
+    self.compression_threads
+  }
+
+  /// Returns pool of compression worker threads, or `None` if pages are compressed on
+  /// the writer thread.
+  ///
+  /// Pool is started on first use and shared by all clones of these properties, i.e.
+  /// by all column writers of a file.
+  pub fn compression_pool(&self) -> Result<Option<Arc<CompressionPool>>> {
+    if self.compression_threads == 0 {
+      return Ok(None);
+    }
+    let mut pool = self.compression_pool
+      .lock()
+      .map_err(|_| general_err!("Compression pool is poisoned"))?;
+    if pool.is_none() {
+      let queue_size = self.compression_threads * COMPRESSION_QUEUE_SIZE_PER_THREAD;
+      *pool = Some(Arc::new(CompressionPool::new(self.compression_threads, queue_size)?));
+    }
+    Ok(pool.clone())
+  }
+
+  /// Returns `created_by` string, including the suffix if set.
+  pub fn created_by(&self) -> &str {
+    &self.created_by
+  }
+
+  /// Returns suffix of the `created_by` string set by the application, if set.
+  pub fn created_by_suffix(&self) -> Option<&str> {
+    self.created_by_suffix.as_ref().map(|s| s.as_str())
+  }
+
+  /// Returns key/value metadata that is written into the file metadata, if set.
+  pub fn key_value_metadata(&self) -> Option<&Vec<KeyValue>> {
+    self.key_value_metadata.as_ref()
+  }
+
+  /// Returns writer information that is written into the file metadata.
+  pub fn writer_info(&self) -> &BTreeMap<String, String> {
+    &self.writer_info
+  }
+
+  /// Returns all key/value metadata of the file metadata, i.e. key/value metadata
+  /// followed by writer information with keys prefixed with `writer.info.`, or `None`
+  /// if neither is set. If deterministic output is enabled, entries are sorted by key,
+  /// entries with the same key keep their order.
+  pub fn file_key_value_metadata(&self) -> Option<Vec<KeyValue>> {
+    if self.key_value_metadata.is_none() && self.writer_info.is_empty() {
+      return None;
+    }
+    let mut key_values = self.key_value_metadata.clone().unwrap_or_default();
+    for (key, value) in &self.writer_info {
+      key_values.push(KeyValue::new(
+        format!("{}{}", WRITER_INFO_KEY_PREFIX, key),
+        Some(value.clone())
+      ));
+    }
+    if self.deterministic_output_enabled {
+      key_values.sort_by(|a, b| a.key().cmp(b.key()));
+    }
+    Some(key_values)
+  }
+
+  /// Returns encoding for a column, if set.
+  /// In case when dictionary is enabled, returns fallback encoding.
+  ///
+  /// If encoding is not set, then column writer will choose the best encoding
+  /// based on the column type.
+  pub fn encoding(&self, col: &ColumnPath) -> Option<Encoding> {
+    self.column_properties.get(col)
+      .and_then(|c| c.encoding())
+      .or_else(|| self.default_column_properties.encoding())
+  }
+
+  /// Returns compression codec for a column.
+  pub fn compression(&self, col: &ColumnPath) -> Compression {
+    self.column_properties.get(col)
+      .and_then(|c| c.compression())
+      .or_else(|| self.default_column_properties.compression())
+      .unwrap_or(DEFAULT_COMPRESSION)
+  }
+
+  /// Returns `true` if dictionary encoding is enabled for a column.
+  pub fn dictionary_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.dictionary_enabled())
+      .or_else(|| self.default_column_properties.dictionary_enabled())
+      .unwrap_or(DEFAULT_DICTIONARY_ENABLED)
+  }
+
+  /// Returns `true` if statistics are written for a column.
+  pub fn statistics_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.statistics_enabled())
+      .or_else(|| self.default_column_properties.statistics_enabled())
+      .unwrap_or(DEFAULT_STATISTICS_ENABLED)
+  }
+
+  /// Returns compression dictionary for a column, if set.
+  pub fn compression_dictionary(&self, col: &ColumnPath) -> Option<Arc<Vec<u8>>> {
+    self.column_properties.get(col)
+      .and_then(|c| c.compression_dictionary())
+      .or_else(|| self.default_column_properties.compression_dictionary())
+  }
+
+  /// Returns `true` if dictionary pages of a column are compressed with the column
+  /// compression codec.
+  pub fn dictionary_page_compression_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.dictionary_page_compression_enabled())
+      .or_else(|| self.default_column_properties.dictionary_page_compression_enabled())
+      .unwrap_or(DEFAULT_DICTIONARY_PAGE_COMPRESSION_ENABLED)
+  }
+
+  /// Returns `true` if Bloom filter is built for a column.
+  pub fn bloom_filter_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.bloom_filter_enabled())
+      .or_else(|| self.default_column_properties.bloom_filter_enabled())
+      .unwrap_or(DEFAULT_BLOOM_FILTER_ENABLED)
+  }
+
+  /// Returns expected number of distinct values in a column chunk of a column.
+  pub fn bloom_filter_ndv(&self, col: &ColumnPath) -> u64 {
+    self.column_properties.get(col)
+      .and_then(|c| c.bloom_filter_ndv())
+      .or_else(|| self.default_column_properties.bloom_filter_ndv())
+      .unwrap_or(DEFAULT_BLOOM_FILTER_NDV)
+  }
+
+  /// Returns false positive probability of Bloom filter for a column.
+  pub fn bloom_filter_fpp(&self, col: &ColumnPath) -> f64 {
+    self.column_properties.get(col)
+      .and_then(|c| c.bloom_filter_fpp())
+      .or_else(|| self.default_column_properties.bloom_filter_fpp())
+      .unwrap_or(DEFAULT_BLOOM_FILTER_FPP)
+  }
+
+  /// Returns maximum size in bytes of min/max statistics of a column, if set.
+  pub fn max_statistics_size(&self, col: &ColumnPath) -> Option<usize> {
+    self.column_properties.get(col)
+      .and_then(|c| c.max_statistics_size())
+      .or_else(|| self.default_column_properties.max_statistics_size())
+  }
+
+  /// Returns `true` if identical column chunks of a column are shared between row
+  /// groups.
+  pub fn chunk_dedup_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.chunk_dedup_enabled())
+      .or_else(|| self.default_column_properties.chunk_dedup_enabled())
+      .unwrap_or(DEFAULT_CHUNK_DEDUP_ENABLED)
+  }
+
+  /// Returns `true` if column writers select encoding of a column from the first page
+  /// of values of every column chunk.
+  pub fn adaptive_encoding_enabled(&self, col: &ColumnPath) -> bool {
+    self.column_properties.get(col)
+      .and_then(|c| c.adaptive_encoding_enabled())
+      .or_else(|| self.default_column_properties.adaptive_encoding_enabled())
+      .unwrap_or(DEFAULT_ADAPTIVE_ENCODING_ENABLED)
+  }
+}
+
+/// Writer properties builder.
+pub struct WriterPropertiesBuilder {
+  data_page_size: usize,
+  data_page_row_count_limit: usize,
+  dictionary_page_size_limit: usize,
+  write_batch_size: usize,
+  writer_version: WriterVersion,
+  page_checksum_enabled: bool,
+  chunk_checksum_enabled: bool,
+  page_index_enabled: bool,
+  int96_timestamps_enabled: bool,
+  audit_enabled: bool,
+  deterministic_output_enabled: bool,
+  compression_threads: usize,
+  created_by: String,
+  created_by_suffix: Option<String>,
+  key_value_metadata: Option<Vec<KeyValue>>,
+  writer_info: BTreeMap<String, String>,
+  default_column_properties: ColumnProperties,
+  column_properties: HashMap<ColumnPath, ColumnProperties>
+}
+
+impl WriterPropertiesBuilder {
+  /// Returns default state of the builder.
+  fn new() -> Self {
+    Self {
+      data_page_size: DEFAULT_DATA_PAGE_SIZE,
+      data_page_row_count_limit: DEFAULT_DATA_PAGE_ROW_COUNT_LIMIT,
+      dictionary_page_size_limit: DEFAULT_DICTIONARY_PAGE_SIZE_LIMIT,
+      write_batch_size: DEFAULT_WRITE_BATCH_SIZE,
+      writer_version: DEFAULT_WRITER_VERSION,
+      page_checksum_enabled: DEFAULT_PAGE_CHECKSUM_ENABLED,
+      chunk_checksum_enabled: DEFAULT_CHUNK_CHECKSUM_ENABLED,
+      page_index_enabled: DEFAULT_PAGE_INDEX_ENABLED,
+      int96_timestamps_enabled: DEFAULT_INT96_TIMESTAMPS_ENABLED,
+      audit_enabled: DEFAULT_AUDIT_ENABLED,
+      deterministic_output_enabled: DEFAULT_DETERMINISTIC_OUTPUT_ENABLED,
+      compression_threads: DEFAULT_COMPRESSION_THREADS,
+      created_by: DEFAULT_CREATED_BY.to_string(),
+      created_by_suffix: None,
+      key_value_metadata: None,
+      writer_info: BTreeMap::new(),
+      default_column_properties: ColumnProperties::new(),
+      column_properties: HashMap::new()
+    }
+  }
+
+  /// Finalizes the configuration and returns immutable writer properties struct.
+  pub fn build(self) -> WriterProperties {
+    WriterProperties {
+      data_page_size: self.data_page_size,
+      data_page_row_count_limit: self.data_page_row_count_limit,
+      dictionary_page_size_limit: self.dictionary_page_size_limit,
+      write_batch_size: self.write_batch_size,
+      writer_version: self.writer_version,
+      page_checksum_enabled: self.page_checksum_enabled,
+      chunk_checksum_enabled: self.chunk_checksum_enabled,
+      page_index_enabled: self.page_index_enabled,
+      int96_timestamps_enabled: self.int96_timestamps_enabled,
+      audit_enabled: self.audit_enabled,
+      deterministic_output_enabled: self.deterministic_output_enabled,
+      compression_threads: self.compression_threads,
+      compression_pool: Arc::new(Mutex::new(None)),
+      created_by: match self.created_by_suffix {
+        Some(ref suffix) => format!("{} {}", self.created_by, suffix),
+        None => self.created_by
+      },
+      created_by_suffix: self.created_by_suffix,
+      key_value_metadata: self.key_value_metadata,
+      writer_info: self.writer_info,
+      default_column_properties: self.default_column_properties,
+      column_properties: self.column_properties
+    }
+  }
+
+  // ----------------------------------------------------------------------
+  // Writer properties related to a file
+
+  /// Sets writer version.
+  ///
+  /// Besides the `version` field of the file metadata, writer version controls
+  /// defaults of the column writer:
+  /// - `PARQUET_1_0` writes data pages v1, `PLAIN_DICTIONARY` for dictionary encoded
+  /// columns, and `PLAIN` encoding when no encoding is set for a column.
+  /// - `PARQUET_2_0` writes data pages v2, `PLAIN` dictionary page with
+  /// `RLE_DICTIONARY` data pages, and, when no encoding is set for a column, `RLE`
+  /// for booleans, `DELTA_BINARY_PACKED` for INT32/INT64, `DELTA_BYTE_ARRAY` for
+  /// BYTE_ARRAY and `PLAIN` for other types.
+  pub fn with_writer_version(mut self, value: WriterVersion) -> Self {
+    self.writer_version = value;
+    self
+  }
+
+  /// Sets data page size limit in bytes.
+  ///
+  /// Column writer adds a data page once encoded values exceed this limit. The limit
+  /// is checked after each mini batch of `write_batch_size` levels, so a page can be
+  /// larger than the limit.
+  pub fn with_data_page_size(mut self, value: usize) -> Self {
+    self.data_page_size = value;
+    self
+  }
+
+  /// Sets maximum number of rows in a data page.
+  ///
+  /// Column writer adds a data page once this number of rows is buffered, regardless
+  /// of the page size in bytes.
+  pub fn with_data_page_row_count_limit(mut self, value: usize) -> Self {
+    self.data_page_row_count_limit = value;
+    self
+  }
+
+  /// Sets dictionary page size limit in bytes.
+  ///
+  /// Once the PLAIN encoded dictionary of a column chunk exceeds this limit, column
+  /// writer writes out the dictionary page and encodes the following pages with the
+  /// fallback encoding set for the column, see `with_encoding`.
+  pub fn with_dictionary_page_size_limit(mut self, value: usize) -> Self {
+    self.dictionary_page_size_limit = value;
+    self
+  }
+
+  /// Sets number of levels that are processed at once when writing a batch.
+  pub fn with_write_batch_size(mut self, value: usize) -> Self {
+    self.write_batch_size = value;
+    self
+  }
+
+  /// Sets flag to write CRC32 checksum of page data into page headers.
+  ///
+  /// Checksum is computed over the page data as stored in the file (i.e. compressed),
+  /// excluding the page header, and allows readers to detect corrupt pages.
+  pub fn with_page_checksum_enabled(mut self, value: bool) -> Self {
+    self.page_checksum_enabled = value;
+    self
+  }
+
+  /// Sets flag to store CRC32 checksum of every column chunk in column chunk metadata.
+  ///
+  /// Checksum is computed over all pages of the column chunk as stored in the file,
+  /// including page headers, so that readers can verify integrity of whole column
+  /// chunks even if pages do not have checksums, see
+  /// `SerializedFileReader::with_checksum_verification`.
+  pub fn with_chunk_checksum_enabled(mut self, value: bool) -> Self {
+    self.chunk_checksum_enabled = value;
+    self
+  }
+
+  /// Sets flag to write column index and offset index of column chunks.
+  ///
+  /// Offset index stores location and first row of every data page. Column index
+  /// stores min/max values and null counts of every data page, and is written only
+  /// for columns with statistics enabled. Both are written after the row groups.
+  ///
+  /// Page index is only read back by this crate, see `file::page_index` for details.
+  pub fn with_page_index_enabled(mut self, value: bool) -> Self {
+    self.page_index_enabled = value;
+    self
+  }
+
+  /// Sets flag to write timestamps into INT96 columns.
+  ///
+  /// INT96 timestamps are deprecated, but older versions of Hive, Impala and Spark only
+  /// understand timestamps stored as INT96. When enabled, row writer encodes
+  /// `Row::Timestamp` values into INT96 columns as nanoseconds of the day and Julian
+  /// day, otherwise such values are rejected.
+  pub fn with_int96_timestamps_enabled(mut self, value: bool) -> Self {
+    self.int96_timestamps_enabled = value;
+    self
+  }
+
+  /// Sets flag to audit everything that is written, e.g. when developing new encoders.
+  ///
+  /// When enabled, column writers cross-check levels, row counts, statistics and
+  /// order and offsets of pages of every column chunk, and file writer cross-checks
+  /// offsets and value counts of column chunks of every row group. Writes fail on the
+  /// first inconsistency with an error describing it. Audit is disabled by default,
+  /// because checks add overhead to every page.
+  pub fn with_audit_enabled(mut self, value: bool) -> Self {
+    self.audit_enabled = value;
+    self
+  }
+
+  /// Sets flag to guarantee byte-identical output for identical input, e.g. for
+  /// content-addressed storage and tests that compare files.
+  ///
+  /// Writers never write timestamps, host names or other environment-dependent values,
+  /// `created_by` only contains the application name and version set with
+  /// `with_created_by`, followed by the suffix set with `with_created_by_suffix`.
+  /// Dictionaries keep values in order of their first occurrence, pages compressed by
+  /// compression threads are written in order of encoding, and thrift structures are
+  /// written with fields in order of their ids. When enabled, key/value metadata of the
+  /// file is additionally sorted by key, so that entries collected from unordered
+  /// sources, e.g. a `HashMap`, are written in the same order.
+  pub fn with_deterministic_output_enabled(mut self, value: bool) -> Self {
+    self.deterministic_output_enabled = value;
+    self
+  }
+
+  /// Sets number of worker threads that compress data pages.
+  ///
+  /// When set to a positive number, column writers encode pages on the writer thread
+  /// and hand them over to a pool of worker threads for compression through a
+  /// bounded queue, so that slow codecs, e.g. ZSTD and GZIP, do not stall encoding.
+  /// Pages are still written in order, and the file is identical to the file written
+  /// without worker threads. By default, pages are compressed on the writer thread.
+  pub fn with_compression_threads(mut self, value: usize) -> Self {
+    self.compression_threads = value;
+    self
+  }
+
+  /// Sets "created by" property.
+  pub fn with_created_by(mut self, value: String) -> Self {
+    self.created_by = value;
+    self
+  }
+
+  /// Sets suffix that is appended to the "created by" property after a space, so that
+  /// files can be traced back to the application that wrote them, e.g.
+  /// `parquet-rs version 0.1.0 ingest-service version 2.1`.
+  pub fn with_created_by_suffix(mut self, value: String) -> Self {
+    self.created_by_suffix = Some(value);
+    self
+  }
+
+  /// Adds writer information entry, e.g. name, version or host of the service that
+  /// writes the file, which is written into the file metadata as key/value metadata
+  /// entry with key `writer.info.<key>`, after entries set with
+  /// `with_key_value_metadata`. Entries are written in order of their keys, setting the
+  /// same key again replaces the value.
+  ///
+  /// Writer information is read back with
+  /// [`FileMetaData::writer_info`](`::file::metadata::FileMetaData::writer_info`).
+  pub fn with_writer_info(mut self, key: String, value: String) -> Self {
+    self.writer_info.insert(key, value);
+    self
+  }
+
+  /// Sets key/value metadata, e.g. schema provenance or job id, that is written into
+  /// the file metadata when file writer is closed.
+  pub fn with_key_value_metadata(mut self, value: Vec<KeyValue>) -> Self {
+    self.key_value_metadata = Some(value);
+    self
+  }
+
+  // ----------------------------------------------------------------------
+  // Setters for any column (global)
+
+  /// Sets encoding for any column.
+  ///
+  /// If dictionary is not enabled, this is treated as a primary encoding for all
+  /// columns. In case when dictionary is enabled for any column, this value is
+  /// considered to be a fallback encoding for that column.
+  ///
+  /// When not set, encoding is selected based on the writer version, see
+  /// `with_writer_version`.
+  ///
+  /// Dictionary encodings (`PLAIN_DICTIONARY`, `RLE_DICTIONARY`) are rejected by the
+  /// column writer, use `with_dictionary_enabled` instead.
+  pub fn with_encoding(mut self, value: Encoding) -> Self {
+    self.default_column_properties.set_encoding(value);
+    self
+  }
+
+  /// Sets compression codec for any column.
+  pub fn with_compression(mut self, value: Compression) -> Self {
+    self.default_column_properties.set_compression(value);
+    self
+  }
+
+  /// Sets compression dictionary for any column.
+  ///
+  /// Dictionary is used only by ZSTD codec and greatly improves compression ratio of
+  /// many small pages with similar content. The same dictionary must be provided to
+  /// the file reader to decompress pages.
+  pub fn with_compression_dictionary(mut self, value: Vec<u8>) -> Self {
+    self.default_column_properties.set_compression_dictionary(value);
+    self
+  }
+
+  /// Sets flag to enable/disable dictionary encoding for any column.
+  ///
+  /// Use this method to set dictionary encoding, instead of explicitly specifying
+  /// encoding in `with_encoding` method.
+  pub fn with_dictionary_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_dictionary_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable statistics for any column.
+  ///
+  /// When enabled, column writers compute min/max values and number of nulls for each
+  /// data page and column chunk.
+  pub fn with_statistics_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_statistics_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable compression of dictionary pages for any column.
+  ///
+  /// When disabled, dictionary pages are written uncompressed, while data pages are
+  /// still compressed with the column codec, so that dictionaries are read without
+  /// decompression. This is not part of the Parquet format: column chunk metadata
+  /// still declares the column codec for all pages, so files written with this flag
+  /// disabled are only readable by this crate, other readers fail to decompress the
+  /// dictionary pages.
+  pub fn with_dictionary_page_compression_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_dictionary_page_compression_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable Bloom filters for any column.
+  ///
+  /// When enabled, column writers insert every non-null value into a split block
+  /// Bloom filter of each column chunk, which is sized based on expected number of
+  /// distinct values and false positive probability. Bloom filters are not built for
+  /// BOOLEAN columns.
+  pub fn with_bloom_filter_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_bloom_filter_enabled(value);
+    self
+  }
+
+  /// Sets expected number of distinct values in a column chunk for any column.
+  pub fn with_bloom_filter_ndv(mut self, value: u64) -> Self {
+    self.default_column_properties.set_bloom_filter_ndv(value);
+    self
+  }
+
+  /// Sets false positive probability of Bloom filters for any column, must be
+  /// between 0 and 1 exclusive.
+  pub fn with_bloom_filter_fpp(mut self, value: f64) -> Self {
+    self.default_column_properties.set_bloom_filter_fpp(value);
+    self
+  }
+
+  /// Sets maximum size in bytes of min/max statistics for any column.
+  ///
+  /// Min/max values of BYTE_ARRAY columns that are compared byte-wise are truncated to
+  /// this size in column chunk statistics, page statistics and column index: min value
+  /// is truncated to its prefix, max value is truncated to its prefix with the last
+  /// byte incremented, so both remain valid bounds. UTF8 strings are truncated at
+  /// character boundaries and the last character of max value is incremented to the
+  /// next code point, so both remain valid strings. Max value that cannot be
+  /// incremented, e.g. its prefix consists of `0xFF` bytes, is not truncated.
+  /// FIXED_LEN_BYTE_ARRAY values are never truncated, since they must have the length
+  /// of the type. By default statistics are not truncated.
+  pub fn with_max_statistics_size(mut self, value: usize) -> Self {
+    self.default_column_properties.set_max_statistics_size(value);
+    self
+  }
+
+  /// Sets flag to enable/disable sharing of identical column chunks for any column.
+  ///
+  /// When enabled, a column chunk of a buffered or parallel row group whose encoded
+  /// bytes are identical to the previous column chunk of the same column, e.g. a
+  /// column with a constant value, is not written again. Its metadata references
+  /// pages of the previous column chunk instead, which is allowed by the format since
+  /// every column chunk is located by its own offsets. Column chunks written with
+  /// `SerializedRowGroupWriter` are streamed into the file and are never shared.
+  ///
+  /// Column chunks are compared after they have been encoded and compressed, so
+  /// sharing reduces file size and bytes written, but not the CPU time spent on
+  /// encoding. Encoded bytes of the last written column chunk of every column with
+  /// sharing enabled are kept in memory for comparison until the file is closed.
+  pub fn with_chunk_dedup_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_chunk_dedup_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable adaptive encoding for any column.
+  ///
+  /// When enabled, the column writer buffers values of the first data page of every
+  /// column chunk and selects encoding based on their number of distinct values, sort
+  /// order and sizes: dictionary encoding if the dictionary and indices are estimated
+  /// to be smaller than plain encoded values, otherwise, for `PARQUET_2_0` writers,
+  /// `DELTA_BINARY_PACKED` for sorted INT32/INT64 values and `DELTA_BYTE_ARRAY` for
+  /// sorted BYTE_ARRAY values, and `PLAIN` encoding in any other case. Encoding and
+  /// dictionary settings of the column are ignored.
+  pub fn with_adaptive_encoding_enabled(mut self, value: bool) -> Self {
+    self.default_column_properties.set_adaptive_encoding_enabled(value);
+    self
+  }
+
+  // ----------------------------------------------------------------------
+  // Setters for a specific column
+
+  /// Helper method to get existing or new mutable reference of column properties.
+  #[inline]
+  fn get_mut_props(&mut self, col: ColumnPath) -> &mut ColumnProperties {
+    self.column_properties.entry(col).or_insert(ColumnProperties::new())
+  }
+
+  /// Sets encoding for a column.
+  /// Takes precedence over globally defined settings.
+  ///
+  /// If dictionary is not enabled, this is treated as a primary encoding for this
+  /// column. In case when dictionary is enabled for this column, either through
+  /// global defaults or explicitly, this value is considered to be a fallback
+  /// encoding for this column.
+  ///
+  /// Column writer returns error if the encoding is not supported for the physical
+  /// type of the column, e.g. `DELTA_BINARY_PACKED` for a BYTE_ARRAY column.
+  pub fn with_column_encoding(mut self, col: ColumnPath, value: Encoding) -> Self {
+    self.get_mut_props(col).set_encoding(value);
+    self
+  }
+
+  /// Sets compression codec for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_compression(mut self, col: ColumnPath, value: Compression) -> Self {
+    self.get_mut_props(col).set_compression(value);
+    self
+  }
+
+  /// Sets compression dictionary for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_compression_dictionary(
+    mut self,
+    col: ColumnPath,
+    value: Vec<u8>
+  ) -> Self {
+    self.get_mut_props(col).set_compression_dictionary(value);
+    self
+  }
+
+  /// Sets flag to enable/disable dictionary encoding for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_dictionary_enabled(mut self, col: ColumnPath, value: bool) -> Self {
+    self.get_mut_props(col).set_dictionary_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable statistics for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_statistics_enabled(mut self, col: ColumnPath, value: bool) -> Self {
+    self.get_mut_props(col).set_statistics_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable compression of dictionary pages for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_dictionary_page_compression_enabled(
+    mut self,
+    col: ColumnPath,
+    value: bool
+  ) -> Self {
+    self.get_mut_props(col).set_dictionary_page_compression_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable Bloom filter for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_bloom_filter_enabled(
+    mut self,
+    col: ColumnPath,
+    value: bool
+  ) -> Self {
+    self.get_mut_props(col).set_bloom_filter_enabled(value);
+    self
+  }
+
+  /// Sets expected number of distinct values in a column chunk for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_bloom_filter_ndv(mut self, col: ColumnPath, value: u64) -> Self {
+    self.get_mut_props(col).set_bloom_filter_ndv(value);
+    self
+  }
+
+  /// Sets false positive probability of Bloom filter for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_bloom_filter_fpp(mut self, col: ColumnPath, value: f64) -> Self {
+    self.get_mut_props(col).set_bloom_filter_fpp(value);
+    self
+  }
+
+  /// Sets maximum size in bytes of min/max statistics for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_max_statistics_size(
+    mut self,
+    col: ColumnPath,
+    value: usize
+  ) -> Self {
+    self.get_mut_props(col).set_max_statistics_size(value);
+    self
+  }
+
+  /// Sets flag to enable/disable sharing of identical column chunks for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_chunk_dedup_enabled(mut self, col: ColumnPath, value: bool) -> Self {
+    self.get_mut_props(col).set_chunk_dedup_enabled(value);
+    self
+  }
+
+  /// Sets flag to enable/disable adaptive encoding for a column.
+  /// Takes precedence over globally defined settings.
+  pub fn with_column_adaptive_encoding_enabled(
+    mut self,
+    col: ColumnPath,
+    value: bool
+  ) -> Self {
+    self.get_mut_props(col).set_adaptive_encoding_enabled(value);
+    self
+  }
+}
+
+/// Container for column properties that can be changed as part of writer.
+///
+/// If a field is `None`, it means that no specific value has been set for this column,
+/// so some subsequent or default value must be used.
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnProperties {
+  encoding: Option<Encoding>,
+  codec: Option<Compression>,
+  compression_dictionary: Option<Arc<Vec<u8>>>,
+  dictionary_enabled: Option<bool>,
+  statistics_enabled: Option<bool>,
+  dictionary_page_compression_enabled: Option<bool>,
+  bloom_filter_enabled: Option<bool>,
+  bloom_filter_ndv: Option<u64>,
+  bloom_filter_fpp: Option<f64>,
+  max_statistics_size: Option<usize>,
+  chunk_dedup_enabled: Option<bool>,
+  adaptive_encoding_enabled: Option<bool>
+}
+
+impl ColumnProperties {
+  /// Initialise column properties with default values.
+  fn new() -> Self {
+    Self {
+      encoding: None,
+      codec: None,
+      compression_dictionary: None,
+      dictionary_enabled: None,
+      statistics_enabled: None,
+      dictionary_page_compression_enabled: None,
+      bloom_filter_enabled: None,
+      bloom_filter_ndv: None,
+      bloom_filter_fpp: None,
+      max_statistics_size: None,
+      chunk_dedup_enabled: None,
+      adaptive_encoding_enabled: None
+    }
+  }
+
+  /// Sets encoding for this column.
+  fn set_encoding(&mut self, value: Encoding) {
+    self.encoding = Some(value);
+  }
+
+  /// Sets compression codec for this column.
+  fn set_compression(&mut self, value: Compression) {
+    self.codec = Some(value);
+  }
+
+  /// Sets compression dictionary for this column.
+  fn set_compression_dictionary(&mut self, value: Vec<u8>) {
+    self.compression_dictionary = Some(Arc::new(value));
+  }
+
+  /// Sets whether or not dictionary encoding is enabled for this column.
+  fn set_dictionary_enabled(&mut self, enabled: bool) {
+    self.dictionary_enabled = Some(enabled);
+  }
+
+  /// Sets whether or not statistics are enabled for this column.
+  fn set_statistics_enabled(&mut self, enabled: bool) {
+    self.statistics_enabled = Some(enabled);
+  }
+
+  /// Sets whether or not dictionary pages are compressed for this column.
+  fn set_dictionary_page_compression_enabled(&mut self, enabled: bool) {
+    self.dictionary_page_compression_enabled = Some(enabled);
+  }
+
+  /// Sets whether or not Bloom filter is built for this column.
+  fn set_bloom_filter_enabled(&mut self, enabled: bool) {
+    self.bloom_filter_enabled = Some(enabled);
+  }
+
+  /// Sets expected number of distinct values for Bloom filter of this column.
+  fn set_bloom_filter_ndv(&mut self, value: u64) {
+    self.bloom_filter_ndv = Some(value);
+  }
+
+  /// Sets false positive probability for Bloom filter of this column.
+  fn set_bloom_filter_fpp(&mut self, value: f64) {
+    self.bloom_filter_fpp = Some(value);
+  }
+
+  /// Sets maximum size of min/max statistics for this column.
+  fn set_max_statistics_size(&mut self, value: usize) {
+    self.max_statistics_size = Some(value);
+  }
+
+  /// Sets whether or not identical column chunks are shared for this column.
+  fn set_chunk_dedup_enabled(&mut self, enabled: bool) {
+    self.chunk_dedup_enabled = Some(enabled);
+  }
+
+  /// Sets whether or not encoding is selected from values for this column.
+  fn set_adaptive_encoding_enabled(&mut self, enabled: bool) {
+    self.adaptive_encoding_enabled = Some(enabled);
+  }
+
+  /// Returns optional encoding for this column.
+  fn encoding(&self) -> Option<Encoding> {
+    self.encoding
+  }
+
+  /// Returns optional compression codec for this column.
+  fn compression(&self) -> Option<Compression> {
+    self.codec
+  }
+
+  /// Returns optional compression dictionary for this column.
+  fn compression_dictionary(&self) -> Option<Arc<Vec<u8>>> {
+    self.compression_dictionary.clone()
+  }
+
+  /// Returns `Some(true)` if dictionary encoding is enabled for this column, if
+  /// disabled then returns `Some(false)`. If result is `None`, then no setting has
+  /// been provided.
+  fn dictionary_enabled(&self) -> Option<bool> {
+    self.dictionary_enabled
+  }
+
+  /// Returns `Some(true)` if statistics are enabled for this column, if disabled then
+  /// returns `Some(false)`. If result is `None`, then no setting has been provided.
+  fn statistics_enabled(&self) -> Option<bool> {
+    self.statistics_enabled
+  }
+
+  /// Returns `Some(true)` if dictionary pages are compressed for this column, if
+  /// disabled then returns `Some(false)`. If result is `None`, then no setting has
+  /// been provided.
+  fn dictionary_page_compression_enabled(&self) -> Option<bool> {
+    self.dictionary_page_compression_enabled
+  }
+
+  /// Returns `Some(true)` if Bloom filter is enabled for this column, if disabled
+  /// then returns `Some(false)`. If result is `None`, then no setting has been
+  /// provided.
+  fn bloom_filter_enabled(&self) -> Option<bool> {
+    self.bloom_filter_enabled
+  }
+
+  /// Returns optional expected number of distinct values for this column.
+  fn bloom_filter_ndv(&self) -> Option<u64> {
+    self.bloom_filter_ndv
+  }
+
+  /// Returns optional false positive probability of Bloom filter for this column.
+  fn bloom_filter_fpp(&self) -> Option<f64> {
+    self.bloom_filter_fpp
+  }
+
+  /// Returns optional maximum size of min/max statistics for this column.
+  fn max_statistics_size(&self) -> Option<usize> {
+    self.max_statistics_size
+  }
+
+  /// Returns `Some(true)` if identical column chunks are shared for this column, if
+  /// disabled then returns `Some(false)`. If result is `None`, then no setting has
+  /// been provided.
+  fn chunk_dedup_enabled(&self) -> Option<bool> {
+    self.chunk_dedup_enabled
+  }
+
+  /// Returns `Some(true)` if encoding is selected from values for this column, if
+  /// disabled then returns `Some(false)`. If result is `None`, then no setting has
+  /// been provided.
+  fn adaptive_encoding_enabled(&self) -> Option<bool> {
+    self.adaptive_encoding_enabled
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_writer_version() {
+    assert_eq!(WriterVersion::PARQUET_1_0.as_num(), 1);
+    assert_eq!(WriterVersion::PARQUET_2_0.as_num(), 2);
+  }
+
+  #[test]
+  fn test_writer_properties_default_settings() {
+    let props = WriterProperties::builder().build();
+    assert_eq!(props.data_page_size(), DEFAULT_DATA_PAGE_SIZE);
+    assert_eq!(props.data_page_row_count_limit(), DEFAULT_DATA_PAGE_ROW_COUNT_LIMIT);
+    assert_eq!(props.dictionary_page_size_limit(), DEFAULT_DICTIONARY_PAGE_SIZE_LIMIT);
+    assert_eq!(props.write_batch_size(), DEFAULT_WRITE_BATCH_SIZE);
+    assert_eq!(props.writer_version(), DEFAULT_WRITER_VERSION);
+    assert_eq!(props.page_checksum_enabled(), DEFAULT_PAGE_CHECKSUM_ENABLED);
+    assert_eq!(props.chunk_checksum_enabled(), DEFAULT_CHUNK_CHECKSUM_ENABLED);
+    assert_eq!(props.page_index_enabled(), DEFAULT_PAGE_INDEX_ENABLED);
+    assert_eq!(props.int96_timestamps_enabled(), DEFAULT_INT96_TIMESTAMPS_ENABLED);
+    assert_eq!(props.audit_enabled(), DEFAULT_AUDIT_ENABLED);
+    assert_eq!(
+      props.deterministic_output_enabled(),
+      DEFAULT_DETERMINISTIC_OUTPUT_ENABLED
+    );
+    assert_eq!(props.compression_threads(), DEFAULT_COMPRESSION_THREADS);
+    assert!(props.compression_pool().unwrap().is_none());
+    assert_eq!(props.created_by(), DEFAULT_CREATED_BY);
+    assert_eq!(props.created_by_suffix(), None);
+    assert_eq!(props.key_value_metadata(), None);
+    assert!(props.writer_info().is_empty());
+    assert_eq!(props.file_key_value_metadata(), None);
+    assert_eq!(props.encoding(&ColumnPath::from("col")), None);
+    assert_eq!(props.compression(&ColumnPath::from("col")), DEFAULT_COMPRESSION);
+    assert_eq!(props.compression_dictionary(&ColumnPath::from("col")), None);
+    assert_eq!(
+      props.dictionary_enabled(&ColumnPath::from("col")),
+      DEFAULT_DICTIONARY_ENABLED
+    );
+    assert_eq!(
+      props.statistics_enabled(&ColumnPath::from("col")),
+      DEFAULT_STATISTICS_ENABLED
+    );
+    assert_eq!(
+      props.dictionary_page_compression_enabled(&ColumnPath::from("col")),
+      DEFAULT_DICTIONARY_PAGE_COMPRESSION_ENABLED
+    );
+    assert_eq!(
+      props.bloom_filter_enabled(&ColumnPath::from("col")),
+      DEFAULT_BLOOM_FILTER_ENABLED
+    );
+    assert_eq!(
+      props.bloom_filter_ndv(&ColumnPath::from("col")),
+      DEFAULT_BLOOM_FILTER_NDV
+    );
+    assert_eq!(
+      props.bloom_filter_fpp(&ColumnPath::from("col")),
+      DEFAULT_BLOOM_FILTER_FPP
+    );
+    assert_eq!(props.max_statistics_size(&ColumnPath::from("col")), None);
+    assert_eq!(
+      props.chunk_dedup_enabled(&ColumnPath::from("col")),
+      DEFAULT_CHUNK_DEDUP_ENABLED
+    );
+    assert_eq!(
+      props.adaptive_encoding_enabled(&ColumnPath::from("col")),
+      DEFAULT_ADAPTIVE_ENCODING_ENABLED
+    );
+  }
+
+  #[test]
+  fn test_writer_properties_builder() {
+    let props = WriterProperties::builder()
+      // File settings
+      .with_writer_version(WriterVersion::PARQUET_2_0)
+      .with_data_page_size(10)
+      .with_data_page_row_count_limit(15)
+      .with_dictionary_page_size_limit(20)
+      .with_write_batch_size(30)
+      .with_page_checksum_enabled(true)
+      .with_chunk_checksum_enabled(true)
+      .with_page_index_enabled(true)
+      .with_int96_timestamps_enabled(true)
+      .with_audit_enabled(true)
+      .with_deterministic_output_enabled(true)
+      .with_compression_threads(2)
+      .with_created_by("default".to_owned())
+      .with_created_by_suffix("suffix".to_owned())
+      .with_key_value_metadata(vec![KeyValue::new("key".to_owned(), None)])
+      .with_writer_info("service".to_owned(), "test".to_owned())
+      .with_writer_info("host".to_owned(), "a".to_owned())
+      // Global column settings
+      .with_encoding(Encoding::DELTA_BINARY_PACKED)
+      .with_compression(Compression::GZIP)
+      .with_compression_dictionary(vec![1, 2, 3])
+      .with_dictionary_enabled(false)
+      .with_statistics_enabled(false)
+      .with_dictionary_page_compression_enabled(false)
+      .with_bloom_filter_enabled(true)
+      .with_bloom_filter_ndv(100)
+      .with_bloom_filter_fpp(0.1)
+      .with_max_statistics_size(64)
+      .with_chunk_dedup_enabled(true)
+      .with_adaptive_encoding_enabled(true)
+      // Column level settings
+      .with_column_encoding(ColumnPath::from("col"), Encoding::RLE)
+      .with_column_compression(ColumnPath::from("col"), Compression::SNAPPY)
+      .with_column_compression_dictionary(ColumnPath::from("col"), vec![4, 5])
+      .with_column_dictionary_enabled(ColumnPath::from("col"), true)
+      .with_column_statistics_enabled(ColumnPath::from("col"), true)
+      .with_column_dictionary_page_compression_enabled(ColumnPath::from("col"), true)
+      .with_column_bloom_filter_enabled(ColumnPath::from("col"), false)
+      .with_column_bloom_filter_ndv(ColumnPath::from("col"), 200)
+      .with_column_bloom_filter_fpp(ColumnPath::from("col"), 0.2)
+      .with_column_max_statistics_size(ColumnPath::from("col"), 16)
+      .with_column_chunk_dedup_enabled(ColumnPath::from("col"), false)
+      .with_column_adaptive_encoding_enabled(ColumnPath::from("col"), false)
+      .build();
+
+    assert_eq!(props.writer_version(), WriterVersion::PARQUET_2_0);
+    assert_eq!(props.data_page_size(), 10);
+    assert_eq!(props.data_page_row_count_limit(), 15);
+    assert_eq!(props.dictionary_page_size_limit(), 20);
+    assert_eq!(props.write_batch_size(), 30);
+    assert_eq!(props.page_checksum_enabled(), true);
+    assert_eq!(props.chunk_checksum_enabled(), true);
+    assert_eq!(props.page_index_enabled(), true);
+    assert_eq!(props.int96_timestamps_enabled(), true);
+    assert_eq!(props.audit_enabled(), true);
+    assert_eq!(props.deterministic_output_enabled(), true);
+    assert_eq!(props.compression_threads(), 2);
+    assert_eq!(props.created_by(), "default suffix");
+    assert_eq!(props.created_by_suffix(), Some("suffix"));
+    assert_eq!(
+      props.key_value_metadata(),
+      Some(&vec![KeyValue::new("key".to_owned(), None)])
+    );
+    assert_eq!(props.writer_info().len(), 2);
+    assert_eq!(
+      props.file_key_value_metadata(),
+      Some(vec![
+        KeyValue::new("key".to_owned(), None),
+        KeyValue::new("writer.info.host".to_owned(), Some("a".to_owned())),
+        KeyValue::new("writer.info.service".to_owned(), Some("test".to_owned()))
+      ])
+    );
+
+    assert_eq!(
+      props.encoding(&ColumnPath::from("a")),
+      Some(Encoding::DELTA_BINARY_PACKED)
+    );
+    assert_eq!(props.compression(&ColumnPath::from("a")), Compression::GZIP);
+    assert_eq!(
+      props.compression_dictionary(&ColumnPath::from("a")),
+      Some(Arc::new(vec![1, 2, 3]))
+    );
+    assert_eq!(props.dictionary_enabled(&ColumnPath::from("a")), false);
+    assert_eq!(props.statistics_enabled(&ColumnPath::from("a")), false);
+    assert_eq!(props.dictionary_page_compression_enabled(&ColumnPath::from("a")), false);
+    assert_eq!(props.bloom_filter_enabled(&ColumnPath::from("a")), true);
+    assert_eq!(props.bloom_filter_ndv(&ColumnPath::from("a")), 100);
+    assert_eq!(props.bloom_filter_fpp(&ColumnPath::from("a")), 0.1);
+    assert_eq!(props.max_statistics_size(&ColumnPath::from("a")), Some(64));
+    assert_eq!(props.chunk_dedup_enabled(&ColumnPath::from("a")), true);
+    assert_eq!(props.adaptive_encoding_enabled(&ColumnPath::from("a")), true);
+
+    assert_eq!(props.encoding(&ColumnPath::from("col")), Some(Encoding::RLE));
+    assert_eq!(props.compression(&ColumnPath::from("col")), Compression::SNAPPY);
+    assert_eq!(
+      props.compression_dictionary(&ColumnPath::from("col")),
+      Some(Arc::new(vec![4, 5]))
+    );
+    assert_eq!(props.dictionary_enabled(&ColumnPath::from("col")), true);
+    assert_eq!(props.statistics_enabled(&ColumnPath::from("col")), true);
+    assert_eq!(
+      props.dictionary_page_compression_enabled(&ColumnPath::from("col")),
+      true
+    );
+    assert_eq!(props.bloom_filter_enabled(&ColumnPath::from("col")), false);
+    assert_eq!(props.bloom_filter_ndv(&ColumnPath::from("col")), 200);
+    assert_eq!(props.bloom_filter_fpp(&ColumnPath::from("col")), 0.2);
+    assert_eq!(props.max_statistics_size(&ColumnPath::from("col")), Some(16));
+    assert_eq!(props.chunk_dedup_enabled(&ColumnPath::from("col")), false);
+    assert_eq!(props.adaptive_encoding_enabled(&ColumnPath::from("col")), false);
+  }
+
+  #[test]
+  fn test_writer_properties_column_fallback_to_global() {
+    let props = WriterProperties::builder()
+      .with_compression(Compression::GZIP)
+      .with_column_encoding(ColumnPath::from("col"), Encoding::RLE)
+      .build();
+
+    // Compression is not set for the column, global value is used
+    assert_eq!(props.encoding(&ColumnPath::from("col")), Some(Encoding::RLE));
+    assert_eq!(props.compression(&ColumnPath::from("col")), Compression::GZIP);
+    assert_eq!(
+      props.dictionary_enabled(&ColumnPath::from("col")),
+      DEFAULT_DICTIONARY_ENABLED
+    );
+  }
+}
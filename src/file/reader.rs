@@ -19,21 +19,35 @@
 //! Contains file reader API, and provides methods to access file metadata, row group
 //! readers to read individual column chunks, or access record iterator.
 
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
 
-use basic::{Type, Compression, Encoding};
+use basic::{Type, Compression, Encoding, PageType as BasicPageType};
+use bloom_filter::{Sbbf, MAX_NUM_BYTES};
 use byteorder::{LittleEndian, ByteOrder};
-use column::page::{Page, PageReader};
+use crc::crc32;
+use column::page::{Page, PageReader, PageReaderMetrics};
 use column::reader::{ColumnReader, ColumnReaderImpl};
-use compression::{create_codec, Codec};
+use compression::{create_codec, create_codec_with_dictionary, Codec};
 use errors::{ParquetError, Result};
+use file::layout::{
+  page_row_ranges, ColumnChunkLayout, FileLayout, PageLayout, PageRowRange,
+  RowGroupLayout
+};
 use file::metadata::*;
+use file::page_cache::{CachedPage, PageCache, PageCacheKey};
+use file::page_index::{ColumnIndex, OffsetIndex};
+use file::progress::{Progress, ProgressCallback, ProgressTracker};
+use file::statistics;
 use parquet_format::FileMetaData as TFileMetaData;
 use parquet_format::{PageType, PageHeader};
-use record::reader::RowIter;
-use schema::types::{self, SchemaDescriptor, Type as SchemaType};
+use record::reader::{DuplicateFieldPolicy, RowIter};
+use schema::types::{self, ColumnDescPtr, ColumnPath, SchemaDescriptor};
+use schema::types::Type as SchemaType;
 use thrift::protocol::TCompactInputProtocol;
 use util::io::FileChunk;
 use util::memory::ByteBufferPtr;
@@ -50,7 +64,8 @@ pub trait FileReader {
   /// Get the total number of row groups for this file.
   fn num_row_groups(&self) -> usize;
 
-  /// Get the `i`th row group reader. Note this doesn't do bound check.
+  /// Get the `i`th row group reader.
+  /// Returns error if `i` is not less than the number of row groups.
   fn get_row_group(&self, i: usize) -> Result<Box<RowGroupReader>>;
 
   /// Get full iterator of `Row`s from a file (over all row groups).
@@ -77,11 +92,310 @@ pub trait RowGroupReader {
   /// Get value reader for the `i`th column chunk.
   fn get_column_reader(&self, i: usize) -> Result<ColumnReader>;
 
+  /// Get serialized bytes of the `i`th column chunk, i.e. all pages with their headers
+  /// as stored in the file, without decompressing or decoding them.
+  fn get_column_chunk_bytes(&self, i: usize) -> Result<Vec<u8>>;
+
+  /// Get Bloom filter of the `i`th column chunk, if it was written.
+  fn get_column_bloom_filter(&self, i: usize) -> Result<Option<Sbbf>>;
+
+  /// Get column index of the `i`th column chunk, if it was written.
+  fn get_column_index(&self, i: usize) -> Result<Option<ColumnIndex>>;
+
+  /// Get offset index of the `i`th column chunk, if it was written.
+  fn get_offset_index(&self, i: usize) -> Result<Option<OffsetIndex>>;
+
   /// Get iterator of `Row`s from this row group.
   ///
   /// Projected schema can be a subset of or equal to the file schema, when it is None,
   /// full file schema is assumed.
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter>;
+
+  /// Get iterator of column descriptors and column readers for all leaves of this row
+  /// group, in schema order.
+  ///
+  /// Projected schema can be a subset of or equal to the file schema, when it is None,
+  /// full file schema is assumed.
+  fn get_column_reader_iter(
+    &self,
+    projection: Option<SchemaType>
+  ) -> Result<ColumnReaderIter>;
+}
+
+/// Iterator over column readers of a row group.
+/// Yields column descriptor and column reader for every leaf of the (projected) schema,
+/// so that columns can be processed without knowing their indices in the file.
+pub struct ColumnReaderIter<'a> {
+  row_group_reader: &'a RowGroupReader,
+  column_indices: Vec<usize>,
+  pos: usize
+}
+
+impl<'a> ColumnReaderIter<'a> {
+  /// Creates new iterator over column readers of `row_group_reader`.
+  /// Returns error if projection is not part of the row group schema.
+  pub fn new(
+    row_group_reader: &'a RowGroupReader,
+    projection: Option<SchemaType>
+  ) -> Result<Self> {
+    let metadata = row_group_reader.metadata();
+    let column_indices = match projection {
+      Some(projection) => {
+        if !metadata.schema_descr().root_schema().check_contains(&projection) {
+          return Err(general_err!("Root schema does not contain projection"));
+        }
+        // Map leaves of the projection to the column chunks of the row group
+        let mut paths: HashMap<&ColumnPath, usize> = HashMap::new();
+        for (i, column) in metadata.columns().iter().enumerate() {
+          paths.insert(column.column_path(), i);
+        }
+        let proj_descr = SchemaDescriptor::new(Rc::new(projection));
+        let mut column_indices = Vec::with_capacity(proj_descr.num_columns());
+        for column in proj_descr.columns() {
+          match paths.get(column.path()) {
+            Some(&i) => column_indices.push(i),
+            None => return Err(general_err!(
+              "Column {} is not found in row group",
+              column.path()
+            ))
+          }
+        }
+        column_indices
+      },
+      None => (0..row_group_reader.num_columns()).collect()
+    };
+
+    Ok(Self {
+      row_group_reader: row_group_reader,
+      column_indices: column_indices,
+      pos: 0
+    })
+  }
+}
+
+impl<'a> Iterator for ColumnReaderIter<'a> {
+  type Item = Result<(ColumnDescPtr, ColumnReader)>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.pos >= self.column_indices.len() {
+      return None;
+    }
+    let i = self.column_indices[self.pos];
+    self.pos += 1;
+    let descr = self.row_group_reader.metadata().schema_descr().column(i);
+    Some(self.row_group_reader.get_column_reader(i).map(|reader| (descr, reader)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.column_indices.len() - self.pos;
+    (len, Some(len))
+  }
+}
+
+impl<'a> ExactSizeIterator for ColumnReaderIter<'a> {}
+
+/// Policy that defines how page readers handle pages of types that are not supported,
+/// e.g. index pages interleaved with data pages in a column chunk.
+///
+/// Skipped pages never contribute to the number of values read from a column chunk.
+#[derive(Clone)]
+pub enum UnknownPagePolicy {
+  /// Skips unknown pages silently. This is the default policy.
+  Skip,
+  /// Skips unknown pages and reports each of them to the callback with page type and
+  /// compressed page size in bytes.
+  Warn(Rc<Fn(BasicPageType, usize)>),
+  /// Returns an error when an unknown page is encountered.
+  Error
+}
+
+impl Default for UnknownPagePolicy {
+  fn default() -> Self {
+    UnknownPagePolicy::Skip
+  }
+}
+
+/// Reader of files that store column chunks outside of the Parquet file, i.e. files
+/// referenced by `file_path` of column chunk metadata.
+///
+/// `file_path` is read from the file footer and cannot be trusted, so implementations
+/// must make sure that it only refers to files that are supposed to be read.
+pub trait ChunkReader {
+  /// Returns path of the file `file_path`, which is also used as key of cached pages of
+  /// its column chunks. Returns an error if the file must not be read.
+  fn resolve(&self, file_path: &str) -> Result<PathBuf>;
+
+  /// Opens file `file_path` to read column chunks from it.
+  fn open(&self, file_path: &str) -> Result<File> {
+    let path = self.resolve(file_path)?;
+    File::open(&path).map_err(|e| {
+      general_err!("Could not open file \"{}\" of column chunk: {}", path.display(), e)
+    })
+  }
+}
+
+/// Chunk reader that opens files relative to a directory, usually the directory of the
+/// Parquet file.
+///
+/// Only relative paths without `..` components are accepted, so files outside of the
+/// directory cannot be read.
+pub struct DirChunkReader {
+  dir: PathBuf
+}
+
+impl DirChunkReader {
+  /// Creates new chunk reader for files within directory `dir`.
+  pub fn new(dir: PathBuf) -> Self {
+    Self { dir: dir }
+  }
+}
+
+impl ChunkReader for DirChunkReader {
+  fn resolve(&self, file_path: &str) -> Result<PathBuf> {
+    let path = Path::new(file_path);
+    let is_contained = path.components().all(|component| match component {
+      Component::Normal(_) | Component::CurDir => true,
+      Component::ParentDir | Component::RootDir | Component::Prefix(_) => false
+    });
+    if file_path.is_empty() || path.is_absolute() || !is_contained {
+      return Err(general_err!(
+        "Invalid column chunk file path \"{}\", must be relative to directory \"{}\"",
+        file_path,
+        self.dir.display()
+      ));
+    }
+    Ok(self.dir.join(path))
+  }
+}
+
+/// Default maximum compressed or uncompressed size of a page in bytes.
+pub const DEFAULT_MAX_PAGE_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Limits for page sizes declared in page headers.
+///
+/// Page readers check page headers against these limits before any buffer for page
+/// data is allocated, so corrupt or malicious files (e.g. decompression bombs) are
+/// rejected with an error instead of exhausting memory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageSizeLimits {
+  max_page_size: usize,
+  max_compression_ratio: Option<usize>
+}
+
+impl PageSizeLimits {
+  /// Creates limits with default maximum page size and without compression ratio
+  /// check.
+  pub fn new() -> Self {
+    Self { max_page_size: DEFAULT_MAX_PAGE_SIZE, max_compression_ratio: None }
+  }
+
+  /// Sets maximum compressed or uncompressed size of a page in bytes.
+  pub fn with_max_page_size(mut self, value: usize) -> Self {
+    self.max_page_size = value;
+    self
+  }
+
+  /// Sets maximum ratio of uncompressed size to compressed size of a compressed page.
+  ///
+  /// Note that highly compressible data (e.g. long runs of the same value) can
+  /// legitimately have a large ratio, so this check is disabled by default.
+  pub fn with_max_compression_ratio(mut self, value: usize) -> Self {
+    self.max_compression_ratio = Some(value);
+    self
+  }
+
+  /// Returns maximum compressed or uncompressed size of a page in bytes.
+  pub fn max_page_size(&self) -> usize {
+    self.max_page_size
+  }
+
+  /// Returns maximum ratio of uncompressed size to compressed size, if set.
+  pub fn max_compression_ratio(&self) -> Option<usize> {
+    self.max_compression_ratio
+  }
+
+  /// Checks sizes of a page, where `num_uncompressed_bytes` is the number of bytes
+  /// stored without compression (levels of data page v2), and `is_compressed`
+  /// indicates whether or not rest of the page is going to be decompressed.
+  fn check(
+    &self,
+    page_header: &PageHeader,
+    num_uncompressed_bytes: usize,
+    is_compressed: bool
+  ) -> Result<()> {
+    let page_type = BasicPageType::from(page_header.type_);
+    let compressed_size = page_header.compressed_page_size;
+    let uncompressed_size = page_header.uncompressed_page_size;
+    if compressed_size < 0 || uncompressed_size < 0 {
+      return Err(general_err!(
+        "Invalid {} page sizes: compressed size {}, uncompressed size {}",
+        page_type,
+        compressed_size,
+        uncompressed_size
+      ));
+    }
+
+    let compressed_size = compressed_size as usize;
+    let uncompressed_size = uncompressed_size as usize;
+    let page_size = ::std::cmp::max(compressed_size, uncompressed_size);
+    if page_size > self.max_page_size {
+      return Err(general_err!(
+        "Size of {} page {} exceeds maximum page size {}",
+        page_type,
+        page_size,
+        self.max_page_size
+      ));
+    }
+
+    if num_uncompressed_bytes > compressed_size ||
+        num_uncompressed_bytes > uncompressed_size {
+      return Err(general_err!(
+        "Invalid {} page sizes: levels size {} exceeds compressed size {} or \
+         uncompressed size {}",
+        page_type,
+        num_uncompressed_bytes,
+        compressed_size,
+        uncompressed_size
+      ));
+    }
+
+    if let Some(max_ratio) = self.max_compression_ratio {
+      let compressed_len = compressed_size - num_uncompressed_bytes;
+      let uncompressed_len = uncompressed_size - num_uncompressed_bytes;
+      if is_compressed && uncompressed_len > compressed_len.saturating_mul(max_ratio) {
+        return Err(general_err!(
+          "Compression ratio of {} page exceeds maximum ratio {} \
+           (compressed size {}, uncompressed size {})",
+          page_type,
+          max_ratio,
+          compressed_len,
+          uncompressed_len
+        ));
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Default for PageSizeLimits {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Compression dictionaries used to decompress column chunks, set for all columns or
+/// for a specific column.
+#[derive(Clone, Default)]
+struct CompressionDictionaries {
+  default: Option<Arc<Vec<u8>>>,
+  columns: HashMap<ColumnPath, Arc<Vec<u8>>>
+}
+
+impl CompressionDictionaries {
+  /// Returns compression dictionary for a column, if set.
+  fn get(&self, col: &ColumnPath) -> Option<Arc<Vec<u8>>> {
+    self.columns.get(col).or(self.default.as_ref()).cloned()
+  }
 }
 
 /// A thin wrapper on `T: Read` to be used by Thrift transport. Write is not supported.
@@ -105,13 +419,24 @@ impl<'a, T: 'a + Read> Read for TMemoryBuffer<'a, T> {
 // ----------------------------------------------------------------------
 // Serialized impl for file & row group readers
 
-const FOOTER_SIZE: usize = 8;
-const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
+/// Size of the file footer: metadata length and magic bytes.
+pub const FOOTER_SIZE: usize = 8;
+/// Magic bytes at the start and at the end of a Parquet file.
+pub const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
 
 /// A serialized implementation for Parquet [`FileReader`].
 pub struct SerializedFileReader {
   buf: BufReader<File>,
-  metadata: ParquetMetaDataPtr
+  metadata: ParquetMetaDataPtr,
+  unknown_page_policy: UnknownPagePolicy,
+  page_size_limits: PageSizeLimits,
+  compression_dictionaries: CompressionDictionaries,
+  progress: Option<ProgressTracker>,
+  duplicate_field_policy: DuplicateFieldPolicy,
+  path: Option<Rc<PathBuf>>,
+  chunk_reader: Option<Rc<ChunkReader>>,
+  page_cache: Option<PageCache>,
+  checksum_verification: bool
 }
 
 impl SerializedFileReader {
@@ -120,7 +445,157 @@ impl SerializedFileReader {
   pub fn new(file: File) -> Result<Self> {
     let mut buf = BufReader::new(file);
     let metadata = Self::parse_metadata(&mut buf)?;
-    Ok(Self { buf: buf, metadata: Rc::new(metadata) })
+    Ok(Self {
+      buf: buf,
+      metadata: Rc::new(metadata),
+      unknown_page_policy: UnknownPagePolicy::default(),
+      page_size_limits: PageSizeLimits::default(),
+      compression_dictionaries: CompressionDictionaries::default(),
+      progress: None,
+      duplicate_field_policy: DuplicateFieldPolicy::default(),
+      path: None,
+      chunk_reader: None,
+      page_cache: None,
+      checksum_verification: false
+    })
+  }
+
+  /// Opens and creates file reader from a Parquet file at `path`.
+  ///
+  /// Unlike `new`, returned errors include the path and the stage that failed, e.g.
+  /// `Could not read metadata of file "data.parquet": Invalid Parquet file. Corrupt
+  /// footer`.
+  pub fn try_from_path(path: &Path) -> Result<Self> {
+    let file = File::open(path).map_err(|e| {
+      general_err!("Could not open file \"{}\": {}", path.display(), e)
+    })?;
+    let mut reader = Self::new(file).map_err(|e| {
+      let message = match e {
+        ParquetError::General(message) |
+        ParquetError::NYI(message) |
+        ParquetError::EOF(message) => message
+      };
+      general_err!("Could not read metadata of file \"{}\": {}", path.display(), message)
+    })?;
+    reader.path = Some(Rc::new(path.to_path_buf()));
+    Ok(reader)
+  }
+
+  /// Opens and creates file reader from a Parquet file at `path`.
+  /// See `try_from_path` for details.
+  pub fn try_from(path: &str) -> Result<Self> {
+    Self::try_from_path(Path::new(path))
+  }
+
+  /// Sets policy for pages of unknown types, which is applied to all page readers
+  /// created from this file reader, and returns itself.
+  pub fn with_unknown_page_policy(mut self, policy: UnknownPagePolicy) -> Self {
+    self.unknown_page_policy = policy;
+    self
+  }
+
+  /// Sets limits for page sizes, which are applied to all page readers created from
+  /// this file reader, and returns itself.
+  pub fn with_page_size_limits(mut self, limits: PageSizeLimits) -> Self {
+    self.page_size_limits = limits;
+    self
+  }
+
+  /// Sets compression dictionary for all columns and returns itself.
+  ///
+  /// Dictionary is used only for column chunks compressed with ZSTD, and must be the
+  /// same as the one used to write the file.
+  pub fn with_compression_dictionary(mut self, value: Vec<u8>) -> Self {
+    self.compression_dictionaries.default = Some(Arc::new(value));
+    self
+  }
+
+  /// Sets compression dictionary for a column and returns itself.
+  /// Takes precedence over the dictionary set for all columns.
+  pub fn with_column_compression_dictionary(
+    mut self,
+    col: ColumnPath,
+    value: Vec<u8>
+  ) -> Self {
+    self.compression_dictionaries.columns.insert(col, Arc::new(value));
+    self
+  }
+
+  /// Sets callback that reports progress of reading this file, and returns itself.
+  ///
+  /// Callback is invoked with bytes read after every page read by any page reader
+  /// created from this file reader, and with rows of a row group once the row iterator
+  /// has read all rows of that row group.
+  pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+    self.progress = Some(ProgressTracker::new(callback));
+    self
+  }
+
+  /// Returns progress accumulated so far, or `None` if progress callback is not set.
+  pub fn progress(&self) -> Option<Progress> {
+    self.progress.as_ref().map(|progress| progress.progress())
+  }
+
+  /// Sets policy for sibling fields with identical names, which is applied to all row
+  /// iterators created from this file reader, and returns itself.
+  pub fn with_duplicate_field_policy(mut self, policy: DuplicateFieldPolicy) -> Self {
+    self.duplicate_field_policy = policy;
+    self
+  }
+
+  /// Sets cache of decompressed pages, which is used by all page readers created from
+  /// this file reader, and returns itself. Cache can be shared with readers of other
+  /// files.
+  ///
+  /// Pages are keyed by path of the file, so returns an error if this reader was not
+  /// opened with `try_from_path` or `try_from`.
+  pub fn with_page_cache(mut self, cache: PageCache) -> Result<Self> {
+    if self.path.is_none() {
+      return Err(general_err!("Page cache requires a file reader opened from a path"));
+    }
+    self.page_cache = Some(cache);
+    Ok(self)
+  }
+
+  /// Sets reader of files that store column chunks outside of this file, i.e. files
+  /// referenced by `file_path` of column chunk metadata, and returns itself.
+  ///
+  /// Reading such column chunks is disabled by default, since `file_path` comes from
+  /// the file footer and cannot be trusted; without a chunk reader they are rejected
+  /// with an error.
+  pub fn with_chunk_reader(mut self, chunk_reader: Rc<ChunkReader>) -> Self {
+    self.chunk_reader = Some(chunk_reader);
+    self
+  }
+
+  /// Enables reading column chunks stored in files within the directory of this file,
+  /// see [`DirChunkReader`](`DirChunkReader`), and returns itself.
+  ///
+  /// Returns an error if this reader was not opened with `try_from_path` or
+  /// `try_from`.
+  pub fn with_external_chunks_enabled(self) -> Result<Self> {
+    let dir = match self.path {
+      Some(ref path) => path.parent().unwrap_or(Path::new("")).to_path_buf(),
+      None => return Err(general_err!(
+        "External column chunks require a file reader opened from a path"
+      ))
+    };
+    Ok(self.with_chunk_reader(Rc::new(DirChunkReader::new(dir))))
+  }
+
+  /// Sets flag to verify checksums of column chunks, and returns itself.
+  ///
+  /// When enabled, every column chunk that has a checksum in its metadata, see
+  /// `with_chunk_checksum_enabled` in writer properties, is read and verified before
+  /// its pages are returned by a page reader, which fails with an error if the
+  /// checksum does not match. Column chunks without checksums are not verified.
+  ///
+  /// Row iterators cannot return errors once created, so `get_row_iter` verifies
+  /// column chunks of all row groups read by the iterator upfront, and returns an
+  /// error instead of the iterator if a checksum does not match.
+  pub fn with_checksum_verification(mut self, value: bool) -> Self {
+    self.checksum_verification = value;
+    self
   }
 
   // Layout of Parquet file
@@ -175,11 +650,65 @@ impl SerializedFileReader {
       t_file_metadata.version,
       t_file_metadata.num_rows,
       t_file_metadata.created_by,
+      t_file_metadata.key_value_metadata
+        .map(|kvs| kvs.into_iter().map(KeyValue::from_thrift).collect()),
       schema,
       schema_descr
     );
     Ok(ParquetMetaData::new(file_metadata, row_groups))
   }
+
+  /// Returns physical layout of this file, collected from page headers of every
+  /// column chunk. Page data is skipped without decompression or decoding.
+  pub fn layout(&self) -> Result<FileLayout> {
+    let mut row_groups = Vec::with_capacity(self.num_row_groups());
+    for i in 0..self.num_row_groups() {
+      let f = self.buf.get_ref().try_clone()?;
+      let row_group_reader = SerializedRowGroupReader::new(f, self.metadata.row_group(i))
+        .with_path(self.path.clone())
+        .with_chunk_reader(self.chunk_reader.clone());
+      row_groups.push(row_group_reader.layout()?);
+    }
+    Ok(FileLayout::new(row_groups))
+  }
+
+  /// Returns data pages of the `column`th column chunk in row group `row_group`
+  /// together with the range of rows within the row group each page covers.
+  pub fn page_row_ranges(
+    &self,
+    row_group: usize,
+    column: usize
+  ) -> Result<Vec<PageRowRange>> {
+    if row_group >= self.num_row_groups() {
+      return Err(general_err!(
+        "Row group index {} out of bounds, file has {} row groups",
+        row_group,
+        self.num_row_groups()
+      ));
+    }
+    let f = self.buf.get_ref().try_clone()?;
+    SerializedRowGroupReader::new(f, self.metadata.row_group(row_group))
+      .with_path(self.path.clone())
+      .with_chunk_reader(self.chunk_reader.clone())
+      .get_column_page_row_ranges(column)
+  }
+
+  /// Verifies checksum of the `column`th column chunk in row group `row_group`, see
+  /// `SerializedRowGroupReader::verify_column_checksum`.
+  pub fn verify_column_checksum(&self, row_group: usize, column: usize) -> Result<bool> {
+    if row_group >= self.num_row_groups() {
+      return Err(general_err!(
+        "Row group index {} out of bounds, file has {} row groups",
+        row_group,
+        self.num_row_groups()
+      ));
+    }
+    let f = self.buf.get_ref().try_clone()?;
+    SerializedRowGroupReader::new(f, self.metadata.row_group(row_group))
+      .with_path(self.path.clone())
+      .with_chunk_reader(self.chunk_reader.clone())
+      .verify_column_checksum(column)
+  }
 }
 
 impl FileReader for SerializedFileReader {
@@ -192,28 +721,322 @@ impl FileReader for SerializedFileReader {
   }
 
   fn get_row_group(&self, i: usize) -> Result<Box<RowGroupReader>> {
+    if i >= self.num_row_groups() {
+      return Err(general_err!(
+        "Row group index {} out of bounds, file has {} row groups",
+        i,
+        self.num_row_groups()
+      ));
+    }
     let row_group_metadata = self.metadata.row_group(i);
     // Row groups should be processed sequentially.
     let f = self.buf.get_ref().try_clone()?;
-    Ok(Box::new(SerializedRowGroupReader::new(f, row_group_metadata)))
+    let row_group_reader = SerializedRowGroupReader::new(f, row_group_metadata)
+      .with_unknown_page_policy(self.unknown_page_policy.clone())
+      .with_page_size_limits(self.page_size_limits)
+      .with_compression_dictionaries(self.compression_dictionaries.clone())
+      .with_progress(self.progress.clone())
+      .with_duplicate_field_policy(self.duplicate_field_policy)
+      .with_path(self.path.clone())
+      .with_chunk_reader(self.chunk_reader.clone())
+      .with_page_cache(self.page_cache.clone())
+      .with_checksum_verification(self.checksum_verification);
+    Ok(Box::new(row_group_reader))
   }
 
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter> {
-    RowIter::from_file(projection, self)
+    let iter = RowIter::from_file(projection, self)?;
+    if self.checksum_verification {
+      for i in 0..self.num_row_groups() {
+        let metadata = self.metadata.row_group(i);
+        for j in row_iter_columns(&metadata, iter.schema_descr()) {
+          self.verify_column_checksum(i, j)?;
+        }
+      }
+    }
+    iter.with_progress(self.progress.clone())
+      .with_duplicate_field_policy(self.duplicate_field_policy)
   }
 }
 
 /// A serialized implementation for Parquet [`RowGroupReader`].
+///
+/// Column chunks with `file_path` set in metadata are read from that file with the
+/// [`ChunkReader`](`ChunkReader`) of the file reader, and are rejected if it is not set.
+/// Column and offset indexes and Bloom filters are always read from this file.
 pub struct SerializedRowGroupReader {
   buf: BufReader<File>,
-  metadata: RowGroupMetaDataPtr
+  metadata: RowGroupMetaDataPtr,
+  unknown_page_policy: UnknownPagePolicy,
+  page_size_limits: PageSizeLimits,
+  compression_dictionaries: CompressionDictionaries,
+  progress: Option<ProgressTracker>,
+  duplicate_field_policy: DuplicateFieldPolicy,
+  path: Option<Rc<PathBuf>>,
+  chunk_reader: Option<Rc<ChunkReader>>,
+  page_cache: Option<PageCache>,
+  checksum_verification: bool
 }
 
 impl SerializedRowGroupReader {
   /// Creates new row group reader from a file and row group metadata.
   fn new(file: File, metadata: RowGroupMetaDataPtr) -> Self {
     let buf = BufReader::new(file);
-    Self { buf, metadata }
+    Self {
+      buf,
+      metadata,
+      unknown_page_policy: UnknownPagePolicy::default(),
+      page_size_limits: PageSizeLimits::default(),
+      compression_dictionaries: CompressionDictionaries::default(),
+      progress: None,
+      duplicate_field_policy: DuplicateFieldPolicy::default(),
+      path: None,
+      chunk_reader: None,
+      page_cache: None,
+      checksum_verification: false
+    }
+  }
+
+  /// Sets policy for pages of unknown types and returns itself.
+  fn with_unknown_page_policy(mut self, policy: UnknownPagePolicy) -> Self {
+    self.unknown_page_policy = policy;
+    self
+  }
+
+  /// Sets limits for page sizes and returns itself.
+  fn with_page_size_limits(mut self, limits: PageSizeLimits) -> Self {
+    self.page_size_limits = limits;
+    self
+  }
+
+  /// Sets compression dictionaries and returns itself.
+  fn with_compression_dictionaries(mut self, value: CompressionDictionaries) -> Self {
+    self.compression_dictionaries = value;
+    self
+  }
+
+  /// Sets progress tracker of the file reader and returns itself.
+  fn with_progress(mut self, progress: Option<ProgressTracker>) -> Self {
+    self.progress = progress;
+    self
+  }
+
+  /// Sets policy for sibling fields with identical names and returns itself.
+  fn with_duplicate_field_policy(mut self, policy: DuplicateFieldPolicy) -> Self {
+    self.duplicate_field_policy = policy;
+    self
+  }
+
+  /// Sets path of the file, used as key of cached pages, and returns itself.
+  fn with_path(mut self, path: Option<Rc<PathBuf>>) -> Self {
+    self.path = path;
+    self
+  }
+
+  /// Sets reader of files that store column chunks and returns itself.
+  fn with_chunk_reader(mut self, chunk_reader: Option<Rc<ChunkReader>>) -> Self {
+    self.chunk_reader = chunk_reader;
+    self
+  }
+
+  /// Sets page cache and returns itself. Cache is only used if path of the file is set.
+  fn with_page_cache(mut self, page_cache: Option<PageCache>) -> Self {
+    self.page_cache = page_cache;
+    self
+  }
+
+  /// Sets flag to verify checksums of column chunks and returns itself.
+  fn with_checksum_verification(mut self, value: bool) -> Self {
+    self.checksum_verification = value;
+    self
+  }
+
+  /// Returns an error if `i` is not a valid index of a column chunk in this row group.
+  fn check_column_index(&self, i: usize) -> Result<()> {
+    if i >= self.num_columns() {
+      return Err(general_err!(
+        "Column index {} out of bounds, row group has {} columns",
+        i,
+        self.num_columns()
+      ));
+    }
+    Ok(())
+  }
+
+  /// Returns physical layout of this row group.
+  pub fn layout(&self) -> Result<RowGroupLayout> {
+    let mut columns = Vec::with_capacity(self.num_columns());
+    for i in 0..self.num_columns() {
+      columns.push(self.get_column_layout(i)?);
+    }
+    Ok(RowGroupLayout::new(self.metadata.num_rows(), columns))
+  }
+
+  /// Returns physical layout of the `i`th column chunk, i.e. size, number of values
+  /// and encoding of every page. Page data is skipped without being decompressed.
+  pub fn get_column_layout(&self, i: usize) -> Result<ColumnChunkLayout> {
+    self.check_column_index(i)?;
+    let col = self.metadata.column(i);
+    let mut file_chunk = self.get_column_chunk(i)?;
+    let mut pages = Vec::new();
+    let mut seen_num_values = 0;
+    while seen_num_values < col.num_values() {
+      let offset = file_chunk.pos();
+      let page_header = read_page_header(&mut file_chunk)?;
+      let header_size = file_chunk.pos() - offset;
+
+      let (num_values, encoding, num_rows) = match page_header.type_ {
+        PageType::DICTIONARY_PAGE => {
+          let header = page_header.dictionary_page_header.as_ref()
+            .ok_or(general_err!("Missing dictionary page header"))?;
+          (header.num_values, Some(Encoding::from(header.encoding)), None)
+        },
+        PageType::DATA_PAGE => {
+          let header = page_header.data_page_header.as_ref()
+            .ok_or(general_err!("Missing data page header"))?;
+          seen_num_values += header.num_values as i64;
+          (header.num_values, Some(Encoding::from(header.encoding)), None)
+        },
+        PageType::DATA_PAGE_V2 => {
+          let header = page_header.data_page_header_v2.as_ref()
+            .ok_or(general_err!("Missing data page v2 header"))?;
+          seen_num_values += header.num_values as i64;
+          let num_rows = Some(header.num_rows as u32);
+          (header.num_values, Some(Encoding::from(header.encoding)), num_rows)
+        },
+        _ => (0, None, None)
+      };
+
+      // Skip page data, we only need the header
+      let compressed_size = page_header.compressed_page_size as u64;
+      let bytes_skipped =
+        io::copy(&mut (&mut file_chunk).take(compressed_size), &mut io::sink())?;
+      if bytes_skipped != compressed_size {
+        return Err(eof_err!("Unexpected end of column chunk"));
+      }
+
+      pages.push(PageLayout {
+        page_type: ::basic::PageType::from(page_header.type_),
+        offset: offset as u64,
+        header_size: header_size,
+        compressed_size: page_header.compressed_page_size as usize,
+        uncompressed_size: page_header.uncompressed_page_size as usize,
+        num_values: num_values as u32,
+        encoding: encoding,
+        crc: page_header.crc.map(|crc| crc as u32),
+        num_rows: num_rows
+      });
+    }
+    Ok(ColumnChunkLayout::new(col.column_path().clone(), col.compression(), pages))
+  }
+
+  /// Verifies checksum of the `i`th column chunk, stored in column chunk metadata when
+  /// the file is written with `with_chunk_checksum_enabled` in writer properties.
+  ///
+  /// Returns `true` if checksum matches bytes of the column chunk in the file, and
+  /// `false` if column chunk does not have a checksum. Returns error if checksum does
+  /// not match.
+  pub fn verify_column_checksum(&self, i: usize) -> Result<bool> {
+    self.check_column_index(i)?;
+    let expected = match self.metadata.column(i).checksum() {
+      Some(checksum) => checksum,
+      None => return Ok(false)
+    };
+    let actual = crc32::checksum_ieee(&self.get_column_chunk_bytes(i)?);
+    if actual != expected {
+      return Err(general_err!(
+        "Checksum mismatch of column {}: expected {}, found {}",
+        self.metadata.column(i).column_path(),
+        expected,
+        actual
+      ));
+    }
+    Ok(true)
+  }
+
+  /// Returns data pages of the `i`th column chunk together with the range of rows
+  /// each page covers, using the offset index if the column chunk has one.
+  /// See [`page_row_ranges`](`::file::layout::page_row_ranges`) for details.
+  pub fn get_column_page_row_ranges(&self, i: usize) -> Result<Vec<PageRowRange>> {
+    let layout = self.get_column_layout(i)?;
+    let offset_index = self.get_offset_index(i)?;
+    page_row_ranges(
+      layout.pages(),
+      offset_index.as_ref(),
+      self.metadata.column(i).column_descr().max_rep_level(),
+      self.metadata.num_rows()
+    )
+  }
+
+  /// Returns path of the file that stores column chunk `col`, i.e. `file_path` of the
+  /// column chunk resolved by the chunk reader, or path of this file if `file_path` is
+  /// not set. Returns `None` if path of this file is unknown and column chunk is stored
+  /// in this file.
+  fn get_column_path(&self, col: &ColumnChunkMetaData) -> Result<Option<Rc<PathBuf>>> {
+    match col.file_path() {
+      Some(file_path) => {
+        let path = self.get_chunk_reader(col, file_path)?.resolve(file_path)?;
+        Ok(Some(Rc::new(path)))
+      },
+      None => Ok(self.path.clone())
+    }
+  }
+
+  /// Returns chunk reader to read column chunk `col` stored in file `file_path`, or an
+  /// error if reading external column chunks is not enabled.
+  fn get_chunk_reader(
+    &self,
+    col: &ColumnChunkMetaData,
+    file_path: &str
+  ) -> Result<&Rc<ChunkReader>> {
+    self.chunk_reader.as_ref().ok_or_else(|| general_err!(
+      "Column {} is stored in file \"{}\", but reading external column chunks is not \
+       enabled",
+      col.column_path(),
+      file_path
+    ))
+  }
+
+  /// Returns file chunk that covers exactly the bytes of the `i`th column chunk, which
+  /// is opened from `file_path` of the column chunk, if set.
+  fn get_column_chunk(&self, i: usize) -> Result<FileChunk> {
+    let col = self.metadata.column(i);
+    let mut col_start = col.data_page_offset();
+    if col.has_dictionary_page() {
+      col_start = col.dictionary_page_offset().unwrap();
+    }
+    let (col_start, col_length) = (col_start as usize, col.compressed_size() as usize);
+    match col.file_path() {
+      Some(file_path) => {
+        let file = self.get_chunk_reader(col, file_path)?.open(file_path)?;
+        Ok(FileChunk::new(&file, col_start, col_length))
+      },
+      None => Ok(FileChunk::new(self.buf.get_ref(), col_start, col_length))
+    }
+  }
+
+  /// Reads serialized page index `name` of column chunk `col`, located at `offset`
+  /// with `length` bytes.
+  fn read_index(
+    &self,
+    name: &str,
+    col: &ColumnChunkMetaData,
+    offset: i64,
+    length: i32
+  ) -> Result<Vec<u8>> {
+    if offset < 0 || length < 0 {
+      return Err(general_err!(
+        "Invalid {} location of column {}: offset {}, length {}",
+        name,
+        col.column_path(),
+        offset,
+        length
+      ));
+    }
+    let mut buf = vec![0; length as usize];
+    FileChunk::new(self.buf.get_ref(), offset as usize, length as usize)
+      .read_exact(&mut buf)?;
+    Ok(buf)
   }
 }
 
@@ -228,20 +1051,29 @@ impl RowGroupReader for SerializedRowGroupReader {
 
   // TODO: fix PARQUET-816
   fn get_column_page_reader(&self, i: usize) -> Result<Box<PageReader>> {
-    let col = self.metadata.column(i);
-    let mut col_start = col.data_page_offset();
-    if col.has_dictionary_page() {
-      col_start = col.dictionary_page_offset().unwrap();
+    self.check_column_index(i)?;
+    if self.checksum_verification {
+      self.verify_column_checksum(i)?;
     }
-    let col_length = col.compressed_size();
-    let file_chunk = FileChunk::new(
-      self.buf.get_ref(), col_start as usize, col_length as usize);
+    let col = self.metadata.column(i);
+    let file_chunk = self.get_column_chunk(i)?;
+    let dictionary = self.compression_dictionaries.get(col.column_path());
+    let decompressor = create_codec_with_dictionary(col.compression(), dictionary)?;
     let page_reader = SerializedPageReader::new(
-      file_chunk, col.num_values(), col.compression())?;
+      file_chunk, col.num_values(), col.compression(), col.column_type())?
+      .with_unknown_page_policy(self.unknown_page_policy.clone())
+      .with_page_size_limits(self.page_size_limits)
+      .with_decompressor(decompressor)
+      .with_progress(self.progress.clone())
+      .with_page_cache(self.page_cache.clone().and_then(|cache| {
+        // Pages are keyed by path of the file that stores the column chunk
+        self.get_column_path(col).ok().and_then(|path| path).map(|path| (cache, path))
+      }));
     Ok(Box::new(page_reader))
   }
 
   fn get_column_reader(&self, i: usize) -> Result<ColumnReader> {
+    self.check_column_index(i)?;
     let schema_descr = self.metadata.schema_descr();
     let col_descr = schema_descr.column(i);
     let col_page_reader = self.get_column_page_reader(i)?;
@@ -266,8 +1098,84 @@ impl RowGroupReader for SerializedRowGroupReader {
     Ok(col_reader)
   }
 
+  fn get_column_chunk_bytes(&self, i: usize) -> Result<Vec<u8>> {
+    self.check_column_index(i)?;
+    let col = self.metadata.column(i);
+    let col_start = col.dictionary_page_offset().unwrap_or(col.data_page_offset());
+    if col_start < 0 || col.compressed_size() < 0 {
+      return Err(general_err!(
+        "Invalid location of column {}: offset {}, length {}",
+        col.column_path(),
+        col_start,
+        col.compressed_size()
+      ));
+    }
+    let mut buf = vec![0; col.compressed_size() as usize];
+    self.get_column_chunk(i)?.read_exact(&mut buf)?;
+    Ok(buf)
+  }
+
+  fn get_column_bloom_filter(&self, i: usize) -> Result<Option<Sbbf>> {
+    self.check_column_index(i)?;
+    let col = self.metadata.column(i);
+    let (offset, length) = match (col.bloom_filter_offset(), col.bloom_filter_length()) {
+      (Some(offset), Some(length)) => (offset, length),
+      _ => return Ok(None)
+    };
+    if offset < 0 || length < 0 || length as usize > MAX_NUM_BYTES {
+      return Err(general_err!(
+        "Invalid Bloom filter location of column {}: offset {}, length {}",
+        col.column_path(),
+        offset,
+        length
+      ));
+    }
+    let mut buf = vec![0; length as usize];
+    FileChunk::new(self.buf.get_ref(), offset as usize, length as usize)
+      .read_exact(&mut buf)?;
+    Ok(Some(Sbbf::from_bytes(&buf)?))
+  }
+
+  fn get_column_index(&self, i: usize) -> Result<Option<ColumnIndex>> {
+    self.check_column_index(i)?;
+    let col = self.metadata.column(i);
+    match (col.column_index_offset(), col.column_index_length()) {
+      (Some(offset), Some(length)) => {
+        let buf = self.read_index("column index", col, offset, length)?;
+        Ok(Some(ColumnIndex::from_bytes(&buf)?))
+      },
+      _ => Ok(None)
+    }
+  }
+
+  fn get_offset_index(&self, i: usize) -> Result<Option<OffsetIndex>> {
+    self.check_column_index(i)?;
+    let col = self.metadata.column(i);
+    match (col.offset_index_offset(), col.offset_index_length()) {
+      (Some(offset), Some(length)) => {
+        let buf = self.read_index("offset index", col, offset, length)?;
+        Ok(Some(OffsetIndex::from_bytes(&buf)?))
+      },
+      _ => Ok(None)
+    }
+  }
+
   fn get_row_iter(&self, projection: Option<SchemaType>) -> Result<RowIter> {
-    RowIter::from_row_group(projection, self)
+    let iter = RowIter::from_row_group(projection, self)?;
+    if self.checksum_verification {
+      for i in row_iter_columns(&self.metadata, iter.schema_descr()) {
+        self.verify_column_checksum(i)?;
+      }
+    }
+    iter.with_progress(self.progress.clone())
+      .with_duplicate_field_policy(self.duplicate_field_policy)
+  }
+
+  fn get_column_reader_iter(
+    &self,
+    projection: Option<SchemaType>
+  ) -> Result<ColumnReaderIter> {
+    ColumnReaderIter::new(self, projection)
   }
 }
 
@@ -284,7 +1192,30 @@ pub struct SerializedPageReader {
   seen_num_values: i64,
 
   // The number of total values in this column chunk.
-  total_num_values: i64
+  total_num_values: i64,
+
+  // Physical type of the column, used to decode page statistics.
+  physical_type: Type,
+
+  // Policy for pages of unknown types.
+  unknown_page_policy: UnknownPagePolicy,
+
+  // Limits for page sizes declared in page headers.
+  page_size_limits: PageSizeLimits,
+
+  // The number of unknown pages and their compressed bytes skipped so far.
+  num_skipped_pages: usize,
+  num_skipped_bytes: usize,
+
+  // Progress tracker of the file reader, bytes are reported after every page.
+  progress: Option<ProgressTracker>,
+
+  // Cache of decompressed pages with path of the file, pages are keyed by path and
+  // offset of the page header.
+  page_cache: Option<(PageCache, Rc<PathBuf>)>,
+
+  // Cumulative metrics of pages returned so far.
+  metrics: PageReaderMetrics
 }
 
 impl SerializedPageReader {
@@ -292,32 +1223,198 @@ impl SerializedPageReader {
   fn new(
     buf: FileChunk,
     total_num_values: i64,
-    compression: Compression
+    compression: Compression,
+    physical_type: Type
   ) -> Result<Self> {
     let decompressor = create_codec(compression)?;
     let result = Self {
       buf: buf,
       total_num_values: total_num_values,
+      physical_type: physical_type,
       seen_num_values: 0,
-      decompressor: decompressor
+      decompressor: decompressor,
+      unknown_page_policy: UnknownPagePolicy::default(),
+      page_size_limits: PageSizeLimits::default(),
+      num_skipped_pages: 0,
+      num_skipped_bytes: 0,
+      progress: None,
+      page_cache: None,
+      metrics: PageReaderMetrics::default()
     };
     Ok(result)
   }
 
+  /// Sets policy for pages of unknown types and returns itself.
+  fn with_unknown_page_policy(mut self, policy: UnknownPagePolicy) -> Self {
+    self.unknown_page_policy = policy;
+    self
+  }
+
+  /// Sets limits for page sizes and returns itself.
+  fn with_page_size_limits(mut self, limits: PageSizeLimits) -> Self {
+    self.page_size_limits = limits;
+    self
+  }
+
+  /// Replaces decompressor created for the column chunk codec and returns itself.
+  fn with_decompressor(mut self, decompressor: Option<Box<Codec>>) -> Self {
+    self.decompressor = decompressor;
+    self
+  }
+
+  /// Sets progress tracker and returns itself.
+  fn with_progress(mut self, progress: Option<ProgressTracker>) -> Self {
+    self.progress = progress;
+    self
+  }
+
+  /// Sets page cache with path of the file and returns itself.
+  fn with_page_cache(mut self, page_cache: Option<(PageCache, Rc<PathBuf>)>) -> Self {
+    self.page_cache = page_cache;
+    self
+  }
+
+  /// Returns page whose header starts at `offset` if it is in the page cache.
+  fn get_cached_page(&self, offset: usize) -> Option<CachedPage> {
+    self.page_cache.as_ref().and_then(|&(ref cache, ref path)| {
+      cache.get(&PageCacheKey::new(path.clone(), offset))
+    })
+  }
+
+  /// Inserts page whose header starts at `offset` into the page cache, page ends at
+  /// the current position.
+  fn cache_page(&self, offset: usize, page: &Page) {
+    if let Some((ref cache, ref path)) = self.page_cache {
+      let cached_page = CachedPage::new(page.clone(), self.buf.pos() - offset);
+      cache.insert(PageCacheKey::new(path.clone(), offset), cached_page);
+    }
+  }
+
+  /// Reports bytes read since position `start` to the progress tracker.
+  fn report_progress(&self, start: usize) {
+    if let Some(ref progress) = self.progress {
+      progress.add_bytes((self.buf.pos() - start) as u64);
+    }
+  }
+
+  /// Updates metrics with the returned page, if any, and the number of bytes read from
+  /// the column chunk. Pages served from the page cache are not read.
+  fn update_metrics(&mut self, page: Option<&Page>, bytes_read: usize) {
+    let (num_pages, decompressed_bytes) = match page {
+      Some(page) => (1, page.buffer().len() as u64),
+      None => (0, 0)
+    };
+    self.metrics = PageReaderMetrics::new(
+      self.metrics.num_pages() + num_pages,
+      self.metrics.compressed_bytes() + bytes_read as u64,
+      self.metrics.decompressed_bytes() + decompressed_bytes
+    );
+  }
+
+  /// Returns number of pages of unknown types skipped so far.
+  pub fn num_skipped_pages(&self) -> usize {
+    self.num_skipped_pages
+  }
+
+  /// Returns total compressed size in bytes of pages of unknown types skipped so far,
+  /// excluding page headers.
+  pub fn num_skipped_bytes(&self) -> usize {
+    self.num_skipped_bytes
+  }
+
   /// Reads Page header from Thrift.
   fn read_page_header(&mut self) -> Result<PageHeader> {
-    let transport = TMemoryBuffer::new(&mut self.buf);
-    let mut prot = TCompactInputProtocol::new(transport);
-    let page_header = PageHeader::read_from_in_protocol(&mut prot)?;
-    Ok(page_header)
+    read_page_header(&mut self.buf)
+  }
+
+  /// Applies unknown page policy to the page with header `page_header`, and skips page
+  /// data without decompression unless policy requires an error.
+  fn skip_unknown_page(&mut self, page_header: &PageHeader) -> Result<()> {
+    let page_type = BasicPageType::from(page_header.type_);
+    if let UnknownPagePolicy::Error = self.unknown_page_policy {
+      return Err(general_err!("Unsupported page type {}", page_type));
+    }
+
+    let page_size = page_header.compressed_page_size as usize;
+    let bytes_skipped = io::copy(
+      &mut (&mut self.buf).take(page_size as u64),
+      &mut io::sink()
+    )?;
+    if bytes_skipped as usize != page_size {
+      return Err(eof_err!(
+        "Expected to skip {} bytes of {} page, but skipped only {}",
+        page_size,
+        page_type,
+        bytes_skipped
+      ));
+    }
+    self.num_skipped_pages += 1;
+    self.num_skipped_bytes += page_size;
+
+    if let UnknownPagePolicy::Warn(ref callback) = self.unknown_page_policy {
+      callback(page_type, page_size);
+    }
+    Ok(())
   }
 }
 
+/// Returns indices of column chunks of a row group, which are read by a row iterator
+/// over the (projected) schema `descr`.
+fn row_iter_columns(metadata: &RowGroupMetaData, descr: &SchemaDescriptor) -> Vec<usize> {
+  let paths: HashSet<&ColumnPath> = descr.columns().iter().map(|c| c.path()).collect();
+  (0..metadata.num_columns())
+    .filter(|&i| paths.contains(metadata.column(i).column_path()))
+    .collect()
+}
+
+/// Reads Thrift page header from the current position of `input`.
+fn read_page_header<T: Read>(input: &mut T) -> Result<PageHeader> {
+  let transport = TMemoryBuffer::new(input);
+  let mut prot = TCompactInputProtocol::new(transport);
+  let page_header = PageHeader::read_from_in_protocol(&mut prot)?;
+  Ok(page_header)
+}
+
+/// Returns `true` if page is a dictionary page, whose compressed and uncompressed
+/// sizes are equal, i.e. page is treated as written without compression. A compressed
+/// dictionary page, whose size happens to be unchanged by compression, cannot be told
+/// apart from it, so this crate's writer never produces one.
+#[inline]
+fn is_uncompressed_dictionary_page(page_header: &PageHeader) -> bool {
+  page_header.type_ == PageType::DICTIONARY_PAGE &&
+    page_header.compressed_page_size == page_header.uncompressed_page_size
+}
+
 impl PageReader for SerializedPageReader {
   fn get_next_page(&mut self) -> Result<Option<Page>> {
+    let start = self.buf.pos();
     while self.seen_num_values < self.total_num_values {
+      let page_offset = self.buf.pos();
+      if let Some(cached_page) = self.get_cached_page(page_offset) {
+        // Page data is neither read nor decompressed again
+        if self.buf.skip(cached_page.file_len()) != cached_page.file_len() {
+          return Err(eof_err!("Cached page exceeds column chunk"));
+        }
+        let page = cached_page.page().clone();
+        if page.page_type() != BasicPageType::DICTIONARY_PAGE {
+          self.seen_num_values += page.num_values() as i64;
+        }
+        self.update_metrics(Some(&page), page_offset - start);
+        self.report_progress(start);
+        return Ok(Some(page));
+      }
+
       let page_header = self.read_page_header()?;
 
+      match page_header.type_ {
+        PageType::DICTIONARY_PAGE | PageType::DATA_PAGE | PageType::DATA_PAGE_V2 => {},
+        _ => {
+          // Unknown pages (e.g., INDEX_PAGE) do not contain values of the column.
+          self.skip_unknown_page(&page_header)?;
+          continue;
+        }
+      }
+
       // When processing data page v2, depending on enabled compression for the page, we
       // should account for uncompressed data ('offset') of repetition and definition
       // levels.
@@ -334,6 +1431,20 @@ impl PageReader for SerializedPageReader {
         can_decompress = header_v2.is_compressed.unwrap_or(true);
       }
 
+      // Dictionary page might be stored uncompressed in a compressed column chunk, see
+      // `with_dictionary_page_compression_enabled` in writer properties. Such page has
+      // equal sizes and is used as is, without attempting to decompress it.
+      if is_uncompressed_dictionary_page(&page_header) {
+        can_decompress = false;
+      }
+
+      // Validate page sizes before any buffer is allocated
+      self.page_size_limits.check(
+        &page_header,
+        offset,
+        can_decompress && self.decompressor.is_some()
+      )?;
+
       let compressed_len = page_header.compressed_page_size as usize - offset;
       let uncompressed_len = page_header.uncompressed_page_size as usize - offset;
       // We still need to read all bytes from buffered stream
@@ -344,32 +1455,36 @@ impl PageReader for SerializedPageReader {
       // page header size and abort if that is exceeded.
       if let Some(decompressor) = self.decompressor.as_mut() {
         if can_decompress {
-          let mut decompressed_buffer = vec![];
-          let decompressed_size =
-            decompressor.decompress(&buffer[offset..], &mut decompressed_buffer)?;
-          if decompressed_size != uncompressed_len {
-            return Err(general_err!(
-              "Actual decompressed size doesn't \
-               match the expected one ({} vs {})",
-              decompressed_size,
-              uncompressed_len
-            ));
-          }
-          if offset == 0 {
-            buffer = decompressed_buffer;
-          } else {
-            // Prepend saved offsets to the buffer
-            buffer.truncate(offset);
-            buffer.append(&mut decompressed_buffer);
+          let mut decompressed_buffer = Vec::with_capacity(uncompressed_len);
+          match decompressor.decompress(&buffer[offset..], &mut decompressed_buffer) {
+            Ok(decompressed_size) if decompressed_size == uncompressed_len => {
+              if offset == 0 {
+                buffer = decompressed_buffer;
+              } else {
+                // Prepend saved offsets to the buffer
+                buffer.truncate(offset);
+                buffer.append(&mut decompressed_buffer);
+              }
+            },
+            Ok(decompressed_size) => {
+              return Err(general_err!(
+                "Actual decompressed size doesn't \
+                 match the expected one ({} vs {})",
+                decompressed_size,
+                uncompressed_len
+              ));
+            },
+            Err(err) => return Err(err)
           }
         }
       }
 
-      // TODO: process statistics
       let result = match page_header.type_ {
         PageType::DICTIONARY_PAGE => {
-          assert!(page_header.dictionary_page_header.is_some());
-          let dict_header = page_header.dictionary_page_header.as_ref().unwrap();
+          let dict_header = match page_header.dictionary_page_header {
+            Some(ref header) => header,
+            None => return Err(general_err!("DICTIONARY_PAGE page header is missing"))
+          };
           let is_sorted = dict_header.is_sorted.unwrap_or(false);
           Page::DictionaryPage {
             buf: ByteBufferPtr::new(buffer),
@@ -379,20 +1494,25 @@ impl PageReader for SerializedPageReader {
           }
         },
         PageType::DATA_PAGE => {
-          assert!(page_header.data_page_header.is_some());
-          let header = page_header.data_page_header.as_ref().unwrap();
+          let header = match page_header.data_page_header {
+            Some(header) => header,
+            None => return Err(general_err!("DATA_PAGE page header is missing"))
+          };
           self.seen_num_values += header.num_values as i64;
           Page::DataPage {
             buf: ByteBufferPtr::new(buffer),
             num_values: header.num_values as u32,
             encoding: Encoding::from(header.encoding),
             def_level_encoding: Encoding::from(header.definition_level_encoding),
-            rep_level_encoding: Encoding::from(header.repetition_level_encoding)
+            rep_level_encoding: Encoding::from(header.repetition_level_encoding),
+            statistics: statistics::from_thrift(self.physical_type, header.statistics)
           }
         },
         PageType::DATA_PAGE_V2 => {
-          assert!(page_header.data_page_header_v2.is_some());
-          let header = page_header.data_page_header_v2.as_ref().unwrap();
+          let header = match page_header.data_page_header_v2 {
+            Some(header) => header,
+            None => return Err(general_err!("DATA_PAGE_V2 page header is missing"))
+          };
           let is_compressed = header.is_compressed.unwrap_or(true);
           self.seen_num_values += header.num_values as i64;
           Page::DataPageV2 {
@@ -403,27 +1523,54 @@ impl PageReader for SerializedPageReader {
             num_rows: header.num_rows as u32,
             def_levels_byte_len: header.definition_levels_byte_length as u32,
             rep_levels_byte_len: header.repetition_levels_byte_length as u32,
-            is_compressed: is_compressed
+            is_compressed: is_compressed,
+            statistics: statistics::from_thrift(self.physical_type, header.statistics)
           }
         },
-        _ => {
-          // For unknown page type (e.g., INDEX_PAGE), skip and read next.
-          continue;
-        }
+        _ => unreachable!("Unknown pages are skipped before reading page data")
       };
+      self.cache_page(page_offset, &result);
+      let bytes_read = self.buf.pos() - start;
+      self.update_metrics(Some(&result), bytes_read);
+      self.report_progress(start);
       return Ok(Some(result));
     }
 
     // We are at the end of this column chunk and no more page left. Return None.
+    // Unknown pages skipped at the end of the column chunk are still reported.
+    if self.buf.pos() > start {
+      let bytes_read = self.buf.pos() - start;
+      self.update_metrics(None, bytes_read);
+      self.report_progress(start);
+    }
     Ok(None)
   }
+
+  fn num_values_left(&self) -> i64 {
+    self.total_num_values - self.seen_num_values
+  }
+
+  fn metrics(&self) -> PageReaderMetrics {
+    self.metrics
+  }
 }
 
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use util::test_common::{get_temp_file, get_test_file};
+
+  use std::cell::RefCell;
+  use std::fs;
+
+  use parquet_format::{
+    DataPageHeader, DataPageHeaderV2, DictionaryPageHeader, IndexPageHeader
+  };
+  use file::builder::InMemoryFileBuilder;
+  use record::api::Row;
+  use schema::parser::parse_message_type;
+  use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+  use util::test_common::{get_temp_file, get_temp_filename, get_test_file, get_test_path};
 
   #[test]
   fn test_file_reader_metadata_size_smaller_than_footer() {
@@ -471,6 +1618,83 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_file_reader_try_from_path() {
+    let path = get_test_path("alltypes_plain.parquet");
+    let reader = SerializedFileReader::try_from_path(&path).unwrap();
+    assert_eq!(reader.num_row_groups(), 1);
+
+    let reader = SerializedFileReader::try_from(path.to_str().unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 1);
+  }
+
+  #[test]
+  fn test_file_reader_try_from_path_errors() {
+    let path = get_temp_filename("try_from_path_missing.parquet");
+    let _ = fs::remove_file(&path);
+    let res = SerializedFileReader::try_from_path(&path);
+    assert!(res.is_err());
+    if let Err(ParquetError::General(message)) = res {
+      assert!(message.starts_with(
+        &format!("Could not open file \"{}\": ", path.display())));
+    }
+
+    get_temp_file("try_from_path_corrupt.parquet", &[1, 2, 3, 4, 5, 6, 7, 8]);
+    let path = get_temp_filename("try_from_path_corrupt.parquet");
+    let res = SerializedFileReader::try_from_path(&path);
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Could not read metadata of file \"{}\": Invalid Parquet file. Corrupt footer",
+        path.display()
+      )
+    );
+
+    get_temp_file("try_from_path_empty.parquet", &[]);
+    let path = get_temp_filename("try_from_path_empty.parquet");
+    let res = SerializedFileReader::try_from(path.to_str().unwrap());
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Could not read metadata of file \"{}\": Invalid Parquet file. Size is \
+         smaller than footer",
+        path.display()
+      )
+    );
+  }
+
+  #[test]
+  fn test_file_reader_page_cache() {
+    let path = get_test_path("alltypes_plain.parquet");
+    let cache = PageCache::new(1024 * 1024);
+
+    let reader = SerializedFileReader::try_from_path(&path)
+      .unwrap()
+      .with_page_cache(cache.clone())
+      .unwrap();
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(cache.hits(), 0);
+    let num_pages = cache.len();
+    assert!(num_pages > 0);
+    assert_eq!(cache.misses(), num_pages as u64);
+
+    // Another reader of the same file is served from the shared cache
+    let reader = SerializedFileReader::try_from_path(&path)
+      .unwrap()
+      .with_page_cache(cache.clone())
+      .unwrap();
+    let cached_rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(cached_rows, rows);
+    assert_eq!(cache.hits(), num_pages as u64);
+    assert_eq!(cache.len(), num_pages);
+
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"));
+    assert_eq!(
+      reader.unwrap().with_page_cache(cache).err().unwrap(),
+      general_err!("Page cache requires a file reader opened from a path")
+    );
+  }
+
   #[test]
   fn test_reuse_file_chunk() {
     // This test covers the case of maintaining the correct start position in a file
@@ -492,6 +1716,74 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_page_reader_metrics() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let row_group = reader.get_row_group(0).unwrap();
+    for i in 0..row_group.num_columns() {
+      let mut page_reader = row_group.get_column_page_reader(i).unwrap();
+      assert_eq!(page_reader.metrics(), PageReaderMetrics::default());
+
+      let mut num_pages = 0;
+      let mut decompressed_bytes = 0;
+      while let Some(page) = page_reader.get_next_page().unwrap() {
+        num_pages += 1;
+        decompressed_bytes += page.buffer().len() as u64;
+      }
+      assert!(num_pages > 0);
+      let column = row_group.metadata().column(i);
+      assert_eq!(
+        page_reader.metrics(),
+        PageReaderMetrics::new(
+          num_pages,
+          column.compressed_size() as u64,
+          decompressed_bytes
+        )
+      );
+    }
+  }
+
+  #[test]
+  fn test_file_reader_index_out_of_bounds() {
+    let test_file = get_test_file("alltypes_plain.parquet");
+    let reader = SerializedFileReader::new(test_file).unwrap();
+    assert_eq!(
+      reader.get_row_group(1).err().unwrap(),
+      general_err!("Row group index 1 out of bounds, file has 1 row groups")
+    );
+
+    let row_group = reader.get_row_group(0).unwrap();
+    let expected =
+      general_err!("Column index 11 out of bounds, row group has 11 columns");
+    assert_eq!(row_group.get_column_page_reader(11).err().unwrap(), expected);
+    assert_eq!(row_group.get_column_reader(11).err().unwrap(), expected);
+    assert_eq!(row_group.get_column_chunk_bytes(11).unwrap_err(), expected);
+    assert_eq!(row_group.get_column_index(11).unwrap_err(), expected);
+  }
+
+  #[test]
+  fn test_file_reader_no_row_groups() {
+    let schemas = vec!["message schema { REQUIRED INT32 a; }", "message schema { }"];
+    for (i, schema) in schemas.iter().enumerate() {
+      let schema = Rc::new(parse_message_type(schema).unwrap());
+      let bytes = InMemoryFileBuilder::new(schema).build().unwrap();
+      let file = get_temp_file(&format!("test_file_reader_no_row_groups_{}", i), &bytes);
+      let reader = SerializedFileReader::new(file).unwrap();
+
+      let metadata = reader.metadata();
+      assert_eq!(metadata.num_row_groups(), 0);
+      assert!(metadata.is_empty());
+      assert!(metadata.file_metadata().is_empty());
+      assert_eq!(metadata.file_metadata().schema_descr().is_empty(), i == 1);
+      assert_eq!(reader.get_row_iter(None).unwrap().count(), 0);
+      assert_eq!(
+        reader.get_row_group(0).err().unwrap(),
+        general_err!("Row group index 0 out of bounds, file has 0 row groups")
+      );
+    }
+  }
+
   #[test]
   fn test_file_reader() {
     let test_file = get_test_file("alltypes_plain.parquet");
@@ -554,7 +1846,8 @@ mod tests {
           num_values,
           encoding,
           def_level_encoding,
-          rep_level_encoding
+          rep_level_encoding,
+          ..
         } => {
           assert_eq!(buf.len(), 11);
           assert_eq!(num_values, 8);
@@ -632,7 +1925,8 @@ mod tests {
           num_rows,
           def_levels_byte_len,
           rep_levels_byte_len,
-          is_compressed
+          is_compressed,
+          ..
         } => {
           assert_eq!(buf.len(), 4);
           assert_eq!(num_values, 5);
@@ -651,4 +1945,510 @@ mod tests {
     }
     assert_eq!(page_count, 2);
   }
+
+  #[test]
+  fn test_column_reader_iter() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+
+    let iter = row_group_reader.get_column_reader_iter(None).unwrap();
+    assert_eq!(iter.size_hint(), (11, Some(11)));
+    assert_eq!(iter.len(), 11);
+    let mut names = Vec::new();
+    for res in iter {
+      let (descr, column_reader) = res.unwrap();
+      let is_expected_reader = match (descr.physical_type(), column_reader) {
+        (Type::BOOLEAN, ColumnReader::BoolColumnReader(_)) => true,
+        (Type::INT32, ColumnReader::Int32ColumnReader(_)) => true,
+        (Type::INT64, ColumnReader::Int64ColumnReader(_)) => true,
+        (Type::INT96, ColumnReader::Int96ColumnReader(_)) => true,
+        (Type::FLOAT, ColumnReader::FloatColumnReader(_)) => true,
+        (Type::DOUBLE, ColumnReader::DoubleColumnReader(_)) => true,
+        (Type::BYTE_ARRAY, ColumnReader::ByteArrayColumnReader(_)) => true,
+        _ => false
+      };
+      assert!(is_expected_reader);
+      names.push(descr.name().to_owned());
+    }
+    assert_eq!(names.len(), 11);
+    assert_eq!(names[0], "id");
+    assert_eq!(names[10], "timestamp_col");
+  }
+
+  #[test]
+  fn test_column_reader_iter_projection() {
+    let reader = SerializedFileReader::new(get_test_file("alltypes_plain.parquet"))
+      .unwrap();
+    let row_group_reader = reader.get_row_group(0).unwrap();
+
+    let projection = parse_message_type("
+      message schema {
+        OPTIONAL DOUBLE double_col;
+        OPTIONAL INT32 id;
+      }
+    ").unwrap();
+    let columns: Vec<(ColumnDescPtr, ColumnReader)> = row_group_reader
+      .get_column_reader_iter(Some(projection))
+      .unwrap()
+      .map(|res| res.unwrap())
+      .collect();
+    assert_eq!(columns.len(), 2);
+    assert_eq!(columns[0].0.name(), "double_col");
+    assert_eq!(columns[1].0.name(), "id");
+    match columns[0].1 {
+      ColumnReader::DoubleColumnReader(_) => {},
+      _ => panic!("Expected DOUBLE column reader")
+    }
+
+    let projection = parse_message_type("
+      message schema {
+        OPTIONAL INT64 id;
+      }
+    ").unwrap();
+    let res = row_group_reader.get_column_reader_iter(Some(projection));
+    assert!(res.is_err());
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Root schema does not contain projection")
+    );
+  }
+
+  /// Writes metadata-only temp file `file_name` with metadata of alltypes_plain.parquet,
+  /// where `file_path` of all column chunks is set to `file_path`.
+  fn write_file_with_chunk_file_path(file_name: &str, file_path: &str) {
+    let bytes = fs::read(get_test_path("alltypes_plain.parquet")).unwrap();
+    let footer_start = bytes.len() - FOOTER_SIZE;
+    let metadata_len = LittleEndian::read_i32(&bytes[footer_start..]) as usize;
+    let mut t_file_metadata = TFileMetaData::read_from_in_protocol(
+      &mut TCompactInputProtocol::new(&bytes[footer_start - metadata_len..footer_start])
+    ).unwrap();
+    for row_group in t_file_metadata.row_groups.iter_mut() {
+      for column in row_group.columns.iter_mut() {
+        column.file_path = Some(file_path.to_string());
+      }
+    }
+    let mut metadata_buf = Vec::new();
+    {
+      let mut protocol = TCompactOutputProtocol::new(&mut metadata_buf);
+      t_file_metadata.write_to_out_protocol(&mut protocol).unwrap();
+      protocol.flush().unwrap();
+    }
+    let mut file_bytes = PARQUET_MAGIC.to_vec();
+    file_bytes.extend_from_slice(&metadata_buf);
+    let mut footer = [0; FOOTER_SIZE];
+    LittleEndian::write_i32(&mut footer[0..4], metadata_buf.len() as i32);
+    footer[4..].copy_from_slice(&PARQUET_MAGIC);
+    file_bytes.extend_from_slice(&footer);
+    get_temp_file(file_name, &file_bytes);
+  }
+
+  #[test]
+  fn test_file_reader_external_column_chunks() {
+    // Metadata-only file whose column chunks are stored in a copy of another file
+    let bytes = fs::read(get_test_path("alltypes_plain.parquet")).unwrap();
+    get_temp_file("external_chunks_data.parquet", &bytes);
+    write_file_with_chunk_file_path(
+      "external_chunks.parquet", "external_chunks_data.parquet");
+
+    let file = get_test_file("alltypes_plain.parquet");
+    let reader = SerializedFileReader::new(file).unwrap();
+    let expected: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    let path = get_temp_filename("external_chunks.parquet");
+    let reader = SerializedFileReader::try_from_path(&path)
+      .unwrap()
+      .with_external_chunks_enabled()
+      .unwrap();
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows.len(), 8);
+    assert_eq!(rows, expected);
+
+    // External column chunks are opt-in
+    let reader = SerializedFileReader::try_from_path(&path).unwrap();
+    let row_group = reader.get_row_group(0).unwrap();
+    assert_eq!(
+      row_group.get_column_reader(0).err().unwrap(),
+      general_err!(
+        "Column \"id\" is stored in file \"external_chunks_data.parquet\", but reading \
+         external column chunks is not enabled"
+      )
+    );
+
+    let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(
+      reader.with_external_chunks_enabled().err().unwrap(),
+      general_err!("External column chunks require a file reader opened from a path")
+    );
+  }
+
+  #[test]
+  fn test_dir_chunk_reader() {
+    let chunk_reader = DirChunkReader::new(PathBuf::from("data"));
+    assert_eq!(
+      chunk_reader.resolve("chunks/a.parquet").unwrap(),
+      PathBuf::from("data/chunks/a.parquet")
+    );
+    assert_eq!(
+      chunk_reader.resolve("./a.parquet").unwrap(),
+      PathBuf::from("data/./a.parquet")
+    );
+
+    for file_path in vec!["", "/etc/passwd", "../a.parquet", "chunks/../../a.parquet"] {
+      assert_eq!(
+        chunk_reader.resolve(file_path).unwrap_err(),
+        general_err!(
+          "Invalid column chunk file path \"{}\", must be relative to directory \"data\"",
+          file_path
+        )
+      );
+      assert!(chunk_reader.open(file_path).is_err());
+    }
+  }
+
+  #[test]
+  fn test_file_reader_external_column_chunks_outside_of_directory() {
+    // Column chunks that refer to a file outside of the directory are rejected
+    let file_path = get_test_path("alltypes_plain.parquet").display().to_string();
+    write_file_with_chunk_file_path("external_chunks_absolute.parquet", &file_path);
+
+    let path = get_temp_filename("external_chunks_absolute.parquet");
+    let reader = SerializedFileReader::try_from_path(&path)
+      .unwrap()
+      .with_external_chunks_enabled()
+      .unwrap();
+    let row_group = reader.get_row_group(0).unwrap();
+    assert_eq!(
+      row_group.get_column_reader(0).err().unwrap(),
+      general_err!(
+        "Invalid column chunk file path \"{}\", must be relative to directory \"{}\"",
+        file_path,
+        path.parent().unwrap().display()
+      )
+    );
+  }
+
+  /// Returns file with an index page, followed by a data page with 4 values and another
+  /// index page, and file length.
+  fn get_file_with_index_pages(file_name: &str) -> (File, usize) {
+    let index_page_header = PageHeader {
+      type_: PageType::INDEX_PAGE,
+      uncompressed_page_size: 3,
+      compressed_page_size: 3,
+      crc: None,
+      data_page_header: None,
+      index_page_header: Some(IndexPageHeader::new()),
+      dictionary_page_header: None,
+      data_page_header_v2: None
+    };
+    let data_page_header = PageHeader {
+      type_: PageType::DATA_PAGE,
+      uncompressed_page_size: 5,
+      compressed_page_size: 5,
+      crc: None,
+      data_page_header: Some(DataPageHeader {
+        num_values: 4,
+        encoding: ::parquet_format::Encoding::PLAIN,
+        definition_level_encoding: ::parquet_format::Encoding::RLE,
+        repetition_level_encoding: ::parquet_format::Encoding::RLE,
+        statistics: None
+      }),
+      index_page_header: None,
+      dictionary_page_header: None,
+      data_page_header_v2: None
+    };
+    let pages = vec![
+      (index_page_header.clone(), vec![1, 2, 3]),
+      (data_page_header, vec![4, 5, 6, 7, 8]),
+      (index_page_header, vec![9, 10, 11])
+    ];
+    get_file_with_pages(file_name, pages)
+  }
+
+  /// Writes pages with their headers into a temporary file and returns the file and
+  /// its length.
+  fn get_file_with_pages(
+    file_name: &str,
+    pages: Vec<(PageHeader, Vec<u8>)>
+  ) -> (File, usize) {
+    let mut buf = Vec::new();
+    for (header, data) in pages {
+      {
+        let mut protocol = TCompactOutputProtocol::new(&mut buf);
+        header.write_to_out_protocol(&mut protocol).unwrap();
+        protocol.flush().unwrap();
+      }
+      buf.extend_from_slice(&data);
+    }
+    (get_temp_file(file_name, &buf), buf.len())
+  }
+
+  /// Returns header of a PLAIN encoded data page with 4 values.
+  fn get_data_page_header(compressed_size: i32, uncompressed_size: i32) -> PageHeader {
+    PageHeader {
+      type_: PageType::DATA_PAGE,
+      uncompressed_page_size: uncompressed_size,
+      compressed_page_size: compressed_size,
+      crc: None,
+      data_page_header: Some(DataPageHeader {
+        num_values: 4,
+        encoding: ::parquet_format::Encoding::PLAIN,
+        definition_level_encoding: ::parquet_format::Encoding::RLE,
+        repetition_level_encoding: ::parquet_format::Encoding::RLE,
+        statistics: None
+      }),
+      index_page_header: None,
+      dictionary_page_header: None,
+      data_page_header_v2: None
+    }
+  }
+
+  #[test]
+  fn test_page_size_limits_default() {
+    let limits = PageSizeLimits::default();
+    assert_eq!(limits.max_page_size(), DEFAULT_MAX_PAGE_SIZE);
+    assert_eq!(limits.max_compression_ratio(), None);
+
+    let limits = PageSizeLimits::new()
+      .with_max_page_size(10)
+      .with_max_compression_ratio(5);
+    assert_eq!(limits.max_page_size(), 10);
+    assert_eq!(limits.max_compression_ratio(), Some(5));
+  }
+
+  #[test]
+  fn test_page_reader_max_page_size() {
+    let header = get_data_page_header(16, 16);
+    let (file, len) =
+      get_file_with_pages("page_max_size.parquet", vec![(header, vec![0; 16])]);
+
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32).unwrap()
+        .with_page_size_limits(PageSizeLimits::new().with_max_page_size(16));
+    assert!(page_reader.get_next_page().unwrap().is_some());
+
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32).unwrap()
+        .with_page_size_limits(PageSizeLimits::new().with_max_page_size(15));
+    let res = page_reader.get_next_page();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Size of DATA_PAGE page 16 exceeds maximum page size 15")
+    );
+  }
+
+  #[test]
+  fn test_page_reader_invalid_page_size() {
+    let header = get_data_page_header(-1, 16);
+    let (file, len) =
+      get_file_with_pages("page_invalid_size.parquet", vec![(header, vec![0; 16])]);
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32)
+        .unwrap();
+    let res = page_reader.get_next_page();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Invalid DATA_PAGE page sizes: compressed size -1, uncompressed size 16"
+      )
+    );
+  }
+
+  #[test]
+  fn test_page_reader_max_compression_ratio() {
+    // Page header claims that 4 bytes decompress into 1 MB
+    let header = get_data_page_header(4, 1024 * 1024);
+    let (file, len) =
+      get_file_with_pages("page_compression_ratio.parquet", vec![(header, vec![0; 4])]);
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::GZIP, Type::INT32).unwrap()
+        .with_page_size_limits(PageSizeLimits::new().with_max_compression_ratio(100));
+    let res = page_reader.get_next_page();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Compression ratio of DATA_PAGE page exceeds maximum ratio 100 \
+         (compressed size 4, uncompressed size 1048576)"
+      )
+    );
+
+    // Ratio is not checked for uncompressed column chunks
+    let header = get_data_page_header(4, 1024 * 1024);
+    let limits = PageSizeLimits::new().with_max_compression_ratio(100);
+    assert!(limits.check(&header, 0, false).is_ok());
+  }
+
+  #[test]
+  fn test_page_reader_invalid_levels_size() {
+    let header = PageHeader {
+      type_: PageType::DATA_PAGE_V2,
+      uncompressed_page_size: 8,
+      compressed_page_size: 8,
+      crc: None,
+      data_page_header: None,
+      index_page_header: None,
+      dictionary_page_header: None,
+      data_page_header_v2: Some(DataPageHeaderV2 {
+        num_values: 4,
+        num_nulls: 0,
+        num_rows: 4,
+        encoding: ::parquet_format::Encoding::PLAIN,
+        definition_levels_byte_length: 6,
+        repetition_levels_byte_length: 6,
+        is_compressed: Some(false),
+        statistics: None
+      })
+    };
+    let (file, len) =
+      get_file_with_pages("page_invalid_levels_size.parquet", vec![(header, vec![0; 8])]);
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32)
+        .unwrap();
+    let res = page_reader.get_next_page();
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!(
+        "Invalid DATA_PAGE_V2 page sizes: levels size 12 exceeds compressed size 8 or \
+         uncompressed size 8"
+      )
+    );
+  }
+
+  #[test]
+  fn test_page_reader_uncompressed_dictionary_page() {
+    // Valid Snappy stream of 8 bytes, which decompresses into 8 bytes "abcdabcd"
+    let data = vec![8, 12, b'a', b'b', b'c', b'd', 1, 4];
+    let header = PageHeader {
+      type_: PageType::DICTIONARY_PAGE,
+      uncompressed_page_size: 8,
+      compressed_page_size: 8,
+      crc: None,
+      data_page_header: None,
+      index_page_header: None,
+      dictionary_page_header: Some(DictionaryPageHeader {
+        num_values: 2,
+        encoding: ::parquet_format::Encoding::PLAIN,
+        is_sorted: None
+      }),
+      data_page_header_v2: None
+    };
+    let (file, len) =
+      get_file_with_pages("page_uncompressed_dict.parquet", vec![(header, data.clone())]);
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::SNAPPY, Type::INT32).unwrap();
+
+    // Page is used as is, even though it could be decompressed
+    let page = page_reader.get_next_page().unwrap().unwrap();
+    assert_eq!(page.page_type(), BasicPageType::DICTIONARY_PAGE);
+    assert_eq!(page.buffer().data(), &data[..]);
+  }
+
+  #[test]
+  fn test_page_reader_missing_page_header() {
+    let page_types = vec![
+      (PageType::DICTIONARY_PAGE, "DICTIONARY_PAGE"),
+      (PageType::DATA_PAGE, "DATA_PAGE"),
+      (PageType::DATA_PAGE_V2, "DATA_PAGE_V2")
+    ];
+    for (page_type, name) in page_types {
+      let header = PageHeader {
+        type_: page_type,
+        uncompressed_page_size: 4,
+        compressed_page_size: 4,
+        crc: None,
+        data_page_header: None,
+        index_page_header: None,
+        dictionary_page_header: None,
+        data_page_header_v2: None
+      };
+      let file_name = format!("page_missing_header_{}.parquet", name);
+      let (file, len) = get_file_with_pages(&file_name, vec![(header, vec![0; 4])]);
+      let chunk = FileChunk::new(&file, 0, len);
+      let mut page_reader =
+        SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32)
+          .unwrap();
+      assert_eq!(
+        page_reader.get_next_page().unwrap_err(),
+        general_err!("{} page header is missing", name)
+      );
+    }
+  }
+
+  #[test]
+  fn test_page_reader_unknown_page_skip() {
+    let (file, len) = get_file_with_index_pages("unknown_page_skip.parquet");
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32)
+        .unwrap();
+
+    let page = page_reader.get_next_page().unwrap().unwrap();
+    assert_eq!(page.page_type(), BasicPageType::DATA_PAGE);
+    assert_eq!(page.num_values(), 4);
+    assert_eq!(page.buffer().data(), &[4, 5, 6, 7, 8]);
+    assert_eq!(page_reader.num_values_left(), 0);
+    assert_eq!(page_reader.num_skipped_pages(), 1);
+    assert_eq!(page_reader.num_skipped_bytes(), 3);
+
+    // All values have been read, trailing index page is not visited
+    assert!(page_reader.get_next_page().unwrap().is_none());
+    assert_eq!(page_reader.num_skipped_pages(), 1);
+  }
+
+  #[test]
+  fn test_page_reader_unknown_page_warn() {
+    let (file, len) = get_file_with_index_pages("unknown_page_warn.parquet");
+    let chunk = FileChunk::new(&file, 0, len);
+    let warnings = Rc::new(RefCell::new(Vec::new()));
+    let warnings_clone = warnings.clone();
+    let callback = move |page_type: BasicPageType, size: usize| {
+      warnings_clone.borrow_mut().push((page_type, size));
+    };
+    let policy = UnknownPagePolicy::Warn(Rc::new(callback));
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32).unwrap()
+        .with_unknown_page_policy(policy);
+
+    let page = page_reader.get_next_page().unwrap().unwrap();
+    assert_eq!(page.num_values(), 4);
+    assert_eq!(*warnings.borrow(), vec![(BasicPageType::INDEX_PAGE, 3)]);
+  }
+
+  #[test]
+  fn test_page_reader_unknown_page_error() {
+    let (file, len) = get_file_with_index_pages("unknown_page_error.parquet");
+    let chunk = FileChunk::new(&file, 0, len);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 4, Compression::UNCOMPRESSED, Type::INT32).unwrap()
+        .with_unknown_page_policy(UnknownPagePolicy::Error);
+
+    let res = page_reader.get_next_page();
+    assert!(res.is_err());
+    assert_eq!(res.err().unwrap(), general_err!("Unsupported page type INDEX_PAGE"));
+    assert_eq!(page_reader.num_values_left(), 4);
+  }
+
+  #[test]
+  fn test_page_reader_unknown_page_truncated() {
+    let (file, len) = get_file_with_index_pages("unknown_page_truncated.parquet");
+    // Column chunk metadata claims more values than there are, and the last byte of
+    // the trailing index page is missing
+    let chunk = FileChunk::new(&file, 0, len - 1);
+    let mut page_reader =
+      SerializedPageReader::new(chunk, 8, Compression::UNCOMPRESSED, Type::INT32)
+        .unwrap();
+
+    assert!(page_reader.get_next_page().unwrap().is_some());
+    let res = page_reader.get_next_page();
+    assert!(res.is_err());
+    assert_eq!(
+      res.err().unwrap(),
+      eof_err!("Expected to skip 3 bytes of INDEX_PAGE page, but skipped only 2")
+    );
+  }
 }
@@ -0,0 +1,375 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains rewrite utilities for existing Parquet files.
+//!
+//! [`RowGroupSplitter`] splits oversized row groups into smaller ones, e.g. a single
+//! row group of several gigabytes, so that files can be scanned by readers that buffer
+//! a whole row group in memory.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::fs::File;
+//! use std::path::Path;
+//! use std::rc::Rc;
+//! use parquet::file::properties::WriterProperties;
+//! use parquet::file::reader::{FileReader, SerializedFileReader};
+//! use parquet::file::rewrite::RowGroupSplitter;
+//! use parquet::file::writer::{FileWriter, SerializedFileWriter};
+//!
+//! let reader = SerializedFileReader::try_from_path(Path::new("data.parquet")).unwrap();
+//! let schema = Rc::new(reader.metadata().file_metadata().schema().clone());
+//! let props = Rc::new(WriterProperties::builder().build());
+//! let file = File::create("split.parquet").unwrap();
+//! let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+//! RowGroupSplitter::new()
+//!   .with_max_bytes(128 * 1024 * 1024)
+//!   .rewrite(&reader, &mut writer)
+//!   .unwrap();
+//! writer.close().unwrap();
+//! ```
+
+use std::cmp;
+use std::io::Write;
+
+use column::reader::{ColumnReader, RecordReader};
+use column::writer::{get_typed_column_writer_mut, ColumnWriter};
+use data_type::DataType;
+use errors::{ParquetError, Result};
+use file::metadata::RowGroupMetaData;
+use file::reader::{FileReader, RowGroupReader};
+use file::writer::{FileWriter, SerializedFileWriter};
+
+/// Default number of records copied between column reader and writer at a time.
+const DEFAULT_COPY_BATCH_SIZE: usize = 1024;
+
+/// Splits row groups that exceed a number of rows or a size in bytes into several
+/// row groups of a file writer.
+///
+/// Row groups within the limits are appended with
+/// [`append_row_group`](`::file::writer::SerializedFileWriter::append_row_group`), i.e.
+/// copied byte-for-byte without decoding. Oversized row groups are split into row
+/// groups of equal number of rows, and their values are decoded and re-encoded with
+/// properties of the file writer, since page boundaries of different columns do not
+/// fall on the same rows. Records of repeated columns are never split.
+pub struct RowGroupSplitter {
+  max_rows: Option<usize>,
+  max_bytes: Option<u64>,
+  batch_size: usize
+}
+
+impl RowGroupSplitter {
+  /// Creates new splitter without limits, which appends all row groups as they are.
+  pub fn new() -> Self {
+    Self { max_rows: None, max_bytes: None, batch_size: DEFAULT_COPY_BATCH_SIZE }
+  }
+
+  /// Sets maximum number of rows in a row group and returns itself.
+  pub fn with_max_rows(mut self, value: usize) -> Self {
+    self.max_rows = Some(cmp::max(value, 1));
+    self
+  }
+
+  /// Sets maximum total uncompressed size of a row group in bytes, as stored in row
+  /// group metadata, and returns itself.
+  ///
+  /// Number of rows of split row groups is estimated from the average size of a row,
+  /// so row groups with skewed rows can still exceed the limit.
+  pub fn with_max_bytes(mut self, value: u64) -> Self {
+    self.max_bytes = Some(cmp::max(value, 1));
+    self
+  }
+
+  /// Sets number of records copied from column readers into column writers at a time,
+  /// and returns itself.
+  pub fn with_batch_size(mut self, value: usize) -> Self {
+    self.batch_size = cmp::max(value, 1);
+    self
+  }
+
+  /// Returns number of rows per row group that row group `metadata` should be split
+  /// into, or `None` if row group is within the limits.
+  pub fn rows_per_row_group(&self, metadata: &RowGroupMetaData) -> Option<usize> {
+    let num_rows = cmp::max(metadata.num_rows(), 0) as usize;
+    let mut rows = num_rows;
+    if let Some(max_rows) = self.max_rows {
+      rows = cmp::min(rows, max_rows);
+    }
+    if let Some(max_bytes) = self.max_bytes {
+      let total_bytes = cmp::max(metadata.total_byte_size(), 0) as u64;
+      if total_bytes > max_bytes {
+        let rows_by_size = (num_rows as u64).saturating_mul(max_bytes) / total_bytes;
+        rows = cmp::min(rows, cmp::max(rows_by_size, 1) as usize);
+      }
+    }
+    if rows < num_rows { Some(rows) } else { None }
+  }
+
+  /// Writes all row groups of `reader` into `writer`, see
+  /// [`write_row_group`](`RowGroupSplitter::write_row_group`).
+  /// File writer is not closed.
+  pub fn rewrite<W: 'static + Write>(
+    &self,
+    reader: &FileReader,
+    writer: &mut SerializedFileWriter<W>
+  ) -> Result<()> {
+    for i in 0..reader.num_row_groups() {
+      self.write_row_group(&*reader.get_row_group(i)?, writer)?;
+    }
+    Ok(())
+  }
+
+  /// Writes row group `row_group` into `writer`, splitting it if it exceeds the limits.
+  /// Schema of the row group must have the same leaf columns as schema of the writer.
+  ///
+  /// Returns the number of row groups written.
+  pub fn write_row_group<W: 'static + Write>(
+    &self,
+    row_group: &RowGroupReader,
+    writer: &mut SerializedFileWriter<W>
+  ) -> Result<usize> {
+    let metadata = row_group.metadata();
+    let rows_per_row_group = match self.rows_per_row_group(&metadata) {
+      Some(rows) => rows,
+      None => {
+        writer.append_row_group(row_group)?;
+        return Ok(1);
+      }
+    };
+
+    // Column writers are cast to types of column readers
+    writer.check_schema_compatible(metadata.schema_descr())?;
+    let mut copiers = Vec::with_capacity(row_group.num_columns());
+    for i in 0..row_group.num_columns() {
+      copiers.push(record_copier(row_group.get_column_reader(i)?, self.batch_size));
+    }
+
+    let num_rows = metadata.num_rows() as usize;
+    let mut rows_written = 0;
+    let mut num_row_groups = 0;
+    while rows_written < num_rows {
+      let rows = cmp::min(rows_per_row_group, num_rows - rows_written);
+      let mut row_group_writer = writer.next_row_group()?;
+      for (i, copier) in copiers.iter_mut().enumerate() {
+        let mut column_writer = match row_group_writer.next_column()? {
+          Some(column_writer) => column_writer,
+          None => return Err(general_err!("Row group has fewer columns than schema"))
+        };
+        let records = copier.copy_records(&mut column_writer, rows)?;
+        if records != rows {
+          return Err(general_err!(
+            "Column {} has {} rows, expected {}",
+            metadata.column(i).column_path(),
+            rows_written + records,
+            num_rows
+          ));
+        }
+        row_group_writer.close_column(column_writer)?;
+      }
+      writer.close_row_group(row_group_writer)?;
+      rows_written += rows;
+      num_row_groups += 1;
+    }
+    Ok(num_row_groups)
+  }
+}
+
+impl Default for RowGroupSplitter {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Copies whole records of a column into column writers.
+trait RecordCopier {
+  /// Copies at most `num_records` records into `column_writer`, which must be of the
+  /// same type as the column. Returns the number of records copied.
+  fn copy_records(
+    &mut self,
+    column_writer: &mut ColumnWriter,
+    num_records: usize
+  ) -> Result<usize>;
+}
+
+/// Record copier that copies records read with a record reader in batches of
+/// `batch_size` records.
+struct TypedRecordCopier<T: DataType> {
+  reader: RecordReader<T>,
+  batch_size: usize
+}
+
+impl<T: DataType> RecordCopier for TypedRecordCopier<T> where T: 'static {
+  fn copy_records(
+    &mut self,
+    column_writer: &mut ColumnWriter,
+    num_records: usize
+  ) -> Result<usize> {
+    let column_writer = get_typed_column_writer_mut::<T>(column_writer);
+    let mut def_levels = Vec::new();
+    let mut rep_levels = Vec::new();
+    let mut values = Vec::new();
+    let mut records_copied = 0;
+    while records_copied < num_records {
+      let batch_size = cmp::min(self.batch_size, num_records - records_copied);
+      let records = self.reader.read_records(
+        batch_size, &mut def_levels, &mut rep_levels, &mut values)?;
+      if records == 0 {
+        break;
+      }
+      // Levels are only returned for columns that have them
+      column_writer.write_batch(
+        &values,
+        if def_levels.is_empty() { None } else { Some(&def_levels[..]) },
+        if rep_levels.is_empty() { None } else { Some(&rep_levels[..]) }
+      )?;
+      def_levels.clear();
+      rep_levels.clear();
+      values.clear();
+      records_copied += records;
+    }
+    Ok(records_copied)
+  }
+}
+
+/// Returns record copier for column reader of any type.
+fn record_copier(column_reader: ColumnReader, batch_size: usize) -> Box<RecordCopier> {
+  macro_rules! copier {
+    ($reader:expr) => {
+      Box::new(TypedRecordCopier { reader: RecordReader::new($reader), batch_size })
+    };
+  }
+  match column_reader {
+    ColumnReader::BoolColumnReader(r) => copier!(r),
+    ColumnReader::Int32ColumnReader(r) => copier!(r),
+    ColumnReader::Int64ColumnReader(r) => copier!(r),
+    ColumnReader::Int96ColumnReader(r) => copier!(r),
+    ColumnReader::FloatColumnReader(r) => copier!(r),
+    ColumnReader::DoubleColumnReader(r) => copier!(r),
+    ColumnReader::ByteArrayColumnReader(r) => copier!(r),
+    ColumnReader::FixedLenByteArrayColumnReader(r) => copier!(r)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use std::rc::Rc;
+
+  use file::metadata::ColumnChunkMetaData;
+  use file::properties::WriterProperties;
+  use file::reader::SerializedFileReader;
+  use record::api::Row;
+  use record::writer::RowWriter;
+  use schema::parser::parse_message_type;
+  use schema::types::SchemaDescriptor;
+  use util::test_common::get_temp_filename;
+
+  #[test]
+  fn test_row_group_splitter() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL BYTE_ARRAY name (UTF8);
+        OPTIONAL GROUP tags (LIST) {
+          REPEATED GROUP list {
+            OPTIONAL INT64 element;
+          }
+        }
+      }
+    ";
+    let schema = Rc::new(parse_message_type(schema).unwrap());
+    let descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let props = Rc::new(WriterProperties::builder().build());
+    let rows: Vec<Row> = (0..100)
+      .map(|i| Row::Group(vec![
+        ("id".to_string(), Row::Int(i)),
+        ("name".to_string(),
+          if i % 3 == 0 { Row::Null } else { Row::Str(format!("name_{}", i)) }),
+        ("tags".to_string(),
+          Row::List((0..i % 4).map(|j| Row::Long(j as i64)).collect()))
+      ]))
+      .collect();
+
+    // One row group of 90 rows and one of 10 rows
+    let path = get_temp_filename("test_row_group_splitter.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let mut file_writer =
+      SerializedFileWriter::new(file, schema.clone(), props.clone()).unwrap();
+    let mut row_writer = RowWriter::new(descr);
+    for (i, row) in rows.iter().enumerate() {
+      row_writer.write(row).unwrap();
+      if i == 89 || i == 99 {
+        row_writer.write_row_group(&mut file_writer).unwrap();
+      }
+    }
+    file_writer.close().unwrap();
+    let reader = SerializedFileReader::try_from_path(&path).unwrap();
+
+    let splitter = RowGroupSplitter::new().with_max_rows(40).with_batch_size(7);
+    assert_eq!(splitter.rows_per_row_group(&reader.metadata().row_group(0)), Some(40));
+    assert_eq!(splitter.rows_per_row_group(&reader.metadata().row_group(1)), None);
+
+    let split_path = get_temp_filename("test_row_group_splitter_split.parquet");
+    let file = fs::File::create(&split_path).unwrap();
+    let mut file_writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    splitter.rewrite(&reader, &mut file_writer).unwrap();
+    file_writer.close().unwrap();
+
+    let split_reader = SerializedFileReader::try_from_path(&split_path).unwrap();
+    let num_rows: Vec<i64> = split_reader
+      .metadata()
+      .row_groups()
+      .iter()
+      .map(|row_group| row_group.num_rows())
+      .collect();
+    assert_eq!(num_rows, vec![40, 40, 10, 10]);
+    let actual: Vec<Row> = split_reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(actual, rows);
+
+    // Row group within the limits is copied without re-encoding
+    for i in 0..3 {
+      assert_eq!(
+        split_reader.get_row_group(3).unwrap().get_column_chunk_bytes(i).unwrap(),
+        reader.get_row_group(1).unwrap().get_column_chunk_bytes(i).unwrap()
+      );
+    }
+  }
+
+  #[test]
+  fn test_row_group_splitter_max_bytes() {
+    let schema = parse_message_type("message schema { REQUIRED INT32 a; }").unwrap();
+    let descr = Rc::new(SchemaDescriptor::new(Rc::new(schema)));
+    let column = ColumnChunkMetaData::builder(descr.column(0)).build().unwrap();
+    let metadata = RowGroupMetaData::builder(descr)
+      .with_column_metadata(vec![Rc::new(column)])
+      .with_num_rows(1000)
+      .with_total_byte_size(4000)
+      .build()
+      .unwrap();
+    assert_eq!(RowGroupSplitter::new().rows_per_row_group(&metadata), None);
+    let splitter = RowGroupSplitter::new().with_max_bytes(1000);
+    assert_eq!(splitter.rows_per_row_group(&metadata), Some(250));
+    let splitter = splitter.with_max_rows(100);
+    assert_eq!(splitter.rows_per_row_group(&metadata), Some(100));
+    let splitter = RowGroupSplitter::new().with_max_bytes(1);
+    assert_eq!(splitter.rows_per_row_group(&metadata), Some(1));
+    let splitter = RowGroupSplitter::new().with_max_bytes(4000);
+    assert_eq!(splitter.rows_per_row_group(&metadata), None);
+  }
+}
@@ -0,0 +1,1222 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains definitions for working with Parquet statistics.
+//!
+//! Statistics hold min/max values, number of nulls and (optionally) number of distinct
+//! values of a column chunk or a data page. Column writers accumulate statistics with
+//! [`StatisticsAccumulator`] while values are written and store them in data page
+//! headers and column chunk metadata, so that readers can skip data based on them.
+//!
+//! Min/max values are stored as plain encoded values without length prefix. Writers
+//! set `min_value`/`max_value` fields of Thrift statistics, which are compared according
+//! to the sort order of the column. Values of the deprecated `min`/`max` fields are
+//! only used when new fields are not set, such statistics are marked with
+//! `is_min_max_deprecated` flag.
+//!
+//! # Example
+//!
+//! ```rust
+//! use parquet::basic::SortOrder;
+//! use parquet::data_type::Int32Type;
+//! use parquet::file::statistics::*;
+//!
+//! let mut accumulator = StatisticsAccumulator::<Int32Type>::new(SortOrder::SIGNED);
+//! accumulator.update(&[3, -1, 7]);
+//! accumulator.update_null_count(2);
+//!
+//! let stats = accumulator.statistics();
+//! assert_eq!(stats, Statistics::int32(Some(-1), Some(7), None, 2, false));
+//! assert_eq!(stats.null_count(), 2);
+//! ```
+
+use std::char;
+use std::cmp::{self, Ordering};
+use std::fmt;
+use std::iter;
+use std::str;
+
+use basic::{LogicalType, SortOrder, Type};
+use byteorder::{ByteOrder, LittleEndian};
+use data_type::*;
+use logical::{LogicalValue, PhysicalValue};
+use parquet_format::Statistics as TStatistics;
+use schema::types::ColumnDescriptor;
+
+// Macro to generate methods that create statistics for a physical type.
+macro_rules! statistics_new_func {
+  ($func:ident, $vtype:ty, $stat:ident) => {
+    pub fn $func(
+      min: Option<$vtype>,
+      max: Option<$vtype>,
+      distinct: Option<u64>,
+      nulls: u64,
+      is_deprecated: bool
+    ) -> Self {
+      Statistics::$stat(TypedStatistics::new(min, max, distinct, nulls, is_deprecated))
+    }
+  };
+}
+
+// Macro to generate getter functions for `Statistics`.
+macro_rules! statistics_enum_func {
+  ($self:ident, $func:ident) => {{
+    match *$self {
+      Statistics::Boolean(ref typed) => typed.$func(),
+      Statistics::Int32(ref typed) => typed.$func(),
+      Statistics::Int64(ref typed) => typed.$func(),
+      Statistics::Int96(ref typed) => typed.$func(),
+      Statistics::Float(ref typed) => typed.$func(),
+      Statistics::Double(ref typed) => typed.$func(),
+      Statistics::ByteArray(ref typed) => typed.$func(),
+      Statistics::FixedLenByteArray(ref typed) => typed.$func()
+    }
+  }};
+}
+
+/// Converts Thrift statistics into statistics of a column with physical type
+/// `physical_type`. Returns `None` if statistics are not set.
+pub fn from_thrift(
+  physical_type: Type,
+  thrift_stats: Option<TStatistics>
+) -> Option<Statistics> {
+  thrift_stats.map(|stats| {
+    // Negative null count is invalid, treat it as if there are no nulls
+    let null_count = cmp::max(stats.null_count.unwrap_or(0), 0) as u64;
+    let distinct_count = stats.distinct_count.map(|value| value as u64);
+
+    // Values of deprecated min/max fields are used only if new fields are not set,
+    // statistics without any min/max values, e.g. of all-null columns, are not
+    // deprecated
+    let (min, max, is_deprecated) =
+      if stats.min_value.is_some() || stats.max_value.is_some() {
+        (stats.min_value, stats.max_value, false)
+      } else if stats.min.is_some() || stats.max.is_some() {
+        (stats.min, stats.max, true)
+      } else {
+        (None, None, false)
+      };
+
+    Statistics::from_bytes(
+      physical_type,
+      min.as_ref().map(|v| &v[..]),
+      max.as_ref().map(|v| &v[..]),
+      distinct_count,
+      null_count,
+      is_deprecated
+    )
+  })
+}
+
+/// Converts statistics into Thrift definition. Returns `None` if statistics are not set.
+pub fn to_thrift(stats: Option<&Statistics>) -> Option<TStatistics> {
+  stats.map(|stats| {
+    let mut thrift_stats = TStatistics {
+      max: None,
+      min: None,
+      null_count: Some(stats.null_count() as i64),
+      distinct_count: stats.distinct_count().map(|value| value as i64),
+      max_value: None,
+      min_value: None
+    };
+
+    let min = stats.min_bytes().map(|v| v.to_vec());
+    let max = stats.max_bytes().map(|v| v.to_vec());
+    if stats.is_min_max_deprecated() {
+      thrift_stats.min = min;
+      thrift_stats.max = max;
+    } else {
+      thrift_stats.min_value = min;
+      thrift_stats.max_value = max;
+    }
+    thrift_stats
+  })
+}
+
+/// Statistics for a column chunk or a data page, typed by the physical type of the
+/// column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statistics {
+  Boolean(TypedStatistics<BoolType>),
+  Int32(TypedStatistics<Int32Type>),
+  Int64(TypedStatistics<Int64Type>),
+  Int96(TypedStatistics<Int96Type>),
+  Float(TypedStatistics<FloatType>),
+  Double(TypedStatistics<DoubleType>),
+  ByteArray(TypedStatistics<ByteArrayType>),
+  FixedLenByteArray(TypedStatistics<FixedLenByteArrayType>)
+}
+
+impl Statistics {
+  statistics_new_func![boolean, bool, Boolean];
+
+  statistics_new_func![int32, i32, Int32];
+
+  statistics_new_func![int64, i64, Int64];
+
+  statistics_new_func![int96, Int96, Int96];
+
+  statistics_new_func![float, f32, Float];
+
+  statistics_new_func![double, f64, Double];
+
+  statistics_new_func![byte_array, ByteArray, ByteArray];
+
+  statistics_new_func![fixed_len_byte_array, ByteArray, FixedLenByteArray];
+
+  /// Creates statistics of a column with physical type `physical_type` from plain
+  /// encoded min/max values. Values that cannot be decoded are treated as not set.
+  pub fn from_bytes(
+    physical_type: Type,
+    min: Option<&[u8]>,
+    max: Option<&[u8]>,
+    distinct: Option<u64>,
+    nulls: u64,
+    is_deprecated: bool
+  ) -> Self {
+    match physical_type {
+      Type::BOOLEAN => Statistics::boolean(
+        min.and_then(read_bool), max.and_then(read_bool), distinct, nulls, is_deprecated),
+      Type::INT32 => Statistics::int32(
+        min.and_then(read_i32), max.and_then(read_i32), distinct, nulls, is_deprecated),
+      Type::INT64 => Statistics::int64(
+        min.and_then(read_i64), max.and_then(read_i64), distinct, nulls, is_deprecated),
+      Type::INT96 => Statistics::int96(
+        min.and_then(read_int96),
+        max.and_then(read_int96),
+        distinct,
+        nulls,
+        is_deprecated
+      ),
+      Type::FLOAT => Statistics::float(
+        min.and_then(read_f32), max.and_then(read_f32), distinct, nulls, is_deprecated),
+      Type::DOUBLE => Statistics::double(
+        min.and_then(read_f64), max.and_then(read_f64), distinct, nulls, is_deprecated),
+      Type::BYTE_ARRAY => Statistics::byte_array(
+        min.map(read_byte_array),
+        max.map(read_byte_array),
+        distinct,
+        nulls,
+        is_deprecated
+      ),
+      Type::FIXED_LEN_BYTE_ARRAY => Statistics::fixed_len_byte_array(
+        min.map(read_byte_array),
+        max.map(read_byte_array),
+        distinct,
+        nulls,
+        is_deprecated
+      )
+    }
+  }
+
+  /// Returns `true` if both min and max values are set.
+  pub fn has_min_max_set(&self) -> bool {
+    statistics_enum_func![self, has_min_max_set]
+  }
+
+  /// Returns `true` if min/max values were read from deprecated `min`/`max` fields,
+  /// which might have been computed with a different sort order.
+  pub fn is_min_max_deprecated(&self) -> bool {
+    statistics_enum_func![self, is_min_max_deprecated]
+  }
+
+  /// Returns number of distinct values, if set.
+  pub fn distinct_count(&self) -> Option<u64> {
+    statistics_enum_func![self, distinct_count]
+  }
+
+  /// Returns number of null values.
+  pub fn null_count(&self) -> u64 {
+    statistics_enum_func![self, null_count]
+  }
+
+  /// Returns plain encoded min value, if set.
+  pub fn min_bytes(&self) -> Option<&[u8]> {
+    statistics_enum_func![self, min_bytes]
+  }
+
+  /// Returns plain encoded max value, if set.
+  pub fn max_bytes(&self) -> Option<&[u8]> {
+    statistics_enum_func![self, max_bytes]
+  }
+
+  /// Returns min/max values converted into logical values of column `descr`, or
+  /// `None` if min/max values are not set or logical type of the column is not
+  /// supported.
+  ///
+  /// Deprecated min/max fields of byte arrays are ignored, since they might have been
+  /// computed with a byte-wise order that differs from the order of the logical type,
+  /// e.g. of strings or decimals.
+  pub fn logical_min_max(
+    &self,
+    descr: &ColumnDescriptor
+  ) -> Option<(LogicalValue, LogicalValue)> {
+    match *self {
+      Statistics::Boolean(ref typed) => typed.logical_min_max(descr),
+      Statistics::Int32(ref typed) => typed.logical_min_max(descr),
+      Statistics::Int64(ref typed) => typed.logical_min_max(descr),
+      Statistics::Int96(ref typed) => typed.logical_min_max(descr),
+      Statistics::Float(ref typed) => typed.logical_min_max(descr),
+      Statistics::Double(ref typed) => typed.logical_min_max(descr),
+      Statistics::ByteArray(ref typed) if !typed.is_min_max_deprecated() => {
+        typed.logical_min_max(descr)
+      },
+      Statistics::FixedLenByteArray(ref typed) if !typed.is_min_max_deprecated() => {
+        typed.logical_min_max(descr)
+      },
+      _ => None
+    }
+  }
+
+  /// Returns min/max values of DECIMAL column `descr` with precision and scale of the
+  /// column, or `None` if min/max values are not set or column is not annotated as
+  /// DECIMAL. See [`logical_min_max`](`Statistics::logical_min_max`).
+  pub fn decimal_min_max(&self, descr: &ColumnDescriptor) -> Option<(Decimal, Decimal)> {
+    match self.logical_min_max(descr)? {
+      (LogicalValue::Decimal(min), LogicalValue::Decimal(max)) => Some((min, max)),
+      _ => None
+    }
+  }
+
+  /// Returns min/max values of INT96 timestamps in milliseconds since Unix epoch, or
+  /// `None` if min/max values are not set or statistics are not INT96 statistics.
+  ///
+  /// Sort order of INT96 is undefined, values are only meaningful if the writer
+  /// compared them as timestamps.
+  pub fn timestamp_min_max(&self) -> Option<(i64, i64)> {
+    match *self {
+      Statistics::Int96(ref typed) => {
+        Some((typed.min()?.to_millis(), typed.max()?.to_millis()))
+      },
+      _ => None
+    }
+  }
+
+  /// Returns physical type of the statistics.
+  pub fn physical_type(&self) -> Type {
+    match *self {
+      Statistics::Boolean(_) => Type::BOOLEAN,
+      Statistics::Int32(_) => Type::INT32,
+      Statistics::Int64(_) => Type::INT64,
+      Statistics::Int96(_) => Type::INT96,
+      Statistics::Float(_) => Type::FLOAT,
+      Statistics::Double(_) => Type::DOUBLE,
+      Statistics::ByteArray(_) => Type::BYTE_ARRAY,
+      Statistics::FixedLenByteArray(_) => Type::FIXED_LEN_BYTE_ARRAY
+    }
+  }
+}
+
+impl fmt::Display for Statistics {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Statistics::Boolean(ref typed) => write!(f, "{}", typed),
+      Statistics::Int32(ref typed) => write!(f, "{}", typed),
+      Statistics::Int64(ref typed) => write!(f, "{}", typed),
+      Statistics::Int96(ref typed) => write!(f, "{}", typed),
+      Statistics::Float(ref typed) => write!(f, "{}", typed),
+      Statistics::Double(ref typed) => write!(f, "{}", typed),
+      Statistics::ByteArray(ref typed) => write!(f, "{}", typed),
+      Statistics::FixedLenByteArray(ref typed) => write!(f, "{}", typed)
+    }
+  }
+}
+
+/// Typed implementation of statistics for a physical type `T`.
+pub struct TypedStatistics<T: DataType> {
+  min: Option<T::T>,
+  max: Option<T::T>,
+  distinct_count: Option<u64>,
+  null_count: u64,
+  is_min_max_deprecated: bool
+}
+
+impl<T: DataType> TypedStatistics<T> {
+  /// Creates new typed statistics.
+  pub fn new(
+    min: Option<T::T>,
+    max: Option<T::T>,
+    distinct_count: Option<u64>,
+    null_count: u64,
+    is_min_max_deprecated: bool
+  ) -> Self {
+    Self {
+      min: min,
+      max: max,
+      distinct_count: distinct_count,
+      null_count: null_count,
+      is_min_max_deprecated: is_min_max_deprecated
+    }
+  }
+
+  /// Returns min value, if set.
+  pub fn min(&self) -> Option<&T::T> {
+    self.min.as_ref()
+  }
+
+  /// Returns max value, if set.
+  pub fn max(&self) -> Option<&T::T> {
+    self.max.as_ref()
+  }
+
+  /// Returns plain encoded min value, if set.
+  pub fn min_bytes(&self) -> Option<&[u8]> {
+    self.min.as_ref().map(|value| value.as_bytes())
+  }
+
+  /// Returns plain encoded max value, if set.
+  pub fn max_bytes(&self) -> Option<&[u8]> {
+    self.max.as_ref().map(|value| value.as_bytes())
+  }
+
+  /// Returns `true` if both min and max values are set.
+  pub fn has_min_max_set(&self) -> bool {
+    self.min.is_some() && self.max.is_some()
+  }
+
+  /// Returns number of distinct values, if set.
+  pub fn distinct_count(&self) -> Option<u64> {
+    self.distinct_count
+  }
+
+  /// Returns number of null values.
+  pub fn null_count(&self) -> u64 {
+    self.null_count
+  }
+
+  /// Returns `true` if min/max values were read from deprecated fields.
+  pub fn is_min_max_deprecated(&self) -> bool {
+    self.is_min_max_deprecated
+  }
+
+  /// Returns min/max values converted into logical values of column `descr`, or
+  /// `None` if min/max values are not set or cannot be converted.
+  pub fn logical_min_max(
+    &self,
+    descr: &ColumnDescriptor
+  ) -> Option<(LogicalValue, LogicalValue)> where T::T: PhysicalValue {
+    let min = self.min()?.to_logical(descr).ok()?;
+    let max = self.max()?.to_logical(descr).ok()?;
+    Some((min, max))
+  }
+}
+
+impl<T: DataType> Clone for TypedStatistics<T> {
+  fn clone(&self) -> Self {
+    Self {
+      min: self.min.clone(),
+      max: self.max.clone(),
+      distinct_count: self.distinct_count,
+      null_count: self.null_count,
+      is_min_max_deprecated: self.is_min_max_deprecated
+    }
+  }
+}
+
+impl<T: DataType> PartialEq for TypedStatistics<T> {
+  fn eq(&self, other: &TypedStatistics<T>) -> bool {
+    self.min == other.min &&
+      self.max == other.max &&
+      self.distinct_count == other.distinct_count &&
+      self.null_count == other.null_count &&
+      self.is_min_max_deprecated == other.is_min_max_deprecated
+  }
+}
+
+impl<T: DataType> fmt::Debug for TypedStatistics<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{{min: {:?}, max: {:?}, distinct_count: {:?}, null_count: {}, \
+       min_max_deprecated: {}}}",
+      self.min,
+      self.max,
+      self.distinct_count,
+      self.null_count,
+      self.is_min_max_deprecated
+    )
+  }
+}
+
+impl<T: DataType> fmt::Display for TypedStatistics<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{{")?;
+    write!(f, "min: ")?;
+    match self.min {
+      Some(ref value) => write!(f, "{:?}", value)?,
+      None => write!(f, "N/A")?
+    }
+    write!(f, ", max: ")?;
+    match self.max {
+      Some(ref value) => write!(f, "{:?}", value)?,
+      None => write!(f, "N/A")?
+    }
+    write!(f, ", distinct_count: ")?;
+    match self.distinct_count {
+      Some(value) => write!(f, "{}", value)?,
+      None => write!(f, "N/A")?
+    }
+    write!(f, ", null_count: {}", self.null_count)?;
+    write!(f, ", min_max_deprecated: {}", self.is_min_max_deprecated)?;
+    write!(f, "}}")
+  }
+}
+
+/// Accumulates statistics of values written into a column chunk or a data page.
+///
+/// Min/max values are compared according to the sort order of the column: integers
+/// annotated as unsigned are compared as unsigned values, byte arrays are compared
+/// byte-wise as unsigned bytes. NaN values of floating point columns are ignored. If
+/// sort order is undefined, only nulls are counted.
+///
+/// Min/max values of byte arrays can be truncated to a maximum size in bytes, see
+/// [`truncate_min_value`](`truncate_min_value`) and
+/// [`truncate_max_value`](`truncate_max_value`), strings are truncated at character
+/// boundaries, see [`truncate_utf8_min_value`](`truncate_utf8_min_value`) and
+/// [`truncate_utf8_max_value`](`truncate_utf8_max_value`).
+pub struct StatisticsAccumulator<T: DataType> {
+  sort_order: SortOrder,
+  min: Option<T::T>,
+  max: Option<T::T>,
+  null_count: u64,
+  max_size: Option<usize>,
+  is_utf8: bool
+}
+
+impl<T: DataType> StatisticsAccumulator<T> {
+  /// Creates new accumulator for values compared with sort order `sort_order`.
+  pub fn new(sort_order: SortOrder) -> Self {
+    Self {
+      sort_order: sort_order,
+      min: None,
+      max: None,
+      null_count: 0,
+      max_size: None,
+      is_utf8: false
+    }
+  }
+
+  /// Sets maximum size in bytes of min/max values in statistics, and returns itself.
+  ///
+  /// Only BYTE_ARRAY values compared byte-wise are truncated. Values of other types
+  /// have fixed size, FIXED_LEN_BYTE_ARRAY values must keep the length of the type, or
+  /// cannot be truncated without changing their order.
+  pub fn with_max_size(mut self, max_size: Option<usize>) -> Self {
+    self.max_size = max_size;
+    self
+  }
+
+  /// Sets logical type of values, and returns itself. Min/max values of UTF8, ENUM and
+  /// JSON strings are truncated at character boundaries, so they remain valid strings.
+  pub fn with_logical_type(mut self, logical_type: LogicalType) -> Self {
+    self.is_utf8 = match logical_type {
+      LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON => true,
+      _ => false
+    };
+    self
+  }
+
+  /// Updates min/max values with non-null `values`.
+  pub fn update(&mut self, values: &[T::T]) {
+    if self.sort_order == SortOrder::UNDEFINED {
+      return;
+    }
+    for value in values {
+      // NaN values are not comparable, therefore cannot be min or max
+      if value.partial_cmp(value).is_none() {
+        continue;
+      }
+      self.update_min(value);
+      self.update_max(value);
+    }
+  }
+
+  /// Adds `num_nulls` to the number of null values.
+  pub fn update_null_count(&mut self, num_nulls: u64) {
+    self.null_count += num_nulls;
+  }
+
+  /// Merges statistics accumulated by `other` into this accumulator.
+  pub fn merge(&mut self, other: &StatisticsAccumulator<T>) {
+    if let Some(ref value) = other.min {
+      self.update_min(value);
+    }
+    if let Some(ref value) = other.max {
+      self.update_max(value);
+    }
+    self.null_count += other.null_count;
+  }
+
+  /// Returns accumulated min value, if any.
+  pub fn min(&self) -> Option<&T::T> {
+    self.min.as_ref()
+  }
+
+  /// Returns accumulated max value, if any.
+  pub fn max(&self) -> Option<&T::T> {
+    self.max.as_ref()
+  }
+
+  /// Returns accumulated number of null values.
+  pub fn null_count(&self) -> u64 {
+    self.null_count
+  }
+
+  /// Returns `true` if no values (including nulls) have been accumulated.
+  pub fn is_empty(&self) -> bool {
+    self.min.is_none() && self.max.is_none() && self.null_count == 0
+  }
+
+  /// Returns plain encoded min and max values, truncated to the maximum size if it is
+  /// set, or `None` if no values have been accumulated.
+  pub fn min_max_bytes(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+    let (min, max) = match (self.min.as_ref(), self.max.as_ref()) {
+      (Some(min), Some(max)) => (min.as_bytes(), max.as_bytes()),
+      _ => return None
+    };
+    match self.truncation_size() {
+      Some(max_size) => {
+        let (min, truncated_max) = if self.is_utf8 {
+          (truncate_utf8_min_value(min, max_size), truncate_utf8_max_value(max, max_size))
+        } else {
+          (truncate_min_value(min, max_size), truncate_max_value(max, max_size))
+        };
+        // Max value that cannot be truncated is kept as is
+        Some((min.to_vec(), truncated_max.unwrap_or_else(|| max.to_vec())))
+      },
+      None => Some((min.to_vec(), max.to_vec()))
+    }
+  }
+
+  /// Returns accumulated statistics.
+  pub fn statistics(&self) -> Statistics {
+    let min_max = self.min_max_bytes();
+    Statistics::from_bytes(
+      T::get_physical_type(),
+      min_max.as_ref().map(|&(ref min, _)| &min[..]),
+      min_max.as_ref().map(|&(_, ref max)| &max[..]),
+      None,
+      self.null_count,
+      false
+    )
+  }
+
+  /// Resets accumulated statistics.
+  pub fn reset(&mut self) {
+    self.min = None;
+    self.max = None;
+    self.null_count = 0;
+  }
+
+  #[inline]
+  fn update_min(&mut self, value: &T::T) {
+    let is_less = match self.min {
+      Some(ref min) => self.compare(value, min) == Ordering::Less,
+      None => true
+    };
+    if is_less {
+      self.min = Some(value.clone());
+    }
+  }
+
+  #[inline]
+  fn update_max(&mut self, value: &T::T) {
+    let is_greater = match self.max {
+      Some(ref max) => self.compare(value, max) == Ordering::Greater,
+      None => true
+    };
+    if is_greater {
+      self.max = Some(value.clone());
+    }
+  }
+
+  /// Returns maximum size of min/max values if they can be truncated.
+  #[inline]
+  fn truncation_size(&self) -> Option<usize> {
+    match T::get_physical_type() {
+      Type::BYTE_ARRAY if self.sort_order == SortOrder::UNSIGNED => self.max_size,
+      _ => None
+    }
+  }
+
+  /// Compares two values according to the sort order.
+  #[inline]
+  pub fn compare(&self, a: &T::T, b: &T::T) -> Ordering {
+    match (self.sort_order, T::get_physical_type()) {
+      (SortOrder::UNSIGNED, Type::INT32) => {
+        LittleEndian::read_u32(a.as_bytes()).cmp(&LittleEndian::read_u32(b.as_bytes()))
+      },
+      (SortOrder::UNSIGNED, Type::INT64) => {
+        LittleEndian::read_u64(a.as_bytes()).cmp(&LittleEndian::read_u64(b.as_bytes()))
+      },
+      // Signed byte arrays are DECIMAL values encoded as big-endian two's complement
+      (SortOrder::SIGNED, Type::BYTE_ARRAY) |
+        (SortOrder::SIGNED, Type::FIXED_LEN_BYTE_ARRAY) => {
+        compare_signed_bytes(a.as_bytes(), b.as_bytes())
+      },
+      _ => a.partial_cmp(b).unwrap_or(Ordering::Equal)
+    }
+  }
+}
+
+/// Compares big-endian two's complement integers of possibly different length, e.g.
+/// DECIMAL values stored in byte arrays. Empty byte array is treated as zero.
+fn compare_signed_bytes(a: &[u8], b: &[u8]) -> Ordering {
+  let is_negative = |bytes: &[u8]| bytes.first().map_or(false, |&byte| (byte as i8) < 0);
+  match (is_negative(a), is_negative(b)) {
+    (true, false) => Ordering::Less,
+    (false, true) => Ordering::Greater,
+    (is_negative, _) => {
+      // Values of the same sign compare byte-wise after sign extension
+      let sign_byte = if is_negative { 0xFF } else { 0 };
+      let len = cmp::max(a.len(), b.len());
+      let a = iter::repeat(sign_byte).take(len - a.len()).chain(a.iter().cloned());
+      let b = iter::repeat(sign_byte).take(len - b.len()).chain(b.iter().cloned());
+      a.cmp(b)
+    }
+  }
+}
+
+/// Truncates min value of byte arrays compared byte-wise to at most `max_size` bytes.
+///
+/// Prefix of a value is less than or equal to the value, therefore it remains a valid
+/// lower bound.
+pub fn truncate_min_value(value: &[u8], max_size: usize) -> &[u8] {
+  &value[..cmp::min(value.len(), max_size)]
+}
+
+/// Truncates max value of byte arrays compared byte-wise to at most `max_size` bytes.
+///
+/// Prefix of a value is incremented as a big-endian number, i.e. the last byte that
+/// is less than `0xFF` is incremented and bytes after it are dropped, so the result is
+/// greater than the value and remains a valid upper bound. Returns `None` if the
+/// prefix consists of `0xFF` bytes only, in which case the value cannot be truncated.
+pub fn truncate_max_value(value: &[u8], max_size: usize) -> Option<Vec<u8>> {
+  if value.len() <= max_size {
+    return Some(value.to_vec());
+  }
+  let mut prefix = value[..max_size].to_vec();
+  while let Some(byte) = prefix.pop() {
+    if byte < 0xff {
+      prefix.push(byte + 1);
+      return Some(prefix);
+    }
+  }
+  None
+}
+
+/// Truncates min value of UTF8 strings to at most `max_size` bytes at a character
+/// boundary, so the result is a valid string and a valid lower bound. Values that are
+/// not valid UTF8 are truncated as bytes, see `truncate_min_value`.
+pub fn truncate_utf8_min_value(value: &[u8], max_size: usize) -> &[u8] {
+  match str::from_utf8(value) {
+    Ok(s) => &value[..utf8_prefix_len(s, max_size)],
+    Err(_) => truncate_min_value(value, max_size)
+  }
+}
+
+/// Truncates max value of UTF8 strings to at most `max_size` bytes.
+///
+/// Prefix of the string at a character boundary is incremented, i.e. the last
+/// character that can be replaced by the next code point without exceeding
+/// `max_size` bytes is replaced and characters after it are dropped, so the result is
+/// a valid string greater than the value. Returns `None` if no character of the prefix
+/// can be incremented. Values that are not valid UTF8 are truncated as bytes, see
+/// `truncate_max_value`.
+pub fn truncate_utf8_max_value(value: &[u8], max_size: usize) -> Option<Vec<u8>> {
+  let s = match str::from_utf8(value) {
+    Ok(s) => s,
+    Err(_) => return truncate_max_value(value, max_size)
+  };
+  if s.len() <= max_size {
+    return Some(value.to_vec());
+  }
+  let mut prefix = s[..utf8_prefix_len(s, max_size)].to_string();
+  while let Some(c) = prefix.pop() {
+    if let Some(next) = next_code_point(c) {
+      if prefix.len() + next.len_utf8() <= max_size {
+        prefix.push(next);
+        return Some(prefix.into_bytes());
+      }
+    }
+  }
+  None
+}
+
+/// Returns length in bytes of the longest prefix of `s` of at most `max_size` bytes
+/// that ends at a character boundary.
+fn utf8_prefix_len(s: &str, max_size: usize) -> usize {
+  if s.len() <= max_size {
+    return s.len();
+  }
+  let mut len = max_size;
+  while !s.is_char_boundary(len) {
+    len -= 1;
+  }
+  len
+}
+
+/// Returns character with the next code point, skipping surrogates, or `None` if `c`
+/// is the last character.
+fn next_code_point(c: char) -> Option<char> {
+  match c as u32 + 1 {
+    0xD800 => char::from_u32(0xE000),
+    code => char::from_u32(code)
+  }
+}
+
+// ----------------------------------------------------------------------
+// Decoding of plain encoded min/max values
+
+fn read_bool(data: &[u8]) -> Option<bool> {
+  if data.len() == 1 { Some(data[0] != 0) } else { None }
+}
+
+fn read_i32(data: &[u8]) -> Option<i32> {
+  if data.len() == 4 { Some(LittleEndian::read_i32(data)) } else { None }
+}
+
+fn read_i64(data: &[u8]) -> Option<i64> {
+  if data.len() == 8 { Some(LittleEndian::read_i64(data)) } else { None }
+}
+
+fn read_int96(data: &[u8]) -> Option<Int96> {
+  if data.len() == 12 {
+    let mut value = vec![0; 3];
+    LittleEndian::read_u32_into(data, &mut value);
+    Some(Int96::from(value))
+  } else {
+    None
+  }
+}
+
+fn read_f32(data: &[u8]) -> Option<f32> {
+  if data.len() == 4 { Some(LittleEndian::read_f32(data)) } else { None }
+}
+
+fn read_f64(data: &[u8]) -> Option<f64> {
+  if data.len() == 8 { Some(LittleEndian::read_f64(data)) } else { None }
+}
+
+fn read_byte_array(data: &[u8]) -> ByteArray {
+  ByteArray::from(data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use basic::LogicalType;
+  use schema::parser::parse_message_type;
+  use schema::types::SchemaDescriptor;
+
+  #[test]
+  fn test_statistics_accumulator_signed() {
+    let mut accumulator = StatisticsAccumulator::<Int32Type>::new(SortOrder::SIGNED);
+    assert!(accumulator.is_empty());
+    accumulator.update(&[5, -3, 10, 0]);
+    accumulator.update_null_count(3);
+    assert!(!accumulator.is_empty());
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::int32(Some(-3), Some(10), None, 3, false)
+    );
+
+    accumulator.reset();
+    assert!(accumulator.is_empty());
+    assert_eq!(accumulator.statistics(), Statistics::int32(None, None, None, 0, false));
+  }
+
+  #[test]
+  fn test_statistics_accumulator_unsigned() {
+    let sort_order = Int32Type::get_sort_order(LogicalType::UINT_32);
+    let mut accumulator = StatisticsAccumulator::<Int32Type>::new(sort_order);
+    accumulator.update(&[5, -3, 10, 0]);
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::int32(Some(0), Some(-3), None, 0, false)
+    );
+
+    let sort_order = Int64Type::get_sort_order(LogicalType::UINT_64);
+    let mut accumulator = StatisticsAccumulator::<Int64Type>::new(sort_order);
+    accumulator.update(&[1, -1, 2]);
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::int64(Some(1), Some(-1), None, 0, false)
+    );
+  }
+
+  #[test]
+  fn test_statistics_accumulator_byte_array() {
+    let sort_order = ByteArrayType::get_sort_order(LogicalType::UTF8);
+    let mut accumulator = StatisticsAccumulator::<ByteArrayType>::new(sort_order);
+    accumulator.update(&[
+      ByteArray::from("b"),
+      ByteArray::from(vec![0xff]),
+      ByteArray::from("a"),
+      ByteArray::from("ab")
+    ]);
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::byte_array(
+        Some(ByteArray::from("a")),
+        Some(ByteArray::from(vec![0xff])),
+        None,
+        0,
+        false
+      )
+    );
+  }
+
+  #[test]
+  fn test_truncate_min_max_value() {
+    assert_eq!(truncate_min_value(b"abcdef", 3), b"abc");
+    assert_eq!(truncate_min_value(b"ab", 3), b"ab");
+
+    assert_eq!(truncate_max_value(b"abcdef", 3), Some(b"abd".to_vec()));
+    assert_eq!(truncate_max_value(b"ab", 3), Some(b"ab".to_vec()));
+    assert_eq!(truncate_max_value(&[1, 0xff, 0xff, 2], 3), Some(vec![2]));
+    assert_eq!(truncate_max_value(&[0xff, 0xff, 0xff, 2], 3), None);
+    assert_eq!(truncate_max_value(b"abc", 0), None);
+  }
+
+  #[test]
+  fn test_truncate_utf8_min_max_value() {
+    // "aé€😀" has characters of 1, 2, 3 and 4 bytes
+    let value = "aé€😀".as_bytes();
+    assert_eq!(truncate_utf8_min_value(value, 10), value);
+    assert_eq!(truncate_utf8_min_value(value, 6), "aé€".as_bytes());
+    assert_eq!(truncate_utf8_min_value(value, 5), "aé".as_bytes());
+    assert_eq!(truncate_utf8_min_value(value, 2), "a".as_bytes());
+    assert_eq!(truncate_utf8_min_value(value, 0), b"");
+
+    assert_eq!(truncate_utf8_max_value(value, 10), Some(value.to_vec()));
+    assert_eq!(truncate_utf8_max_value(value, 6), Some("aé₭".as_bytes().to_vec()));
+    assert_eq!(truncate_utf8_max_value(value, 5), Some("aê".as_bytes().to_vec()));
+    assert_eq!(truncate_utf8_max_value(value, 2), Some("b".as_bytes().to_vec()));
+    assert_eq!(truncate_utf8_max_value(value, 0), None);
+
+    // Next code point of U+007F needs 2 bytes, previous character is incremented
+    assert_eq!(truncate_utf8_max_value(b"a\x7fz", 2), Some(b"b".to_vec()));
+    // Surrogates are skipped
+    assert_eq!(
+      truncate_utf8_max_value("\u{D7FF}a".as_bytes(), 3),
+      Some("\u{E000}".as_bytes().to_vec())
+    );
+    // Last code point cannot be incremented
+    assert_eq!(truncate_utf8_max_value("\u{10FFFF}a".as_bytes(), 4), None);
+
+    // Invalid UTF8 is truncated as bytes
+    assert_eq!(truncate_utf8_min_value(&[0xff, 0xfe, 0x61], 2), &[0xff, 0xfe]);
+    assert_eq!(truncate_utf8_max_value(&[0xfe, 0xfe, 0x61], 2), Some(vec![0xfe, 0xff]));
+  }
+
+  #[test]
+  fn test_statistics_accumulator_truncation() {
+    let sort_order = ByteArrayType::get_sort_order(LogicalType::UTF8);
+    let mut accumulator = StatisticsAccumulator::<ByteArrayType>::new(sort_order)
+      .with_max_size(Some(4));
+    accumulator.update(&[
+      ByteArray::from("parquet"),
+      ByteArray::from("column"),
+      ByteArray::from("row")
+    ]);
+    assert_eq!(
+      accumulator.min_max_bytes(),
+      Some((b"colu".to_vec(), b"row".to_vec()))
+    );
+    accumulator.update(&[ByteArray::from("writer")]);
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::byte_array(
+        Some(ByteArray::from("colu")),
+        Some(ByteArray::from("wriu")),
+        None,
+        0,
+        false
+      )
+    );
+
+    // Max value with 0xFF prefix is not truncated
+    accumulator.update(&[ByteArray::from(vec![0xff, 0xff, 0xff, 0xff, 0xff])]);
+    assert_eq!(
+      accumulator.min_max_bytes(),
+      Some((b"colu".to_vec(), vec![0xff, 0xff, 0xff, 0xff, 0xff]))
+    );
+
+    // Strings are truncated at character boundaries
+    let mut accumulator = StatisticsAccumulator::<ByteArrayType>::new(sort_order)
+      .with_max_size(Some(4))
+      .with_logical_type(LogicalType::UTF8);
+    accumulator.update(&[ByteArray::from("aé€"), ByteArray::from("€éa")]);
+    assert_eq!(
+      accumulator.min_max_bytes(),
+      Some(("aé".as_bytes().to_vec(), "₭".as_bytes().to_vec()))
+    );
+
+    // Fixed length byte arrays keep length of the type
+    let sort_order = FixedLenByteArrayType::get_sort_order(LogicalType::NONE);
+    let mut accumulator = StatisticsAccumulator::<FixedLenByteArrayType>::new(sort_order)
+      .with_max_size(Some(1));
+    accumulator.update(&[ByteArray::from(vec![1, 2, 3]), ByteArray::from(vec![4, 5, 6])]);
+    assert_eq!(accumulator.min_max_bytes(), Some((vec![1, 2, 3], vec![4, 5, 6])));
+
+    // Signed byte arrays, e.g. decimals, are not truncated
+    let sort_order = ByteArrayType::get_sort_order(LogicalType::DECIMAL);
+    let mut accumulator = StatisticsAccumulator::<ByteArrayType>::new(sort_order)
+      .with_max_size(Some(1));
+    accumulator.update(&[ByteArray::from(vec![1, 2, 3])]);
+    assert_eq!(accumulator.min_max_bytes(), Some((vec![1, 2, 3], vec![1, 2, 3])));
+  }
+
+  #[test]
+  fn test_statistics_accumulator_float_nan() {
+    let mut accumulator = StatisticsAccumulator::<DoubleType>::new(SortOrder::SIGNED);
+    accumulator.update(&[::std::f64::NAN, 1.5, -2.0, ::std::f64::NAN]);
+    assert_eq!(
+      accumulator.statistics(),
+      Statistics::double(Some(-2.0), Some(1.5), None, 0, false)
+    );
+
+    let mut accumulator = StatisticsAccumulator::<FloatType>::new(SortOrder::SIGNED);
+    accumulator.update(&[::std::f32::NAN]);
+    assert!(!accumulator.statistics().has_min_max_set());
+  }
+
+  #[test]
+  fn test_statistics_accumulator_undefined_sort_order() {
+    let sort_order = Int96Type::get_sort_order(LogicalType::NONE);
+    let mut accumulator = StatisticsAccumulator::<Int96Type>::new(sort_order);
+    accumulator.update(&[Int96::from(vec![1, 2, 3])]);
+    accumulator.update_null_count(1);
+    assert_eq!(accumulator.statistics(), Statistics::int96(None, None, None, 1, false));
+  }
+
+  #[test]
+  fn test_statistics_accumulator_merge() {
+    let mut page1 = StatisticsAccumulator::<Int64Type>::new(SortOrder::SIGNED);
+    page1.update(&[10, 20]);
+    page1.update_null_count(1);
+    let mut page2 = StatisticsAccumulator::<Int64Type>::new(SortOrder::SIGNED);
+    page2.update(&[-5, 15]);
+    page2.update_null_count(2);
+    let page3 = StatisticsAccumulator::<Int64Type>::new(SortOrder::SIGNED);
+
+    let mut chunk = StatisticsAccumulator::<Int64Type>::new(SortOrder::SIGNED);
+    chunk.merge(&page1);
+    chunk.merge(&page2);
+    chunk.merge(&page3);
+    assert_eq!(
+      chunk.statistics(),
+      Statistics::int64(Some(-5), Some(20), None, 3, false)
+    );
+  }
+
+  #[test]
+  fn test_statistics_accumulator_decimal() {
+    let sort_order = FixedLenByteArrayType::get_sort_order(LogicalType::DECIMAL);
+    let mut accumulator = StatisticsAccumulator::<FixedLenByteArrayType>::new(sort_order);
+    let values: Vec<ByteArray> = [5, -300, 127, -1, 0]
+      .iter()
+      .map(|&value| ByteArray::from_decimal(value, 2).unwrap())
+      .collect();
+    accumulator.update(&values);
+    assert_eq!(accumulator.min().unwrap().to_decimal(), Some(-300));
+    assert_eq!(accumulator.max().unwrap().to_decimal(), Some(127));
+
+    // Decimals in byte arrays might have different lengths
+    let sort_order = ByteArrayType::get_sort_order(LogicalType::DECIMAL);
+    let mut accumulator = StatisticsAccumulator::<ByteArrayType>::new(sort_order);
+    accumulator.update(&[
+      ByteArray::from(vec![0x01, 0x00]),
+      ByteArray::from(vec![0x7F]),
+      ByteArray::from(vec![0xFF, 0x00]),
+      ByteArray::from(vec![0x80])
+    ]);
+    assert_eq!(accumulator.min().unwrap().to_decimal(), Some(-256));
+    assert_eq!(accumulator.max().unwrap().to_decimal(), Some(256));
+  }
+
+  #[test]
+  fn test_statistics_decimal_min_max() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a (DECIMAL(5, 2));
+        REQUIRED FIXED_LEN_BYTE_ARRAY(4) b (DECIMAL(9, 3));
+        REQUIRED INT64 c;
+      }
+    ";
+    let descr = SchemaDescriptor::new(Rc::new(parse_message_type(schema).unwrap()));
+
+    let stats = Statistics::int32(Some(-150), Some(2500), None, 0, false);
+    assert_eq!(
+      stats.decimal_min_max(&descr.column(0)),
+      Some((Decimal::new(-150, 5, 2), Decimal::new(2500, 5, 2)))
+    );
+
+    let min = ByteArray::from_decimal(-1_000_000, 4);
+    let max = ByteArray::from_decimal(42, 4);
+    let stats = Statistics::fixed_len_byte_array(min, max, None, 0, false);
+    assert_eq!(
+      stats.decimal_min_max(&descr.column(1)),
+      Some((Decimal::new(-1_000_000, 9, 3), Decimal::new(42, 9, 3)))
+    );
+
+    // Deprecated min/max values of byte arrays might be compared as unsigned bytes
+    let min = ByteArray::from_decimal(-1, 4);
+    let max = ByteArray::from_decimal(1, 4);
+    let stats = Statistics::fixed_len_byte_array(min, max, None, 0, true);
+    assert_eq!(stats.decimal_min_max(&descr.column(1)), None);
+
+    // Column is not annotated as DECIMAL
+    let stats = Statistics::int64(Some(1), Some(2), None, 0, false);
+    assert_eq!(stats.decimal_min_max(&descr.column(2)), None);
+  }
+
+  #[test]
+  fn test_statistics_logical_min_max() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a (INT_16);
+        REQUIRED INT64 b (TIMESTAMP_MILLIS);
+        REQUIRED BYTE_ARRAY c (UTF8);
+        REQUIRED INT32 d (UINT_8);
+      }
+    ";
+    let descr = SchemaDescriptor::new(Rc::new(parse_message_type(schema).unwrap()));
+
+    let stats = Statistics::int32(Some(-3), Some(7), None, 0, false);
+    assert_eq!(
+      stats.logical_min_max(&descr.column(0)),
+      Some((LogicalValue::Short(-3), LogicalValue::Short(7)))
+    );
+
+    let stats = Statistics::int64(Some(0), Some(1_238_544_000_000), None, 0, false);
+    assert_eq!(
+      stats.logical_min_max(&descr.column(1)),
+      Some((LogicalValue::Timestamp(0), LogicalValue::Timestamp(1_238_544_000_000)))
+    );
+
+    let min = Some(ByteArray::from("apple"));
+    let max = Some(ByteArray::from("pear"));
+    let stats = Statistics::byte_array(min.clone(), max.clone(), None, 0, false);
+    assert_eq!(
+      stats.logical_min_max(&descr.column(2)),
+      Some((
+        LogicalValue::Str("apple".to_string()),
+        LogicalValue::Str("pear".to_string())
+      ))
+    );
+    let stats = Statistics::byte_array(min, max, None, 0, true);
+    assert_eq!(stats.logical_min_max(&descr.column(2)), None);
+
+    // Unsupported logical type
+    let stats = Statistics::int32(Some(1), Some(2), None, 0, false);
+    assert_eq!(stats.logical_min_max(&descr.column(3)), None);
+  }
+
+  #[test]
+  fn test_statistics_timestamp_min_max() {
+    let min = Int96::from_millis(-1);
+    let max = Int96::from_millis(1_238_544_000_000);
+    let stats = Statistics::int96(Some(min), Some(max), None, 0, false);
+    assert_eq!(stats.timestamp_min_max(), Some((-1, 1_238_544_000_000)));
+
+    let stats = Statistics::int96(None, None, None, 0, false);
+    assert_eq!(stats.timestamp_min_max(), None);
+    let stats = Statistics::int64(Some(1), Some(2), None, 0, false);
+    assert_eq!(stats.timestamp_min_max(), None);
+  }
+
+  #[test]
+  fn test_statistics_thrift_roundtrip() {
+    let stats = vec![
+      Statistics::boolean(Some(false), Some(true), None, 1, false),
+      Statistics::int32(Some(-1), Some(100), Some(12), 0, false),
+      Statistics::int64(Some(-1), Some(100), None, 7, false),
+      Statistics::int96(
+        Some(Int96::from(vec![1, 2, 3])),
+        Some(Int96::from(vec![4, 5, 6])),
+        None,
+        0,
+        true
+      ),
+      Statistics::float(Some(-1.5), Some(2.5), None, 0, false),
+      Statistics::double(Some(-1.5), Some(2.5), None, 0, true),
+      Statistics::byte_array(
+        Some(ByteArray::from("a")), Some(ByteArray::from("z")), None, 0, false),
+      Statistics::fixed_len_byte_array(
+        Some(ByteArray::from("abc")), Some(ByteArray::from("xyz")), None, 0, false),
+      Statistics::int32(None, None, None, 10, false)
+    ];
+
+    for stat in stats {
+      let thrift_stats = to_thrift(Some(&stat));
+      assert_eq!(from_thrift(stat.physical_type(), thrift_stats), Some(stat));
+    }
+    assert_eq!(to_thrift(None), None);
+    assert_eq!(from_thrift(Type::INT32, None), None);
+  }
+
+  #[test]
+  fn test_statistics_from_thrift() {
+    // New fields take precedence over deprecated fields
+    let thrift_stats = TStatistics {
+      max: Some(vec![1, 0, 0, 0]),
+      min: Some(vec![0, 0, 0, 0]),
+      null_count: Some(5),
+      distinct_count: None,
+      max_value: Some(vec![100, 0, 0, 0]),
+      min_value: Some(vec![10, 0, 0, 0])
+    };
+    assert_eq!(
+      from_thrift(Type::INT32, Some(thrift_stats)),
+      Some(Statistics::int32(Some(10), Some(100), None, 5, false))
+    );
+
+    // Values with invalid length and negative null count are ignored
+    let thrift_stats = TStatistics {
+      max: Some(vec![1, 0]),
+      min: Some(vec![0, 0, 0, 0]),
+      null_count: Some(-1),
+      distinct_count: None,
+      max_value: None,
+      min_value: None
+    };
+    assert_eq!(
+      from_thrift(Type::INT32, Some(thrift_stats)),
+      Some(Statistics::int32(Some(0), None, None, 0, true))
+    );
+  }
+
+  #[test]
+  fn test_statistics_display() {
+    let stats = Statistics::int32(Some(1), Some(12), None, 12, true);
+    assert_eq!(
+      stats.to_string(),
+      "{min: 1, max: 12, distinct_count: N/A, null_count: 12, min_max_deprecated: true}"
+    );
+
+    let stats = Statistics::int64(None, None, Some(3), 0, false);
+    assert_eq!(
+      stats.to_string(),
+      "{min: N/A, max: N/A, distinct_count: 3, null_count: 0, min_max_deprecated: false}"
+    );
+  }
+}
@@ -0,0 +1,3151 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains file writer API, and provides methods to write row groups and columns by
+//! using row group writers and column writers respectively.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use bloom_filter::Sbbf;
+use byteorder::{ByteOrder, LittleEndian};
+use crc::crc32;
+use column::page::{CompressedPage, Page, PageWriteSpec, PageWriter};
+use column::writer::{
+  close_column_writer, get_column_writer_with_mem_tracker, ColumnWriter
+};
+use compression::create_codec;
+use errors::{ParquetError, Result};
+use file::metadata::*;
+#[cfg(feature = "rayon")]
+use file::page_index::ColumnIndex;
+use file::page_index::{OffsetIndex, PageLocation};
+use file::properties::{WriterProperties, WriterPropertiesPtr};
+use file::progress::{Progress, ProgressCallback, ProgressTracker};
+use file::statistics;
+use file::reader::{RowGroupReader, FOOTER_SIZE, PARQUET_MAGIC};
+use parquet_format as parquet;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use schema::types::{
+  self, ColumnDescPtr, ColumnPath, SchemaDescPtr, SchemaDescriptor, TypePtr
+};
+use thrift::protocol::{TCompactOutputProtocol, TOutputProtocol};
+use util::io::TrackedWrite;
+use util::memory::{MemTracker, MemTrackerPtr};
+
+// ----------------------------------------------------------------------
+// APIs for file & row group writers
+
+/// Parquet file writer API.
+/// Provides methods to write row groups sequentially.
+///
+/// The main workflow should be as following:
+/// - Create file writer, this will open a new file and potentially write some metadata.
+/// - Request a new row group writer by calling `next_row_group`.
+/// - Once finished writing row group, close row group writer by passing it into
+/// `close_row_group` method - this will finalise row group metadata and update metrics.
+/// - Write subsequent row groups, if necessary.
+/// - After all row groups have been written, close the file writer using `close` method.
+pub trait FileWriter {
+  /// Creates new row group from this file writer.
+  /// In case of IO error or Thrift error, returns `Err`.
+  ///
+  /// There is no limit on a number of row groups in a file; however, row groups have
+  /// to be written sequentially. Every time the next row group is requested, the
+  /// previous row group must be finalised and closed using `close_row_group` method.
+  fn next_row_group(&mut self) -> Result<Box<RowGroupWriter>>;
+
+  /// Finalises and closes row group that was created using `next_row_group` method.
+  /// After calling this method, the next row group is available for writes.
+  fn close_row_group(&mut self, row_group_writer: Box<RowGroupWriter>) -> Result<()>;
+
+  /// Closes and finalises file writer, returns metadata of the written file, i.e. file
+  /// metadata and metadata of every row group and column chunk with row counts, sizes
+  /// and offsets, the same as metadata read from the file footer.
+  ///
+  /// All row groups must be appended before this method is called.
+  /// No writes are allowed after this point.
+  ///
+  /// Can be called multiple times. It is up to implementation to either result in no-op,
+  /// or return an `Err` for subsequent calls.
+  fn close(&mut self) -> Result<ParquetMetaDataPtr>;
+}
+
+/// Parquet row group writer API.
+/// Provides methods to access column writers in an iterator-like fashion, order is
+/// guaranteed to match the order of schema leaves (column descriptors).
+///
+/// All columns should be written sequentially; the main workflow is:
+/// - Request the next column using `next_column` method - this will return `None` if no
+/// more columns are available to write.
+/// - Once done writing a column, close column writer with `close_column` method - this
+/// will finalise column chunk metadata and update row group metrics.
+/// - Once all columns have been written, close row group writer with `close` method -
+/// it will return row group metadata and is no-op on already closed row group.
+pub trait RowGroupWriter {
+  /// Returns the next column writer, if available; otherwise returns `None`.
+  /// In case of any IO error or Thrift error, or if row group writer has already been
+  /// closed returns `Err`.
+  ///
+  /// To request the next column writer, the previous one must be finalised and closed
+  /// using `close_column`.
+  fn next_column(&mut self) -> Result<Option<ColumnWriter>>;
+
+  /// Closes column writer that was created using `next_column` method.
+  /// This should be called before requesting the next column writer.
+  fn close_column(&mut self, column_writer: ColumnWriter) -> Result<()>;
+
+  /// Returns number of columns in this row group, i.e. number of leaves in the schema.
+  fn num_columns(&self) -> usize;
+
+  /// Returns total compressed size in bytes of column chunks closed so far, including
+  /// page headers.
+  fn total_compressed_size(&self) -> u64;
+
+  /// Returns total uncompressed size in bytes of column chunks closed so far, including
+  /// page headers.
+  fn total_uncompressed_size(&self) -> u64;
+
+  /// Returns memory in bytes currently used by column writers of this row group, i.e.
+  /// by encoders, dictionaries and data pages that are buffered or queued for
+  /// compression.
+  ///
+  /// Default implementation does not track memory and returns `0`.
+  fn memory_usage(&self) -> i64 {
+    0
+  }
+
+  /// Closes this row group writer and returns row group metadata.
+  /// Returns `Err` if not all columns have been written and closed.
+  /// After calling this method row group writer must not be used.
+  ///
+  /// It is recommended to call this method before requesting another row group, but it
+  /// will be closed automatically before returning a new row group.
+  ///
+  /// Can be called multiple times. In subsequent calls will result in no-op and return
+  /// already created row group metadata.
+  fn close(&mut self) -> Result<RowGroupMetaDataPtr>;
+}
+
+// ----------------------------------------------------------------------
+// Writer callbacks
+
+/// Callback invoked after a page has been written into the sink, with path of the
+/// column and page write metrics, e.g. page type, offset and sizes.
+/// Called for dictionary pages as well as data pages.
+pub type PageFlushCallback = Rc<Fn(&ColumnPath, &PageWriteSpec)>;
+
+/// Callback invoked after a row group has been closed, with metadata of the row group.
+pub type RowGroupCloseCallback = Rc<Fn(&RowGroupMetaData)>;
+
+/// Callback invoked after a page has been written into the sink, with number of bytes
+/// of the page and total number of bytes written into the file so far.
+///
+/// Writer waits for the callback to return before encoding further pages, so the
+/// callback can block to throttle producers when downstream storage is slow, or
+/// return an error to abort writing.
+pub type BackpressureCallback = Rc<Fn(u64, u64) -> Result<()>>;
+
+// ----------------------------------------------------------------------
+// Serialized impl for file & row group writers
+
+/// A serialized implementation for Parquet [`FileWriter`].
+/// See documentation on file writer for more information.
+///
+/// File can be written into any sink that implements `Write`, e.g. `File` or in-memory
+/// `Vec<u8>`; positions of pages and column chunks are tracked by the writer, so sink
+/// does not need to support seeking.
+pub struct SerializedFileWriter<W: Write> {
+  sink: FileSink<W>,
+  schema: TypePtr,
+  descr: SchemaDescPtr,
+  props: WriterPropertiesPtr,
+  row_groups: Vec<RowGroupMetaDataPtr>,
+  on_page_flush: Option<PageFlushCallback>,
+  on_row_group_close: Option<RowGroupCloseCallback>,
+  backpressure: Option<BackpressureCallback>,
+  progress: Option<ProgressTracker>,
+  shared_chunks: Vec<Option<SharedChunk>>,
+  mem_tracker: MemTrackerPtr,
+  previous_writer_closed: bool,
+  is_closed: bool
+}
+
+impl<W: Write> SerializedFileWriter<W> {
+  /// Creates new file writer for the sink and schema `schema`, columns are written
+  /// according to writer properties `props`.
+  /// Writes Parquet magic at the start of the sink, returns error if sink cannot be
+  /// written.
+  ///
+  /// Sink is expected to be empty, offsets in file metadata are relative to the first
+  /// byte written by this writer.
+  ///
+  /// Returns error without writing anything if compression codec of any column is not
+  /// supported. Columns can use different codecs.
+  pub fn new(sink: W, schema: TypePtr, props: WriterPropertiesPtr) -> Result<Self> {
+    let descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    validate_compression(&descr, &props)?;
+    let mut sink = FileSink::new(sink);
+    Self::start_file(&mut sink)?;
+    let shared_chunks = (0..descr.num_columns()).map(|_| None).collect();
+    Ok(Self {
+      sink: sink,
+      schema: schema,
+      descr: descr,
+      props: props,
+      row_groups: Vec::new(),
+      on_page_flush: None,
+      on_row_group_close: None,
+      backpressure: None,
+      progress: None,
+      shared_chunks: shared_chunks,
+      mem_tracker: Rc::new(MemTracker::new()),
+      previous_writer_closed: true,
+      is_closed: false
+    })
+  }
+
+  /// Sets callback that is invoked after every page of every column chunk has been
+  /// written, and returns itself. Useful to emit metrics or build external indexes.
+  pub fn with_on_page_flush(mut self, callback: PageFlushCallback) -> Self {
+    self.on_page_flush = Some(callback);
+    self
+  }
+
+  /// Sets callback that is invoked after every row group has been closed, and returns
+  /// itself.
+  pub fn with_on_row_group_close(mut self, callback: RowGroupCloseCallback) -> Self {
+    self.on_row_group_close = Some(callback);
+    self
+  }
+
+  /// Sets backpressure callback that is invoked after every page has been written,
+  /// and returns itself.
+  ///
+  /// Error returned by the callback is returned by the method that wrote the page,
+  /// e.g. `write_batch` of a column writer or `close_buffered_row_group`. Callback is
+  /// not invoked for pages of row groups copied with `append_row_group` and for pages
+  /// of shared column chunks.
+  pub fn with_backpressure(mut self, callback: BackpressureCallback) -> Self {
+    self.backpressure = Some(callback);
+    self
+  }
+
+  /// Sets callback that reports progress of writing this file, and returns itself.
+  ///
+  /// Callback is invoked with bytes written after every page, with rows and remaining
+  /// bytes of a row group once it has been closed, and with bytes of page indexes and
+  /// metadata once the file is closed.
+  pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+    self.progress = Some(ProgressTracker::new(callback));
+    self
+  }
+
+  /// Returns progress accumulated so far, or `None` if progress callback is not set.
+  pub fn progress(&self) -> Option<Progress> {
+    self.progress.as_ref().map(|progress| progress.progress())
+  }
+
+  /// Returns memory in bytes currently used by column writers of row groups of this
+  /// file writer, i.e. by encoders, dictionaries and data pages that are buffered or
+  /// queued for compression. Memory is released once pages are written and column
+  /// writers are closed.
+  ///
+  /// Encoded column chunks of buffered row groups and column writers of row groups
+  /// written with `write_row_group_parallel` are not included.
+  pub fn memory_usage(&self) -> i64 {
+    self.mem_tracker.memory_usage()
+  }
+
+  /// Returns maximum memory in bytes used by column writers of this file writer so far,
+  /// see `memory_usage`.
+  pub fn max_memory_usage(&self) -> i64 {
+    self.mem_tracker.max_memory_usage()
+  }
+
+  /// Creates new buffered row group writer, which allows writing columns in any order.
+  /// Row group is written into the file when it is closed with
+  /// `close_buffered_row_group`.
+  pub fn next_buffered_row_group(&mut self) -> Result<BufferedRowGroupWriter> {
+    self.assert_closed()?;
+    BufferedRowGroupWriter::new_with_mem_tracker(
+      self.descr.clone(),
+      self.props.clone(),
+      self.mem_tracker.clone()
+    )
+  }
+
+  /// Closes all column writers of the buffered row group writer and writes column
+  /// chunks into the file in schema order, followed by row group metadata update.
+  ///
+  /// Page flush callback is invoked for pages of the row group at this point, with
+  /// offsets of pages in the file.
+  pub fn close_buffered_row_group(
+    &mut self,
+    row_group_writer: BufferedRowGroupWriter
+  ) -> Result<()> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+    let on_page_flush = self.page_flush_callback();
+    let row_group_metadata = row_group_writer.flush(
+      &mut self.sink,
+      on_page_flush.as_ref(),
+      self.backpressure.as_ref(),
+      &self.props,
+      &mut self.shared_chunks
+    )?;
+    self.record_row_group(row_group_metadata)
+  }
+
+  /// Writes row group, column chunks are encoded and compressed in parallel worker
+  /// threads and written into the file in schema order.
+  ///
+  /// `write_column` is called on a worker thread with index of the leaf column and its
+  /// column writer, and should write all values of the column in this row group.
+  /// Column writers are created and closed by this method.
+  ///
+  /// Available with the `rayon` feature.
+  #[cfg(feature = "rayon")]
+  pub fn write_row_group_parallel<F>(&mut self, write_column: F) -> Result<()>
+      where F: Fn(usize, &mut ColumnWriter) -> Result<()> + Send + Sync {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+    let schema = types::to_thrift(self.schema.as_ref())?;
+    let props = self.props.as_ref().clone();
+    let encoded: Vec<Result<SendableColumnChunk>> = (0..self.descr.num_columns())
+      .into_par_iter()
+      .map(|i| encode_column_chunk(&schema, &props, i, &write_column))
+      .collect();
+
+    let mut chunks = Vec::with_capacity(encoded.len());
+    for (i, chunk) in encoded.into_iter().enumerate() {
+      chunks.push(chunk?.into_encoded(self.descr.column(i))?);
+    }
+    let on_page_flush = self.page_flush_callback();
+    let row_group_metadata = write_column_chunks(
+      self.descr.clone(),
+      chunks,
+      &mut self.sink,
+      on_page_flush.as_ref(),
+      self.backpressure.as_ref(),
+      &self.props,
+      &mut self.shared_chunks
+    )?;
+    self.record_row_group(row_group_metadata)
+  }
+
+  /// Appends row group of an existing file, read with `row_group`, to this file.
+  ///
+  /// Column chunks are copied byte-for-byte without decoding values, Bloom filters and
+  /// page indexes are copied as well; only metadata is rewritten with new offsets.
+  /// Schema of the row group must have the same leaf columns as schema of this writer.
+  /// Page flush callback is not invoked for copied pages.
+  pub fn append_row_group(&mut self, row_group: &RowGroupReader) -> Result<()> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+    let row_group_metadata = row_group.metadata();
+    self.check_schema_compatible(row_group_metadata.schema_descr())?;
+
+    let mut columns = Vec::with_capacity(row_group.num_columns());
+    for i in 0..row_group.num_columns() {
+      let column = row_group_metadata.column(i);
+      if let Some(file_path) = column.file_path() {
+        return Err(nyi_err!(
+          "Cannot append column chunk {} stored in external file {}",
+          column.column_path(),
+          file_path
+        ));
+      }
+      let data = row_group.get_column_chunk_bytes(i)?;
+      let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+      let shift = self.sink.pos() as i64 - start;
+      self.sink.write_all(&data)?;
+
+      let bloom_filter = match row_group.get_column_bloom_filter(i)? {
+        Some(filter) => {
+          let offset = self.sink.pos() as i64;
+          self.sink.write_all(&filter.to_bytes())?;
+          Some((offset, filter.num_bytes() as i32))
+        },
+        None => None
+      };
+
+      // Metadata is converted through Thrift to reference column of this writer
+      let metadata =
+        ColumnChunkMetaData::from_thrift(self.descr.column(i), column.to_thrift())?;
+      let metadata = ColumnChunkMetaDataBuilder::from_metadata(&metadata)
+        .with_column_index(row_group.get_column_index(i)?)
+        .with_offset_index(row_group.get_offset_index(i)?)
+        .build()?;
+      let metadata = ColumnChunkMetaDataBuilder::from_metadata(
+          &shift_column_metadata(&metadata, shift)?
+        )
+        .with_bloom_filter_offset(bloom_filter.map(|(offset, _)| offset))
+        .with_bloom_filter_length(bloom_filter.map(|(_, length)| length))
+        .build()?;
+      SerializedPageWriter::new(self.sink.clone()).write_metadata(&metadata)?;
+      columns.push(Rc::new(metadata));
+    }
+
+    let metadata = RowGroupMetaData::builder(self.descr.clone())
+      .with_column_metadata(columns)
+      .with_num_rows(row_group_metadata.num_rows())
+      .with_total_byte_size(row_group_metadata.total_byte_size())
+      .build()?;
+    self.record_row_group(Rc::new(metadata))
+  }
+
+  /// Returns error if leaf columns of `schema` do not match leaf columns of the schema
+  /// of this writer, i.e. their paths, types or levels are different.
+  pub fn check_schema_compatible(&self, schema: &SchemaDescriptor) -> Result<()> {
+    if schema.num_columns() != self.descr.num_columns() {
+      return Err(general_err!(
+        "Incompatible schema, expected {} columns, found {}",
+        self.descr.num_columns(),
+        schema.num_columns()
+      ));
+    }
+    for i in 0..schema.num_columns() {
+      let expected = self.descr.column(i);
+      let actual = schema.column(i);
+      let compatible = expected.path() == actual.path() &&
+        expected.physical_type() == actual.physical_type() &&
+        expected.logical_type() == actual.logical_type() &&
+        expected.type_length() == actual.type_length() &&
+        expected.max_def_level() == actual.max_def_level() &&
+        expected.max_rep_level() == actual.max_rep_level();
+      if !compatible {
+        return Err(general_err!(
+          "Incompatible schema, column {} does not match column {}",
+          actual.path(),
+          expected.path()
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns underlying sink of this writer.
+  /// Returns error if file writer has not been closed yet.
+  pub fn into_inner(self) -> Result<W> {
+    if !self.is_closed {
+      return Err(general_err!("File writer is not closed"));
+    }
+    self.sink.into_inner()
+  }
+
+  /// Writes magic bytes at the beginning of the file.
+  fn start_file(sink: &mut FileSink<W>) -> Result<()> {
+    sink.write_all(&PARQUET_MAGIC)?;
+    Ok(())
+  }
+
+  /// Closes row group writer and records its metadata.
+  fn finalise_row_group_writer(
+    &mut self,
+    mut row_group_writer: Box<RowGroupWriter>
+  ) -> Result<()> {
+    let row_group_metadata = row_group_writer.close()?;
+    self.record_row_group(row_group_metadata)
+  }
+
+  /// Records metadata of a row group that has been written into the file.
+  /// Returns error if audit is enabled and metadata is inconsistent with the file.
+  fn record_row_group(&mut self, row_group_metadata: RowGroupMetaDataPtr) -> Result<()> {
+    if self.props.audit_enabled() {
+      self.audit_row_group(&row_group_metadata)?;
+    }
+    if let Some(ref callback) = self.on_row_group_close {
+      callback(&row_group_metadata);
+    }
+    if let Some(ref progress) = self.progress {
+      let num_bytes = self.sink.pos() - progress.progress().num_bytes();
+      progress.complete_row_group(row_group_metadata.num_rows() as u64, num_bytes);
+    }
+    self.row_groups.push(row_group_metadata);
+    Ok(())
+  }
+
+  /// Checks that column chunks of the row group are stored in schema order after the
+  /// previous row group without overlaps, and that number of values of each column
+  /// chunk matches number of rows of the row group. Column chunks may reference pages
+  /// of a column chunk of the same column in a previous row group, see
+  /// `with_chunk_dedup_enabled` in writer properties.
+  fn audit_row_group(&self, row_group: &RowGroupMetaData) -> Result<()> {
+    let index = self.row_groups.len();
+    let mut prev_end = self.row_groups.last()
+      .and_then(|prev| prev.columns().iter().map(|column| chunk_range(column).1).max())
+      .unwrap_or(PARQUET_MAGIC.len() as i64);
+
+    for (i, column) in row_group.columns().iter().enumerate() {
+      let max_rep_level = column.column_descr().max_rep_level();
+      let num_values = column.num_values();
+      let num_rows = row_group.num_rows();
+      if (max_rep_level == 0 && num_values != num_rows) ||
+          (max_rep_level > 0 && num_values < num_rows) {
+        return Err(general_err!(
+          "Audit of row group {} failed: column {} has {} values, but row group has {} \
+           rows",
+          index,
+          column.column_path(),
+          num_values,
+          num_rows
+        ));
+      }
+      if num_values == 0 {
+        continue;
+      }
+
+      let (start, end) = chunk_range(column);
+      let is_shared = self.row_groups.iter()
+        .any(|prev| chunk_range(prev.column(i)) == (start, end));
+      if is_shared {
+        continue;
+      }
+      if start < prev_end || end > self.sink.pos() as i64 {
+        return Err(general_err!(
+          "Audit of row group {} failed: column {} is stored at offsets {}..{}, which \
+           overlap previous column chunk ending at offset {} or exceed file size {}",
+          index,
+          column.column_path(),
+          start,
+          end,
+          prev_end,
+          self.sink.pos()
+        ));
+      }
+      prev_end = end;
+    }
+    Ok(())
+  }
+
+  /// Returns page flush callback, which also reports bytes of every page to progress
+  /// tracker if it is set.
+  fn page_flush_callback(&self) -> Option<PageFlushCallback> {
+    let progress = match self.progress {
+      Some(ref progress) => progress.clone(),
+      None => return self.on_page_flush.clone()
+    };
+    let on_page_flush = self.on_page_flush.clone();
+    let callback: PageFlushCallback =
+      Rc::new(move |path: &ColumnPath, spec: &PageWriteSpec| {
+        if let Some(ref callback) = on_page_flush {
+          callback(path, spec);
+        }
+        progress.add_bytes(spec.bytes_written);
+      });
+    Some(callback)
+  }
+
+  /// Writes column indexes and offset indexes of all row groups after the last row
+  /// group, and records their locations in column chunk metadata.
+  ///
+  /// Column indexes of all column chunks are written first, followed by offset
+  /// indexes, so that page indexes are stored together and can be read with a
+  /// single request.
+  fn write_page_indexes(&mut self) -> Result<()> {
+    let row_groups = self.row_groups.clone();
+
+    let mut column_index_locations = Vec::with_capacity(row_groups.len());
+    for row_group in &row_groups {
+      let mut locations = Vec::with_capacity(row_group.num_columns());
+      for column in row_group.columns() {
+        locations.push(match column.column_index() {
+          Some(column_index) => Some(self.write_index(&column_index.to_bytes()?)?),
+          None => None
+        });
+      }
+      column_index_locations.push(locations);
+    }
+
+    let mut offset_index_locations = Vec::with_capacity(row_groups.len());
+    for row_group in &row_groups {
+      let mut locations = Vec::with_capacity(row_group.num_columns());
+      for column in row_group.columns() {
+        locations.push(match column.offset_index() {
+          Some(offset_index) => Some(self.write_index(&offset_index.to_bytes()?)?),
+          None => None
+        });
+      }
+      offset_index_locations.push(locations);
+    }
+
+    self.row_groups.clear();
+    for (i, row_group) in row_groups.iter().enumerate() {
+      let mut columns = Vec::with_capacity(row_group.num_columns());
+      for (j, column) in row_group.columns().iter().enumerate() {
+        let column_index = column_index_locations[i][j];
+        let offset_index = offset_index_locations[i][j];
+        let column_metadata = ColumnChunkMetaDataBuilder::from_metadata(column)
+          .with_column_index_offset(column_index.map(|(offset, _)| offset))
+          .with_column_index_length(column_index.map(|(_, length)| length))
+          .with_offset_index_offset(offset_index.map(|(offset, _)| offset))
+          .with_offset_index_length(offset_index.map(|(_, length)| length))
+          .build()?;
+        columns.push(Rc::new(column_metadata));
+      }
+      let row_group_metadata = RowGroupMetaData::builder(row_group.schema_descr_ptr())
+        .with_column_metadata(columns)
+        .with_num_rows(row_group.num_rows())
+        .with_total_byte_size(row_group.total_byte_size())
+        .build()?;
+      self.row_groups.push(Rc::new(row_group_metadata));
+    }
+    Ok(())
+  }
+
+  /// Writes serialized page index at the current position of the file, returns its
+  /// offset and length.
+  fn write_index(&mut self, bytes: &[u8]) -> Result<(i64, i32)> {
+    let offset = self.sink.pos();
+    self.sink.write_all(bytes)?;
+    Ok((offset as i64, bytes.len() as i32))
+  }
+
+  /// Assembles and writes metadata at the end of the file, followed by the footer.
+  fn write_metadata(&mut self) -> Result<ParquetMetaData> {
+    let key_value_metadata = self.props.file_key_value_metadata();
+    let file_metadata = parquet::FileMetaData {
+      version: self.props.writer_version().as_num(),
+      schema: types::to_thrift(self.schema.as_ref())?,
+      num_rows: self.row_groups.iter().map(|x| x.num_rows()).sum(),
+      row_groups: self.row_groups.iter().map(|x| x.to_thrift()).collect(),
+      key_value_metadata: key_value_metadata.as_ref()
+        .map(|kvs| kvs.iter().map(|kv| kv.to_thrift()).collect()),
+      created_by: Some(self.props.created_by().to_owned()),
+      column_orders: None
+    };
+
+    // Write file metadata
+    let mut metadata_buf = Vec::new();
+    {
+      let mut protocol = TCompactOutputProtocol::new(&mut metadata_buf);
+      file_metadata.write_to_out_protocol(&mut protocol)?;
+      protocol.flush()?;
+    }
+    self.sink.write_all(&metadata_buf)?;
+
+    // Write footer
+    let mut footer_buffer: [u8; FOOTER_SIZE] = [0; FOOTER_SIZE];
+    LittleEndian::write_i32(&mut footer_buffer[0..4], metadata_buf.len() as i32);
+    footer_buffer[4..].copy_from_slice(&PARQUET_MAGIC);
+    self.sink.write_all(&footer_buffer)?;
+    self.sink.flush()?;
+
+    let mut row_groups = Vec::with_capacity(self.row_groups.len());
+    for row_group in &self.row_groups {
+      row_groups.push(
+        RowGroupMetaData::builder(self.descr.clone())
+          .with_column_metadata(row_group.columns().to_vec())
+          .with_num_rows(row_group.num_rows())
+          .with_total_byte_size(row_group.total_byte_size())
+          .build()?
+      );
+    }
+    let metadata = FileMetaData::new(
+      file_metadata.version,
+      file_metadata.num_rows,
+      file_metadata.created_by,
+      key_value_metadata,
+      self.schema.clone(),
+      self.descr.clone()
+    );
+    Ok(ParquetMetaData::new(metadata, row_groups))
+  }
+
+  #[inline]
+  fn assert_closed(&self) -> Result<()> {
+    if self.is_closed {
+      Err(general_err!("File writer is closed"))
+    } else {
+      Ok(())
+    }
+  }
+
+  #[inline]
+  fn assert_previous_writer_closed(&self) -> Result<()> {
+    if !self.previous_writer_closed {
+      Err(general_err!("Previous row group writer was not closed"))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl<W: 'static + Write> FileWriter for SerializedFileWriter<W> {
+  #[inline]
+  fn next_row_group(&mut self) -> Result<Box<RowGroupWriter>> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+    let row_group_writer = SerializedRowGroupWriter::new(
+      self.descr.clone(),
+      self.props.clone(),
+      self.sink.clone()
+    ).with_on_page_flush(self.page_flush_callback())
+      .with_backpressure(self.backpressure.clone())
+      .with_mem_tracker(self.mem_tracker.clone());
+    self.previous_writer_closed = false;
+    Ok(Box::new(row_group_writer))
+  }
+
+  #[inline]
+  fn close_row_group(&mut self, row_group_writer: Box<RowGroupWriter>) -> Result<()> {
+    self.assert_closed()?;
+    let res = self.finalise_row_group_writer(row_group_writer);
+    self.previous_writer_closed = res.is_ok();
+    res
+  }
+
+  #[inline]
+  fn close(&mut self) -> Result<ParquetMetaDataPtr> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+    self.write_page_indexes()?;
+    let metadata = self.write_metadata()?;
+    self.is_closed = true;
+    if let Some(ref progress) = self.progress {
+      progress.add_bytes(self.sink.pos() - progress.progress().num_bytes());
+    }
+    Ok(Rc::new(metadata))
+  }
+}
+
+/// A serialized implementation for Parquet [`RowGroupWriter`].
+/// Coordinates writing of a row group with column writers.
+/// See documentation on row group writer for more information.
+pub struct SerializedRowGroupWriter<W: Write> {
+  descr: SchemaDescPtr,
+  props: WriterPropertiesPtr,
+  sink: FileSink<W>,
+  on_page_flush: Option<PageFlushCallback>,
+  backpressure: Option<BackpressureCallback>,
+  mem_tracker: MemTrackerPtr,
+  total_rows_written: Option<u64>,
+  total_bytes_written: u64,
+  total_compressed_size: u64,
+  total_uncompressed_size: u64,
+  column_index: usize,
+  previous_writer_closed: bool,
+  row_group_metadata: Option<RowGroupMetaDataPtr>,
+  column_chunks: Vec<ColumnChunkMetaDataPtr>
+}
+
+impl<W: Write> SerializedRowGroupWriter<W> {
+  /// Creates new row group writer for the schema `descr` and writer properties
+  /// `props`, column chunks are appended at the current position of the `sink`.
+  pub fn new(
+    descr: SchemaDescPtr,
+    props: WriterPropertiesPtr,
+    sink: FileSink<W>
+  ) -> Self {
+    let num_columns = descr.num_columns();
+    Self {
+      descr: descr,
+      props: props,
+      sink: sink,
+      on_page_flush: None,
+      backpressure: None,
+      mem_tracker: Rc::new(MemTracker::new()),
+      total_rows_written: None,
+      total_bytes_written: 0,
+      total_compressed_size: 0,
+      total_uncompressed_size: 0,
+      column_index: 0,
+      previous_writer_closed: true,
+      row_group_metadata: None,
+      column_chunks: Vec::with_capacity(num_columns)
+    }
+  }
+
+  /// Sets callback that is invoked after every page of every column chunk has been
+  /// written, and returns itself.
+  pub fn with_on_page_flush(mut self, callback: Option<PageFlushCallback>) -> Self {
+    self.on_page_flush = callback;
+    self
+  }
+
+  /// Sets backpressure callback that is invoked after every page of every column chunk
+  /// has been written, and returns itself.
+  pub fn with_backpressure(mut self, callback: Option<BackpressureCallback>) -> Self {
+    self.backpressure = callback;
+    self
+  }
+
+  /// Sets memory tracker of column writers, e.g. memory tracker shared by all row
+  /// groups of a file writer, and returns itself.
+  pub fn with_mem_tracker(mut self, mem_tracker: MemTrackerPtr) -> Self {
+    self.mem_tracker = mem_tracker;
+    self
+  }
+
+  /// Checks and finalises current column writer.
+  fn finalise_column_writer(&mut self, writer: ColumnWriter) -> Result<()> {
+    let (bytes_written, rows_written, metadata) = close_column_writer(writer)?;
+
+    // Update row group writer metrics
+    self.total_bytes_written += bytes_written;
+    self.total_compressed_size += metadata.compressed_size() as u64;
+    self.total_uncompressed_size += metadata.uncompressed_size() as u64;
+    self.column_chunks.push(Rc::new(metadata));
+    if let Some(rows) = self.total_rows_written {
+      if rows != rows_written {
+        return Err(general_err!(
+          "Incorrect number of rows, expected {} != {} rows",
+          rows,
+          rows_written
+        ));
+      }
+    } else {
+      self.total_rows_written = Some(rows_written);
+    }
+
+    Ok(())
+  }
+
+  #[inline]
+  fn assert_closed(&self) -> Result<()> {
+    if self.row_group_metadata.is_some() {
+      Err(general_err!("Row group writer is closed"))
+    } else {
+      Ok(())
+    }
+  }
+
+  #[inline]
+  fn assert_previous_writer_closed(&self) -> Result<()> {
+    if !self.previous_writer_closed {
+      Err(general_err!("Previous column writer was not closed"))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+impl<W: 'static + Write> RowGroupWriter for SerializedRowGroupWriter<W> {
+  #[inline]
+  fn next_column(&mut self) -> Result<Option<ColumnWriter>> {
+    self.assert_closed()?;
+    self.assert_previous_writer_closed()?;
+
+    if self.column_index >= self.descr.num_columns() {
+      return Ok(None);
+    }
+    let column_descr = self.descr.column(self.column_index);
+    let mut page_writer = SerializedPageWriter::new(self.sink.clone())
+      .with_page_checksum_enabled(self.props.page_checksum_enabled())
+      .with_chunk_checksum_enabled(self.props.chunk_checksum_enabled());
+    if let Some(ref callback) = self.on_page_flush {
+      let callback = callback.clone();
+      let path = column_descr.path().clone();
+      page_writer = page_writer.with_on_page_flush(
+        Rc::new(move |spec: &PageWriteSpec| callback(&path, spec))
+      );
+    }
+    if let Some(ref callback) = self.backpressure {
+      page_writer = page_writer.with_backpressure(callback.clone());
+    }
+    let page_writer = Box::new(page_writer);
+    let column_writer = get_column_writer_with_mem_tracker(
+      column_descr,
+      self.props.clone(),
+      page_writer,
+      self.mem_tracker.clone()
+    )?;
+    self.column_index += 1;
+    self.previous_writer_closed = false;
+
+    Ok(Some(column_writer))
+  }
+
+  #[inline]
+  fn close_column(&mut self, column_writer: ColumnWriter) -> Result<()> {
+    let res = self.finalise_column_writer(column_writer);
+    self.previous_writer_closed = res.is_ok();
+    res
+  }
+
+  fn num_columns(&self) -> usize {
+    self.descr.num_columns()
+  }
+
+  fn total_compressed_size(&self) -> u64 {
+    self.total_compressed_size
+  }
+
+  fn total_uncompressed_size(&self) -> u64 {
+    self.total_uncompressed_size
+  }
+
+  fn memory_usage(&self) -> i64 {
+    self.mem_tracker.memory_usage()
+  }
+
+  #[inline]
+  fn close(&mut self) -> Result<RowGroupMetaDataPtr> {
+    if self.row_group_metadata.is_none() {
+      self.assert_previous_writer_closed()?;
+      if self.column_chunks.len() != self.num_columns() {
+        return Err(general_err!(
+          "Not all columns have been written, expected {} columns, but closed {}",
+          self.num_columns(),
+          self.column_chunks.len()
+        ));
+      }
+
+      // Total byte size of the row group is defined as size of uncompressed data
+      let row_group_metadata = RowGroupMetaData::builder(self.descr.clone())
+        .with_column_metadata(self.column_chunks.clone())
+        .with_total_byte_size(self.total_uncompressed_size as i64)
+        .with_num_rows(self.total_rows_written.unwrap_or(0) as i64)
+        .build()?;
+
+      self.row_group_metadata = Some(Rc::new(row_group_metadata));
+    }
+
+    let metadata = self.row_group_metadata.as_ref().unwrap().clone();
+    Ok(metadata)
+  }
+}
+
+// ----------------------------------------------------------------------
+// Buffered row group writer
+
+/// Row group writer that buffers encoded column chunks in memory.
+///
+/// Unlike [`RowGroupWriter`], all column writers are available at once, so columns can
+/// be written in any order or interleaved per batch, which is convenient for
+/// row-oriented producers. Column chunks are written into the file in schema order
+/// when the row group is closed with `SerializedFileWriter::close_buffered_row_group`.
+///
+/// The whole encoded row group is kept in memory until it is closed.
+pub struct BufferedRowGroupWriter {
+  descr: SchemaDescPtr,
+  mem_tracker: MemTrackerPtr,
+  column_writers: Vec<ColumnWriter>,
+  sinks: Vec<FileSink<Vec<u8>>>,
+  page_specs: Vec<Rc<RefCell<Vec<PageWriteSpec>>>>
+}
+
+impl BufferedRowGroupWriter {
+  /// Creates new buffered row group writer for the schema `descr` and writer
+  /// properties `props`, column writers are created for all leaves of the schema.
+  pub fn new(descr: SchemaDescPtr, props: WriterPropertiesPtr) -> Result<Self> {
+    Self::new_with_mem_tracker(descr, props, Rc::new(MemTracker::new()))
+  }
+
+  /// Creates new buffered row group writer like `new`, memory of column writers is
+  /// tracked by `mem_tracker`.
+  pub fn new_with_mem_tracker(
+    descr: SchemaDescPtr,
+    props: WriterPropertiesPtr,
+    mem_tracker: MemTrackerPtr
+  ) -> Result<Self> {
+    let num_columns = descr.num_columns();
+    let mut column_writers = Vec::with_capacity(num_columns);
+    let mut sinks = Vec::with_capacity(num_columns);
+    let mut page_specs = Vec::with_capacity(num_columns);
+    for i in 0..num_columns {
+      let (column_writer, sink, specs) =
+        buffered_column_writer(descr.column(i), props.clone(), mem_tracker.clone())?;
+      column_writers.push(column_writer);
+      sinks.push(sink);
+      page_specs.push(specs);
+    }
+    Ok(Self {
+      descr: descr,
+      mem_tracker: mem_tracker,
+      column_writers: column_writers,
+      sinks: sinks,
+      page_specs: page_specs
+    })
+  }
+
+  /// Returns number of columns in this row group, i.e. number of leaves in the schema.
+  pub fn num_columns(&self) -> usize {
+    self.column_writers.len()
+  }
+
+  /// Returns memory in bytes currently used by column writers of this row group, see
+  /// `RowGroupWriter::memory_usage`. Encoded column chunks are not included.
+  pub fn memory_usage(&self) -> i64 {
+    self.mem_tracker.memory_usage()
+  }
+
+  /// Returns column writer for the `i`th leaf of the schema.
+  /// Use `get_typed_column_writer_mut` to get a typed column writer.
+  pub fn column(&mut self, i: usize) -> Result<&mut ColumnWriter> {
+    let num_columns = self.num_columns();
+    self.column_writers.get_mut(i).ok_or_else(|| {
+      general_err!(
+        "Column index {} out of bounds, row group has {} columns",
+        i,
+        num_columns
+      )
+    })
+  }
+
+  /// Closes all column writers and writes buffered column chunks into `sink` in schema
+  /// order, returns row group metadata with offsets of column chunks in `sink`.
+  fn flush<W: Write>(
+    self,
+    sink: &mut FileSink<W>,
+    on_page_flush: Option<&PageFlushCallback>,
+    backpressure: Option<&BackpressureCallback>,
+    props: &WriterProperties,
+    shared_chunks: &mut [Option<SharedChunk>]
+  ) -> Result<RowGroupMetaDataPtr> {
+    let mut chunks = Vec::with_capacity(self.column_writers.len());
+    let columns = self.column_writers.into_iter().zip(self.sinks).zip(self.page_specs);
+    for ((column_writer, buffer), page_specs) in columns {
+      chunks.push(close_buffered_column_writer(column_writer, buffer, page_specs)?);
+    }
+    write_column_chunks(
+      self.descr,
+      chunks,
+      sink,
+      on_page_flush,
+      backpressure,
+      props,
+      shared_chunks
+    )
+  }
+}
+
+/// Column chunk encoded into an in-memory buffer. Offsets in column chunk metadata and
+/// page write metrics are relative to the start of the buffer.
+struct EncodedColumnChunk {
+  data: Vec<u8>,
+  rows_written: u64,
+  metadata: ColumnChunkMetaData,
+  page_specs: Vec<PageWriteSpec>
+}
+
+/// Creates column writer for the column `descr`, which writes pages into an in-memory
+/// buffer. Returns column writer, buffer and page write metrics collected so far.
+fn buffered_column_writer(
+  descr: ColumnDescPtr,
+  props: WriterPropertiesPtr,
+  mem_tracker: MemTrackerPtr
+) -> Result<(ColumnWriter, FileSink<Vec<u8>>, Rc<RefCell<Vec<PageWriteSpec>>>)> {
+  let sink = FileSink::new(Vec::new());
+  let specs = Rc::new(RefCell::new(Vec::new()));
+  let specs_ref = specs.clone();
+  let page_writer = SerializedPageWriter::new(sink.clone())
+    .with_page_checksum_enabled(props.page_checksum_enabled())
+    .with_chunk_checksum_enabled(props.chunk_checksum_enabled())
+    .with_on_page_flush(Rc::new(move |spec: &PageWriteSpec| {
+      specs_ref.borrow_mut().push(shift_page_spec(spec, 0));
+    }));
+  let column_writer =
+    get_column_writer_with_mem_tracker(descr, props, Box::new(page_writer), mem_tracker)?;
+  Ok((column_writer, sink, specs))
+}
+
+/// Closes column writer created with `buffered_column_writer` and returns encoded
+/// column chunk.
+fn close_buffered_column_writer(
+  column_writer: ColumnWriter,
+  buffer: FileSink<Vec<u8>>,
+  page_specs: Rc<RefCell<Vec<PageWriteSpec>>>
+) -> Result<EncodedColumnChunk> {
+  let (bytes_written, rows_written, metadata) = close_column_writer(column_writer)?;
+  // Serialized column chunk metadata follows column chunk data in the buffer, it is
+  // written again with offsets of the column chunk in the file
+  let mut data = buffer.into_inner()?;
+  data.truncate(bytes_written as usize);
+  let page_specs = page_specs.borrow_mut().drain(..).collect();
+  Ok(EncodedColumnChunk {
+    data: data,
+    rows_written: rows_written,
+    metadata: metadata,
+    page_specs: page_specs
+  })
+}
+
+/// Column chunk written into the file, identical column chunks of the same column in
+/// later row groups reference its pages instead of being written again. Encoded bytes
+/// are kept for comparison, since chunks are only compared once they are encoded.
+struct SharedChunk {
+  data: Vec<u8>,
+  offset: u64
+}
+
+/// Encoded column chunk that can be sent between threads, column chunk metadata is
+/// stored as Thrift definition.
+#[cfg(feature = "rayon")]
+struct SendableColumnChunk {
+  data: Vec<u8>,
+  rows_written: u64,
+  column_chunk: parquet::ColumnChunk,
+  column_index: Option<ColumnIndex>,
+  offset_index: Option<OffsetIndex>,
+  page_specs: Vec<PageWriteSpec>
+}
+
+#[cfg(feature = "rayon")]
+impl SendableColumnChunk {
+  /// Converts into encoded column chunk of the column `descr`.
+  fn into_encoded(self, descr: ColumnDescPtr) -> Result<EncodedColumnChunk> {
+    let metadata = ColumnChunkMetaData::from_thrift(descr, self.column_chunk)?;
+    let metadata = ColumnChunkMetaDataBuilder::from_metadata(&metadata)
+      .with_column_index(self.column_index)
+      .with_offset_index(self.offset_index)
+      .build()?;
+    Ok(EncodedColumnChunk {
+      data: self.data,
+      rows_written: self.rows_written,
+      metadata: metadata,
+      page_specs: self.page_specs
+    })
+  }
+}
+
+/// Encodes `i`th leaf column of the schema into an in-memory buffer with values
+/// written by `write_column`, called on a worker thread.
+#[cfg(feature = "rayon")]
+fn encode_column_chunk<F>(
+  schema: &[parquet::SchemaElement],
+  props: &WriterProperties,
+  i: usize,
+  write_column: &F
+) -> Result<SendableColumnChunk> where F: Fn(usize, &mut ColumnWriter) -> Result<()> {
+  // Schema and writer properties are reference counted, and cannot be shared between
+  // threads, so they are rebuilt on the worker thread
+  let mut elements = schema.to_vec();
+  let descr = SchemaDescriptor::new(types::from_thrift(&mut elements)?);
+  let (mut column_writer, buffer, page_specs) = buffered_column_writer(
+    descr.column(i),
+    Rc::new(props.clone()),
+    Rc::new(MemTracker::new())
+  )?;
+  write_column(i, &mut column_writer)?;
+  let chunk = close_buffered_column_writer(column_writer, buffer, page_specs)?;
+  Ok(SendableColumnChunk {
+    data: chunk.data,
+    rows_written: chunk.rows_written,
+    column_chunk: chunk.metadata.to_thrift(),
+    column_index: chunk.metadata.column_index().cloned(),
+    offset_index: chunk.metadata.offset_index().cloned(),
+    page_specs: chunk.page_specs
+  })
+}
+
+/// Writes encoded column chunks into `sink` in order, returns row group metadata with
+/// offsets of column chunks in `sink`. Page flush callback is invoked for every page
+/// with its offset in `sink`.
+///
+/// When sharing of column chunks is enabled for a column, a column chunk identical to
+/// the one in `shared_chunks` is not written, its metadata references pages of the
+/// shared column chunk, and page flush callback is not invoked for its pages.
+fn write_column_chunks<W: Write>(
+  descr: SchemaDescPtr,
+  chunks: Vec<EncodedColumnChunk>,
+  sink: &mut FileSink<W>,
+  on_page_flush: Option<&PageFlushCallback>,
+  backpressure: Option<&BackpressureCallback>,
+  props: &WriterProperties,
+  shared_chunks: &mut [Option<SharedChunk>]
+) -> Result<RowGroupMetaDataPtr> {
+  let mut column_chunks = Vec::with_capacity(chunks.len());
+  let mut total_rows_written = None;
+  let mut total_uncompressed_size = 0;
+
+  for (i, chunk) in chunks.into_iter().enumerate() {
+    if let Some(rows) = total_rows_written {
+      if rows != chunk.rows_written {
+        return Err(general_err!(
+          "Incorrect number of rows, expected {} != {} rows",
+          rows,
+          chunk.rows_written
+        ));
+      }
+    } else {
+      total_rows_written = Some(chunk.rows_written);
+    }
+
+    let dedup_enabled = props.chunk_dedup_enabled(descr.column(i).path());
+    let shared_offset = match shared_chunks[i] {
+      Some(ref shared) if dedup_enabled && shared.data == chunk.data => {
+        Some(shared.offset)
+      },
+      _ => None
+    };
+
+    let metadata = match shared_offset {
+      Some(offset) => {
+        // Pages are already in the file, only column chunk metadata is written
+        let metadata = shift_column_metadata(&chunk.metadata, offset as i64)?;
+        ColumnChunkMetaDataBuilder::from_metadata(&metadata)
+          .with_file_offset(sink.pos() as i64)
+          .build()?
+      },
+      None => {
+        let shift = sink.pos();
+        sink.write_all(&chunk.data)?;
+        let metadata = shift_column_metadata(&chunk.metadata, shift as i64)?;
+        if let Some(callback) = on_page_flush {
+          for spec in &chunk.page_specs {
+            callback(metadata.column_path(), &shift_page_spec(spec, shift));
+          }
+        }
+        if let Some(callback) = backpressure {
+          for spec in &chunk.page_specs {
+            let page_end = shift + spec.offset + spec.bytes_written;
+            callback(spec.bytes_written, page_end)?;
+          }
+        }
+        if dedup_enabled {
+          shared_chunks[i] = Some(SharedChunk { data: chunk.data, offset: shift });
+        }
+        metadata
+      }
+    };
+    SerializedPageWriter::new(sink.clone()).write_metadata(&metadata)?;
+    total_uncompressed_size += metadata.uncompressed_size();
+    column_chunks.push(Rc::new(metadata));
+  }
+
+  // Total byte size of the row group is defined as size of uncompressed data
+  let row_group_metadata = RowGroupMetaData::builder(descr)
+    .with_column_metadata(column_chunks)
+    .with_total_byte_size(total_uncompressed_size)
+    .with_num_rows(total_rows_written.unwrap_or(0) as i64)
+    .build()?;
+  Ok(Rc::new(row_group_metadata))
+}
+
+/// Checks that compression codec of every leaf column is supported by the writer.
+fn validate_compression(
+  descr: &SchemaDescriptor,
+  props: &WriterProperties
+) -> Result<()> {
+  for i in 0..descr.num_columns() {
+    let column_path = descr.column(i).path().clone();
+    let codec = props.compression(&column_path);
+    if create_codec(codec).is_err() {
+      return Err(general_err!(
+        "Compression codec {} of column {} is not supported",
+        codec,
+        column_path.string()
+      ));
+    }
+  }
+  Ok(())
+}
+
+/// Returns start and end offsets of pages of the column chunk in the file.
+fn chunk_range(column: &ColumnChunkMetaData) -> (i64, i64) {
+  let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+  (start, start + column.compressed_size())
+}
+
+/// Returns copy of page write metrics `spec` with page offset moved by `shift` bytes.
+fn shift_page_spec(spec: &PageWriteSpec, shift: u64) -> PageWriteSpec {
+  let mut shifted = PageWriteSpec::new();
+  shifted.page_type = spec.page_type;
+  shifted.uncompressed_size = spec.uncompressed_size;
+  shifted.compressed_size = spec.compressed_size;
+  shifted.num_values = spec.num_values;
+  shifted.offset = spec.offset + shift;
+  shifted.bytes_written = spec.bytes_written;
+  shifted
+}
+
+/// Returns copy of column chunk metadata with all offsets moved by `shift` bytes.
+fn shift_column_metadata(
+  metadata: &ColumnChunkMetaData,
+  shift: i64
+) -> Result<ColumnChunkMetaData> {
+  let offset_index = metadata.offset_index().map(|offset_index| {
+    let page_locations = offset_index.page_locations().iter()
+      .map(|location| PageLocation {
+        offset: location.offset + shift,
+        compressed_page_size: location.compressed_page_size,
+        first_row_index: location.first_row_index
+      })
+      .collect();
+    OffsetIndex::new(page_locations)
+  });
+  ColumnChunkMetaDataBuilder::from_metadata(metadata)
+    .with_file_offset(metadata.file_offset() + shift)
+    .with_data_page_offset(metadata.data_page_offset() + shift)
+    .with_index_page_offset(metadata.index_page_offset().map(|v| v + shift))
+    .with_dictionary_page_offset(metadata.dictionary_page_offset().map(|v| v + shift))
+    .with_bloom_filter_offset(metadata.bloom_filter_offset().map(|v| v + shift))
+    .with_offset_index(offset_index)
+    .build()
+}
+
+// ----------------------------------------------------------------------
+// Serialized impl for page writer
+
+/// A serialized implementation for Parquet [`PageWriter`].
+/// Writes and serializes pages and metadata into the sink, starting at its current
+/// position.
+pub struct SerializedPageWriter<W: Write> {
+  sink: FileSink<W>,
+  page_checksum_enabled: bool,
+  chunk_checksum: Option<u32>,
+  on_page_flush: Option<Rc<Fn(&PageWriteSpec)>>,
+  backpressure: Option<BackpressureCallback>
+}
+
+impl<W: Write> SerializedPageWriter<W> {
+  /// Creates new page writer.
+  pub fn new(sink: FileSink<W>) -> Self {
+    Self {
+      sink: sink,
+      page_checksum_enabled: false,
+      chunk_checksum: None,
+      on_page_flush: None,
+      backpressure: None
+    }
+  }
+
+  /// Sets flag to write CRC32 checksum of page data into page headers, and returns
+  /// itself.
+  pub fn with_page_checksum_enabled(mut self, value: bool) -> Self {
+    self.page_checksum_enabled = value;
+    self
+  }
+
+  /// Sets flag to compute CRC32 checksum of all pages written by this page writer,
+  /// including page headers, and returns itself.
+  pub fn with_chunk_checksum_enabled(mut self, value: bool) -> Self {
+    self.chunk_checksum = if value { Some(0) } else { None };
+    self
+  }
+
+  /// Sets callback that is invoked with page write metrics after every page has been
+  /// written, and returns itself.
+  pub fn with_on_page_flush(mut self, callback: Rc<Fn(&PageWriteSpec)>) -> Self {
+    self.on_page_flush = Some(callback);
+    self
+  }
+
+  /// Sets backpressure callback that is invoked with number of bytes of the page and
+  /// current position of the sink after every page has been written, and returns
+  /// itself. Error returned by the callback is returned by `write_page`.
+  pub fn with_backpressure(mut self, callback: BackpressureCallback) -> Self {
+    self.backpressure = Some(callback);
+    self
+  }
+
+  /// Serializes page header into Thrift.
+  /// Returns number of bytes that have been written into the sink.
+  #[inline]
+  fn serialize_page_header(&mut self, header: parquet::PageHeader) -> Result<usize> {
+    let mut buf = Vec::new();
+    {
+      let mut protocol = TCompactOutputProtocol::new(&mut buf);
+      header.write_to_out_protocol(&mut protocol)?;
+      protocol.flush()?;
+    }
+    self.sink.write_all(&buf)?;
+    self.update_chunk_checksum(&buf);
+    Ok(buf.len())
+  }
+
+  /// Updates checksum of the column chunk with bytes of a page, if enabled.
+  #[inline]
+  fn update_chunk_checksum(&mut self, bytes: &[u8]) {
+    if let Some(checksum) = self.chunk_checksum {
+      self.chunk_checksum = Some(crc32::update(checksum, &crc32::IEEE_TABLE, bytes));
+    }
+  }
+
+  /// Serializes column chunk into Thrift.
+  /// Returns Ok() if there are not errors serializing and writing data into the sink.
+  #[inline]
+  fn serialize_column_chunk(&mut self, chunk: parquet::ColumnChunk) -> Result<()> {
+    let mut buf = Vec::new();
+    {
+      let mut protocol = TCompactOutputProtocol::new(&mut buf);
+      chunk.write_to_out_protocol(&mut protocol)?;
+      protocol.flush()?;
+    }
+    self.sink.write_all(&buf)?;
+    Ok(())
+  }
+}
+
+impl<W: Write> PageWriter for SerializedPageWriter<W> {
+  fn write_page(&mut self, page: CompressedPage) -> Result<PageWriteSpec> {
+    let uncompressed_size = page.uncompressed_size();
+    let compressed_size = page.compressed_size();
+    let num_values = page.num_values();
+    let encoding = page.encoding();
+    let page_type = page.page_type();
+    let crc = if self.page_checksum_enabled {
+      Some(crc32::checksum_ieee(page.data()) as i32)
+    } else {
+      None
+    };
+
+    let mut page_header = parquet::PageHeader {
+      type_: page_type.into(),
+      uncompressed_page_size: uncompressed_size as i32,
+      compressed_page_size: compressed_size as i32,
+      crc: crc,
+      data_page_header: None,
+      index_page_header: None,
+      dictionary_page_header: None,
+      data_page_header_v2: None
+    };
+
+    match page.compressed_page() {
+      &Page::DataPage { def_level_encoding, rep_level_encoding, ref statistics, .. } => {
+        let data_page_header = parquet::DataPageHeader {
+          num_values: num_values as i32,
+          encoding: encoding.into(),
+          definition_level_encoding: def_level_encoding.into(),
+          repetition_level_encoding: rep_level_encoding.into(),
+          statistics: statistics::to_thrift(statistics.as_ref())
+        };
+        page_header.data_page_header = Some(data_page_header);
+      },
+      &Page::DataPageV2 {
+        num_nulls,
+        num_rows,
+        def_levels_byte_len,
+        rep_levels_byte_len,
+        is_compressed,
+        ref statistics,
+        ..
+      } => {
+        let data_page_header_v2 = parquet::DataPageHeaderV2 {
+          num_values: num_values as i32,
+          num_nulls: num_nulls as i32,
+          num_rows: num_rows as i32,
+          encoding: encoding.into(),
+          definition_levels_byte_length: def_levels_byte_len as i32,
+          repetition_levels_byte_length: rep_levels_byte_len as i32,
+          is_compressed: Some(is_compressed),
+          statistics: statistics::to_thrift(statistics.as_ref())
+        };
+        page_header.data_page_header_v2 = Some(data_page_header_v2);
+      },
+      &Page::DictionaryPage { is_sorted, .. } => {
+        let dictionary_page_header = parquet::DictionaryPageHeader {
+          num_values: num_values as i32,
+          encoding: encoding.into(),
+          is_sorted: Some(is_sorted)
+        };
+        page_header.dictionary_page_header = Some(dictionary_page_header);
+      }
+    }
+
+    let start_pos = self.sink.pos();
+    let header_size = self.serialize_page_header(page_header)?;
+    self.sink.write_all(page.data())?;
+    self.update_chunk_checksum(page.data());
+
+    let mut spec = PageWriteSpec::new();
+    spec.page_type = page_type;
+    spec.uncompressed_size = uncompressed_size + header_size;
+    spec.compressed_size = compressed_size + header_size;
+    spec.offset = start_pos;
+    spec.bytes_written = self.sink.pos() - start_pos;
+    spec.num_values = num_values;
+
+    if let Some(ref callback) = self.on_page_flush {
+      callback(&spec);
+    }
+    if let Some(ref callback) = self.backpressure {
+      callback(spec.bytes_written, self.sink.pos())?;
+    }
+
+    Ok(spec)
+  }
+
+  fn write_bloom_filter(&mut self, filter: &Sbbf) -> Result<u64> {
+    let offset = self.sink.pos();
+    self.sink.write_all(&filter.to_bytes())?;
+    Ok(offset)
+  }
+
+  fn write_metadata(&mut self, metadata: &ColumnChunkMetaData) -> Result<()> {
+    self.serialize_column_chunk(metadata.to_thrift())
+  }
+
+  fn chunk_checksum(&self) -> Option<u32> {
+    self.chunk_checksum
+  }
+
+  fn close(&mut self) -> Result<()> {
+    self.sink.flush()?;
+    Ok(())
+  }
+}
+
+// ----------------------------------------------------------------------
+// Sink shared by file, row group and page writers
+
+/// Sink that is shared by file writer, row group writers and page writers.
+///
+/// Wraps [`TrackedWrite`], number of bytes written into the underlying writer is used
+/// as the current position in the file instead of seeking. Clones of the sink write
+/// into the same underlying writer and share the position.
+pub struct FileSink<W: Write> {
+  inner: Rc<RefCell<TrackedWrite<W>>>
+}
+
+impl<W: Write> FileSink<W> {
+  /// Creates new sink for writer `inner`, position starts at `0`.
+  pub fn new(inner: W) -> Self {
+    Self { inner: Rc::new(RefCell::new(TrackedWrite::new(inner))) }
+  }
+
+  /// Returns current position, i.e. number of bytes written so far.
+  pub fn pos(&self) -> u64 {
+    self.inner.borrow().bytes_written() as u64
+  }
+
+  /// Returns underlying writer.
+  /// Returns error if sink is still shared with other writers.
+  pub fn into_inner(self) -> Result<W> {
+    match Rc::try_unwrap(self.inner) {
+      Ok(inner) => Ok(inner.into_inner().into_inner()),
+      Err(_) => Err(general_err!("Sink is still used by other writers"))
+    }
+  }
+}
+
+impl<W: Write> Clone for FileSink<W> {
+  fn clone(&self) -> Self {
+    Self { inner: self.inner.clone() }
+  }
+}
+
+impl<W: Write> Write for FileSink<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.inner.borrow_mut().write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.borrow_mut().flush()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::error::Error;
+  use std::fs;
+  use std::io::{Read, Seek, SeekFrom};
+  use std::ops::Range;
+
+  use basic::{Repetition, Type};
+  use column::reader::get_typed_column_reader;
+  use column::writer::{get_typed_column_writer, get_typed_column_writer_mut};
+  use data_type::Int32Type;
+  use basic::{Compression, Encoding};
+  use file::layout::PageLayout;
+  use file::page_cache::PageCache;
+  use file::page_index::BoundaryOrder;
+  use file::properties::{WriterProperties, WriterVersion, DEFAULT_CREATED_BY};
+  use file::reader::{FileReader, RowGroupReader, SerializedFileReader};
+  use file::statistics::Statistics;
+  use record::api::Row;
+  use schema::types::{self, ColumnPath};
+  use util::test_common::get_temp_filename;
+
+  #[test]
+  fn test_file_writer_error_after_close() {
+    let path = get_temp_filename("test_file_writer_error_after_close.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    writer.close().unwrap();
+    {
+      let res = writer.next_row_group();
+      assert!(res.is_err());
+      if let Err(err) = res {
+        assert_eq!(err.description(), "File writer is closed");
+      }
+    }
+    {
+      let res = writer.close();
+      assert!(res.is_err());
+      if let Err(err) = res {
+        assert_eq!(err.description(), "File writer is closed");
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_writer_previous_row_group_not_closed() {
+    let path = get_temp_filename("test_file_writer_rg_not_closed.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let _row_group_writer = writer.next_row_group().unwrap();
+    let res = writer.next_row_group();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Previous row group writer was not closed");
+    }
+    let res = writer.close();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Previous row group writer was not closed");
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_error_after_close() {
+    let path = get_temp_filename("test_rg_writer_error_after_close.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    row_group_writer.close().unwrap();
+
+    let res = row_group_writer.next_column();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Row group writer is closed");
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_num_rows_mismatch() {
+    let path = get_temp_filename("test_rg_writer_num_rows_mismatch.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(2);
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[1, 2, 3], None, None).unwrap();
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[1, 2], None, None).unwrap();
+    let res = row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed));
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Incorrect number of rows, expected 3 != 2 rows");
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_previous_column_not_closed() {
+    let path = get_temp_filename("test_rg_writer_column_not_closed.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    let _col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let res = row_group_writer.next_column();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(err.description(), "Previous column writer was not closed");
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_not_all_columns_closed() {
+    let path = get_temp_filename("test_rg_writer_columns_not_closed.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(2);
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    assert_eq!(row_group_writer.num_columns(), 2);
+
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[1, 2, 3], None, None).unwrap();
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+
+    let res = row_group_writer.close();
+    assert!(res.is_err());
+    if let Err(err) = res {
+      assert_eq!(
+        err.description(),
+        "Not all columns have been written, expected 2 columns, but closed 1"
+      );
+    }
+  }
+
+  #[test]
+  fn test_row_group_writer_sizes() {
+    let path = get_temp_filename("test_rg_writer_sizes.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(3);
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    assert_eq!(row_group_writer.total_compressed_size(), 0);
+    assert_eq!(row_group_writer.total_uncompressed_size(), 0);
+
+    let mut num_columns = 0;
+    while let Some(col_writer) = row_group_writer.next_column().unwrap() {
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&[1, 2, 3, 4, 5, 6, 7, 8], None, None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      num_columns += 1;
+    }
+    assert_eq!(num_columns, 3);
+
+    let metadata = row_group_writer.close().unwrap();
+    let compressed_size: i64 = metadata.columns().iter()
+      .map(|c| c.compressed_size())
+      .sum();
+    let uncompressed_size: i64 = metadata.columns().iter()
+      .map(|c| c.uncompressed_size())
+      .sum();
+    assert!(compressed_size > 0);
+    assert_eq!(row_group_writer.total_compressed_size(), compressed_size as u64);
+    assert_eq!(row_group_writer.total_uncompressed_size(), uncompressed_size as u64);
+    assert_eq!(metadata.total_byte_size(), uncompressed_size);
+    assert_eq!(metadata.num_rows(), 8);
+
+    // Closing row group again returns the same metadata
+    assert!(Rc::ptr_eq(&row_group_writer.close().unwrap(), &metadata));
+    writer.close_row_group(row_group_writer).unwrap();
+    writer.close().unwrap();
+  }
+
+  #[test]
+  fn test_file_writer_empty_file() {
+    let path = get_temp_filename("test_file_writer_empty_file.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .build().unwrap())
+        ])
+        .build().unwrap()
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut writer = SerializedFileWriter::new(file, schema.clone(), props).unwrap();
+    writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 0);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+    assert_eq!(reader.metadata().file_metadata().schema(), schema.as_ref());
+    assert_eq!(
+      reader.metadata().file_metadata().created_by().as_ref().map(|s| s.as_str()),
+      Some(DEFAULT_CREATED_BY)
+    );
+  }
+
+  #[test]
+  fn test_file_writer_with_properties() {
+    let path = get_temp_filename("test_file_writer_with_properties.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_writer_version(WriterVersion::PARQUET_2_0)
+        .with_created_by("test writer".to_owned())
+        .build()
+    );
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let file_metadata = reader.metadata().file_metadata();
+    assert_eq!(file_metadata.version(), 2);
+    assert_eq!(
+      file_metadata.created_by().as_ref().map(|s| s.as_str()),
+      Some("test writer")
+    );
+  }
+
+  #[test]
+  fn test_file_writer_key_value_metadata() {
+    let path = get_temp_filename("test_file_writer_key_value_metadata.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let key_value_metadata = vec![
+      KeyValue::new("job_id".to_owned(), Some("1234".to_owned())),
+      KeyValue::new("empty".to_owned(), None)
+    ];
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_key_value_metadata(key_value_metadata.clone())
+        .build()
+    );
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let file_metadata = reader.metadata().file_metadata();
+    assert_eq!(file_metadata.key_value_metadata(), &Some(key_value_metadata));
+
+    // Key/value metadata is not written, if not set
+    let props = Rc::new(WriterProperties::builder().build());
+    let reader = test_file_roundtrip_with_props(
+      "test_file_writer_no_key_value.parquet", vec![vec![1]], props);
+    assert_eq!(reader.metadata().file_metadata().key_value_metadata(), &None);
+  }
+
+  #[test]
+  fn test_file_writer_created_by_suffix_and_writer_info() {
+    let path = get_temp_filename("test_file_writer_writer_info.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_created_by("test writer".to_owned())
+        .with_created_by_suffix("ingest version 2.1".to_owned())
+        .with_key_value_metadata(vec![KeyValue::new("job_id".to_owned(), None)])
+        .with_writer_info("service".to_owned(), "ingest".to_owned())
+        .with_writer_info("version".to_owned(), "2.1".to_owned())
+        .build()
+    );
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let metadata = writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    for file_metadata in &[metadata.file_metadata(), reader.metadata().file_metadata()] {
+      assert_eq!(
+        file_metadata.created_by().as_ref().map(|s| s.as_str()),
+        Some("test writer ingest version 2.1")
+      );
+      assert_eq!(
+        file_metadata.key_value_metadata(),
+        &Some(vec![
+          KeyValue::new("job_id".to_owned(), None),
+          KeyValue::new("writer.info.service".to_owned(), Some("ingest".to_owned())),
+          KeyValue::new("writer.info.version".to_owned(), Some("2.1".to_owned()))
+        ])
+      );
+      let writer_info = file_metadata.writer_info();
+      assert_eq!(writer_info.len(), 2);
+      assert_eq!(writer_info.get("service").map(|s| s.as_str()), Some("ingest"));
+      assert_eq!(writer_info.get("version").map(|s| s.as_str()), Some("2.1"));
+    }
+
+    // Writer information alone is written as key/value metadata
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_writer_info("service".to_owned(), "ingest".to_owned())
+        .build()
+    );
+    let reader = test_file_roundtrip_with_props(
+      "test_file_writer_writer_info_only.parquet", vec![vec![1]], props);
+    let file_metadata = reader.metadata().file_metadata();
+    assert_eq!(file_metadata.key_value_metadata().as_ref().map(|kvs| kvs.len()), Some(1));
+    assert_eq!(file_metadata.writer_info().len(), 1);
+  }
+
+  #[test]
+  fn test_file_writer_memory_usage() {
+    let path = get_temp_filename("test_file_writer_memory_usage.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_data_page_row_count_limit(100)
+        .build()
+    );
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    assert_eq!(writer.memory_usage(), 0);
+
+    let values: Vec<i32> = (0..1000).map(|i| i % 100).collect();
+    let mut row_group_writer = writer.next_row_group().unwrap();
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&values[..100], None, None).unwrap();
+    let usage = row_group_writer.memory_usage();
+    assert!(usage > 0);
+    assert_eq!(writer.memory_usage(), usage);
+
+    // Data pages are buffered while dictionary encoding is used
+    typed.write_batch(&values[100..], None, None).unwrap();
+    assert!(row_group_writer.memory_usage() > usage);
+    assert_eq!(writer.memory_usage(), row_group_writer.memory_usage());
+
+    // Memory is released once pages are written
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+    assert_eq!(row_group_writer.memory_usage(), 0);
+    writer.close_row_group(row_group_writer).unwrap();
+    assert_eq!(writer.memory_usage(), 0);
+    assert!(writer.max_memory_usage() > usage);
+
+    // Buffered row groups share the memory tracker of the file writer
+    let mut row_group_writer = writer.next_buffered_row_group().unwrap();
+    {
+      let typed = get_typed_column_writer_mut::<Int32Type>(
+        row_group_writer.column(0).unwrap()
+      );
+      typed.write_batch(&values, None, None).unwrap();
+    }
+    assert!(row_group_writer.memory_usage() > 0);
+    assert_eq!(writer.memory_usage(), row_group_writer.memory_usage());
+    writer.close_buffered_row_group(row_group_writer).unwrap();
+    assert_eq!(writer.memory_usage(), 0);
+    writer.close().unwrap();
+  }
+
+  #[test]
+  fn test_file_writer_deterministic_output() {
+    let data = vec![
+      (0..1000).map(|i| i % 37).collect::<Vec<i32>>(),
+      (0..500).map(|i| i * 7 - 1000).collect::<Vec<i32>>()
+    ];
+    let write_file = |file_name: &str, key_value_metadata: Vec<KeyValue>, deterministic| {
+      let props = WriterProperties::builder()
+        .with_compression(Compression::GZIP)
+        .with_compression_threads(2)
+        .with_data_page_size(256)
+        .with_dictionary_page_size_limit(1024)
+        .with_page_index_enabled(true)
+        .with_chunk_checksum_enabled(true)
+        .with_created_by_suffix("test version 1.0".to_owned())
+        .with_key_value_metadata(key_value_metadata)
+        .with_writer_info("service".to_owned(), "test".to_owned())
+        .with_deterministic_output_enabled(deterministic)
+        .build();
+      test_file_roundtrip_with_props(file_name, data.clone(), Rc::new(props));
+      let mut buf = Vec::new();
+      fs::File::open(get_temp_filename(file_name))
+        .unwrap()
+        .read_to_end(&mut buf)
+        .unwrap();
+      buf
+    };
+    let a = KeyValue::new("a".to_owned(), Some("1".to_owned()));
+    let b = KeyValue::new("b".to_owned(), Some("2".to_owned()));
+
+    // Same data and key/value metadata in different order result in the same bytes
+    let first = write_file(
+      "test_file_writer_deterministic_output_1.parquet",
+      vec![b.clone(), a.clone()],
+      true
+    );
+    let second = write_file(
+      "test_file_writer_deterministic_output_2.parquet",
+      vec![a.clone(), b.clone()],
+      true
+    );
+    assert_eq!(first, second);
+
+    // Without deterministic output key/value metadata is written in the order it is set
+    let third = write_file(
+      "test_file_writer_deterministic_output_3.parquet",
+      vec![b.clone(), a.clone()],
+      false
+    );
+    assert!(first != third);
+    assert_eq!(first.len(), third.len());
+  }
+
+  #[test]
+  fn test_file_writer_row_groups() {
+    let data = vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8], vec![9, 10]];
+    test_file_roundtrip("test_file_writer_row_groups.parquet", data);
+  }
+
+  #[test]
+  fn test_file_writer_empty_row_groups() {
+    let data = vec![vec![], vec![1, 2, 3], vec![]];
+    test_file_roundtrip("test_file_writer_empty_row_groups.parquet", data);
+  }
+
+  #[test]
+  fn test_file_writer_all_null_and_empty_row_groups() {
+    let path =
+      get_temp_filename("test_file_writer_all_null_and_empty_row_groups.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut vec![
+          Rc::new(types::Type::primitive_type_builder("col1", Type::INT32)
+            .with_repetition(Repetition::OPTIONAL)
+            .build().unwrap())
+        ])
+        .build().unwrap()
+    );
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_page_index_enabled(true)
+        .with_audit_enabled(true)
+        .build()
+    );
+    let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    for &num_nulls in &[0, 3] {
+      let mut row_group_writer = writer.next_row_group().unwrap();
+      let col_writer = row_group_writer.next_column().unwrap().unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&[], Some(&vec![0; num_nulls]), None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      writer.close_row_group(row_group_writer).unwrap();
+    }
+    writer.close().unwrap();
+
+    let file_size = fs::metadata(&path).unwrap().len() as i64;
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+    for (i, &num_nulls) in [0, 3].iter().enumerate() {
+      let row_group = reader.metadata().row_group(i);
+      assert_eq!(row_group.num_rows(), num_nulls);
+      // Column chunks without values still point to their pages, but have no
+      // dictionary page
+      let column = row_group.column(0);
+      let (start, end) = chunk_range(column);
+      assert!(start >= PARQUET_MAGIC.len() as i64 && end <= file_size);
+      assert_eq!(column.dictionary_page_offset(), None);
+      assert_eq!(column.num_values(), num_nulls);
+      assert_eq!(
+        column.statistics(),
+        Some(&Statistics::int32(None, None, None, num_nulls as u64, false))
+      );
+    }
+
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows, vec![Row::Group(vec![("col1".to_string(), Row::Null)]); 3]);
+  }
+
+  #[test]
+  fn test_file_writer_compression() {
+    let codecs = vec![
+      Compression::SNAPPY,
+      Compression::GZIP,
+      Compression::BROTLI,
+      Compression::ZSTD
+    ];
+    for codec in codecs {
+      let props = Rc::new(WriterProperties::builder().with_compression(codec).build());
+      let data = vec![(0..1000).map(|i| i % 10).collect(), vec![1, 2, 3]];
+      let file_name = format!("test_file_writer_compression_{}.parquet", codec);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+      let row_group_metadata = reader.metadata().row_group(0);
+      assert_eq!(row_group_metadata.column(0).compression(), codec);
+    }
+  }
+
+  #[test]
+  fn test_file_writer_v2() {
+    for dictionary_enabled in vec![true, false] {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_writer_version(WriterVersion::PARQUET_2_0)
+          .with_compression(Compression::SNAPPY)
+          .with_dictionary_enabled(dictionary_enabled)
+          .build()
+      );
+      let data = vec![(0..1000).map(|i| i % 10).collect(), vec![1, 2, 3]];
+      let file_name = format!("test_file_writer_v2_{}.parquet", dictionary_enabled);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+      assert_eq!(reader.metadata().file_metadata().version(), 2);
+      let expected_encodings = if dictionary_enabled {
+        vec![Encoding::PLAIN, Encoding::RLE_DICTIONARY]
+      } else {
+        vec![Encoding::DELTA_BINARY_PACKED]
+      };
+      let row_group_metadata = reader.metadata().row_group(0);
+      assert_eq!(row_group_metadata.column(0).encodings(), &expected_encodings);
+    }
+  }
+
+  #[test]
+  fn test_file_writer_page_checksum() {
+    for checksum_enabled in vec![true, false] {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_compression(Compression::SNAPPY)
+          .with_page_checksum_enabled(checksum_enabled)
+          .build()
+      );
+      let data = vec![(0..1000).collect(), vec![1, 2, 3]];
+      let file_name =
+        format!("test_file_writer_page_checksum_{}.parquet", checksum_enabled);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+
+      let mut file = fs::File::open(get_temp_filename(&file_name)).unwrap();
+      let layout = reader.layout().unwrap();
+      for row_group in layout.row_groups() {
+        for page in row_group.columns()[0].pages() {
+          if !checksum_enabled {
+            assert_eq!(page.crc, None);
+            continue;
+          }
+          let mut buf = vec![0; page.compressed_size];
+          file.seek(SeekFrom::Start(page.offset + page.header_size as u64)).unwrap();
+          file.read_exact(&mut buf).unwrap();
+          assert_eq!(page.crc, Some(crc32::checksum_ieee(&buf)));
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_writer_chunk_checksum() {
+    for checksum_enabled in vec![true, false] {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_compression(Compression::SNAPPY)
+          .with_data_page_size(256)
+          .with_chunk_checksum_enabled(checksum_enabled)
+          .build()
+      );
+      let data = vec![(0..1000).map(|i| i % 100).collect(), vec![1, 2, 3]];
+      let file_name =
+        format!("test_file_writer_chunk_checksum_{}.parquet", checksum_enabled);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+
+      for i in 0..reader.num_row_groups() {
+        let column = reader.metadata().row_group(i).columns()[0].clone();
+        let row_group_reader = reader.get_row_group(i).unwrap();
+        let bytes = row_group_reader.get_column_chunk_bytes(0).unwrap();
+        if checksum_enabled {
+          assert_eq!(column.checksum(), Some(crc32::checksum_ieee(&bytes)));
+        } else {
+          assert_eq!(column.checksum(), None);
+        }
+        assert_eq!(reader.verify_column_checksum(i, 0).unwrap(), checksum_enabled);
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_reader_chunk_checksum_verification() {
+    let props = Rc::new(
+      WriterProperties::builder().with_chunk_checksum_enabled(true).build()
+    );
+    let file_name = "test_file_reader_chunk_checksum_verification.parquet";
+    let reader = test_file_roundtrip_with_props(file_name, vec![vec![1, 2, 3]], props);
+    let column = reader.metadata().row_group(0).columns()[0].clone();
+    let mut bytes = reader.get_row_group(0).unwrap().get_column_chunk_bytes(0).unwrap();
+
+    // Corrupt the last byte of the column chunk, i.e. values of the last data page
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    let start = column.dictionary_page_offset().unwrap_or(column.data_page_offset());
+    let offset = start as u64 + last as u64;
+    let path = get_temp_filename(file_name);
+    let mut file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.write_all(&bytes[last..]).unwrap();
+
+    let (checksum, actual) = (column.checksum().unwrap(), crc32::checksum_ieee(&bytes));
+    let expected = move || general_err!(
+      "Checksum mismatch of column \"col1\": expected {}, found {}",
+      checksum,
+      actual
+    );
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.verify_column_checksum(0, 0).unwrap_err(), expected());
+    assert!(reader.get_row_group(0).unwrap().get_column_page_reader(0).is_ok());
+
+    let reader = reader.with_checksum_verification(true);
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(row_group_reader.get_column_page_reader(0).err(), Some(expected()));
+    assert_eq!(row_group_reader.get_column_reader(0).err(), Some(expected()));
+    // Row iterators report the mismatch when created instead of panicking
+    assert_eq!(reader.get_row_iter(None).err(), Some(expected()));
+    assert_eq!(row_group_reader.get_row_iter(None).err(), Some(expected()));
+
+    // Corrupt the first byte of the column chunk as well, i.e. the first page header
+    bytes[0] ^= 0xff;
+    file.seek(SeekFrom::Start(start as u64)).unwrap();
+    file.write_all(&bytes[..1]).unwrap();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(
+      reader.verify_column_checksum(0, 0).unwrap_err(),
+      general_err!(
+        "Checksum mismatch of column \"col1\": expected {}, found {}",
+        column.checksum().unwrap(),
+        crc32::checksum_ieee(&bytes)
+      )
+    );
+  }
+
+  #[test]
+  fn test_file_reader_page_cache_multiple_pages() {
+    for version in vec![WriterVersion::PARQUET_1_0, WriterVersion::PARQUET_2_0] {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_writer_version(version)
+          .with_dictionary_enabled(false)
+          .with_data_page_size(256)
+          .build()
+      );
+      let file_name = format!("test_file_reader_page_cache_{:?}.parquet", version);
+      test_file_roundtrip_with_props(&file_name, vec![(0..1000).collect()], props);
+      let path = get_temp_filename(&file_name);
+
+      let read_pages = |cache: Option<&PageCache>| -> Vec<Page> {
+        let mut reader = SerializedFileReader::try_from_path(&path).unwrap();
+        if let Some(cache) = cache {
+          reader = reader.with_page_cache(cache.clone()).unwrap();
+        }
+        let mut page_reader =
+          reader.get_row_group(0).unwrap().get_column_page_reader(0).unwrap();
+        let mut pages = Vec::new();
+        while let Some(page) = page_reader.get_next_page().unwrap() {
+          pages.push(page);
+        }
+        pages
+      };
+
+      let expected = read_pages(None);
+      assert!(expected.len() > 2);
+      assert!(expected[0].buffer().data() != expected[1].buffer().data());
+
+      // Pages are cached on the first read and served from the cache on the second read,
+      // each page must be cached under its own offset
+      let cache = PageCache::new(1024 * 1024);
+      for (i, pages) in vec![read_pages(Some(&cache)), read_pages(Some(&cache))]
+        .into_iter()
+        .enumerate()
+      {
+        assert_eq!(pages.len(), expected.len());
+        for (page, expected_page) in pages.iter().zip(expected.iter()) {
+          assert_eq!(page.page_type(), expected_page.page_type());
+          assert_eq!(page.num_values(), expected_page.num_values());
+          assert_eq!(page.buffer().data(), expected_page.buffer().data());
+        }
+        assert_eq!(cache.hits(), (i * expected.len()) as u64);
+      }
+      assert_eq!(cache.len(), expected.len());
+      assert_eq!(cache.misses(), expected.len() as u64);
+    }
+  }
+
+  #[test]
+  fn test_file_writer_bloom_filter() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_compression(Compression::SNAPPY)
+        .with_bloom_filter_enabled(true)
+        .with_bloom_filter_ndv(100)
+        .build()
+    );
+    let data = vec![(0..100).collect(), (100..200).collect()];
+    let file_name = "test_file_writer_bloom_filter.parquet";
+    let reader = test_file_roundtrip_with_props(file_name, data, props);
+
+    for i in 0..reader.num_row_groups() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      let filter = row_group_reader.get_column_bloom_filter(0).unwrap().unwrap();
+      let (start, end) = (i as i32 * 100, (i as i32 + 1) * 100);
+      assert!((start..end).all(|value| filter.check(&value)));
+      let false_positives = (1000..2000i32).filter(|value| filter.check(value)).count();
+      assert!(false_positives < 200, "too many false positives: {}", false_positives);
+    }
+
+    // Files without Bloom filters
+    let reader = test_file_roundtrip_with_props(
+      "test_file_writer_no_bloom_filter.parquet",
+      vec![vec![1, 2, 3]],
+      Rc::new(WriterProperties::builder().build())
+    );
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(row_group_reader.get_column_bloom_filter(0).unwrap(), None);
+  }
+
+  #[test]
+  fn test_file_writer_page_index() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(10)
+        .with_write_batch_size(10)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![(0..100).collect(), (100..200).rev().collect()];
+    let file_name = "test_file_writer_page_index.parquet";
+    let reader = test_file_roundtrip_with_props(file_name, data, props);
+
+    let orders = [BoundaryOrder::ASCENDING, BoundaryOrder::DESCENDING];
+    for i in 0..reader.num_row_groups() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      let metadata = row_group_reader.metadata();
+      let column_index = row_group_reader.get_column_index(0).unwrap().unwrap();
+      assert_eq!(column_index.num_pages(), 10);
+      assert_eq!(column_index.boundary_order(), orders[i]);
+      assert_eq!(column_index.null_pages(), &[false; 10][..]);
+
+      let offset_index = row_group_reader.get_offset_index(0).unwrap().unwrap();
+      let locations = offset_index.page_locations();
+      assert_eq!(locations.len(), 10);
+      assert_eq!(locations[0].offset, metadata.column(0).data_page_offset());
+      for (j, location) in locations.iter().enumerate() {
+        assert_eq!(location.first_row_index, j as i64 * 10);
+      }
+
+      // Page indexes are written after all row groups
+      let column_index_offset = metadata.column(0).column_index_offset().unwrap();
+      let last_column = reader.metadata().row_group(1).column(0);
+      let last_column_end =
+        last_column.data_page_offset() + last_column.compressed_size();
+      assert!(column_index_offset >= last_column_end);
+    }
+
+    // Files without page indexes
+    let reader = test_file_roundtrip_with_props(
+      "test_file_writer_no_page_index.parquet",
+      vec![vec![1, 2, 3]],
+      Rc::new(WriterProperties::builder().build())
+    );
+    let row_group_reader = reader.get_row_group(0).unwrap();
+    assert_eq!(row_group_reader.get_column_index(0).unwrap(), None);
+    assert_eq!(row_group_reader.get_offset_index(0).unwrap(), None);
+  }
+
+  #[test]
+  fn test_file_writer_page_row_ranges() {
+    for page_index_enabled in vec![true, false] {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_dictionary_enabled(false)
+          .with_data_page_row_count_limit(4)
+          .with_write_batch_size(4)
+          .with_page_index_enabled(page_index_enabled)
+          .build()
+      );
+      let data = vec![(0..10).collect(), (10..15).collect()];
+      let file_name =
+        format!("test_file_writer_page_row_ranges_{}.parquet", page_index_enabled);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+
+      let layout = reader.layout().unwrap();
+      let expected = vec![vec![0..4, 4..8, 8..10], vec![0..4, 4..5]];
+      for i in 0..reader.num_row_groups() {
+        let ranges = reader.page_row_ranges(i, 0).unwrap();
+        let data_pages: Vec<&PageLayout> = layout.row_groups()[i].columns()[0]
+          .pages()
+          .iter()
+          .filter(|p| p.is_data_page())
+          .collect();
+        assert_eq!(ranges.len(), data_pages.len());
+        for (range, page) in ranges.iter().zip(data_pages) {
+          assert_eq!(&range.page, page);
+        }
+        let row_ranges: Vec<Range<i64>> = ranges.iter().map(|r| r.row_range()).collect();
+        assert_eq!(row_ranges, expected[i]);
+      }
+      assert!(reader.page_row_ranges(2, 0).is_err());
+      assert!(reader.page_row_ranges(0, 1).is_err());
+    }
+  }
+
+  #[test]
+  fn test_file_writer_callbacks() {
+    let pages = Rc::new(RefCell::new(Vec::new()));
+    let row_groups = Rc::new(RefCell::new(Vec::new()));
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(2)
+        .with_write_batch_size(2)
+        .build()
+    );
+
+    let pages_ref = pages.clone();
+    let row_groups_ref = row_groups.clone();
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap()
+        .with_on_page_flush(Rc::new(move |path: &ColumnPath, spec: &PageWriteSpec| {
+          pages_ref.borrow_mut().push((path.string(), spec.offset, spec.num_values));
+        }))
+        .with_on_row_group_close(Rc::new(move |metadata: &RowGroupMetaData| {
+          row_groups_ref.borrow_mut().push(metadata.num_rows());
+        }));
+
+    for subset in &[vec![1, 2, 3], vec![4]] {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      while let Some(col_writer) = row_group_writer.next_column().unwrap() {
+        let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+        typed.write_batch(&subset[..], None, None).unwrap();
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      }
+      // Offsets reported to the callback match offsets in column chunk metadata
+      let row_group_metadata = row_group_writer.close().unwrap();
+      for column in row_group_metadata.columns() {
+        let offset = column.data_page_offset() as u64;
+        assert!(pages.borrow().iter().any(|page| page.1 == offset));
+      }
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+
+    let pages: Vec<_> = pages.borrow().iter()
+      .map(|&(ref path, _, num_values)| (path.clone(), num_values))
+      .collect();
+    assert_eq!(
+      pages,
+      vec![
+        ("col1".to_owned(), 2),
+        ("col1".to_owned(), 1),
+        ("col2".to_owned(), 2),
+        ("col2".to_owned(), 1),
+        ("col1".to_owned(), 1),
+        ("col2".to_owned(), 1)
+      ]
+    );
+    assert_eq!(*row_groups.borrow(), vec![3, 1]);
+  }
+
+  #[test]
+  fn test_file_writer_progress() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(2)
+        .with_write_batch_size(2)
+        .build()
+    );
+    let path = get_temp_filename("test_file_writer_progress.parquet");
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let reports_ref = reports.clone();
+    let file = fs::File::create(&path).unwrap();
+    let mut file_writer =
+      SerializedFileWriter::new(file, get_test_schema(1), props).unwrap()
+        .with_progress(Rc::new(move |progress: &Progress| {
+          reports_ref.borrow_mut().push(*progress);
+        }));
+
+    for subset in &[vec![1, 2, 3], vec![4]] {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      let col_writer = row_group_writer.next_column().unwrap().unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&subset[..], None, None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+
+    // 3 pages, 2 row groups and file metadata are reported
+    let reports = reports.borrow();
+    assert_eq!(reports.len(), 6);
+    let file_size = fs::metadata(&path).unwrap().len();
+    assert_eq!(file_writer.progress(), Some(Progress::new(4, file_size, 2)));
+    assert_eq!(reports.last(), Some(&Progress::new(4, file_size, 2)));
+    assert_eq!(reports.iter().map(|p| p.num_row_groups()).collect::<Vec<_>>(),
+      vec![0, 0, 1, 1, 2, 2]);
+    for window in reports.windows(2) {
+      assert!(window[0].num_bytes() <= window[1].num_bytes());
+    }
+
+    // Read the file back, reader reports the same rows and row groups
+    let read_reports = Rc::new(RefCell::new(Vec::new()));
+    let read_reports_ref = read_reports.clone();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap()
+      .with_progress(Rc::new(move |progress: &Progress| {
+        read_reports_ref.borrow_mut().push(*progress);
+      }));
+    assert_eq!(reader.get_row_iter(None).unwrap().count(), 4);
+    let progress = reader.progress().unwrap();
+    assert_eq!(progress.num_rows(), 4);
+    assert_eq!(progress.num_row_groups(), 2);
+    assert!(progress.num_bytes() > 0 && progress.num_bytes() < file_size);
+    // 3 pages and 2 row groups
+    assert_eq!(read_reports.borrow().len(), 5);
+  }
+
+  #[test]
+  fn test_file_writer_backpressure() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_dictionary_enabled(false)
+        .with_data_page_row_count_limit(2)
+        .with_write_batch_size(2)
+        .build()
+    );
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let calls_ref = calls.clone();
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(1), props.clone()).unwrap()
+        .with_backpressure(Rc::new(move |bytes: u64, total_bytes: u64| {
+          calls_ref.borrow_mut().push((bytes, total_bytes));
+          Ok(())
+        }));
+
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[1, 2, 3], None, None).unwrap();
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+    file_writer.close_row_group(row_group_writer).unwrap();
+
+    let mut row_group_writer = file_writer.next_buffered_row_group().unwrap();
+    {
+      let col_writer = row_group_writer.column(0).unwrap();
+      let typed = get_typed_column_writer_mut::<Int32Type>(col_writer);
+      typed.write_batch(&[4, 5, 6], None, None).unwrap();
+    }
+    // Buffered pages are reported once the row group is written into the file
+    assert_eq!(calls.borrow().len(), 2);
+    file_writer.close_buffered_row_group(row_group_writer).unwrap();
+    file_writer.close().unwrap();
+
+    // Total bytes grow by bytes of every page, starting after the magic
+    let calls = calls.borrow();
+    assert_eq!(calls.len(), 4);
+    assert_eq!(calls[0].1, PARQUET_MAGIC.len() as u64 + calls[0].0);
+    assert_eq!(calls[1].1, calls[0].1 + calls[1].0);
+    assert!(calls[2].1 > calls[1].1);
+    assert_eq!(calls[3].1, calls[2].1 + calls[3].0);
+
+    // Error of the callback aborts writing
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(1), props).unwrap()
+        .with_backpressure(Rc::new(|_: u64, total_bytes: u64| {
+          if total_bytes > 100 {
+            Err(general_err!("Storage is overloaded"))
+          } else {
+            Ok(())
+          }
+        }));
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    let values: Vec<i32> = (0..100).collect();
+    let result = typed.write_batch(&values, None, None);
+    assert_eq!(result.unwrap_err(), general_err!("Storage is overloaded"));
+  }
+
+  #[test]
+  fn test_file_writer_buffered_row_group() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_data_page_row_count_limit(3)
+        .with_write_batch_size(3)
+        .with_bloom_filter_enabled(true)
+        .with_page_checksum_enabled(true)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![vec![1, 2, 3, 4, 5, 6, 7], vec![8, 9]];
+
+    // Columns are written one by one, in schema order
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props.clone()).unwrap();
+    for subset in &data {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      while let Some(col_writer) = row_group_writer.next_column().unwrap() {
+        let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+        typed.write_batch(&subset[..], None, None).unwrap();
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      }
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+    let expected = file_writer.into_inner().unwrap();
+
+    // Columns are written interleaved per batch, in reverse order
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap();
+    for subset in &data {
+      let mut row_group_writer = file_writer.next_buffered_row_group().unwrap();
+      assert_eq!(row_group_writer.num_columns(), 2);
+      for batch in subset.chunks(2) {
+        for i in (0..2).rev() {
+          let col_writer = row_group_writer.column(i).unwrap();
+          let typed = get_typed_column_writer_mut::<Int32Type>(col_writer);
+          typed.write_batch(batch, None, None).unwrap();
+        }
+      }
+      file_writer.close_buffered_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+    assert_eq!(file_writer.into_inner().unwrap(), expected);
+  }
+
+  #[test]
+  #[cfg(feature = "rayon")]
+  fn test_file_writer_parallel_row_group() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_data_page_row_count_limit(3)
+        .with_bloom_filter_enabled(true)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![vec![1, 2, 3, 4, 5, 6, 7], vec![8, 9, 10, 11, 12, 13, 14]];
+
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props.clone()).unwrap();
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    let mut i = 0;
+    while let Some(col_writer) = row_group_writer.next_column().unwrap() {
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&data[i], None, None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      i += 1;
+    }
+    file_writer.close_row_group(row_group_writer).unwrap();
+    file_writer.close().unwrap();
+    let expected = file_writer.into_inner().unwrap();
+
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap();
+    file_writer.write_row_group_parallel(|i, col_writer| {
+      let typed = get_typed_column_writer_mut::<Int32Type>(col_writer);
+      typed.write_batch(&data[i], None, None)?;
+      Ok(())
+    }).unwrap();
+    file_writer.close().unwrap();
+    assert_eq!(file_writer.into_inner().unwrap(), expected);
+
+    // Errors of worker threads are returned
+    let mut file_writer = SerializedFileWriter::new(
+      Vec::new(),
+      get_test_schema(2),
+      Rc::new(WriterProperties::builder().build())
+    ).unwrap();
+    let res = file_writer.write_row_group_parallel(|i, _| {
+      if i == 1 { Err(general_err!("Failed to write column {}", i)) } else { Ok(()) }
+    });
+    assert_eq!(res, Err(general_err!("Failed to write column 1")));
+  }
+
+  #[test]
+  fn test_file_writer_append_row_groups() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_compression(Compression::SNAPPY)
+        .with_data_page_row_count_limit(2)
+        .with_bloom_filter_enabled(true)
+        .with_bloom_filter_ndv(10)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8]];
+    let source = test_file_roundtrip_with_props(
+      "test_file_writer_append_row_groups_source.parquet",
+      data.clone(),
+      props
+    );
+
+    // Row groups are appended after a row group written with column writers
+    let path = get_temp_filename("test_file_writer_append_row_groups.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut file_writer = SerializedFileWriter::new(file, get_test_schema(1), props)
+      .unwrap();
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[0], None, None).unwrap();
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+    file_writer.close_row_group(row_group_writer).unwrap();
+    for i in 0..source.num_row_groups() {
+      let row_group_reader = source.get_row_group(i).unwrap();
+      file_writer.append_row_group(row_group_reader.as_ref()).unwrap();
+    }
+    file_writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 3);
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 9);
+    let expected = vec![vec![0], data[0].clone(), data[1].clone()];
+    for i in 0..reader.num_row_groups() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      let col_reader = row_group_reader.get_column_reader(0).unwrap();
+      let mut typed = get_typed_column_reader::<Int32Type>(col_reader);
+      let mut values = vec![0; 10];
+      let (values_read, _) = typed.read_batch(10, None, None, &mut values).unwrap();
+      assert_eq!(&values[..values_read], &expected[i][..]);
+    }
+
+    for i in 1..reader.num_row_groups() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      let column = row_group_reader.metadata().column(0);
+      let source_reader = source.get_row_group(i - 1).unwrap();
+      assert_eq!(
+        row_group_reader.get_column_chunk_bytes(0).unwrap(),
+        source_reader.get_column_chunk_bytes(0).unwrap()
+      );
+      let filter = row_group_reader.get_column_bloom_filter(0).unwrap().unwrap();
+      assert!(expected[i].iter().all(|value| filter.check(value)));
+      assert_eq!(
+        row_group_reader.get_column_index(0).unwrap(),
+        source_reader.get_column_index(0).unwrap()
+      );
+      let offset_index = row_group_reader.get_offset_index(0).unwrap().unwrap();
+      let locations = offset_index.page_locations();
+      assert_eq!(locations.len(), if i == 1 { 3 } else { 2 });
+      assert_eq!(locations[0].offset, column.data_page_offset());
+    }
+  }
+
+  #[test]
+  fn test_file_writer_audit() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_audit_enabled(true)
+        .with_data_page_row_count_limit(2)
+        .with_bloom_filter_enabled(true)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![vec![1, 2, 3, 4, 5], vec![], vec![6, 7, 8]];
+    let source = test_file_roundtrip_with_props(
+      "test_file_writer_audit.parquet",
+      data,
+      props.clone()
+    );
+
+    // Appended row groups are audited as well
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(1), props).unwrap();
+    for i in 0..source.num_row_groups() {
+      let row_group_reader = source.get_row_group(i).unwrap();
+      file_writer.append_row_group(row_group_reader.as_ref()).unwrap();
+    }
+    file_writer.close().unwrap();
+  }
+
+  #[test]
+  fn test_file_writer_append_row_group_incompatible_schema() {
+    let source = test_file_roundtrip_with_props(
+      "test_file_writer_append_incompatible_source.parquet",
+      vec![vec![1, 2, 3]],
+      Rc::new(WriterProperties::builder().build())
+    );
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap();
+    let row_group_reader = source.get_row_group(0).unwrap();
+    match file_writer.append_row_group(row_group_reader.as_ref()) {
+      Err(err) => {
+        assert_eq!(err.description(), "Incompatible schema, expected 2 columns, found 1")
+      },
+      Ok(_) => panic!("Expected error for incompatible schema")
+    }
+  }
+
+  #[test]
+  fn test_file_writer_chunk_dedup() {
+    // First column is constant, second column differs in every row group
+    let data = vec![(vec![7, 7, 7], vec![1, 2, 3]), (vec![7, 7, 7], vec![4, 5, 6])];
+    let write = |dedup_enabled: bool| {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_chunk_dedup_enabled(dedup_enabled)
+          .with_page_index_enabled(true)
+          .with_audit_enabled(true)
+          .build()
+      );
+      let mut file_writer =
+        SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap();
+      for &(ref constant, ref values) in &data {
+        let mut row_group_writer = file_writer.next_buffered_row_group().unwrap();
+        for (i, column_values) in vec![constant, values].into_iter().enumerate() {
+          let col_writer = row_group_writer.column(i).unwrap();
+          let typed = get_typed_column_writer_mut::<Int32Type>(col_writer);
+          typed.write_batch(&column_values[..], None, None).unwrap();
+        }
+        file_writer.close_buffered_row_group(row_group_writer).unwrap();
+      }
+      let metadata = file_writer.close().unwrap();
+      (metadata, file_writer.into_inner().unwrap())
+    };
+
+    let (metadata, buf) = write(true);
+    let (_, expected_buf) = write(false);
+    let shared_chunk_size = metadata.row_group(0).column(0).compressed_size() as usize;
+    assert!(buf.len() + shared_chunk_size <= expected_buf.len());
+
+    // Constant column chunk references pages of the first row group
+    let first = metadata.row_group(0).column(0);
+    let second = metadata.row_group(1).column(0);
+    assert_eq!(second.data_page_offset(), first.data_page_offset());
+    assert_eq!(second.compressed_size(), first.compressed_size());
+    assert!(second.file_offset() > first.file_offset());
+    assert!(
+      metadata.row_group(1).column(1).data_page_offset() >
+        metadata.row_group(0).column(1).data_page_offset()
+    );
+
+    let path = get_temp_filename("test_file_writer_chunk_dedup.parquet");
+    fs::File::create(&path).unwrap().write_all(&buf).unwrap();
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    for (i, &(ref constant, ref values)) in data.iter().enumerate() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      for (j, expected) in vec![constant, values].into_iter().enumerate() {
+        let mut column_reader = get_typed_column_reader::<Int32Type>(
+          row_group_reader.get_column_reader(j).unwrap());
+        let mut res = vec![0; 3];
+        let (values_read, _) =
+          column_reader.read_batch(3, None, None, &mut res).unwrap();
+        assert_eq!(values_read, 3);
+        assert_eq!(&res, expected);
+      }
+    }
+  }
+
+  #[test]
+  fn test_file_writer_buffered_row_group_errors() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(2), props).unwrap();
+    let mut row_group_writer = file_writer.next_buffered_row_group().unwrap();
+    match row_group_writer.column(2) {
+      Err(err) => assert_eq!(
+        err.description(),
+        "Column index 2 out of bounds, row group has 2 columns"
+      ),
+      Ok(_) => panic!("Expected error for column index out of bounds")
+    }
+
+    for (i, values) in vec![vec![1, 2], vec![3]].iter().enumerate() {
+      let col_writer = row_group_writer.column(i).unwrap();
+      let typed = get_typed_column_writer_mut::<Int32Type>(col_writer);
+      typed.write_batch(&values[..], None, None).unwrap();
+    }
+    match file_writer.close_buffered_row_group(row_group_writer) {
+      Err(err) => {
+        assert_eq!(err.description(), "Incorrect number of rows, expected 2 != 1 rows")
+      },
+      Ok(_) => panic!("Expected error for columns with different number of rows")
+    }
+  }
+
+  #[test]
+  fn test_file_writer_in_memory() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_page_checksum_enabled(true)
+        .with_bloom_filter_enabled(true)
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let data = vec![vec![1, 2, 3, 4, 5], vec![6, 7, 8]];
+    let file_name = "test_file_writer_in_memory.parquet";
+    test_file_roundtrip_with_props(file_name, data.clone(), props.clone());
+
+    let mut file_writer =
+      SerializedFileWriter::new(Vec::new(), get_test_schema(1), props).unwrap();
+    for subset in &data {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      let col_writer = row_group_writer.next_column().unwrap().unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&subset[..], None, None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+    let buf = file_writer.into_inner().unwrap();
+
+    // Bytes written into memory match bytes of the file written with the same data
+    let mut file_buf = Vec::new();
+    fs::File::open(get_temp_filename(file_name)).unwrap()
+      .read_to_end(&mut file_buf).unwrap();
+    assert_eq!(buf, file_buf);
+  }
+
+  #[test]
+  fn test_file_writer_into_inner_not_closed() {
+    let schema = Rc::new(types::Type::group_type_builder("schema").build().unwrap());
+    let props = Rc::new(WriterProperties::builder().build());
+    let writer = SerializedFileWriter::new(Vec::new(), schema, props).unwrap();
+    match writer.into_inner() {
+      Err(err) => assert_eq!(err.description(), "File writer is not closed"),
+      Ok(_) => panic!("Expected error for file writer that is not closed")
+    }
+  }
+
+  #[test]
+  fn test_file_sink_pos() {
+    let mut sink = FileSink::new(Vec::new());
+    let mut other = sink.clone();
+    sink.write_all(&[1, 2, 3]).unwrap();
+    other.write_all(&[4, 5]).unwrap();
+    assert_eq!(sink.pos(), 5);
+    assert_eq!(other.pos(), 5);
+    assert!(sink.into_inner().is_err());
+    assert_eq!(other.into_inner().unwrap(), vec![1, 2, 3, 4, 5]);
+  }
+
+  #[test]
+  fn test_file_writer_compression_dictionary() {
+    let path = get_temp_filename("test_file_writer_compression_dictionary.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+    let dictionary: Vec<u8> = (0..100).map(|i| (i % 10) as u8).collect();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_compression(Compression::ZSTD)
+        .with_compression_dictionary(dictionary.clone())
+        .with_dictionary_enabled(false)
+        .build()
+    );
+    let mut file_writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    let col_writer = row_group_writer.next_column().unwrap().unwrap();
+    let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+    typed.write_batch(&[1, 2, 3, 4, 5], None, None).unwrap();
+    row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+    file_writer.close_row_group(row_group_writer).unwrap();
+    file_writer.close().unwrap();
+
+    // File can be read with the same dictionary
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap())
+      .unwrap()
+      .with_compression_dictionary(dictionary);
+    let col_reader = reader.get_row_group(0).unwrap().get_column_reader(0).unwrap();
+    let mut typed = get_typed_column_reader::<Int32Type>(col_reader);
+    let mut values = vec![0; 5];
+    let (values_read, _) = typed.read_batch(5, None, None, &mut values).unwrap();
+    assert_eq!(values_read, 5);
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+
+    // Pages cannot be decompressed without dictionary
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let mut page_reader =
+      reader.get_row_group(0).unwrap().get_column_page_reader(0).unwrap();
+    assert!(page_reader.get_next_page().is_err());
+  }
+
+  #[test]
+  fn test_file_writer_uncompressed_dictionary_page() {
+    let codecs = vec![Compression::SNAPPY, Compression::GZIP, Compression::BROTLI];
+    for codec in codecs {
+      let props = Rc::new(
+        WriterProperties::builder()
+          .with_compression(codec)
+          .with_dictionary_page_compression_enabled(false)
+          .build()
+      );
+      let data = vec![(0..1000).map(|i| i % 10).collect(), vec![1, 2, 3]];
+      let file_name = format!("test_file_writer_uncompressed_dict_{}.parquet", codec);
+      let reader = test_file_roundtrip_with_props(&file_name, data, props);
+      let row_group_metadata = reader.metadata().row_group(0);
+      assert_eq!(row_group_metadata.column(0).compression(), codec);
+    }
+  }
+
+  #[test]
+  fn test_file_writer_statistics() {
+    let props = Rc::new(WriterProperties::builder().build());
+    let data = vec![vec![3, 1, 2], vec![10, -5]];
+    let reader =
+      test_file_roundtrip_with_props("test_file_writer_statistics.parquet", data, props);
+    let expected = vec![
+      Statistics::int32(Some(1), Some(3), None, 0, false),
+      Statistics::int32(Some(-5), Some(10), None, 0, false)
+    ];
+    for i in 0..reader.num_row_groups() {
+      let row_group_metadata = reader.metadata().row_group(i);
+      assert_eq!(row_group_metadata.column(0).statistics(), Some(&expected[i]));
+    }
+  }
+
+  #[test]
+  fn test_file_writer_column_encoding() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_column_dictionary_enabled(ColumnPath::from("col1"), false)
+        .with_column_encoding(ColumnPath::from("col1"), Encoding::DELTA_BINARY_PACKED)
+        .build()
+    );
+    let data = vec![(0..1000).collect(), vec![5, 4, 3, 2, 1]];
+    let reader = test_file_roundtrip_with_props(
+      "test_file_writer_column_encoding.parquet", data, props);
+    let row_group_metadata = reader.metadata().row_group(0);
+    assert_eq!(
+      row_group_metadata.column(0).encodings(),
+      &vec![Encoding::DELTA_BINARY_PACKED]
+    );
+  }
+
+  #[test]
+  fn test_file_writer_column_compression() {
+    let path = get_temp_filename("test_file_writer_column_compression.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_compression(Compression::GZIP)
+        .with_column_compression(ColumnPath::from("col1"), Compression::SNAPPY)
+        .with_column_compression(ColumnPath::from("col3"), Compression::UNCOMPRESSED)
+        .build()
+    );
+    let mut file_writer =
+      SerializedFileWriter::new(file, get_test_schema(3), props).unwrap();
+    for _ in 0..2 {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      for i in 0..3 {
+        let col_writer = row_group_writer.next_column().unwrap().unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+        let values: Vec<i32> = (0..100).map(|v| v * (i + 1)).collect();
+        typed.write_batch(&values, None, None).unwrap();
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      }
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+
+    // Codec is resolved per column chunk on read
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let expected =
+      vec![Compression::SNAPPY, Compression::GZIP, Compression::UNCOMPRESSED];
+    assert_eq!(reader.metadata().compressions(), expected);
+    for row_group_metadata in reader.metadata().row_groups() {
+      assert_eq!(row_group_metadata.compressions(), expected);
+      for i in 0..3 {
+        assert_eq!(row_group_metadata.column(i).compression(), expected[i]);
+      }
+    }
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows.len(), 200);
+    assert_eq!(
+      rows[99],
+      Row::Group(vec![
+        ("col1".to_string(), Row::Int(99)),
+        ("col2".to_string(), Row::Int(198)),
+        ("col3".to_string(), Row::Int(297))
+      ])
+    );
+  }
+
+  #[test]
+  fn test_file_writer_unsupported_column_compression() {
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_column_compression(ColumnPath::from("col2"), Compression::LZO)
+        .build()
+    );
+    let result = SerializedFileWriter::new(Vec::new(), get_test_schema(2), props);
+    assert_eq!(
+      result.err().unwrap(),
+      general_err!("Compression codec LZO of column col2 is not supported")
+    );
+  }
+
+  #[test]
+  fn test_file_writer_close_metadata() {
+    let path = get_temp_filename("test_file_writer_close_metadata.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let props = Rc::new(
+      WriterProperties::builder()
+        .with_key_value_metadata(vec![KeyValue::new("a".to_owned(), None)])
+        .with_page_index_enabled(true)
+        .build()
+    );
+    let mut file_writer =
+      SerializedFileWriter::new(file, get_test_schema(2), props).unwrap();
+    for num_rows in vec![10, 5] {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      for _ in 0..2 {
+        let col_writer = row_group_writer.next_column().unwrap().unwrap();
+        let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+        let values: Vec<i32> = (0..num_rows).collect();
+        typed.write_batch(&values, None, None).unwrap();
+        row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      }
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    let metadata = file_writer.close().unwrap();
+
+    // Returned metadata matches metadata read from the file
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let expected = reader.metadata();
+    let file_metadata = metadata.file_metadata();
+    assert_eq!(file_metadata.num_rows(), 15);
+    assert_eq!(file_metadata.version(), expected.file_metadata().version());
+    assert_eq!(file_metadata.created_by(), expected.file_metadata().created_by());
+    assert_eq!(
+      file_metadata.key_value_metadata(),
+      expected.file_metadata().key_value_metadata()
+    );
+    assert_eq!(file_metadata.schema(), expected.file_metadata().schema());
+    assert_eq!(metadata.num_row_groups(), 2);
+    for i in 0..2 {
+      let row_group = metadata.row_group(i);
+      assert_eq!(row_group.to_thrift(), expected.row_group(i).to_thrift());
+      assert!(row_group.column(1).data_page_offset() > 0);
+      assert!(row_group.column(1).column_index_offset().is_some());
+    }
+  }
+
+  /// Returns schema with `num_columns` required INT32 columns.
+  fn get_test_schema(num_columns: usize) -> TypePtr {
+    let mut fields = (0..num_columns).map(|i| {
+      Rc::new(types::Type::primitive_type_builder(&format!("col{}", i + 1), Type::INT32)
+        .with_repetition(Repetition::REQUIRED)
+        .build().unwrap())
+    }).collect();
+    Rc::new(
+      types::Type::group_type_builder("schema")
+        .with_fields(&mut fields)
+        .build().unwrap()
+    )
+  }
+
+  /// Writes one required INT32 column with a row group per vector in `data`, reads the
+  /// file back and compares values.
+  fn test_file_roundtrip(file_name: &str, data: Vec<Vec<i32>>) {
+    let props = Rc::new(WriterProperties::builder().build());
+    test_file_roundtrip_with_props(file_name, data, props);
+  }
+
+  /// Same as `test_file_roundtrip`, but writes file with writer properties `props`.
+  /// Returns reader for the written file.
+  fn test_file_roundtrip_with_props(
+    file_name: &str,
+    data: Vec<Vec<i32>>,
+    props: WriterPropertiesPtr
+  ) -> SerializedFileReader {
+    let path = get_temp_filename(file_name);
+    let file = fs::File::create(&path).unwrap();
+    let schema = get_test_schema(1);
+
+    let mut file_writer = SerializedFileWriter::new(file, schema, props).unwrap();
+    for subset in &data {
+      let mut row_group_writer = file_writer.next_row_group().unwrap();
+      let col_writer = row_group_writer.next_column().unwrap().unwrap();
+      let mut typed = get_typed_column_writer::<Int32Type>(col_writer);
+      typed.write_batch(&subset[..], None, None).unwrap();
+      row_group_writer.close_column(ColumnWriter::Int32ColumnWriter(typed)).unwrap();
+      assert!(row_group_writer.next_column().unwrap().is_none());
+      file_writer.close_row_group(row_group_writer).unwrap();
+    }
+    file_writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), data.len());
+    let total_rows: usize = data.iter().map(|v| v.len()).sum();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), total_rows as i64);
+
+    for i in 0..reader.num_row_groups() {
+      let row_group_reader = reader.get_row_group(i).unwrap();
+      assert_eq!(row_group_reader.metadata().num_rows(), data[i].len() as i64);
+      let col_reader = row_group_reader.get_column_reader(0).unwrap();
+      let mut typed = get_typed_column_reader::<Int32Type>(col_reader);
+      let mut values = vec![0; data[i].len() + 1];
+      let (values_read, _) = typed.read_batch(values.len(), None, None, &mut values)
+        .unwrap();
+      assert_eq!(&values[..values_read], &data[i][..]);
+    }
+
+    let rows: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(rows.len(), total_rows);
+    reader
+  }
+}
@@ -103,6 +103,14 @@
 //! group readers [`RowGroupReader`](`file::reader::RowGroupReader`) to get access to
 //! column readers and record iterator.
 //!
+//! File writer [`SerializedFileWriter`](`file::writer::SerializedFileWriter`) writes
+//! Parquet files row group by row group, using column writers from the [`column`]
+//! module to write values of each column chunk. Compression, encodings and other
+//! settings of the writer are configured with
+//! [`WriterProperties`](`file::properties::WriterProperties`). Records can be written
+//! with [`RowWriter`](`record::writer::RowWriter`), which shreds
+//! [`Row`](`record::api::Row`)s into values of each column.
+//!
 //! # Read API
 //!
 //! Crate offers several methods to read data from a Parquet file:
@@ -128,14 +136,19 @@ extern crate arena;
 extern crate snap;
 extern crate brotli;
 extern crate flate2;
+extern crate zstd;
+extern crate crc;
 extern crate rand;
 extern crate x86intrin;
 extern crate parquet_format;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 #[macro_use]
 pub mod errors;
 pub mod basic;
 pub mod data_type;
+pub mod logical;
 
 // Exported for external use, such as benchmarks
 pub use util::memory;
@@ -146,7 +159,9 @@ pub use encodings::decoding;
 mod util;
 mod encodings;
 pub mod compression;
+pub mod bloom_filter;
 pub mod column;
 pub mod record;
 pub mod schema;
 pub mod file;
+pub mod bench;
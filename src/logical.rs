@@ -0,0 +1,738 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains conversion between physical values, i.e. values of Parquet physical types,
+//! and logical values, i.e. values interpreted according to the logical type of a
+//! column.
+//!
+//! Semantics of logical types are defined here once and shared by the record API,
+//! min/max values of statistics, filter literals and command line tools:
+//! - INT32 values are converted based on `INT_8`, `INT_16` and `INT_32` annotations,
+//! `DATE` and `TIME_MILLIS` values are kept as number of days since Unix epoch and
+//! milliseconds since midnight.
+//! - INT64 values annotated as `TIMESTAMP_MILLIS` and INT96 values are converted into
+//! timestamps in milliseconds since Unix epoch, `TIME_MICROS` and `TIMESTAMP_MICROS`
+//! values are kept as microseconds.
+//! - BYTE_ARRAY values annotated as `UTF8`, `ENUM` or `JSON` are converted into
+//! strings, unless they are not valid UTF-8.
+//! - INT32, INT64, BYTE_ARRAY and FIXED_LEN_BYTE_ARRAY values annotated as `DECIMAL`
+//! are converted into [`Decimal`](`::data_type::Decimal`) values with precision and
+//! scale of the column.
+//!
+//! Unsigned integers and `INTERVAL` values are not supported yet.
+//!
+//! Timestamps of INT96, `TIMESTAMP_MILLIS` and `TIMESTAMP_MICROS` columns can also be
+//! normalized into a single [`TimeUnit`](`TimeUnit`) with overflow checks.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//!
+//! use parquet::data_type::Decimal;
+//! use parquet::logical::{LogicalValue, PhysicalValue};
+//! use parquet::schema::types::{ColumnDescriptor, ColumnPath, Type};
+//!
+//! let tpe = Type::decimal_type_builder("price", 9, 2).build().unwrap();
+//! let descr =
+//!   ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("price"));
+//!
+//! let value = 150i32.to_logical(&descr).unwrap();
+//! assert_eq!(value, LogicalValue::Decimal(Decimal::new(150, 9, 2)));
+//! assert_eq!(value.to_string(), "1.50");
+//! assert_eq!(i32::from_logical(&value, &descr).unwrap(), 150);
+//! ```
+
+use std::fmt;
+
+use basic::{LogicalType, Type as PhysicalType};
+use data_type::{ByteArray, Decimal, Int96};
+use errors::{ParquetError, Result};
+use schema::types::ColumnDescriptor;
+
+/// Value of a primitive column, interpreted according to the logical type of the
+/// column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LogicalValue {
+  Bool(bool),
+  Byte(i8),
+  Short(i16),
+  Int(i32),
+  Long(i64),
+  Float(f32),
+  Double(f64),
+  Str(String),
+  Bytes(ByteArray),
+  Timestamp(i64), // Timestamp with milliseconds since Unix epoch
+  Decimal(Decimal)
+}
+
+impl LogicalValue {
+  /// Converts BOOLEAN value into logical value.
+  pub fn from_bool(_logical_type: LogicalType, value: bool) -> Result<Self> {
+    Ok(LogicalValue::Bool(value))
+  }
+
+  /// Converts INT32 value with logical type, other than `DECIMAL`, into logical value.
+  pub fn from_int32(logical_type: LogicalType, value: i32) -> Result<Self> {
+    match logical_type {
+      LogicalType::INT_8 => Ok(LogicalValue::Byte(value as i8)),
+      LogicalType::INT_16 => Ok(LogicalValue::Short(value as i16)),
+      LogicalType::INT_32 | LogicalType::DATE | LogicalType::TIME_MILLIS |
+      LogicalType::NONE => Ok(LogicalValue::Int(value)),
+      _ => Err(unsupported_err(PhysicalType::INT32, logical_type))
+    }
+  }
+
+  /// Converts INT64 value with logical type, other than `DECIMAL`, into logical value.
+  pub fn from_int64(logical_type: LogicalType, value: i64) -> Result<Self> {
+    match logical_type {
+      LogicalType::TIMESTAMP_MILLIS => Ok(LogicalValue::Timestamp(value)),
+      LogicalType::INT_64 | LogicalType::TIME_MICROS | LogicalType::TIMESTAMP_MICROS |
+      LogicalType::NONE => Ok(LogicalValue::Long(value)),
+      _ => Err(unsupported_err(PhysicalType::INT64, logical_type))
+    }
+  }
+
+  /// Converts INT96 value, i.e. timestamp with nanoseconds, into timestamp with
+  /// milliseconds, rounded down.
+  pub fn from_int96(_logical_type: LogicalType, value: &Int96) -> Result<Self> {
+    Ok(LogicalValue::Timestamp(value.to_millis()))
+  }
+
+  /// Converts FLOAT value into logical value.
+  pub fn from_float(_logical_type: LogicalType, value: f32) -> Result<Self> {
+    Ok(LogicalValue::Float(value))
+  }
+
+  /// Converts DOUBLE value into logical value.
+  pub fn from_double(_logical_type: LogicalType, value: f64) -> Result<Self> {
+    Ok(LogicalValue::Double(value))
+  }
+
+  /// Converts BYTE_ARRAY or FIXED_LEN_BYTE_ARRAY value with logical type, other than
+  /// `DECIMAL`, into either UTF-8 string or array of bytes.
+  pub fn from_byte_array(
+    physical_type: PhysicalType,
+    logical_type: LogicalType,
+    value: ByteArray
+  ) -> Result<Self> {
+    match (physical_type, logical_type) {
+      (PhysicalType::BYTE_ARRAY, LogicalType::UTF8) |
+      (PhysicalType::BYTE_ARRAY, LogicalType::ENUM) |
+      (PhysicalType::BYTE_ARRAY, LogicalType::JSON) => {
+        // Annotation might be assumed by writer workarounds, therefore keep bytes
+        // that are not valid UTF8 instead of converting them into string
+        match String::from_utf8(value.data().to_vec()) {
+          Ok(string) => Ok(LogicalValue::Str(string)),
+          Err(_) => Ok(LogicalValue::Bytes(value))
+        }
+      },
+      (PhysicalType::BYTE_ARRAY, LogicalType::BSON) |
+      (PhysicalType::BYTE_ARRAY, LogicalType::NONE) |
+      (PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE) => {
+        Ok(LogicalValue::Bytes(value))
+      },
+      _ => Err(unsupported_err(physical_type, logical_type))
+    }
+  }
+}
+
+impl fmt::Display for LogicalValue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      LogicalValue::Bool(value) => write!(f, "{}", value),
+      LogicalValue::Byte(value) => write!(f, "{}", value),
+      LogicalValue::Short(value) => write!(f, "{}", value),
+      LogicalValue::Int(value) => write!(f, "{}", value),
+      LogicalValue::Long(value) => write!(f, "{}", value),
+      LogicalValue::Float(value) => write!(f, "{:?}", value),
+      LogicalValue::Double(value) => write!(f, "{:?}", value),
+      LogicalValue::Str(ref value) => write!(f, "\"{}\"", value),
+      LogicalValue::Bytes(ref value) => write!(f, "{:?}", value.data()),
+      LogicalValue::Timestamp(value) => write!(f, "{}", value),
+      LogicalValue::Decimal(ref value) => write!(f, "{}", value)
+    }
+  }
+}
+
+/// Unit of timestamp values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+  Millis,
+  Micros,
+  Nanos
+}
+
+impl TimeUnit {
+  /// Returns unit of timestamp values of the physical and logical type, i.e.
+  /// nanoseconds for INT96 values and milliseconds or microseconds for INT64 values
+  /// annotated as `TIMESTAMP_MILLIS` or `TIMESTAMP_MICROS`, or `None` if values of the
+  /// type are not timestamps.
+  pub fn from_type(
+    physical_type: PhysicalType,
+    logical_type: LogicalType
+  ) -> Option<Self> {
+    match (physical_type, logical_type) {
+      (PhysicalType::INT96, _) => Some(TimeUnit::Nanos),
+      (PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS) => Some(TimeUnit::Millis),
+      (PhysicalType::INT64, LogicalType::TIMESTAMP_MICROS) => Some(TimeUnit::Micros),
+      _ => None
+    }
+  }
+
+  /// Returns the number of units in a second.
+  #[inline]
+  pub fn per_second(&self) -> i64 {
+    match *self {
+      TimeUnit::Millis => 1_000,
+      TimeUnit::Micros => 1_000_000,
+      TimeUnit::Nanos => 1_000_000_000
+    }
+  }
+
+  /// Converts timestamp `value` in this unit into timestamp in `unit`. Values are
+  /// rounded down when converted into a coarser unit.
+  ///
+  /// Returns an error if the converted value does not fit into `i64`.
+  pub fn convert(&self, value: i64, unit: TimeUnit) -> Result<i64> {
+    let (from, to) = (self.per_second(), unit.per_second());
+    if from >= to {
+      let factor = from / to;
+      let result = value / factor;
+      return Ok(if value % factor < 0 { result - 1 } else { result });
+    }
+    value.checked_mul(to / from).ok_or_else(|| general_err!(
+      "Timestamp {} in {} overflows when converted into {}",
+      value,
+      self,
+      unit
+    ))
+  }
+
+  /// Converts timestamp `value` in this unit into timestamp in `unit` the same way as
+  /// [`convert`](`TimeUnit::convert`), but values that do not fit into `i64` saturate
+  /// at `i64::MIN` or `i64::MAX`.
+  pub fn saturating_convert(&self, value: i64, unit: TimeUnit) -> i64 {
+    self.convert(value, unit).unwrap_or_else(|_| saturated(value < 0))
+  }
+
+  /// Converts INT96 timestamp, i.e. Julian day and nanoseconds of the day, into
+  /// timestamp in `unit` since Unix epoch.
+  ///
+  /// Returns an error if the converted value does not fit into `i64`.
+  pub fn from_int96(value: &Int96, unit: TimeUnit) -> Result<i64> {
+    let seconds_in_a_day: i64 = 86_400;
+
+    let data = value.data();
+    let days = data[2] as i64 - JULIAN_DAY_OF_UNIX_EPOCH;
+    let nanoseconds = ((data[1] as i64) << 32) + data[0] as i64;
+    let time = TimeUnit::Nanos.convert(nanoseconds, unit)?;
+    days
+      .checked_mul(seconds_in_a_day * unit.per_second())
+      .and_then(|days| days.checked_add(time))
+      .ok_or_else(|| general_err!(
+        "INT96 timestamp of Julian day {} overflows when converted into {}",
+        data[2],
+        unit
+      ))
+  }
+
+  /// Converts INT96 timestamp into timestamp in `unit` the same way as
+  /// [`from_int96`](`TimeUnit::from_int96`), but values that do not fit into `i64`
+  /// saturate at `i64::MIN` or `i64::MAX`.
+  pub fn saturating_from_int96(value: &Int96, unit: TimeUnit) -> i64 {
+    TimeUnit::from_int96(value, unit)
+      .unwrap_or_else(|_| saturated((value.data()[2] as i64) < JULIAN_DAY_OF_UNIX_EPOCH))
+  }
+}
+
+/// Julian day of Unix epoch, i.e. 1970-01-01.
+const JULIAN_DAY_OF_UNIX_EPOCH: i64 = 2_440_588;
+
+/// Returns the timestamp a value saturates at when it overflows `i64`.
+#[inline]
+fn saturated(is_negative: bool) -> i64 {
+  if is_negative { i64::min_value() } else { i64::max_value() }
+}
+
+impl fmt::Display for TimeUnit {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      TimeUnit::Millis => write!(f, "milliseconds"),
+      TimeUnit::Micros => write!(f, "microseconds"),
+      TimeUnit::Nanos => write!(f, "nanoseconds")
+    }
+  }
+}
+
+/// Physical value that can be converted into a logical value of a column and back.
+///
+/// Implemented for Rust representations of all Parquet physical types, i.e. for
+/// `T::T` of every [`DataType`](`::data_type::DataType`).
+pub trait PhysicalValue: Sized {
+  /// Converts this value of column `descr` into logical value.
+  ///
+  /// Returns error if logical type of the column is not supported.
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue>;
+
+  /// Converts logical value into value of column `descr`.
+  ///
+  /// Returns error if logical value cannot be stored in the column, e.g. string in an
+  /// INT32 column or DECIMAL value with a different scale.
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self>;
+}
+
+impl PhysicalValue for bool {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    LogicalValue::from_bool(descr.logical_type(), *self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Bool(v) => Ok(v),
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for i32 {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    if descr.logical_type() == LogicalType::DECIMAL {
+      return Ok(to_decimal(*self as i128, descr));
+    }
+    LogicalValue::from_int32(descr.logical_type(), *self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Byte(v) => Ok(v as i32),
+      LogicalValue::Short(v) => Ok(v as i32),
+      LogicalValue::Int(v) => Ok(v),
+      LogicalValue::Decimal(ref v) => match decimal_unscaled(v, descr) {
+        Some(unscaled) if unscaled >= i32::min_value() as i128 &&
+            unscaled <= i32::max_value() as i128 => Ok(unscaled as i32),
+        _ => Err(conversion_err(value, descr))
+      },
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for i64 {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    if descr.logical_type() == LogicalType::DECIMAL {
+      return Ok(to_decimal(*self as i128, descr));
+    }
+    LogicalValue::from_int64(descr.logical_type(), *self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Long(v) => Ok(v),
+      LogicalValue::Timestamp(v)
+        if descr.logical_type() == LogicalType::TIMESTAMP_MILLIS => Ok(v),
+      LogicalValue::Decimal(ref v) => match decimal_unscaled(v, descr) {
+        Some(unscaled) if unscaled >= i64::min_value() as i128 &&
+            unscaled <= i64::max_value() as i128 => Ok(unscaled as i64),
+        _ => Err(conversion_err(value, descr))
+      },
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for Int96 {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    LogicalValue::from_int96(descr.logical_type(), self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Timestamp(v) => Ok(Int96::from_millis(v)),
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for f32 {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    LogicalValue::from_float(descr.logical_type(), *self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Float(v) => Ok(v),
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for f64 {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    LogicalValue::from_double(descr.logical_type(), *self)
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    match *value {
+      LogicalValue::Double(v) => Ok(v),
+      _ => Err(conversion_err(value, descr))
+    }
+  }
+}
+
+impl PhysicalValue for ByteArray {
+  fn to_logical(&self, descr: &ColumnDescriptor) -> Result<LogicalValue> {
+    if descr.logical_type() == LogicalType::DECIMAL {
+      return match self.to_decimal() {
+        Some(unscaled) => Ok(to_decimal(unscaled, descr)),
+        None => Err(general_err!(
+          "Invalid DECIMAL value of {} bytes in column {}",
+          self.len(),
+          descr.path()
+        ))
+      };
+    }
+    LogicalValue::from_byte_array(
+      descr.physical_type(),
+      descr.logical_type(),
+      self.clone()
+    )
+  }
+
+  fn from_logical(value: &LogicalValue, descr: &ColumnDescriptor) -> Result<Self> {
+    let is_fixed_len = descr.physical_type() == PhysicalType::FIXED_LEN_BYTE_ARRAY;
+    let type_length = descr.type_length() as usize;
+    let converted = match *value {
+      LogicalValue::Str(ref v) if !is_fixed_len => Some(ByteArray::from(v.as_str())),
+      LogicalValue::Bytes(ref v) if !is_fixed_len || v.len() == type_length => {
+        Some(v.clone())
+      },
+      LogicalValue::Decimal(ref v) if is_fixed_len => {
+        decimal_unscaled(v, descr)
+          .and_then(|unscaled| ByteArray::from_decimal(unscaled, type_length))
+      },
+      LogicalValue::Decimal(ref v) => {
+        // Values of BYTE_ARRAY columns are stored in the minimal number of bytes
+        decimal_unscaled(v, descr).and_then(|unscaled| {
+          (1..17).filter_map(|length| ByteArray::from_decimal(unscaled, length)).next()
+        })
+      },
+      _ => None
+    };
+    converted.ok_or_else(|| conversion_err(value, descr))
+  }
+}
+
+/// Returns DECIMAL value with precision and scale of column `descr`.
+#[inline]
+fn to_decimal(unscaled: i128, descr: &ColumnDescriptor) -> LogicalValue {
+  let decimal = Decimal::new(unscaled, descr.type_precision(), descr.type_scale());
+  LogicalValue::Decimal(decimal)
+}
+
+/// Returns unscaled value of DECIMAL value, if column `descr` is annotated as DECIMAL
+/// with the same scale and value fits into precision of the column.
+fn decimal_unscaled(value: &Decimal, descr: &ColumnDescriptor) -> Option<i128> {
+  if descr.logical_type() != LogicalType::DECIMAL || value.scale() != descr.type_scale() {
+    return None;
+  }
+  let unscaled = value.unscaled();
+  if let Some(bound) = decimal_bound(descr.type_precision()) {
+    if unscaled <= -bound || unscaled >= bound {
+      return None;
+    }
+  }
+  Some(unscaled)
+}
+
+/// Returns exclusive bound of absolute values of unscaled DECIMAL values of
+/// `precision` digits, i.e. `10^precision`, or `None` if any `i128` value fits.
+///
+/// `i128` values have at most 39 digits, so every value fits into precision of 39 or
+/// more digits, for which the bound itself would overflow `i128`.
+#[inline]
+pub(crate) fn decimal_bound(precision: i32) -> Option<i128> {
+  if precision >= 0 && precision <= 38 {
+    Some(10i128.pow(precision as u32))
+  } else {
+    None
+  }
+}
+
+/// Returns error for logical type that is not supported for the physical type.
+fn unsupported_err(
+  physical_type: PhysicalType,
+  logical_type: LogicalType
+) -> ParquetError {
+  nyi_err!(
+    "Conversion for physical type {}, logical type {} is not supported",
+    physical_type,
+    logical_type
+  )
+}
+
+/// Returns error for logical value that cannot be stored in column `descr`.
+fn conversion_err(value: &LogicalValue, descr: &ColumnDescriptor) -> ParquetError {
+  general_err!(
+    "Cannot convert {} into column {} of type {}",
+    value,
+    descr.path(),
+    descr.physical_type()
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::rc::Rc;
+
+  use basic::Repetition;
+  use schema::types::{ColumnPath, Type};
+
+  #[test]
+  fn test_decimal_bound() {
+    assert_eq!(decimal_bound(0), Some(1));
+    assert_eq!(decimal_bound(5), Some(100000));
+    assert_eq!(decimal_bound(38), Some(10i128.pow(38)));
+    assert_eq!(decimal_bound(39), None);
+  }
+
+  #[test]
+  fn test_logical_value_from_physical_types() {
+    assert_eq!(
+      LogicalValue::from_int32(LogicalType::INT_8, 7),
+      Ok(LogicalValue::Byte(7))
+    );
+    assert_eq!(
+      LogicalValue::from_int32(LogicalType::DATE, 10),
+      Ok(LogicalValue::Int(10))
+    );
+    assert_eq!(
+      LogicalValue::from_int32(LogicalType::UINT_32, 1),
+      Err(nyi_err!(
+        "Conversion for physical type INT32, logical type UINT_32 is not supported"
+      ))
+    );
+    assert_eq!(
+      LogicalValue::from_int64(LogicalType::TIMESTAMP_MILLIS, 1_238_544_000_000),
+      Ok(LogicalValue::Timestamp(1_238_544_000_000))
+    );
+    assert_eq!(
+      LogicalValue::from_int96(LogicalType::NONE, &Int96::from_millis(-1)),
+      Ok(LogicalValue::Timestamp(-1))
+    );
+    assert_eq!(
+      LogicalValue::from_byte_array(
+        PhysicalType::BYTE_ARRAY, LogicalType::UTF8, ByteArray::from("abc")),
+      Ok(LogicalValue::Str("abc".to_string()))
+    );
+    assert_eq!(
+      LogicalValue::from_byte_array(
+        PhysicalType::BYTE_ARRAY, LogicalType::UTF8, ByteArray::from(vec![0xFF])),
+      Ok(LogicalValue::Bytes(ByteArray::from(vec![0xFF])))
+    );
+    assert!(
+      LogicalValue::from_byte_array(
+        PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::UTF8, ByteArray::from("abc")
+      ).is_err()
+    );
+  }
+
+  #[test]
+  fn test_time_unit_convert() {
+    assert_eq!(
+      TimeUnit::from_type(PhysicalType::INT64, LogicalType::TIMESTAMP_MICROS),
+      Some(TimeUnit::Micros)
+    );
+    assert_eq!(
+      TimeUnit::from_type(PhysicalType::INT96, LogicalType::NONE),
+      Some(TimeUnit::Nanos)
+    );
+    assert_eq!(TimeUnit::from_type(PhysicalType::INT64, LogicalType::NONE), None);
+
+    assert_eq!(TimeUnit::Millis.convert(1_500, TimeUnit::Millis), Ok(1_500));
+    assert_eq!(TimeUnit::Millis.convert(1_500, TimeUnit::Nanos), Ok(1_500_000_000));
+    assert_eq!(TimeUnit::Micros.convert(1_500, TimeUnit::Millis), Ok(1));
+    assert_eq!(TimeUnit::Micros.convert(-1_500, TimeUnit::Millis), Ok(-2));
+    assert_eq!(
+      TimeUnit::Millis.convert(i64::max_value() / 10, TimeUnit::Micros),
+      Err(general_err!(
+        "Timestamp 922337203685477580 in milliseconds overflows when converted into \
+         microseconds"
+      ))
+    );
+
+    let value = Int96::from_nanos(1_238_544_060_123_456_789);
+    assert_eq!(TimeUnit::from_int96(&value, TimeUnit::Nanos), Ok(value.to_nanos()));
+    assert_eq!(
+      TimeUnit::from_int96(&value, TimeUnit::Micros),
+      Ok(1_238_544_060_123_456)
+    );
+    assert_eq!(TimeUnit::from_int96(&Int96::from_millis(-1), TimeUnit::Millis), Ok(-1));
+
+    let value = Int96::from(vec![0, 0, 4_000_000]);
+    assert_eq!(
+      TimeUnit::from_int96(&value, TimeUnit::Nanos),
+      Err(general_err!(
+        "INT96 timestamp of Julian day 4000000 overflows when converted into nanoseconds"
+      ))
+    );
+    assert!(TimeUnit::from_int96(&value, TimeUnit::Millis).is_ok());
+  }
+
+  #[test]
+  fn test_time_unit_saturating_convert() {
+    assert_eq!(
+      TimeUnit::Millis.saturating_convert(1_500, TimeUnit::Nanos),
+      1_500_000_000
+    );
+    assert_eq!(TimeUnit::Micros.saturating_convert(-1_500, TimeUnit::Millis), -2);
+    // 9999-12-31 does not fit into i64 in nanoseconds
+    let max_date = 253_402_214_400_000;
+    assert_eq!(
+      TimeUnit::Millis.saturating_convert(max_date, TimeUnit::Nanos),
+      i64::max_value()
+    );
+    assert_eq!(
+      TimeUnit::Millis.saturating_convert(-max_date, TimeUnit::Nanos),
+      i64::min_value()
+    );
+
+    let value = Int96::from_millis(-1);
+    assert_eq!(TimeUnit::saturating_from_int96(&value, TimeUnit::Millis), -1);
+    let value = Int96::from(vec![0, 0, 4_000_000]);
+    assert_eq!(
+      TimeUnit::saturating_from_int96(&value, TimeUnit::Nanos),
+      i64::max_value()
+    );
+    let value = Int96::from(vec![0, 0, 0]);
+    assert_eq!(
+      TimeUnit::saturating_from_int96(&value, TimeUnit::Nanos),
+      i64::min_value()
+    );
+    assert!(TimeUnit::saturating_from_int96(&value, TimeUnit::Millis) < 0);
+  }
+
+  #[test]
+  fn test_logical_value_display() {
+    assert_eq!(LogicalValue::Int(3).to_string(), "3");
+    assert_eq!(LogicalValue::Double(6.0).to_string(), "6.0");
+    assert_eq!(LogicalValue::Str("abc".to_string()).to_string(), "\"abc\"");
+    assert_eq!(LogicalValue::Bytes(ByteArray::from(vec![1, 2])).to_string(), "[1, 2]");
+    assert_eq!(LogicalValue::Decimal(Decimal::new(-5, 5, 3)).to_string(), "-0.005");
+  }
+
+  #[test]
+  fn test_physical_value_decimal_round_trip() {
+    for &(precision, physical_type) in &[
+      (9, PhysicalType::INT32),
+      (18, PhysicalType::INT64),
+      (20, PhysicalType::FIXED_LEN_BYTE_ARRAY)
+    ] {
+      let descr = decimal_descr(precision, 2);
+      assert_eq!(descr.physical_type(), physical_type);
+      let value = LogicalValue::Decimal(Decimal::new(-150, precision, 2));
+      let round_trip = match physical_type {
+        PhysicalType::INT32 => i32::from_logical(&value, &descr).and_then(|v| {
+          v.to_logical(&descr)
+        }),
+        PhysicalType::INT64 => i64::from_logical(&value, &descr).and_then(|v| {
+          v.to_logical(&descr)
+        }),
+        _ => ByteArray::from_logical(&value, &descr).and_then(|v| v.to_logical(&descr))
+      };
+      assert_eq!(round_trip, Ok(value));
+
+      // Scale must match the column, value must fit into precision
+      let other_scale = LogicalValue::Decimal(Decimal::new(-150, precision, 3));
+      assert!(i64::from_logical(&other_scale, &descr).is_err());
+      let too_large =
+        LogicalValue::Decimal(Decimal::new(10i128.pow(precision as u32), precision, 2));
+      assert!(ByteArray::from_logical(&too_large, &descr).is_err());
+    }
+
+    // BYTE_ARRAY values use the minimal number of bytes
+    let tpe = Type::primitive_type_builder("col", PhysicalType::BYTE_ARRAY)
+      .with_logical_type(LogicalType::DECIMAL)
+      .with_precision(5)
+      .with_scale(1)
+      .build()
+      .unwrap();
+    let descr = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col"));
+    let value = LogicalValue::Decimal(Decimal::new(-129, 5, 1));
+    let bytes = ByteArray::from_logical(&value, &descr).unwrap();
+    assert_eq!(bytes.data(), &[0xFF, 0x7F]);
+    assert_eq!(bytes.to_logical(&descr), Ok(value));
+  }
+
+  #[test]
+  fn test_physical_value_from_logical() {
+    let descr = primitive_descr(PhysicalType::INT64, LogicalType::TIMESTAMP_MILLIS, 0);
+    assert_eq!(i64::from_logical(&LogicalValue::Timestamp(5), &descr), Ok(5));
+    assert_eq!(i64::from_logical(&LogicalValue::Long(6), &descr), Ok(6));
+    let descr = primitive_descr(PhysicalType::INT64, LogicalType::NONE, 0);
+    assert_eq!(
+      i64::from_logical(&LogicalValue::Timestamp(5), &descr),
+      Err(general_err!("Cannot convert 5 into column \"col\" of type INT64"))
+    );
+
+    let descr = primitive_descr(PhysicalType::INT32, LogicalType::INT_16, 0);
+    assert_eq!(i32::from_logical(&LogicalValue::Byte(-1), &descr), Ok(-1));
+    assert_eq!(i32::from_logical(&LogicalValue::Short(2), &descr), Ok(2));
+    assert!(i32::from_logical(&LogicalValue::Long(2), &descr).is_err());
+
+    let descr = primitive_descr(PhysicalType::FIXED_LEN_BYTE_ARRAY, LogicalType::NONE, 2);
+    let bytes = ByteArray::from(vec![1, 2]);
+    assert_eq!(
+      ByteArray::from_logical(&LogicalValue::Bytes(bytes.clone()), &descr),
+      Ok(bytes.clone())
+    );
+    let short_bytes = LogicalValue::Bytes(ByteArray::from(vec![1]));
+    assert!(ByteArray::from_logical(&short_bytes, &descr).is_err());
+    let string = LogicalValue::Str("ab".to_string());
+    assert!(ByteArray::from_logical(&string, &descr).is_err());
+    assert_eq!(bytes.to_logical(&descr), Ok(LogicalValue::Bytes(bytes.clone())));
+
+    let descr = primitive_descr(PhysicalType::INT96, LogicalType::NONE, 0);
+    let value = LogicalValue::Timestamp(1_238_544_060_123);
+    let int96 = Int96::from_logical(&value, &descr).unwrap();
+    assert_eq!(int96.to_logical(&descr), Ok(value));
+  }
+
+  fn decimal_descr(precision: i32, scale: i32) -> ColumnDescriptor {
+    let tpe = Type::decimal_type_builder("col", precision, scale)
+      .with_repetition(Repetition::REQUIRED)
+      .build()
+      .unwrap();
+    ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col"))
+  }
+
+  fn primitive_descr(
+    physical_type: PhysicalType,
+    logical_type: LogicalType,
+    length: i32
+  ) -> ColumnDescriptor {
+    let tpe = Type::primitive_type_builder("col", physical_type)
+      .with_logical_type(logical_type)
+      .with_length(length)
+      .build()
+      .unwrap();
+    ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col"))
+  }
+}
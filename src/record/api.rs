@@ -20,19 +20,10 @@
 use std::fmt;
 
 use basic::{LogicalType, Type as PhysicalType};
-use data_type::{ByteArray, Int96};
-
-/// Macro as a shortcut to generate 'not yet implemented' panic error.
-macro_rules! nyi {
-  ($physical_type:ident, $logical_type:ident, $value:ident) => ({
-    unimplemented!(
-      "Conversion for physical type {}, logical type {}, value {:?}",
-      $physical_type,
-      $logical_type,
-      $value
-    );
-  });
-}
+use data_type::{ByteArray, Decimal, Int96};
+use errors::Result;
+use logical::{LogicalValue, PhysicalValue};
+use schema::types::ColumnDescriptor;
 
 /// Row API to represent a nested Parquet record.
 #[derive(Clone, Debug, PartialEq)]
@@ -48,7 +39,8 @@ pub enum Row {
   Double(f64),
   Str(String),
   Bytes(ByteArray),
-  Timestamp(u64), // Timestamp with milliseconds
+  Timestamp(u64), // Timestamp with milliseconds, unless normalized by the reader
+  Decimal(Decimal),
   // Complex types
   Group(Vec<(String, Row)>), // Struct, child elements are tuples of field-value pairs
   List(Vec<Row>), // List of elements
@@ -56,76 +48,86 @@ pub enum Row {
 }
 
 impl Row {
+  /// Converts physical value of column `descr` into row value, see
+  /// [`PhysicalValue::to_logical`](`::logical::PhysicalValue::to_logical`).
+  ///
+  /// Panics if logical type of the column is not supported.
+  pub fn from_physical<V: PhysicalValue>(descr: &ColumnDescriptor, value: &V) -> Self {
+    Self::from_conversion(value.to_logical(descr))
+  }
+
+  /// Returns logical value of a primitive row value, or `None` for null and complex
+  /// values.
+  pub fn to_logical(&self) -> Option<LogicalValue> {
+    match *self {
+      Row::Bool(value) => Some(LogicalValue::Bool(value)),
+      Row::Byte(value) => Some(LogicalValue::Byte(value)),
+      Row::Short(value) => Some(LogicalValue::Short(value)),
+      Row::Int(value) => Some(LogicalValue::Int(value)),
+      Row::Long(value) => Some(LogicalValue::Long(value)),
+      Row::Float(value) => Some(LogicalValue::Float(value)),
+      Row::Double(value) => Some(LogicalValue::Double(value)),
+      Row::Str(ref value) => Some(LogicalValue::Str(value.clone())),
+      Row::Bytes(ref value) => Some(LogicalValue::Bytes(value.clone())),
+      Row::Timestamp(value) => Some(LogicalValue::Timestamp(value as i64)),
+      Row::Decimal(value) => Some(LogicalValue::Decimal(value)),
+      Row::Null | Row::Group(_) | Row::List(_) | Row::Map(_) => None
+    }
+  }
+
   /// Converts Parquet BOOLEAN type with logical type into `bool` value.
   pub fn convert_bool(
     _physical_type: PhysicalType,
-    _logical_type: LogicalType,
+    logical_type: LogicalType,
     value: bool
   ) -> Self {
-    Row::Bool(value)
+    Self::from_conversion(LogicalValue::from_bool(logical_type, value))
   }
 
   /// Converts Parquet INT32 type with logical type into `i32` value.
   pub fn convert_int32(
-    physical_type: PhysicalType,
+    _physical_type: PhysicalType,
     logical_type: LogicalType,
     value: i32
   ) -> Self {
-    match logical_type {
-      LogicalType::INT_8 => Row::Byte(value as i8),
-      LogicalType::INT_16 => Row::Short(value as i16),
-      LogicalType::INT_32 | LogicalType::NONE => Row::Int(value),
-      _ => nyi!(physical_type, logical_type, value)
-    }
+    Self::from_conversion(LogicalValue::from_int32(logical_type, value))
   }
 
   /// Converts Parquet INT64 type with logical type into `i64` value.
   pub fn convert_int64(
-    physical_type: PhysicalType,
+    _physical_type: PhysicalType,
     logical_type: LogicalType,
     value: i64
   ) -> Self {
-    match logical_type {
-      LogicalType::INT_64 | LogicalType::NONE => Row::Long(value),
-      _ => nyi!(physical_type, logical_type, value)
-    }
+    Self::from_conversion(LogicalValue::from_int64(logical_type, value))
   }
 
   /// Converts Parquet INT96 (nanosecond timestamps) type and logical type into
   /// `Timestamp` value.
   pub fn convert_int96(
     _physical_type: PhysicalType,
-    _logical_type: LogicalType,
+    logical_type: LogicalType,
     value: Int96
   ) -> Self {
-    let julian_to_unix_epoch_days: u64 = 2_440_588;
-    let milli_seconds_in_a_day: u64 = 86_400_000;
-    let nano_seconds_in_a_day: u64 = milli_seconds_in_a_day * 1_000_000;
-
-    let days_since_epoch = value.data()[2] as u64 - julian_to_unix_epoch_days;
-    let nanoseconds: u64 = ((value.data()[1] as u64) << 32) + value.data()[0] as u64;
-    let nanos = days_since_epoch * nano_seconds_in_a_day + nanoseconds;
-    let millis = nanos / 1_000_000;
-
-    Row::Timestamp(millis)
+    Self::from_conversion(LogicalValue::from_int96(logical_type, &value))
   }
 
   /// Converts Parquet FLOAT type with logical type into `f32` value.
   pub fn convert_float(
     _physical_type: PhysicalType,
-    _logical_type: LogicalType,
+    logical_type: LogicalType,
     value: f32
   ) -> Self {
-    Row::Float(value)
+    Self::from_conversion(LogicalValue::from_float(logical_type, value))
   }
 
   /// Converts Parquet DOUBLE type with logical type into `f64` value.
   pub fn convert_double(
     _physical_type: PhysicalType,
-    _logical_type: LogicalType,
+    logical_type: LogicalType,
     value: f64
   ) -> Self {
-    Row::Double(value)
+    Self::from_conversion(LogicalValue::from_double(logical_type, value))
   }
 
   /// Converts Parquet BYTE_ARRAY type with logical type into either UTF8 string or
@@ -135,18 +137,34 @@ impl Row {
     logical_type: LogicalType,
     value: ByteArray
   ) -> Self {
-    match physical_type {
-      PhysicalType::BYTE_ARRAY => {
-        match logical_type {
-          LogicalType::UTF8 | LogicalType::ENUM | LogicalType::JSON => {
-            let value = unsafe { String::from_utf8_unchecked(value.data().to_vec()) };
-            Row::Str(value)
-          },
-          LogicalType::BSON | LogicalType::NONE => Row::Bytes(value),
-          _ => nyi!(physical_type, logical_type, value)
-        }
-      },
-      _ => nyi!(physical_type, logical_type, value)
+    Self::from_conversion(
+      LogicalValue::from_byte_array(physical_type, logical_type, value))
+  }
+
+  /// Returns row value of successful conversion, panics with the conversion error
+  /// otherwise.
+  fn from_conversion(result: Result<LogicalValue>) -> Self {
+    match result {
+      Ok(value) => Row::from(value),
+      Err(e) => panic!("{}", e)
+    }
+  }
+}
+
+impl From<LogicalValue> for Row {
+  fn from(value: LogicalValue) -> Self {
+    match value {
+      LogicalValue::Bool(value) => Row::Bool(value),
+      LogicalValue::Byte(value) => Row::Byte(value),
+      LogicalValue::Short(value) => Row::Short(value),
+      LogicalValue::Int(value) => Row::Int(value),
+      LogicalValue::Long(value) => Row::Long(value),
+      LogicalValue::Float(value) => Row::Float(value),
+      LogicalValue::Double(value) => Row::Double(value),
+      LogicalValue::Str(value) => Row::Str(value),
+      LogicalValue::Bytes(value) => Row::Bytes(value),
+      LogicalValue::Timestamp(value) => Row::Timestamp(value as u64),
+      LogicalValue::Decimal(value) => Row::Decimal(value)
     }
   }
 }
@@ -165,6 +183,7 @@ impl fmt::Display for Row {
       Row::Str(ref value) => write!(f, "\"{}\"", value),
       Row::Bytes(ref value) => write!(f, "{:?}", value.data()),
       Row::Timestamp(value) => write!(f, "{}", value),
+      Row::Decimal(ref value) => write!(f, "{}", value),
       Row::Group(ref fields) => {
         write!(f, "{{")?;
         for (i, &(ref key, ref value)) in fields.iter().enumerate() {
@@ -288,6 +307,12 @@ mod tests {
       PhysicalType::BYTE_ARRAY, LogicalType::JSON, value);
     assert_eq!(row, Row::Str("{\"a\":1}".to_string()));
 
+    // Invalid UTF8
+    let value = ByteArray::from(vec![0xFF, 0xFE]);
+    let row = Row::convert_byte_array(
+      PhysicalType::BYTE_ARRAY, LogicalType::UTF8, value.clone());
+    assert_eq!(row, Row::Bytes(value));
+
     // NONE
     let value = ByteArray::from(vec![1, 2, 3, 4, 5]);
     let row = Row::convert_byte_array(
@@ -301,6 +326,38 @@ mod tests {
     assert_eq!(row, Row::Bytes(value));
   }
 
+  #[test]
+  fn test_row_from_physical() {
+    use std::rc::Rc;
+
+    use schema::types::{ColumnPath, Type};
+
+    let tpe = Type::decimal_type_builder("col", 12, 3).build().unwrap();
+    let descr = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col"));
+    let row = Row::from_physical(&descr, &-12345i64);
+    assert_eq!(row, Row::Decimal(Decimal::new(-12345, 12, 3)));
+    let value = LogicalValue::Decimal(Decimal::new(-12345, 12, 3));
+    assert_eq!(row.to_logical(), Some(value));
+
+    let tpe = Type::primitive_type_builder("col", PhysicalType::INT64)
+      .with_logical_type(LogicalType::TIMESTAMP_MILLIS)
+      .build()
+      .unwrap();
+    let descr = ColumnDescriptor::new(Rc::new(tpe), None, 0, 0, ColumnPath::from("col"));
+    let row = Row::from_physical(&descr, &1_238_544_000_000i64);
+    assert_eq!(row, Row::Timestamp(1_238_544_000_000));
+    assert_eq!(row.to_logical(), Some(LogicalValue::Timestamp(1_238_544_000_000)));
+
+    assert_eq!(Row::Null.to_logical(), None);
+    assert_eq!(Row::List(vec![Row::Int(1)]).to_logical(), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "logical type UINT_8 is not supported")]
+  fn test_row_convert_unsupported() {
+    Row::convert_int32(PhysicalType::INT32, LogicalType::UINT_8, 1);
+  }
+
   #[test]
   fn test_row_display() {
     // Primitive types
@@ -318,6 +375,7 @@ mod tests {
     assert_eq!(format!("{}", Row::Str("abc".to_string())), "\"abc\"");
     assert_eq!(format!("{}", Row::Bytes(ByteArray::from(vec![1, 2, 3]))), "[1, 2, 3]");
     assert_eq!(format!("{}", Row::Timestamp(12345678)), "12345678");
+    assert_eq!(format!("{}", Row::Decimal(Decimal::new(1234, 6, 2))), "12.34");
 
     // Complex types
     let row = Row::Group(vec![
@@ -0,0 +1,368 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains coercion of values read from files into types of a target schema.
+//!
+//! Consumers that expect a fixed schema can read files, which were written with
+//! narrower or differently annotated types, without branching on the file schema:
+//!
+//! - INT32 values (including INT_8, INT_16 and INT_32) are widened into INT64.
+//! - FLOAT values are widened into DOUBLE.
+//! - BYTE_ARRAY values without UTF8 annotation are decoded as UTF-8 strings, values
+//!   that are not valid UTF-8 are kept as bytes.
+//!
+//! Values of dictionary encoded pages are always decoded by column readers, so column
+//! chunks are read as plain values regardless of the encoding chosen by the writer.
+//!
+//! See [`RowIter::with_target_schema`](`::record::reader::RowIter::with_target_schema`)
+//! for rows and
+//! [`ColumnReader::read_batch_coerced`](`::column::reader::ColumnReader::read_batch_coerced`)
+//! for batches of values.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use basic::{LogicalType, Repetition, Type as PhysicalType};
+use errors::{ParquetError, Result};
+use record::api::Row;
+use schema::types::{ColumnPath, Type, TypePtr};
+
+/// Coercion of values of a primitive column into the type of a target schema.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Coercion {
+  /// Values are returned as read.
+  Identity,
+  /// `Row::Byte`, `Row::Short` and `Row::Int` values are converted into `Row::Long`.
+  IntToLong,
+  /// `Row::Float` values are converted into `Row::Double`.
+  FloatToDouble,
+  /// `Row::Bytes` values are decoded as UTF-8 into `Row::Str`. Values that are not
+  /// valid UTF-8 are returned as `Row::Bytes`, the same way as values of UTF8 columns.
+  BytesToStr
+}
+
+impl Coercion {
+  /// Returns coercion of values of primitive type `source`, as stored in a file, into
+  /// primitive type `target`. Returns error if values cannot be coerced.
+  pub fn new(source: &Type, target: &Type) -> Result<Self> {
+    let from = (source.get_physical_type(), source.get_basic_info().logical_type());
+    let to = (target.get_physical_type(), target.get_basic_info().logical_type());
+    if from == to {
+      return Ok(Coercion::Identity);
+    }
+    let coercion = match (from.0, to.0) {
+      (PhysicalType::INT32, PhysicalType::INT64)
+          if is_signed_int(from.1) && is_signed_int(to.1) => {
+        Some(Coercion::IntToLong)
+      },
+      (PhysicalType::FLOAT, PhysicalType::DOUBLE)
+          if from.1 == LogicalType::NONE && to.1 == LogicalType::NONE => {
+        Some(Coercion::FloatToDouble)
+      },
+      (PhysicalType::BYTE_ARRAY, PhysicalType::BYTE_ARRAY)
+          if to.1 == LogicalType::UTF8 => {
+        match from.1 {
+          LogicalType::NONE => Some(Coercion::BytesToStr),
+          // Values are already converted into strings
+          LogicalType::ENUM | LogicalType::JSON => Some(Coercion::Identity),
+          _ => None
+        }
+      },
+      _ => None
+    };
+    coercion.ok_or_else(|| general_err!(
+      "Cannot coerce field {} of type {} into type {}",
+      source.name(),
+      type_name(from),
+      type_name(to)
+    ))
+  }
+
+  /// Applies coercion to a row value. Null values and values of other types are
+  /// returned as is.
+  pub fn apply(&self, row: Row) -> Row {
+    match (*self, row) {
+      (Coercion::IntToLong, Row::Byte(value)) => Row::Long(value as i64),
+      (Coercion::IntToLong, Row::Short(value)) => Row::Long(value as i64),
+      (Coercion::IntToLong, Row::Int(value)) => Row::Long(value as i64),
+      (Coercion::FloatToDouble, Row::Float(value)) => Row::Double(value as f64),
+      (Coercion::BytesToStr, Row::Bytes(value)) => {
+        match String::from_utf8(value.data().to_vec()) {
+          Ok(value) => Row::Str(value),
+          Err(_) => Row::Bytes(value)
+        }
+      },
+      (_, row) => row
+    }
+  }
+}
+
+/// Returns `true` if values of the logical type are read as signed integers.
+fn is_signed_int(logical_type: LogicalType) -> bool {
+  match logical_type {
+    LogicalType::NONE | LogicalType::INT_8 | LogicalType::INT_16 | LogicalType::INT_32 |
+    LogicalType::INT_64 => true,
+    _ => false
+  }
+}
+
+/// Returns name of the type for error messages.
+fn type_name((physical_type, logical_type): (PhysicalType, LogicalType)) -> String {
+  match logical_type {
+    LogicalType::NONE => physical_type.to_string(),
+    _ => format!("{} ({})", physical_type, logical_type)
+  }
+}
+
+/// Coercion of a file schema into a target schema.
+///
+/// Fields of the target schema are matched with fields of the file schema by name.
+/// The projection of the file schema to the matched fields is used to read rows, and
+/// values of each leaf column are coerced into the type of the target field.
+#[derive(Debug)]
+pub struct SchemaCoercion {
+  projection: TypePtr,
+  columns: HashMap<ColumnPath, Coercion>
+}
+
+impl SchemaCoercion {
+  /// Creates coercion of message type `source` into message type `target`.
+  ///
+  /// Returns error if a field of `target` is not found in `source`, if repetition or
+  /// structure of a field differs, or if values of a leaf column cannot be coerced.
+  /// A required field can be read as an optional field.
+  pub fn new(source: &Type, target: &Type) -> Result<Self> {
+    if !source.is_schema() || !target.is_schema() {
+      return Err(general_err!("Schema coercion requires message types"));
+    }
+    let mut columns = HashMap::new();
+    let projection = coerce_group(source, target, &mut Vec::new(), &mut columns)?;
+    Ok(Self { projection: Rc::new(projection), columns: columns })
+  }
+
+  /// Returns projection of the file schema to the fields of the target schema.
+  pub fn projection(&self) -> TypePtr {
+    self.projection.clone()
+  }
+
+  /// Returns coercion of leaf column `path`, `Coercion::Identity` if column is not
+  /// coerced.
+  pub fn column(&self, path: &ColumnPath) -> Coercion {
+    self.columns.get(path).cloned().unwrap_or(Coercion::Identity)
+  }
+
+  /// Returns coercions of all leaf columns by column path.
+  pub fn columns(&self) -> &HashMap<ColumnPath, Coercion> {
+    &self.columns
+  }
+}
+
+/// Returns copy of group type `source` with fields of `target` and collects coercions
+/// of leaf columns into `columns`.
+fn coerce_group(
+  source: &Type,
+  target: &Type,
+  path: &mut Vec<String>,
+  columns: &mut HashMap<ColumnPath, Coercion>
+) -> Result<Type> {
+  let mut fields = Vec::new();
+  for target_field in target.get_fields() {
+    path.push(target_field.name().to_string());
+    let field_path = ColumnPath::new(path.clone());
+    let source_field = source.get_fields().iter()
+      .find(|field| field.name() == target_field.name())
+      .ok_or_else(|| {
+        general_err!("Field {} is not found in file schema", field_path.string())
+      })?;
+
+    let source_repetition = source_field.get_basic_info().repetition();
+    let target_repetition = target_field.get_basic_info().repetition();
+    if source_repetition != target_repetition &&
+        !(source_repetition == Repetition::REQUIRED &&
+          target_repetition == Repetition::OPTIONAL) {
+      return Err(general_err!(
+        "Cannot coerce field {} with repetition {} into {}",
+        field_path.string(),
+        source_repetition,
+        target_repetition
+      ));
+    }
+
+    if source_field.is_primitive() && target_field.is_primitive() {
+      let coercion = Coercion::new(source_field, target_field)?;
+      columns.insert(field_path, coercion);
+      fields.push(source_field.clone());
+    } else if source_field.is_group() && target_field.is_group() &&
+        source_field.get_basic_info().logical_type() ==
+          target_field.get_basic_info().logical_type() {
+      let group = coerce_group(source_field, target_field, path, columns)?;
+      fields.push(Rc::new(group));
+    } else {
+      return Err(general_err!(
+        "Cannot coerce field {}, structure of the field differs",
+        field_path.string()
+      ));
+    }
+    path.pop();
+  }
+
+  let info = source.get_basic_info();
+  let mut builder = Type::group_type_builder(info.name())
+    .with_logical_type(info.logical_type())
+    .with_fields(&mut fields);
+  if info.has_repetition() {
+    builder = builder.with_repetition(info.repetition());
+  }
+  if info.has_id() {
+    builder = builder.with_id(info.id());
+  }
+  builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use data_type::ByteArray;
+  use schema::parser::parse_message_type;
+
+  fn primitive(physical_type: PhysicalType, logical_type: LogicalType) -> Type {
+    Type::primitive_type_builder("a", physical_type)
+      .with_logical_type(logical_type)
+      .build()
+      .unwrap()
+  }
+
+  #[test]
+  fn test_coercion_new() {
+    let cases = vec![
+      (PhysicalType::INT32, LogicalType::NONE, PhysicalType::INT32, LogicalType::NONE,
+        Coercion::Identity),
+      (PhysicalType::INT32, LogicalType::INT_16, PhysicalType::INT64, LogicalType::NONE,
+        Coercion::IntToLong),
+      (PhysicalType::FLOAT, LogicalType::NONE, PhysicalType::DOUBLE, LogicalType::NONE,
+        Coercion::FloatToDouble),
+      (PhysicalType::BYTE_ARRAY, LogicalType::NONE, PhysicalType::BYTE_ARRAY,
+        LogicalType::UTF8, Coercion::BytesToStr),
+      (PhysicalType::BYTE_ARRAY, LogicalType::ENUM, PhysicalType::BYTE_ARRAY,
+        LogicalType::UTF8, Coercion::Identity)
+    ];
+    for (source_physical, source_logical, target_physical, target_logical, coercion)
+        in cases {
+      let source = primitive(source_physical, source_logical);
+      let target = primitive(target_physical, target_logical);
+      assert_eq!(Coercion::new(&source, &target).unwrap(), coercion);
+    }
+
+    let source = primitive(PhysicalType::INT64, LogicalType::NONE);
+    let target = primitive(PhysicalType::INT32, LogicalType::NONE);
+    assert_eq!(
+      Coercion::new(&source, &target).unwrap_err(),
+      general_err!("Cannot coerce field a of type INT64 into type INT32")
+    );
+    let source = primitive(PhysicalType::INT32, LogicalType::UINT_32);
+    let target = primitive(PhysicalType::INT64, LogicalType::INT_64);
+    assert_eq!(
+      Coercion::new(&source, &target).unwrap_err(),
+      general_err!(
+        "Cannot coerce field a of type INT32 (UINT_32) into type INT64 (INT_64)"
+      )
+    );
+  }
+
+  #[test]
+  fn test_coercion_apply() {
+    assert_eq!(Coercion::IntToLong.apply(Row::Short(-2)), Row::Long(-2));
+    assert_eq!(Coercion::IntToLong.apply(Row::Int(7)), Row::Long(7));
+    assert_eq!(Coercion::IntToLong.apply(Row::Null), Row::Null);
+    assert_eq!(Coercion::FloatToDouble.apply(Row::Float(0.5)), Row::Double(0.5));
+    assert_eq!(
+      Coercion::BytesToStr.apply(Row::Bytes(ByteArray::from("abc"))),
+      Row::Str("abc".to_string())
+    );
+    // Invalid UTF-8 is kept as bytes
+    assert_eq!(
+      Coercion::BytesToStr.apply(Row::Bytes(ByteArray::from(vec![0xff]))),
+      Row::Bytes(ByteArray::from(vec![0xff]))
+    );
+    assert_eq!(Coercion::Identity.apply(Row::Int(1)), Row::Int(1));
+  }
+
+  #[test]
+  fn test_schema_coercion() {
+    let source = parse_message_type("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL FLOAT b;
+        OPTIONAL group c {
+          REQUIRED BYTE_ARRAY d;
+          OPTIONAL INT64 e;
+        }
+        OPTIONAL INT32 f;
+      }
+    ").unwrap();
+    let target = parse_message_type("
+      message schema {
+        OPTIONAL group c {
+          REQUIRED BYTE_ARRAY d (UTF8);
+        }
+        OPTIONAL INT64 a;
+        OPTIONAL DOUBLE b;
+      }
+    ").unwrap();
+    let coercion = SchemaCoercion::new(&source, &target).unwrap();
+    let projection = parse_message_type("
+      message schema {
+        OPTIONAL group c {
+          REQUIRED BYTE_ARRAY d;
+        }
+        REQUIRED INT32 a;
+        OPTIONAL FLOAT b;
+      }
+    ").unwrap();
+    assert_eq!(*coercion.projection(), projection);
+    assert_eq!(coercion.columns().len(), 3);
+    assert_eq!(coercion.column(&ColumnPath::from("a")), Coercion::IntToLong);
+    assert_eq!(coercion.column(&ColumnPath::from("b")), Coercion::FloatToDouble);
+    assert_eq!(
+      coercion.column(&ColumnPath::new(vec!["c".to_string(), "d".to_string()])),
+      Coercion::BytesToStr
+    );
+    assert_eq!(coercion.column(&ColumnPath::from("f")), Coercion::Identity);
+
+    let errors = vec![
+      ("OPTIONAL INT32 x;", "Field x is not found in file schema"),
+      (
+        "REQUIRED FLOAT b;",
+        "Cannot coerce field b with repetition OPTIONAL into REQUIRED"
+      ),
+      ("OPTIONAL INT32 c;", "Cannot coerce field c, structure of the field differs"),
+      (
+        "OPTIONAL group c { OPTIONAL INT32 e; }",
+        "Cannot coerce field e of type INT64 into type INT32"
+      )
+    ];
+    for (field, message) in errors {
+      let target =
+        parse_message_type(&format!("message schema {{ {} }}", field)).unwrap();
+      assert_eq!(
+        SchemaCoercion::new(&source, &target).unwrap_err(),
+        general_err!("{}", message)
+      );
+    }
+  }
+}
@@ -15,8 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Contains record-based API for reading Parquet files.
+//! Contains record-based API for reading and writing Parquet files.
 
 pub mod api;
+pub mod coercion;
 pub mod reader;
+pub mod shredder;
 mod triplet;
+pub mod writer;
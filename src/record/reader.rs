@@ -23,27 +23,107 @@ use std::rc::Rc;
 
 use basic::{LogicalType, Repetition};
 use errors::{ParquetError, Result};
+use file::compat::{self, WriterCompat};
+use file::progress::ProgressTracker;
 use file::reader::{FileReader, RowGroupReader};
+use logical::TimeUnit;
 use schema::types::{ColumnPath, SchemaDescriptor, SchemaDescPtr, Type, TypePtr};
 use record::api::Row;
+use record::coercion::{Coercion, SchemaCoercion};
 use record::triplet::TripletIter;
 
 /// Default batch size for a reader
 const DEFAULT_BATCH_SIZE: usize = 256;
 
+/// Policy for sibling fields with identical names in a schema, which some writers emit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuplicateFieldPolicy {
+  /// Returns an error when a row iterator is created for a schema with duplicate field
+  /// names.
+  Error,
+  /// Renames duplicate fields in `Row::Group` by appending the number of the occurrence,
+  /// e.g. fields `a`, `a` and `a` are named `a`, `a_1` and `a_2`. Suffixes that clash
+  /// with other field names are skipped.
+  Suffix,
+  /// Keeps duplicate names in `Row::Group`, fields are distinguished by position only.
+  /// This is the default policy.
+  Positional
+}
+
+impl Default for DuplicateFieldPolicy {
+  fn default() -> Self {
+    DuplicateFieldPolicy::Positional
+  }
+}
+
+/// Returns path of the first field that has a sibling with the same name, or `None` if
+/// all sibling fields of the type have unique names.
+fn find_duplicate_field(tp: &Type) -> Option<ColumnPath> {
+  if tp.is_primitive() {
+    return None;
+  }
+  let fields = tp.get_fields();
+  for (i, field) in fields.iter().enumerate() {
+    if fields[..i].iter().any(|other| other.name() == field.name()) {
+      return Some(ColumnPath::new(vec![field.name().to_string()]));
+    }
+    if let Some(path) = find_duplicate_field(field) {
+      let mut parts = vec![field.name().to_string()];
+      parts.extend(path.parts().iter().cloned());
+      return Some(ColumnPath::new(parts));
+    }
+  }
+  None
+}
+
+/// Returns names of fields as they appear in `Row::Group`, duplicate names are renamed
+/// according to the policy.
+fn field_names(fields: &[TypePtr], policy: DuplicateFieldPolicy) -> Vec<String> {
+  let mut names: Vec<String> = Vec::with_capacity(fields.len());
+  for (i, field) in fields.iter().enumerate() {
+    let name = field.name();
+    let num_previous = fields[..i].iter().filter(|other| other.name() == name).count();
+    if policy != DuplicateFieldPolicy::Suffix || num_previous == 0 {
+      names.push(name.to_string());
+      continue;
+    }
+    let mut suffix = num_previous;
+    let mut unique_name = format!("{}_{}", name, suffix);
+    while names.contains(&unique_name) ||
+        fields.iter().any(|other| other.name() == unique_name) {
+      suffix += 1;
+      unique_name = format!("{}_{}", name, suffix);
+    }
+    names.push(unique_name);
+  }
+  names
+}
+
 /// Tree builder for `Reader` enum.
 /// Serves as a container of options for building a reader tree and a builder, and
 /// accessing a records iterator [`RowIter`].
 pub struct TreeBuilder {
   // Batch size (>= 1) for triplet iterators
-  batch_size: usize
+  batch_size: usize,
+  // Workarounds for the writer of a file
+  compat: WriterCompat,
+  // Naming of sibling fields with identical names
+  duplicate_field_policy: DuplicateFieldPolicy,
+  // Coercions of leaf columns into types of a target schema
+  coercions: HashMap<ColumnPath, Coercion>,
+  // Unit that timestamp values are normalized into
+  timestamp_unit: Option<TimeUnit>
 }
 
 impl TreeBuilder {
   /// Creates new tree builder with default parameters.
   pub fn new() -> Self {
     Self {
-      batch_size: DEFAULT_BATCH_SIZE
+      batch_size: DEFAULT_BATCH_SIZE,
+      compat: WriterCompat::disabled(),
+      duplicate_field_policy: DuplicateFieldPolicy::default(),
+      coercions: HashMap::new(),
+      timestamp_unit: None
     }
   }
 
@@ -53,36 +133,70 @@ impl TreeBuilder {
     self
   }
 
+  /// Sets writer workarounds that are applied when converting values.
+  /// By default all workarounds are disabled.
+  pub fn with_compat(mut self, compat: WriterCompat) -> Self {
+    self.compat = compat;
+    self
+  }
+
+  /// Sets policy for naming sibling fields with identical names in `Row::Group`.
+  /// `DuplicateFieldPolicy::Error` is treated as `Positional`, the schema is checked
+  /// when a row iterator is created.
+  pub fn with_duplicate_field_policy(mut self, policy: DuplicateFieldPolicy) -> Self {
+    self.duplicate_field_policy = policy;
+    self
+  }
+
+  /// Sets coercions of leaf columns by column path, which are applied to values after
+  /// they are converted. Columns without coercion are read as is.
+  pub fn with_coercions(mut self, coercions: HashMap<ColumnPath, Coercion>) -> Self {
+    self.coercions = coercions;
+    self
+  }
+
+  /// Sets unit that values of INT96, `TIMESTAMP_MILLIS` and `TIMESTAMP_MICROS` columns
+  /// are normalized into. By default values are read as timestamps in milliseconds,
+  /// except for `TIMESTAMP_MICROS` values, which are read as `Row::Long`.
+  pub fn with_timestamp_unit(mut self, unit: Option<TimeUnit>) -> Self {
+    self.timestamp_unit = unit;
+    self
+  }
+
   /// Creates new root reader for provided schema and row group.
   pub fn build(
     &self,
     descr: SchemaDescPtr,
     row_group_reader: &RowGroupReader
   ) -> Reader {
-    // Prepare lookup table of column path -> original column index
-    // This allows to prune columns and map schema leaf nodes to the column readers
-    let mut paths: HashMap<ColumnPath, usize> = HashMap::new();
+    // Prepare lookup table of column path -> original column indexes
+    // This allows to prune columns and map schema leaf nodes to the column readers.
+    // Paths are not unique if the schema has sibling fields with identical names, such
+    // columns are assigned to leaf nodes in schema order.
+    let mut paths: HashMap<ColumnPath, Vec<usize>> = HashMap::new();
     let row_group_metadata = row_group_reader.metadata();
 
-    for col_index in 0..row_group_reader.num_columns() {
+    for col_index in (0..row_group_reader.num_columns()).rev() {
       let col_meta = row_group_metadata.column(col_index);
       let col_path = col_meta.column_path().clone();
-      paths.insert(col_path, col_index);
+      paths.entry(col_path).or_insert_with(Vec::new).push(col_index);
     }
 
     // Build child readers for the message type
     let mut readers = Vec::new();
     let mut path = Vec::new();
 
-    for field in descr.root_schema().get_fields() {
+    let fields = descr.root_schema().get_fields();
+    for field in fields {
       let reader = self.reader_tree(
-        field.clone(), &mut path, 0, 0, &paths, row_group_reader);
+        field.clone(), &mut path, 0, 0, &mut paths, row_group_reader);
       readers.push(reader);
     }
 
     // Return group reader for message type,
     // it is always required with definition level 0
-    Reader::GroupReader(None, 0, readers)
+    let names = field_names(fields, self.duplicate_field_policy);
+    Reader::GroupReader(None, 0, names, readers)
   }
 
   /// Creates iterator of `Row`s directly from schema descriptor and row group.
@@ -102,7 +216,7 @@ impl TreeBuilder {
     mut path: &mut Vec<String>,
     mut curr_def_level: i16,
     mut curr_rep_level: i16,
-    paths: &HashMap<ColumnPath, usize>,
+    paths: &mut HashMap<ColumnPath, Vec<usize>>,
     row_group_reader: &RowGroupReader
   ) -> Reader {
     assert!(field.get_basic_info().has_repetition());
@@ -122,10 +236,20 @@ impl TreeBuilder {
     path.push(String::from(field.name()));
     let reader = if field.is_primitive() {
       let col_path = ColumnPath::new(path.to_vec());
-      let orig_index = *paths.get(&col_path).unwrap();
+      let orig_index =
+        paths.get_mut(&col_path).and_then(|indexes| indexes.pop()).unwrap();
       let col_descr = row_group_reader.metadata().column(orig_index).column_descr_ptr();
       let col_reader = row_group_reader.get_column_reader(orig_index).unwrap();
-      let column = TripletIter::new(col_descr, col_reader, self.batch_size);
+      let logical_type =
+        self.compat.logical_type(col_descr.physical_type(), col_descr.logical_type());
+      let mut column = TripletIter::new(col_descr, col_reader, self.batch_size);
+      column.set_logical_type(logical_type);
+      if let Some(coercion) = self.coercions.get(&col_path) {
+        column.set_coercion(*coercion);
+      }
+      if let Some(unit) = self.timestamp_unit {
+        column.set_timestamp_unit(unit);
+      }
       Reader::PrimitiveReader(field, column)
     } else {
       match field.get_basic_info().logical_type() {
@@ -141,7 +265,7 @@ impl TreeBuilder {
             field
           );
 
-          if Reader::is_element_type(&repeated_field) {
+          if compat::is_list_element(&field, &repeated_field) {
             // Support for backward compatible lists
             let reader = self.reader_tree(repeated_field.clone(), &mut path,
               curr_def_level, curr_rep_level, paths, row_group_reader);
@@ -209,7 +333,8 @@ impl TreeBuilder {
               curr_def_level, curr_rep_level, paths, row_group_reader);
             readers.push(reader);
           }
-          Reader::GroupReader(Some(field), curr_def_level, readers)
+          let names = field_names(field.get_fields(), self.duplicate_field_policy);
+          Reader::GroupReader(Some(field), curr_def_level, names, readers)
         }
       }
     };
@@ -225,9 +350,10 @@ pub enum Reader {
   PrimitiveReader(TypePtr, TripletIter),
   // Optional reader with definition level of a parent and a reader
   OptionReader(i16, Box<Reader>),
-  // Group (struct) reader with type information, definition level and list of child
-  // readers. When it represents message type, type information is None
-  GroupReader(Option<TypePtr>, i16, Vec<Reader>),
+  // Group (struct) reader with type information, definition level, names of fields in
+  // rows and list of child readers. When it represents message type, type information
+  // is None
+  GroupReader(Option<TypePtr>, i16, Vec<String>, Vec<Reader>),
   // Reader for repeated values, e.g. lists, contains type information, definition level,
   // repetition level and a child reader
   RepeatedReader(TypePtr, i16, i16, Box<Reader>),
@@ -246,55 +372,6 @@ impl Reader {
     }
   }
 
-  /// Returns true if repeated type is an element type for the list.
-  /// Used to determine legacy list types.
-  /// This method is copied from Spark Parquet reader and is based on the reference:
-  /// https://github.com/apache/parquet-format/blob/master/LogicalTypes.md
-  ///   #backward-compatibility-rules
-  fn is_element_type(repeated_type: &Type) -> bool {
-    // For legacy 2-level list types with primitive element type, e.g.:
-    //
-    //    // ARRAY<INT> (nullable list, non-null elements)
-    //    optional group my_list (LIST) {
-    //      repeated int32 element;
-    //    }
-    //
-    repeated_type.is_primitive() ||
-    // For legacy 2-level list types whose element type is a group type with 2 or more
-    // fields, e.g.:
-    //
-    //    // ARRAY<STRUCT<str: STRING, num: INT>> (nullable list, non-null elements)
-    //    optional group my_list (LIST) {
-    //      repeated group element {
-    //        required binary str (UTF8);
-    //        required int32 num;
-    //      };
-    //    }
-    //
-    repeated_type.is_group() && repeated_type.get_fields().len() > 1 ||
-    // For legacy 2-level list types generated by parquet-avro (Parquet version < 1.6.0),
-    // e.g.:
-    //
-    //    // ARRAY<STRUCT<str: STRING>> (nullable list, non-null elements)
-    //    optional group my_list (LIST) {
-    //      repeated group array {
-    //        required binary str (UTF8);
-    //      };
-    //    }
-    //
-    repeated_type.name() == "array" ||
-    // For Parquet data generated by parquet-thrift, e.g.:
-    //
-    //    // ARRAY<STRUCT<str: STRING>> (nullable list, non-null elements)
-    //    optional group my_list (LIST) {
-    //      repeated group my_list_tuple {
-    //        required binary str (UTF8);
-    //      };
-    //    }
-    //
-    repeated_type.name().ends_with("_tuple")
-  }
-
   /// Reads current record as `Row` from the reader tree.
   /// Automatically advances all necessary readers.
   fn read(&mut self) -> Row {
@@ -312,15 +389,15 @@ impl Reader {
           Row::Null
         }
       },
-      Reader::GroupReader(_, def_level, ref mut readers) => {
+      Reader::GroupReader(_, def_level, ref names, ref mut readers) => {
         let mut fields = Vec::new();
-        for reader in readers {
+        for (name, reader) in names.iter().zip(readers.iter_mut()) {
           if reader.repetition() != Repetition::OPTIONAL ||
               reader.current_def_level() > def_level {
-            fields.push((String::from(reader.field_name()), reader.read()));
+            fields.push((name.clone(), reader.read()));
           } else {
             reader.advance_columns();
-            fields.push((String::from(reader.field_name()), Row::Null));
+            fields.push((name.clone(), Row::Null));
           }
         }
         Row::Group(fields)
@@ -379,7 +456,7 @@ impl Reader {
     match *self {
       Reader::PrimitiveReader(ref field, _) => field.name(),
       Reader::OptionReader(_, ref reader) => reader.field_name(),
-      Reader::GroupReader(ref opt, _, _) => match opt {
+      Reader::GroupReader(ref opt, _, _, _) => match opt {
         &Some(ref field) => field.name(),
         &None => panic!("Field is None for group reader")
       },
@@ -397,7 +474,7 @@ impl Reader {
       Reader::OptionReader(_, ref reader) => {
         reader.repetition()
       },
-      Reader::GroupReader(ref opt, _, _) => match opt {
+      Reader::GroupReader(ref opt, _, _, _) => match opt {
         &Some(ref field) => field.get_basic_info().repetition(),
         &None => panic!("Field is None for group reader")
       },
@@ -416,7 +493,9 @@ impl Reader {
     match *self {
       Reader::PrimitiveReader(_, ref column) => column.has_next(),
       Reader::OptionReader(_, ref reader) => reader.has_next(),
-      Reader::GroupReader(_, _, ref readers) => readers.first().unwrap().has_next(),
+      Reader::GroupReader(_, _, _, ref readers) => {
+        readers.first().map(|reader| reader.has_next()).unwrap_or(false)
+      },
       Reader::RepeatedReader(_, _, _, ref reader) => reader.has_next(),
       Reader::KeyValueReader(_, _, _, ref keys, _) => keys.has_next()
     }
@@ -428,7 +507,7 @@ impl Reader {
     match *self {
       Reader::PrimitiveReader(_, ref column) => column.current_def_level(),
       Reader::OptionReader(_, ref reader) => reader.current_def_level(),
-      Reader::GroupReader(_, _, ref readers) => match readers.first() {
+      Reader::GroupReader(_, _, _, ref readers) => match readers.first() {
         Some(reader) => reader.current_def_level(),
         None => panic!("Current definition level: empty group reader")
       },
@@ -443,7 +522,7 @@ impl Reader {
     match *self {
       Reader::PrimitiveReader(_, ref column) => column.current_rep_level(),
       Reader::OptionReader(_, ref reader) => reader.current_rep_level(),
-      Reader::GroupReader(_, _, ref readers) => match readers.first() {
+      Reader::GroupReader(_, _, _, ref readers) => match readers.first() {
         Some(reader) => reader.current_rep_level(),
         None => panic!("Current repetition level: empty group reader")
       },
@@ -461,7 +540,7 @@ impl Reader {
       Reader::OptionReader(_, ref mut reader) => {
         reader.advance_columns();
       },
-      Reader::GroupReader(_, _, ref mut readers) => {
+      Reader::GroupReader(_, _, _, ref mut readers) => {
         for reader in readers {
           reader.advance_columns();
         }
@@ -487,46 +566,137 @@ pub struct RowIter<'a> {
   descr: SchemaDescPtr,
   tree_builder: TreeBuilder,
   file_reader: Option<&'a FileReader>,
+  // Row group reader of an iterator for a single row group, until its rows are loaded
+  row_group_reader: Option<&'a RowGroupReader>,
   current_row_group: usize,
   num_row_groups: usize,
-  row_iter: Option<ReaderIter>
+  row_iter: Option<ReaderIter>,
+  // Progress tracker and the number of rows of the current row group that has not been
+  // reported as completed yet
+  progress: Option<ProgressTracker>,
+  num_unreported_rows: Option<usize>
 }
 
 impl<'a> RowIter<'a> {
   /// Creates iterator of [`Row`](`::record::api::Row`)s for all row groups in a file.
+  ///
+  /// Workarounds for the writer of the file are applied based on `created_by`, see
+  /// [`WriterCompat`](`::file::compat::WriterCompat`).
   pub fn from_file(proj: Option<Type>, reader: &'a FileReader) -> Result<Self> {
-    let descr = Self::get_proj_descr(proj,
-      reader.metadata().file_metadata().schema_descr_ptr())?;
+    let file_metadata = reader.metadata().file_metadata();
+    let descr = Self::get_proj_descr(proj, file_metadata.schema_descr_ptr())?;
     let num_row_groups = reader.num_row_groups();
+    let created_by = file_metadata.created_by();
+    let compat = WriterCompat::new(created_by.as_ref().map(|s| s.as_str()));
 
     Ok(Self {
       descr: descr,
-      tree_builder: Self::tree_builder(),
+      tree_builder: Self::tree_builder().with_compat(compat),
       file_reader: Some(reader),
+      row_group_reader: None,
       current_row_group: 0,
       num_row_groups: num_row_groups,
-      row_iter: None
+      row_iter: None,
+      progress: None,
+      num_unreported_rows: None
     })
   }
 
   /// Creates iterator of [`Row`](`::record::api::Row`)s for a specific row group.
   pub fn from_row_group(proj: Option<Type>, reader: &'a RowGroupReader) -> Result<Self> {
     let descr = Self::get_proj_descr(proj, reader.metadata().schema_descr_ptr())?;
-    let tree_builder = Self::tree_builder();
-    let row_iter = tree_builder.as_iter(descr.clone(), reader);
+    let num_rows = reader.metadata().num_rows() as usize;
 
     // For row group we need to set `current_row_group` >= `num_row_groups`, because we
-    // only have one row group and can't buffer more.
+    // only have one row group and can't buffer more. Rows are loaded on the first call
+    // of `next`, so settings of the iterator apply to the row group.
     Ok(Self {
       descr: descr,
-      tree_builder: tree_builder,
+      tree_builder: Self::tree_builder(),
       file_reader: None,
+      row_group_reader: Some(reader),
       current_row_group: 0,
       num_row_groups: 0,
-      row_iter: Some(row_iter)
+      row_iter: None,
+      progress: None,
+      num_unreported_rows: Some(num_rows)
     })
   }
 
+  /// Sets writer workarounds for this iterator, e.g. `WriterCompat::disabled()` to read
+  /// values exactly as annotated in the file schema.
+  ///
+  /// Only affects row groups that have not been loaded yet.
+  pub fn with_compat(mut self, compat: WriterCompat) -> Self {
+    self.tree_builder = self.tree_builder.with_compat(compat);
+    self
+  }
+
+  /// Sets policy for sibling fields with identical names for this iterator.
+  ///
+  /// Returns an error for `DuplicateFieldPolicy::Error` if the (projected) schema has
+  /// duplicate field names. Only affects row groups that have not been loaded yet.
+  pub fn with_duplicate_field_policy(
+    mut self,
+    policy: DuplicateFieldPolicy
+  ) -> Result<Self> {
+    if policy == DuplicateFieldPolicy::Error {
+      if let Some(path) = find_duplicate_field(self.descr.root_schema()) {
+        return Err(general_err!("Schema has duplicate field {}", path.string()));
+      }
+    }
+    self.tree_builder = self.tree_builder.with_duplicate_field_policy(policy);
+    Ok(self)
+  }
+
+  /// Sets target schema for this iterator. Fields of the target schema are read from
+  /// fields of the (projected) file schema with the same name, and values are coerced
+  /// into types of the target fields, e.g. INT32 values are read as INT64 values, see
+  /// [`coercion`](`::record::coercion`) module for supported coercions.
+  ///
+  /// Returns an error if the file schema cannot be coerced into the target schema.
+  /// Only affects row groups that have not been loaded yet.
+  pub fn with_target_schema(mut self, target: Type) -> Result<Self> {
+    let coercion = SchemaCoercion::new(self.descr.root_schema(), &target)?;
+    self.descr = Rc::new(SchemaDescriptor::new(coercion.projection()));
+    self.tree_builder = self.tree_builder.with_coercions(coercion.columns().clone());
+    Ok(self)
+  }
+
+  /// Sets unit that all timestamp values of this iterator are normalized into, i.e.
+  /// values of INT96, `TIMESTAMP_MILLIS` and `TIMESTAMP_MICROS` columns are returned as
+  /// `Row::Timestamp` in `unit` since Unix epoch. Values are rounded down when
+  /// converted into a coarser unit.
+  ///
+  /// Values that do not fit into `i64` when converted into a finer unit, e.g. far
+  /// future dates in nanoseconds, saturate at `i64::MIN` or `i64::MAX`. Only affects
+  /// row groups that have not been loaded yet.
+  pub fn with_timestamp_unit(mut self, unit: TimeUnit) -> Self {
+    self.tree_builder = self.tree_builder.with_timestamp_unit(Some(unit));
+    self
+  }
+
+  /// Returns schema descriptor of the (projected) file schema read by this iterator.
+  pub(crate) fn schema_descr(&self) -> &SchemaDescriptor {
+    &self.descr
+  }
+
+  /// Sets progress tracker for this iterator, which records a completed row group once
+  /// all rows of the row group have been read.
+  pub fn with_progress(mut self, progress: Option<ProgressTracker>) -> Self {
+    self.progress = progress;
+    self
+  }
+
+  /// Reports the current row group as completed, if it has not been reported yet.
+  fn complete_row_group(&mut self) {
+    if let Some(num_rows) = self.num_unreported_rows.take() {
+      if let Some(ref progress) = self.progress {
+        progress.complete_row_group(num_rows as u64, 0);
+      }
+    }
+  }
+
   /// Returns common tree builder, so the same settings are applied to both iterators
   /// from file reader and row group.
   #[inline]
@@ -561,12 +731,18 @@ impl<'a> Iterator for RowIter<'a> {
   type Item = Row;
 
   fn next(&mut self) -> Option<Row> {
+    if let Some(row_group_reader) = self.row_group_reader.take() {
+      let iter = self.tree_builder.as_iter(self.descr.clone(), row_group_reader);
+      self.row_iter = Some(iter);
+    }
+
     let mut row = None;
     if let Some(ref mut iter) = self.row_iter {
       row = iter.next();
     }
 
     while row.is_none() && self.current_row_group < self.num_row_groups {
+      self.complete_row_group();
       // We do not expect any failures when accessing a row group, and file reader
       // must be set for selecting next row group.
       let row_group_reader = &*self.file_reader
@@ -575,14 +751,36 @@ impl<'a> Iterator for RowIter<'a> {
         .get_row_group(self.current_row_group).unwrap();
       self.current_row_group += 1;
       let mut iter = self.tree_builder.as_iter(self.descr.clone(), row_group_reader);
+      self.num_unreported_rows = Some(row_group_reader.metadata().num_rows() as usize);
       row = iter.next();
       self.row_iter = Some(iter);
     }
 
+    if row.is_none() {
+      self.complete_row_group();
+    }
     row
   }
+
+  /// Returns the exact number of rows left, based on the number of rows of the current
+  /// and remaining row groups in metadata.
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let mut num_rows = self.row_iter.as_ref().map(|iter| iter.len()).unwrap_or(0);
+    if let Some(row_group_reader) = self.row_group_reader {
+      num_rows += row_group_reader.metadata().num_rows() as usize;
+    }
+    if let Some(file_reader) = self.file_reader {
+      let metadata = file_reader.metadata();
+      for i in self.current_row_group..self.num_row_groups {
+        num_rows += metadata.row_group(i).num_rows() as usize;
+      }
+    }
+    (num_rows, Some(num_rows))
+  }
 }
 
+impl<'a> ExactSizeIterator for RowIter<'a> {}
+
 /// Internal iterator of [`Row`](`::record::api::Row`)s for a reader.
 pub struct ReaderIter {
   root_reader: Reader,
@@ -611,17 +809,29 @@ impl Iterator for ReaderIter {
       None
     }
   }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.records_left, Some(self.records_left))
+  }
 }
 
+impl ExactSizeIterator for ReaderIter {}
+
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use std::fs;
+  use std::io::Write;
+
+  use data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
   use errors::{ParquetError, Result};
+  use file::builder::{ColumnData, InMemoryFileBuilder};
+  use file::compat::WriterCompat;
   use file::reader::{FileReader, SerializedFileReader};
   use record::api::Row;
   use schema::parser::parse_message_type;
-  use util::test_common::get_test_file;
+  use util::test_common::{get_temp_filename, get_test_file};
 
   #[test]
   fn test_file_reader_rows_nulls() {
@@ -1222,6 +1432,375 @@ mod tests {
     test_file_reader_rows("nested_maps.snappy.parquet", Some(schema)).unwrap();
   }
 
+  #[test]
+  fn test_file_reader_rows_writer_compat() {
+    let schema = "
+      message schema {
+        OPTIONAL BYTE_ARRAY string_col;
+      }
+    ";
+
+    // alltypes_plain.parquet is written by impala, strings are not annotated as UTF8
+    let proj = parse_message_type(&schema).unwrap();
+    let rows = test_file_reader_rows("alltypes_plain.parquet", Some(proj)).unwrap();
+    assert_eq!(rows.len(), 8);
+    assert_eq!(
+      rows[0],
+      Row::Group(vec![("string_col".to_string(), Row::Str("0".to_string()))])
+    );
+
+    let file = get_test_file("alltypes_plain.parquet");
+    let file_reader: Box<FileReader> = Box::new(SerializedFileReader::new(file).unwrap());
+    let rows: Vec<Row> = file_reader
+      .get_row_iter(Some(parse_message_type(&schema).unwrap()))
+      .unwrap()
+      .with_compat(WriterCompat::disabled())
+      .collect();
+    assert_eq!(rows.len(), 8);
+    assert_eq!(
+      rows[1],
+      Row::Group(vec![
+        ("string_col".to_string(), Row::Bytes(ByteArray::from(vec![b'1'])))
+      ])
+    );
+  }
+
+  #[test]
+  fn test_file_reader_rows_legacy_lists() {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        OPTIONAL GROUP a (LIST) {
+          REPEATED INT32 element;
+        }
+        REQUIRED GROUP b (LIST) {
+          REPEATED GROUP array {
+            REQUIRED BYTE_ARRAY str (UTF8);
+          }
+        }
+        OPTIONAL GROUP c (LIST) {
+          REPEATED GROUP c_tuple {
+            REQUIRED INT32 num;
+          }
+        }
+      }
+    ").unwrap());
+    let read_rows = |bytes: Vec<u8>, name: &str| -> Vec<Row> {
+      let path = get_temp_filename(name);
+      fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+      let file = fs::File::open(&path).unwrap();
+      let file_reader = SerializedFileReader::new(file).unwrap();
+      file_reader.get_row_iter(None).unwrap().collect()
+    };
+
+    // Lists written with 2-level layout: [1, 2], [{str: x}], [{num: 3}, {num: 4}] in the
+    // first row and null, [], [] in the second row
+    let bytes = InMemoryFileBuilder::new(schema.clone())
+      .with_columns(vec![
+        ColumnData::new::<Int32Type>(vec![1, 2])
+          .with_def_levels(vec![2, 2, 0])
+          .with_rep_levels(vec![0, 1, 0]),
+        ColumnData::new::<ByteArrayType>(vec![ByteArray::from("x")])
+          .with_def_levels(vec![1, 0])
+          .with_rep_levels(vec![0, 0]),
+        ColumnData::new::<Int32Type>(vec![3, 4])
+          .with_def_levels(vec![2, 2, 1])
+          .with_rep_levels(vec![0, 1, 0])
+      ])
+      .build()
+      .unwrap();
+    let rows = read_rows(bytes, "test_file_reader_rows_legacy_lists.parquet");
+    let expected_rows = vec![
+      Row::Group(vec![
+        ("a".to_string(), Row::List(vec![Row::Int(1), Row::Int(2)])),
+        ("b".to_string(), Row::List(vec![
+          Row::Group(vec![("str".to_string(), Row::Str("x".to_string()))])
+        ])),
+        ("c".to_string(), Row::List(vec![
+          Row::Group(vec![("num".to_string(), Row::Int(3))]),
+          Row::Group(vec![("num".to_string(), Row::Int(4))])
+        ]))
+      ]),
+      Row::Group(vec![
+        ("a".to_string(), Row::Null),
+        ("b".to_string(), Row::List(vec![])),
+        ("c".to_string(), Row::List(vec![]))
+      ])
+    ];
+    assert_eq!(rows, expected_rows);
+
+    // Rows are written with the same layout
+    let bytes = InMemoryFileBuilder::new(schema)
+      .with_rows(expected_rows.clone())
+      .build()
+      .unwrap();
+    let rows = read_rows(bytes, "test_file_reader_rows_legacy_lists_roundtrip.parquet");
+    assert_eq!(rows, expected_rows);
+  }
+
+  #[test]
+  fn test_file_reader_rows_target_schema() {
+    let target = "
+      message schema {
+        OPTIONAL BYTE_ARRAY string_col (UTF8);
+        OPTIONAL INT64 id;
+        OPTIONAL DOUBLE float_col;
+      }
+    ";
+    let file = get_test_file("alltypes_plain.parquet");
+    let file_reader = SerializedFileReader::new(file).unwrap();
+    let rows: Vec<Row> = RowIter::from_file(None, &file_reader)
+      .unwrap()
+      .with_compat(WriterCompat::disabled())
+      .with_target_schema(parse_message_type(&target).unwrap())
+      .unwrap()
+      .collect();
+    assert_eq!(rows.len(), 8);
+    assert_eq!(
+      rows[1],
+      Row::Group(vec![
+        ("string_col".to_string(), Row::Str("1".to_string())),
+        ("id".to_string(), Row::Long(5)),
+        ("float_col".to_string(), Row::Double(1.1f32 as f64))
+      ])
+    );
+
+    let target = "
+      message schema {
+        OPTIONAL INT32 bigint_col;
+      }
+    ";
+    let res = RowIter::from_file(None, &file_reader)
+      .unwrap()
+      .with_target_schema(parse_message_type(&target).unwrap());
+    assert_eq!(
+      res.err().unwrap(),
+      general_err!("Cannot coerce field bigint_col of type INT64 into type INT32")
+    );
+  }
+
+  #[test]
+  fn test_file_reader_rows_target_schema_invalid_utf8() {
+    let schema = Rc::new(parse_message_type("
+      message schema {
+        REQUIRED BYTE_ARRAY a;
+      }
+    ").unwrap());
+    let bytes = InMemoryFileBuilder::new(schema)
+      .with_columns(vec![
+        ColumnData::new::<ByteArrayType>(
+          vec![ByteArray::from("abc"), ByteArray::from(vec![0xff, 0xfe])])
+      ])
+      .build()
+      .unwrap();
+    let path = get_temp_filename("test_file_reader_rows_target_schema_invalid_utf8");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+    let file_reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+
+    // Values that are not valid UTF-8 are returned as bytes instead of strings
+    let target = "
+      message schema {
+        REQUIRED BYTE_ARRAY a (UTF8);
+      }
+    ";
+    let rows: Vec<Row> = RowIter::from_file(None, &file_reader)
+      .unwrap()
+      .with_target_schema(parse_message_type(&target).unwrap())
+      .unwrap()
+      .collect();
+    assert_eq!(
+      rows,
+      vec![
+        Row::Group(vec![("a".to_string(), Row::Str("abc".to_string()))]),
+        Row::Group(vec![("a".to_string(), Row::Bytes(ByteArray::from(vec![0xff, 0xfe])))])
+      ]
+    );
+  }
+
+  #[test]
+  fn test_file_reader_rows_timestamp_unit() {
+    let schema = "
+      message schema {
+        OPTIONAL INT96 timestamp_col;
+      }
+    ";
+    let file = get_test_file("alltypes_plain.parquet");
+    let file_reader = SerializedFileReader::new(file).unwrap();
+    let read_timestamps = |unit: Option<TimeUnit>| -> Vec<i64> {
+      let proj = parse_message_type(&schema).unwrap();
+      let mut row_iter = RowIter::from_file(Some(proj), &file_reader).unwrap();
+      if let Some(unit) = unit {
+        row_iter = row_iter.with_timestamp_unit(unit);
+      }
+      row_iter.map(|row| match row {
+        Row::Group(ref fields) => match fields[0].1 {
+          Row::Timestamp(value) => value as i64,
+          ref value => panic!("Expected timestamp, found {}", value)
+        },
+        _ => panic!("Expected group")
+      }).collect()
+    };
+
+    let millis = read_timestamps(None);
+    assert_eq!(millis.len(), 8);
+    assert_eq!(read_timestamps(Some(TimeUnit::Millis)), millis);
+    assert_eq!(
+      read_timestamps(Some(TimeUnit::Nanos)),
+      millis.iter().map(|value| value * 1_000_000).collect::<Vec<i64>>()
+    );
+
+    let schema = "
+      message schema {
+        REQUIRED INT64 ts (TIMESTAMP_MICROS);
+      }
+    ";
+    let bytes = InMemoryFileBuilder::new(Rc::new(parse_message_type(schema).unwrap()))
+      .with_columns(vec![
+        ColumnData::new::<Int64Type>(vec![1_500, -1_500, 253_402_214_400_000_000])
+      ])
+      .build()
+      .unwrap();
+    let path = get_temp_filename("test_file_reader_rows_timestamp_unit.parquet");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+    let file_reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let rows: Vec<Row> = RowIter::from_file(None, &file_reader)
+      .unwrap()
+      .with_timestamp_unit(TimeUnit::Millis)
+      .collect();
+    assert_eq!(rows, vec![
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(1))]),
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(-2i64 as u64))]),
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(253_402_214_400_000))])
+    ]);
+
+    // 9999-12-31 in microseconds saturates in nanoseconds
+    let rows: Vec<Row> = RowIter::from_file(None, &file_reader)
+      .unwrap()
+      .with_timestamp_unit(TimeUnit::Nanos)
+      .collect();
+    assert_eq!(rows[0], Row::Group(vec![("ts".to_string(), Row::Timestamp(1_500_000))]));
+    assert_eq!(
+      rows[2],
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(i64::max_value() as u64))])
+    );
+  }
+
+  #[test]
+  fn test_file_reader_iter_size_hint() {
+    let file = get_test_file("alltypes_plain.parquet");
+    let file_reader = SerializedFileReader::new(file).unwrap();
+
+    let mut iter = RowIter::from_file(None, &file_reader).unwrap();
+    assert_eq!(iter.size_hint(), (8, Some(8)));
+    iter.next().unwrap();
+    assert_eq!(iter.len(), 7);
+    assert_eq!(iter.by_ref().count(), 7);
+    assert_eq!(iter.size_hint(), (0, Some(0)));
+
+    let row_group_reader = file_reader.get_row_group(0).unwrap();
+    let mut iter = RowIter::from_row_group(None, &*row_group_reader).unwrap();
+    assert_eq!(iter.len(), 8);
+    iter.nth(2).unwrap();
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+  }
+
+  #[test]
+  fn test_file_reader_rows_duplicate_fields() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a;
+        REQUIRED INT32 a;
+        REQUIRED GROUP b {
+          REQUIRED INT32 c;
+          REQUIRED INT32 c;
+          REQUIRED INT32 c_1;
+        }
+      }
+    ";
+    let bytes = InMemoryFileBuilder::new(Rc::new(parse_message_type(schema).unwrap()))
+      .with_columns(vec![
+        ColumnData::new::<Int32Type>(vec![1]),
+        ColumnData::new::<Int32Type>(vec![2]),
+        ColumnData::new::<Int32Type>(vec![3]),
+        ColumnData::new::<Int32Type>(vec![4]),
+        ColumnData::new::<Int32Type>(vec![5])
+      ])
+      .build()
+      .unwrap();
+    let path = get_temp_filename("test_file_reader_rows_duplicate_fields.parquet");
+    fs::File::create(&path).unwrap().write_all(&bytes).unwrap();
+
+    let read_rows = |policy: DuplicateFieldPolicy| -> Result<Vec<Row>> {
+      let file_reader = SerializedFileReader::new(fs::File::open(&path).unwrap())?
+        .with_duplicate_field_policy(policy);
+      let rows: Vec<Row> = file_reader.get_row_iter(None)?.collect();
+      let row_group_reader = file_reader.get_row_group(0)?;
+      assert_eq!(row_group_reader.get_row_iter(None)?.collect::<Vec<Row>>(), rows);
+      Ok(rows)
+    };
+
+    let expected_rows = vec![
+      Row::Group(vec![
+        ("a".to_string(), Row::Int(1)),
+        ("a".to_string(), Row::Int(2)),
+        ("b".to_string(), Row::Group(vec![
+          ("c".to_string(), Row::Int(3)),
+          ("c".to_string(), Row::Int(4)),
+          ("c_1".to_string(), Row::Int(5))
+        ]))
+      ])
+    ];
+    assert_eq!(read_rows(DuplicateFieldPolicy::Positional).unwrap(), expected_rows);
+
+    let expected_rows = vec![
+      Row::Group(vec![
+        ("a".to_string(), Row::Int(1)),
+        ("a_1".to_string(), Row::Int(2)),
+        ("b".to_string(), Row::Group(vec![
+          ("c".to_string(), Row::Int(3)),
+          ("c_2".to_string(), Row::Int(4)),
+          ("c_1".to_string(), Row::Int(5))
+        ]))
+      ])
+    ];
+    assert_eq!(read_rows(DuplicateFieldPolicy::Suffix).unwrap(), expected_rows);
+
+    assert_eq!(
+      read_rows(DuplicateFieldPolicy::Error).unwrap_err(),
+      general_err!("Schema has duplicate field a")
+    );
+
+    // Projection without duplicate fields is allowed
+    let proj = parse_message_type("
+      message schema {
+        REQUIRED GROUP b {
+          REQUIRED INT32 c_1;
+        }
+      }
+    ").unwrap();
+    let file_reader = SerializedFileReader::new(fs::File::open(&path).unwrap())
+      .unwrap()
+      .with_duplicate_field_policy(DuplicateFieldPolicy::Error);
+    let rows: Vec<Row> = file_reader.get_row_iter(Some(proj)).unwrap().collect();
+    assert_eq!(
+      rows,
+      vec![Row::Group(vec![
+        ("b".to_string(), Row::Group(vec![("c_1".to_string(), Row::Int(5))]))])]
+    );
+
+    let proj = parse_message_type("
+      message schema {
+        REQUIRED GROUP b {
+          REQUIRED INT32 c;
+          REQUIRED INT32 c;
+        }
+      }
+    ").unwrap();
+    assert_eq!(
+      file_reader.get_row_iter(Some(proj)).err().unwrap(),
+      general_err!("Schema has duplicate field b.c")
+    );
+  }
+
   fn test_file_reader_rows(file_name: &str, schema: Option<Type>) -> Result<Vec<Row>> {
     let file = get_test_file(file_name);
     let file_reader: Box<FileReader> = Box::new(SerializedFileReader::new(file)?);
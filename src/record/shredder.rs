@@ -0,0 +1,1345 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains Dremel shredding of nested values into leaf columns.
+//!
+//! [`Shredder`](`Shredder`) walks [`Row`](`::record::api::Row`)s against a schema and
+//! produces definition levels, repetition levels and non-null values of every leaf
+//! column, which can be passed to `write_batch` of a typed column writer. Nested values
+//! are represented the same way as rows returned by the record reader: structs are
+//! `Row::Group`, lists are `Row::List` and maps are `Row::Map`.
+//!
+//! [`ColumnarBatch`](`ColumnarBatch`) holds shredded columns of a number of rows and
+//! converts them back into rows, so columnar and row-based processing can be mixed
+//! within one pipeline.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::rc::Rc;
+//!
+//! use parquet::record::api::Row;
+//! use parquet::record::shredder::{ColumnValues, ColumnarBatch, Shredder};
+//! use parquet::schema::parser::parse_message_type;
+//! use parquet::schema::types::SchemaDescriptor;
+//!
+//! let schema = "
+//!   message schema {
+//!     OPTIONAL group a (LIST) {
+//!       REPEATED group list {
+//!         OPTIONAL INT32 element;
+//!       }
+//!     }
+//!   }
+//! ";
+//! let schema = Rc::new(parse_message_type(schema).unwrap());
+//! let descr = Rc::new(SchemaDescriptor::new(schema));
+//!
+//! let mut shredder = Shredder::new(descr.clone());
+//! shredder.shred(&Row::Group(vec![
+//!   ("a".to_string(), Row::List(vec![Row::Int(1), Row::Null, Row::Int(2)]))
+//! ])).unwrap();
+//! shredder.shred(&Row::Group(vec![("a".to_string(), Row::Null)])).unwrap();
+//!
+//! let column = &shredder.columns()[0];
+//! assert_eq!(column.values(), &ColumnValues::Int32(vec![1, 2]));
+//! assert_eq!(column.def_levels(), Some(&[3, 2, 3, 0][..]));
+//! assert_eq!(column.rep_levels(), Some(&[0, 1, 1, 0][..]));
+//!
+//! let batch = ColumnarBatch::new(descr, shredder.into_columns()).unwrap();
+//! assert_eq!(batch.num_rows(), 2);
+//! assert_eq!(
+//!   batch.to_rows().unwrap()[1],
+//!   Row::Group(vec![("a".to_string(), Row::Null)])
+//! );
+//! ```
+
+use basic::{LogicalType, Repetition, Type as PhysicalType};
+use column::writer::ColumnWriter;
+use data_type::{ByteArray, Int96};
+use errors::{ParquetError, Result};
+use file::compat;
+use logical::{LogicalValue, PhysicalValue};
+use record::api::Row;
+use schema::types::{ColumnDescPtr, ColumnDescriptor, SchemaDescPtr, TypePtr};
+
+/// Shredder of rows into definition levels, repetition levels and values of each leaf
+/// column of a schema.
+///
+/// Shredded values are buffered in memory until they are cleared, or written into
+/// column writers with [`ShreddedColumn::write_into`](`ShreddedColumn::write_into`).
+pub struct Shredder {
+  root: Writer,
+  columns: Vec<ShreddedColumn>,
+  num_rows: usize
+}
+
+impl Shredder {
+  /// Creates new shredder for the schema.
+  pub fn new(descr: SchemaDescPtr) -> Self {
+    let columns = (0..descr.num_columns())
+      .map(|i| ShreddedColumn::new(descr.column(i)))
+      .collect();
+    Self { root: Writer::root(&descr), columns: columns, num_rows: 0 }
+  }
+
+  /// Sets whether `Row::Timestamp` values can be shredded into INT96 columns, and
+  /// returns itself. Disabled by default.
+  pub fn with_int96_timestamps_enabled(mut self, enabled: bool) -> Self {
+    for column in self.columns.iter_mut() {
+      column.int96_timestamps_enabled = enabled;
+    }
+    self
+  }
+
+  /// Shreds a row, which must be a `Row::Group` with fields of the message type, into
+  /// leaf columns. Missing fields are shredded as nulls.
+  ///
+  /// Returns an error if the row does not match the schema, in which case nothing is
+  /// buffered for the row.
+  pub fn shred(&mut self, row: &Row) -> Result<()> {
+    let lengths: Vec<(usize, usize)> =
+      self.columns.iter().map(|column| column.len()).collect();
+    match self.root.write(row, 0, &mut self.columns) {
+      Ok(()) => {
+        self.num_rows += 1;
+        Ok(())
+      },
+      Err(error) => {
+        for (column, &len) in self.columns.iter_mut().zip(lengths.iter()) {
+          column.truncate(len);
+        }
+        Err(error)
+      }
+    }
+  }
+
+  /// Returns the number of rows shredded since the last clear.
+  pub fn num_rows(&self) -> usize {
+    self.num_rows
+  }
+
+  /// Returns shredded leaf columns in the order of the schema.
+  pub fn columns(&self) -> &[ShreddedColumn] {
+    &self.columns
+  }
+
+  /// Returns mutable shredded leaf columns in the order of the schema, e.g. to write
+  /// them into column writers.
+  pub fn columns_mut(&mut self) -> &mut [ShreddedColumn] {
+    &mut self.columns
+  }
+
+  /// Discards all shredded rows.
+  pub fn clear(&mut self) {
+    for column in self.columns.iter_mut() {
+      column.clear();
+    }
+    self.num_rows = 0;
+  }
+
+  /// Consumes shredder and returns shredded leaf columns in the order of the schema,
+  /// e.g. to create a [`ColumnarBatch`](`ColumnarBatch`).
+  pub fn into_columns(self) -> Vec<ShreddedColumn> {
+    self.columns
+  }
+}
+
+/// Batch of rows in columnar form, where every leaf column of a schema is stored as
+/// definition levels, repetition levels and non-null values.
+///
+/// Batch is created from rows with `from_rows`, or from columns produced by other
+/// means, e.g. by a column reader, with `new`; and is converted back into rows with
+/// `to_rows`, which assembles nested values the same way as the record reader.
+pub struct ColumnarBatch {
+  descr: SchemaDescPtr,
+  columns: Vec<ShreddedColumn>,
+  num_rows: usize
+}
+
+impl ColumnarBatch {
+  /// Creates batch from leaf columns of the schema `descr` in the order of the
+  /// schema.
+  ///
+  /// Returns an error if columns do not match leaf columns of the schema, or columns
+  /// have different number of rows.
+  pub fn new(descr: SchemaDescPtr, columns: Vec<ShreddedColumn>) -> Result<Self> {
+    if columns.len() != descr.num_columns() {
+      return Err(general_err!(
+        "Expected {} columns, found {}",
+        descr.num_columns(),
+        columns.len()
+      ));
+    }
+    let mut num_rows = None;
+    for (i, column) in columns.iter().enumerate() {
+      let column_descr = descr.column(i);
+      if column.descr.path() != column_descr.path() ||
+          column.descr.physical_type() != column_descr.physical_type() {
+        return Err(general_err!(
+          "Column {} does not match column {} of the schema",
+          column.descr.path().string(),
+          column_descr.path().string()
+        ));
+      }
+      let column_rows = column.num_rows();
+      match num_rows {
+        Some(rows) if rows != column_rows => {
+          return Err(general_err!(
+            "Incorrect number of rows in column {}, expected {} != {} rows",
+            column.descr.path().string(),
+            rows,
+            column_rows
+          ));
+        },
+        _ => num_rows = Some(column_rows)
+      }
+    }
+    Ok(Self { descr: descr, columns: columns, num_rows: num_rows.unwrap_or(0) })
+  }
+
+  /// Creates batch by shredding rows, see [`Shredder`](`Shredder`).
+  pub fn from_rows(descr: SchemaDescPtr, rows: &[Row]) -> Result<Self> {
+    let mut shredder = Shredder::new(descr.clone());
+    for row in rows {
+      shredder.shred(row)?;
+    }
+    Self::new(descr, shredder.into_columns())
+  }
+
+  /// Returns schema descriptor of the batch.
+  pub fn descr(&self) -> &SchemaDescPtr {
+    &self.descr
+  }
+
+  /// Returns the number of rows in the batch.
+  pub fn num_rows(&self) -> usize {
+    self.num_rows
+  }
+
+  /// Returns leaf columns in the order of the schema.
+  pub fn columns(&self) -> &[ShreddedColumn] {
+    &self.columns
+  }
+
+  /// Consumes batch and returns leaf columns in the order of the schema, e.g. to write
+  /// them into column writers.
+  pub fn into_columns(self) -> Vec<ShreddedColumn> {
+    self.columns
+  }
+
+  /// Assembles rows of the batch. Every row is a `Row::Group` with all fields of the
+  /// message type.
+  ///
+  /// Returns an error if levels of columns are not consistent with each other.
+  pub fn to_rows(&self) -> Result<Vec<Row>> {
+    let root = Writer::root(&self.descr);
+    let mut cursors: Vec<Cursor> = self.columns.iter().map(Cursor::new).collect();
+    let mut rows = Vec::with_capacity(self.num_rows);
+    for _ in 0..self.num_rows {
+      rows.push(root.read(&mut cursors)?);
+    }
+    for cursor in &cursors {
+      if cursor.has_next() {
+        return Err(general_err!(
+          "Column {} has values that do not belong to any of {} rows",
+          cursor.column.descr.path().string(),
+          self.num_rows
+        ));
+      }
+    }
+    Ok(rows)
+  }
+}
+
+/// Position of record assembly in a shredded column.
+struct Cursor<'a> {
+  column: &'a ShreddedColumn,
+  level_index: usize,
+  value_index: usize
+}
+
+impl<'a> Cursor<'a> {
+  fn new(column: &'a ShreddedColumn) -> Self {
+    Self { column: column, level_index: 0, value_index: 0 }
+  }
+
+  #[inline]
+  fn has_next(&self) -> bool {
+    self.level_index < self.column.num_levels()
+  }
+
+  /// Returns current definition level, or 0 if all levels have been read.
+  #[inline]
+  fn def_level(&self) -> i16 {
+    match self.column.def_levels() {
+      Some(levels) => levels.get(self.level_index).cloned().unwrap_or(0),
+      None => 0
+    }
+  }
+
+  /// Returns current repetition level, or 0 if all levels have been read.
+  #[inline]
+  fn rep_level(&self) -> i16 {
+    match self.column.rep_levels() {
+      Some(levels) => levels.get(self.level_index).cloned().unwrap_or(0),
+      None => 0
+    }
+  }
+
+  /// Skips current null value.
+  fn advance(&mut self) -> Result<()> {
+    if !self.has_next() {
+      return Err(self.eof());
+    }
+    self.level_index += 1;
+    Ok(())
+  }
+
+  /// Returns current non-null value and advances to the next one.
+  fn read(&mut self) -> Result<Row> {
+    if !self.has_next() {
+      return Err(self.eof());
+    }
+    let value = match self.column.row_value(self.value_index) {
+      Some(value) => value,
+      None => return Err(self.eof())
+    };
+    self.level_index += 1;
+    self.value_index += 1;
+    Ok(value)
+  }
+
+  fn eof(&self) -> ParquetError {
+    general_err!("Not enough values in column {}", self.column.descr.path().string())
+  }
+}
+
+/// Writer tree for record shredding, mirrors reader tree of the record reader.
+enum Writer {
+  // Primitive writer with type information and index of the leaf column
+  PrimitiveWriter(TypePtr, usize),
+  // Optional writer with definition level of a parent and a writer
+  OptionWriter(i16, Box<Writer>),
+  // Group (struct) writer with type information and child writers with field names.
+  // When it represents message type, type information is None
+  GroupWriter(Option<TypePtr>, Vec<(String, Writer)>),
+  // Writer of repeated values, e.g. lists, contains type information, definition level
+  // and repetition level of an empty list and a child writer of elements
+  RepeatedWriter(TypePtr, i16, i16, Box<Writer>),
+  // Writer of key-value pairs, e.g. maps, contains type information, definition level,
+  // repetition level, child writer for keys and child writer for values
+  KeyValueWriter(TypePtr, i16, i16, Box<Writer>, Box<Writer>)
+}
+
+impl Writer {
+  /// Builds tree of writers for the message type of the schema.
+  fn root(descr: &SchemaDescPtr) -> Writer {
+    let mut leaf_index = 0;
+    let mut fields = Vec::new();
+    for field in descr.root_schema().get_fields() {
+      let writer = Writer::tree(field.clone(), 0, 0, false, &mut leaf_index);
+      fields.push((String::from(field.name()), writer));
+    }
+    assert_eq!(leaf_index, descr.num_columns(), "Invalid schema {:?}", descr);
+    Writer::GroupWriter(None, fields)
+  }
+
+  /// Builds tree of writers for the field recursively. Leaf columns are numbered in
+  /// the order of schema traversal, which is the order of columns in a row group.
+  fn tree(
+    field: TypePtr,
+    mut curr_def_level: i16,
+    mut curr_rep_level: i16,
+    is_element: bool,
+    leaf_index: &mut usize
+  ) -> Writer {
+    assert!(field.get_basic_info().has_repetition());
+    let repetition = field.get_basic_info().repetition();
+    match repetition {
+      Repetition::OPTIONAL => {
+        curr_def_level += 1;
+      },
+      Repetition::REPEATED => {
+        curr_def_level += 1;
+        curr_rep_level += 1;
+      },
+      _ => {}
+    }
+
+    let writer = if field.is_primitive() {
+      *leaf_index += 1;
+      Writer::PrimitiveWriter(field.clone(), *leaf_index - 1)
+    } else {
+      match field.get_basic_info().logical_type() {
+        LogicalType::LIST => {
+          assert_eq!(field.get_fields().len(), 1, "Invalid list type {:?}", field);
+
+          let repeated_field = field.get_fields()[0].clone();
+          assert_eq!(
+            repeated_field.get_basic_info().repetition(),
+            Repetition::REPEATED,
+            "Invalid list type {:?}",
+            field
+          );
+
+          let writer = if compat::is_list_element(&field, &repeated_field) {
+            // Backward compatible lists, repeated field is the element itself
+            Writer::tree(
+              repeated_field, curr_def_level, curr_rep_level, true, leaf_index)
+          } else {
+            let child_field = repeated_field.get_fields()[0].clone();
+            Writer::tree(
+              child_field, curr_def_level + 1, curr_rep_level + 1, false, leaf_index)
+          };
+          Writer::RepeatedWriter(
+            field.clone(), curr_def_level, curr_rep_level, Box::new(writer))
+        },
+        LogicalType::MAP | LogicalType::MAP_KEY_VALUE => {
+          assert_eq!(field.get_fields().len(), 1, "Invalid map type: {:?}", field);
+          let key_value_type = field.get_fields()[0].clone();
+          assert_eq!(
+            key_value_type.get_fields().len(),
+            2,
+            "Invalid map type: {:?}",
+            field
+          );
+
+          let key_writer = Writer::tree(key_value_type.get_fields()[0].clone(),
+            curr_def_level + 1, curr_rep_level + 1, false, leaf_index);
+          let value_writer = Writer::tree(key_value_type.get_fields()[1].clone(),
+            curr_def_level + 1, curr_rep_level + 1, false, leaf_index);
+          Writer::KeyValueWriter(field.clone(), curr_def_level, curr_rep_level,
+            Box::new(key_writer), Box::new(value_writer))
+        },
+        _ => {
+          let mut writers = Vec::new();
+          for child in field.get_fields() {
+            let writer = Writer::tree(
+              child.clone(), curr_def_level, curr_rep_level, false, leaf_index);
+            writers.push((String::from(child.name()), writer));
+          }
+          Writer::GroupWriter(Some(field.clone()), writers)
+        }
+      }
+    };
+
+    match repetition {
+      Repetition::OPTIONAL => Writer::OptionWriter(curr_def_level - 1, Box::new(writer)),
+      // Repeated field outside of a list is written from a list of its values
+      Repetition::REPEATED if !is_element => Writer::RepeatedWriter(
+        field, curr_def_level - 1, curr_rep_level - 1, Box::new(writer)),
+      _ => writer
+    }
+  }
+
+  /// Returns description of the field for error messages.
+  fn description(&self) -> String {
+    match *self {
+      Writer::PrimitiveWriter(ref field, _) => format!("field {}", field.name()),
+      Writer::OptionWriter(_, ref writer) => writer.description(),
+      Writer::GroupWriter(Some(ref field), _) => format!("group {}", field.name()),
+      Writer::GroupWriter(None, _) => String::from("schema"),
+      Writer::RepeatedWriter(ref field, _, _, _) => format!("list {}", field.name()),
+      Writer::KeyValueWriter(ref field, _, _, _, _) => format!("map {}", field.name())
+    }
+  }
+
+  /// Shreds value into leaf columns, where `rep_level` is the repetition level of the
+  /// first value written into each leaf column.
+  fn write(
+    &self,
+    value: &Row,
+    rep_level: i16,
+    columns: &mut [ShreddedColumn]
+  ) -> Result<()> {
+    match *self {
+      Writer::PrimitiveWriter(_, index) => {
+        if *value == Row::Null {
+          return Err(general_err!("Cannot write null into required field {}",
+            columns[index].descr.path().string()));
+        }
+        columns[index].push(value, rep_level)
+      },
+      Writer::OptionWriter(def_level, ref writer) => {
+        if *value == Row::Null {
+          writer.write_null(def_level, rep_level, columns);
+          Ok(())
+        } else {
+          writer.write(value, rep_level, columns)
+        }
+      },
+      Writer::GroupWriter(_, ref writers) => {
+        let fields = match *value {
+          Row::Group(ref fields) => fields,
+          _ => {
+            return Err(general_err!("Cannot write {} as {}", value, self.description()))
+          }
+        };
+        for &(ref name, _) in fields {
+          if !writers.iter().any(|&(ref field_name, _)| field_name == name) {
+            return Err(general_err!("Field {} does not exist in {}",
+              name, self.description()));
+          }
+        }
+        for &(ref name, ref writer) in writers {
+          // Missing fields are written as nulls
+          let field_value = fields.iter()
+            .find(|&&(ref field_name, _)| field_name == name)
+            .map(|&(_, ref field_value)| field_value)
+            .unwrap_or(&Row::Null);
+          writer.write(field_value, rep_level, columns)?;
+        }
+        Ok(())
+      },
+      Writer::RepeatedWriter(_, def_level, list_rep_level, ref writer) => {
+        let elements = match *value {
+          Row::List(ref elements) => elements,
+          _ => {
+            return Err(general_err!("Cannot write {} as {}", value, self.description()))
+          }
+        };
+        if elements.is_empty() {
+          writer.write_null(def_level, rep_level, columns);
+        }
+        for (i, element) in elements.iter().enumerate() {
+          let element_rep_level = if i == 0 { rep_level } else { list_rep_level + 1 };
+          writer.write(element, element_rep_level, columns)?;
+        }
+        Ok(())
+      },
+      Writer::KeyValueWriter(_, def_level, map_rep_level, ref keys, ref values) => {
+        let pairs = match *value {
+          Row::Map(ref pairs) => pairs,
+          _ => {
+            return Err(general_err!("Cannot write {} as {}", value, self.description()))
+          }
+        };
+        if pairs.is_empty() {
+          keys.write_null(def_level, rep_level, columns);
+          values.write_null(def_level, rep_level, columns);
+        }
+        for (i, &(ref key, ref value)) in pairs.iter().enumerate() {
+          let pair_rep_level = if i == 0 { rep_level } else { map_rep_level + 1 };
+          keys.write(key, pair_rep_level, columns)?;
+          values.write(value, pair_rep_level, columns)?;
+        }
+        Ok(())
+      }
+    }
+  }
+
+  /// Writes null with the definition level into all leaf columns of this writer.
+  fn write_null(&self, def_level: i16, rep_level: i16, columns: &mut [ShreddedColumn]) {
+    match *self {
+      Writer::PrimitiveWriter(_, index) => {
+        columns[index].push_null(def_level, rep_level);
+      },
+      Writer::OptionWriter(_, ref writer) => {
+        writer.write_null(def_level, rep_level, columns);
+      },
+      Writer::GroupWriter(_, ref writers) => {
+        for &(_, ref writer) in writers {
+          writer.write_null(def_level, rep_level, columns);
+        }
+      },
+      Writer::RepeatedWriter(_, _, _, ref writer) => {
+        writer.write_null(def_level, rep_level, columns);
+      },
+      Writer::KeyValueWriter(_, _, _, ref keys, ref values) => {
+        keys.write_null(def_level, rep_level, columns);
+        values.write_null(def_level, rep_level, columns);
+      }
+    }
+  }
+
+  /// Assembles current value from leaf columns of this writer, which is the reverse of
+  /// `write`, and advances their cursors.
+  fn read(&self, cursors: &mut [Cursor]) -> Result<Row> {
+    match *self {
+      Writer::PrimitiveWriter(_, index) => cursors[index].read(),
+      Writer::OptionWriter(def_level, ref writer) => {
+        if writer.def_level(cursors) > def_level {
+          writer.read(cursors)
+        } else {
+          writer.advance(cursors)?;
+          Ok(Row::Null)
+        }
+      },
+      Writer::GroupWriter(_, ref writers) => {
+        let mut fields = Vec::with_capacity(writers.len());
+        for &(ref name, ref writer) in writers {
+          fields.push((name.clone(), writer.read(cursors)?));
+        }
+        Ok(Row::Group(fields))
+      },
+      Writer::RepeatedWriter(_, def_level, list_rep_level, ref writer) => {
+        let mut elements = Vec::new();
+        loop {
+          if writer.def_level(cursors) > def_level {
+            elements.push(writer.read(cursors)?);
+          } else {
+            // Empty list
+            writer.advance(cursors)?;
+            break;
+          }
+          if !writer.has_next(cursors) || writer.rep_level(cursors) <= list_rep_level {
+            break;
+          }
+        }
+        Ok(Row::List(elements))
+      },
+      Writer::KeyValueWriter(_, def_level, map_rep_level, ref keys, ref values) => {
+        let mut pairs = Vec::new();
+        loop {
+          if keys.def_level(cursors) > def_level {
+            let key = keys.read(cursors)?;
+            pairs.push((key, values.read(cursors)?));
+          } else {
+            // Empty map
+            keys.advance(cursors)?;
+            values.advance(cursors)?;
+            break;
+          }
+          if !keys.has_next(cursors) || keys.rep_level(cursors) <= map_rep_level {
+            break;
+          }
+        }
+        Ok(Row::Map(pairs))
+      }
+    }
+  }
+
+  /// Skips current null value in all leaf columns of this writer.
+  fn advance(&self, cursors: &mut [Cursor]) -> Result<()> {
+    match *self {
+      Writer::PrimitiveWriter(_, index) => cursors[index].advance(),
+      Writer::OptionWriter(_, ref writer) => writer.advance(cursors),
+      Writer::GroupWriter(_, ref writers) => {
+        for &(_, ref writer) in writers {
+          writer.advance(cursors)?;
+        }
+        Ok(())
+      },
+      Writer::RepeatedWriter(_, _, _, ref writer) => writer.advance(cursors),
+      Writer::KeyValueWriter(_, _, _, ref keys, ref values) => {
+        keys.advance(cursors)?;
+        values.advance(cursors)
+      }
+    }
+  }
+
+  /// Returns the first leaf column of this writer, whose levels are used to assemble
+  /// values of this writer.
+  fn first_leaf(&self) -> Option<usize> {
+    match *self {
+      Writer::PrimitiveWriter(_, index) => Some(index),
+      Writer::OptionWriter(_, ref writer) => writer.first_leaf(),
+      Writer::GroupWriter(_, ref writers) => {
+        writers.first().and_then(|&(_, ref writer)| writer.first_leaf())
+      },
+      Writer::RepeatedWriter(_, _, _, ref writer) => writer.first_leaf(),
+      Writer::KeyValueWriter(_, _, _, ref keys, _) => keys.first_leaf()
+    }
+  }
+
+  #[inline]
+  fn has_next(&self, cursors: &[Cursor]) -> bool {
+    self.first_leaf().map(|index| cursors[index].has_next()).unwrap_or(false)
+  }
+
+  #[inline]
+  fn def_level(&self, cursors: &[Cursor]) -> i16 {
+    self.first_leaf().map(|index| cursors[index].def_level()).unwrap_or(0)
+  }
+
+  #[inline]
+  fn rep_level(&self, cursors: &[Cursor]) -> i16 {
+    self.first_leaf().map(|index| cursors[index].rep_level()).unwrap_or(0)
+  }
+}
+
+/// Values of a leaf column, typed by physical type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnValues {
+  Bool(Vec<bool>),
+  Int32(Vec<i32>),
+  Int64(Vec<i64>),
+  Int96(Vec<Int96>),
+  Float(Vec<f32>),
+  Double(Vec<f64>),
+  ByteArray(Vec<ByteArray>),
+  FixedLenByteArray(Vec<ByteArray>)
+}
+
+impl ColumnValues {
+  /// Returns physical type of values.
+  pub fn physical_type(&self) -> PhysicalType {
+    match *self {
+      ColumnValues::Bool(_) => PhysicalType::BOOLEAN,
+      ColumnValues::Int32(_) => PhysicalType::INT32,
+      ColumnValues::Int64(_) => PhysicalType::INT64,
+      ColumnValues::Int96(_) => PhysicalType::INT96,
+      ColumnValues::Float(_) => PhysicalType::FLOAT,
+      ColumnValues::Double(_) => PhysicalType::DOUBLE,
+      ColumnValues::ByteArray(_) => PhysicalType::BYTE_ARRAY,
+      ColumnValues::FixedLenByteArray(_) => PhysicalType::FIXED_LEN_BYTE_ARRAY
+    }
+  }
+}
+
+/// Definition levels, repetition levels and non-null values of a leaf column, ready to
+/// be written with `write_batch` of the typed column writer.
+///
+/// Levels are only buffered if the maximum level of the column is greater than 0.
+pub struct ShreddedColumn {
+  descr: ColumnDescPtr,
+  def_levels: Vec<i16>,
+  rep_levels: Vec<i16>,
+  values: ColumnValues,
+  int96_timestamps_enabled: bool
+}
+
+impl ShreddedColumn {
+  fn new(descr: ColumnDescPtr) -> Self {
+    let values = match descr.physical_type() {
+      PhysicalType::BOOLEAN => ColumnValues::Bool(Vec::new()),
+      PhysicalType::INT32 => ColumnValues::Int32(Vec::new()),
+      PhysicalType::INT64 => ColumnValues::Int64(Vec::new()),
+      PhysicalType::INT96 => ColumnValues::Int96(Vec::new()),
+      PhysicalType::FLOAT => ColumnValues::Float(Vec::new()),
+      PhysicalType::DOUBLE => ColumnValues::Double(Vec::new()),
+      PhysicalType::BYTE_ARRAY => ColumnValues::ByteArray(Vec::new()),
+      PhysicalType::FIXED_LEN_BYTE_ARRAY => ColumnValues::FixedLenByteArray(Vec::new())
+    };
+    Self {
+      descr: descr,
+      def_levels: Vec::new(),
+      rep_levels: Vec::new(),
+      values: values,
+      int96_timestamps_enabled: false
+    }
+  }
+
+  /// Creates shredded column from levels and non-null values, e.g. read with a column
+  /// reader. Levels must be `None` if the corresponding maximum level of the column is
+  /// 0.
+  ///
+  /// Returns an error if values do not match physical type of the column, or levels
+  /// are not valid for the column or do not match the number of values.
+  pub fn from_values(
+    descr: ColumnDescPtr,
+    values: ColumnValues,
+    def_levels: Option<Vec<i16>>,
+    rep_levels: Option<Vec<i16>>
+  ) -> Result<Self> {
+    if values.physical_type() != descr.physical_type() {
+      return Err(general_err!(
+        "Cannot create column {} of type {} from {} values",
+        descr.path().string(),
+        descr.physical_type(),
+        values.physical_type()
+      ));
+    }
+    let max_def_level = descr.max_def_level();
+    let max_rep_level = descr.max_rep_level();
+    let def_levels = check_levels(&descr, "definition", max_def_level, def_levels)?;
+    let rep_levels = check_levels(&descr, "repetition", max_rep_level, rep_levels)?;
+    if max_rep_level > 0 && def_levels.len() != rep_levels.len() {
+      return Err(general_err!(
+        "Column {} has {} definition levels, but {} repetition levels",
+        descr.path().string(),
+        def_levels.len(),
+        rep_levels.len()
+      ));
+    }
+    if rep_levels.first().map(|&level| level != 0).unwrap_or(false) {
+      return Err(general_err!(
+        "First repetition level of column {} must be 0",
+        descr.path().string()
+      ));
+    }
+
+    let column = Self {
+      descr: descr,
+      def_levels: def_levels,
+      rep_levels: rep_levels,
+      values: values,
+      int96_timestamps_enabled: false
+    };
+    let (num_levels, num_values) = column.len();
+    let num_non_null = if max_def_level > 0 {
+      column.def_levels.iter().filter(|&&level| level == max_def_level).count()
+    } else {
+      num_levels
+    };
+    if num_non_null != num_values {
+      return Err(general_err!(
+        "Column {} has {} non-null levels, but {} values",
+        column.descr.path().string(),
+        num_non_null,
+        num_values
+      ));
+    }
+    Ok(column)
+  }
+
+  /// Returns descriptor of the leaf column.
+  pub fn descr(&self) -> &ColumnDescPtr {
+    &self.descr
+  }
+
+  /// Returns definition levels, or `None` if the column is required.
+  pub fn def_levels(&self) -> Option<&[i16]> {
+    if self.descr.max_def_level() > 0 {
+      Some(&self.def_levels[..])
+    } else {
+      None
+    }
+  }
+
+  /// Returns repetition levels, or `None` if the column is not repeated.
+  pub fn rep_levels(&self) -> Option<&[i16]> {
+    if self.descr.max_rep_level() > 0 {
+      Some(&self.rep_levels[..])
+    } else {
+      None
+    }
+  }
+
+  /// Returns non-null values.
+  pub fn values(&self) -> &ColumnValues {
+    &self.values
+  }
+
+  /// Returns the number of buffered levels, which is the number of values including
+  /// nulls and empty lists.
+  pub fn num_levels(&self) -> usize {
+    self.len().0
+  }
+
+  /// Returns the number of buffered non-null values.
+  pub fn num_values(&self) -> usize {
+    self.len().1
+  }
+
+  /// Returns the number of rows, which is the number of repetition levels equal to 0
+  /// for repeated columns, and the number of levels otherwise.
+  pub fn num_rows(&self) -> usize {
+    if self.descr.max_rep_level() > 0 {
+      self.rep_levels.iter().filter(|&&level| level == 0).count()
+    } else {
+      self.num_levels()
+    }
+  }
+
+  /// Discards buffered levels and values.
+  pub fn clear(&mut self) {
+    self.truncate((0, 0));
+  }
+
+  /// Returns a tuple of the number of buffered levels and values, used to discard
+  /// values of a row that failed to be shredded.
+  fn len(&self) -> (usize, usize) {
+    let num_values = match self.values {
+      ColumnValues::Bool(ref values) => values.len(),
+      ColumnValues::Int32(ref values) => values.len(),
+      ColumnValues::Int64(ref values) => values.len(),
+      ColumnValues::Int96(ref values) => values.len(),
+      ColumnValues::Float(ref values) => values.len(),
+      ColumnValues::Double(ref values) => values.len(),
+      ColumnValues::ByteArray(ref values) => values.len(),
+      ColumnValues::FixedLenByteArray(ref values) => values.len()
+    };
+    if self.descr.max_def_level() == 0 {
+      // Levels of required columns are not buffered
+      (num_values, num_values)
+    } else {
+      (self.def_levels.len(), num_values)
+    }
+  }
+
+  fn truncate(&mut self, len: (usize, usize)) {
+    let (num_levels, num_values) = len;
+    self.def_levels.truncate(num_levels);
+    self.rep_levels.truncate(num_levels);
+    match self.values {
+      ColumnValues::Bool(ref mut values) => values.truncate(num_values),
+      ColumnValues::Int32(ref mut values) => values.truncate(num_values),
+      ColumnValues::Int64(ref mut values) => values.truncate(num_values),
+      ColumnValues::Int96(ref mut values) => values.truncate(num_values),
+      ColumnValues::Float(ref mut values) => values.truncate(num_values),
+      ColumnValues::Double(ref mut values) => values.truncate(num_values),
+      ColumnValues::ByteArray(ref mut values) => values.truncate(num_values),
+      ColumnValues::FixedLenByteArray(ref mut values) => values.truncate(num_values)
+    }
+  }
+
+  /// Converts non-null value at the index into a row value, or returns `None` if the
+  /// index is out of bounds.
+  fn row_value(&self, index: usize) -> Option<Row> {
+    let descr = &*self.descr;
+    let value = match self.values {
+      ColumnValues::Bool(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::Int32(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::Int64(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::Int96(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::Float(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::Double(ref values) => Row::from_physical(descr, values.get(index)?),
+      ColumnValues::ByteArray(ref values) => {
+        Row::from_physical(descr, values.get(index)?)
+      },
+      ColumnValues::FixedLenByteArray(ref values) => {
+        Row::from_physical(descr, values.get(index)?)
+      }
+    };
+    Some(value)
+  }
+
+  /// Buffers levels of a null value.
+  fn push_null(&mut self, def_level: i16, rep_level: i16) {
+    self.push_levels(def_level, rep_level);
+  }
+
+  /// Converts and buffers a non-null value.
+  fn push(&mut self, value: &Row, rep_level: i16) -> Result<()> {
+    let descr = &*self.descr;
+    let int96_timestamps_enabled = self.int96_timestamps_enabled;
+    let is_valid = match (&mut self.values, value.to_logical()) {
+      (_, None) => false,
+      (&mut ColumnValues::Int96(_), _) if !int96_timestamps_enabled => false,
+      (&mut ColumnValues::Bool(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::Int32(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::Int64(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::Int96(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::Float(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::Double(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::ByteArray(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      },
+      (&mut ColumnValues::FixedLenByteArray(ref mut values), Some(ref v)) => {
+        push_value(values, v, descr)
+      }
+    };
+    if !is_valid {
+      return Err(general_err!(
+        "Cannot write {} into column {} of type {}",
+        value,
+        self.descr.path().string(),
+        self.descr.physical_type()
+      ));
+    }
+    let max_def_level = self.descr.max_def_level();
+    self.push_levels(max_def_level, rep_level);
+    Ok(())
+  }
+
+  #[inline]
+  fn push_levels(&mut self, def_level: i16, rep_level: i16) {
+    if self.descr.max_def_level() > 0 {
+      self.def_levels.push(def_level);
+    }
+    if self.descr.max_rep_level() > 0 {
+      self.rep_levels.push(rep_level);
+    }
+  }
+
+  /// Writes buffered levels and values into the column writer and clears buffers.
+  ///
+  /// Returns an error if the column writer does not match physical type of the column.
+  pub fn write_into(&mut self, column_writer: &mut ColumnWriter) -> Result<()> {
+    {
+      let def_levels = self.def_levels();
+      let rep_levels = self.rep_levels();
+      match (&self.values, column_writer) {
+        (
+          &ColumnValues::Bool(ref values),
+          &mut ColumnWriter::BoolColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::Int32(ref values),
+          &mut ColumnWriter::Int32ColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::Int64(ref values),
+          &mut ColumnWriter::Int64ColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::Int96(ref values),
+          &mut ColumnWriter::Int96ColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::Float(ref values),
+          &mut ColumnWriter::FloatColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::Double(ref values),
+          &mut ColumnWriter::DoubleColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::ByteArray(ref values),
+          &mut ColumnWriter::ByteArrayColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        (
+          &ColumnValues::FixedLenByteArray(ref values),
+          &mut ColumnWriter::FixedLenByteArrayColumnWriter(ref mut w)
+        ) => w.write_batch(values, def_levels, rep_levels)?,
+        _ => {
+          return Err(general_err!("Column writer does not match column {}",
+            self.descr.path().string()));
+        }
+      };
+    }
+    self.clear();
+    Ok(())
+  }
+}
+
+/// Converts logical value into a value of column `descr` and appends it to `values`.
+/// Returns `false` if the value cannot be stored in the column.
+fn push_value<V: PhysicalValue>(
+  values: &mut Vec<V>,
+  value: &LogicalValue,
+  descr: &ColumnDescriptor
+) -> bool {
+  match V::from_logical(value, descr) {
+    Ok(v) => {
+      values.push(v);
+      true
+    },
+    Err(_) => false
+  }
+}
+
+/// Checks that levels are present if and only if the maximum level is greater than 0,
+/// and that all levels are within the maximum level. Returns levels or an empty vector.
+fn check_levels(
+  descr: &ColumnDescPtr,
+  level_name: &str,
+  max_level: i16,
+  levels: Option<Vec<i16>>
+) -> Result<Vec<i16>> {
+  match levels {
+    Some(levels) => {
+      if max_level == 0 {
+        return Err(general_err!(
+          "Column {} does not have {} levels",
+          descr.path().string(),
+          level_name
+        ));
+      }
+      if let Some(level) = levels.iter().find(|&&level| level < 0 || level > max_level) {
+        return Err(general_err!(
+          "Invalid {} level {} of column {}, maximum level is {}",
+          level_name,
+          level,
+          descr.path().string(),
+          max_level
+        ));
+      }
+      Ok(levels)
+    },
+    None if max_level > 0 => Err(general_err!(
+      "Column {} requires {} levels",
+      descr.path().string(),
+      level_name
+    )),
+    None => Ok(Vec::new())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::rc::Rc;
+
+  use schema::parser::parse_message_type;
+  use schema::types::SchemaDescriptor;
+
+  fn descr(schema: &str) -> SchemaDescPtr {
+    let schema = Rc::new(parse_message_type(schema).unwrap());
+    Rc::new(SchemaDescriptor::new(schema))
+  }
+
+  fn shredder(schema: &str) -> Shredder {
+    Shredder::new(descr(schema))
+  }
+
+  #[test]
+  fn test_shred_nested_values() {
+    let mut shredder = shredder("
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL GROUP tags (LIST) {
+          REPEATED GROUP list {
+            OPTIONAL BYTE_ARRAY element (UTF8);
+          }
+        }
+        OPTIONAL GROUP scores (MAP) {
+          REPEATED GROUP key_value {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL DOUBLE value;
+          }
+        }
+        OPTIONAL GROUP point {
+          REQUIRED INT64 x;
+          OPTIONAL INT64 y;
+        }
+        REPEATED INT32 legacy;
+      }
+    ");
+
+    let rows = vec![
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(1)),
+        ("tags".to_string(), Row::List(vec![
+          Row::Str("a".to_string()), Row::Null, Row::Str("b".to_string())])),
+        ("scores".to_string(), Row::Map(vec![
+          (Row::Str("x".to_string()), Row::Double(1.5)),
+          (Row::Str("y".to_string()), Row::Null)])),
+        ("point".to_string(), Row::Group(vec![("x".to_string(), Row::Long(10))])),
+        ("legacy".to_string(), Row::List(vec![Row::Int(7), Row::Int(8)]))
+      ]),
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(2)),
+        ("tags".to_string(), Row::List(vec![])),
+        ("scores".to_string(), Row::Null),
+        ("point".to_string(), Row::Null),
+        ("legacy".to_string(), Row::List(vec![]))
+      ])
+    ];
+    for row in &rows {
+      shredder.shred(row).unwrap();
+    }
+    assert_eq!(shredder.num_rows(), 2);
+
+    let columns = shredder.columns();
+    assert_eq!(columns.len(), 7);
+
+    assert_eq!(columns[0].values(), &ColumnValues::Int32(vec![1, 2]));
+    assert_eq!(columns[0].def_levels(), None);
+    assert_eq!(columns[0].rep_levels(), None);
+
+    assert_eq!(
+      columns[1].values(),
+      &ColumnValues::ByteArray(vec![ByteArray::from("a"), ByteArray::from("b")])
+    );
+    assert_eq!(columns[1].def_levels(), Some(&[3, 2, 3, 1][..]));
+    assert_eq!(columns[1].rep_levels(), Some(&[0, 1, 1, 0][..]));
+
+    assert_eq!(
+      columns[2].values(),
+      &ColumnValues::ByteArray(vec![ByteArray::from("x"), ByteArray::from("y")])
+    );
+    assert_eq!(columns[2].def_levels(), Some(&[2, 2, 0][..]));
+    assert_eq!(columns[2].rep_levels(), Some(&[0, 1, 0][..]));
+    assert_eq!(columns[3].values(), &ColumnValues::Double(vec![1.5]));
+    assert_eq!(columns[3].def_levels(), Some(&[3, 2, 0][..]));
+    assert_eq!(columns[3].rep_levels(), Some(&[0, 1, 0][..]));
+
+    assert_eq!(columns[4].values(), &ColumnValues::Int64(vec![10]));
+    assert_eq!(columns[4].def_levels(), Some(&[1, 0][..]));
+    assert_eq!(columns[4].rep_levels(), None);
+    assert_eq!(columns[5].values(), &ColumnValues::Int64(vec![]));
+    assert_eq!(columns[5].def_levels(), Some(&[1, 0][..]));
+
+    // Repeated field outside of a list
+    assert_eq!(columns[6].values(), &ColumnValues::Int32(vec![7, 8]));
+    assert_eq!(columns[6].def_levels(), Some(&[1, 1, 0][..]));
+    assert_eq!(columns[6].rep_levels(), Some(&[0, 1, 0][..]));
+
+    shredder.clear();
+    assert_eq!(shredder.num_rows(), 0);
+    assert!(shredder.columns().iter().all(|column| {
+      column.num_levels() == 0 && column.num_values() == 0
+    }));
+  }
+
+  #[test]
+  fn test_columnar_batch_round_trip() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL GROUP tags (LIST) {
+          REPEATED GROUP list {
+            OPTIONAL BYTE_ARRAY element (UTF8);
+          }
+        }
+        OPTIONAL GROUP scores (MAP) {
+          REPEATED GROUP key_value {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL DOUBLE value;
+          }
+        }
+        OPTIONAL GROUP point {
+          REQUIRED INT64 x;
+          OPTIONAL INT64 y;
+        }
+        REPEATED INT32 legacy;
+      }
+    ";
+    let descr = descr(schema);
+    let rows = vec![
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(1)),
+        ("tags".to_string(), Row::List(vec![
+          Row::Str("a".to_string()), Row::Null, Row::Str("b".to_string())])),
+        ("scores".to_string(), Row::Map(vec![
+          (Row::Str("x".to_string()), Row::Double(1.5)),
+          (Row::Str("y".to_string()), Row::Null)])),
+        ("point".to_string(), Row::Group(vec![
+          ("x".to_string(), Row::Long(10)), ("y".to_string(), Row::Null)])),
+        ("legacy".to_string(), Row::List(vec![Row::Int(7), Row::Int(8)]))
+      ]),
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(2)),
+        ("tags".to_string(), Row::List(vec![])),
+        ("scores".to_string(), Row::Map(vec![])),
+        ("point".to_string(), Row::Null),
+        ("legacy".to_string(), Row::List(vec![]))
+      ]),
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(3)),
+        ("tags".to_string(), Row::Null),
+        ("scores".to_string(), Row::Null),
+        ("point".to_string(), Row::Group(vec![
+          ("x".to_string(), Row::Long(20)), ("y".to_string(), Row::Long(30))])),
+        ("legacy".to_string(), Row::List(vec![Row::Int(9)]))
+      ])
+    ];
+
+    let batch = ColumnarBatch::from_rows(descr.clone(), &rows).unwrap();
+    assert_eq!(batch.num_rows(), 3);
+    assert_eq!(batch.columns().len(), 7);
+    assert_eq!(batch.to_rows().unwrap(), rows);
+
+    // Batch created from columns is assembled into the same rows
+    let columns = batch.into_columns().into_iter().map(|column| {
+      ShreddedColumn::from_values(
+        column.descr().clone(),
+        column.values().clone(),
+        column.def_levels().map(|levels| levels.to_vec()),
+        column.rep_levels().map(|levels| levels.to_vec())
+      ).unwrap()
+    }).collect();
+    let batch = ColumnarBatch::new(descr.clone(), columns).unwrap();
+    assert_eq!(batch.to_rows().unwrap(), rows);
+
+    let batch = ColumnarBatch::from_rows(descr, &[]).unwrap();
+    assert_eq!(batch.num_rows(), 0);
+    assert_eq!(batch.to_rows().unwrap(), vec![]);
+  }
+
+  #[test]
+  fn test_columnar_batch_invalid_columns() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL GROUP b (LIST) {
+          REPEATED GROUP list {
+            REQUIRED INT32 element;
+          }
+        }
+      }
+    ";
+    let descr = descr(schema);
+    let a = descr.column(0);
+    let b = descr.column(1);
+
+    assert_eq!(
+      ShreddedColumn::from_values(a.clone(), ColumnValues::Int64(vec![1]), None, None)
+        .err().unwrap(),
+      general_err!("Cannot create column a of type INT32 from INT64 values")
+    );
+    assert_eq!(
+      ShreddedColumn::from_values(
+        a.clone(), ColumnValues::Int32(vec![1]), Some(vec![0]), None)
+        .err().unwrap(),
+      general_err!("Column a does not have definition levels")
+    );
+    assert_eq!(
+      ShreddedColumn::from_values(
+        b.clone(), ColumnValues::Int32(vec![1]), Some(vec![3]), Some(vec![0]))
+        .err().unwrap(),
+      general_err!(
+        "Invalid definition level 3 of column b.list.element, maximum level is 2")
+    );
+    assert_eq!(
+      ShreddedColumn::from_values(
+        b.clone(), ColumnValues::Int32(vec![1]), Some(vec![2, 2]), Some(vec![0, 1]))
+        .err().unwrap(),
+      general_err!("Column b.list.element has 2 non-null levels, but 1 values")
+    );
+
+    let a_values = ShreddedColumn::from_values(
+      a.clone(), ColumnValues::Int32(vec![1, 2]), None, None).unwrap();
+    let b_values = ShreddedColumn::from_values(
+      b.clone(), ColumnValues::Int32(vec![1, 2]), Some(vec![2, 2]), Some(vec![0, 1]))
+      .unwrap();
+    assert_eq!(
+      ColumnarBatch::new(descr.clone(), vec![a_values]).err().unwrap(),
+      general_err!("Expected 2 columns, found 1")
+    );
+    let a_values = ShreddedColumn::from_values(
+      a, ColumnValues::Int32(vec![1, 2]), None, None).unwrap();
+    assert_eq!(
+      ColumnarBatch::new(descr, vec![a_values, b_values]).err().unwrap(),
+      general_err!(
+        "Incorrect number of rows in column b.list.element, expected 2 != 1 rows")
+    );
+  }
+
+  #[test]
+  fn test_shred_invalid_row() {
+    let mut shredder = shredder("
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL GROUP b (LIST) {
+          REPEATED GROUP list {
+            REQUIRED INT32 element;
+          }
+        }
+      }
+    ");
+    let row = Row::Group(vec![
+      ("a".to_string(), Row::Int(1)),
+      ("b".to_string(), Row::List(vec![Row::Int(2), Row::Null]))
+    ]);
+    assert_eq!(
+      shredder.shred(&row).unwrap_err(),
+      general_err!("Cannot write null into required field b.list.element")
+    );
+    // Values of the row that failed to be shredded are discarded
+    assert_eq!(shredder.num_rows(), 0);
+    assert_eq!(shredder.columns()[0].num_values(), 0);
+    assert_eq!(shredder.columns()[1].num_levels(), 0);
+    assert_eq!(shredder.columns()[1].num_values(), 0);
+  }
+}
@@ -19,7 +19,9 @@ use basic::{LogicalType, Type as PhysicalType};
 use column::reader::{get_typed_column_reader, ColumnReader, ColumnReaderImpl};
 use data_type::*;
 use errors::{ParquetError, Result};
+use logical::{PhysicalValue, TimeUnit};
 use record::api::Row;
+use record::coercion::Coercion;
 use schema::types::ColumnDescPtr;
 
 /// Macro to generate simple functions that cover all types of triplet iterator.
@@ -92,6 +94,53 @@ impl TripletIter {
     }
   }
 
+  /// Overrides logical type that is used to convert values of this iterator into
+  /// [`Row`](`::record::api::Row`)s, e.g. to work around incorrect annotations.
+  pub fn set_logical_type(&mut self, logical_type: LogicalType) {
+    match *self {
+      TripletIter::BoolTripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::Int32TripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::Int64TripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::Int96TripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::FloatTripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::DoubleTripletIter(ref mut typed) => typed.logical_type = logical_type,
+      TripletIter::ByteArrayTripletIter(ref mut typed) => {
+        typed.logical_type = logical_type
+      },
+      TripletIter::FixedLenByteArrayTripletIter(ref mut typed) => {
+        typed.logical_type = logical_type
+      }
+    }
+  }
+
+  /// Sets coercion that is applied to values of this iterator after they are converted
+  /// into [`Row`](`::record::api::Row`)s.
+  pub fn set_coercion(&mut self, coercion: Coercion) {
+    match *self {
+      TripletIter::BoolTripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::Int32TripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::Int64TripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::Int96TripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::FloatTripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::DoubleTripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::ByteArrayTripletIter(ref mut typed) => typed.coercion = coercion,
+      TripletIter::FixedLenByteArrayTripletIter(ref mut typed) => {
+        typed.coercion = coercion
+      }
+    }
+  }
+
+  /// Sets unit that timestamp values of this iterator are normalized into, must be
+  /// called after the logical type is overridden. Has no effect on columns that are not
+  /// timestamps, see [`TimeUnit::from_type`](`::logical::TimeUnit::from_type`).
+  pub fn set_timestamp_unit(&mut self, unit: TimeUnit) {
+    match *self {
+      TripletIter::Int64TripletIter(ref mut typed) => typed.set_timestamp_unit(unit),
+      TripletIter::Int96TripletIter(ref mut typed) => typed.set_timestamp_unit(unit),
+      _ => {}
+    }
+  }
+
   /// Invokes underlying typed triplet iterator to buffer current value.
   /// Should be called once - either before `is_null` or `current_value`.
   #[inline]
@@ -144,37 +193,25 @@ impl TripletIter {
   pub fn current_value(&self) -> Row {
     assert!(!self.is_null(), "Value is null");
     match *self {
-      TripletIter::BoolTripletIter(ref typed) => {
-        Row::convert_bool(
-          typed.physical_type(), typed.logical_type(), *typed.current_value())
-      },
-      TripletIter::Int32TripletIter(ref typed) => {
-        Row::convert_int32(
-          typed.physical_type(), typed.logical_type(), *typed.current_value())
-      },
+      TripletIter::BoolTripletIter(ref typed) => typed.current_row(Row::convert_bool),
+      TripletIter::Int32TripletIter(ref typed) => typed.current_row(Row::convert_int32),
       TripletIter::Int64TripletIter(ref typed) => {
-        Row::convert_int64(
-          typed.physical_type(), typed.logical_type(), *typed.current_value())
+        typed
+          .current_timestamp(|value, from, to| from.saturating_convert(*value, to))
+          .unwrap_or_else(|| typed.current_row(Row::convert_int64))
       },
       TripletIter::Int96TripletIter(ref typed) => {
-        Row::convert_int96(
-          typed.physical_type(), typed.logical_type(), typed.current_value().clone())
-      },
-      TripletIter::FloatTripletIter(ref typed) => {
-        Row::convert_float(
-          typed.physical_type(), typed.logical_type(), *typed.current_value())
-      },
-      TripletIter::DoubleTripletIter(ref typed) => {
-        Row::convert_double(
-          typed.physical_type(), typed.logical_type(), *typed.current_value())
+        typed
+          .current_timestamp(|value, _, to| TimeUnit::saturating_from_int96(value, to))
+          .unwrap_or_else(|| typed.current_row(Row::convert_int96))
       },
+      TripletIter::FloatTripletIter(ref typed) => typed.current_row(Row::convert_float),
+      TripletIter::DoubleTripletIter(ref typed) => typed.current_row(Row::convert_double),
       TripletIter::ByteArrayTripletIter(ref typed) => {
-        Row::convert_byte_array(
-          typed.physical_type(), typed.logical_type(), typed.current_value().clone())
+        typed.current_row(Row::convert_byte_array)
       },
       TripletIter::FixedLenByteArrayTripletIter(ref typed) => {
-        Row::convert_byte_array(
-          typed.physical_type(), typed.logical_type(), typed.current_value().clone())
+        typed.current_row(Row::convert_byte_array)
       }
     }
   }
@@ -184,8 +221,12 @@ impl TripletIter {
 /// (primitive leaf column), provides per-element access.
 pub struct TypedTripletIter<T: DataType> {
   reader: ColumnReaderImpl<T>,
+  descr: ColumnDescPtr,
   physical_type: PhysicalType,
   logical_type: LogicalType,
+  coercion: Coercion,
+  // source and target units of normalized timestamp values
+  timestamp_units: Option<(TimeUnit, TimeUnit)>,
   batch_size: usize,
   // type properties
   max_def_level: i16,
@@ -222,6 +263,9 @@ impl<T: DataType> TypedTripletIter<T> where T: 'static {
       reader: get_typed_column_reader(column_reader),
       physical_type: descr.physical_type(),
       logical_type: descr.logical_type(),
+      coercion: Coercion::Identity,
+      timestamp_units: None,
+      descr: descr,
       batch_size: batch_size,
       max_def_level: max_def_level,
       max_rep_level: max_rep_level,
@@ -271,6 +315,41 @@ impl<T: DataType> TypedTripletIter<T> where T: 'static {
     &self.values[self.curr_triplet_index]
   }
 
+  /// Returns current value converted into row value. Values are converted with
+  /// logical type of the column, unless it has been overridden, in which case
+  /// `convert` function is used with the overridden logical type. Coercion is applied
+  /// to the converted value.
+  #[inline]
+  fn current_row<F>(&self, convert: F) -> Row
+      where F: Fn(PhysicalType, LogicalType, T::T) -> Row, T::T: PhysicalValue {
+    let value = self.current_value();
+    let row = if self.logical_type == self.descr.logical_type() {
+      Row::from_physical(&*self.descr, value)
+    } else {
+      convert(self.physical_type, self.logical_type, value.clone())
+    };
+    match self.coercion {
+      Coercion::Identity => row,
+      coercion => coercion.apply(row)
+    }
+  }
+
+  /// Sets target unit of timestamp values, if values of the column are timestamps.
+  fn set_timestamp_unit(&mut self, unit: TimeUnit) {
+    self.timestamp_units =
+      TimeUnit::from_type(self.physical_type, self.logical_type).map(|from| (from, unit));
+  }
+
+  /// Returns current value as `Row::Timestamp` in the target unit using `to_unit`
+  /// function, or `None` if timestamp values are not normalized.
+  #[inline]
+  fn current_timestamp<F>(&self, to_unit: F) -> Option<Row>
+      where F: Fn(&T::T, TimeUnit, TimeUnit) -> i64 {
+    self.timestamp_units.map(|(from, to)| {
+      Row::Timestamp(to_unit(self.current_value(), from, to) as u64)
+    })
+  }
+
   /// Returns current definition level.
   /// If field is required, then maximum definition level is returned.
   #[inline]
@@ -0,0 +1,350 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Contains implementation of record shredding, i.e. writing
+//! [`Row`](`::record::api::Row`)s into Parquet columns.
+
+use std::rc::Rc;
+
+use errors::{ParquetError, Result};
+use file::properties::WriterPropertiesPtr;
+use file::writer::{FileWriter, RowGroupWriter};
+use record::api::Row;
+use record::shredder::Shredder;
+use schema::types::{SchemaDescPtr, SchemaDescriptor, TypePtr};
+
+/// Trait for Rust types that are written as Parquet records, usually implemented with
+/// `#[derive(ParquetRecordWriter)]` from the `parquet_derive` crate.
+pub trait RecordWriter: Sized {
+  /// Returns message type of the records.
+  fn schema() -> Result<TypePtr>;
+
+  /// Converts the record into a `Row::Group` of its fields.
+  fn to_row(&self) -> Row;
+
+  /// Writes records into all columns of the row group writer, see
+  /// [`RowWriter::write_columns`](`RowWriter::write_columns`).
+  fn write_to_row_group(
+    records: &[Self],
+    row_group_writer: &mut RowGroupWriter
+  ) -> Result<()> {
+    let descr = Rc::new(SchemaDescriptor::new(Self::schema()?));
+    let mut row_writer = RowWriter::new(descr);
+    for record in records {
+      row_writer.write(&record.to_row())?;
+    }
+    row_writer.write_columns(row_group_writer)
+  }
+}
+
+/// Row writer that shreds [`Row`](`::record::api::Row`)s into definition levels,
+/// repetition levels and values of each leaf column with
+/// [`Shredder`](`::record::shredder::Shredder`), and writes them into column writers of
+/// a row group.
+///
+/// Rows are buffered in memory until they are written with `write_row_group` or
+/// `write_columns`. Each row must be a `Row::Group` with fields of the message type;
+/// missing fields are written as nulls, lists are `Row::List` and maps are `Row::Map`,
+/// same as rows returned by the record reader.
+pub struct RowWriter {
+  shredder: Shredder
+}
+
+impl RowWriter {
+  /// Creates new row writer for the schema.
+  pub fn new(descr: SchemaDescPtr) -> Self {
+    Self { shredder: Shredder::new(descr) }
+  }
+
+  /// Sets writer properties that control conversion of values, and returns itself.
+  ///
+  /// Currently only `int96_timestamps_enabled` is used, which allows writing
+  /// `Row::Timestamp` values into INT96 columns.
+  pub fn with_properties(mut self, props: WriterPropertiesPtr) -> Self {
+    self.shredder =
+      self.shredder.with_int96_timestamps_enabled(props.int96_timestamps_enabled());
+    self
+  }
+
+  /// Shreds and buffers a row.
+  ///
+  /// Returns an error if the row does not match the schema, in which case nothing is
+  /// buffered for the row.
+  pub fn write(&mut self, row: &Row) -> Result<()> {
+    self.shredder.shred(row)
+  }
+
+  /// Returns the number of rows buffered since the last write of columns.
+  pub fn num_rows(&self) -> usize {
+    self.shredder.num_rows()
+  }
+
+  /// Writes buffered rows into the next row group of the file writer.
+  pub fn write_row_group(&mut self, file_writer: &mut FileWriter) -> Result<()> {
+    let mut row_group_writer = file_writer.next_row_group()?;
+    self.write_columns(&mut *row_group_writer)?;
+    file_writer.close_row_group(row_group_writer)
+  }
+
+  /// Writes buffered rows into all columns of the row group writer, which must not have
+  /// any columns written yet. The row group writer is not closed.
+  pub fn write_columns(&mut self, row_group_writer: &mut RowGroupWriter) -> Result<()> {
+    for column in self.shredder.columns_mut() {
+      let mut column_writer = match row_group_writer.next_column()? {
+        Some(column_writer) => column_writer,
+        None => return Err(general_err!("Row group has fewer columns than schema"))
+      };
+      column.write_into(&mut column_writer)?;
+      row_group_writer.close_column(column_writer)?;
+    }
+    self.shredder.clear();
+    Ok(())
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+
+  use file::properties::WriterProperties;
+  use file::reader::{FileReader, SerializedFileReader};
+  use file::writer::SerializedFileWriter;
+  use schema::parser::parse_message_type;
+  use schema::types::SchemaDescriptor;
+  use util::test_common::get_temp_filename;
+
+  fn column_len(row_writer: &RowWriter, i: usize) -> (usize, usize) {
+    let column = &row_writer.shredder.columns()[i];
+    (column.num_levels(), column.num_values())
+  }
+
+  #[test]
+  fn test_row_writer_roundtrip() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 id;
+        OPTIONAL BYTE_ARRAY name (UTF8);
+        OPTIONAL GROUP tags (LIST) {
+          REPEATED GROUP list {
+            OPTIONAL BYTE_ARRAY element (UTF8);
+          }
+        }
+        OPTIONAL GROUP scores (MAP) {
+          REPEATED GROUP key_value {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL DOUBLE value;
+          }
+        }
+        OPTIONAL GROUP point {
+          REQUIRED INT64 x;
+          OPTIONAL BOOLEAN flag;
+        }
+      }
+    ";
+    let rows = vec![
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(1)),
+        ("name".to_string(), Row::Str("a".to_string())),
+        ("tags".to_string(), Row::List(vec![
+          Row::Str("x".to_string()), Row::Null, Row::Str("y".to_string())])),
+        ("scores".to_string(), Row::Map(vec![
+          (Row::Str("k1".to_string()), Row::Double(1.5)),
+          (Row::Str("k2".to_string()), Row::Null)])),
+        ("point".to_string(), Row::Group(vec![
+          ("x".to_string(), Row::Long(10)), ("flag".to_string(), Row::Bool(true))]))
+      ]),
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(2)),
+        ("name".to_string(), Row::Null),
+        ("tags".to_string(), Row::List(vec![])),
+        ("scores".to_string(), Row::Null),
+        ("point".to_string(), Row::Null)
+      ]),
+      Row::Group(vec![
+        ("id".to_string(), Row::Int(3)),
+        ("name".to_string(), Row::Str("c".to_string())),
+        ("tags".to_string(), Row::Null),
+        ("scores".to_string(), Row::Map(vec![])),
+        ("point".to_string(), Row::Group(vec![
+          ("x".to_string(), Row::Long(-1)), ("flag".to_string(), Row::Null)]))
+      ])
+    ];
+
+    let schema = Rc::new(parse_message_type(schema).unwrap());
+    let descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let props = Rc::new(WriterProperties::builder().build());
+    let path = get_temp_filename("test_row_writer_roundtrip.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let mut file_writer = SerializedFileWriter::new(file, schema, props).unwrap();
+
+    let mut row_writer = RowWriter::new(descr);
+    for row in &rows[..2] {
+      row_writer.write(row).unwrap();
+    }
+    assert_eq!(row_writer.num_rows(), 2);
+    row_writer.write_row_group(&mut file_writer).unwrap();
+    assert_eq!(row_writer.num_rows(), 0);
+    row_writer.write(&rows[2]).unwrap();
+    row_writer.write_row_group(&mut file_writer).unwrap();
+    file_writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(reader.num_row_groups(), 2);
+    let actual: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(actual, rows);
+  }
+
+  #[test]
+  fn test_row_writer_int96_timestamps() {
+    let schema = "
+      message schema {
+        OPTIONAL INT96 ts;
+      }
+    ";
+    let schema = Rc::new(parse_message_type(schema).unwrap());
+    let descr = Rc::new(SchemaDescriptor::new(schema.clone()));
+    let rows = vec![
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(1_238_544_000_000))]),
+      Row::Group(vec![("ts".to_string(), Row::Null)]),
+      Row::Group(vec![("ts".to_string(), Row::Timestamp(1_238_544_060_123))])
+    ];
+
+    // Timestamps are rejected unless INT96 timestamps are enabled
+    let mut row_writer = RowWriter::new(descr.clone());
+    assert!(row_writer.write(&rows[0]).is_err());
+
+    let props = Rc::new(
+      WriterProperties::builder().with_int96_timestamps_enabled(true).build()
+    );
+    let path = get_temp_filename("test_row_writer_int96_timestamps.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let mut file_writer =
+      SerializedFileWriter::new(file, schema, props.clone()).unwrap();
+    let mut row_writer = RowWriter::new(descr).with_properties(props);
+    for row in &rows {
+      row_writer.write(row).unwrap();
+    }
+    row_writer.write_row_group(&mut file_writer).unwrap();
+    file_writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let actual: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    assert_eq!(actual, rows);
+  }
+
+  struct TestRecord {
+    id: i64,
+    name: Option<String>
+  }
+
+  // Same implementation as the one generated by `#[derive(ParquetRecordWriter)]`
+  impl RecordWriter for TestRecord {
+    fn schema() -> Result<TypePtr> {
+      let schema = "
+        message TestRecord {
+          REQUIRED INT64 id;
+          OPTIONAL BYTE_ARRAY name (UTF8);
+        }
+      ";
+      Ok(Rc::new(parse_message_type(schema)?))
+    }
+
+    fn to_row(&self) -> Row {
+      Row::Group(vec![
+        ("id".to_string(), Row::Long(self.id)),
+        ("name".to_string(), match self.name {
+          Some(ref value) => Row::Str(value.clone()),
+          None => Row::Null
+        })
+      ])
+    }
+  }
+
+  #[test]
+  fn test_record_writer() {
+    let records = vec![
+      TestRecord { id: 1, name: Some("a".to_string()) },
+      TestRecord { id: 2, name: None }
+    ];
+
+    let props = Rc::new(WriterProperties::builder().build());
+    let path = get_temp_filename("test_record_writer.parquet");
+    let file = fs::File::create(&path).unwrap();
+    let mut file_writer =
+      SerializedFileWriter::new(file, TestRecord::schema().unwrap(), props).unwrap();
+    let mut row_group_writer = file_writer.next_row_group().unwrap();
+    TestRecord::write_to_row_group(&records, &mut *row_group_writer).unwrap();
+    file_writer.close_row_group(row_group_writer).unwrap();
+    file_writer.close().unwrap();
+
+    let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+    let actual: Vec<Row> = reader.get_row_iter(None).unwrap().collect();
+    let expected: Vec<Row> = records.iter().map(|record| record.to_row()).collect();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn test_row_writer_invalid_rows() {
+    let schema = "
+      message schema {
+        REQUIRED INT32 a;
+        OPTIONAL BYTE_ARRAY b (UTF8);
+      }
+    ";
+    let schema = Rc::new(parse_message_type(schema).unwrap());
+    let descr = Rc::new(SchemaDescriptor::new(schema));
+    let mut row_writer = RowWriter::new(descr);
+
+    let row = Row::Group(vec![("b".to_string(), Row::Str("b".to_string()))]);
+    assert_eq!(
+      row_writer.write(&row).unwrap_err(),
+      general_err!("Cannot write null into required field a")
+    );
+
+    let row = Row::Group(vec![
+      ("a".to_string(), Row::Int(1)), ("b".to_string(), Row::Long(2))]);
+    assert_eq!(
+      row_writer.write(&row).unwrap_err(),
+      general_err!("Cannot write 2 into column b of type BYTE_ARRAY")
+    );
+
+    let row = Row::Group(vec![
+      ("a".to_string(), Row::Int(1)), ("c".to_string(), Row::Null)]);
+    assert_eq!(
+      row_writer.write(&row).unwrap_err(),
+      general_err!("Field c does not exist in schema")
+    );
+
+    assert_eq!(
+      row_writer.write(&Row::Int(1)).unwrap_err(),
+      general_err!("Cannot write 1 as schema")
+    );
+
+    // Values of rows that failed to be written are discarded
+    assert_eq!(row_writer.num_rows(), 0);
+    assert_eq!(column_len(&row_writer, 0), (0, 0));
+    assert_eq!(column_len(&row_writer, 1), (0, 0));
+
+    let row = Row::Group(vec![("a".to_string(), Row::Int(1))]);
+    row_writer.write(&row).unwrap();
+    assert_eq!(row_writer.num_rows(), 1);
+    assert_eq!(column_len(&row_writer, 0), (0, 1));
+    assert_eq!(column_len(&row_writer, 1), (1, 0));
+  }
+}
@@ -81,6 +81,12 @@ pub fn print_file_metadata(out: &mut io::Write, file_metadata: &FileMetaData) {
   if let Some(created_by) = file_metadata.created_by().as_ref() {
     writeln!(out, "created by: {}", created_by);
   }
+  if let Some(key_value_metadata) = file_metadata.key_value_metadata().as_ref() {
+    writeln!(out, "metadata:");
+    for kv in key_value_metadata {
+      writeln!(out, "  {}: {}", kv.key(), kv.value().unwrap_or("NONE"));
+    }
+  }
   let schema = file_metadata.schema();
   print_schema(out, schema);
 }
@@ -141,6 +147,33 @@ fn print_column_chunk_metadata(out: &mut io::Write, cc_metadata: &ColumnChunkMet
     Some(dpo) => dpo.to_string()
   };
   writeln!(out, "dictionary page offset: {}", dict_page_offset_str);
+  let bloom_filter_str = match cc_metadata.bloom_filter_offset() {
+    None => "N/A".to_owned(),
+    Some(offset) => format!(
+      "offset {}, length {}",
+      offset,
+      cc_metadata.bloom_filter_length().unwrap_or(0)
+    )
+  };
+  writeln!(out, "bloom filter: {}", bloom_filter_str);
+  let column_index_str = match cc_metadata.column_index_offset() {
+    None => "N/A".to_owned(),
+    Some(offset) => format!(
+      "offset {}, length {}",
+      offset,
+      cc_metadata.column_index_length().unwrap_or(0)
+    )
+  };
+  writeln!(out, "column index: {}", column_index_str);
+  let offset_index_str = match cc_metadata.offset_index_offset() {
+    None => "N/A".to_owned(),
+    Some(offset) => format!(
+      "offset {}, length {}",
+      offset,
+      cc_metadata.offset_index_length().unwrap_or(0)
+    )
+  };
+  writeln!(out, "offset index: {}", offset_index_str);
   writeln!(out, "");
 }
 
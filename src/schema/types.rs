@@ -17,7 +17,7 @@
 
 //! Contains structs and methods to build Parquet schema and schema descriptors.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::From;
 use std::fmt;
 use std::rc::Rc;
@@ -64,6 +64,30 @@ impl Type {
     PrimitiveTypeBuilder::new(name, physical_type)
   }
 
+  /// Creates primitive type builder for DECIMAL values with `precision` and `scale`.
+  ///
+  /// Values are stored in the smallest physical type: INT32 for precision up to 9,
+  /// INT64 for precision up to 18, and FIXED_LEN_BYTE_ARRAY with the minimal number of
+  /// bytes otherwise.
+  pub fn decimal_type_builder(
+    name: &str,
+    precision: i32,
+    scale: i32
+  ) -> PrimitiveTypeBuilder {
+    let (physical_type, length) = if precision <= 9 {
+      (PhysicalType::INT32, 0)
+    } else if precision <= 18 {
+      (PhysicalType::INT64, 0)
+    } else {
+      (PhysicalType::FIXED_LEN_BYTE_ARRAY, decimal_length(precision))
+    };
+    PrimitiveTypeBuilder::new(name, physical_type)
+      .with_logical_type(LogicalType::DECIMAL)
+      .with_length(length)
+      .with_precision(precision)
+      .with_scale(scale)
+  }
+
   /// Creates group type builder with provided column name.
   pub fn group_type_builder(name: &str) -> GroupTypeBuilder {
     GroupTypeBuilder::new(name)
@@ -134,6 +158,26 @@ impl Type {
     }
   }
 
+  /// Returns projection of this group type to fields with ids in `field_ids`, which
+  /// can be passed to row iterators as a projection.
+  ///
+  /// Fields are matched by id rather than name, so a field that was renamed since the
+  /// file was written is still selected. A selected group is included with all its
+  /// fields; a group that is not selected is included with its selected fields only.
+  /// Ids that are not found are ignored, e.g. fields added after the file was written.
+  ///
+  /// Returns an error if this type is not a group type or none of the ids is found.
+  pub fn project_field_ids(&self, field_ids: &[i32]) -> Result<Type> {
+    if !self.is_group() {
+      return Err(general_err!("Cannot project fields of non-group type {}", self.name()));
+    }
+    let ids: HashSet<i32> = field_ids.iter().cloned().collect();
+    match project_group(self, &ids)? {
+      Some(projection) => Ok(projection),
+      None => Err(general_err!("Schema does not contain field ids {:?}", field_ids))
+    }
+  }
+
   /// Returns `true` if this type is a primitive type, `false` otherwise.
   pub fn is_primitive(&self) -> bool {
     match *self {
@@ -425,6 +469,43 @@ impl BasicTypeInfo {
   }
 }
 
+/// Returns copy of the group type with fields selected by `ids`, or `None` if no field
+/// is selected, see [`Type::project_field_ids`](`Type::project_field_ids`).
+fn project_group(group: &Type, ids: &HashSet<i32>) -> Result<Option<Type>> {
+  let mut fields = Vec::new();
+  for field in group.get_fields() {
+    let info = field.get_basic_info();
+    if info.has_id() && ids.contains(&info.id()) {
+      fields.push(field.clone());
+    } else if field.is_group() {
+      if let Some(projection) = project_group(field, ids)? {
+        fields.push(Rc::new(projection));
+      }
+    }
+  }
+  if fields.is_empty() {
+    return Ok(None);
+  }
+
+  let info = group.get_basic_info();
+  let mut builder = Type::group_type_builder(info.name())
+    .with_logical_type(info.logical_type())
+    .with_fields(&mut fields);
+  if info.has_repetition() {
+    builder = builder.with_repetition(info.repetition());
+  }
+  if info.has_id() {
+    builder = builder.with_id(info.id());
+  }
+  builder.build().map(Some)
+}
+
+/// Returns the minimal number of bytes of FIXED_LEN_BYTE_ARRAY to store DECIMAL values
+/// with `precision` digits, including the sign bit.
+fn decimal_length(precision: i32) -> i32 {
+  ((precision as f64 * 10f64.log2() + 1.0) / 8.0).ceil() as i32
+}
+
 // ----------------------------------------------------------------------
 // Parquet descriptor definitions
 
@@ -454,6 +535,11 @@ impl ColumnPath {
   pub fn string(&self) -> String {
     self.parts.join(".")
   }
+
+  /// Returns field names of this column path.
+  pub fn parts(&self) -> &[String] {
+    &self.parts
+  }
 }
 
 impl fmt::Display for ColumnPath {
@@ -662,6 +748,11 @@ impl SchemaDescriptor {
     self.leaves.len()
   }
 
+  /// Returns `true` if schema has no leaf-level columns.
+  pub fn is_empty(&self) -> bool {
+    self.leaves.is_empty()
+  }
+
   /// Returns column root [`Type`](`::schema::types::Type`) for a field position.
   pub fn get_column_root(&self, i: usize) -> &Type {
     assert!(
@@ -822,6 +913,67 @@ fn from_thrift_helper(
 }
 
 
+/// Method to convert to Thrift.
+/// Schema is flattened into a list of elements in depth-first order, where the first
+/// element is the root of the schema.
+pub fn to_thrift(schema: &Type) -> Result<Vec<SchemaElement>> {
+  if !schema.is_group() {
+    return Err(general_err!("Root schema must be Group type"));
+  }
+  let mut elements: Vec<SchemaElement> = Vec::new();
+  to_thrift_helper(schema, &mut elements);
+  Ok(elements)
+}
+
+/// Constructs list of `SchemaElement` from the schema using depth-first traversal.
+/// Here we assume that schema is always valid and starts with group type.
+fn to_thrift_helper(schema: &Type, elements: &mut Vec<SchemaElement>) {
+  let basic_info = schema.get_basic_info();
+  let repetition = if basic_info.has_repetition() {
+    Some(basic_info.repetition().into())
+  } else {
+    None
+  };
+  let field_id = if basic_info.has_id() { Some(basic_info.id()) } else { None };
+
+  match *schema {
+    Type::PrimitiveType { physical_type, type_length, scale, precision, .. } => {
+      let element = SchemaElement {
+        type_: Some(physical_type.into()),
+        type_length: if type_length >= 0 { Some(type_length) } else { None },
+        repetition_type: repetition,
+        name: basic_info.name().to_owned(),
+        num_children: None,
+        converted_type: basic_info.logical_type().into(),
+        scale: if scale >= 0 { Some(scale) } else { None },
+        precision: if precision >= 0 { Some(precision) } else { None },
+        field_id: field_id
+      };
+      elements.push(element);
+    },
+    Type::GroupType { ref fields, .. } => {
+      let element = SchemaElement {
+        type_: None,
+        type_length: None,
+        repetition_type: repetition,
+        name: basic_info.name().to_owned(),
+        num_children: Some(fields.len() as i32),
+        converted_type: basic_info.logical_type().into(),
+        scale: None,
+        precision: None,
+        field_id: field_id
+      };
+      elements.push(element);
+
+      // Add child elements for a group
+      for field in fields {
+        to_thrift_helper(field, elements);
+      }
+    }
+  }
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -962,6 +1114,34 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_decimal_type() {
+    let types = vec![
+      (1, PhysicalType::INT32, 0),
+      (9, PhysicalType::INT32, 0),
+      (10, PhysicalType::INT64, 0),
+      (18, PhysicalType::INT64, 0),
+      (19, PhysicalType::FIXED_LEN_BYTE_ARRAY, 9),
+      (38, PhysicalType::FIXED_LEN_BYTE_ARRAY, 16)
+    ];
+    for (precision, expected_type, expected_length) in types {
+      let tpe = Type::decimal_type_builder("dec", precision, 1)
+        .with_repetition(Repetition::REQUIRED)
+        .build()
+        .unwrap();
+      assert_eq!(tpe.get_basic_info().logical_type(), LogicalType::DECIMAL);
+      match tpe {
+        Type::PrimitiveType { physical_type, type_length, precision: p, scale, .. } => {
+          assert_eq!(physical_type, expected_type);
+          assert_eq!(type_length, expected_length);
+          assert_eq!(p, precision);
+          assert_eq!(scale, 1);
+        },
+        _ => panic!("Expected primitive type")
+      }
+    }
+  }
+
   #[test]
   fn test_group_type() {
     let f1 = Type::primitive_type_builder("f1", PhysicalType::INT32)
@@ -1316,4 +1496,93 @@ mod tests {
     assert!(f1.check_contains(&f2)); // should match
     assert!(!f2.check_contains(&f1)); // should fail
   }
+
+  #[test]
+  fn test_project_field_ids() {
+    let schema = parse_message_type("
+      message schema {
+        REQUIRED INT32 a = 1;
+        OPTIONAL GROUP b = 2 {
+          REQUIRED INT64 c = 3;
+          OPTIONAL BYTE_ARRAY d (UTF8) = 4;
+        }
+        OPTIONAL GROUP e = 5 {
+          REQUIRED BOOLEAN f = 6;
+        }
+      }
+    ").unwrap();
+
+    let projection = schema.project_field_ids(&[4, 1, 5, 10]).unwrap();
+    let expected = parse_message_type("
+      message schema {
+        REQUIRED INT32 a = 1;
+        OPTIONAL GROUP b = 2 {
+          OPTIONAL BYTE_ARRAY d (UTF8) = 4;
+        }
+        OPTIONAL GROUP e = 5 {
+          REQUIRED BOOLEAN f = 6;
+        }
+      }
+    ").unwrap();
+    assert_eq!(projection, expected);
+    assert!(schema.check_contains(&projection));
+
+    // Order of fields follows the schema
+    let projection = schema.project_field_ids(&[3, 1]).unwrap();
+    let fields: Vec<&str> = projection.get_fields().iter().map(|f| f.name()).collect();
+    assert_eq!(fields, vec!["a", "b"]);
+
+    assert_eq!(
+      schema.project_field_ids(&[7, 8]).unwrap_err(),
+      general_err!("Schema does not contain field ids [7, 8]")
+    );
+    assert_eq!(
+      schema.get_fields()[0].project_field_ids(&[1]).unwrap_err(),
+      general_err!("Cannot project fields of non-group type a")
+    );
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion() {
+    let message_type = "
+      message conversions {
+        REQUIRED INT64 id;
+        OPTIONAL group int_array_Array (LIST) {
+          REPEATED group list {
+            OPTIONAL group element (LIST) {
+              REPEATED group list {
+                OPTIONAL INT32 element;
+              }
+            }
+          }
+        }
+        OPTIONAL group int_map (MAP) {
+          REPEATED group map (MAP_KEY_VALUE) {
+            REQUIRED BYTE_ARRAY key (UTF8);
+            OPTIONAL INT32 value;
+          }
+        }
+        REQUIRED FIXED_LEN_BYTE_ARRAY(16) dec (DECIMAL(20, 2));
+        OPTIONAL BYTE_ARRAY str (UTF8);
+      }
+    ";
+    let expected_schema = parse_message_type(message_type).unwrap();
+    let mut thrift_schema = to_thrift(&expected_schema).unwrap();
+    assert_eq!(thrift_schema.len(), 13);
+    assert_eq!(thrift_schema[0].num_children, Some(5));
+    let result_schema = from_thrift(&mut thrift_schema).unwrap();
+    assert_eq!(result_schema, Rc::new(expected_schema));
+  }
+
+  #[test]
+  fn test_schema_type_thrift_conversion_err() {
+    let schema = Type::primitive_type_builder("col", PhysicalType::INT32)
+      .build()
+      .unwrap();
+    let thrift_schema = to_thrift(&schema);
+    assert!(thrift_schema.is_err());
+    if let Err(e) = thrift_schema {
+      assert_eq!(e.description(), "Root schema must be Group type");
+    }
+  }
 }
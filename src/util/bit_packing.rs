@@ -15,6 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use byteorder::{ByteOrder, LittleEndian};
+
+use util::bit_util::trailing_bits;
+
 /// Unpack 32 values with bit width `num_bits` from `in_ptr`, and write to `out_ptr`.
 /// Return the `in_ptr` where the starting offset points to the first byte after all the
 /// bytes that were consumed.
@@ -69,6 +73,26 @@ pub unsafe fn unpack32(
   in_ptr
 }
 
+/// Unpack 8 values with bit width `num_bits` from the first `num_bits` bytes of
+/// `input`, and write to `output`. Bit widths up to 64 are supported.
+pub fn unpack8_64(input: &[u8], output: &mut [u64; 8], num_bits: usize) {
+  assert!(num_bits <= 64);
+  // Every value is read as a 64-bit word at its first byte, plus one more byte for
+  // values that span 9 bytes, so input is copied into a zero padded buffer
+  let mut buf = [0u8; 72];
+  buf[..num_bits].copy_from_slice(&input[..num_bits]);
+  for i in 0..8 {
+    let bit_offset = i * num_bits;
+    let byte_offset = bit_offset / 8;
+    let shift = bit_offset % 8;
+    let mut value = LittleEndian::read_u64(&buf[byte_offset..byte_offset + 8]) >> shift;
+    if shift + num_bits > 64 {
+      value |= (buf[byte_offset + 8] as u64) << (64 - shift);
+    }
+    output[i] = trailing_bits(value, num_bits);
+  }
+}
+
 unsafe fn nullunpacker32(in_buf: *const u32, mut out: *mut u32) -> *const u32 {
   for _ in 0..32 {
     *out = 0;
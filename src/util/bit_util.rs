@@ -18,8 +18,10 @@
 use std::cmp;
 use std::mem::{size_of, transmute_copy};
 
+use byteorder::{ByteOrder, LittleEndian};
+
 use errors::{ParquetError, Result};
-use util::bit_packing::unpack32;
+use util::bit_packing::{unpack32, unpack8_64};
 use util::memory::ByteBufferPtr;
 
 /// Reads `$size` of bytes from `$src`, and reinterprets them as type `$ty`, in
@@ -113,6 +115,11 @@ pub fn trailing_bits(v: u64, num_bits: usize) -> u64 {
   (v << n) >> n
 }
 
+#[inline]
+pub fn get_array_bit(bits: &[u8], i: usize) -> bool {
+  bits[i / 8] & (1 << (i % 8)) != 0
+}
+
 #[inline]
 pub fn set_array_bit(bits: &mut [u8], i: usize) {
   bits[i / 8] |= 1 << (i % 8);
@@ -123,6 +130,47 @@ pub fn unset_array_bit(bits: &mut [u8], i: usize) {
   bits[i / 8] &= !(1 << (i % 8));
 }
 
+/// Writes `num_bits` least-significant bits of `value` into `bits` starting at bit
+/// `offset`, a byte at a time. Other bits of `bits` are not changed.
+#[inline]
+pub fn set_array_bits(bits: &mut [u8], offset: usize, value: u64, num_bits: usize) {
+  assert!(num_bits <= 64);
+  let mut value = value;
+  let mut offset = offset;
+  let mut bits_left = num_bits;
+  while bits_left > 0 {
+    let shift = offset % 8;
+    let n = cmp::min(8 - shift, bits_left);
+    let mask = (((1u16 << n) - 1) as u8) << shift;
+    let byte = &mut bits[offset / 8];
+    *byte = (*byte & !mask) | (((value as u8) << shift) & mask);
+    value >>= n;
+    offset += n;
+    bits_left -= n;
+  }
+}
+
+/// Sets `num_bits` bits of `bits` starting at bit `offset` to `value`, whole bytes are
+/// filled at once. Other bits of `bits` are not changed.
+#[inline]
+pub fn fill_array_bits(bits: &mut [u8], offset: usize, num_bits: usize, value: bool) {
+  let end = offset + num_bits;
+  let mut i = offset;
+  while i < end && i % 8 != 0 {
+    if value { set_array_bit(bits, i) } else { unset_array_bit(bits, i) }
+    i += 1;
+  }
+  let byte_value = if value { 0xff } else { 0 };
+  while end - i >= 8 {
+    bits[i / 8] = byte_value;
+    i += 8;
+  }
+  while i < end {
+    if value { set_array_bit(bits, i) } else { unset_array_bit(bits, i) }
+    i += 1;
+  }
+}
+
 /// Returns the minimum number of bits needed to represent the value 'x'
 #[inline]
 pub fn num_required_bits(x: u64) -> usize {
@@ -367,8 +415,9 @@ pub struct BitReader {
   // The byte buffer to read from, passed in by client
   buffer: ByteBufferPtr,
 
-  // Bytes are memcpy'd from `buffer` and values are read from this variable.
-  // This is faster than reading values byte by byte directly from `buffer`
+  // 8 bytes at `byte_offset` loaded from `buffer` with a single unaligned load, values
+  // are read from this variable. This is faster than reading values byte by byte
+  // directly from `buffer`
   buffered_values: u64,
 
   //
@@ -392,25 +441,24 @@ pub struct BitReader {
 impl BitReader {
   pub fn new(buffer: ByteBufferPtr) -> Self {
     let total_bytes = buffer.len();
-    let num_bytes = cmp::min(8, total_bytes);
-    let buffered_values = read_num_bytes!(u64, num_bytes, buffer.as_ref());
-    BitReader {
+    let mut reader = BitReader {
       buffer: buffer,
-      buffered_values: buffered_values,
+      buffered_values: 0,
       byte_offset: 0,
       bit_offset: 0,
       total_bytes: total_bytes
-    }
+    };
+    reader.reload_buffer_values();
+    reader
   }
 
   #[inline]
   pub fn reset(&mut self, buffer: ByteBufferPtr) {
     self.buffer = buffer;
     self.total_bytes = self.buffer.len();
-    let num_bytes = cmp::min(8, self.total_bytes);
-    self.buffered_values = read_num_bytes!(u64, num_bytes, self.buffer.as_ref());
     self.byte_offset = 0;
     self.bit_offset = 0;
+    self.reload_buffer_values();
   }
 
   /// Gets the current byte offset
@@ -419,13 +467,21 @@ impl BitReader {
     self.byte_offset + ceil(self.bit_offset as i64, 8) as usize
   }
 
+  /// Returns the number of bits left to read.
+  #[inline]
+  pub fn bits_left(&self) -> usize {
+    (self.total_bytes * 8).saturating_sub(self.byte_offset * 8 + self.bit_offset)
+  }
+
   /// Reads a value of type `T` and of size `num_bits`.
   ///
-  /// Returns `None` if there's not enough data available. `Some` otherwise.
+  /// Returns `None` if there's not enough data available, or if `num_bits` is larger
+  /// than the size of `T`. `Some` otherwise.
   #[inline]
   pub fn get_value<T: Default>(&mut self, num_bits: usize) -> Option<T> {
-    assert!(num_bits <= 64);
-    assert!(num_bits <= size_of::<T>() * 8);
+    if !is_valid_num_bits::<T>(num_bits) {
+      return None;
+    }
 
     if self.byte_offset * 8 + self.bit_offset + num_bits > self.total_bytes * 8 {
       return None;
@@ -452,10 +508,18 @@ impl BitReader {
     Some(result)
   }
 
+  /// Reads a batch of values of type `T` and of size `num_bits` into `batch`, values are
+  /// unpacked 32 at a time for 32-bit types and 8 at a time for 64-bit types.
+  ///
+  /// Returns the number of values read, which is less than `batch.len()` if there's not
+  /// enough data available, or an error if `num_bits` is larger than the size of `T`.
   #[inline]
-  pub fn get_batch<T: Default>(&mut self, batch: &mut [T], num_bits: usize) -> usize {
-    assert!(num_bits <= 32);
-    assert!(num_bits <= size_of::<T>() * 8);
+  pub fn get_batch<T: Default>(
+    &mut self,
+    batch: &mut [T],
+    num_bits: usize
+  ) -> Result<usize> {
+    check_num_bits::<T>(num_bits)?;
 
     let mut values_to_read = batch.len();
     let needed_bits = num_bits * values_to_read;
@@ -469,11 +533,141 @@ impl BitReader {
     // First align bit offset to byte offset
     if self.bit_offset != 0 {
       while i < values_to_read && self.bit_offset != 0 {
-        batch[i] = self.get_value(num_bits).expect("expected to have more data");
+        batch[i] = self.next_value(num_bits)?;
         i += 1;
       }
     }
 
+    if size_of::<T>() == 8 {
+      // Every 8 values occupy exactly `num_bits` bytes
+      let mut out_buf = [0u64; 8];
+      while values_to_read - i >= 8 {
+        unpack8_64(
+          &self.buffer.data()[self.byte_offset..self.byte_offset + num_bits],
+          &mut out_buf,
+          num_bits
+        );
+        self.byte_offset += num_bits;
+        for n in 0..8 {
+          batch[i] = unsafe { transmute_copy::<u64, T>(&out_buf[n]) };
+          i += 1;
+        }
+      }
+    } else {
+      self.get_batch_32(batch, num_bits, values_to_read, &mut i);
+    }
+
+    self.reload_buffer_values();
+    while i < values_to_read {
+      batch[i] = self.next_value(num_bits)?;
+      i += 1;
+    }
+
+    Ok(values_to_read)
+  }
+
+  /// Reads at most `num_values` values of 1 bit into `bitmap` starting at bit `offset`,
+  /// 64 values at a time, e.g. to decode boolean values without unpacking them into
+  /// bytes.
+  ///
+  /// Returns the number of values read, which is less than `num_values` if there's not
+  /// enough data available.
+  #[inline]
+  pub fn get_bitmap(
+    &mut self,
+    bitmap: &mut [u8],
+    offset: usize,
+    num_values: usize
+  ) -> Result<usize> {
+    let values_to_read = cmp::min(num_values, self.bits_left());
+    if offset + values_to_read > bitmap.len() * 8 {
+      return Err(general_err!(
+        "Bitmap of {} bytes is too small for {} values at offset {}",
+        bitmap.len(),
+        values_to_read,
+        offset
+      ));
+    }
+    let mut i = 0;
+    while i < values_to_read {
+      let num_bits = cmp::min(64, values_to_read - i);
+      let value = self.next_value::<u64>(num_bits)?;
+      set_array_bits(bitmap, offset + i, value, num_bits);
+      i += num_bits;
+    }
+    Ok(values_to_read)
+  }
+
+  /// Reads a batch of values of size `num_bits` into `batch`, used to decode definition
+  /// and repetition levels. Values of up to 8 bits are unpacked 8 at a time from a
+  /// single 64-bit word, wider values are read with `get_batch`.
+  ///
+  /// Returns the number of values read, which is less than `batch.len()` if there's not
+  /// enough data available, or an error if `num_bits` is larger than 16.
+  #[inline]
+  pub fn get_i16_batch(&mut self, batch: &mut [i16], num_bits: usize) -> Result<usize> {
+    if num_bits == 0 || num_bits > 8 {
+      return self.get_batch::<i16>(batch, num_bits);
+    }
+
+    let mut values_to_read = batch.len();
+    let remaining_bits = (self.total_bytes - self.byte_offset) * 8 - self.bit_offset;
+    if remaining_bits < num_bits * values_to_read {
+      values_to_read = remaining_bits / num_bits;
+    }
+
+    let mut i = 0;
+    while i < values_to_read && self.bit_offset != 0 {
+      batch[i] = self.next_value(num_bits)?;
+      i += 1;
+    }
+
+    // Every 8 values occupy exactly `num_bits` bytes, the rest of the word stays zero
+    let mask = (1u64 << num_bits) - 1;
+    let mut word_buf = [0u8; 8];
+    while values_to_read - i >= 8 {
+      word_buf[..num_bits].copy_from_slice(
+        &self.buffer.data()[self.byte_offset..self.byte_offset + num_bits]
+      );
+      let word = LittleEndian::read_u64(&word_buf);
+      for n in 0..8 {
+        batch[i + n] = ((word >> (n * num_bits)) & mask) as i16;
+      }
+      self.byte_offset += num_bits;
+      i += 8;
+    }
+
+    self.reload_buffer_values();
+    while i < values_to_read {
+      batch[i] = self.next_value(num_bits)?;
+      i += 1;
+    }
+
+    Ok(values_to_read)
+  }
+
+  /// Reads a value of size `num_bits` like `get_value`, which is known to be available,
+  /// e.g. when number of values is bounded by the number of bits left. Returns an error
+  /// instead of panicking if the value is not available.
+  #[inline]
+  fn next_value<T: Default>(&mut self, num_bits: usize) -> Result<T> {
+    self.get_value(num_bits).ok_or_else(|| {
+      eof_err!("Expected value of {} bits, found {} bits", num_bits, self.bits_left())
+    })
+  }
+
+  /// Unpacks values of up to 32 bits, 32 values at a time, starting at index `*index`
+  /// of `batch` until fewer than 32 values of `values_to_read` are left. Bit reader must
+  /// be byte aligned.
+  #[inline]
+  fn get_batch_32<T: Default>(
+    &mut self,
+    batch: &mut [T],
+    num_bits: usize,
+    values_to_read: usize,
+    index: &mut usize
+  ) {
+    let mut i = *index;
     unsafe {
       let in_buf = &self.buffer.data()[self.byte_offset..];
       let mut in_ptr = in_buf as *const [u8] as *const u8 as *const u32;
@@ -511,16 +705,28 @@ impl BitReader {
         }
       }
     }
+    *index = i;
+  }
 
-    assert!(values_to_read - i < 32);
+  /// Skips at most `num_values` values of size `num_bits` without reading them.
+  ///
+  /// Returns the number of values skipped, which is less than `num_values` if there's
+  /// not enough data available, or an error if `num_bits` is larger than 64.
+  #[inline]
+  pub fn skip(&mut self, num_values: usize, num_bits: usize) -> Result<usize> {
+    check_num_bits::<u64>(num_bits)?;
+    let remaining_bits = (self.total_bytes - self.byte_offset) * 8 - self.bit_offset;
+    let values_to_skip = if num_bits == 0 {
+      num_values
+    } else {
+      cmp::min(num_values, remaining_bits / num_bits)
+    };
 
+    let bit_offset = self.bit_offset + values_to_skip * num_bits;
+    self.byte_offset += bit_offset / 8;
+    self.bit_offset = bit_offset % 8;
     self.reload_buffer_values();
-    while i < values_to_read {
-      batch[i] = self.get_value(num_bits).expect("expected to have more data");
-      i += 1;
-    }
-
-    values_to_read
+    Ok(values_to_skip)
   }
 
   /// Reads a `num_bytes`-sized value from this buffer and return it.
@@ -529,9 +735,12 @@ impl BitReader {
   /// reading the value.
 
   /// Returns `Some` if there's enough bytes left to form a value of `T`.
-  /// Otherwise `None`.
+  /// Otherwise `None`, also if `num_bytes` is larger than the size of `T`.
   #[inline]
   pub fn get_aligned<T: Default>(&mut self, num_bytes: usize) -> Option<T> {
+    if num_bytes > size_of::<T>() {
+      return None;
+    }
     let bytes_read = ceil(self.bit_offset as i64, 8) as usize;
     if self.byte_offset + bytes_read + num_bytes > self.total_bytes {
       return None;
@@ -539,10 +748,7 @@ impl BitReader {
 
     // Advance byte_offset to next unread byte and read num_bytes
     self.byte_offset += bytes_read;
-    let v = read_num_bytes!(
-      T, num_bytes,
-      self.buffer.start_from(self.byte_offset).as_ref()
-    );
+    let v = read_num_bytes!(T, num_bytes, &self.buffer.data()[self.byte_offset..]);
     self.byte_offset += num_bytes;
 
     // Reset buffered_values
@@ -554,24 +760,36 @@ impl BitReader {
   /// Reads a VLQ encoded (in little endian order) int from the stream.
   /// The encoded int must start at the beginning of a byte.
   ///
-  /// Returns `None` if there's not enough bytes in the stream. `Some` otherwise.
+  /// Returns `None` if there's not enough bytes in the stream or the int is longer than
+  /// `MAX_VLQ_BYTE_LEN` bytes, in which case the reader is not advanced. `Some`
+  /// otherwise.
   #[inline]
   pub fn get_vlq_int(&mut self) -> Option<i64> {
+    // Bytes are read directly from the buffer, buffered values are reloaded once
+    let start = self.byte_offset + ceil(self.bit_offset as i64, 8) as usize;
+    if start >= self.total_bytes {
+      return None;
+    }
     let mut shift = 0;
     let mut v: i64 = 0;
-    while let Some(byte) = self.get_aligned::<u8>(1) {
+    let mut end = None;
+    for (i, &byte) in self.buffer.data()[start..].iter().enumerate() {
+      if shift >= MAX_VLQ_BYTE_LEN * 7 {
+        return None;
+      }
       v |= ((byte & 0x7F) as i64) << shift;
       shift += 7;
-      assert!(
-        shift <= MAX_VLQ_BYTE_LEN * 7,
-        "Num of bytes exceed MAX_VLQ_BYTE_LEN ({})",
-        MAX_VLQ_BYTE_LEN
-      );
       if byte & 0x80 == 0 {
-        return Some(v);
+        end = Some(start + i + 1);
+        break;
       }
     }
-    None
+    end.map(|end| {
+      self.byte_offset = end;
+      self.bit_offset = 0;
+      self.reload_buffer_values();
+      v
+    })
   }
 
   /// Reads a zigzag-VLQ encoded (in little endian order) int from the stream
@@ -591,12 +809,18 @@ impl BitReader {
     })
   }
 
+  /// Loads 8 bytes at `byte_offset` into `buffered_values` with a single unaligned
+  /// load, bytes past the end of the buffer are read as zeros.
   #[inline]
   fn reload_buffer_values(&mut self) {
-    let bytes_to_read = cmp::min(self.total_bytes - self.byte_offset, 8);
-    self.buffered_values = read_num_bytes!(
-      u64, bytes_to_read, self.buffer.start_from(self.byte_offset).as_ref()
-    );
+    let data = &self.buffer.data()[self.byte_offset..];
+    self.buffered_values = if data.len() >= 8 {
+      LittleEndian::read_u64(data)
+    } else {
+      let mut buf = [0u8; 8];
+      buf[..data.len()].copy_from_slice(data);
+      LittleEndian::read_u64(&buf)
+    };
   }
 }
 
@@ -607,6 +831,182 @@ impl From<Vec<u8>> for BitReader {
   }
 }
 
+/// Utility class to write values that are packed from the most significant bit to the
+/// least significant bit of every byte, as in the deprecated BIT_PACKED encoding.
+/// Unlike `BitWriter`, values are written directly into the buffer.
+pub struct MsbBitWriter {
+  buffer: Vec<u8>,
+  // Current bit offset in `buffer`
+  bit_offset: usize
+}
+
+impl MsbBitWriter {
+  /// Creates writer into a preallocated buffer, which is filled with zeros.
+  pub fn new_from_buf(mut buffer: Vec<u8>) -> Self {
+    for byte in buffer.iter_mut() {
+      *byte = 0;
+    }
+    MsbBitWriter { buffer: buffer, bit_offset: 0 }
+  }
+
+  /// Writes `num_bits` least significant bits of value `v`.
+  ///
+  /// Returns false if there's not enough space left in the buffer.
+  #[inline]
+  pub fn put_value(&mut self, v: u64, num_bits: usize) -> bool {
+    assert!(num_bits <= 64);
+    if self.bit_offset + num_bits > self.buffer.len() * 8 {
+      return false;
+    }
+    let mut remaining = num_bits;
+    while remaining > 0 {
+      let bit_in_byte = self.bit_offset % 8;
+      let n = cmp::min(8 - bit_in_byte, remaining);
+      let bits = trailing_bits(v >> (remaining - n), n) as u8;
+      self.buffer[self.bit_offset / 8] |= bits << (8 - bit_in_byte - n);
+      self.bit_offset += n;
+      remaining -= n;
+    }
+    true
+  }
+
+  /// Returns the number of bytes written so far, including a partially written byte.
+  #[inline]
+  pub fn bytes_written(&self) -> usize {
+    ceil(self.bit_offset as i64, 8) as usize
+  }
+
+  /// Consumes and returns the buffer, truncated to the bytes written.
+  #[inline]
+  pub fn consume(mut self) -> Vec<u8> {
+    let len = self.bytes_written();
+    self.buffer.truncate(len);
+    self.buffer
+  }
+}
+
+/// Utility class to read values that are packed from the most significant bit to the
+/// least significant bit of every byte, as in the deprecated BIT_PACKED encoding.
+pub struct MsbBitReader {
+  buffer: ByteBufferPtr,
+  // Current bit offset in `buffer`
+  bit_offset: usize
+}
+
+impl MsbBitReader {
+  pub fn new(buffer: ByteBufferPtr) -> Self {
+    MsbBitReader { buffer: buffer, bit_offset: 0 }
+  }
+
+  #[inline]
+  pub fn reset(&mut self, buffer: ByteBufferPtr) {
+    self.buffer = buffer;
+    self.bit_offset = 0;
+  }
+
+  /// Gets the current byte offset, including a partially read byte.
+  #[inline]
+  pub fn get_byte_offset(&self) -> usize {
+    ceil(self.bit_offset as i64, 8) as usize
+  }
+
+  /// Reads a value of type `T` and of size `num_bits`.
+  ///
+  /// Returns `None` if there's not enough data available, or if `num_bits` is larger
+  /// than the size of `T`. `Some` otherwise.
+  #[inline]
+  pub fn get_value<T: Default>(&mut self, num_bits: usize) -> Option<T> {
+    if !is_valid_num_bits::<T>(num_bits) {
+      return None;
+    }
+
+    if self.bit_offset + num_bits > self.buffer.len() * 8 {
+      return None;
+    }
+
+    let data = self.buffer.as_ref();
+    let mut v: u64 = 0;
+    let mut remaining = num_bits;
+    while remaining > 0 {
+      let bit_in_byte = self.bit_offset % 8;
+      let n = cmp::min(8 - bit_in_byte, remaining);
+      let bits = (data[self.bit_offset / 8] << bit_in_byte) >> (8 - n);
+      v = (v << n) | bits as u64;
+      self.bit_offset += n;
+      remaining -= n;
+    }
+
+    let result: T = unsafe {
+      transmute_copy::<u64, T>(&v)
+    };
+    Some(result)
+  }
+
+  /// Reads values of size `num_bits` into `batch` until it is full or there's not
+  /// enough data available.
+  ///
+  /// Returns the number of values read, or an error if `num_bits` is larger than the
+  /// size of `T`.
+  #[inline]
+  pub fn get_batch<T: Default>(
+    &mut self,
+    batch: &mut [T],
+    num_bits: usize
+  ) -> Result<usize> {
+    check_num_bits::<T>(num_bits)?;
+    for (i, value) in batch.iter_mut().enumerate() {
+      match self.get_value(num_bits) {
+        Some(v) => *value = v,
+        None => return Ok(i)
+      }
+    }
+    Ok(batch.len())
+  }
+
+  /// Skips at most `num_values` values of size `num_bits` without reading them.
+  ///
+  /// Returns the number of values skipped.
+  #[inline]
+  pub fn skip(&mut self, num_values: usize, num_bits: usize) -> usize {
+    let remaining_bits = self.buffer.len() * 8 - self.bit_offset;
+    let values_to_skip = if num_bits == 0 {
+      num_values
+    } else {
+      cmp::min(num_values, remaining_bits / num_bits)
+    };
+    self.bit_offset += values_to_skip * num_bits;
+    values_to_skip
+  }
+}
+
+impl From<Vec<u8>> for MsbBitReader {
+  #[inline]
+  fn from(buffer: Vec<u8>) -> Self {
+    MsbBitReader::new(ByteBufferPtr::new(buffer))
+  }
+}
+
+/// Returns `true` if values of `num_bits` bits fit into type `T`.
+#[inline]
+fn is_valid_num_bits<T>(num_bits: usize) -> bool {
+  num_bits <= 64 && num_bits <= size_of::<T>() * 8
+}
+
+/// Returns an error if values of `num_bits` bits do not fit into type `T`, e.g. when
+/// bit width is read from corrupt data.
+#[inline]
+fn check_num_bits<T>(num_bits: usize) -> Result<()> {
+  if is_valid_num_bits::<T>(num_bits) {
+    Ok(())
+  } else {
+    Err(general_err!(
+      "Bit width {} is out of range for values of {} bits",
+      num_bits,
+      size_of::<T>() * 8
+    ))
+  }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -677,6 +1077,58 @@ mod tests {
     assert_eq!(bit_reader.get_value::<i32>(1), None);
     bit_reader.reset(buffer.all());
     assert_eq!(bit_reader.get_aligned::<i32>(3), None);
+
+    // Values wider than `T` are not read
+    let mut bit_reader = BitReader::from(vec![0xFF; 16]);
+    assert_eq!(bit_reader.get_aligned::<u32>(8), None);
+    assert_eq!(bit_reader.get_byte_offset(), 0);
+  }
+
+  #[test]
+  fn test_bit_reader_skip() {
+    let values: Vec<u64> = (0..200).map(|i| i % 31).collect();
+    let mut writer = BitWriter::new(200);
+    for value in &values {
+      writer.put_value(*value, 5);
+    }
+    writer.put_aligned::<u8>(42, 1);
+    let buffer = writer.consume();
+
+    let mut reader = BitReader::from(buffer.clone());
+    assert_eq!(reader.skip(3, 5), Ok(3));
+    assert_eq!(reader.get_value::<u64>(5), Some(values[3]));
+    // Skip across buffered values
+    assert_eq!(reader.skip(100, 5), Ok(100));
+    assert_eq!(reader.get_value::<u64>(5), Some(values[104]));
+    let mut batch = vec![0u64; 40];
+    assert_eq!(reader.get_batch(&mut batch, 5), Ok(40));
+    assert_eq!(&batch[..], &values[105..145]);
+    assert_eq!(reader.skip(55, 5), Ok(55));
+    assert_eq!(reader.get_aligned::<u8>(1), Some(42));
+    assert_eq!(reader.skip(1, 5), Ok(0));
+  }
+
+  #[test]
+  fn test_bit_reader_invalid_num_bits() {
+    let mut reader = BitReader::from(vec![0xFF; 32]);
+    assert_eq!(reader.get_value::<u8>(9), None);
+    assert_eq!(reader.get_value::<u64>(65), None);
+    let mut batch = vec![0u8; 4];
+    assert!(reader.get_batch::<u8>(&mut batch, 9).is_err());
+    let mut batch = vec![0i16; 4];
+    assert!(reader.get_i16_batch(&mut batch, 17).is_err());
+    assert!(reader.skip(1, 65).is_err());
+    // Bitmap is too small for values read
+    let mut bitmap = vec![0u8; 1];
+    assert!(reader.get_bitmap(&mut bitmap, 4, 8).is_err());
+    // Nothing was read
+    assert_eq!(reader.get_value::<u8>(8), Some(0xFF));
+    assert_eq!(reader.get_byte_offset(), 1);
+
+    let mut reader = MsbBitReader::from(vec![0xFF; 4]);
+    assert_eq!(reader.get_value::<u8>(9), None);
+    let mut batch = vec![0i16; 4];
+    assert!(reader.get_batch::<i16>(&mut batch, 17).is_err());
   }
 
   #[test]
@@ -686,6 +1138,39 @@ mod tests {
     let mut bit_reader = BitReader::from(buffer);
     assert_eq!(bit_reader.get_vlq_int(), Some(137));
     assert_eq!(bit_reader.get_vlq_int(), Some(105202));
+
+    // Ints longer than `MAX_VLQ_BYTE_LEN` bytes are rejected
+    let mut bit_reader = BitReader::from(vec![0xFF; MAX_VLQ_BYTE_LEN + 1]);
+    assert_eq!(bit_reader.get_vlq_int(), None);
+    assert_eq!(bit_reader.get_byte_offset(), 0);
+  }
+
+  #[test]
+  fn test_bit_reader_get_vlq_int_truncated() {
+    // Second value is unaligned and truncated, the reader is not advanced
+    let buffer: Vec<u8> = vec![0x89, 0x01, 0x0F, 0xF2, 0xB5];
+    let mut bit_reader = BitReader::from(buffer);
+    assert_eq!(bit_reader.get_vlq_int(), Some(137));
+    assert_eq!(bit_reader.get_value::<u8>(4), Some(15));
+    assert_eq!(bit_reader.get_vlq_int(), None);
+    assert_eq!(bit_reader.get_byte_offset(), 3);
+    assert_eq!(bit_reader.get_aligned::<u16>(2), Some(0xB5F2));
+    assert_eq!(bit_reader.get_vlq_int(), None);
+  }
+
+  #[test]
+  fn test_bit_reader_reload_across_words() {
+    // Values of 7 bits cross boundaries of 8-byte loads
+    let values: Vec<u64> = (0..100).map(|i| (i * 37) % 128).collect();
+    let mut writer = BitWriter::new(100);
+    for v in &values {
+      assert!(writer.put_value(*v, 7));
+    }
+    let mut bit_reader = BitReader::from(writer.consume());
+    for v in &values {
+      assert_eq!(bit_reader.get_value::<u64>(7), Some(*v));
+    }
+    assert_eq!(bit_reader.get_value::<u64>(7), None);
   }
 
   #[test]
@@ -698,6 +1183,37 @@ mod tests {
     assert_eq!(bit_reader.get_zigzag_vlq_int(), Some(-2));
   }
 
+  #[test]
+  fn test_msb_bit_writer_reader() {
+    // Example of the deprecated BIT_PACKED encoding from the Parquet format
+    let mut writer = MsbBitWriter::new_from_buf(vec![255; 4]);
+    for i in 0..8 {
+      assert!(writer.put_value(i, 3));
+    }
+    assert_eq!(writer.bytes_written(), 3);
+    assert_eq!(writer.consume(), vec![0b00000101, 0b00111001, 0b01110111]);
+
+    let mut reader = MsbBitReader::from(vec![0b00000101, 0b00111001, 0b01110111]);
+    let mut batch = vec![0; 10];
+    assert_eq!(reader.get_batch::<i16>(&mut batch, 3), Ok(8));
+    assert_eq!(batch, vec![0, 1, 2, 3, 4, 5, 6, 7, 0, 0]);
+    assert_eq!(reader.get_byte_offset(), 3);
+    assert_eq!(reader.get_value::<i16>(1), None);
+
+    let mut reader = MsbBitReader::from(vec![0b00000101, 0b00111001, 0b01110111]);
+    assert_eq!(reader.skip(5, 3), 5);
+    assert_eq!(reader.get_value::<i16>(3), Some(5));
+    assert_eq!(reader.skip(5, 3), 2);
+
+    let mut writer = MsbBitWriter::new_from_buf(vec![0; 3]);
+    assert!(writer.put_value(1, 1));
+    assert!(writer.put_value(0x1234, 16));
+    assert!(!writer.put_value(0xFF, 8));
+    let mut reader = MsbBitReader::from(writer.consume());
+    assert_eq!(reader.get_value::<bool>(1), Some(true));
+    assert_eq!(reader.get_value::<u32>(16), Some(0x1234));
+  }
+
   #[test]
   fn test_set_array_bit() {
     let mut buffer = vec![0, 0, 0];
@@ -717,6 +1233,47 @@ mod tests {
     assert_eq!(buffer, vec![16, 8, 0]);
   }
 
+  #[test]
+  fn test_set_array_bits() {
+    let mut buffer = vec![0xff, 0, 0];
+    set_array_bits(&mut buffer[..], 4, 0b1010_0101, 8);
+    assert_eq!(buffer, vec![0x5f, 0x0a, 0]);
+    set_array_bits(&mut buffer[..], 0, 0, 3);
+    assert_eq!(buffer, vec![0x58, 0x0a, 0]);
+    set_array_bits(&mut buffer[..], 8, u64::max_value(), 16);
+    assert_eq!(buffer, vec![0x58, 0xff, 0xff]);
+
+    let mut buffer = vec![0; 4];
+    fill_array_bits(&mut buffer[..], 3, 22, true);
+    assert_eq!(buffer, vec![0xf8, 0xff, 0xff, 0x01]);
+    fill_array_bits(&mut buffer[..], 5, 2, false);
+    assert_eq!(buffer, vec![0x98, 0xff, 0xff, 0x01]);
+    fill_array_bits(&mut buffer[..], 8, 16, false);
+    assert_eq!(buffer, vec![0x98, 0, 0, 0x01]);
+  }
+
+  #[test]
+  fn test_bit_reader_get_bitmap() {
+    let values = random_bools(200);
+    let mut writer = BitWriter::new(32);
+    for &value in &values {
+      assert!(writer.put_value(value as u64, 1));
+    }
+    let buffer = writer.consume();
+
+    for &offset in &[0, 3, 8, 13] {
+      let mut reader = BitReader::from(buffer.clone());
+      let mut bitmap = vec![0; (offset + values.len() + 7) / 8];
+      assert_eq!(reader.get_bitmap(&mut bitmap, offset, 5), Ok(5));
+      assert_eq!(reader.get_bitmap(&mut bitmap, offset + 5, 100), Ok(100));
+      assert_eq!(reader.get_bitmap(&mut bitmap, offset + 105, 200), Ok(95));
+      for (i, &value) in values.iter().enumerate() {
+        assert_eq!(get_array_bit(&bitmap, offset + i), value);
+      }
+      assert_eq!(reader.get_bitmap(&mut bitmap, 0, 1), Ok(0));
+    }
+  }
+
   #[test]
   fn test_num_required_bits() {
     assert_eq!(num_required_bits(0), 0);
@@ -871,44 +1428,73 @@ mod tests {
   fn test_get_batch() {
     const SIZE: &[usize] = &[1, 31, 32, 33, 128, 129];
     for s in SIZE {
-      for i in 0..33 {
+      for i in 0..65 {
         match i {
           0 ... 8  => test_get_batch_helper::<u8>(*s, i),
           9 ... 16 => test_get_batch_helper::<u16>(*s, i),
-          _        => test_get_batch_helper::<u32>(*s, i),
+          17 ... 32 => test_get_batch_helper::<u32>(*s, i),
+          _        => test_get_batch_helper::<u64>(*s, i),
         }
       }
     }
   }
 
+  #[test]
+  fn test_get_i16_batch() {
+    for num_bits in 0..17 {
+      let values: Vec<u64> = random_numbers::<u64>(150)
+        .iter()
+        .map(|v| trailing_bits(*v, num_bits))
+        .collect();
+      let mut writer = BitWriter::new(num_bits * 150 / 8 + 8);
+      for v in &values {
+        assert!(writer.put_value(*v, num_bits));
+      }
+      let expected: Vec<i16> = values.iter().map(|v| *v as i16).collect();
+
+      // Start unaligned, then read batches of different sizes
+      let mut reader = BitReader::from(writer.consume());
+      let mut batch = vec![0i16; 150];
+      let mut offset = 0;
+      for &size in &[3, 64, 17, 66] {
+        let read = reader
+          .get_i16_batch(&mut batch[offset..offset + size], num_bits)
+          .unwrap();
+        assert_eq!(read, size);
+        offset += size;
+      }
+      assert_eq!(batch, expected, "num_bits = {}", num_bits);
+    }
+  }
+
   fn test_get_batch_helper<T>(
     total: usize, num_bits: usize
   ) where T: Default + Clone + Debug + Eq {
-    assert!(num_bits <= 32);
+    assert!(num_bits <= 64);
     let num_bytes = ceil(num_bits as i64, 8);
     let mut writer = BitWriter::new(num_bytes as usize * total);
 
-    let values: Vec<u32> = random_numbers::<u32>(total)
+    let values: Vec<u64> = random_numbers::<u64>(total)
       .iter()
-      .map(|v| v & (((1u64 << num_bits) - 1)) as u32)
+      .map(|v| trailing_bits(*v, num_bits))
       .collect();
 
     // Generic values used to check against actual values read from `get_batch`.
     let expected_values: Vec<T> = values
       .iter()
       .map(|v| unsafe {
-        transmute_copy::<u32, T>(&v)
+        transmute_copy::<u64, T>(&v)
       })
       .collect();
 
     for i in 0..total {
-      assert!(writer.put_value(values[i] as u64, num_bits));
+      assert!(writer.put_value(values[i], num_bits));
     }
 
     let buf = writer.consume();
     let mut reader = BitReader::from(buf);
     let mut batch = vec![T::default(); values.len()];
-    let values_read = reader.get_batch::<T>(&mut batch, num_bits);
+    let values_read = reader.get_batch::<T>(&mut batch, num_bits).unwrap();
     assert_eq!(values_read, values.len());
     for i in 0..batch.len() {
       assert_eq!(batch[i], expected_values[i], "num_bits = {}, index = {}", num_bits, i);
@@ -15,6 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use byteorder::{ByteOrder, LittleEndian};
+
 use data_type::AsBytes;
 
 #[cfg(target_feature = "sse4.2")]
@@ -74,6 +76,80 @@ fn murmur_hash2_64a<T: AsBytes>(data: &T, seed: u64) -> u64 {
   h
 }
 
+const XXH_PRIME64_1: u64 = 0x9E3779B185EBCA87;
+const XXH_PRIME64_2: u64 = 0xC2B2AE3D27D4EB4F;
+const XXH_PRIME64_3: u64 = 0x165667B19E3779F9;
+const XXH_PRIME64_4: u64 = 0x85EBCA77C2B2AE63;
+const XXH_PRIME64_5: u64 = 0x27D4EB2F165667C5;
+
+#[inline]
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+  acc.wrapping_add(input.wrapping_mul(XXH_PRIME64_2))
+    .rotate_left(31)
+    .wrapping_mul(XXH_PRIME64_1)
+}
+
+#[inline]
+fn xxh64_merge_round(acc: u64, val: u64) -> u64 {
+  (acc ^ xxh64_round(0, val)).wrapping_mul(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_4)
+}
+
+/// Rust implementation of XXH64 (64-bit xxHash), which is used by Parquet bloom
+/// filters.
+pub fn xxhash64(data: &[u8], seed: u64) -> u64 {
+  let len = data.len();
+  let mut offset = 0;
+
+  let mut h = if len >= 32 {
+    let mut v1 = seed.wrapping_add(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_2);
+    let mut v2 = seed.wrapping_add(XXH_PRIME64_2);
+    let mut v3 = seed;
+    let mut v4 = seed.wrapping_sub(XXH_PRIME64_1);
+    while offset + 32 <= len {
+      v1 = xxh64_round(v1, LittleEndian::read_u64(&data[offset..]));
+      v2 = xxh64_round(v2, LittleEndian::read_u64(&data[offset + 8..]));
+      v3 = xxh64_round(v3, LittleEndian::read_u64(&data[offset + 16..]));
+      v4 = xxh64_round(v4, LittleEndian::read_u64(&data[offset + 24..]));
+      offset += 32;
+    }
+    let mut h = v1.rotate_left(1)
+      .wrapping_add(v2.rotate_left(7))
+      .wrapping_add(v3.rotate_left(12))
+      .wrapping_add(v4.rotate_left(18));
+    h = xxh64_merge_round(h, v1);
+    h = xxh64_merge_round(h, v2);
+    h = xxh64_merge_round(h, v3);
+    xxh64_merge_round(h, v4)
+  } else {
+    seed.wrapping_add(XXH_PRIME64_5)
+  };
+
+  h = h.wrapping_add(len as u64);
+
+  while offset + 8 <= len {
+    h ^= xxh64_round(0, LittleEndian::read_u64(&data[offset..]));
+    h = h.rotate_left(27).wrapping_mul(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_4);
+    offset += 8;
+  }
+  if offset + 4 <= len {
+    h ^= (LittleEndian::read_u32(&data[offset..]) as u64).wrapping_mul(XXH_PRIME64_1);
+    h = h.rotate_left(23).wrapping_mul(XXH_PRIME64_2).wrapping_add(XXH_PRIME64_3);
+    offset += 4;
+  }
+  while offset < len {
+    h ^= (data[offset] as u64).wrapping_mul(XXH_PRIME64_5);
+    h = h.rotate_left(11).wrapping_mul(XXH_PRIME64_1);
+    offset += 1;
+  }
+
+  h ^= h >> 33;
+  h = h.wrapping_mul(XXH_PRIME64_2);
+  h ^= h >> 29;
+  h = h.wrapping_mul(XXH_PRIME64_3);
+  h ^= h >> 32;
+  h
+}
+
 /// CRC32 hash implementation using SSE4 instructions. Borrowed from Impala.
 #[cfg(target_feature = "sse4.2")]
 pub fn crc32_hash<T: AsBytes>(data: &T, seed: u32) -> u32 {
@@ -127,6 +203,18 @@ mod tests {
     assert_eq!(result, 2392198230801491746);
   }
 
+  #[test]
+  fn test_xxhash64() {
+    assert_eq!(xxhash64(b"", 0), 0xEF46DB3751D8E999);
+    assert_eq!(xxhash64(b"abc", 0), 0x44BC2CF5AD770999);
+    assert_eq!(
+      xxhash64(b"Nobody inspects the spammish repetition", 0),
+      0xFBCEA83C8A378BF1
+    );
+    let data: Vec<u8> = (0..100).collect();
+    assert_eq!(xxhash64(&data, 0), 0x6AC1E58032166597);
+  }
+
   #[test]
   #[cfg(target_feature = "sse4.2")]
   fn test_crc32() {
@@ -17,7 +17,7 @@
 
 use std::cmp;
 use std::fs::File;
-use std::io::{BufReader, Error, ErrorKind, Read, Result, Seek, SeekFrom};
+use std::io::{BufReader, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
 use std::sync::Mutex;
 
 /// Struct that represents a slice of a file data with independent start position and
@@ -43,6 +43,19 @@ impl FileChunk {
       end: start + length
     }
   }
+
+  /// Returns current position of this chunk in a file.
+  pub fn pos(&self) -> usize {
+    self.start
+  }
+
+  /// Advances position by at most `num_bytes` without reading them, and returns the
+  /// number of bytes skipped.
+  pub fn skip(&mut self, num_bytes: usize) -> usize {
+    let num_bytes = cmp::min(num_bytes, self.end - self.start);
+    self.start += num_bytes;
+    num_bytes
+  }
 }
 
 impl Read for FileChunk {
@@ -63,6 +76,49 @@ impl Read for FileChunk {
   }
 }
 
+/// Writer wrapper that counts number of bytes written into the underlying writer.
+///
+/// Number of bytes written is used as the current offset in the output, so offsets of
+/// pages and column chunks can be recorded without requiring `Seek`, which makes it
+/// possible to write into non-seekable sinks, e.g. sockets or in-memory buffers.
+pub struct TrackedWrite<W: Write> {
+  inner: W,
+  bytes_written: usize
+}
+
+impl<W: Write> TrackedWrite<W> {
+  /// Creates new tracked writer for `inner`, offset starts at `0`.
+  pub fn new(inner: W) -> Self {
+    Self { inner: inner, bytes_written: 0 }
+  }
+
+  /// Returns number of bytes written so far, i.e. current offset in the output.
+  pub fn bytes_written(&self) -> usize {
+    self.bytes_written
+  }
+
+  /// Returns reference to the underlying writer.
+  pub fn inner(&self) -> &W {
+    &self.inner
+  }
+
+  /// Returns underlying writer.
+  pub fn into_inner(self) -> W {
+    self.inner
+  }
+}
+
+impl<W: Write> Write for TrackedWrite<W> {
+  fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    let bytes_written = self.inner.write(buf)?;
+    self.bytes_written += bytes_written;
+    Ok(bytes_written)
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    self.inner.flush()
+  }
+}
 
 #[cfg(test)]
 mod tests {
@@ -86,11 +142,27 @@ mod tests {
 
     let bytes_read = chunk.read(&mut buf[0..2]).unwrap();
     assert_eq!(bytes_read, 2);
+    assert_eq!(chunk.pos(), 2);
     let bytes_read = chunk.read(&mut buf[2..]).unwrap();
     assert_eq!(bytes_read, 2);
+    assert_eq!(chunk.pos(), 4);
     assert_eq!(buf, vec![b'P', b'A', b'R', b'1']);
   }
 
+  #[test]
+  fn test_io_skip() {
+    let mut buf = vec![0; 2];
+    let mut chunk = FileChunk::new(&get_test_file("alltypes_plain.parquet"), 0, 4);
+
+    assert_eq!(chunk.skip(2), 2);
+    assert_eq!(chunk.pos(), 2);
+    let bytes_read = chunk.read(&mut buf[..]).unwrap();
+    assert_eq!(bytes_read, 2);
+    assert_eq!(buf, vec![b'R', b'1']);
+    // Cannot skip past the end of the chunk
+    assert_eq!(chunk.skip(2), 0);
+  }
+
   #[test]
   fn test_io_seek_switch() {
     let mut buf = vec![0; 4];
@@ -103,4 +175,18 @@ mod tests {
     assert_eq!(bytes_read, 4);
     assert_eq!(buf, vec![b'P', b'A', b'R', b'1']);
   }
+
+  #[test]
+  fn test_io_tracked_write() {
+    let mut writer = TrackedWrite::new(Vec::new());
+    assert_eq!(writer.bytes_written(), 0);
+    writer.write_all(&[1, 2, 3]).unwrap();
+    assert_eq!(writer.bytes_written(), 3);
+    writer.write_all(&[]).unwrap();
+    writer.write_all(&[4, 5]).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(writer.bytes_written(), 5);
+    assert_eq!(writer.inner(), &vec![1, 2, 3, 4, 5]);
+    assert_eq!(writer.into_inner(), vec![1, 2, 3, 4, 5]);
+  }
 }
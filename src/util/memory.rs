@@ -71,6 +71,72 @@ impl MemTracker {
   }
 }
 
+/// Memory accounted in an optional [`MemTracker`] on behalf of a structure that does
+/// not allocate through [`Buffer`], for example encoders that write into bit writers.
+///
+/// The owner reports its current footprint with `set_size`, and the tracker is updated
+/// with the difference. Reported memory is released when this struct is dropped.
+pub struct TrackedMemory {
+  mem_tracker: Option<MemTrackerPtr>,
+  size: i64
+}
+
+impl TrackedMemory {
+  /// Creates new tracked memory of zero size without memory tracker.
+  pub fn new() -> Self {
+    TrackedMemory {
+      mem_tracker: None,
+      size: 0
+    }
+  }
+
+  /// Adds [`MemTracker`], current size is reported to the tracker.
+  #[inline]
+  pub fn with_mem_tracker(mut self, mc: MemTrackerPtr) -> Self {
+    mc.alloc(self.size);
+    self.mem_tracker = Some(mc);
+    self
+  }
+
+  /// Sets current size in bytes, memory tracker is updated with the difference.
+  #[inline]
+  pub fn set_size(&mut self, num_bytes: usize) {
+    let new_size = num_bytes as i64;
+    if let Some(ref mc) = self.mem_tracker {
+      mc.alloc(new_size - self.size);
+    }
+    self.size = new_size;
+  }
+
+  /// Returns current size in bytes.
+  #[inline]
+  pub fn size(&self) -> usize {
+    self.size as usize
+  }
+
+  /// Converts `data` into [`ByteBufferPtr`]. When memory tracker is set, memory of
+  /// `data` is tracked until the last reference to the returned buffer is dropped.
+  #[inline]
+  pub fn to_buffer_ptr(&self, data: Vec<u8>) -> ByteBufferPtr {
+    match self.mem_tracker {
+      Some(ref mc) => {
+        mc.alloc(data.capacity() as i64);
+        BufferPtr::new(data).with_mem_tracker(mc.clone())
+      },
+      None => BufferPtr::new(data)
+    }
+  }
+}
+
+impl Drop for TrackedMemory {
+  #[inline]
+  fn drop(&mut self) {
+    if let Some(ref mc) = self.mem_tracker {
+      mc.alloc(-self.size);
+    }
+  }
+}
+
 // ----------------------------------------------------------------------
 // Buffer classes
 
@@ -177,9 +243,18 @@ impl<T: Clone> Buffer<T> {
   }
 
   /// Adds `value` to the buffer.
+  ///
+  /// Memory tracker is also updated, if available.
   #[inline]
   pub fn push(&mut self, value: T) {
-    self.data.push(value)
+    let old_capacity = self.data.capacity();
+    self.data.push(value);
+    if self.data.capacity() > old_capacity {
+      if let Some(ref mc) = self.mem_tracker {
+        let capacity_diff = self.data.capacity() as i64 - old_capacity as i64;
+        mc.alloc(capacity_diff * self.type_length as i64);
+      }
+    }
   }
 
   /// Returns current capacity for the buffer.
@@ -450,6 +525,29 @@ mod tests {
     assert_eq!(mem_tracker.memory_usage(), buffer.capacity() as i64);
   }
 
+  #[test]
+  fn test_tracked_memory() {
+    let mem_tracker = Rc::new(MemTracker::new());
+    {
+      let mut memory = TrackedMemory::new();
+      memory.set_size(10);
+      assert_eq!(memory.size(), 10);
+
+      let mut memory = memory.with_mem_tracker(mem_tracker.clone());
+      assert_eq!(mem_tracker.memory_usage(), 10);
+      memory.set_size(30);
+      assert_eq!(mem_tracker.memory_usage(), 30);
+      memory.set_size(20);
+      assert_eq!(mem_tracker.memory_usage(), 20);
+      assert_eq!(mem_tracker.max_memory_usage(), 30);
+
+      let ptr = memory.to_buffer_ptr(vec![0; 5]);
+      assert!(ptr.is_mem_tracked());
+      assert!(mem_tracker.memory_usage() >= 25);
+    }
+    assert_eq!(mem_tracker.memory_usage(), 0);
+  }
+
   #[test]
   fn test_byte_buffer() {
     let mut buffer = ByteBuffer::new();
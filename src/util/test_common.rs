@@ -20,6 +20,7 @@ use rand::distributions::range::SampleRange;
 use std::env;
 use std::fs;
 use std::io::Write;
+use std::path::PathBuf;
 
 use data_type::{ByteArray, DataType, FixedLenByteArrayType};
 
@@ -95,18 +96,24 @@ pub fn random_numbers_range<T>(
   }
 }
 
-/// Returns file handle for a test parquet file from 'data' directory
-pub fn get_test_file(file_name: &str) -> fs::File {
+/// Returns path to a test parquet file in 'data' directory
+pub fn get_test_path(file_name: &str) -> PathBuf {
   let mut path_buf = env::current_dir().unwrap();
   path_buf.push("data");
   path_buf.push(file_name);
+  path_buf
+}
+
+/// Returns file handle for a test parquet file from 'data' directory
+pub fn get_test_file(file_name: &str) -> fs::File {
+  let path_buf = get_test_path(file_name);
   let file = fs::File::open(path_buf.as_path());
   assert!(file.is_ok());
   file.unwrap()
 }
 
-/// Returns file handle for a temp file in 'target' directory with a provided content
-pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
+/// Returns path to a temp file in 'target' directory, file itself is not created.
+pub fn get_temp_filename(file_name: &str) -> PathBuf {
   // build tmp path to a file in "target/debug/testdata"
   let mut path_buf = env::current_dir().unwrap();
   path_buf.push("target");
@@ -114,6 +121,12 @@ pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
   path_buf.push("testdata");
   fs::create_dir_all(&path_buf).unwrap();
   path_buf.push(file_name);
+  path_buf
+}
+
+/// Returns file handle for a temp file in 'target' directory with a provided content
+pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
+  let path_buf = get_temp_filename(file_name);
 
   // write file content
   let mut tmp_file = fs::File::create(path_buf.as_path()).unwrap();